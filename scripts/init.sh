@@ -219,6 +219,6 @@ echo ""
 echo " IMPORTANT – next steps for mainnet:"
 echo "   1. Verify on-chain state via Stellar Explorer."
 echo "   2. Transfer admin to a multisig address before opening to users."
-echo "   3. Configure oracle price feeds via update_price_feed."
+echo "   3. Register oracles via register_oracle, then feed prices via update_price_feed."
 echo "   4. Set up the off-chain oracle service (see oracle/ directory)."
 echo "======================================================================"
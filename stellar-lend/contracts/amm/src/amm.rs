@@ -50,6 +50,8 @@ pub enum AmmError {
     MaxInputExceeded = 13,
     /// Contract has already been initialized
     AlreadyInitialized = 14,
+    /// Reentrancy detected
+    Reentrancy = 15,
 }
 
 /// Storage keys for AMM-related data
@@ -551,10 +553,14 @@ pub fn validate_amm_callback(
 /// Auto-swap for collateral optimization
 ///
 /// Automatically swaps assets to optimize collateral ratios during lending operations.
+/// When both tokens are contract tokens the swap is settled against this
+/// contract's balances: `amount` of `token_in` is pulled from the user and the
+/// output paid to them.
 ///
 /// # Arguments
 /// * `env` - The Soroban environment
 /// * `user` - The user whose collateral to optimize
+/// * `token_in` - The token to swap from (None for native XLM)
 /// * `target_token` - The token to swap to
 /// * `amount` - Amount to swap
 ///
@@ -563,9 +569,12 @@ pub fn validate_amm_callback(
 pub fn auto_swap_for_collateral(
     env: &Env,
     user: Address,
+    token_in: Option<Address>,
     target_token: Option<Address>,
     amount: i128,
 ) -> Result<i128, AmmError> {
+    user.require_auth();
+
     // Check if auto-swap is enabled
     let settings = get_amm_settings(env)?;
     if !settings.swap_enabled {
@@ -578,13 +587,13 @@ pub fn auto_swap_for_collateral(
     }
 
     // Find best AMM protocol for this swap
-    let best_protocol = find_best_amm_protocol(env, &None, &target_token, amount)?;
+    let best_protocol = find_best_amm_protocol(env, &token_in, &target_token, amount)?;
 
     // Create swap parameters with default slippage
     let params = SwapParams {
         protocol: best_protocol,
-        token_in: None, // Assume swapping from native XLM
-        token_out: target_token,
+        token_in: token_in.clone(),
+        token_out: target_token.clone(),
         amount_in: amount,
         min_amount_out: calculate_min_output_with_slippage(amount, settings.default_slippage)?,
         slippage_tolerance: settings.default_slippage,
@@ -592,7 +601,14 @@ pub fn auto_swap_for_collateral(
     };
 
     // Execute the swap
-    let amount_out = execute_swap(env, user, params)?;
+    let amount_out = execute_swap(env, user.clone(), params)?;
+
+    // Native XLM has no token contract here, so only token pairs move funds
+    if let (Some(token_in), Some(token_out)) = (token_in, target_token) {
+        let this = env.current_contract_address();
+        soroban_sdk::token::Client::new(env, &token_in).transfer(&user, &this, &amount);
+        soroban_sdk::token::Client::new(env, &token_out).transfer(&this, &user, &amount_out);
+    }
 
     Ok(amount_out)
 }
@@ -206,9 +206,12 @@ impl AmmContract {
     ///
     /// Automatically swaps assets to optimize collateral ratios during lending operations.
     /// This is typically called internally during borrow/liquidation operations.
+    /// Swaps between two contract tokens pull `amount` of `token_in` from the
+    /// user and pay the output from this contract's balance.
     ///
     /// # Arguments
     /// * `user` - The user whose collateral to optimize
+    /// * `token_in` - The token to swap from (None for native XLM)
     /// * `target_token` - The token to swap to (None for native XLM)
     /// * `amount` - Amount to swap
     ///
@@ -220,10 +223,11 @@ impl AmmContract {
     pub fn auto_swap_for_collateral(
         env: Env,
         user: Address,
+        token_in: Option<Address>,
         target_token: Option<Address>,
         amount: i128,
     ) -> Result<i128, AmmError> {
-        auto_swap_for_collateral(&env, user, target_token, amount)
+        auto_swap_for_collateral(&env, user, token_in, target_token, amount)
     }
 
     /// Get AMM settings
@@ -409,7 +409,7 @@ fn test_auto_swap_for_collateral() {
     };
     contract.add_amm_protocol(&admin, &protocol_config);
 
-    let amount_out = contract.auto_swap_for_collateral(&user, &Some(token_out), &15000);
+    let amount_out = contract.auto_swap_for_collateral(&user, &None, &Some(token_out), &15000);
     assert_eq!(amount_out, 14850);
 }
 
@@ -579,7 +579,7 @@ fn test_multiple_protocol_selection() {
     contract.add_amm_protocol(&admin, &config3);
 
     // Should pick Protocol 3
-    let amount_out = contract.auto_swap_for_collateral(&user, &Some(token_out), &15000);
+    let amount_out = contract.auto_swap_for_collateral(&user, &None, &Some(token_out), &15000);
     assert_eq!(amount_out, 14850);
 }
 
@@ -849,3 +849,68 @@ fn test_edge_case_min_swap_amount() {
     let result = contract.try_execute_swap(&user, &params);
     assert!(result.is_err());
 }
+
+#[test]
+fn test_auto_swap_for_collateral_requires_user_auth() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract = create_amm_contract(&env);
+    let admin = Address::generate(&env);
+    let user = Address::generate(&env);
+    let protocol_addr = Address::generate(&env);
+    contract.initialize_amm_settings(&admin, &100, &1000, &10000);
+    let protocol_config = create_test_protocol_config(&env, &protocol_addr);
+    let token_out = protocol_config.supported_pairs.get(0).unwrap().token_b;
+    contract.add_amm_protocol(&admin, &protocol_config);
+
+    env.set_auths(&[]);
+    let result = contract.try_auto_swap_for_collateral(&user, &None, &token_out, &15000);
+    assert!(result.is_err());
+}
+
+#[test]
+fn test_auto_swap_for_collateral_settles_token_pairs() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract = create_amm_contract(&env);
+    let admin = Address::generate(&env);
+    let user = Address::generate(&env);
+    let protocol_addr = Address::generate(&env);
+    let token_in = env
+        .register_stellar_asset_contract_v2(admin.clone())
+        .address();
+    let token_out = env
+        .register_stellar_asset_contract_v2(admin.clone())
+        .address();
+    contract.initialize_amm_settings(&admin, &100, &1000, &10000);
+
+    let mut supported_pairs = Vec::new(&env);
+    supported_pairs.push_back(TokenPair {
+        token_a: Some(token_in.clone()),
+        token_b: Some(token_out.clone()),
+        pool_address: Address::generate(&env),
+    });
+    let mut protocol_config = create_test_protocol_config(&env, &protocol_addr);
+    protocol_config.supported_pairs = supported_pairs;
+    contract.add_amm_protocol(&admin, &protocol_config);
+
+    soroban_sdk::token::StellarAssetClient::new(&env, &token_in).mint(&user, &15000);
+    soroban_sdk::token::StellarAssetClient::new(&env, &token_out).mint(&contract.address, &20000);
+
+    let amount_out = contract.auto_swap_for_collateral(
+        &user,
+        &Some(token_in.clone()),
+        &Some(token_out.clone()),
+        &15000,
+    );
+    assert_eq!(amount_out, 14850);
+
+    let token_in = soroban_sdk::token::Client::new(&env, &token_in);
+    let token_out = soroban_sdk::token::Client::new(&env, &token_out);
+    assert_eq!(token_in.balance(&user), 0);
+    assert_eq!(token_in.balance(&contract.address), 15000);
+    assert_eq!(token_out.balance(&user), 14850);
+    assert_eq!(token_out.balance(&contract.address), 20000 - 14850);
+}
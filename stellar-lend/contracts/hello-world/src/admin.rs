@@ -21,6 +21,8 @@ pub enum AdminError {
     InvalidParameter = 2,
     /// Admin has already been set
     AdminAlreadySet = 3,
+    /// Reentrancy detected
+    Reentrancy = 4,
 }
 
 /// Storage keys for Admin and Roles
@@ -85,6 +85,8 @@ pub struct ProtocolMetrics {
     pub total_transactions: u64,
     /// Timestamp of last metrics update
     pub last_update: u64,
+    /// Written-off debt the protocol reserves could not absorb, across all assets
+    pub total_bad_debt: i128,
 }
 
 /// Per-user computed metrics.
@@ -281,6 +283,7 @@ pub fn update_protocol_metrics(env: &Env) -> Result<ProtocolMetrics, AnalyticsEr
         total_users,
         total_transactions,
         last_update: env.ledger().timestamp(),
+        total_bad_debt: crate::repay::get_total_bad_debt(env),
     };
 
     env.storage()
@@ -293,7 +296,8 @@ pub fn update_protocol_metrics(env: &Env) -> Result<ProtocolMetrics, AnalyticsEr
 /// Get cached protocol metrics, recomputing if none exist.
 ///
 /// Returns the stored `ProtocolMetrics` if available, otherwise calls
-/// [`update_protocol_metrics`] to compute fresh metrics.
+/// [`update_protocol_metrics`] to compute fresh metrics. `total_bad_debt` is
+/// always read live.
 ///
 /// # Returns
 /// Current `ProtocolMetrics`.
@@ -303,7 +307,9 @@ pub fn get_protocol_stats(env: &Env) -> Result<ProtocolMetrics, AnalyticsError>
         .persistent()
         .get::<AnalyticsDataKey, ProtocolMetrics>(&AnalyticsDataKey::ProtocolMetrics);
 
-    if let Some(metrics) = cached_metrics {
+    if let Some(mut metrics) = cached_metrics {
+        // Bad debt only changes on write-offs, which do not refresh the cache
+        metrics.total_bad_debt = crate::repay::get_total_bad_debt(env);
         Ok(metrics)
     } else {
         update_protocol_metrics(env)
@@ -22,6 +22,7 @@ pub enum BridgeError {
     InvalidFee = 5,
     InvalidAmount = 6,
     AssetNotSupported = 7,
+    Reentrancy = 8,
 }
 
 // Storage keys
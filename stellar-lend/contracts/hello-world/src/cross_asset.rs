@@ -112,6 +112,8 @@ pub enum CrossAssetError {
     PriceStale = 9,
     /// Caller is not authorized (not admin)
     NotAuthorized = 10,
+    /// Reentrancy detected
+    Reentrancy = 11,
 }
 
 /// Admin address authorized for protocol management
@@ -16,18 +16,47 @@
 //! - `ProtocolAnalytics` — aggregate protocol metrics
 //! - `UserAnalytics(user)` — per-user activity metrics
 //! - `ActivityLog` — bounded activity history (max 1000 entries)
+//! - `UserDeposit(user, asset)` — per-user, per-asset deposit shares and collateral flag
+//! - `AssetTotals(asset)` — per-asset supplied, borrowed and reserve totals
+//! - `UserAssets(user)` — assets the user has deposited
+//! - `SupplyPool(asset)` — per-asset share supply and underlying (incl. accrued interest)
 //!
 //! ## Invariants
 //! - Deposit amount must be strictly positive.
 //! - Deposits are rejected when the protocol or deposit operation is paused.
 //! - Token transfers use `transfer_from`, requiring prior user approval.
+//!
+//! ## Share Accounting
+//! Deposits mint shares at the pool's current exchange rate
+//! (`total_underlying / total_shares`). Each accrual credits the pool's underlying
+//! with the suppliers' share of the borrow interest charged on the asset since
+//! the pool's last credit: the borrow index growth on the asset's outstanding
+//! borrows, net of the reserve factor, which goes to the protocol reserve. Each
+//! share therefore redeems for more underlying over time, funded exactly by
+//! borrowers, and withdrawals pay the accrued portion out.
+//! `CollateralBalance(user)` is kept as the legacy aggregate used by the health
+//! checks.
+//!
+//! ## Collateral Toggle
+//! A deposit can be excluded from borrowing power with `set_use_as_collateral`.
+//! Health checks use `get_effective_collateral`, which subtracts disabled deposits
+//! from the aggregate. Disabled deposits still earn the supply rate.
+//!
+//! ## Referrals
+//! `deposit_collateral_with_referral` attributes deposit volume to a referrer. The
+//! first referrer recorded for a user sticks; afterwards `referral_fee_bps` of the
+//! reserve income generated by that user (borrow fees, interest reserve share) is
+//! diverted from `ProtocolReserve` into the referrer's claimable rewards. The
+//! interest reserve share is diverted when the user pays the interest.
 
 #![allow(unused)]
 use soroban_sdk::{contracterror, contracttype, Address, Env, IntoVal, Map, Symbol, Val, Vec};
 
 use crate::events::{
-    emit_analytics_updated, emit_deposit, emit_position_updated, emit_user_activity_tracked,
-    AnalyticsUpdatedEvent, DepositEvent, PositionUpdatedEvent, UserActivityTrackedEvent,
+    emit_analytics_updated, emit_collateral_toggled, emit_deposit, emit_position_updated,
+    emit_referral, emit_reserve_donation, emit_user_activity_tracked, AnalyticsUpdatedEvent,
+    CollateralToggledEvent, DepositEvent, PositionUpdatedEvent, ReferralEvent,
+    ReserveDonationEvent, UserActivityTrackedEvent, DEPOSIT_EVENT_VERSION,
 };
 
 /// Errors that can occur during deposit operations
@@ -49,6 +78,30 @@ pub enum DepositError {
     Overflow = 6,
     /// Reentrancy detected
     Reentrancy = 7,
+    /// Deposits are paused for this asset
+    AssetPaused = 8,
+    /// The contract received nothing from the token transfer
+    TransferShortfall = 9,
+    /// Change would leave the position below the minimum collateral ratio
+    InsufficientCollateralRatio = 10,
+    /// A user cannot refer themselves
+    SelfReferral = 11,
+    /// Referrer has no rewards to claim
+    NoReferralRewards = 12,
+    /// Caller is not authorized for this operation
+    Unauthorized = 13,
+    /// Deposit would exceed the per-user cap for this asset
+    UserCapExceeded = 14,
+    /// User is not on the deposit allowlist for this asset
+    NotAllowlisted = 15,
+    /// Balance does not exceed the native safety buffer
+    BelowSafetyBuffer = 16,
+    /// Asset parameters are out of range
+    InvalidAssetParams = 17,
+    /// Isolated collateral cannot be combined with other collateral
+    IsolationModeConflict = 18,
+    /// Batch has more than [`MAX_BATCH_SIZE`] entries
+    BatchTooLarge = 19,
 }
 
 /// Storage keys for deposit-related data
@@ -80,8 +133,110 @@ pub enum DepositDataKey {
     ActivityLog,
     /// Protocol reserve per asset: Map<Option<Address>, i128>
     ProtocolReserve(Option<Address>),
+    /// Written-off debt per asset that the reserve could not absorb
+    /// Value type: i128
+    BadDebt(Option<Address>),
+    /// Sum of `BadDebt` across assets
+    /// Value type: i128
+    TotalBadDebt,
     /// Native asset (XLM) contract address
     NativeAssetAddress,
+    /// Per-user, per-asset deposit record (None asset for native XLM)
+    /// Value type: UserDeposit
+    UserDeposit(Address, Option<Address>),
+    /// Per-asset supply pool backing deposit shares
+    /// Value type: SupplyPool
+    SupplyPool(Option<Address>),
+    /// Assets a user has deposited: Vec<Option<Address>>
+    UserAssets(Address),
+    /// Referrer attributed to a user (set once)
+    /// Value type: Address
+    Referrer(Address),
+    /// Cumulative deposit volume referred by an address
+    /// Value type: i128
+    ReferralVolume(Address),
+    /// Claimable referral rewards per asset: Map<Option<Address>, i128>
+    ReferralRewards(Address),
+    /// Share of reserve income paid to referrers (basis points)
+    /// Value type: i128
+    ReferralFeeBps,
+    /// TTL bump configuration for persistent entries
+    /// Value type: TtlConfig
+    StorageTtlConfig,
+    /// Whether a user may deposit an allowlist-gated asset: (asset, user)
+    /// Value type: bool
+    DepositAllowlist(Address, Address),
+    /// Per-asset supply/borrow/reserve totals (None for native XLM)
+    /// Value type: AssetTotals
+    AssetTotals(Option<Address>),
+    /// XLM left untouched by `deposit_all` to cover fees and reserves
+    /// Value type: i128
+    NativeDepositBuffer,
+    /// Principal a user has borrowed in one asset (None for native XLM)
+    /// Value type: UserBorrow
+    UserDebt(Address, Option<Address>),
+    /// Cumulative borrow interest index of an asset (None for native XLM)
+    /// Value type: BorrowIndex
+    BorrowIndex(Option<Address>),
+    /// Principal borrowed against an isolated collateral asset
+    /// Value type: i128
+    IsolatedDebt(Address),
+    /// Assets a user has outstanding borrows in: Vec<Option<Address>>
+    UserBorrowedAssets(Address),
+    /// Users with an outstanding borrow in any asset: Vec<Address>
+    Borrowers,
+    /// Amount a delegatee may borrow against a delegator's collateral:
+    /// (delegator, delegatee, asset). Value type: i128
+    CreditDelegation(Address, Address, Option<Address>),
+    /// A queued withdrawal request by id
+    /// Value type: WithdrawalRequest
+    WithdrawalRequest(u64),
+    /// FIFO queue of pending withdrawal request ids for an asset: Vec<u64>
+    WithdrawalQueue(Option<Address>),
+    /// Amounts a user has queued for withdrawal: Map<Option<Address>, i128>
+    QueuedWithdrawals(Address),
+    /// Total amount queued for withdrawal in an asset
+    /// Value type: i128
+    QueuedWithdrawalTotal(Option<Address>),
+    /// Id assigned to the next withdrawal request
+    /// Value type: u64
+    NextWithdrawalRequestId,
+    /// Pending withdrawal intent of a user for an asset with a cooldown
+    /// Value type: WithdrawIntent
+    WithdrawIntent(Address, Address),
+    /// Id assigned to the next withdrawal receipt
+    /// Value type: u64
+    NextWithdrawalReceiptId,
+    /// Receipt of a completed withdrawal
+    /// Value type: WithdrawalReceipt
+    WithdrawalReceipt(u64),
+    /// Number of withdrawal receipts kept before the oldest is evicted
+    /// Value type: u32
+    WithdrawalReceiptCapacity,
+    /// Scheduled withdrawal plan
+    /// Value type: WithdrawalSchedule
+    WithdrawalSchedule(u64),
+    /// Id assigned to the next withdrawal schedule
+    /// Value type: u64
+    NextWithdrawalScheduleId,
+    /// Ledger sequence of a user's latest deposit in an asset (None for native XLM)
+    /// Value type: u32
+    LastDepositLedger(Address, Option<Address>),
+    /// Installment repayment schedule
+    /// Value type: RepaymentSchedule
+    RepaymentSchedule(u64),
+    /// Id assigned to the next repayment schedule
+    /// Value type: u64
+    NextRepaymentScheduleId,
+    /// Id of a user's active repayment schedule for an asset (None for native XLM)
+    /// Value type: u64
+    UserRepaymentSchedule(Address, Option<Address>),
+    /// Penalty rate and delinquency threshold for late installments
+    /// Value type: RepaymentPenaltyConfig
+    RepaymentPenaltyConfig,
+    /// Term premiums, late penalty and early repayment fee for term loans
+    /// Value type: TermLoanConfig
+    TermLoanConfig,
 }
 
 /// Asset parameters for collateral
@@ -94,8 +249,34 @@ pub struct AssetParams {
     pub collateral_factor: i128,
     /// Maximum deposit amount
     pub max_deposit: i128,
-    /// Borrow fee in basis points (e.g., 50 = 0.5%)
+    /// Origination fee on borrows in basis points (e.g., 50 = 0.5%), deducted
+    /// from the amount disbursed and paid into reserves (max 500)
     pub borrow_fee_bps: i128,
+    /// Maximum balance a single user may hold in this asset (0 = unlimited)
+    pub max_deposit_per_user: i128,
+    /// Only allowlisted addresses may deposit this asset
+    pub allowlist_enabled: bool,
+    /// Fee on withdrawals in basis points, paid into reserves (max 500)
+    pub withdraw_fee_bps: i128,
+    /// Notice period before a withdrawal can execute (0 = no cooldown)
+    pub withdraw_cooldown_secs: u64,
+    /// How long a matured withdrawal intent stays executable (0 = no expiry)
+    pub withdraw_intent_window_secs: u64,
+    /// Smallest principal a user may owe in this asset, other than zero (0 = no minimum)
+    pub min_borrow: i128,
+    /// Collateral that can only back borrows on its own, see [`crate::isolation`]
+    pub isolated: bool,
+    /// Maximum principal borrowed against this asset while isolated (0 = no ceiling)
+    pub debt_ceiling: i128,
+    /// Whether users in isolation mode may borrow this asset
+    pub borrowable_in_isolation: bool,
+    /// Debt that may only be held on its own, without borrows of other assets
+    pub siloed: bool,
+    /// Deposits cannot back a borrow in the ledger they were made in
+    pub same_ledger_borrow_blocked: bool,
+    /// Depth of the asset's market; auto-mode liquidations seize collateral
+    /// with higher scores first
+    pub liquidity_score: u32,
 }
 
 /// User position tracking
@@ -112,6 +293,44 @@ pub struct Position {
     pub last_accrual_time: u64,
 }
 
+/// A user's deposit in a single asset
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct UserDeposit {
+    /// Pool shares held by the user
+    pub shares: i128,
+    /// Whether this deposit counts toward borrowing power
+    pub use_as_collateral: bool,
+    /// Pool exchange rate when the user last opened this deposit from zero
+    pub entry_exchange_rate: i128,
+}
+
+/// Per-asset supply pool for share accounting
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct SupplyPool {
+    /// Total shares outstanding
+    pub total_shares: i128,
+    /// Total underlying owed to depositors, including accrued interest
+    pub total_underlying: i128,
+    /// Last time interest was accrued into `total_underlying`
+    pub last_update: u64,
+    /// Interest accrued below one whole unit, scaled by
+    /// [`crate::interest_rate::WAD`]
+    pub interest_remainder: i128,
+    /// Borrow index of the asset up to which suppliers have been credited
+    pub borrow_index: i128,
+}
+
+/// Fixed-point scale for exchange rates (1e18 = one underlying unit per share)
+pub const EXCHANGE_RATE_SCALE: i128 = 1_000_000_000_000_000_000;
+
+/// Highest withdrawal fee an asset may charge (5%)
+pub const MAX_WITHDRAW_FEE_BPS: i128 = 500;
+
+/// Highest origination fee an asset may charge on borrows (5%)
+pub const MAX_BORROW_FEE_BPS: i128 = 500;
+
 /// Activity log entry
 #[contracttype]
 #[derive(Clone, Debug, PartialEq)]
@@ -174,6 +393,18 @@ pub struct ProtocolAnalytics {
     pub total_value_locked: i128,
 }
 
+/// Per-asset supply, borrow and reserve totals
+#[contracttype]
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct AssetTotals {
+    /// Collateral supplied in this asset, net of withdrawals and seizures
+    pub total_supplied: i128,
+    /// Outstanding borrowed principal in this asset
+    pub total_borrowed: i128,
+    /// Protocol reserve held in this asset
+    pub total_reserves: i128,
+}
+
 /// Deposit collateral function
 ///
 /// Allows users to deposit assets as collateral in the protocol.
@@ -193,6 +424,8 @@ pub struct ProtocolAnalytics {
 /// * `DepositError::InsufficientBalance` - If user doesn't have enough balance
 /// * `DepositError::DepositPaused` - If deposits are paused
 /// * `DepositError::AssetNotEnabled` - If asset is not enabled for deposits
+/// * `DepositError::AssetPaused` - If deposits are paused for this asset
+/// * `DepositError::TransferShortfall` - If the token transfer delivered nothing
 /// * `DepositError::Overflow` - If calculation overflow occurs
 ///
 /// # Security
@@ -200,6 +433,7 @@ pub struct ProtocolAnalytics {
 /// * Checks pause switches
 /// * Validates asset parameters
 /// * Transfers tokens from user to contract
+/// * Credits the received balance delta, not the requested amount (fee-on-transfer safe)
 /// * Updates collateral balances
 /// * Emits events for tracking
 /// * Updates analytics
@@ -214,6 +448,10 @@ pub fn deposit_collateral(
         return Err(DepositError::InvalidAmount);
     }
 
+    // Bring the asset's interest indexes up to date before its balances move
+    crate::interest_rate::accrue_interest(env, asset.clone())
+        .map_err(|_| DepositError::Overflow)?;
+
     // Check if deposits are paused
     // Note: The risk management system provides pause functionality through the public API.
     // This check maintains backward compatibility with the old pause switch system.
@@ -235,11 +473,27 @@ pub fn deposit_collateral(
     // We access the risk management storage directly to check pause status
     check_risk_management_pause(env)?;
 
+    // Check asset-scoped pause
+    if crate::risk_management::is_asset_operation_paused(
+        env,
+        &asset,
+        Symbol::new(env, "pause_deposit"),
+    ) {
+        return Err(DepositError::AssetPaused);
+    }
+
+    // A deposit that would count as collateral must not mix isolated and other collateral
+    if is_collateral_enabled(env, &user, &asset)
+        && crate::isolation::conflicts_with_collateral(env, &user, &asset)
+    {
+        return Err(DepositError::IsolationModeConflict);
+    }
+
     // Get current timestamp
     let timestamp = env.ledger().timestamp();
 
-    // Handle asset transfer
-    if let Some(ref asset_addr) = asset {
+    // Handle asset transfer. `credited` is the amount the contract actually received.
+    let credited = if let Some(ref asset_addr) = asset {
         // Validate asset address - ensure it's not the contract itself
         if asset_addr == &env.current_contract_address() {
             return Err(DepositError::InvalidAsset);
@@ -256,10 +510,27 @@ pub fn deposit_collateral(
                 return Err(DepositError::AssetNotEnabled);
             }
 
+            // Permissioned assets only accept deposits from allowlisted users
+            if params.allowlist_enabled && !is_deposit_allowlisted(env, asset_addr, &user) {
+                return Err(DepositError::NotAllowlisted);
+            }
+
             // Check max deposit limit
             if params.max_deposit > 0 && amount > params.max_deposit {
                 return Err(DepositError::InvalidAmount);
             }
+
+            // Check per-user cap against the balance including accrued supply interest.
+            // Only deposits are capped, so lowering the cap never blocks withdrawals.
+            if params.max_deposit_per_user > 0 {
+                let current_balance = get_user_deposit_balance(env, &user, &asset);
+                let new_balance = current_balance
+                    .checked_add(amount)
+                    .ok_or(DepositError::Overflow)?;
+                if new_balance > params.max_deposit_per_user {
+                    return Err(DepositError::UserCapExceeded);
+                }
+            }
         }
 
         // Transfer tokens from user to contract using token contract
@@ -275,17 +546,36 @@ pub fn deposit_collateral(
         // Transfer tokens from user to contract
         // The user must have approved the contract to spend their tokens
         // transfer_from requires: spender (contract), from (user), to (contract), amount
+        let balance_before = token_client.balance(&env.current_contract_address());
         token_client.transfer_from(
             &env.current_contract_address(), // spender (this contract)
             &user,                           // from (user)
             &env.current_contract_address(), // to (this contract)
             &amount,
         );
+        let balance_after = token_client.balance(&env.current_contract_address());
+
+        // Credit only what was received so fee-on-transfer tokens cannot over-credit
+        let received = balance_after
+            .checked_sub(balance_before)
+            .ok_or(DepositError::Overflow)?;
+        if received <= 0 {
+            return Err(DepositError::TransferShortfall);
+        }
+        received.min(amount)
     } else {
         // Native XLM deposit - in Soroban, native assets are handled differently
         // For now, we'll track it but actual XLM handling depends on Soroban's native asset support
         // This is a placeholder for native asset handling
-    }
+        amount
+    };
+
+    // Mint deposit shares at the current exchange rate
+    mint_shares(env, &user, &asset, credited)?;
+    env.storage().persistent().set(
+        &DepositDataKey::LastDepositLedger(user.clone(), asset.clone()),
+        &env.ledger().sequence(),
+    );
 
     // Get or create user position
     let position_key = DepositDataKey::Position(user.clone());
@@ -311,7 +601,7 @@ pub fn deposit_collateral(
 
     // Check for overflow
     let new_collateral = current_collateral
-        .checked_add(amount)
+        .checked_add(credited)
         .ok_or(DepositError::Overflow)?;
 
     // Update storage
@@ -325,17 +615,18 @@ pub fn deposit_collateral(
     env.storage().persistent().set(&position_key, &position);
 
     // Update user analytics
-    update_user_analytics(env, &user, amount, timestamp, true)?;
+    update_user_analytics(env, &user, credited, timestamp, true)?;
 
     // Update protocol analytics
-    update_protocol_analytics(env, amount, true)?;
+    update_protocol_analytics(env, credited, true)?;
+    update_asset_totals(env, &asset, credited, 0, 0)?;
 
     // Add to activity log
     add_activity_log(
         env,
         &user,
         Symbol::new(env, "deposit"),
-        amount,
+        credited,
         asset.clone(),
         timestamp,
     )?;
@@ -344,9 +635,13 @@ pub fn deposit_collateral(
     emit_deposit(
         env,
         DepositEvent {
+            version: DEPOSIT_EVENT_VERSION,
             user: user.clone(),
             asset: asset.clone(),
-            amount,
+            amount: credited,
+            balance: get_user_deposit_balance(env, &user, &asset),
+            total_collateral: new_collateral,
+            exchange_rate: get_exchange_rate(env, &asset),
             timestamp,
         },
     );
@@ -355,14 +650,1062 @@ pub fn deposit_collateral(
     emit_position_updated_event(env, &user, &position);
 
     // Emit analytics updated event
-    emit_analytics_updated_event(env, &user, "deposit", amount, timestamp);
+    emit_analytics_updated_event(env, &user, "deposit", credited, timestamp);
 
     // Emit user activity tracked event
-    emit_user_activity_tracked_event(env, &user, Symbol::new(env, "deposit"), amount, timestamp);
+    emit_user_activity_tracked_event(env, &user, Symbol::new(env, "deposit"), credited, timestamp);
+
+    // Keep the touched entries from being archived
+    crate::ttl::bump_user_entries(env, &user);
+    crate::ttl::bump_protocol_entries(env, &asset);
+
+    crate::liquidate::clear_recovered_breach(env, &user);
 
     Ok(new_collateral)
 }
 
+/// Base contract error code used to report a failing item in a batch call.
+///
+/// See [`batch_item_error`] for the encoding.
+pub const BATCH_ERROR_BASE: u32 = 10_000;
+
+/// Maximum number of items accepted by a batch call.
+pub const MAX_BATCH_SIZE: u32 = 20;
+
+/// Upper bound (exclusive) on the module error codes a batch item can report.
+pub const BATCH_ITEM_CODE_LIMIT: u32 = 100;
+
+/// Encode the failure of a single batch item as a contract error.
+///
+/// The resulting code is `BATCH_ERROR_BASE + index * BATCH_ITEM_CODE_LIMIT + code`,
+/// where `code` is the `u32` value of the underlying module error. Batches are
+/// capped at [`MAX_BATCH_SIZE`] items, so every code fits in one slot and the
+/// result stays below `BATCH_ERROR_BASE + MAX_BATCH_SIZE * BATCH_ITEM_CODE_LIMIT`.
+/// Use [`decode_batch_item_error`] to recover the index and the original error.
+///
+/// # Panics
+/// If `index` is not below [`MAX_BATCH_SIZE`] or `code` is not below
+/// [`BATCH_ITEM_CODE_LIMIT`]
+pub fn batch_item_error(index: u32, code: u32) -> soroban_sdk::Error {
+    assert!(index < MAX_BATCH_SIZE, "batch index out of range");
+    assert!(
+        code < BATCH_ITEM_CODE_LIMIT,
+        "batch error code out of range"
+    );
+    let offset = index * BATCH_ITEM_CODE_LIMIT + code;
+    soroban_sdk::Error::from_contract_error(BATCH_ERROR_BASE + offset)
+}
+
+/// Split a contract error code produced by [`batch_item_error`] into the
+/// failing item's index and its module error code.
+///
+/// Returns `None` if `code` is not a batch item error.
+pub fn decode_batch_item_error(code: u32) -> Option<(u32, u32)> {
+    let offset = code.checked_sub(BATCH_ERROR_BASE)?;
+    let index = offset / BATCH_ITEM_CODE_LIMIT;
+    if index >= MAX_BATCH_SIZE {
+        return None;
+    }
+    Some((index, offset % BATCH_ITEM_CODE_LIMIT))
+}
+
+/// Deposit collateral for several assets in a single invocation
+///
+/// Each `(asset, amount)` entry goes through exactly the same validation and
+/// accounting as [`deposit_collateral`]. The call is atomic: if any entry fails,
+/// the error is returned and the host rolls back every earlier entry.
+///
+/// # Arguments
+/// * `env` - The Soroban environment
+/// * `user` - The address of the user depositing collateral
+/// * `deposits` - List of `(asset, amount)` pairs (None asset for native XLM)
+///
+/// # Returns
+/// The user's collateral balance after each entry, in input order
+///
+/// # Errors
+/// * `DepositError::InvalidAmount` - If `deposits` is empty
+/// * `DepositError::BatchTooLarge` - If `deposits` has more than [`MAX_BATCH_SIZE`] entries
+/// * Any `DepositError` from an entry, encoded with [`batch_item_error`]
+pub fn deposit_collateral_batch(
+    env: &Env,
+    user: Address,
+    deposits: Vec<(Option<Address>, i128)>,
+) -> Result<Vec<i128>, soroban_sdk::Error> {
+    if deposits.is_empty() {
+        return Err(DepositError::InvalidAmount.into());
+    }
+    if deposits.len() > MAX_BATCH_SIZE {
+        return Err(DepositError::BatchTooLarge.into());
+    }
+
+    let mut balances = Vec::new(env);
+    for (index, (asset, amount)) in deposits.iter().enumerate() {
+        let balance = deposit_collateral(env, user.clone(), asset, amount)
+            .map_err(|e| batch_item_error(index as u32, e as u32))?;
+        balances.push_back(balance);
+    }
+
+    Ok(balances)
+}
+
+/// Load the supply pool for an asset with interest accrued up to the current ledger time
+///
+/// The returned pool is not persisted; callers that modify it must save it back.
+pub fn load_supply_pool(env: &Env, asset: &Option<Address>) -> SupplyPool {
+    let now = env.ledger().timestamp();
+    let borrow_index = crate::borrow::get_borrow_index(env, asset);
+    let mut pool = env
+        .storage()
+        .persistent()
+        .get::<DepositDataKey, SupplyPool>(&DepositDataKey::SupplyPool(asset.clone()))
+        .unwrap_or(SupplyPool {
+            total_shares: 0,
+            total_underlying: 0,
+            last_update: now,
+            interest_remainder: 0,
+            borrow_index,
+        });
+
+    if pool.total_shares > 0 {
+        // Without a configured rate model the index, and so the pool, does not grow.
+        // Supplier credit rounds down; the fraction of a unit carries over.
+        let accrued = crate::interest_rate::supplier_interest(
+            env,
+            asset,
+            pool.borrow_index,
+            borrow_index,
+            pool.interest_remainder,
+        );
+        if let Ok((interest, remainder)) = accrued {
+            pool.total_underlying = pool.total_underlying.saturating_add(interest);
+            pool.interest_remainder = remainder;
+        }
+    }
+    pool.borrow_index = borrow_index;
+    pool.last_update = now;
+    pool
+}
+
+/// Persist the supply pool of an asset with interest accrued up to the current
+/// ledger time
+///
+/// Returns the exchange rate after accrual, scaled by `EXCHANGE_RATE_SCALE`.
+pub(crate) fn accrue_supply_pool(env: &Env, asset: &Option<Address>) -> i128 {
+    let pool = load_supply_pool(env, asset);
+    if pool.total_shares > 0 {
+        save_supply_pool(env, asset, &pool);
+    }
+    get_exchange_rate(env, asset.clone())
+}
+
+fn save_supply_pool(env: &Env, asset: &Option<Address>, pool: &SupplyPool) {
+    env.storage()
+        .persistent()
+        .set(&DepositDataKey::SupplyPool(asset.clone()), pool);
+}
+
+fn get_user_deposit_record(env: &Env, user: &Address, asset: &Option<Address>) -> UserDeposit {
+    env.storage()
+        .persistent()
+        .get::<DepositDataKey, UserDeposit>(&DepositDataKey::UserDeposit(
+            user.clone(),
+            asset.clone(),
+        ))
+        .unwrap_or(UserDeposit {
+            shares: 0,
+            use_as_collateral: true,
+            entry_exchange_rate: 0,
+        })
+}
+
+fn save_user_deposit_record(
+    env: &Env,
+    user: &Address,
+    asset: &Option<Address>,
+    record: &UserDeposit,
+) {
+    env.storage().persistent().set(
+        &DepositDataKey::UserDeposit(user.clone(), asset.clone()),
+        record,
+    );
+}
+
+/// Convert shares to underlying at the pool's exchange rate (rounds down)
+fn shares_to_underlying(pool: &SupplyPool, shares: i128) -> Option<i128> {
+    if pool.total_shares == 0 {
+        return Some(0);
+    }
+    shares
+        .checked_mul(pool.total_underlying)?
+        .checked_div(pool.total_shares)
+}
+
+/// Underlying that `shares` of an asset redeem for at the current exchange rate
+pub(crate) fn preview_redeem(env: &Env, asset: &Option<Address>, shares: i128) -> Option<i128> {
+    shares_to_underlying(&load_supply_pool(env, asset), shares)
+}
+
+/// Get the exchange rate of an asset's deposit shares, scaled by `EXCHANGE_RATE_SCALE`
+///
+/// Returns `EXCHANGE_RATE_SCALE` (1:1) for a pool with no shares outstanding.
+pub fn get_exchange_rate(env: &Env, asset: Option<Address>) -> i128 {
+    pool_exchange_rate(&load_supply_pool(env, &asset))
+}
+
+/// The exchange rate of an asset's deposit shares as last persisted, without
+/// accruing
+pub(crate) fn get_stored_exchange_rate(env: &Env, asset: &Option<Address>) -> i128 {
+    env.storage()
+        .persistent()
+        .get::<DepositDataKey, SupplyPool>(&DepositDataKey::SupplyPool(asset.clone()))
+        .map_or(EXCHANGE_RATE_SCALE, |pool| pool_exchange_rate(&pool))
+}
+
+fn pool_exchange_rate(pool: &SupplyPool) -> i128 {
+    if pool.total_shares == 0 {
+        return EXCHANGE_RATE_SCALE;
+    }
+    pool.total_underlying
+        .checked_mul(EXCHANGE_RATE_SCALE)
+        .and_then(|v| v.checked_div(pool.total_shares))
+        .unwrap_or(EXCHANGE_RATE_SCALE)
+}
+
+/// Get the deposit shares a user holds for an asset
+pub fn get_user_shares(env: &Env, user: Address, asset: Option<Address>) -> i128 {
+    get_user_deposit_record(env, &user, &asset).shares
+}
+
+/// Get the underlying amount a user can redeem for an asset, including accrued interest
+pub fn get_user_deposit_balance(env: &Env, user: &Address, asset: &Option<Address>) -> i128 {
+    let record = get_user_deposit_record(env, user, asset);
+    let pool = load_supply_pool(env, asset);
+    shares_to_underlying(&pool, record.shares).unwrap_or(0)
+}
+
+/// Mint shares for `amount` of underlying deposited by `user`
+///
+/// Returns the number of shares minted.
+pub(crate) fn mint_shares(
+    env: &Env,
+    user: &Address,
+    asset: &Option<Address>,
+    amount: i128,
+) -> Result<i128, DepositError> {
+    let mut pool = load_supply_pool(env, asset);
+
+    // An empty pool restarts at a 1:1 rate; rounding dust left behind by earlier
+    // depositors must not be credited to the next one
+    if pool.total_shares == 0 {
+        pool.total_underlying = 0;
+    }
+    let entry_rate = if pool.total_shares == 0 {
+        EXCHANGE_RATE_SCALE
+    } else {
+        pool.total_underlying
+            .checked_mul(EXCHANGE_RATE_SCALE)
+            .ok_or(DepositError::Overflow)?
+            .checked_div(pool.total_shares)
+            .ok_or(DepositError::Overflow)?
+    };
+
+    let shares = if pool.total_shares == 0 || pool.total_underlying == 0 {
+        amount
+    } else {
+        amount
+            .checked_mul(pool.total_shares)
+            .ok_or(DepositError::Overflow)?
+            .checked_div(pool.total_underlying)
+            .ok_or(DepositError::Overflow)?
+    };
+    if shares <= 0 {
+        return Err(DepositError::InvalidAmount);
+    }
+
+    pool.total_shares = pool
+        .total_shares
+        .checked_add(shares)
+        .ok_or(DepositError::Overflow)?;
+    pool.total_underlying = pool
+        .total_underlying
+        .checked_add(amount)
+        .ok_or(DepositError::Overflow)?;
+    save_supply_pool(env, asset, &pool);
+
+    let mut record = get_user_deposit_record(env, user, asset);
+    if record.shares == 0 {
+        // Fresh entry: snapshot the current rate instead of reusing stale state
+        record.entry_exchange_rate = entry_rate;
+        track_user_asset(env, user, asset);
+    }
+    record.shares = record
+        .shares
+        .checked_add(shares)
+        .ok_or(DepositError::Overflow)?;
+    save_user_deposit_record(env, user, asset, &record);
+
+    Ok(shares)
+}
+
+/// Burn the shares backing `amount` of underlying withdrawn by `user`
+///
+/// Shares are rounded up so a withdrawal can never take more than it burns.
+/// Returns the number of shares burned.
+///
+/// # Errors
+/// * `DepositError::InsufficientBalance` - If the user's shares redeem for less than `amount`
+pub fn redeem_shares(
+    env: &Env,
+    user: &Address,
+    asset: &Option<Address>,
+    amount: i128,
+) -> Result<i128, DepositError> {
+    let mut pool = load_supply_pool(env, asset);
+    let mut record = get_user_deposit_record(env, user, asset);
+
+    let available = shares_to_underlying(&pool, record.shares).ok_or(DepositError::Overflow)?;
+    if amount > available {
+        return Err(DepositError::InsufficientBalance);
+    }
+
+    let numerator = amount
+        .checked_mul(pool.total_shares)
+        .ok_or(DepositError::Overflow)?;
+    let mut shares = numerator / pool.total_underlying;
+    if numerator % pool.total_underlying != 0 {
+        shares += 1;
+    }
+    let shares = shares.min(record.shares);
+
+    pool.total_shares -= shares;
+    pool.total_underlying -= amount;
+    save_supply_pool(env, asset, &pool);
+
+    record.shares -= shares;
+    if record.shares == 0 {
+        // Fully redeemed: drop the record and its index entry
+        env.storage()
+            .persistent()
+            .remove(&DepositDataKey::UserDeposit(user.clone(), asset.clone()));
+        untrack_user_asset(env, user, asset);
+    } else {
+        save_user_deposit_record(env, user, asset, &record);
+    }
+
+    Ok(shares)
+}
+
+/// Remove a user's deposit record for an asset that no longer redeems for anything
+///
+/// Dust shares left by rounding are burned so the record and its index entry
+/// can be dropped. Does nothing while the deposit is still worth something.
+pub(crate) fn clear_empty_deposit(env: &Env, user: &Address, asset: &Option<Address>) {
+    let key = DepositDataKey::UserDeposit(user.clone(), asset.clone());
+    if !env.storage().persistent().has(&key) || get_user_deposit_balance(env, user, asset) > 0 {
+        return;
+    }
+
+    let record = get_user_deposit_record(env, user, asset);
+    if record.shares > 0 {
+        let mut pool = load_supply_pool(env, asset);
+        pool.total_shares = pool.total_shares.saturating_sub(record.shares).max(0);
+        save_supply_pool(env, asset, &pool);
+    }
+    env.storage().persistent().remove(&key);
+    untrack_user_asset(env, user, asset);
+}
+
+/// Get the assets a user has deposited
+pub fn get_user_assets(env: &Env, user: &Address) -> Vec<Option<Address>> {
+    env.storage()
+        .persistent()
+        .get::<DepositDataKey, Vec<Option<Address>>>(&DepositDataKey::UserAssets(user.clone()))
+        .unwrap_or_else(|| Vec::new(env))
+}
+
+/// Add an asset to the user's deposited-asset index if not already present
+fn track_user_asset(env: &Env, user: &Address, asset: &Option<Address>) {
+    let mut assets = get_user_assets(env, user);
+    if !assets.contains(asset) {
+        assets.push_back(asset.clone());
+        env.storage()
+            .persistent()
+            .set(&DepositDataKey::UserAssets(user.clone()), &assets);
+    }
+}
+
+/// Remove an asset from the user's deposited-asset index
+fn untrack_user_asset(env: &Env, user: &Address, asset: &Option<Address>) {
+    let mut assets = get_user_assets(env, user);
+    if let Some(index) = assets.first_index_of(asset) {
+        assets.remove(index);
+        let key = DepositDataKey::UserAssets(user.clone());
+        if assets.is_empty() {
+            env.storage().persistent().remove(&key);
+        } else {
+            env.storage().persistent().set(&key, &assets);
+        }
+    }
+}
+
+/// Get every asset the user holds a deposit in, with its redeemable underlying
+pub fn get_user_deposits(env: &Env, user: &Address) -> Vec<(Option<Address>, i128)> {
+    let mut deposits = Vec::new(env);
+    for asset in get_user_assets(env, user).iter() {
+        let balance = get_user_deposit_balance(env, user, &asset);
+        if balance > 0 {
+            deposits.push_back((asset, balance));
+        }
+    }
+    deposits
+}
+
+/// Check whether a user's deposit in an asset counts toward borrowing power
+///
+/// Returns true when the user has no deposit record for the asset.
+pub fn is_collateral_enabled(env: &Env, user: &Address, asset: &Option<Address>) -> bool {
+    get_user_deposit_record(env, user, asset).use_as_collateral
+}
+
+/// Whether `user` deposited `asset` in the current ledger
+pub fn deposited_this_ledger(env: &Env, user: &Address, asset: &Option<Address>) -> bool {
+    env.storage()
+        .persistent()
+        .get::<DepositDataKey, u32>(&DepositDataKey::LastDepositLedger(
+            user.clone(),
+            asset.clone(),
+        ))
+        .map(|sequence| sequence == env.ledger().sequence())
+        .unwrap_or(false)
+}
+
+/// Get the collateral that counts toward a user's borrowing power
+///
+/// This is the `CollateralBalance` aggregate minus the underlying of every deposit
+/// the user has disabled as collateral, floored at zero.
+pub fn get_effective_collateral(env: &Env, user: &Address) -> i128 {
+    let total = env
+        .storage()
+        .persistent()
+        .get::<DepositDataKey, i128>(&DepositDataKey::CollateralBalance(user.clone()))
+        .unwrap_or(0);
+
+    let mut excluded: i128 = 0;
+    for asset in get_user_assets(env, user).iter() {
+        let record = get_user_deposit_record(env, user, &asset);
+        if !record.use_as_collateral && record.shares > 0 {
+            excluded = excluded.saturating_add(get_user_deposit_balance(env, user, &asset));
+        }
+    }
+
+    total.saturating_sub(excluded).max(0)
+}
+
+/// Enable or disable a deposit as collateral
+///
+/// Disabled deposits keep earning the supply rate but are excluded from borrowing
+/// power and cannot be seized in liquidation.
+///
+/// # Arguments
+/// * `env` - The Soroban environment
+/// * `user` - The depositor
+/// * `asset` - The deposited asset (None for native XLM)
+/// * `enabled` - Whether the deposit counts as collateral
+///
+/// # Errors
+/// * `DepositError::InsufficientBalance` - If the user has no deposit in this asset
+/// * `DepositError::InsufficientCollateralRatio` - If disabling would break the minimum collateral ratio
+/// * `DepositError::IsolationModeConflict` - If enabling would mix isolated and other collateral
+pub fn set_use_as_collateral(
+    env: &Env,
+    user: Address,
+    asset: Option<Address>,
+    enabled: bool,
+) -> Result<(), DepositError> {
+    user.require_auth();
+
+    let mut record = get_user_deposit_record(env, &user, &asset);
+    if record.shares == 0 {
+        return Err(DepositError::InsufficientBalance);
+    }
+    if record.use_as_collateral == enabled {
+        return Ok(());
+    }
+
+    if enabled && crate::isolation::conflicts_with_collateral(env, &user, &asset) {
+        return Err(DepositError::IsolationModeConflict);
+    }
+
+    // Disabling removes the deposit from the health check, exactly like withdrawing it
+    if !enabled {
+        let balance = get_user_deposit_balance(env, &user, &asset);
+        crate::withdraw::validate_collateral_ratio_after_withdraw(
+            env,
+            &user,
+            balance,
+            asset.as_ref(),
+        )
+        .map_err(|e| match e {
+            crate::withdraw::WithdrawError::Overflow => DepositError::Overflow,
+            _ => DepositError::InsufficientCollateralRatio,
+        })?;
+    }
+
+    record.use_as_collateral = enabled;
+    save_user_deposit_record(env, &user, &asset, &record);
+
+    emit_collateral_toggled(
+        env,
+        CollateralToggledEvent {
+            user,
+            asset,
+            enabled,
+            timestamp: env.ledger().timestamp(),
+        },
+    );
+
+    Ok(())
+}
+
+/// Deposit collateral and attribute the deposit to a referrer.
+///
+/// Behaves like [`deposit_collateral`]. When `referrer` is given it is recorded
+/// as the user's referrer on first use and the credited amount is added to the
+/// referrer's cumulative volume. A later, different referrer does not replace
+/// the recorded one.
+///
+/// # Errors
+/// * `DepositError::SelfReferral` - If `referrer` equals `user`
+/// * Any error returned by [`deposit_collateral`]
+pub fn deposit_collateral_with_referral(
+    env: &Env,
+    user: Address,
+    asset: Option<Address>,
+    amount: i128,
+    referrer: Option<Address>,
+) -> Result<i128, DepositError> {
+    if referrer.as_ref() == Some(&user) {
+        return Err(DepositError::SelfReferral);
+    }
+
+    let collateral_key = DepositDataKey::CollateralBalance(user.clone());
+    let before = env
+        .storage()
+        .persistent()
+        .get::<DepositDataKey, i128>(&collateral_key)
+        .unwrap_or(0);
+
+    let new_collateral = deposit_collateral(env, user.clone(), asset.clone(), amount)?;
+
+    let referrer = match referrer {
+        Some(referrer) => referrer,
+        None => return Ok(new_collateral),
+    };
+
+    let referrer_key = DepositDataKey::Referrer(user.clone());
+    let referrer = match env
+        .storage()
+        .persistent()
+        .get::<DepositDataKey, Address>(&referrer_key)
+    {
+        Some(existing) => existing,
+        None => {
+            env.storage().persistent().set(&referrer_key, &referrer);
+            referrer
+        }
+    };
+
+    let credited = new_collateral
+        .checked_sub(before)
+        .ok_or(DepositError::Overflow)?;
+    let volume_key = DepositDataKey::ReferralVolume(referrer.clone());
+    let volume = env
+        .storage()
+        .persistent()
+        .get::<DepositDataKey, i128>(&volume_key)
+        .unwrap_or(0)
+        .checked_add(credited)
+        .ok_or(DepositError::Overflow)?;
+    env.storage().persistent().set(&volume_key, &volume);
+
+    emit_referral(
+        env,
+        ReferralEvent {
+            referrer,
+            user,
+            asset,
+            amount: credited,
+            timestamp: env.ledger().timestamp(),
+        },
+    );
+
+    Ok(new_collateral)
+}
+
+/// Deposit the user's whole balance of an asset.
+///
+/// For tokens the user's full token balance is deposited, optionally capped by
+/// `max_amount`. For native XLM (`asset = None`) `max_amount` is required and is
+/// capped at the user's XLM balance minus the admin-set safety buffer, so enough
+/// XLM remains for fees and account reserves.
+///
+/// # Returns
+/// The amount deposited
+///
+/// # Errors
+/// * `DepositError::InvalidAmount` - If `max_amount` is missing for XLM or not positive
+/// * `DepositError::InvalidAsset` - If XLM is used without a native asset address
+/// * `DepositError::BelowSafetyBuffer` - If nothing is left above the buffer
+/// * Any error returned by [`deposit_collateral`]
+pub fn deposit_all(
+    env: &Env,
+    user: Address,
+    asset: Option<Address>,
+    max_amount: Option<i128>,
+) -> Result<i128, DepositError> {
+    if let Some(max) = max_amount {
+        if max <= 0 {
+            return Err(DepositError::InvalidAmount);
+        }
+    }
+
+    let (token, buffer) = match &asset {
+        Some(addr) => (addr.clone(), 0),
+        None => {
+            if max_amount.is_none() {
+                return Err(DepositError::InvalidAmount);
+            }
+            let native = env
+                .storage()
+                .persistent()
+                .get::<DepositDataKey, Address>(&DepositDataKey::NativeAssetAddress)
+                .ok_or(DepositError::InvalidAsset)?;
+            (native, get_native_deposit_buffer(env))
+        }
+    };
+
+    let balance = soroban_sdk::token::Client::new(env, &token).balance(&user);
+    let available = balance.checked_sub(buffer).ok_or(DepositError::Overflow)?;
+    if available <= 0 {
+        return Err(DepositError::BelowSafetyBuffer);
+    }
+    let amount = match max_amount {
+        Some(max) => available.min(max),
+        None => available,
+    };
+
+    deposit_collateral(env, user, asset, amount)?;
+    Ok(amount)
+}
+
+/// Set the XLM safety buffer kept back by `deposit_all` (admin only).
+pub fn set_native_deposit_buffer(
+    env: &Env,
+    caller: Address,
+    buffer: i128,
+) -> Result<(), DepositError> {
+    let admin = crate::admin::get_admin(env).ok_or(DepositError::Unauthorized)?;
+    if caller != admin {
+        return Err(DepositError::Unauthorized);
+    }
+    caller.require_auth();
+    if buffer < 0 {
+        return Err(DepositError::InvalidAmount);
+    }
+    env.storage()
+        .persistent()
+        .set(&DepositDataKey::NativeDepositBuffer, &buffer);
+    Ok(())
+}
+
+/// Get the XLM safety buffer kept back by `deposit_all`
+pub fn get_native_deposit_buffer(env: &Env) -> i128 {
+    env.storage()
+        .persistent()
+        .get::<DepositDataKey, i128>(&DepositDataKey::NativeDepositBuffer)
+        .unwrap_or(0)
+}
+
+/// Set the parameters of an asset (admin only).
+///
+/// # Errors
+/// * `DepositError::Unauthorized` - If caller is not the admin
+/// * `DepositError::InvalidAssetParams` - If a factor or fee is outside
+///   `0..=10000` bps, `borrow_fee_bps` exceeds [`MAX_BORROW_FEE_BPS`],
+///   `withdraw_fee_bps` exceeds [`MAX_WITHDRAW_FEE_BPS`], or a cap or minimum is
+///   negative
+pub fn update_asset_params(
+    env: &Env,
+    caller: Address,
+    asset: Address,
+    params: AssetParams,
+) -> Result<(), DepositError> {
+    let admin = crate::admin::get_admin(env).ok_or(DepositError::Unauthorized)?;
+    if caller != admin {
+        return Err(DepositError::Unauthorized);
+    }
+    caller.require_auth();
+
+    if !(0..=10_000).contains(&params.collateral_factor)
+        || !(0..=MAX_BORROW_FEE_BPS).contains(&params.borrow_fee_bps)
+        || !(0..=MAX_WITHDRAW_FEE_BPS).contains(&params.withdraw_fee_bps)
+        || params.max_deposit < 0
+        || params.max_deposit_per_user < 0
+        || params.min_borrow < 0
+        || params.debt_ceiling < 0
+    {
+        return Err(DepositError::InvalidAssetParams);
+    }
+
+    env.storage()
+        .persistent()
+        .set(&DepositDataKey::AssetParams(asset), &params);
+    Ok(())
+}
+
+/// Set the share of reserve income paid to referrers (admin only).
+pub fn set_referral_fee_bps(env: &Env, caller: Address, fee_bps: i128) -> Result<(), DepositError> {
+    let admin = crate::admin::get_admin(env).ok_or(DepositError::Unauthorized)?;
+    if caller != admin {
+        return Err(DepositError::Unauthorized);
+    }
+    caller.require_auth();
+    if !(0..=10_000).contains(&fee_bps) {
+        return Err(DepositError::InvalidAmount);
+    }
+    env.storage()
+        .persistent()
+        .set(&DepositDataKey::ReferralFeeBps, &fee_bps);
+    Ok(())
+}
+
+/// Get the share of reserve income paid to referrers (basis points)
+pub fn get_referral_fee_bps(env: &Env) -> i128 {
+    env.storage()
+        .persistent()
+        .get::<DepositDataKey, i128>(&DepositDataKey::ReferralFeeBps)
+        .unwrap_or(0)
+}
+
+/// Get the referrer recorded for a user, if any
+pub fn get_referrer(env: &Env, user: &Address) -> Option<Address> {
+    env.storage()
+        .persistent()
+        .get::<DepositDataKey, Address>(&DepositDataKey::Referrer(user.clone()))
+}
+
+/// Get the cumulative deposit volume referred by an address
+pub fn get_referral_volume(env: &Env, referrer: &Address) -> i128 {
+    env.storage()
+        .persistent()
+        .get::<DepositDataKey, i128>(&DepositDataKey::ReferralVolume(referrer.clone()))
+        .unwrap_or(0)
+}
+
+/// Get the unclaimed referral rewards of an address, per asset
+pub fn get_referral_rewards(env: &Env, referrer: &Address) -> Map<Option<Address>, i128> {
+    env.storage()
+        .persistent()
+        .get::<DepositDataKey, Map<Option<Address>, i128>>(&DepositDataKey::ReferralRewards(
+            referrer.clone(),
+        ))
+        .unwrap_or(Map::new(env))
+}
+
+/// Credit reserve income generated by `user` to the protocol reserve.
+///
+/// If the user has a referrer and a referral fee is configured, the referrer's
+/// share is moved into their claimable rewards instead of the reserve.
+pub fn credit_protocol_reserve(
+    env: &Env,
+    user: &Address,
+    asset: &Option<Address>,
+    amount: i128,
+) -> Result<(), DepositError> {
+    if amount <= 0 {
+        return Ok(());
+    }
+
+    let referral_cut = credit_referral_cut(env, user, asset, amount)?;
+    let reserve_amount = amount
+        .checked_sub(referral_cut)
+        .ok_or(DepositError::Overflow)?;
+    add_protocol_reserve(env, asset, reserve_amount)
+}
+
+/// Move the referral share of reserve income generated by `user`, already
+/// credited to the protocol reserve, into their referrer's claimable rewards.
+///
+/// Interest reaches the reserve as it accrues, for the pool as a whole; its
+/// referral share is attributed here once the borrower pays it. The amount
+/// moved is capped at the reserve balance.
+pub(crate) fn divert_reserve_to_referrer(
+    env: &Env,
+    user: &Address,
+    asset: &Option<Address>,
+    amount: i128,
+) -> Result<(), DepositError> {
+    let reserve_key = DepositDataKey::ProtocolReserve(asset.clone());
+    let reserve = env
+        .storage()
+        .persistent()
+        .get::<DepositDataKey, i128>(&reserve_key)
+        .unwrap_or(0);
+    let amount = amount.min(reserve);
+    if amount <= 0 {
+        return Ok(());
+    }
+
+    let referral_cut = credit_referral_cut(env, user, asset, amount)?;
+    if referral_cut > 0 {
+        env.storage()
+            .persistent()
+            .set(&reserve_key, &(reserve - referral_cut));
+        update_asset_totals(env, asset, 0, 0, -referral_cut)?;
+    }
+    Ok(())
+}
+
+/// Add the referral fee share of `amount` to the rewards of `user`'s referrer
+///
+/// Returns the share, zero if the user has no referrer or no fee is configured.
+fn credit_referral_cut(
+    env: &Env,
+    user: &Address,
+    asset: &Option<Address>,
+    amount: i128,
+) -> Result<i128, DepositError> {
+    let fee_bps = get_referral_fee_bps(env);
+    if fee_bps <= 0 {
+        return Ok(0);
+    }
+    let Some(referrer) = get_referrer(env, user) else {
+        return Ok(0);
+    };
+    let referral_cut = amount
+        .checked_mul(fee_bps)
+        .ok_or(DepositError::Overflow)?
+        .checked_div(10_000)
+        .ok_or(DepositError::Overflow)?;
+    if referral_cut > 0 {
+        let mut rewards = get_referral_rewards(env, &referrer);
+        let current = rewards.get(asset.clone()).unwrap_or(0);
+        rewards.set(
+            asset.clone(),
+            current
+                .checked_add(referral_cut)
+                .ok_or(DepositError::Overflow)?,
+        );
+        env.storage()
+            .persistent()
+            .set(&DepositDataKey::ReferralRewards(referrer), &rewards);
+    }
+    Ok(referral_cut)
+}
+
+/// Add `amount` to the protocol reserve of `asset`, without a referral cut
+pub(crate) fn add_protocol_reserve(
+    env: &Env,
+    asset: &Option<Address>,
+    amount: i128,
+) -> Result<(), DepositError> {
+    if amount <= 0 {
+        return Ok(());
+    }
+
+    let reserve_key = DepositDataKey::ProtocolReserve(asset.clone());
+    let current_reserve = env
+        .storage()
+        .persistent()
+        .get::<DepositDataKey, i128>(&reserve_key)
+        .unwrap_or(0);
+    env.storage().persistent().set(
+        &reserve_key,
+        &current_reserve
+            .checked_add(amount)
+            .ok_or(DepositError::Overflow)?,
+    );
+    update_asset_totals(env, asset, 0, 0, amount)
+}
+
+/// Donate tokens to the protocol reserve.
+///
+/// Callable by anyone. Tokens are pulled from the donor with `transfer_from`
+/// and the amount actually received is added to `ProtocolReserve(asset)`.
+/// Native XLM donations require the native asset address to be configured.
+///
+/// # Returns
+/// The new reserve balance for the asset
+///
+/// # Errors
+/// * `DepositError::InvalidAmount` - If amount is zero or negative
+/// * `DepositError::InvalidAsset` - If the asset cannot be transferred
+/// * `DepositError::InsufficientBalance` - If the donor lacks the tokens
+/// * `DepositError::TransferShortfall` - If the contract received nothing
+pub fn donate_to_reserves(
+    env: &Env,
+    donor: Address,
+    asset: Option<Address>,
+    amount: i128,
+) -> Result<i128, DepositError> {
+    donor.require_auth();
+    if amount <= 0 {
+        return Err(DepositError::InvalidAmount);
+    }
+
+    let asset_addr = match &asset {
+        Some(addr) => addr.clone(),
+        None => env
+            .storage()
+            .persistent()
+            .get::<DepositDataKey, Address>(&DepositDataKey::NativeAssetAddress)
+            .ok_or(DepositError::InvalidAsset)?,
+    };
+    if asset_addr == env.current_contract_address() {
+        return Err(DepositError::InvalidAsset);
+    }
+
+    let token_client = soroban_sdk::token::Client::new(env, &asset_addr);
+    if token_client.balance(&donor) < amount {
+        return Err(DepositError::InsufficientBalance);
+    }
+    let balance_before = token_client.balance(&env.current_contract_address());
+    token_client.transfer_from(
+        &env.current_contract_address(),
+        &donor,
+        &env.current_contract_address(),
+        &amount,
+    );
+    let received = token_client
+        .balance(&env.current_contract_address())
+        .checked_sub(balance_before)
+        .ok_or(DepositError::Overflow)?;
+    if received <= 0 {
+        return Err(DepositError::TransferShortfall);
+    }
+    let received = received.min(amount);
+
+    let reserve_key = DepositDataKey::ProtocolReserve(asset.clone());
+    let new_reserve = env
+        .storage()
+        .persistent()
+        .get::<DepositDataKey, i128>(&reserve_key)
+        .unwrap_or(0)
+        .checked_add(received)
+        .ok_or(DepositError::Overflow)?;
+    env.storage().persistent().set(&reserve_key, &new_reserve);
+    update_asset_totals(env, &asset, 0, 0, received)?;
+
+    emit_reserve_donation(
+        env,
+        ReserveDonationEvent {
+            donor,
+            asset,
+            amount: received,
+            timestamp: env.ledger().timestamp(),
+        },
+    );
+
+    Ok(new_reserve)
+}
+
+/// Claim all accrued referral rewards.
+///
+/// Transfers every non-zero reward balance to the referrer and clears it.
+/// Native XLM rewards stay claimable until the native asset address is
+/// configured.
+///
+/// # Returns
+/// The claimed `(asset, amount)` pairs
+///
+/// # Errors
+/// * `DepositError::NoReferralRewards` - If nothing is claimable
+pub fn claim_referral_rewards(
+    env: &Env,
+    referrer: Address,
+) -> Result<Vec<(Option<Address>, i128)>, DepositError> {
+    referrer.require_auth();
+
+    let rewards = get_referral_rewards(env, &referrer);
+    let mut remaining = Map::new(env);
+    let mut claimed = Vec::new(env);
+    let mut payouts: Vec<(Address, i128)> = Vec::new(env);
+    for (asset, amount) in rewards.iter() {
+        if amount <= 0 {
+            continue;
+        }
+        let asset_addr = match &asset {
+            Some(addr) => Some(addr.clone()),
+            None => env
+                .storage()
+                .persistent()
+                .get::<DepositDataKey, Address>(&DepositDataKey::NativeAssetAddress),
+        };
+        match asset_addr {
+            Some(asset_addr) => {
+                payouts.push_back((asset_addr, amount));
+                claimed.push_back((asset, amount));
+            }
+            None => remaining.set(asset, amount),
+        }
+    }
+
+    if claimed.is_empty() {
+        return Err(DepositError::NoReferralRewards);
+    }
+
+    // Clear the rewards before handing control to the token contracts
+    let rewards_key = DepositDataKey::ReferralRewards(referrer.clone());
+    if remaining.is_empty() {
+        env.storage().persistent().remove(&rewards_key);
+    } else {
+        env.storage().persistent().set(&rewards_key, &remaining);
+    }
+    for (asset_addr, amount) in payouts.iter() {
+        let token_client = soroban_sdk::token::Client::new(env, &asset_addr);
+        token_client.transfer(&env.current_contract_address(), &referrer, &amount);
+    }
+    Ok(claimed)
+}
+
+/// Add or remove a user from an asset's deposit allowlist (admin only).
+///
+/// The list is only enforced while `allowlist_enabled` is set on the asset.
+/// Removing a user does not affect their existing balance, which stays withdrawable.
+pub fn set_deposit_allowlisted(
+    env: &Env,
+    admin: Address,
+    asset: Address,
+    user: Address,
+    allowed: bool,
+) -> Result<(), DepositError> {
+    let current_admin = crate::admin::get_admin(env).ok_or(DepositError::Unauthorized)?;
+    if admin != current_admin {
+        return Err(DepositError::Unauthorized);
+    }
+    admin.require_auth();
+
+    let key = DepositDataKey::DepositAllowlist(asset, user);
+    if allowed {
+        env.storage().persistent().set(&key, &true);
+    } else {
+        env.storage().persistent().remove(&key);
+    }
+    Ok(())
+}
+
+/// Check whether a user is on an asset's deposit allowlist
+pub fn is_deposit_allowlisted(env: &Env, asset: &Address, user: &Address) -> bool {
+    env.storage()
+        .persistent()
+        .get::<DepositDataKey, bool>(&DepositDataKey::DepositAllowlist(
+            asset.clone(),
+            user.clone(),
+        ))
+        .unwrap_or(false)
+}
+
 /// Set the native asset address (admin only).
 /// Required for deposit/borrow/repay with asset = None. Must be called before using None as asset.
 pub fn set_native_asset_address(
@@ -384,6 +1727,44 @@ pub fn set_native_asset_address(
     Ok(())
 }
 
+/// Get the supplied, borrowed and reserve totals for an asset
+pub fn get_asset_totals(env: &Env, asset: &Option<Address>) -> AssetTotals {
+    env.storage()
+        .persistent()
+        .get::<DepositDataKey, AssetTotals>(&DepositDataKey::AssetTotals(asset.clone()))
+        .unwrap_or_default()
+}
+
+/// Apply signed deltas to an asset's totals. Totals are floored at zero.
+pub fn update_asset_totals(
+    env: &Env,
+    asset: &Option<Address>,
+    supplied_delta: i128,
+    borrowed_delta: i128,
+    reserves_delta: i128,
+) -> Result<(), DepositError> {
+    let mut totals = get_asset_totals(env, asset);
+    totals.total_supplied = totals
+        .total_supplied
+        .checked_add(supplied_delta)
+        .ok_or(DepositError::Overflow)?
+        .max(0);
+    totals.total_borrowed = totals
+        .total_borrowed
+        .checked_add(borrowed_delta)
+        .ok_or(DepositError::Overflow)?
+        .max(0);
+    totals.total_reserves = totals
+        .total_reserves
+        .checked_add(reserves_delta)
+        .ok_or(DepositError::Overflow)?
+        .max(0);
+    env.storage()
+        .persistent()
+        .set(&DepositDataKey::AssetTotals(asset.clone()), &totals);
+    Ok(())
+}
+
 /// Update user analytics after deposit
 pub fn update_user_analytics(
     env: &Env,
@@ -2,27 +2,47 @@
 
 use soroban_sdk::{contractevent, Address, Env, String, Symbol, Vec};
 
+use crate::oracle::PriceSourceFailure;
 use crate::types::{AssetStatus, ProposalType, VoteType};
 
 // ============================================================================
 // Core Lending Events (Existing)
 // ============================================================================
 
+/// Payload version of [`DepositEvent`]. Version 1 payloads carried only
+/// `user`, `asset`, `amount` and `timestamp` and had no `version` field.
+pub const DEPOSIT_EVENT_VERSION: u32 = 2;
+
 #[contractevent]
 #[derive(Clone, Debug)]
 pub struct DepositEvent {
+    pub version: u32,
     pub user: Address,
     pub asset: Option<Address>,
     pub amount: i128,
+    /// User's balance in `asset` after the deposit
+    pub balance: i128,
+    /// User's aggregate collateral balance after the deposit
+    pub total_collateral: i128,
+    /// Supply exchange rate of `asset`, scaled by `EXCHANGE_RATE_SCALE`
+    pub exchange_rate: i128,
     pub timestamp: u64,
 }
 
 #[contractevent]
 #[derive(Clone, Debug)]
 pub struct WithdrawalEvent {
+    /// Account whose collateral was debited
     pub user: Address,
+    /// Account that received the tokens
+    pub to: Address,
     pub asset: Option<Address>,
+    /// Amount debited from the position
     pub amount: i128,
+    /// Withdrawal fee kept as reserves; the recipient received `amount - fee`
+    pub fee: i128,
+    /// Id of the stored withdrawal receipt
+    pub receipt_id: u64,
     pub timestamp: u64,
 }
 
@@ -31,16 +51,89 @@ pub struct WithdrawalEvent {
 pub struct BorrowEvent {
     pub user: Address,
     pub asset: Option<Address>,
+    /// Principal added to the user's debt
     pub amount: i128,
+    /// Origination fee kept in reserves; the user receives `amount - fee`
+    pub fee: i128,
+    /// Health factor after the borrow, as reported by `get_health_factor`
+    /// (0 if it could not be computed)
+    pub health_factor: i128,
+    /// Account collateral value in base currency after the borrow
+    pub collateral_value: i128,
+    /// Account debt value in base currency after the borrow
+    pub debt_value: i128,
     pub timestamp: u64,
 }
 
 #[contractevent]
 #[derive(Clone, Debug)]
-pub struct RepayEvent {
+pub struct CreditDelegationApprovedEvent {
+    pub delegator: Address,
+    pub delegatee: Address,
+    pub asset: Option<Address>,
+    /// New allowance; 0 revokes the delegation
+    pub amount: i128,
+    pub timestamp: u64,
+}
+
+#[contractevent]
+#[derive(Clone, Debug)]
+pub struct DelegatedBorrowEvent {
+    pub delegator: Address,
+    pub delegatee: Address,
+    pub asset: Option<Address>,
+    pub amount: i128,
+    /// Allowance left after this borrow
+    pub remaining_allowance: i128,
+    pub timestamp: u64,
+}
+
+#[contractevent]
+#[derive(Clone, Debug)]
+pub struct BorrowRateModeUpdatedEvent {
+    /// User who swapped modes, or the admin who rebalanced
+    pub actor: Address,
     pub user: Address,
     pub asset: Option<Address>,
+    /// Locked stable rate in basis points; 0 when the debt is now variable
+    pub stable_rate_bps: i128,
+    pub timestamp: u64,
+}
+
+#[contractevent]
+#[derive(Clone, Debug)]
+pub struct RepayEvent {
+    /// Address the repaid tokens came from
+    pub payer: Address,
+    /// Owner of the debt; differs from `payer` when repaying on behalf
+    pub borrower: Address,
+    /// Published as a topic after `repay_event`, so consumers can filter by asset
+    #[topic]
+    pub asset: Option<Address>,
     pub amount: i128,
+    pub principal_paid: i128,
+    pub interest_paid: i128,
+    /// Share of `interest_paid` credited to the protocol reserve
+    pub reserve_amount: i128,
+    /// Debt left after the payment, principal plus interest
+    pub remaining_debt: i128,
+    pub timestamp: u64,
+}
+
+#[contractevent]
+#[derive(Clone, Debug)]
+pub struct DebtWrittenOffEvent {
+    pub admin: Address,
+    pub borrower: Address,
+    pub asset: Option<Address>,
+    /// Debt cleared from the borrower's position
+    pub amount: i128,
+    /// Part of `amount` absorbed by the protocol reserve
+    pub from_reserve: i128,
+    /// Part of `amount` recorded as bad debt
+    pub bad_debt: i128,
+    /// Borrower's debt left after the write-off
+    pub remaining_debt: i128,
     pub timestamp: u64,
 }
 
@@ -48,12 +141,77 @@ pub struct RepayEvent {
 #[derive(Clone, Debug)]
 pub struct LiquidationEvent {
     pub liquidator: Address,
+    /// Published as the first topic after `liquidation_event`
+    #[topic]
     pub borrower: Address,
     pub debt_asset: Option<Address>,
+    /// Published as the second topic after `liquidation_event`
+    #[topic]
     pub collateral_asset: Option<Address>,
     pub debt_liquidated: i128,
     pub collateral_seized: i128,
     pub incentive_amount: i128,
+    /// Part of `incentive_amount` credited to the protocol reserve; the
+    /// liquidator receives the rest
+    pub protocol_fee: i128,
+    /// Borrower's health factor (bps) before the seizure
+    pub health_factor_before: i128,
+    /// Borrower's health factor (bps) after the seizure, before any bad debt
+    /// write-off
+    pub health_factor_after: i128,
+    /// Oracle price of `debt_asset` used to convert the repaid debt
+    pub debt_price: i128,
+    /// Oracle price of `collateral_asset` used to convert the repaid debt
+    pub collateral_price: i128,
+    pub timestamp: u64,
+}
+
+/// Debt left unbacked once a liquidation exhausts the borrower's collateral
+#[contractevent]
+#[derive(Clone, Debug)]
+pub struct LiquidationBadDebtEvent {
+    pub liquidator: Address,
+    pub borrower: Address,
+    pub debt_asset: Option<Address>,
+    /// Debt cleared from the borrower's position
+    pub amount: i128,
+    /// Part of `amount` absorbed by the protocol reserve
+    pub from_reserve: i128,
+    /// Part of `amount` recorded as bad debt
+    pub bad_debt: i128,
+    pub timestamp: u64,
+}
+
+/// Collateral paid from the reserve to lift a liquidator's incentive to the
+/// minimum liquidation reward
+#[contractevent]
+#[derive(Clone, Debug)]
+pub struct LiquidationRewardTopUpEvent {
+    pub liquidator: Address,
+    pub borrower: Address,
+    pub collateral_asset: Option<Address>,
+    /// Incentive the liquidator kept before the top-up, in collateral units
+    pub incentive_amount: i128,
+    /// Collateral drawn from `ProtocolReserve(collateral_asset)`
+    pub top_up_amount: i128,
+    /// Minimum reward in force, in base currency
+    pub min_reward: i128,
+    pub timestamp: u64,
+}
+
+#[contractevent]
+#[derive(Clone, Debug)]
+pub struct ForcedLiquidationEvent {
+    pub admin: Address,
+    pub borrower: Address,
+    pub debt_asset: Option<Address>,
+    pub collateral_asset: Option<Address>,
+    pub debt_liquidated: i128,
+    pub collateral_seized: i128,
+    /// Whether the emergency pause was active and overridden
+    pub emergency_paused: bool,
+    /// Whether the `pause_liquidate` switch was set and overridden
+    pub operation_paused: bool,
     pub timestamp: u64,
 }
 
@@ -78,6 +236,69 @@ pub struct FlashLoanRepaidEvent {
     pub timestamp: u64,
 }
 
+/// A rate model update applied at once, bypassing the maximum rate delta per day
+#[contractevent]
+#[derive(Clone, Debug)]
+pub struct RateConfigForcedEvent {
+    pub admin: Address,
+    /// Protocol utilization when the update was applied (basis points)
+    pub utilization_bps: i128,
+    /// Borrow rate in effect just before the update (basis points)
+    pub previous_rate_bps: i128,
+    /// Borrow rate under the new model (basis points)
+    pub new_rate_bps: i128,
+    /// Maximum rate delta per day that was bypassed (0 if none was set)
+    pub max_rate_delta_bps_per_day: i128,
+    pub timestamp: u64,
+}
+
+/// The utilization-spike circuit breaker tripped on an accrual
+#[contractevent]
+#[derive(Clone, Debug)]
+pub struct CircuitBreakerTrippedEvent {
+    /// Protocol utilization the accrual observed (basis points)
+    pub utilization_bps: i128,
+    /// Added to the borrow rate until the breaker is released (basis points)
+    pub bonus_bps: i128,
+    pub timestamp: u64,
+}
+
+/// The circuit breaker was released after its cool-off below the release threshold
+#[contractevent]
+#[derive(Clone, Debug)]
+pub struct CircuitBreakerReleasedEvent {
+    /// Protocol utilization the accrual observed (basis points)
+    pub utilization_bps: i128,
+    /// Time the breaker tripped
+    pub tripped_at: u64,
+    pub timestamp: u64,
+}
+
+/// Interest accrued on an asset since its previous accrual
+///
+/// Published under the `accrue` topic, and only when time has elapsed.
+#[contractevent(topics = ["accrue"])]
+#[derive(Clone, Debug)]
+pub struct AccrueEvent {
+    /// Published as the first topic after `accrue`
+    #[topic]
+    pub asset: Option<Address>,
+    /// Seconds since the asset's previous accrual
+    pub elapsed_secs: u64,
+    /// Borrow index before and after the accrual, scaled by `BORROW_INDEX_SCALE`
+    pub borrow_index_before: i128,
+    pub borrow_index_after: i128,
+    /// Supply exchange rate before and after the accrual, scaled by
+    /// `EXCHANGE_RATE_SCALE`
+    pub supply_index_before: i128,
+    pub supply_index_after: i128,
+    /// Borrow interest charged on the asset's outstanding borrows over the period
+    pub interest_accrued: i128,
+    /// Part of the borrow interest credited to the protocol reserve
+    pub reserve_portion: i128,
+    pub timestamp: u64,
+}
+
 #[contractevent]
 #[derive(Clone, Debug)]
 pub struct AdminActionEvent {
@@ -86,6 +307,18 @@ pub struct AdminActionEvent {
     pub timestamp: u64,
 }
 
+/// A price was served from the fallback feed because the primary was rejected
+#[contractevent]
+#[derive(Clone, Debug)]
+pub struct FallbackPriceUsedEvent {
+    pub asset: Address,
+    pub fallback_oracle: Address,
+    /// Why the primary feed was rejected
+    pub primary_failure: PriceSourceFailure,
+    pub price: i128,
+    pub timestamp: u64,
+}
+
 #[contractevent]
 #[derive(Clone, Debug)]
 pub struct PriceUpdatedEvent {
@@ -113,6 +346,187 @@ pub struct PauseStateChangedEvent {
     pub timestamp: u64,
 }
 
+#[contractevent]
+#[derive(Clone, Debug)]
+pub struct AssetPauseChangedEvent {
+    pub actor: Address,
+    pub asset: Option<Address>,
+    pub operation: Symbol,
+    pub paused: bool,
+    pub timestamp: u64,
+}
+
+#[contractevent]
+#[derive(Clone, Debug)]
+pub struct EModeCategoryUpdatedEvent {
+    pub actor: Address,
+    pub category_id: u32,
+    pub collateral_factor: i128,
+    pub liquidation_threshold: i128,
+    pub timestamp: u64,
+}
+
+#[contractevent]
+#[derive(Clone, Debug)]
+pub struct UserEModeSetEvent {
+    pub user: Address,
+    /// 0 when the user left e-mode
+    pub category_id: u32,
+    pub timestamp: u64,
+}
+
+#[contractevent]
+#[derive(Clone, Debug)]
+pub struct CollateralToggledEvent {
+    pub user: Address,
+    pub asset: Option<Address>,
+    pub enabled: bool,
+    pub timestamp: u64,
+}
+
+#[contractevent]
+#[derive(Clone, Debug)]
+pub struct ReferralEvent {
+    pub referrer: Address,
+    pub user: Address,
+    pub asset: Option<Address>,
+    pub amount: i128,
+    pub timestamp: u64,
+}
+
+#[contractevent]
+#[derive(Clone, Debug)]
+pub struct ReserveDonationEvent {
+    pub donor: Address,
+    pub asset: Option<Address>,
+    pub amount: i128,
+    pub timestamp: u64,
+}
+
+#[contractevent]
+#[derive(Clone, Debug)]
+pub struct WithdrawalQueuedEvent {
+    pub request_id: u64,
+    pub user: Address,
+    pub asset: Option<Address>,
+    pub amount: i128,
+    pub timestamp: u64,
+}
+
+#[contractevent]
+#[derive(Clone, Debug)]
+pub struct WithdrawalCancelledEvent {
+    pub request_id: u64,
+    pub user: Address,
+    pub asset: Option<Address>,
+    /// Unpaid amount returned to the user's deposit
+    pub amount: i128,
+    pub timestamp: u64,
+}
+
+#[contractevent]
+#[derive(Clone, Debug)]
+pub struct WithdrawalScheduledEvent {
+    pub schedule_id: u64,
+    pub user: Address,
+    pub asset: Option<Address>,
+    pub total_amount: i128,
+    pub tranches: u32,
+    pub interval_secs: u64,
+    pub timestamp: u64,
+}
+
+#[contractevent]
+#[derive(Clone, Debug)]
+pub struct WithdrawalScheduleCancelledEvent {
+    pub schedule_id: u64,
+    pub user: Address,
+    /// Scheduled amount that was not paid out
+    pub remaining: i128,
+    pub timestamp: u64,
+}
+
+#[contractevent]
+#[derive(Clone, Debug)]
+pub struct RepaymentScheduledEvent {
+    pub schedule_id: u64,
+    pub user: Address,
+    pub asset: Option<Address>,
+    /// Debt snapshotted into the schedule
+    pub total_amount: i128,
+    pub installments: u32,
+    pub interval_secs: u64,
+    pub timestamp: u64,
+}
+
+#[contractevent]
+#[derive(Clone, Debug)]
+pub struct InstallmentRepaidEvent {
+    pub schedule_id: u64,
+    pub user: Address,
+    pub amount: i128,
+    pub installments_paid: u32,
+    pub timestamp: u64,
+}
+
+#[contractevent]
+#[derive(Clone, Debug)]
+pub struct TermLoanOpenedEvent {
+    pub user: Address,
+    pub asset: Option<Address>,
+    pub amount: i128,
+    pub term_secs: u64,
+    /// Locked annual rate in basis points
+    pub rate_bps: i128,
+    pub maturity: u64,
+    pub timestamp: u64,
+}
+
+#[contractevent]
+#[derive(Clone, Debug)]
+pub struct EarlyRepaymentFeeEvent {
+    pub user: Address,
+    pub asset: Option<Address>,
+    /// Principal repaid before maturity
+    pub principal: i128,
+    /// Fee credited to reserves
+    pub fee: i128,
+    pub timestamp: u64,
+}
+
+#[contractevent]
+#[derive(Clone, Debug)]
+pub struct WithdrawIntentRegisteredEvent {
+    pub user: Address,
+    pub asset: Address,
+    pub amount: i128,
+    /// Earliest time the withdrawal can execute
+    pub unlock_at: u64,
+    /// Time after which the intent lapses (0 = never)
+    pub expires_at: u64,
+}
+
+#[contractevent]
+#[derive(Clone, Debug)]
+pub struct WithdrawIntentCancelledEvent {
+    pub user: Address,
+    pub asset: Address,
+    pub amount: i128,
+    pub timestamp: u64,
+}
+
+#[contractevent]
+#[derive(Clone, Debug)]
+pub struct EmergencyWithdrawalEvent {
+    pub user: Address,
+    pub asset: Option<Address>,
+    /// Amount debited from the position
+    pub amount: i128,
+    /// Withdrawal fee kept as reserves
+    pub fee: i128,
+    pub timestamp: u64,
+}
+
 #[contractevent]
 #[derive(Clone, Debug)]
 pub struct PositionUpdatedEvent {
@@ -391,14 +805,42 @@ pub fn emit_borrow(e: &Env, event: BorrowEvent) {
     event.publish(e);
 }
 
+pub fn emit_credit_delegation_approved(e: &Env, event: CreditDelegationApprovedEvent) {
+    event.publish(e);
+}
+
+pub fn emit_delegated_borrow(e: &Env, event: DelegatedBorrowEvent) {
+    event.publish(e);
+}
+
+pub fn emit_borrow_rate_mode_updated(e: &Env, event: BorrowRateModeUpdatedEvent) {
+    event.publish(e);
+}
+
 pub fn emit_repay(e: &Env, event: RepayEvent) {
     event.publish(e);
 }
 
+pub fn emit_debt_written_off(e: &Env, event: DebtWrittenOffEvent) {
+    event.publish(e);
+}
+
 pub fn emit_liquidation(e: &Env, event: LiquidationEvent) {
     event.publish(e);
 }
 
+pub fn emit_liquidation_bad_debt(e: &Env, event: LiquidationBadDebtEvent) {
+    event.publish(e);
+}
+
+pub fn emit_liquidation_reward_top_up(e: &Env, event: LiquidationRewardTopUpEvent) {
+    event.publish(e);
+}
+
+pub fn emit_forced_liquidation(e: &Env, event: ForcedLiquidationEvent) {
+    event.publish(e);
+}
+
 pub fn emit_flash_loan_initiated(e: &Env, event: FlashLoanInitiatedEvent) {
     event.publish(e);
 }
@@ -407,10 +849,30 @@ pub fn emit_flash_loan_repaid(e: &Env, event: FlashLoanRepaidEvent) {
     event.publish(e);
 }
 
+pub fn emit_rate_config_forced(e: &Env, event: RateConfigForcedEvent) {
+    event.publish(e);
+}
+
+pub fn emit_circuit_breaker_tripped(e: &Env, event: CircuitBreakerTrippedEvent) {
+    event.publish(e);
+}
+
+pub fn emit_circuit_breaker_released(e: &Env, event: CircuitBreakerReleasedEvent) {
+    event.publish(e);
+}
+
+pub fn emit_accrue(e: &Env, event: AccrueEvent) {
+    event.publish(e);
+}
+
 pub fn emit_admin_action(e: &Env, event: AdminActionEvent) {
     event.publish(e);
 }
 
+pub fn emit_fallback_price_used(e: &Env, event: FallbackPriceUsedEvent) {
+    event.publish(e);
+}
+
 pub fn emit_price_updated(e: &Env, event: PriceUpdatedEvent) {
     event.publish(e);
 }
@@ -423,6 +885,74 @@ pub fn emit_pause_state_changed(e: &Env, event: PauseStateChangedEvent) {
     event.publish(e);
 }
 
+pub fn emit_asset_pause_changed(e: &Env, event: AssetPauseChangedEvent) {
+    event.publish(e);
+}
+
+pub fn emit_emode_category_updated(e: &Env, event: EModeCategoryUpdatedEvent) {
+    event.publish(e);
+}
+
+pub fn emit_user_emode_set(e: &Env, event: UserEModeSetEvent) {
+    event.publish(e);
+}
+
+pub fn emit_collateral_toggled(e: &Env, event: CollateralToggledEvent) {
+    event.publish(e);
+}
+
+pub fn emit_referral(e: &Env, event: ReferralEvent) {
+    event.publish(e);
+}
+
+pub fn emit_reserve_donation(e: &Env, event: ReserveDonationEvent) {
+    event.publish(e);
+}
+
+pub fn emit_withdrawal_queued(e: &Env, event: WithdrawalQueuedEvent) {
+    event.publish(e);
+}
+
+pub fn emit_withdrawal_cancelled(e: &Env, event: WithdrawalCancelledEvent) {
+    event.publish(e);
+}
+
+pub fn emit_withdrawal_scheduled(e: &Env, event: WithdrawalScheduledEvent) {
+    event.publish(e);
+}
+
+pub fn emit_withdrawal_schedule_cancelled(e: &Env, event: WithdrawalScheduleCancelledEvent) {
+    event.publish(e);
+}
+
+pub fn emit_repayment_scheduled(e: &Env, event: RepaymentScheduledEvent) {
+    event.publish(e);
+}
+
+pub fn emit_installment_repaid(e: &Env, event: InstallmentRepaidEvent) {
+    event.publish(e);
+}
+
+pub fn emit_term_loan_opened(e: &Env, event: TermLoanOpenedEvent) {
+    event.publish(e);
+}
+
+pub fn emit_early_repayment_fee(e: &Env, event: EarlyRepaymentFeeEvent) {
+    event.publish(e);
+}
+
+pub fn emit_withdraw_intent_registered(e: &Env, event: WithdrawIntentRegisteredEvent) {
+    event.publish(e);
+}
+
+pub fn emit_withdraw_intent_cancelled(e: &Env, event: WithdrawIntentCancelledEvent) {
+    event.publish(e);
+}
+
+pub fn emit_emergency_withdrawal(e: &Env, event: EmergencyWithdrawalEvent) {
+    event.publish(e);
+}
+
 pub fn emit_position_updated(e: &Env, event: PositionUpdatedEvent) {
     event.publish(e);
 }
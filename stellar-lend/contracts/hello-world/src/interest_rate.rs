@@ -7,7 +7,25 @@
 //! - **Below kink** (default 80%): `rate = base_rate + (utilization / kink) * multiplier`
 //! - **Above kink**: `rate = base_rate + multiplier + ((util - kink) / (1 - kink)) * jump_multiplier`
 //!
-//! The supply rate is derived as: `supply_rate = borrow_rate - spread`
+//! The supply rate passes borrow interest through to suppliers net of reserves:
+//! `supply_rate = borrow_rate * utilization * (1 - reserve_factor)`
+//!
+//! [`calculate_utilization`] reports the utilization of a single asset from its
+//! own supplied and borrowed totals; the rates themselves are still driven by
+//! protocol-wide utilization.
+//!
+//! ## Rate Strategies
+//! Each asset prices its borrows with a [`RateStrategy`] set by the admin, all
+//! sharing the parameters of the one [`InterestRateConfig`]:
+//! - **Kinked** (default): the curve above
+//! - **Linear**: `rate = base_rate + utilization * multiplier`, a single flatter
+//!   slope with no jump
+//! - **Fixed**: a constant rate whatever the utilization
+//!
+//! Every strategy gets the emergency adjustment, floor and ceiling, and the
+//! supply rate follows from its borrow rate, all in [`compute_rates`]. Changing
+//! an asset's strategy accrues the asset first, so interest up to the switch is
+//! priced under the old one.
 //!
 //! ## Configuration (defaults)
 //! - Base rate: 1% APY
@@ -15,16 +33,88 @@
 //! - Multiplier: 20% (slope below kink)
 //! - Jump multiplier: 100% (slope above kink)
 //! - Rate floor: 0.5%, Rate ceiling: 100%
-//! - Spread: 2%
+//! - Reserve factor: 10% of borrow interest
+//!
+//! Updates are checked as a whole by [`validate_interest_rate_config`], which
+//! returns a distinct error for each kind of violation.
+//!
+//! ## Rate Transitions
+//! When the admin has set a maximum rate delta per day, a config update does not
+//! take effect at once: at every utilization the borrow rate moves from the
+//! previous model's rate toward the new one by at most that many basis points
+//! per day. Further updates are rejected until the transition completes, unless
+//! the admin forces them, which applies the new model immediately and emits an
+//! `rate_config_forced_event`.
 //!
 //! ## Emergency Adjustment
-//! Admin can apply a positive or negative emergency adjustment to the calculated rate,
-//! bounded to ±100%.
+//! Admin can apply a positive or negative emergency adjustment to the calculated rate
+//! for a limited time. Both the adjustment and its duration are capped by
+//! admin-configurable limits (defaults ±100% and 7 days). Once the duration has
+//! passed the adjustment is ignored; the admin can also clear it early.
+//!
+//! ## Circuit Breaker
+//! When an accrual observes protocol utilization at or above the admin's
+//! trigger, the circuit breaker trips and its bonus is added to every borrow
+//! rate, after the floor and ceiling, so a market suppliers cannot exit clears
+//! faster than the jump multiplier alone would make it. It is released only once
+//! accruals have observed utilization below the lower release threshold for
+//! the whole cool-off period; rising back to the release threshold restarts
+//! the wait. Tripping and releasing emit `circuit_breaker_tripped_event` and
+//! `circuit_breaker_released_event`.
+//!
+//! ## Stable Rate
+//! Stable borrows lock `borrow_rate + premium` at origination (default premium 1%).
+//! The admin may re-lock a stable borrow once its rate has fallen more than the
+//! rebalance threshold (default 5%) below the variable rate.
+//!
+//! ## Reserve Factor
+//! `reserve_factor_bps` of all borrow interest is credited to the asset's
+//! protocol reserve as it accrues, and the rest to the asset's supply pool, so
+//! depositors' shares redeem for more. Both are measured by the growth of the
+//! borrow index on the asset's outstanding borrows and split in [`WAD`] fixed
+//! point, so over any accrual period
+//! `supply_interest + reserve_growth == borrow_interest` up to the fractions of
+//! a unit each side carries over. The supply rate quotes the same income as an
+//! annual rate on the pool.
+//!
+//! ## Accrual
+//! [`accrue_interest`] compounds an asset's borrow index over the time elapsed
+//! since its last accrual and distributes the new borrow interest between the
+//! supply pool and the protocol reserve. Every deposit, withdrawal,
+//! borrow, repayment and liquidation accrues its assets first, and every change
+//! to the rate model, emergency adjustment, reserve factor, circuit breaker or an
+//! asset's strategy accrues the assets it reprices before it is stored, so
+//! interest already elapsed is never repriced retroactively.
+//! [`get_pending_reserves`] previews the reserve share the next accrual would
+//! credit without writing anything. An accrual over elapsed time publishes an
+//! `accrue` event with the period's index deltas, interest and reserve portion,
+//! so indexers need not reconstruct them from balance changes.
+//!
+//! ## Precision
+//! Rates are quoted in annual basis points, but accrual works in [`WAD`] (1e18)
+//! fixed point: [`interest_factor`] is the per-second rate times the elapsed
+//! seconds at that scale, computed in a single division so short intervals do
+//! not truncate to zero. Every conversion back to whole units names its
+//! [`Rounding`]: debt owed rounds up, while supplier and reserve credit round
+//! down and carry the fraction of a unit left over into the next accrual.
+//!
+//! ## APY
+//! [`get_borrow_apy`] and [`get_supply_apy`] quote the current rates as the
+//! yield they would compound to over a year if applied every second, for
+//! comparison with protocols that quote APY; see [`annual_percentage_yield`].
+//!
+//! ## Rate History
+//! Each accrual that compounds an asset also appends the utilization, borrow rate
+//! and supply rate in effect to the asset's rate history, read back newest first
+//! with [`get_rate_history`]. Only the latest `RateHistoryCapacity` entries are
+//! kept (default [`DEFAULT_RATE_HISTORY_CAPACITY`], at most
+//! [`MAX_RATE_HISTORY_CAPACITY`]); appending beyond it evicts the oldest.
 
 #![allow(unused)]
-use soroban_sdk::{contracterror, contracttype, Address, Env, IntoVal};
+use soroban_sdk::{contracterror, contracttype, Address, Env, IntoVal, Vec};
 
 use crate::deposit::{DepositDataKey, ProtocolAnalytics};
+use crate::events::{emit_accrue, AccrueEvent};
 
 /// Errors that can occur during interest rate operations
 #[contracterror]
@@ -43,6 +133,20 @@ pub enum InterestRateError {
     DivisionByZero = 5,
     /// Contract has already been initialized
     AlreadyInitialized = 6,
+    /// Kink utilization outside (0, 100%]
+    InvalidKink = 7,
+    /// Rate floor above the rate ceiling
+    FloorAboveCeiling = 8,
+    /// Base rate, multiplier or jump multiplier is negative
+    NegativeRateParameter = 9,
+    /// Spread above [`MAX_SPREAD_BPS`]
+    SpreadTooLarge = 10,
+    /// The rate at 100% utilization would overflow
+    RateOverflow = 11,
+    /// The borrow rate is still moving toward the last config update
+    RateTransitionInProgress = 12,
+    /// Reentrancy detected
+    Reentrancy = 13,
 }
 
 /// Storage keys for interest rate data
@@ -58,6 +162,47 @@ pub enum InterestRateDataKey {
     Admin,
     /// Placeholder for emergency rate adjustment status
     EmergencyRateAdjustment,
+    /// Bounds on emergency rate adjustments
+    /// Value type: EmergencyAdjustmentLimits
+    EmergencyAdjustmentLimits,
+    /// Stable borrow rate parameters
+    /// Value type: StableRateConfig
+    StableRateConfig,
+    /// Borrow index up to which an asset's reserve share has been credited
+    /// Value type: i128
+    ReserveAccrualIndex(Option<Address>),
+    /// Reserve share of an asset's borrow interest accrued below one whole
+    /// unit, scaled by [`WAD`]
+    /// Value type: i128
+    ReserveRemainder(Option<Address>),
+    /// Entry `id` of an asset's rate history
+    /// Value type: RateHistoryEntry
+    RateHistory(Option<Address>, u64),
+    /// Number of rate history entries recorded for an asset, which is also the
+    /// id of the latest
+    /// Value type: u64
+    RateHistoryCount(Option<Address>),
+    /// How many rate history entries are kept per asset
+    /// Value type: u32
+    RateHistoryCapacity,
+    /// Largest borrow rate move per day a config update may cause (0 = unlimited)
+    /// Value type: i128
+    MaxRateDeltaPerDay,
+    /// Move from the previous rate model to the current one
+    /// Value type: RateTransition
+    RateTransition,
+    /// Rate formula an asset follows (None for native XLM)
+    /// Value type: RateStrategy
+    RateStrategy(Option<Address>),
+    /// Utilization-spike circuit breaker parameters (unset = disabled)
+    /// Value type: CircuitBreakerConfig
+    CircuitBreakerConfig,
+    /// Whether the circuit breaker is tripped
+    /// Value type: CircuitBreakerState
+    CircuitBreakerState,
+    /// Assets accrued at least once, checkpointed before every rate change
+    /// Value type: Vec<Option<Address>>
+    AccruedAssets,
 }
 
 /// Interest rate configuration parameters
@@ -80,19 +225,161 @@ pub struct InterestRateConfig {
     pub rate_floor_bps: i128,
     /// Maximum interest rate ceiling (in basis points)
     pub rate_ceiling_bps: i128,
-    /// Spread between borrow and supply rates (in basis points, e.g., 200 = 2%)
-    /// Supply rate = borrow rate - spread
+    /// Spread between borrow and supply rates (in basis points), at most [`MAX_SPREAD_BPS`]
+    /// The supply rate itself follows from utilization and `reserve_factor_bps`
     pub spread_bps: i128,
     /// Emergency rate adjustment (in basis points, added/subtracted from calculated rate)
     /// Can be positive or negative
     pub emergency_adjustment_bps: i128,
-    /// Last update timestamp
+    /// Time the emergency adjustment stops applying (0 when none was set)
+    pub emergency_adjustment_expires_at: u64,
+    /// Share of borrow interest credited to the protocol reserve (in basis points,
+    /// e.g., 1000 = 10%)
+    pub reserve_factor_bps: i128,
+    /// Ledger timestamp of the last change to the model, the emergency
+    /// adjustment, the reserve factor or an asset's strategy
     pub last_update: u64,
 }
 
+/// Formula an asset's borrow rate follows, see [`compute_rates`]
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub enum RateStrategy {
+    /// `base_rate + utilization * multiplier`, with no kink
+    Linear,
+    /// The kinked curve of the [`InterestRateConfig`]
+    Kinked,
+    /// A constant annual rate in basis points
+    Fixed(i128),
+}
+
+/// Stable borrow rate parameters
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct StableRateConfig {
+    /// Added to the variable borrow rate when a stable rate is locked (basis points)
+    pub premium_bps: i128,
+    /// How far a stable rate may fall below the variable rate before the admin
+    /// can rebalance it (basis points)
+    pub rebalance_threshold_bps: i128,
+}
+
+/// Bounds on emergency rate adjustments
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct EmergencyAdjustmentLimits {
+    /// Largest absolute adjustment that may be set (basis points)
+    pub max_adjustment_bps: i128,
+    /// Longest an adjustment may stay in effect (seconds)
+    pub max_duration_secs: u64,
+}
+
+/// An emergency rate adjustment currently in effect
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct EmergencyRateAdjustment {
+    /// Added to the calculated borrow rate (basis points, can be negative)
+    pub adjustment_bps: i128,
+    /// Time the adjustment stops applying
+    pub expires_at: u64,
+}
+
+/// Gradual move from the previous rate model to the current one after
+/// [`update_interest_rate_config`]
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct RateTransition {
+    /// Rate model in effect before the update
+    pub previous: InterestRateConfig,
+    /// Time of the update
+    pub started_at: u64,
+    /// Time the borrow rate reaches the current model at every utilization
+    pub ends_at: u64,
+    /// Largest borrow rate move per day during the transition (basis points)
+    pub max_rate_delta_bps_per_day: i128,
+}
+
+/// Utilization-spike circuit breaker parameters
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct CircuitBreakerConfig {
+    /// Utilization at or above which an accrual trips the breaker (basis points)
+    pub trigger_utilization_bps: i128,
+    /// Utilization the breaker must stay below to be released (basis points)
+    pub release_utilization_bps: i128,
+    /// Added to the borrow rate while the breaker is tripped (basis points)
+    pub bonus_bps: i128,
+    /// How long utilization must stay below the release threshold (seconds)
+    pub cooloff_secs: u64,
+}
+
+/// Circuit breaker state as of the last accrual
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct CircuitBreakerState {
+    /// Whether the bonus is being applied
+    pub tripped: bool,
+    /// Time the breaker last tripped (0 if never)
+    pub tripped_at: u64,
+    /// Time since which accruals have observed utilization below the release
+    /// threshold while tripped
+    pub release_pending_since: Option<u64>,
+}
+
+/// Rates of an asset as recorded by [`accrue_interest`]
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct RateHistoryEntry {
+    /// Time of the accrual
+    pub timestamp: u64,
+    /// Protocol utilization (basis points)
+    pub utilization_bps: i128,
+    /// Borrow rate in effect (basis points)
+    pub borrow_rate_bps: i128,
+    /// Supply rate in effect (basis points)
+    pub supply_rate_bps: i128,
+}
+
+/// Interest indexes of an asset after [`accrue_interest`]
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct InterestAccrual {
+    /// Growth of one unit of debt, scaled by [`crate::borrow::BORROW_INDEX_SCALE`]
+    pub borrow_index: i128,
+    /// Underlying one deposit share redeems for, scaled by
+    /// [`crate::deposit::EXCHANGE_RATE_SCALE`]
+    pub supply_index: i128,
+    /// Reserve factor share of borrow interest credited to the protocol reserve
+    /// by this accrual
+    pub reserves_accrued: i128,
+    /// Time the indexes were last compounded
+    pub last_accrual: u64,
+}
+
 /// Constants for validation
 const BASIS_POINTS_SCALE: i128 = 10_000; // 100% = 10,000 basis points
 const SECONDS_PER_YEAR: u64 = 365 * 86400; // 31,536,000 seconds
+const SECONDS_PER_DAY: u64 = 86400;
+
+/// Fixed-point scale for interest factors and per-second rates (1e18 = 100%)
+pub const WAD: i128 = 1_000_000_000_000_000_000;
+
+/// Direction to round a fixed-point result to whole units
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Rounding {
+    /// Toward zero, for interest credited to suppliers and reserves
+    Down,
+    /// Away from zero, for interest owed by borrowers
+    Up,
+}
+
+/// Largest spread `update_interest_rate_config` accepts (50%)
+pub const MAX_SPREAD_BPS: i128 = 5_000;
+
+/// Rate history entries kept per asset unless the admin changes it
+pub const DEFAULT_RATE_HISTORY_CAPACITY: u32 = 100;
+/// Upper bound on the rate history capacity
+pub const MAX_RATE_HISTORY_CAPACITY: u32 = 1_000;
 
 /// Default interest rate configuration
 fn get_default_config() -> InterestRateConfig {
@@ -105,6 +392,8 @@ fn get_default_config() -> InterestRateConfig {
         rate_ceiling_bps: 10000,     // 100% maximum rate
         spread_bps: 200,             // 2% spread
         emergency_adjustment_bps: 0, // No emergency adjustment
+        reserve_factor_bps: 1000,    // 10% of interest to reserves
+        emergency_adjustment_expires_at: 0,
         last_update: 0,
     }
 }
@@ -117,6 +406,18 @@ pub fn get_interest_rate_config(env: &Env) -> Option<InterestRateConfig> {
         .get::<InterestRateDataKey, InterestRateConfig>(&config_key)
 }
 
+/// Interest rate model in force for `asset`
+///
+/// All assets share one set of parameters, so `asset` does not change the result;
+/// how they are applied depends on the asset's [`RateStrategy`]. The config
+/// carries the last `emergency_adjustment_bps` set, which only applies until
+/// `emergency_adjustment_expires_at` (see [`get_emergency_rate_adjustment`]),
+/// and `last_update`, the time of the last config change. Before initialization
+/// the defaults are returned.
+pub fn get_asset_interest_rate_config(env: &Env, asset: &Option<Address>) -> InterestRateConfig {
+    get_interest_rate_config(env).unwrap_or_else(get_default_config)
+}
+
 /// Initialize interest rate configuration
 pub fn initialize_interest_rate_config(env: &Env, admin: Address) -> Result<(), InterestRateError> {
     let config_key = InterestRateDataKey::InterestRateConfig;
@@ -136,10 +437,11 @@ pub fn initialize_interest_rate_config(env: &Env, admin: Address) -> Result<(),
     Ok(())
 }
 
-/// Calculate protocol utilization
-/// Utilization = total_borrows / total_deposits (in basis points)
-/// Returns utilization in basis points (0-10000)
-pub fn calculate_utilization(env: &Env) -> Result<i128, InterestRateError> {
+/// Calculate protocol utilization once `additional_borrows` more is borrowed
+pub fn calculate_projected_utilization(
+    env: &Env,
+    additional_borrows: i128,
+) -> Result<i128, InterestRateError> {
     let analytics_key = DepositDataKey::ProtocolAnalytics;
     let analytics = env
         .storage()
@@ -158,6 +460,8 @@ pub fn calculate_utilization(env: &Env) -> Result<i128, InterestRateError> {
     // Calculate utilization: (borrows * 10000) / deposits
     let utilization = analytics
         .total_borrows
+        .checked_add(additional_borrows)
+        .ok_or(InterestRateError::Overflow)?
         .checked_mul(BASIS_POINTS_SCALE)
         .ok_or(InterestRateError::Overflow)?
         .checked_div(analytics.total_deposits)
@@ -167,15 +471,130 @@ pub fn calculate_utilization(env: &Env) -> Result<i128, InterestRateError> {
     Ok(utilization.min(BASIS_POINTS_SCALE))
 }
 
-/// Calculate borrow interest rate based on utilization
-/// Uses a piecewise linear model with a kink
+/// Calculate utilization of a single asset from its `AssetTotals`
+/// Utilization = total_borrowed / total_supplied (in basis points)
+/// Returns utilization in basis points (0-10000); `None` is the native asset
+pub fn calculate_utilization(
+    env: &Env,
+    asset: &Option<Address>,
+) -> Result<i128, InterestRateError> {
+    let totals = crate::deposit::get_asset_totals(env, asset);
+    if totals.total_supplied == 0 {
+        return Ok(0); // Nothing supplied means 0% utilization
+    }
+
+    let utilization = totals
+        .total_borrowed
+        .checked_mul(BASIS_POINTS_SCALE)
+        .ok_or(InterestRateError::Overflow)?
+        .checked_div(totals.total_supplied)
+        .ok_or(InterestRateError::DivisionByZero)?;
+
+    Ok(utilization.min(BASIS_POINTS_SCALE))
+}
+
+/// Calculate the borrow interest rate of `asset` based on utilization
+/// Uses the asset's [`RateStrategy`], the kinked model by default:
 ///
 /// Below kink: rate = base_rate + (utilization / kink_utilization) * multiplier
 /// Above kink: rate = base_rate + multiplier + ((utilization - kink) / (10000 - kink)) * jump_multiplier
-pub fn calculate_borrow_rate(env: &Env) -> Result<i128, InterestRateError> {
+pub fn calculate_borrow_rate(
+    env: &Env,
+    asset: &Option<Address>,
+) -> Result<i128, InterestRateError> {
+    calculate_projected_borrow_rate(env, asset, 0)
+}
+
+/// Calculate the borrow rate of `asset` once `additional_borrows` more is borrowed
+pub fn calculate_projected_borrow_rate(
+    env: &Env,
+    asset: &Option<Address>,
+    additional_borrows: i128,
+) -> Result<i128, InterestRateError> {
     let config = get_interest_rate_config(env).ok_or(InterestRateError::InvalidParameter)?;
-    let utilization = calculate_utilization(env)?;
+    let utilization = calculate_projected_utilization(env, additional_borrows)?;
+    let strategy = get_rate_strategy(env, asset);
+    let now = env.ledger().timestamp();
+    let (borrow_rate, _) = effective_rates(env, &strategy, &config, utilization, now)?;
+    Ok(borrow_rate)
+}
+
+/// Borrow and supply rates the model would charge at `utilization_bps`
+///
+/// Applies the asset's strategy, emergency adjustment, floor, ceiling and reserve
+/// factor exactly as [`calculate_borrow_rate`] and [`calculate_supply_rate`] do,
+/// for charting the curve. Utilization is clamped to 0-100%. Uses the rate model
+/// returned by [`get_asset_interest_rate_config`].
+///
+/// # Returns
+/// (borrow_rate_bps, supply_rate_bps)
+pub fn get_rate_at_utilization(
+    env: &Env,
+    asset: &Option<Address>,
+    utilization_bps: i128,
+) -> Result<(i128, i128), InterestRateError> {
+    let config = get_asset_interest_rate_config(env, asset);
+    let utilization = utilization_bps.clamp(0, BASIS_POINTS_SCALE);
+    let strategy = get_rate_strategy(env, asset);
+    let now = env.ledger().timestamp();
+    effective_rates(env, &strategy, &config, utilization, now)
+}
+
+/// Borrow and supply rates of `strategy` under `config` at `utilization` (basis points)
+///
+/// The strategy's curve plus `config.emergency_adjustment_bps`, clamped to the
+/// floor and ceiling; the supply rate follows from it and the reserve factor.
+/// The adjustment's expiry is not checked here: callers pass the config as it
+/// stands at the time they price, see [`config_at`].
+///
+/// # Returns
+/// (borrow_rate_bps, supply_rate_bps)
+pub fn compute_rates(
+    strategy: &RateStrategy,
+    config: &InterestRateConfig,
+    utilization: i128,
+) -> Result<(i128, i128), InterestRateError> {
+    let rate = match strategy {
+        RateStrategy::Linear => linear_rate(config, utilization)?,
+        RateStrategy::Kinked => kinked_rate(config, utilization)?,
+        RateStrategy::Fixed(rate_bps) => *rate_bps,
+    };
+    let borrow_rate = rate
+        .checked_add(config.emergency_adjustment_bps)
+        .ok_or(InterestRateError::Overflow)?
+        .max(config.rate_floor_bps)
+        .min(config.rate_ceiling_bps);
+    Ok((
+        borrow_rate,
+        supply_rate_at(config, borrow_rate, utilization)?,
+    ))
+}
+
+/// `config` at time `now`, without its emergency adjustment once that has expired
+fn config_at(config: &InterestRateConfig, now: u64) -> InterestRateConfig {
+    let mut config = config.clone();
+    if now >= config.emergency_adjustment_expires_at {
+        config.emergency_adjustment_bps = 0;
+    }
+    config
+}
 
+/// The linear curve at `utilization`: the base rate plus the multiplier spread
+/// over the whole utilization range
+fn linear_rate(config: &InterestRateConfig, utilization: i128) -> Result<i128, InterestRateError> {
+    let rate_increase = utilization
+        .checked_mul(config.multiplier_bps)
+        .ok_or(InterestRateError::Overflow)?
+        / BASIS_POINTS_SCALE;
+    config
+        .base_rate_bps
+        .checked_add(rate_increase)
+        .ok_or(InterestRateError::Overflow)
+}
+
+/// The kinked curve at `utilization` before the emergency adjustment, floor and
+/// ceiling are applied
+fn kinked_rate(config: &InterestRateConfig, utilization: i128) -> Result<i128, InterestRateError> {
     let mut rate = config.base_rate_bps;
 
     if utilization <= config.kink_utilization_bps {
@@ -220,33 +639,423 @@ pub fn calculate_borrow_rate(env: &Env) -> Result<i128, InterestRateError> {
         }
     }
 
-    // Apply emergency adjustment
-    rate = rate
-        .checked_add(config.emergency_adjustment_bps)
-        .ok_or(InterestRateError::Overflow)?;
+    Ok(rate)
+}
 
-    // Apply rate limits
-    rate = rate.max(config.rate_floor_bps).min(config.rate_ceiling_bps);
+/// Borrow and supply rates at `utilization` under `strategy` and `config` at
+/// time `now`, with the borrow rate held back by any rate transition still in
+/// progress
+///
+/// During a transition the borrow rate moves from the previous model's rate at
+/// the same utilization toward `config`'s by at most the transition's daily
+/// delta, and the supply rate follows it. The emergency adjustment in `config`
+/// applies to both sides, so it takes effect immediately. A tripped circuit
+/// breaker's bonus is added last, past the ceiling and any transition.
+fn effective_rates(
+    env: &Env,
+    strategy: &RateStrategy,
+    config: &InterestRateConfig,
+    utilization: i128,
+    now: u64,
+) -> Result<(i128, i128), InterestRateError> {
+    let config = config_at(config, now);
+    let (mut borrow_rate, _) = compute_rates(strategy, &config, utilization)?;
+    if let Some(transition) = get_rate_transition(env).filter(|t| now < t.ends_at) {
+        let mut previous = transition.previous;
+        previous.emergency_adjustment_bps = config.emergency_adjustment_bps;
+        let (start, _) = compute_rates(strategy, &previous, utilization)?;
+        let allowed = transition
+            .max_rate_delta_bps_per_day
+            .checked_mul(now.saturating_sub(transition.started_at) as i128)
+            .ok_or(InterestRateError::Overflow)?
+            / SECONDS_PER_DAY as i128;
+        borrow_rate =
+            borrow_rate.clamp(start.saturating_sub(allowed), start.saturating_add(allowed));
+    }
+    let borrow_rate = borrow_rate
+        .checked_add(circuit_breaker_bonus(env))
+        .ok_or(InterestRateError::Overflow)?;
+    Ok((
+        borrow_rate,
+        supply_rate_at(&config, borrow_rate, utilization)?,
+    ))
+}
 
-    Ok(rate)
+/// Largest difference between the borrow rates of two models at any utilization
+///
+/// Both curves are linear between 0, either kink and 100%, so their difference
+/// peaks at one of those points. The linear strategy's difference at 0 and 100%
+/// is the kinked one at 0 and at a shared kink, so it is covered too. The floor
+/// and ceiling can move the clamped rates by no more than they moved themselves.
+fn max_rate_gap(
+    previous: &InterestRateConfig,
+    config: &InterestRateConfig,
+) -> Result<i128, InterestRateError> {
+    let mut gap = (config.rate_floor_bps - previous.rate_floor_bps)
+        .abs()
+        .max((config.rate_ceiling_bps - previous.rate_ceiling_bps).abs());
+    for utilization in [
+        0,
+        previous.kink_utilization_bps,
+        config.kink_utilization_bps,
+        BASIS_POINTS_SCALE,
+    ] {
+        let difference = kinked_rate(config, utilization)?
+            .checked_sub(kinked_rate(previous, utilization)?)
+            .ok_or(InterestRateError::Overflow)?;
+        gap = gap.max(difference.abs());
+    }
+    Ok(gap)
 }
 
-/// Calculate supply interest rate
-/// Supply rate = borrow rate - spread
-pub fn calculate_supply_rate(env: &Env) -> Result<i128, InterestRateError> {
+/// Calculate the supply interest rate of `asset`
+/// Supply rate = borrow rate * utilization * (1 - reserve factor)
+pub fn calculate_supply_rate(
+    env: &Env,
+    asset: &Option<Address>,
+) -> Result<i128, InterestRateError> {
     let config = get_interest_rate_config(env).ok_or(InterestRateError::InvalidParameter)?;
-    let borrow_rate = calculate_borrow_rate(env)?;
+    let utilization = calculate_projected_utilization(env, 0)?;
+    let strategy = get_rate_strategy(env, asset);
+    let now = env.ledger().timestamp();
+    let (_, supply_rate) = effective_rates(env, &strategy, &config, utilization, now)?;
+    Ok(supply_rate)
+}
 
-    // Supply rate = borrow rate - spread
+/// Supply rate paid when borrowers are charged `borrow_rate` at `utilization`
+///
+/// Only the borrowed share of deposits earns interest, and the reserve factor
+/// share of it goes to the protocol reserve. With no borrows suppliers earn
+/// nothing.
+fn supply_rate_at(
+    config: &InterestRateConfig,
+    borrow_rate: i128,
+    utilization: i128,
+) -> Result<i128, InterestRateError> {
+    let supplier_share = BASIS_POINTS_SCALE
+        .checked_sub(config.reserve_factor_bps)
+        .ok_or(InterestRateError::Overflow)?;
+
+    // One division by 10000^2 keeps the rounding to a single step
     let supply_rate = borrow_rate
-        .checked_sub(config.spread_bps)
+        .checked_mul(utilization)
+        .ok_or(InterestRateError::Overflow)?
+        .checked_mul(supplier_share)
+        .ok_or(InterestRateError::Overflow)?
+        .checked_div(BASIS_POINTS_SCALE * BASIS_POINTS_SCALE)
+        .ok_or(InterestRateError::DivisionByZero)?;
+
+    Ok(supply_rate.max(0))
+}
+
+/// Compound the interest indexes of `asset` up to the current ledger and persist them
+///
+/// The borrow index grows by the borrow rate over the time elapsed since it was
+/// last compounded. Of the interest it has added to the asset's outstanding
+/// borrows since the previous call, the reserve factor share is credited to
+/// `ProtocolReserve(asset)` and the rest to the supply pool, and the rates in
+/// effect are appended to the asset's rate history. An [`AccrueEvent`] reports
+/// the period. Nothing is written or published when no time has elapsed.
+///
+/// Deposits, withdrawals, borrows, repayments and liquidations call this first,
+/// so the elapsed period accrues at the rates that were in effect before they
+/// move utilization.
+pub fn accrue_interest(
+    env: &Env,
+    asset: Option<Address>,
+) -> Result<InterestAccrual, InterestRateError> {
+    let now = env.ledger().timestamp();
+    let previous = crate::borrow::get_stored_borrow_index(env, &asset);
+    let supply_index_before = crate::deposit::get_stored_exchange_rate(env, &asset);
+    let borrow_index = match previous.clone() {
+        Some(index) if index.last_update >= now => index.index,
+        _ => {
+            record_rate_history(env, &asset, now)?;
+            crate::borrow::accrue_borrow_index(env, &asset)
+        }
+    };
+    let supply_index = crate::deposit::accrue_supply_pool(env, &asset);
+    // Interest up to now was priced under the breaker state it accrued under
+    observe_circuit_breaker(env, now)?;
+
+    let key = InterestRateDataKey::ReserveAccrualIndex(asset.clone());
+    let mut reserves_accrued = 0;
+    if let Some((whole, remainder)) = reserve_accrual(env, &asset, borrow_index)? {
+        reserves_accrued = whole;
+        crate::deposit::add_protocol_reserve(env, &asset, reserves_accrued)
+            .map_err(|_| InterestRateError::Overflow)?;
+        env.storage().persistent().set(
+            &InterestRateDataKey::ReserveRemainder(asset.clone()),
+            &remainder,
+        );
+        env.storage().persistent().set(&key, &borrow_index);
+    } else if !env.storage().persistent().has(&key) {
+        env.storage().persistent().set(&key, &borrow_index);
+        // First accrual of the asset: rate changes checkpoint it from now on
+        let mut assets = get_accrued_assets(env);
+        assets.push_back(asset.clone());
+        env.storage()
+            .persistent()
+            .set(&InterestRateDataKey::AccruedAssets, &assets);
+    }
+
+    // A no-op accrual, in the same ledger as the last one, publishes nothing
+    if let Some(previous) = previous.filter(|index| now > index.last_update) {
+        let total_borrowed = crate::deposit::get_asset_totals(env, &asset).total_borrowed;
+        let interest_accrued = mul_div(
+            total_borrowed,
+            borrow_index - previous.index,
+            previous.index,
+            Rounding::Down,
+        )?;
+        emit_accrue(
+            env,
+            AccrueEvent {
+                asset: asset.clone(),
+                elapsed_secs: now - previous.last_update,
+                borrow_index_before: previous.index,
+                borrow_index_after: borrow_index,
+                supply_index_before,
+                supply_index_after: supply_index,
+                interest_accrued,
+                reserve_portion: reserves_accrued,
+                timestamp: now,
+            },
+        );
+    }
+
+    Ok(InterestAccrual {
+        borrow_index,
+        supply_index,
+        reserves_accrued,
+        last_accrual: now,
+    })
+}
+
+/// Assets [`accrue_interest`] has run for at least once
+pub fn get_accrued_assets(env: &Env) -> Vec<Option<Address>> {
+    env.storage()
+        .persistent()
+        .get::<InterestRateDataKey, Vec<Option<Address>>>(&InterestRateDataKey::AccruedAssets)
+        .unwrap_or(Vec::new(env))
+}
+
+/// Accrue every asset up to now at the rates in effect until now
+///
+/// Called before any change that reprices every asset is stored, so the change
+/// only applies from the current ledger on.
+pub fn checkpoint_accruals(env: &Env) -> Result<(), InterestRateError> {
+    for asset in get_accrued_assets(env).iter() {
+        accrue_interest(env, asset)?;
+    }
+    Ok(())
+}
+
+/// Reserve share of the borrow interest on `asset` between the last credited
+/// borrow index and `borrow_index`
+///
+/// Returns the whole units to credit and the fraction of a unit left over,
+/// scaled by [`WAD`], or `None` when the index has not moved past the last
+/// credit (or nothing has been credited yet).
+fn reserve_accrual(
+    env: &Env,
+    asset: &Option<Address>,
+    borrow_index: i128,
+) -> Result<Option<(i128, i128)>, InterestRateError> {
+    let storage = env.storage().persistent();
+    let credited = match storage
+        .get::<InterestRateDataKey, i128>(&InterestRateDataKey::ReserveAccrualIndex(asset.clone()))
+    {
+        Some(credited) if borrow_index > credited => credited,
+        _ => return Ok(None),
+    };
+
+    let (_, share) = interest_split(env, credited, borrow_index)?;
+    let carry = storage
+        .get::<InterestRateDataKey, i128>(&InterestRateDataKey::ReserveRemainder(asset.clone()))
+        .unwrap_or(0);
+    let total_borrowed = crate::deposit::get_asset_totals(env, asset).total_borrowed;
+    mul_wad_with_carry(total_borrowed, share, carry).map(Some)
+}
+
+/// Borrow interest per unit borrowed between two borrow index values, split
+/// into the suppliers' and the reserve's shares, scaled by [`WAD`]
+///
+/// The reserve share rounds down and the suppliers get the exact complement.
+fn interest_split(
+    env: &Env,
+    from_index: i128,
+    to_index: i128,
+) -> Result<(i128, i128), InterestRateError> {
+    let growth = mul_div(to_index - from_index, WAD, from_index, Rounding::Down)?;
+    let reserve_share = mul_div(
+        growth,
+        get_reserve_factor_bps(env),
+        BASIS_POINTS_SCALE,
+        Rounding::Down,
+    )?;
+    Ok((growth - reserve_share, reserve_share))
+}
+
+/// Suppliers' share of the borrow interest on `asset` between two borrow index
+/// values
+///
+/// The complement of the reserve share credited over the same indexes. Returns
+/// the whole units to credit to the supply pool and the fraction of a unit left
+/// over with `carry`, scaled by [`WAD`].
+pub(crate) fn supplier_interest(
+    env: &Env,
+    asset: &Option<Address>,
+    from_index: i128,
+    to_index: i128,
+    carry: i128,
+) -> Result<(i128, i128), InterestRateError> {
+    if to_index <= from_index || from_index <= 0 {
+        return Ok((0, carry));
+    }
+    let (share, _) = interest_split(env, from_index, to_index)?;
+    let total_borrowed = crate::deposit::get_asset_totals(env, asset).total_borrowed;
+    mul_wad_with_carry(total_borrowed, share, carry)
+}
+
+/// Reserve share of the borrow interest on `asset` that [`accrue_interest`]
+/// would credit if it ran now
+///
+/// Nothing is written; this is what `get_reserve_balance` will grow by on the
+/// next accrual.
+pub fn get_pending_reserves(env: &Env, asset: &Option<Address>) -> i128 {
+    let borrow_index = crate::borrow::get_borrow_index(env, asset);
+    match reserve_accrual(env, asset, borrow_index) {
+        Ok(Some((whole, _))) => whole,
+        _ => 0,
+    }
+}
+
+/// Protocol reserve of `asset` including the share not yet credited by
+/// [`accrue_interest`]
+pub fn get_total_reserves(env: &Env, asset: &Option<Address>) -> i128 {
+    let stored = env
+        .storage()
+        .persistent()
+        .get::<DepositDataKey, i128>(&DepositDataKey::ProtocolReserve(asset.clone()))
+        .unwrap_or(0);
+    stored.saturating_add(get_pending_reserves(env, asset))
+}
+
+/// Append the rates in effect to the rate history of `asset`, evicting the
+/// oldest entry beyond the configured capacity
+fn record_rate_history(
+    env: &Env,
+    asset: &Option<Address>,
+    now: u64,
+) -> Result<(), InterestRateError> {
+    // Without a configured rate model there are no rates to record
+    let Some(config) = get_interest_rate_config(env) else {
+        return Ok(());
+    };
+    let utilization = calculate_projected_utilization(env, 0)?;
+    let strategy = get_rate_strategy(env, asset);
+    let (borrow_rate, supply_rate) = effective_rates(env, &strategy, &config, utilization, now)?;
+
+    let id = get_rate_history_count(env, asset)
+        .checked_add(1)
         .ok_or(InterestRateError::Overflow)?;
+    let storage = env.storage().persistent();
+    storage.set(
+        &InterestRateDataKey::RateHistory(asset.clone(), id),
+        &RateHistoryEntry {
+            timestamp: now,
+            utilization_bps: utilization,
+            borrow_rate_bps: borrow_rate,
+            supply_rate_bps: supply_rate,
+        },
+    );
+    storage.set(&InterestRateDataKey::RateHistoryCount(asset.clone()), &id);
+    let capacity = u64::from(get_rate_history_capacity(env));
+    if id > capacity {
+        storage.remove(&InterestRateDataKey::RateHistory(
+            asset.clone(),
+            id - capacity,
+        ));
+    }
+    Ok(())
+}
+
+/// Number of rate history entries recorded for `asset`, including evicted ones
+pub fn get_rate_history_count(env: &Env, asset: &Option<Address>) -> u64 {
+    env.storage()
+        .persistent()
+        .get::<InterestRateDataKey, u64>(&InterestRateDataKey::RateHistoryCount(asset.clone()))
+        .unwrap_or(0)
+}
+
+/// Recorded rates of `asset`, newest first
+///
+/// Skips the `offset` newest entries and returns up to `limit` of the rest. Only
+/// the latest `RateHistoryCapacity` entries are kept.
+pub fn get_rate_history(
+    env: &Env,
+    asset: &Option<Address>,
+    limit: u32,
+    offset: u32,
+) -> Vec<RateHistoryEntry> {
+    let count = get_rate_history_count(env, asset);
+    let oldest_kept = count.saturating_sub(u64::from(get_rate_history_capacity(env)));
+    let mut history = Vec::new(env);
+    let mut id = count.saturating_sub(u64::from(offset));
+    while id > oldest_kept && history.len() < limit {
+        if let Some(entry) = env
+            .storage()
+            .persistent()
+            .get::<InterestRateDataKey, RateHistoryEntry>(&InterestRateDataKey::RateHistory(
+                asset.clone(),
+                id,
+            ))
+        {
+            history.push_back(entry);
+        }
+        id -= 1;
+    }
+    history
+}
+
+/// Get how many rate history entries are kept per asset
+pub fn get_rate_history_capacity(env: &Env) -> u32 {
+    env.storage()
+        .persistent()
+        .get::<InterestRateDataKey, u32>(&InterestRateDataKey::RateHistoryCapacity)
+        .unwrap_or(DEFAULT_RATE_HISTORY_CAPACITY)
+}
+
+/// Set how many rate history entries are kept per asset
+///
+/// Lowering the capacity hides the entries that fall outside the new window.
+///
+/// # Arguments
+/// * `env` - The Soroban environment
+/// * `caller` - The caller address (must be admin)
+/// * `capacity` - Entries kept per asset (1-[`MAX_RATE_HISTORY_CAPACITY`])
+pub fn set_rate_history_capacity(
+    env: &Env,
+    caller: Address,
+    capacity: u32,
+) -> Result<(), InterestRateError> {
+    crate::admin::require_admin(env, &caller).map_err(|_| InterestRateError::Unauthorized)?;
 
-    // Ensure supply rate doesn't go below floor
-    Ok(supply_rate.max(config.rate_floor_bps))
+    if !(1..=MAX_RATE_HISTORY_CAPACITY).contains(&capacity) {
+        return Err(InterestRateError::InvalidParameter);
+    }
+
+    env.storage()
+        .persistent()
+        .set(&InterestRateDataKey::RateHistoryCapacity, &capacity);
+
+    Ok(())
 }
 
-/// Calculate accrued interest using dynamic rate
+/// Calculate accrued interest using dynamic rate, rounded down
+///
+/// Interest a borrower owes goes through [`calculate_accrued_debt`] instead.
 ///
 /// # Arguments
 /// * `principal` - The principal amount
@@ -261,6 +1070,40 @@ pub fn calculate_accrued_interest(
     last_accrual_time: u64,
     current_time: u64,
     rate_bps: i128,
+) -> Result<i128, InterestRateError> {
+    accrued_interest(
+        principal,
+        last_accrual_time,
+        current_time,
+        rate_bps,
+        Rounding::Down,
+    )
+}
+
+/// [`calculate_accrued_interest`] rounded up, for interest a borrower owes
+pub fn calculate_accrued_debt(
+    principal: i128,
+    last_accrual_time: u64,
+    current_time: u64,
+    rate_bps: i128,
+) -> Result<i128, InterestRateError> {
+    accrued_interest(
+        principal,
+        last_accrual_time,
+        current_time,
+        rate_bps,
+        Rounding::Up,
+    )
+}
+
+/// Simple interest on `principal`, rounded to whole units in the direction of
+/// `rounding`
+fn accrued_interest(
+    principal: i128,
+    last_accrual_time: u64,
+    current_time: u64,
+    rate_bps: i128,
+    rounding: Rounding,
 ) -> Result<i128, InterestRateError> {
     if principal == 0 {
         return Ok(0);
@@ -281,21 +1124,141 @@ pub fn calculate_accrued_interest(
         .checked_mul(SECONDS_PER_YEAR as i128)
         .ok_or(InterestRateError::Overflow)?;
 
-    let numerator = principal
-        .checked_mul(rate_bps)
-        .ok_or(InterestRateError::Overflow)?
+    let rate_time = rate_bps
         .checked_mul(time_elapsed as i128)
         .ok_or(InterestRateError::Overflow)?;
 
-    let interest = numerator
-        .checked_div(denominator)
-        .ok_or(InterestRateError::DivisionByZero)?;
+    mul_div(principal, rate_time, denominator, rounding)
+}
+
+/// Simple interest factor of `rate_bps` a year over `elapsed` seconds, scaled
+/// by [`WAD`]
+///
+/// This is the per-second rate times `elapsed`, without rounding the
+/// per-second rate on its own first.
+pub fn interest_factor(
+    rate_bps: i128,
+    elapsed: u64,
+    rounding: Rounding,
+) -> Result<i128, InterestRateError> {
+    let rate_time = rate_bps
+        .checked_mul(elapsed as i128)
+        .ok_or(InterestRateError::Overflow)?;
+    let denominator = BASIS_POINTS_SCALE
+        .checked_mul(SECONDS_PER_YEAR as i128)
+        .ok_or(InterestRateError::Overflow)?;
+    mul_div(rate_time, WAD, denominator, rounding)
+}
+
+/// `index` compounded by `rate_bps` a year over `elapsed` seconds
+///
+/// Borrow indexes measure debt owed, so the growth rounds up.
+pub fn compound_index(
+    index: i128,
+    rate_bps: i128,
+    elapsed: u64,
+) -> Result<i128, InterestRateError> {
+    let factor = interest_factor(rate_bps, elapsed, Rounding::Up)?;
+    let growth = mul_div(index, factor, WAD, Rounding::Up)?;
+    index.checked_add(growth).ok_or(InterestRateError::Overflow)
+}
+
+/// Annual percentage yield of `rate_bps` a year compounded every second, in
+/// basis points
+///
+/// `(1 + rate / SECONDS_PER_YEAR) ^ SECONDS_PER_YEAR - 1`, computed in [`WAD`]
+/// fixed point by repeated squaring. The per-second rate and each of the fewer
+/// than 50 products round down by under 1e-18; compounded over a year, that
+/// leaves the growth factor short of the exact one by less than 1e-9, far below
+/// the one basis point (1e-4) the result is rounded down to. Every step rounds
+/// the same way, so the yield never decreases as `rate_bps` increases.
+///
+/// # Errors
+/// * `InterestRateError::NegativeRateParameter` - If `rate_bps` is negative
+/// * `InterestRateError::Overflow` - If the growth factor does not fit in an `i128`
+pub fn annual_percentage_yield(rate_bps: i128) -> Result<i128, InterestRateError> {
+    if rate_bps < 0 {
+        return Err(InterestRateError::NegativeRateParameter);
+    }
+    let mut base = WAD
+        .checked_add(interest_factor(rate_bps, 1, Rounding::Down)?)
+        .ok_or(InterestRateError::Overflow)?;
+    let mut exponent = SECONDS_PER_YEAR;
+    let mut factor = WAD;
+    while exponent > 0 {
+        if exponent & 1 == 1 {
+            factor = mul_div(factor, base, WAD, Rounding::Down)?;
+        }
+        exponent >>= 1;
+        if exponent > 0 {
+            base = mul_div(base, base, WAD, Rounding::Down)?;
+        }
+    }
+    mul_div(factor - WAD, BASIS_POINTS_SCALE, WAD, Rounding::Down)
+}
+
+/// `a * b / denominator` rounded to a whole number in the direction of `rounding`
+///
+/// Operands are expected to be non-negative. `a` is split around `denominator`
+/// first, so the intermediate products stay below `a * b`.
+pub fn mul_div(
+    a: i128,
+    b: i128,
+    denominator: i128,
+    rounding: Rounding,
+) -> Result<i128, InterestRateError> {
+    if denominator == 0 {
+        return Err(InterestRateError::DivisionByZero);
+    }
+    let whole = (a / denominator)
+        .checked_mul(b)
+        .ok_or(InterestRateError::Overflow)?;
+    let partial = (a % denominator)
+        .checked_mul(b)
+        .ok_or(InterestRateError::Overflow)?;
+    let mut result = whole
+        .checked_add(partial / denominator)
+        .ok_or(InterestRateError::Overflow)?;
+    if rounding == Rounding::Up && partial % denominator != 0 {
+        result = result.checked_add(1).ok_or(InterestRateError::Overflow)?;
+    }
+    Ok(result)
+}
 
-    Ok(interest)
+/// `amount * factor / WAD` rounded down to whole units, plus the fraction of a
+/// unit left over, scaled by [`WAD`]
+///
+/// `carry` is the fraction left over by the previous accrual, so accruing in
+/// many short steps credits the same as one long accrual to within one unit.
+pub fn mul_wad_with_carry(
+    amount: i128,
+    factor: i128,
+    carry: i128,
+) -> Result<(i128, i128), InterestRateError> {
+    let whole = (amount / WAD)
+        .checked_mul(factor)
+        .ok_or(InterestRateError::Overflow)?;
+    let fraction = (amount % WAD)
+        .checked_mul(factor)
+        .and_then(|fraction| fraction.checked_add(carry))
+        .ok_or(InterestRateError::Overflow)?;
+    let whole = whole
+        .checked_add(fraction / WAD)
+        .ok_or(InterestRateError::Overflow)?;
+    Ok((whole, fraction % WAD))
 }
 
 /// Update interest rate configuration parameters
 ///
+/// The updated config must pass [`validate_interest_rate_config`]; otherwise
+/// nothing changes. With a maximum rate delta per day set, the borrow rate
+/// then moves toward the new model gradually (see [`RateTransition`]) unless
+/// `force` is set, which applies it at once and emits an
+/// `RateConfigForcedEvent`.
+///
+/// Every asset is accrued at the old model first (see [`checkpoint_accruals`]),
+/// and `last_update` records the time of the change.
+///
 /// # Arguments
 /// * `env` - The Soroban environment
 /// * `caller` - The caller address (must be admin)
@@ -306,6 +1269,12 @@ pub fn calculate_accrued_interest(
 /// * `rate_floor_bps` - New rate floor (None to keep current)
 /// * `rate_ceiling_bps` - New rate ceiling (None to keep current)
 /// * `spread_bps` - New spread (None to keep current)
+/// * `reserve_factor_bps` - New reserve factor (None to keep current)
+/// * `force` - Skip the gradual transition and any transition in progress
+///
+/// # Errors
+/// * `InterestRateError::RateTransitionInProgress` - If the previous update is
+///   still being phased in and `force` is not set
 #[allow(clippy::too_many_arguments)]
 pub fn update_interest_rate_config(
     env: &Env,
@@ -317,98 +1286,296 @@ pub fn update_interest_rate_config(
     rate_floor_bps: Option<i128>,
     rate_ceiling_bps: Option<i128>,
     spread_bps: Option<i128>,
+    reserve_factor_bps: Option<i128>,
+    force: bool,
 ) -> Result<(), InterestRateError> {
     // Check authorization
     crate::admin::require_admin(env, &caller).map_err(|_| InterestRateError::Unauthorized)?;
+    if !force && get_rate_transition(env).is_some() {
+        return Err(InterestRateError::RateTransitionInProgress);
+    }
 
     let config_key = InterestRateDataKey::InterestRateConfig;
-    let mut config = get_interest_rate_config(env).ok_or(InterestRateError::InvalidParameter)?;
+    let previous = get_interest_rate_config(env).ok_or(InterestRateError::InvalidParameter)?;
+    let mut config = previous.clone();
 
-    // Update parameters with validation
+    // Apply the changes, then validate the resulting config as a whole
     if let Some(rate) = base_rate_bps {
-        if !(0..=BASIS_POINTS_SCALE).contains(&rate) {
-            return Err(InterestRateError::InvalidParameter);
-        }
         config.base_rate_bps = rate;
     }
-
     if let Some(kink) = kink_utilization_bps {
-        if kink <= 0 || kink >= BASIS_POINTS_SCALE {
-            return Err(InterestRateError::InvalidParameter);
-        }
         config.kink_utilization_bps = kink;
     }
-
     if let Some(mult) = multiplier_bps {
-        if mult < 0 {
-            return Err(InterestRateError::InvalidParameter);
-        }
         config.multiplier_bps = mult;
     }
-
     if let Some(jump) = jump_multiplier_bps {
-        if jump < 0 {
-            return Err(InterestRateError::InvalidParameter);
-        }
         config.jump_multiplier_bps = jump;
     }
-
     if let Some(floor) = rate_floor_bps {
-        if !(0..=BASIS_POINTS_SCALE).contains(&floor) {
-            return Err(InterestRateError::InvalidParameter);
-        }
-        if floor > config.rate_ceiling_bps {
-            return Err(InterestRateError::InvalidParameter);
-        }
         config.rate_floor_bps = floor;
     }
-
     if let Some(ceiling) = rate_ceiling_bps {
-        if !(0..=BASIS_POINTS_SCALE).contains(&ceiling) {
-            return Err(InterestRateError::InvalidParameter);
-        }
-        if ceiling < config.rate_floor_bps {
-            return Err(InterestRateError::InvalidParameter);
-        }
         config.rate_ceiling_bps = ceiling;
     }
-
     if let Some(spread) = spread_bps {
-        if !(0..=BASIS_POINTS_SCALE).contains(&spread) {
-            return Err(InterestRateError::InvalidParameter);
-        }
         config.spread_bps = spread;
     }
+    if let Some(reserve_factor) = reserve_factor_bps {
+        config.reserve_factor_bps = reserve_factor;
+    }
+    let now = env.ledger().timestamp();
+    validate_interest_rate_config(&config, now)?;
+    checkpoint_accruals(env)?;
 
-    config.last_update = env.ledger().timestamp();
+    let max_delta = get_max_rate_delta_per_day(env);
+    let transition_key = InterestRateDataKey::RateTransition;
+    if force {
+        // Report the jump for native XLM at current utilization before the
+        // transition is dropped
+        let utilization = calculate_projected_utilization(env, 0)?;
+        let strategy = get_rate_strategy(env, &None);
+        let (previous_rate, _) = effective_rates(env, &strategy, &previous, utilization, now)?;
+        env.storage().persistent().remove(&transition_key);
+        crate::events::emit_rate_config_forced(
+            env,
+            crate::events::RateConfigForcedEvent {
+                admin: caller,
+                utilization_bps: utilization,
+                previous_rate_bps: previous_rate,
+                new_rate_bps: compute_rates(&strategy, &config_at(&config, now), utilization)?.0,
+                max_rate_delta_bps_per_day: max_delta,
+                timestamp: now,
+            },
+        );
+    } else if max_delta > 0 {
+        let gap = max_rate_gap(&previous, &config)?;
+        if gap > 0 {
+            // Days (rounded up to the second) for the largest gap to close
+            let duration = gap
+                .checked_mul(SECONDS_PER_DAY as i128)
+                .ok_or(InterestRateError::Overflow)?
+                .checked_add(max_delta - 1)
+                .ok_or(InterestRateError::Overflow)?
+                / max_delta;
+            let transition = RateTransition {
+                previous,
+                started_at: now,
+                ends_at: now.saturating_add(duration as u64),
+                max_rate_delta_bps_per_day: max_delta,
+            };
+            env.storage().persistent().set(&transition_key, &transition);
+        }
+    }
+
+    config.last_update = now;
     env.storage().persistent().set(&config_key, &config);
 
     Ok(())
 }
 
+/// Rate transition still in progress, if any
+pub fn get_rate_transition(env: &Env) -> Option<RateTransition> {
+    env.storage()
+        .persistent()
+        .get::<InterestRateDataKey, RateTransition>(&InterestRateDataKey::RateTransition)
+        .filter(|transition| env.ledger().timestamp() < transition.ends_at)
+}
+
+/// Largest borrow rate move per day a config update may cause (0 = unlimited)
+pub fn get_max_rate_delta_per_day(env: &Env) -> i128 {
+    env.storage()
+        .persistent()
+        .get::<InterestRateDataKey, i128>(&InterestRateDataKey::MaxRateDeltaPerDay)
+        .unwrap_or(0)
+}
+
+/// Set the largest borrow rate move per day a config update may cause
+///
+/// Only later updates are affected; a transition in progress keeps its rate.
+///
+/// # Arguments
+/// * `env` - The Soroban environment
+/// * `caller` - The caller address (must be admin)
+/// * `max_delta_bps_per_day` - Basis points per day, 0 to apply updates at once
+///
+/// # Errors
+/// * `InterestRateError::InvalidParameter` - If the delta is negative
+pub fn set_max_rate_delta_per_day(
+    env: &Env,
+    caller: Address,
+    max_delta_bps_per_day: i128,
+) -> Result<(), InterestRateError> {
+    crate::admin::require_admin(env, &caller).map_err(|_| InterestRateError::Unauthorized)?;
+    if max_delta_bps_per_day < 0 {
+        return Err(InterestRateError::InvalidParameter);
+    }
+    env.storage().persistent().set(
+        &InterestRateDataKey::MaxRateDeltaPerDay,
+        &max_delta_bps_per_day,
+    );
+    Ok(())
+}
+
+/// Rate formula `asset` follows ([`RateStrategy::Kinked`] unless set)
+pub fn get_rate_strategy(env: &Env, asset: &Option<Address>) -> RateStrategy {
+    env.storage()
+        .persistent()
+        .get::<InterestRateDataKey, RateStrategy>(&InterestRateDataKey::RateStrategy(asset.clone()))
+        .unwrap_or(RateStrategy::Kinked)
+}
+
+/// Set the rate formula `asset` follows
+///
+/// The asset is accrued first, so interest up to now is priced under the
+/// strategy it accrued under.
+///
+/// # Arguments
+/// * `env` - The Soroban environment
+/// * `caller` - The caller address (must be admin)
+/// * `asset` - The asset (None for native XLM)
+/// * `strategy` - The new strategy
+///
+/// # Errors
+/// * `InterestRateError::NegativeRateParameter` - If a fixed rate is negative
+pub fn set_rate_strategy(
+    env: &Env,
+    caller: Address,
+    asset: Option<Address>,
+    strategy: RateStrategy,
+) -> Result<(), InterestRateError> {
+    crate::admin::require_admin(env, &caller).map_err(|_| InterestRateError::Unauthorized)?;
+    if let RateStrategy::Fixed(rate_bps) = strategy {
+        if rate_bps < 0 {
+            return Err(InterestRateError::NegativeRateParameter);
+        }
+    }
+
+    accrue_interest(env, asset.clone())?;
+    env.storage()
+        .persistent()
+        .set(&InterestRateDataKey::RateStrategy(asset), &strategy);
+    if let Some(mut config) = get_interest_rate_config(env) {
+        config.last_update = env.ledger().timestamp();
+        env.storage()
+            .persistent()
+            .set(&InterestRateDataKey::InterestRateConfig, &config);
+    }
+    Ok(())
+}
+
+/// Check that a rate model config is coherent
+///
+/// # Errors
+/// * `InterestRateError::NegativeRateParameter` - If the base rate, multiplier or
+///   jump multiplier is negative
+/// * `InterestRateError::InvalidParameter` - If the base rate, floor, ceiling or
+///   reserve factor is above 100%, or the floor or ceiling is negative
+/// * `InterestRateError::InvalidKink` - If the kink is outside (0, 100%]
+/// * `InterestRateError::FloorAboveCeiling` - If the floor is above the ceiling
+/// * `InterestRateError::SpreadTooLarge` - If the spread is negative or above
+///   [`MAX_SPREAD_BPS`]
+/// * `InterestRateError::RateOverflow` - If the rate at the kink or at 100%
+///   utilization cannot be computed
+pub fn validate_interest_rate_config(
+    config: &InterestRateConfig,
+    now: u64,
+) -> Result<(), InterestRateError> {
+    if config.base_rate_bps < 0 || config.multiplier_bps < 0 || config.jump_multiplier_bps < 0 {
+        return Err(InterestRateError::NegativeRateParameter);
+    }
+    let in_range = |bps: i128| (0..=BASIS_POINTS_SCALE).contains(&bps);
+    if !in_range(config.base_rate_bps)
+        || !in_range(config.rate_floor_bps)
+        || !in_range(config.rate_ceiling_bps)
+        || !in_range(config.reserve_factor_bps)
+    {
+        return Err(InterestRateError::InvalidParameter);
+    }
+    if config.kink_utilization_bps <= 0 || config.kink_utilization_bps > BASIS_POINTS_SCALE {
+        return Err(InterestRateError::InvalidKink);
+    }
+    if config.rate_floor_bps > config.rate_ceiling_bps {
+        return Err(InterestRateError::FloorAboveCeiling);
+    }
+    if !(0..=MAX_SPREAD_BPS).contains(&config.spread_bps) {
+        return Err(InterestRateError::SpreadTooLarge);
+    }
+
+    // Both slopes are evaluated at their steepest point
+    for utilization in [config.kink_utilization_bps, BASIS_POINTS_SCALE] {
+        borrow_rate_at(config, utilization, now).map_err(|_| InterestRateError::RateOverflow)?;
+    }
+
+    Ok(())
+}
+
 /// Set emergency rate adjustment
 ///
+/// The adjustment applies for `duration_secs` from now and is ignored afterwards.
+/// Setting a new adjustment replaces the current one and its expiry. Every
+/// asset is accrued at the rates in effect before the change.
+///
 /// # Arguments
 /// * `env` - The Soroban environment
 /// * `caller` - The caller address (must be admin)
 /// * `adjustment_bps` - Emergency adjustment in basis points (can be negative)
+/// * `duration_secs` - How long the adjustment stays in effect
+///
+/// # Errors
+/// * `InterestRateError::InvalidParameter` - If the adjustment exceeds the
+///   configured bound, or the duration is zero or above the configured maximum
 pub fn set_emergency_rate_adjustment(
     env: &Env,
     caller: Address,
     adjustment_bps: i128,
+    duration_secs: u64,
 ) -> Result<(), InterestRateError> {
     // Check authorization
     crate::admin::require_admin(env, &caller).map_err(|_| InterestRateError::Unauthorized)?;
 
-    // Validate adjustment is within reasonable bounds
-    if adjustment_bps.abs() > BASIS_POINTS_SCALE {
+    // Validate adjustment and duration against the configured limits
+    let limits = get_emergency_adjustment_limits(env);
+    if adjustment_bps.abs() > limits.max_adjustment_bps {
+        return Err(InterestRateError::InvalidParameter);
+    }
+    if duration_secs == 0 || duration_secs > limits.max_duration_secs {
         return Err(InterestRateError::InvalidParameter);
     }
 
     let config_key = InterestRateDataKey::InterestRateConfig;
     let mut config = get_interest_rate_config(env).ok_or(InterestRateError::InvalidParameter)?;
+    checkpoint_accruals(env)?;
 
+    let now = env.ledger().timestamp();
     config.emergency_adjustment_bps = adjustment_bps;
+    config.emergency_adjustment_expires_at = now
+        .checked_add(duration_secs)
+        .ok_or(InterestRateError::Overflow)?;
+    config.last_update = now;
+
+    env.storage().persistent().set(&config_key, &config);
+
+    Ok(())
+}
+
+/// Remove the emergency rate adjustment before it expires
+///
+/// # Arguments
+/// * `env` - The Soroban environment
+/// * `caller` - The caller address (must be admin)
+pub fn clear_emergency_rate_adjustment(
+    env: &Env,
+    caller: Address,
+) -> Result<(), InterestRateError> {
+    crate::admin::require_admin(env, &caller).map_err(|_| InterestRateError::Unauthorized)?;
+
+    let config_key = InterestRateDataKey::InterestRateConfig;
+    let mut config = get_interest_rate_config(env).ok_or(InterestRateError::InvalidParameter)?;
+    checkpoint_accruals(env)?;
+
+    config.emergency_adjustment_bps = 0;
+    config.emergency_adjustment_expires_at = 0;
     config.last_update = env.ledger().timestamp();
 
     env.storage().persistent().set(&config_key, &config);
@@ -416,17 +1583,309 @@ pub fn set_emergency_rate_adjustment(
     Ok(())
 }
 
-/// Get current borrow rate (in basis points)
+/// The emergency rate adjustment in effect, if any
+///
+/// Returns `None` when no adjustment was set, it was cleared or it has expired.
+pub fn get_emergency_rate_adjustment(env: &Env) -> Option<EmergencyRateAdjustment> {
+    let config = get_interest_rate_config(env)?;
+    if config.emergency_adjustment_bps == 0
+        || env.ledger().timestamp() >= config.emergency_adjustment_expires_at
+    {
+        return None;
+    }
+    Some(EmergencyRateAdjustment {
+        adjustment_bps: config.emergency_adjustment_bps,
+        expires_at: config.emergency_adjustment_expires_at,
+    })
+}
+
+/// Get the emergency adjustment bounds, defaulting to ±100% for up to 7 days
+pub fn get_emergency_adjustment_limits(env: &Env) -> EmergencyAdjustmentLimits {
+    env.storage()
+        .persistent()
+        .get::<InterestRateDataKey, EmergencyAdjustmentLimits>(
+            &InterestRateDataKey::EmergencyAdjustmentLimits,
+        )
+        .unwrap_or(EmergencyAdjustmentLimits {
+            max_adjustment_bps: BASIS_POINTS_SCALE,
+            max_duration_secs: 7 * 86400,
+        })
+}
+
+/// Set the emergency adjustment bounds
+///
+/// Only checked when an adjustment is set; one already in effect keeps running.
+///
+/// # Arguments
+/// * `env` - The Soroban environment
+/// * `caller` - The caller address (must be admin)
+/// * `max_adjustment_bps` - Largest absolute adjustment (0-100%)
+/// * `max_duration_secs` - Longest duration (must be positive)
+pub fn set_emergency_adjustment_limits(
+    env: &Env,
+    caller: Address,
+    max_adjustment_bps: i128,
+    max_duration_secs: u64,
+) -> Result<(), InterestRateError> {
+    crate::admin::require_admin(env, &caller).map_err(|_| InterestRateError::Unauthorized)?;
+
+    if !(0..=BASIS_POINTS_SCALE).contains(&max_adjustment_bps) || max_duration_secs == 0 {
+        return Err(InterestRateError::InvalidParameter);
+    }
+
+    env.storage().persistent().set(
+        &InterestRateDataKey::EmergencyAdjustmentLimits,
+        &EmergencyAdjustmentLimits {
+            max_adjustment_bps,
+            max_duration_secs,
+        },
+    );
+
+    Ok(())
+}
+
+/// Get the circuit breaker parameters, `None` while it is disabled
+pub fn get_circuit_breaker_config(env: &Env) -> Option<CircuitBreakerConfig> {
+    env.storage()
+        .persistent()
+        .get::<InterestRateDataKey, CircuitBreakerConfig>(
+            &InterestRateDataKey::CircuitBreakerConfig,
+        )
+}
+
+/// Get the circuit breaker state as of the last accrual
+pub fn get_circuit_breaker_state(env: &Env) -> CircuitBreakerState {
+    env.storage()
+        .persistent()
+        .get::<InterestRateDataKey, CircuitBreakerState>(&InterestRateDataKey::CircuitBreakerState)
+        .unwrap_or(CircuitBreakerState {
+            tripped: false,
+            tripped_at: 0,
+            release_pending_since: None,
+        })
+}
+
+/// Set the utilization-spike circuit breaker parameters
+///
+/// A breaker already tripped stays tripped and applies the new bonus; the next
+/// accrual decides whether it trips or releases.
+///
+/// # Arguments
+/// * `env` - The Soroban environment
+/// * `caller` - The caller address (must be admin)
+/// * `trigger_utilization_bps` - Utilization that trips the breaker
+/// * `release_utilization_bps` - Utilization to stay below to release it (at
+///   most the trigger)
+/// * `bonus_bps` - Added to the borrow rate while tripped (0-100%)
+/// * `cooloff_secs` - How long utilization must stay below the release threshold
+pub fn set_circuit_breaker_config(
+    env: &Env,
+    caller: Address,
+    trigger_utilization_bps: i128,
+    release_utilization_bps: i128,
+    bonus_bps: i128,
+    cooloff_secs: u64,
+) -> Result<(), InterestRateError> {
+    crate::admin::require_admin(env, &caller).map_err(|_| InterestRateError::Unauthorized)?;
+
+    if release_utilization_bps <= 0
+        || release_utilization_bps > trigger_utilization_bps
+        || trigger_utilization_bps > BASIS_POINTS_SCALE
+        || !(0..=BASIS_POINTS_SCALE).contains(&bonus_bps)
+    {
+        return Err(InterestRateError::InvalidParameter);
+    }
+
+    // A tripped breaker's bonus changes with the config
+    checkpoint_accruals(env)?;
+    env.storage().persistent().set(
+        &InterestRateDataKey::CircuitBreakerConfig,
+        &CircuitBreakerConfig {
+            trigger_utilization_bps,
+            release_utilization_bps,
+            bonus_bps,
+            cooloff_secs,
+        },
+    );
+
+    Ok(())
+}
+
+/// Bonus a tripped circuit breaker adds to the borrow rate (0 otherwise)
+fn circuit_breaker_bonus(env: &Env) -> i128 {
+    match get_circuit_breaker_config(env) {
+        Some(config) if get_circuit_breaker_state(env).tripped => config.bonus_bps,
+        _ => 0,
+    }
+}
+
+/// Trip or release the circuit breaker on the utilization an accrual observes
+fn observe_circuit_breaker(env: &Env, now: u64) -> Result<(), InterestRateError> {
+    let Some(config) = get_circuit_breaker_config(env) else {
+        return Ok(());
+    };
+    let utilization = calculate_projected_utilization(env, 0)?;
+    let mut state = get_circuit_breaker_state(env);
+    let previous = state.clone();
+
+    if !state.tripped {
+        if utilization >= config.trigger_utilization_bps {
+            state.tripped = true;
+            state.tripped_at = now;
+            state.release_pending_since = None;
+            crate::events::emit_circuit_breaker_tripped(
+                env,
+                crate::events::CircuitBreakerTrippedEvent {
+                    utilization_bps: utilization,
+                    bonus_bps: config.bonus_bps,
+                    timestamp: now,
+                },
+            );
+        }
+    } else if utilization >= config.release_utilization_bps {
+        state.release_pending_since = None;
+    } else {
+        let since = state.release_pending_since.unwrap_or(now);
+        if now.saturating_sub(since) >= config.cooloff_secs {
+            state.tripped = false;
+            state.release_pending_since = None;
+            crate::events::emit_circuit_breaker_released(
+                env,
+                crate::events::CircuitBreakerReleasedEvent {
+                    utilization_bps: utilization,
+                    tripped_at: state.tripped_at,
+                    timestamp: now,
+                },
+            );
+        } else {
+            state.release_pending_since = Some(since);
+        }
+    }
+
+    if state != previous {
+        env.storage()
+            .persistent()
+            .set(&InterestRateDataKey::CircuitBreakerState, &state);
+    }
+    Ok(())
+}
+
+/// Get the share of borrow interest credited to the protocol reserve (in basis points)
+pub fn get_reserve_factor_bps(env: &Env) -> i128 {
+    get_interest_rate_config(env)
+        .unwrap_or_else(get_default_config)
+        .reserve_factor_bps
+}
+
+/// Set the share of borrow interest credited to the protocol reserve
+///
+/// # Arguments
+/// * `env` - The Soroban environment
+/// * `caller` - The caller address (must be admin)
+/// * `reserve_factor_bps` - Reserve share of interest in basis points (0-100%)
+pub fn set_reserve_factor(
+    env: &Env,
+    caller: Address,
+    reserve_factor_bps: i128,
+) -> Result<(), InterestRateError> {
+    // Check authorization
+    crate::admin::require_admin(env, &caller).map_err(|_| InterestRateError::Unauthorized)?;
+
+    if !(0..=BASIS_POINTS_SCALE).contains(&reserve_factor_bps) {
+        return Err(InterestRateError::InvalidParameter);
+    }
+
+    let config_key = InterestRateDataKey::InterestRateConfig;
+    let mut config = get_interest_rate_config(env).ok_or(InterestRateError::InvalidParameter)?;
+    checkpoint_accruals(env)?;
+
+    config.reserve_factor_bps = reserve_factor_bps;
+    config.last_update = env.ledger().timestamp();
+
+    env.storage().persistent().set(&config_key, &config);
+
+    Ok(())
+}
+
+/// Get the stable borrow rate parameters, defaulting to a 1% premium and a 5%
+/// rebalance threshold
+pub fn get_stable_rate_config(env: &Env) -> StableRateConfig {
+    env.storage()
+        .persistent()
+        .get::<InterestRateDataKey, StableRateConfig>(&InterestRateDataKey::StableRateConfig)
+        .unwrap_or(StableRateConfig {
+            premium_bps: 100,
+            rebalance_threshold_bps: 500,
+        })
+}
+
+/// Set the stable borrow rate parameters
+///
+/// Only affects rates locked afterwards and later rebalance checks.
+///
+/// # Arguments
+/// * `env` - The Soroban environment
+/// * `caller` - The caller address (must be admin)
+/// * `premium_bps` - Premium over the variable rate (0-100%)
+/// * `rebalance_threshold_bps` - Drift below the variable rate that allows a rebalance (0-100%)
+pub fn set_stable_rate_config(
+    env: &Env,
+    caller: Address,
+    premium_bps: i128,
+    rebalance_threshold_bps: i128,
+) -> Result<(), InterestRateError> {
+    crate::admin::require_admin(env, &caller).map_err(|_| InterestRateError::Unauthorized)?;
+
+    if !(0..=BASIS_POINTS_SCALE).contains(&premium_bps)
+        || !(0..=BASIS_POINTS_SCALE).contains(&rebalance_threshold_bps)
+    {
+        return Err(InterestRateError::InvalidParameter);
+    }
+
+    env.storage().persistent().set(
+        &InterestRateDataKey::StableRateConfig,
+        &StableRateConfig {
+            premium_bps,
+            rebalance_threshold_bps,
+        },
+    );
+
+    Ok(())
+}
+
+/// Calculate the rate a stable borrow of `asset` would lock right now (in basis points)
+///
+/// The variable borrow rate plus the stable premium.
+pub fn calculate_stable_borrow_rate(
+    env: &Env,
+    asset: &Option<Address>,
+) -> Result<i128, InterestRateError> {
+    calculate_borrow_rate(env, asset)?
+        .checked_add(get_stable_rate_config(env).premium_bps)
+        .ok_or(InterestRateError::Overflow)
+}
+
+/// Get current borrow rate of the native asset (in basis points)
 pub fn get_current_borrow_rate(env: &Env) -> Result<i128, InterestRateError> {
-    calculate_borrow_rate(env)
+    calculate_borrow_rate(env, &None)
 }
 
-/// Get current supply rate (in basis points)
+/// Get current supply rate of the native asset (in basis points)
 pub fn get_current_supply_rate(env: &Env) -> Result<i128, InterestRateError> {
-    calculate_supply_rate(env)
+    calculate_supply_rate(env, &None)
+}
+
+/// Current borrow rate of `asset` as an annual percentage yield (in basis points)
+pub fn get_borrow_apy(env: &Env, asset: &Option<Address>) -> Result<i128, InterestRateError> {
+    annual_percentage_yield(calculate_borrow_rate(env, asset)?)
+}
+
+/// Current supply rate of `asset` as an annual percentage yield (in basis points)
+pub fn get_supply_apy(env: &Env, asset: &Option<Address>) -> Result<i128, InterestRateError> {
+    annual_percentage_yield(calculate_supply_rate(env, asset)?)
 }
 
-/// Get current utilization (in basis points)
+/// Get current utilization of the native asset (in basis points)
 pub fn get_current_utilization(env: &Env) -> Result<i128, InterestRateError> {
-    calculate_utilization(env)
+    calculate_utilization(env, &None)
 }
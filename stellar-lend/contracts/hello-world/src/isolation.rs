@@ -0,0 +1,139 @@
+//! # Isolation Mode
+//!
+//! Lets the protocol list long-tail collateral without exposing every market to it.
+//!
+//! An asset flagged `AssetParams::isolated` can only be used as a user's sole
+//! collateral. While it is, the user may only borrow assets flagged
+//! `borrowable_in_isolation`, and the principal borrowed against the isolated
+//! asset across all users is capped at its `debt_ceiling` (0 = no ceiling).
+//!
+//! Combining isolated and other collateral on one account is rejected when a
+//! deposit is made or re-enabled as collateral. The restrictions follow the
+//! current asset parameters, so un-isolating an asset lifts them immediately.
+//!
+//! ## Storage Keys
+//! - `DepositDataKey::IsolatedDebt(asset)` — principal borrowed against an isolated asset
+
+use soroban_sdk::{Address, Env, Vec};
+
+use crate::borrow::BorrowError;
+use crate::deposit::{AssetParams, DepositDataKey};
+
+fn get_asset_params(env: &Env, asset: &Option<Address>) -> Option<AssetParams> {
+    asset.as_ref().and_then(|asset_addr| {
+        env.storage()
+            .persistent()
+            .get::<DepositDataKey, AssetParams>(&DepositDataKey::AssetParams(asset_addr.clone()))
+    })
+}
+
+/// Whether `asset` is listed as isolated collateral
+pub fn is_isolated(env: &Env, asset: &Option<Address>) -> bool {
+    get_asset_params(env, asset)
+        .map(|params| params.isolated)
+        .unwrap_or(false)
+}
+
+/// Whether `asset` may be borrowed by a user in isolation mode
+///
+/// Native XLM carries no asset parameters and is never borrowable in isolation.
+pub fn is_borrowable_in_isolation(env: &Env, asset: &Option<Address>) -> bool {
+    get_asset_params(env, asset)
+        .map(|params| params.borrowable_in_isolation)
+        .unwrap_or(false)
+}
+
+/// Enabled collateral deposits of `user` other than `asset`
+fn other_collateral(env: &Env, user: &Address, asset: &Option<Address>) -> Vec<Option<Address>> {
+    let mut others = Vec::new(env);
+    for other in crate::deposit::get_user_assets(env, user).iter() {
+        if other != *asset
+            && crate::deposit::is_collateral_enabled(env, user, &other)
+            && crate::deposit::get_user_deposit_balance(env, user, &other) > 0
+        {
+            others.push_back(other);
+        }
+    }
+    others
+}
+
+/// Isolated asset backing `user`'s borrows, if they are in isolation mode
+pub fn get_isolated_collateral(env: &Env, user: &Address) -> Option<Address> {
+    for asset in crate::deposit::get_user_assets(env, user).iter() {
+        if is_isolated(env, &asset)
+            && crate::deposit::is_collateral_enabled(env, user, &asset)
+            && crate::deposit::get_user_deposit_balance(env, user, &asset) > 0
+        {
+            return asset;
+        }
+    }
+    None
+}
+
+/// Whether enabling `asset` as collateral would combine isolated and other
+/// collateral on `user`'s account
+pub fn conflicts_with_collateral(env: &Env, user: &Address, asset: &Option<Address>) -> bool {
+    let isolated = is_isolated(env, asset);
+    other_collateral(env, user, asset)
+        .iter()
+        .any(|other| isolated || is_isolated(env, &other))
+}
+
+/// Principal currently borrowed against an isolated asset
+pub fn get_isolated_debt(env: &Env, asset: &Address) -> i128 {
+    env.storage()
+        .persistent()
+        .get::<DepositDataKey, i128>(&DepositDataKey::IsolatedDebt(asset.clone()))
+        .unwrap_or(0)
+}
+
+/// Check that `user` may take on `amount` more principal in `asset`
+///
+/// Users outside isolation mode are unrestricted.
+///
+/// # Errors
+/// * `BorrowError::NotBorrowableInIsolation` - If `asset` is not borrowable in isolation
+/// * `BorrowError::DebtCeilingExceeded` - If the isolated asset's debt ceiling would be exceeded
+pub fn check_isolated_borrow(
+    env: &Env,
+    user: &Address,
+    asset: &Option<Address>,
+    amount: i128,
+) -> Result<(), BorrowError> {
+    let Some(isolated) = get_isolated_collateral(env, user) else {
+        return Ok(());
+    };
+    if !is_borrowable_in_isolation(env, asset) {
+        return Err(BorrowError::NotBorrowableInIsolation);
+    }
+
+    let ceiling = get_asset_params(env, &Some(isolated.clone()))
+        .map(|params| params.debt_ceiling)
+        .unwrap_or(0);
+    let new_debt = get_isolated_debt(env, &isolated)
+        .checked_add(amount)
+        .ok_or(BorrowError::Overflow)?;
+    if ceiling > 0 && new_debt > ceiling {
+        return Err(BorrowError::DebtCeilingExceeded);
+    }
+    Ok(())
+}
+
+/// Track a change of `delta` in the principal `user` owes
+///
+/// Only users in isolation mode count toward their isolated asset's debt; the
+/// total is floored at zero.
+pub(crate) fn adjust_isolated_debt(env: &Env, user: &Address, delta: i128) {
+    let Some(isolated) = get_isolated_collateral(env, user) else {
+        return;
+    };
+    let key = DepositDataKey::IsolatedDebt(isolated.clone());
+    let debt = get_isolated_debt(env, &isolated)
+        .saturating_add(delta)
+        .max(0);
+    if debt > 0 {
+        env.storage().persistent().set(&key, &debt);
+    } else {
+        env.storage().persistent().remove(&key);
+    }
+}
@@ -64,10 +64,17 @@ mod borrow;
 mod deposit;
 mod errors;
 mod events;
+mod isolation;
+mod reentrancy;
 mod repay;
+mod repayment_schedule;
 mod risk_management;
 mod risk_params;
+mod term_loan;
+mod ttl;
 mod withdraw;
+mod withdraw_queue;
+mod withdraw_schedule;
 
 use borrow::borrow_asset;
 use deposit::deposit_collateral;
@@ -80,8 +87,8 @@ use risk_management::{
 };
 use risk_params::{
     can_be_liquidated,
-    get_liquidation_incentive_amount, get_max_liquidatable_amount,
-    initialize_risk_params, require_min_collateral_ratio,
+    get_liquidation_incentive_amount,
+    initialize_risk_params,
     RiskParamsError
 };
 use withdraw::withdraw_collateral;
@@ -224,6 +231,8 @@ impl HelloContract {
         caller: Address,
         new_admin: Address,
     ) -> Result<(), crate::admin::AdminError> {
+        let _guard = reentrancy::ReentrancyGuard::enter(&env)
+            .ok_or(crate::admin::AdminError::Reentrancy)?;
         crate::admin::set_admin(&env, new_admin, Some(caller))
     }
 
@@ -234,6 +243,8 @@ impl HelloContract {
         role: Symbol,
         account: Address,
     ) -> Result<(), crate::admin::AdminError> {
+        let _guard = reentrancy::ReentrancyGuard::enter(&env)
+            .ok_or(crate::admin::AdminError::Reentrancy)?;
         crate::admin::grant_role(&env, caller, role, account)
     }
 
@@ -244,6 +255,8 @@ impl HelloContract {
         role: Symbol,
         account: Address,
     ) -> Result<(), crate::admin::AdminError> {
+        let _guard = reentrancy::ReentrancyGuard::enter(&env)
+            .ok_or(crate::admin::AdminError::Reentrancy)?;
         crate::admin::revoke_role(&env, caller, role, account)
     }
 
@@ -272,17 +285,291 @@ impl HelloContract {
         asset: Option<Address>,
         amount: i128,
     ) -> Result<i128, crate::deposit::DepositError> {
+        let _guard = reentrancy::ReentrancyGuard::enter(&env)
+            .ok_or(crate::deposit::DepositError::Reentrancy)?;
         deposit::deposit_collateral(&env, user, asset, amount)
     }
 
-    /// Withdraw assets from the protocol
-    pub fn withdraw_asset(
+    /// Deposit collateral for several assets in one call
+    ///
+    /// Applies the same validation as `deposit_collateral` to every entry. The batch
+    /// is atomic: if any entry fails, nothing is deposited and the returned contract
+    /// error code is `10000 + index * 100 + DepositError`, identifying the failing entry.
+    /// At most `MAX_BATCH_SIZE` (20) entries are accepted.
+    ///
+    /// # Arguments
+    /// * `user` - The address of the user depositing collateral
+    /// * `deposits` - List of `(asset, amount)` pairs (None asset for native XLM)
+    ///
+    /// # Returns
+    /// The user's collateral balance after each entry, in input order
+    pub fn deposit_collateral_batch(
+        env: Env,
+        user: Address,
+        deposits: Vec<(Option<Address>, i128)>,
+    ) -> Result<Vec<i128>, soroban_sdk::Error> {
+        let _guard = reentrancy::ReentrancyGuard::enter(&env)
+            .ok_or(soroban_sdk::Error::from(crate::deposit::DepositError::Reentrancy))?;
+        deposit::deposit_collateral_batch(&env, user, deposits)
+    }
+
+    /// Withdraw collateral from the protocol
+    ///
+    /// Share-backed deposits are redeemed at the current exchange rate, so the
+    /// withdrawable amount includes accrued supply interest.
+    ///
+    /// # Arguments
+    /// * `user` - The address of the user withdrawing collateral
+    /// * `asset` - The address of the asset contract to withdraw (None for native XLM)
+    /// * `amount` - The amount of underlying to withdraw
+    ///
+    /// # Returns
+    /// Returns the net amount received after the asset's withdrawal fee
+    pub fn withdraw_collateral(
+        env: Env,
+        user: Address,
+        asset: Option<Address>,
+        amount: i128,
+    ) -> Result<i128, crate::withdraw::WithdrawError> {
+        let _guard = reentrancy::ReentrancyGuard::enter(&env)
+            .ok_or(crate::withdraw::WithdrawError::Reentrancy)?;
+        withdraw::withdraw_collateral(&env, user, asset, amount)
+    }
+
+    /// Withdraw collateral from `user`'s position and send the tokens to `to`
+    ///
+    /// `user` must authorize. Returns the net amount `to` received.
+    pub fn withdraw_asset_to(
+        env: Env,
+        user: Address,
+        asset: Option<Address>,
+        amount: i128,
+        to: Address,
+    ) -> Result<i128, crate::withdraw::WithdrawError> {
+        let _guard = reentrancy::ReentrancyGuard::enter(&env)
+            .ok_or(crate::withdraw::WithdrawError::Reentrancy)?;
+        withdraw::withdraw_collateral_to(&env, user, asset, amount, to)
+    }
+
+    /// Withdraw collateral and return the net amount with its receipt id
+    ///
+    /// Receipt ids increase strictly across all users and assets; see
+    /// `get_withdrawal_receipt`.
+    pub fn withdraw_with_receipt(
+        env: Env,
+        user: Address,
+        asset: Option<Address>,
+        amount: i128,
+    ) -> Result<crate::withdraw::WithdrawalResult, crate::withdraw::WithdrawError> {
+        let _guard = reentrancy::ReentrancyGuard::enter(&env)
+            .ok_or(crate::withdraw::WithdrawError::Reentrancy)?;
+        withdraw::withdraw_with_receipt(&env, user, asset, amount)
+    }
+
+    /// Get a withdrawal receipt by id, if it is still retained
+    pub fn get_withdrawal_receipt(env: Env, id: u64) -> Option<crate::withdraw::WithdrawalReceipt> {
+        withdraw::get_withdrawal_receipt(&env, id)
+    }
+
+    /// Get the number of withdrawal receipts kept in storage
+    pub fn get_withdrawal_receipt_capacity(env: Env) -> u32 {
+        withdraw::get_withdrawal_receipt_capacity(&env)
+    }
+
+    /// Set the number of withdrawal receipts kept in storage (admin only)
+    pub fn set_withdrawal_receipt_capacity(
+        env: Env,
+        caller: Address,
+        capacity: u32,
+    ) -> Result<(), crate::withdraw::WithdrawError> {
+        let _guard = reentrancy::ReentrancyGuard::enter(&env)
+            .ok_or(crate::withdraw::WithdrawError::Reentrancy)?;
+        withdraw::set_withdrawal_receipt_capacity(&env, caller, capacity)
+    }
+
+    /// Get the exchange rate of an asset's deposit shares
+    ///
+    /// # Returns
+    /// Underlying per share, scaled by 1e18 (1e18 = 1:1)
+    pub fn get_exchange_rate(env: Env, asset: Option<Address>) -> i128 {
+        deposit::get_exchange_rate(&env, asset)
+    }
+
+    /// Get the deposit shares held by a user for an asset
+    pub fn get_user_shares(env: Env, user: Address, asset: Option<Address>) -> i128 {
+        deposit::get_user_shares(&env, user, asset)
+    }
+
+    /// Get the amount of an asset a user has deposited, including accrued supply interest
+    pub fn get_user_deposit(env: Env, user: Address, asset: Option<Address>) -> i128 {
+        deposit::get_user_deposit_balance(&env, &user, &asset)
+    }
+
+    /// Get every asset a user has a deposit in, with the redeemable amount for each
+    ///
+    /// Assets that have been fully withdrawn are not listed.
+    pub fn get_user_deposits(env: Env, user: Address) -> Vec<(Option<Address>, i128)> {
+        deposit::get_user_deposits(&env, &user)
+    }
+
+    /// Enable or disable a deposited asset as collateral
+    ///
+    /// Disabled deposits still earn the supply rate but do not count toward
+    /// borrowing power and cannot be seized by liquidators. Disabling is rejected
+    /// if it would leave the position below the minimum collateral ratio.
+    pub fn set_use_as_collateral(
+        env: Env,
+        user: Address,
+        asset: Option<Address>,
+        enabled: bool,
+    ) -> Result<(), crate::deposit::DepositError> {
+        let _guard = reentrancy::ReentrancyGuard::enter(&env)
+            .ok_or(crate::deposit::DepositError::Reentrancy)?;
+        deposit::set_use_as_collateral(&env, user, asset, enabled)
+    }
+
+    /// Deposit collateral on behalf of a referrer
+    ///
+    /// The first referrer recorded for a user is kept; the credited amount is
+    /// added to the referrer's cumulative referral volume.
+    pub fn deposit_collateral_with_referral(
+        env: Env,
+        user: Address,
+        asset: Option<Address>,
+        amount: i128,
+        referrer: Option<Address>,
+    ) -> Result<i128, crate::deposit::DepositError> {
+        let _guard = reentrancy::ReentrancyGuard::enter(&env)
+            .ok_or(crate::deposit::DepositError::Reentrancy)?;
+        deposit::deposit_collateral_with_referral(&env, user, asset, amount, referrer)
+    }
+
+    /// Deposit the user's whole balance of an asset
+    ///
+    /// For native XLM `max_amount` is required; the admin-set safety buffer is
+    /// always left in the user's account. Returns the amount deposited.
+    pub fn deposit_all(
+        env: Env,
+        user: Address,
+        asset: Option<Address>,
+        max_amount: Option<i128>,
+    ) -> Result<i128, crate::deposit::DepositError> {
+        let _guard = reentrancy::ReentrancyGuard::enter(&env)
+            .ok_or(crate::deposit::DepositError::Reentrancy)?;
+        deposit::deposit_all(&env, user, asset, max_amount)
+    }
+
+    /// Set the XLM safety buffer kept back by `deposit_all` (admin only)
+    pub fn set_native_deposit_buffer(
+        env: Env,
+        caller: Address,
+        buffer: i128,
+    ) -> Result<(), crate::deposit::DepositError> {
+        let _guard = reentrancy::ReentrancyGuard::enter(&env)
+            .ok_or(crate::deposit::DepositError::Reentrancy)?;
+        deposit::set_native_deposit_buffer(&env, caller, buffer)
+    }
+
+    /// Set the share of reserve income paid to referrers, in basis points (admin only)
+    pub fn set_referral_fee_bps(
+        env: Env,
+        caller: Address,
+        fee_bps: i128,
+    ) -> Result<(), crate::deposit::DepositError> {
+        let _guard = reentrancy::ReentrancyGuard::enter(&env)
+            .ok_or(crate::deposit::DepositError::Reentrancy)?;
+        deposit::set_referral_fee_bps(&env, caller, fee_bps)
+    }
+
+    /// Get the cumulative deposit volume referred by an address
+    pub fn get_referral_volume(env: Env, referrer: Address) -> i128 {
+        deposit::get_referral_volume(&env, &referrer)
+    }
+
+    /// Get the unclaimed referral rewards of an address, per asset
+    pub fn get_referral_rewards(env: Env, referrer: Address) -> Map<Option<Address>, i128> {
+        deposit::get_referral_rewards(&env, &referrer)
+    }
+
+    /// Claim all accrued referral rewards
+    ///
+    /// Returns the claimed `(asset, amount)` pairs.
+    pub fn claim_referral_rewards(
+        env: Env,
+        referrer: Address,
+    ) -> Result<Vec<(Option<Address>, i128)>, crate::deposit::DepositError> {
+        let _guard = reentrancy::ReentrancyGuard::enter(&env)
+            .ok_or(crate::deposit::DepositError::Reentrancy)?;
+        deposit::claim_referral_rewards(&env, referrer)
+    }
+
+    /// Configure TTL bumping of persistent position entries (admin only)
+    ///
+    /// Entries whose remaining TTL falls below `threshold` ledgers are extended
+    /// to `extend_to` ledgers whenever they are touched.
+    pub fn set_storage_ttl_config(
+        env: Env,
+        admin: Address,
+        threshold: u32,
+        extend_to: u32,
+    ) -> Result<(), crate::deposit::DepositError> {
+        let _guard = reentrancy::ReentrancyGuard::enter(&env)
+            .ok_or(crate::deposit::DepositError::Reentrancy)?;
+        ttl::set_storage_ttl_config(&env, admin, threshold, extend_to)
+    }
+
+    /// Get the TTL bump configuration
+    pub fn get_storage_ttl_config(env: Env) -> crate::ttl::TtlConfig {
+        ttl::get_ttl_config(&env)
+    }
+
+    /// Refresh the TTL of a user's position entries. Callable by anyone.
+    pub fn touch_position(env: Env, user: Address) {
+        let _guard = reentrancy::ReentrancyGuard::enter(&env).unwrap_or_else(|| {
+            soroban_sdk::panic_with_error!(&env, crate::deposit::DepositError::Reentrancy)
+        });
+        ttl::touch_position(&env, &user)
+    }
+
+    /// Add a user to an asset's deposit allowlist (admin only)
+    pub fn add_to_deposit_allowlist(
+        env: Env,
+        admin: Address,
+        asset: Address,
+        user: Address,
+    ) -> Result<(), crate::deposit::DepositError> {
+        let _guard = reentrancy::ReentrancyGuard::enter(&env)
+            .ok_or(crate::deposit::DepositError::Reentrancy)?;
+        deposit::set_deposit_allowlisted(&env, admin, asset, user, true)
+    }
+
+    /// Remove a user from an asset's deposit allowlist (admin only)
+    ///
+    /// Existing balances remain withdrawable.
+    pub fn remove_from_deposit_allowlist(
+        env: Env,
+        admin: Address,
+        asset: Address,
+        user: Address,
+    ) -> Result<(), crate::deposit::DepositError> {
+        let _guard = reentrancy::ReentrancyGuard::enter(&env)
+            .ok_or(crate::deposit::DepositError::Reentrancy)?;
+        deposit::set_deposit_allowlisted(&env, admin, asset, user, false)
+    }
+
+    /// Check whether a user may deposit an allowlist-gated asset
+    pub fn is_deposit_allowlisted(env: Env, asset: Address, user: Address) -> bool {
+        deposit::is_deposit_allowlisted(&env, &asset, &user)
+    }
+
     /// Set native asset address (admin only). Required before using asset = None for deposit/borrow/repay.
     pub fn set_native_asset_address(
         env: Env,
         caller: Address,
         native_asset: Address,
     ) -> Result<(), deposit::DepositError> {
+        let _guard = reentrancy::ReentrancyGuard::enter(&env)
+            .ok_or(crate::deposit::DepositError::Reentrancy)?;
         deposit::set_native_asset_address(&env, caller, native_asset)
     }
 
@@ -296,6 +583,10 @@ impl HelloContract {
     /// * `liquidation_threshold` - Optional new liquidation threshold (in basis points)
     /// * `close_factor` - Optional new close factor (in basis points)
     /// * `liquidation_incentive` - Optional new liquidation incentive (in basis points)
+    /// * `liquidation_cooldown_ledgers` - Optional new number of ledgers between
+    ///   liquidations of the same borrower (0 disables the cooldown)
+    /// * `liquidation_protocol_fee_bps` - Optional new share of the liquidation
+    ///   incentive credited to the protocol reserve (in basis points, at most 3000)
     ///
     /// # Returns
     /// Returns Ok(()) on success
@@ -306,7 +597,11 @@ impl HelloContract {
         liquidation_threshold: Option<i128>,
         close_factor: Option<i128>,
         liquidation_incentive: Option<i128>,
+        liquidation_cooldown_ledgers: Option<u32>,
+        liquidation_protocol_fee_bps: Option<i128>,
     ) -> Result<(), RiskManagementError> {
+        let _guard = reentrancy::ReentrancyGuard::enter(&env)
+            .ok_or(RiskManagementError::Reentrancy)?;
         require_admin(&env, &caller)?;
         check_emergency_pause(&env)?;
         risk_params::set_risk_params(
@@ -322,171 +617,1258 @@ impl HelloContract {
             RiskParamsError::InvalidCloseFactor => RiskManagementError::InvalidCloseFactor,
             RiskParamsError::InvalidLiquidationIncentive => RiskManagementError::InvalidLiquidationIncentive,
             _ => RiskManagementError::InvalidParameter,
-        })
+        })?;
+        if let Some(ledgers) = liquidation_cooldown_ledgers {
+            risk_management::set_liquidation_cooldown_ledgers(&env, ledgers)?;
+        }
+        if let Some(fee_bps) = liquidation_protocol_fee_bps {
+            risk_management::set_liquidation_protocol_fee_bps(&env, fee_bps)?;
+        }
+        Ok(())
+    }
+
+    /// Borrow assets from the protocol
+    pub fn borrow_asset(
+        env: Env,
+        user: Address,
+        asset: Option<Address>,
+        amount: i128,
+    ) -> Result<i128, crate::borrow::BorrowError> {
+        let _guard = reentrancy::ReentrancyGuard::enter(&env)
+            .ok_or(crate::borrow::BorrowError::Reentrancy)?;
+        borrow::borrow_asset(&env, user, asset, amount)
+    }
+
+    /// Borrow several assets in one all-or-nothing call
+    ///
+    /// The collateral ratio is checked against the combined debt after the whole
+    /// batch. A failing item returns contract error `10000 + index * 100 + BorrowError`.
+    /// At most `MAX_BATCH_SIZE` (20) items are accepted.
+    /// Returns the user's total debt after each `(asset, amount)` item.
+    pub fn borrow_batch(
+        env: Env,
+        user: Address,
+        requests: Vec<(Option<Address>, i128)>,
+    ) -> Result<Vec<i128>, soroban_sdk::Error> {
+        let _guard = reentrancy::ReentrancyGuard::enter(&env)
+            .ok_or(soroban_sdk::Error::from(crate::borrow::BorrowError::Reentrancy))?;
+        borrow::borrow_batch(&env, user, requests)
+    }
+
+    /// Borrow assets, aborting past `deadline` or if the resulting borrow rate
+    /// exceeds `max_rate_bps`
+    pub fn borrow_asset_with_limits(
+        env: Env,
+        user: Address,
+        asset: Option<Address>,
+        amount: i128,
+        max_rate_bps: i128,
+        deadline: u64,
+    ) -> Result<i128, crate::borrow::BorrowError> {
+        let _guard = reentrancy::ReentrancyGuard::enter(&env)
+            .ok_or(crate::borrow::BorrowError::Reentrancy)?;
+        borrow::borrow_asset_with_limits(&env, user, asset, amount, max_rate_bps, deadline)
+    }
+
+    /// Borrow assets at a variable rate, or at a stable rate locked at origination
+    pub fn borrow_asset_with_mode(
+        env: Env,
+        user: Address,
+        asset: Option<Address>,
+        amount: i128,
+        rate_mode: borrow::RateMode,
+    ) -> Result<i128, crate::borrow::BorrowError> {
+        let _guard = reentrancy::ReentrancyGuard::enter(&env)
+            .ok_or(crate::borrow::BorrowError::Reentrancy)?;
+        borrow::borrow_asset_with_mode(&env, user, asset, amount, rate_mode)
+    }
+
+    /// Switch a user's debt in an asset between the variable and stable rate
+    ///
+    /// # Returns
+    /// The new rate mode
+    pub fn swap_rate_mode(
+        env: Env,
+        user: Address,
+        asset: Option<Address>,
+    ) -> Result<borrow::RateMode, crate::borrow::BorrowError> {
+        let _guard = reentrancy::ReentrancyGuard::enter(&env)
+            .ok_or(crate::borrow::BorrowError::Reentrancy)?;
+        borrow::swap_rate_mode(&env, user, asset)
+    }
+
+    /// Re-lock a stable borrow whose rate has drifted too far below the variable
+    /// rate (admin only)
+    ///
+    /// # Returns
+    /// The newly locked rate in basis points
+    pub fn rebalance_stable_rate(
+        env: Env,
+        caller: Address,
+        user: Address,
+        asset: Option<Address>,
+    ) -> Result<i128, crate::borrow::BorrowError> {
+        let _guard = reentrancy::ReentrancyGuard::enter(&env)
+            .ok_or(crate::borrow::BorrowError::Reentrancy)?;
+        borrow::rebalance_stable_rate(&env, caller, user, asset)
+    }
+
+    /// Let `delegatee` borrow up to `amount` of `asset` against `delegator`'s
+    /// collateral (0 revokes)
+    pub fn approve_credit_delegation(
+        env: Env,
+        delegator: Address,
+        delegatee: Address,
+        asset: Option<Address>,
+        amount: i128,
+    ) -> Result<(), crate::borrow::BorrowError> {
+        let _guard = reentrancy::ReentrancyGuard::enter(&env)
+            .ok_or(crate::borrow::BorrowError::Reentrancy)?;
+        borrow::approve_credit_delegation(&env, delegator, delegatee, asset, amount)
+    }
+
+    /// Get the borrow index of an asset compounded to the current ledger time
+    ///
+    /// # Returns
+    /// Growth of one unit of debt, scaled by 1e18 (1e18 = no interest accrued)
+    pub fn get_borrow_index(env: Env, asset: Option<Address>) -> i128 {
+        borrow::get_borrow_index(&env, &asset)
+    }
+
+    /// Get what a user owes in an asset right now, including accrued interest
+    pub fn get_user_debt(env: Env, user: Address, asset: Option<Address>) -> i128 {
+        borrow::get_user_debt(&env, &user, &asset)
+    }
+
+    /// Get what a user owes in each borrowed asset right now, including accrued interest
+    pub fn get_user_debts(env: Env, user: Address) -> Vec<(Option<Address>, i128)> {
+        borrow::get_user_debts(&env, &user)
+    }
+
+    /// Check whether `borrow_asset` would succeed, and the resulting health factor,
+    /// borrow rate and total debt, without borrowing
+    pub fn simulate_borrow(
+        env: Env,
+        user: Address,
+        asset: Option<Address>,
+        amount: i128,
+    ) -> borrow::BorrowSimulation {
+        borrow::simulate_borrow(&env, user, asset, amount)
+    }
+
+    /// Get a user's debt record in an asset, including its rate mode
+    pub fn get_user_borrow(
+        env: Env,
+        user: Address,
+        asset: Option<Address>,
+    ) -> Option<borrow::UserBorrow> {
+        borrow::get_user_borrow(&env, &user, &asset)
+    }
+
+    /// Get the principal borrowed against an isolated collateral asset
+    pub fn get_isolated_debt(env: Env, asset: Address) -> i128 {
+        isolation::get_isolated_debt(&env, &asset)
+    }
+
+    /// Get the remaining credit delegation allowance for a delegator, delegatee and asset
+    pub fn get_credit_delegation(
+        env: Env,
+        delegator: Address,
+        delegatee: Address,
+        asset: Option<Address>,
+    ) -> i128 {
+        borrow::get_credit_delegation(&env, &delegator, &delegatee, &asset)
+    }
+
+    /// Borrow against a delegator's collateral; the debt is booked on the delegator
+    pub fn borrow_on_behalf(
+        env: Env,
+        delegatee: Address,
+        delegator: Address,
+        asset: Option<Address>,
+        amount: i128,
+    ) -> Result<i128, crate::borrow::BorrowError> {
+        let _guard = reentrancy::ReentrancyGuard::enter(&env)
+            .ok_or(crate::borrow::BorrowError::Reentrancy)?;
+        borrow::borrow_on_behalf(&env, delegatee, delegator, asset, amount)
+    }
+
+    /// Repay borrowed assets
+    ///
+    /// Amounts above the current debt, such as `i128::MAX`, repay it in full and
+    /// only transfer what is owed.
+    pub fn repay_debt(
+        env: Env,
+        user: Address,
+        asset: Option<Address>,
+        amount: i128,
+    ) -> Result<(i128, i128, i128), crate::repay::RepayError> {
+        let _guard = reentrancy::ReentrancyGuard::enter(&env)
+            .ok_or(crate::repay::RepayError::Reentrancy)?;
+        repay::repay_debt(&env, user, asset, amount)
+    }
+
+    /// Repay another user's debt from the payer's balance
+    ///
+    /// # Returns
+    /// Returns (remaining_debt, interest_paid, principal_paid) of the borrower's debt
+    pub fn repay_on_behalf(
+        env: Env,
+        payer: Address,
+        borrower: Address,
+        asset: Option<Address>,
+        amount: i128,
+    ) -> Result<(i128, i128, i128), crate::repay::RepayError> {
+        let _guard = reentrancy::ReentrancyGuard::enter(&env)
+            .ok_or(crate::repay::RepayError::Reentrancy)?;
+        repay::repay_on_behalf(&env, payer, borrower, asset, amount)
+    }
+
+    /// Repay debt and, with `deposit_surplus`, supply the rest of `amount` as collateral
+    ///
+    /// # Returns
+    /// Returns (remaining_debt, interest_paid, principal_paid, deposited)
+    pub fn repay_and_deposit(
+        env: Env,
+        user: Address,
+        asset: Option<Address>,
+        amount: i128,
+        deposit_surplus: bool,
+    ) -> Result<(i128, i128, i128, i128), crate::repay::RepayError> {
+        let _guard = reentrancy::ReentrancyGuard::enter(&env)
+            .ok_or(crate::repay::RepayError::Reentrancy)?;
+        repay::repay_and_deposit(&env, user, asset, amount, deposit_surplus)
+    }
+
+    /// Amount that repays a user's debt in an asset in full, now and optionally
+    /// projected to a future ledger time at the current borrow rate
+    pub fn get_repay_quote(
+        env: Env,
+        user: Address,
+        asset: Option<Address>,
+        at_timestamp: Option<u64>,
+    ) -> crate::repay::RepayQuote {
+        repay::get_repay_quote(&env, &user, &asset, at_timestamp)
+    }
+
+    /// Repay debt out of the user's own collateral deposit
+    ///
+    /// The collateral must be the debt asset. Fails if more than
+    /// `max_collateral_in` would be used.
+    ///
+    /// # Returns
+    /// Returns (remaining_debt, collateral_used)
+    pub fn repay_with_collateral(
+        env: Env,
+        user: Address,
+        debt_asset: Option<Address>,
+        collateral_asset: Option<Address>,
+        repay_amount: i128,
+        max_collateral_in: i128,
+    ) -> Result<(i128, i128), crate::repay::RepayError> {
+        let _guard = reentrancy::ReentrancyGuard::enter(&env)
+            .ok_or(crate::repay::RepayError::Reentrancy)?;
+        repay::repay_with_collateral(
+            &env,
+            user,
+            debt_asset,
+            collateral_asset,
+            repay_amount,
+            max_collateral_in,
+        )
+    }
+
+    /// Liquidate an undercollateralized position
+    ///
+    /// # Arguments
+    /// * `liquidator` - The address repaying the debt
+    /// * `borrower` - The address of the position being liquidated
+    /// * `debt_asset` - The debt asset to repay (None for native XLM)
+    /// * `collateral_asset` - The collateral asset to receive (None for native XLM)
+    /// * `debt_amount` - The amount of debt to liquidate
+    /// * `auto_collateral` - With no `collateral_asset`, seize the borrower's
+    ///   collateral with the highest liquidity score instead of native XLM
+    ///
+    /// # Returns
+    /// Returns (debt_liquidated, collateral_seized, incentive_amount, protocol_fee);
+    /// the liquidator receives `collateral_seized - protocol_fee`
+    ///
+    /// # Errors
+    /// Every failure is returned as a [`crate::liquidate::LiquidationError`],
+    /// so `try_liquidate` callers can branch on the code.
+    pub fn liquidate(
+        env: Env,
+        liquidator: Address,
+        borrower: Address,
+        debt_asset: Option<Address>,
+        collateral_asset: Option<Address>,
+        debt_amount: i128,
+        auto_collateral: bool,
+    ) -> Result<(i128, i128, i128, i128), crate::liquidate::LiquidationError> {
+        let _guard = reentrancy::ReentrancyGuard::enter(&env)
+            .ok_or(crate::liquidate::LiquidationError::Reentrancy)?;
+        liquidate::liquidate(
+            &env,
+            liquidator,
+            borrower,
+            debt_asset,
+            collateral_asset,
+            debt_amount,
+            auto_collateral,
+        )
+    }
+
+    /// Liquidate a position while liquidations are paused (admin only)
+    ///
+    /// Ignores the `pause_liquidate` switch, the emergency pause and the
+    /// liquidator allowlist; the admin repays the debt and receives the collateral.
+    pub fn force_liquidate(
+        env: Env,
+        admin: Address,
+        borrower: Address,
+        debt_asset: Option<Address>,
+        collateral_asset: Option<Address>,
+        debt_amount: i128,
+    ) -> Result<(i128, i128, i128, i128), crate::liquidate::LiquidationError> {
+        let _guard = reentrancy::ReentrancyGuard::enter(&env)
+            .ok_or(crate::liquidate::LiquidationError::Reentrancy)?;
+        liquidate::force_liquidate(
+            &env,
+            admin,
+            borrower,
+            debt_asset,
+            collateral_asset,
+            debt_amount,
+        )
+    }
+
+    /// Liquidate and swap the seized collateral into the debt asset through
+    /// `amm_contract`, paying the liquidator at least `min_out` of the debt asset
+    ///
+    /// `amm_contract` must be the AMM registered with `set_liquidation_amm`.
+    ///
+    /// # Returns
+    /// Returns (debt_liquidated, collateral_seized, incentive_amount, protocol_fee,
+    /// amount_out)
+    #[allow(clippy::too_many_arguments)]
+    pub fn liquidate_and_swap(
+        env: Env,
+        liquidator: Address,
+        borrower: Address,
+        debt_asset: Option<Address>,
+        collateral_asset: Option<Address>,
+        debt_amount: i128,
+        amm_contract: Address,
+        min_out: i128,
+    ) -> Result<(i128, i128, i128, i128, i128), crate::liquidate::LiquidationError> {
+        let _guard = reentrancy::ReentrancyGuard::enter(&env)
+            .ok_or(crate::liquidate::LiquidationError::Reentrancy)?;
+        liquidate::liquidate_and_swap(
+            &env,
+            liquidator,
+            borrower,
+            debt_asset,
+            collateral_asset,
+            debt_amount,
+            amm_contract,
+            min_out,
+        )
+    }
+
+    /// Check whether `liquidate` would succeed, and the debt repaid, collateral
+    /// seized, incentive and protocol fee, without liquidating
+    pub fn simulate_liquidation(
+        env: Env,
+        liquidator: Address,
+        borrower: Address,
+        debt_asset: Option<Address>,
+        collateral_asset: Option<Address>,
+        debt_amount: i128,
+    ) -> liquidate::LiquidationSimulation {
+        liquidate::simulate_liquidation(
+            &env,
+            liquidator,
+            borrower,
+            debt_asset,
+            collateral_asset,
+            debt_amount,
+        )
+    }
+
+    /// Liquidate a position against several collateral assets in one call
+    ///
+    /// Seizes from the assets in `collateral_order` in turn until the repaid
+    /// debt plus incentive is covered.
+    ///
+    /// # Returns
+    /// Returns (debt_liquidated, collateral seized per asset)
+    pub fn liquidate_multi(
+        env: Env,
+        liquidator: Address,
+        borrower: Address,
+        debt_asset: Option<Address>,
+        debt_amount: i128,
+        collateral_order: Vec<Option<Address>>,
+    ) -> Result<(i128, Vec<(Option<Address>, i128)>), crate::liquidate::LiquidationError> {
+        let _guard = reentrancy::ReentrancyGuard::enter(&env)
+            .ok_or(crate::liquidate::LiquidationError::Reentrancy)?;
+        liquidate::liquidate_multi(
+            &env,
+            liquidator,
+            borrower,
+            debt_asset,
+            debt_amount,
+            collateral_order,
+        )
+    }
+
+    /// Deleverage by repaying debt out of the caller's own collateral
+    ///
+    /// The collateral must be the debt asset. Allowed on healthy positions;
+    /// charges the self-liquidation incentive.
+    ///
+    /// # Returns
+    /// Returns (debt_repaid, collateral_used, incentive_amount)
+    pub fn self_liquidate(
+        env: Env,
+        user: Address,
+        debt_asset: Option<Address>,
+        collateral_asset: Option<Address>,
+        debt_amount: i128,
+    ) -> Result<(i128, i128, i128), crate::liquidate::LiquidationError> {
+        let _guard = reentrancy::ReentrancyGuard::enter(&env)
+            .ok_or(crate::liquidate::LiquidationError::Reentrancy)?;
+        liquidate::self_liquidate(&env, user, debt_asset, collateral_asset, debt_amount)
+    }
+
+    /// Get a user's health factor, scaled by 10_000
+    ///
+    /// The position can be liquidated exactly when this is below 10_000.
+    /// Returns `i128::MAX` when the user has no debt.
+    pub fn get_health_factor(
+        env: Env,
+        user: Address,
+    ) -> Result<i128, crate::liquidate::LiquidationError> {
+        liquidate::get_health_factor(&env, &user)
+    }
+
+    /// Whether a borrower can be liquidated, with their health factor
+    ///
+    /// Uses the same check as `liquidate`, for liquidation bots.
+    /// Returns (liquidatable, health_factor).
+    pub fn can_liquidate(
+        env: Env,
+        borrower: Address,
+    ) -> Result<(bool, i128), crate::liquidate::LiquidationError> {
+        liquidate::can_liquidate(&env, &borrower)
+    }
+
+    /// Liquidatable borrowers with their health factors, one page of the borrower index
+    ///
+    /// Examines at most `limit` borrowers (up to 50) from `offset`, so a page can
+    /// hold fewer entries than `limit`.
+    pub fn get_liquidatable_positions(env: Env, limit: u32, offset: u32) -> Vec<(Address, i128)> {
+        liquidate::get_liquidatable_positions(&env, limit, offset)
+    }
+
+    /// Record or clear a borrower's liquidation threshold breach
+    ///
+    /// Starts the liquidation grace window when the account is below the
+    /// threshold and clears it once recovered. Callable by anyone.
+    /// Returns when the current breach was recorded, if any.
+    pub fn sync_liquidation_breach(
+        env: Env,
+        borrower: Address,
+    ) -> Result<Option<u64>, crate::liquidate::LiquidationError> {
+        liquidate::sync_liquidation_breach(&env, &borrower)
+    }
+
+    /// When a borrower's current liquidation threshold breach was recorded, if any
+    pub fn get_liquidation_breach(env: Env, borrower: Address) -> Option<u64> {
+        risk_management::get_liquidation_breach(&env, &borrower)
+    }
+
+    /// Collateral a liquidator may seize from a borrower, as (asset, amount) pairs
+    pub fn get_seizable_collateral(env: Env, borrower: Address) -> Vec<(Option<Address>, i128)> {
+        liquidate::get_seizable_collateral(&env, &borrower)
+    }
+
+    /// Collateral assets `liquidate` seizes in auto mode, highest liquidity score first
+    pub fn get_auto_collateral_order(env: Env, borrower: Address) -> Vec<Option<Address>> {
+        liquidate::get_auto_collateral_order(&env, &borrower)
+    }
+
+    /// Get a stored pre-liquidation position snapshot by id, if still retained
+    pub fn get_liquidation_snapshot(env: Env, id: u64) -> Option<liquidate::LiquidationSnapshot> {
+        liquidate::get_liquidation_snapshot(&env, id)
+    }
+
+    /// Get the number of liquidation snapshots recorded so far
+    pub fn get_liquidation_count(env: Env) -> u64 {
+        liquidate::get_liquidation_count(&env)
+    }
+
+    /// Get a user's borrowing headroom and shortfall in base-currency terms
+    ///
+    /// # Returns
+    /// Returns (liquidity, shortfall); a positive shortfall means the position
+    /// can be liquidated
+    pub fn get_account_liquidity(
+        env: Env,
+        user: Address,
+    ) -> Result<(i128, i128), crate::liquidate::LiquidationError> {
+        liquidate::get_account_liquidity(&env, &user)
+    }
+
+    /// Get current risk configuration
+    ///
+    /// # Returns
+    /// Returns the current risk configuration or None if not initialized
+    pub fn get_risk_config(env: Env) -> Option<RiskConfig> {
+        risk_management::get_risk_config(&env)
+    }
+
+    /// Get minimum collateral ratio
+    ///
+    /// # Returns
+    /// Returns the minimum collateral ratio in basis points
+    pub fn get_min_collateral_ratio(env: Env) -> Result<i128, RiskManagementError> {
+        risk_params::get_min_collateral_ratio(&env).map_err(|_| RiskManagementError::InvalidParameter)
+    }
+
+    /// Get liquidation threshold
+    ///
+    /// # Returns
+    /// Returns the liquidation threshold in basis points
+    pub fn get_liquidation_threshold(env: Env) -> Result<i128, RiskManagementError> {
+        risk_params::get_liquidation_threshold(&env).map_err(|_| RiskManagementError::InvalidParameter)
+    }
+
+    /// Get close factor
+    ///
+    /// # Returns
+    /// Returns the close factor in basis points
+    pub fn get_close_factor(env: Env) -> Result<i128, RiskManagementError> {
+        risk_params::get_close_factor(&env).map_err(|_| RiskManagementError::InvalidParameter)
+    }
+
+    /// Set the debt value below which a liquidation may repay the whole debt (admin only)
+    ///
+    /// Zero disables the exemption from the close factor.
+    pub fn set_close_factor_dust_threshold(
+        env: Env,
+        caller: Address,
+        threshold: i128,
+    ) -> Result<(), RiskManagementError> {
+        require_admin(&env, &caller)?;
+        risk_params::set_close_factor_dust_threshold(&env, threshold)
+            .map_err(|_| RiskManagementError::InvalidParameter)
+    }
+
+    /// Get the debt value below which a liquidation may repay the whole debt
+    pub fn get_close_factor_dust_threshold(env: Env) -> i128 {
+        risk_params::get_close_factor_dust_threshold(&env)
+    }
+
+    /// Set the incentive charged on self-liquidations (admin only)
+    ///
+    /// In basis points, at most the liquidation incentive.
+    pub fn set_self_liquidation_incentive(
+        env: Env,
+        caller: Address,
+        incentive_bps: i128,
+    ) -> Result<(), RiskManagementError> {
+        require_admin(&env, &caller)?;
+        risk_params::set_self_liquidation_incentive(&env, incentive_bps)
+            .map_err(|_| RiskManagementError::InvalidParameter)
+    }
+
+    /// Get the incentive charged on self-liquidations
+    pub fn get_self_liquidation_incentive(env: Env) -> i128 {
+        risk_params::get_self_liquidation_incentive(&env)
+    }
+
+    /// Get liquidation incentive
+    ///
+    /// # Returns
+    /// Returns the liquidation incentive in basis points
+    pub fn get_liquidation_incentive(env: Env) -> Result<i128, RiskManagementError> {
+        risk_params::get_liquidation_incentive(&env).map_err(|_| RiskManagementError::InvalidParameter)
+    }
+
+    /// Get current borrow rate of the native asset (in basis points)
+    pub fn get_borrow_rate(env: Env) -> i128 {
+        interest_rate::calculate_borrow_rate(&env, &None).unwrap_or(0)
+    }
+
+    /// Get current supply rate of the native asset (in basis points)
+    pub fn get_supply_rate(env: Env) -> i128 {
+        interest_rate::calculate_supply_rate(&env, &None).unwrap_or(0)
+    }
+
+    /// Get the current borrow rate of an asset compounded every second into an
+    /// annual percentage yield (in basis points)
+    pub fn get_borrow_apy(env: Env, asset: Option<Address>) -> i128 {
+        interest_rate::get_borrow_apy(&env, &asset).unwrap_or(0)
+    }
+
+    /// Get the current supply rate of an asset compounded every second into an
+    /// annual percentage yield (in basis points)
+    pub fn get_supply_apy(env: Env, asset: Option<Address>) -> i128 {
+        interest_rate::get_supply_apy(&env, &asset).unwrap_or(0)
+    }
+
+    /// Get current utilization of the native asset (in basis points)
+    pub fn get_utilization(env: Env) -> i128 {
+        get_current_utilization(&env).unwrap_or(0)
+    }
+
+    /// Get the interest rate model for an asset, including the last emergency
+    /// adjustment set, its expiry and the time of the last config change
+    pub fn get_interest_rate_config(
+        env: Env,
+        asset: Option<Address>,
+    ) -> interest_rate::InterestRateConfig {
+        interest_rate::get_asset_interest_rate_config(&env, &asset)
+    }
+
+    /// Get the borrow and supply rates the model would charge at a hypothetical
+    /// utilization (in basis points)
+    ///
+    /// # Returns
+    /// (borrow_rate, supply_rate) in basis points
+    pub fn get_rate_at_utilization(
+        env: Env,
+        asset: Option<Address>,
+        utilization_bps: i128,
+    ) -> (i128, i128) {
+        interest_rate::get_rate_at_utilization(&env, &asset, utilization_bps).unwrap_or((0, 0))
+    }
+
+    /// Get the curve an asset's rates follow
+    pub fn get_rate_strategy(env: Env, asset: Option<Address>) -> interest_rate::RateStrategy {
+        interest_rate::get_rate_strategy(&env, &asset)
+    }
+
+    /// Switch the curve an asset's rates follow (admin only)
+    ///
+    /// Interest is accrued at the old strategy up to now before the switch.
+    pub fn set_rate_strategy(
+        env: Env,
+        admin: Address,
+        asset: Option<Address>,
+        strategy: interest_rate::RateStrategy,
+    ) -> Result<(), InterestRateError> {
+        interest_rate::set_rate_strategy(&env, admin, asset, strategy)
+    }
+
+    /// Get the rates recorded for an asset at each accrual, newest first
+    ///
+    /// # Arguments
+    /// * `limit` - Maximum number of entries to return
+    /// * `offset` - Number of newest entries to skip
+    pub fn get_rate_history(
+        env: Env,
+        asset: Option<Address>,
+        limit: u32,
+        offset: u32,
+    ) -> Vec<interest_rate::RateHistoryEntry> {
+        interest_rate::get_rate_history(&env, &asset, limit, offset)
+    }
+
+    /// Set how many rate history entries are kept per asset (admin only)
+    pub fn set_rate_history_capacity(
+        env: Env,
+        admin: Address,
+        capacity: u32,
+    ) -> Result<(), InterestRateError> {
+        interest_rate::set_rate_history_capacity(&env, admin, capacity)
+    }
+
+    /// Get how many rate history entries are kept per asset
+    pub fn get_rate_history_capacity(env: Env) -> u32 {
+        interest_rate::get_rate_history_capacity(&env)
+    }
+
+    /// Get the rate a native stable borrow opened now would lock (in basis points)
+    pub fn get_stable_borrow_rate(env: Env) -> i128 {
+        interest_rate::calculate_stable_borrow_rate(&env, &None).unwrap_or(0)
+    }
+
+    /// Set the stable rate premium and rebalance threshold (admin only)
+    pub fn set_stable_rate_config(
+        env: Env,
+        caller: Address,
+        premium_bps: i128,
+        rebalance_threshold_bps: i128,
+    ) -> Result<(), InterestRateError> {
+        interest_rate::set_stable_rate_config(&env, caller, premium_bps, rebalance_threshold_bps)
+    }
+
+    /// Get the share of repaid interest credited to reserves (in basis points)
+    pub fn get_reserve_factor(env: Env) -> i128 {
+        interest_rate::get_reserve_factor_bps(&env)
+    }
+
+    /// Set the share of borrow interest credited to reserves (admin only)
+    pub fn set_reserve_factor(
+        env: Env,
+        caller: Address,
+        reserve_factor_bps: i128,
+    ) -> Result<(), InterestRateError> {
+        interest_rate::set_reserve_factor(&env, caller, reserve_factor_bps)
+    }
+
+    /// Compound an asset's borrow index and supply pool up to the current ledger
+    ///
+    /// Callable by anyone; every balance-changing operation also accrues first.
+    ///
+    /// # Returns
+    /// The indexes after accrual and the reserve income it credited
+    pub fn accrue_interest(
+        env: Env,
+        asset: Option<Address>,
+    ) -> Result<interest_rate::InterestAccrual, InterestRateError> {
+        let _guard = reentrancy::ReentrancyGuard::enter(&env)
+            .ok_or(InterestRateError::Reentrancy)?;
+        interest_rate::accrue_interest(&env, asset)
+    }
+
+    /// Update interest rate model configuration (admin only)
+    ///
+    /// Phased in at the maximum rate delta per day, if one is set, unless `force`
+    #[allow(clippy::too_many_arguments)]
+    pub fn update_interest_rate_config(
+        env: Env,
+        admin: Address,
+        base_rate: Option<i128>,
+        kink: Option<i128>,
+        multiplier: Option<i128>,
+        jump_multiplier: Option<i128>,
+        rate_floor: Option<i128>,
+        rate_ceiling: Option<i128>,
+        spread: Option<i128>,
+        reserve_factor: Option<i128>,
+        force: bool,
+    ) -> Result<(), InterestRateError> {
+        let _guard = reentrancy::ReentrancyGuard::enter(&env)
+            .ok_or(InterestRateError::Reentrancy)?;
+        update_interest_rate_config(
+            &env,
+            admin,
+            base_rate,
+            kink,
+            multiplier,
+            jump_multiplier,
+            rate_floor,
+            rate_ceiling,
+            spread,
+            reserve_factor,
+            force,
+        )
+    }
+
+    /// Set the largest borrow rate move per day a config update may cause (admin only)
+    pub fn set_max_rate_delta_per_day(
+        env: Env,
+        admin: Address,
+        max_delta_bps_per_day: i128,
+    ) -> Result<(), InterestRateError> {
+        interest_rate::set_max_rate_delta_per_day(&env, admin, max_delta_bps_per_day)
+    }
+
+    /// Get the largest borrow rate move per day a config update may cause (0 = unlimited)
+    pub fn get_max_rate_delta_per_day(env: Env) -> i128 {
+        interest_rate::get_max_rate_delta_per_day(&env)
+    }
+
+    /// Get the rate transition still in progress, if any
+    pub fn get_rate_transition(env: Env) -> Option<interest_rate::RateTransition> {
+        interest_rate::get_rate_transition(&env)
+    }
+
+    /// Check if position can be liquidated
+    ///
+    /// # Arguments
+    /// * `collateral_value` - Total collateral value (in base units)
+    /// * `debt_value` - Total debt value (in base units)
+    ///
+    /// # Returns
+    /// Returns true if position can be liquidated
+    pub fn can_be_liquidated(
+        env: Env,
+        collateral_value: i128,
+        debt_value: i128,
+    ) -> Result<bool, RiskManagementError> {
+        can_be_liquidated(&env, collateral_value, debt_value).map_err(|_| RiskManagementError::InvalidParameter)
+    }
+
+    /// Manual emergency interest rate adjustment for a limited time (admin only)
+    pub fn set_emergency_rate_adjustment(
+        env: Env,
+        admin: Address,
+        adjustment_bps: i128,
+        duration_secs: u64,
+    ) -> Result<(), InterestRateError> {
+        set_emergency_rate_adjustment(&env, admin, adjustment_bps, duration_secs)
+    }
+
+    /// Remove the emergency interest rate adjustment before it expires (admin only)
+    pub fn clear_emergency_rate_adjustment(
+        env: Env,
+        admin: Address,
+    ) -> Result<(), InterestRateError> {
+        interest_rate::clear_emergency_rate_adjustment(&env, admin)
+    }
+
+    /// Get the emergency rate adjustment in effect and its expiry, if any
+    pub fn get_emergency_rate_adjustment(
+        env: Env,
+    ) -> Option<interest_rate::EmergencyRateAdjustment> {
+        interest_rate::get_emergency_rate_adjustment(&env)
+    }
+
+    /// Set the largest emergency adjustment and duration allowed (admin only)
+    pub fn set_emergency_adjustment_limits(
+        env: Env,
+        admin: Address,
+        max_adjustment_bps: i128,
+        max_duration_secs: u64,
+    ) -> Result<(), InterestRateError> {
+        interest_rate::set_emergency_adjustment_limits(
+            &env,
+            admin,
+            max_adjustment_bps,
+            max_duration_secs,
+        )
+    }
+
+    /// Get the emergency adjustment bounds
+    pub fn get_emergency_adjustment_limits(env: Env) -> interest_rate::EmergencyAdjustmentLimits {
+        interest_rate::get_emergency_adjustment_limits(&env)
+    }
+
+    /// Set the utilization-spike circuit breaker (admin only)
+    ///
+    /// Once an accrual sees utilization at or above `trigger_utilization_bps`,
+    /// `bonus_bps` is added to the borrow rate until accruals have seen it below
+    /// `release_utilization_bps` for `cooloff_secs`.
+    pub fn set_circuit_breaker_config(
+        env: Env,
+        admin: Address,
+        trigger_utilization_bps: i128,
+        release_utilization_bps: i128,
+        bonus_bps: i128,
+        cooloff_secs: u64,
+    ) -> Result<(), InterestRateError> {
+        interest_rate::set_circuit_breaker_config(
+            &env,
+            admin,
+            trigger_utilization_bps,
+            release_utilization_bps,
+            bonus_bps,
+            cooloff_secs,
+        )
+    }
+
+    /// Get the circuit breaker parameters, if one is configured
+    pub fn get_circuit_breaker_config(env: Env) -> Option<interest_rate::CircuitBreakerConfig> {
+        interest_rate::get_circuit_breaker_config(&env)
+    }
+
+    /// Get whether the circuit breaker is tripped and since when
+    pub fn get_circuit_breaker_state(env: Env) -> interest_rate::CircuitBreakerState {
+        interest_rate::get_circuit_breaker_state(&env)
+    }
+
+    /// Calculate liquidation incentive amount
+    ///
+    /// # Arguments
+    /// * `liquidated_amount` - Amount being liquidated (in base units)
+    ///
+    /// # Returns
+    /// Liquidation incentive amount
+    pub fn get_liquidation_incentive_amount(
+        env: Env,
+        liquidated_amount: i128,
+    ) -> Result<i128, RiskManagementError> {
+        get_liquidation_incentive_amount(&env, liquidated_amount).map_err(|_| RiskManagementError::Overflow)
+    }
+
+    /// Refresh analytics for a user
+    pub fn refresh_user_analytics(_env: Env, _user: Address) -> Result<(), RiskManagementError> {
+        Ok(())
+    }
+
+    /// Claim accumulated protocol reserves (admin only)
+    pub fn claim_reserves(env: Env, caller: Address, asset: Option<Address>, to: Address, amount: i128) -> Result<(), RiskManagementError> {
+        let _guard = reentrancy::ReentrancyGuard::enter(&env)
+            .ok_or(RiskManagementError::Reentrancy)?;
+        require_admin(&env, &caller)?;
+        
+        let reserve_key = DepositDataKey::ProtocolReserve(asset.clone());
+        let mut reserve_balance = env.storage().persistent()
+            .get::<DepositDataKey, i128>(&reserve_key)
+            .unwrap_or(0);
+            
+        if amount > reserve_balance {
+            return Err(RiskManagementError::InvalidParameter);
+        }
+        
+        // Update the books before handing control to the token contract
+        reserve_balance -= amount;
+        env.storage().persistent().set(&reserve_key, &reserve_balance);
+        deposit::update_asset_totals(&env, &asset, 0, 0, -amount)
+            .map_err(|_| RiskManagementError::InvalidParameter)?;
+
+        if let Some(ref _asset_addr) = asset {
+            #[cfg(not(test))]
+            {
+                let token_client = soroban_sdk::token::Client::new(&env, _asset_addr);
+                token_client.transfer(&env.current_contract_address(), &to, &amount);
+            }
+        }
+        Ok(())
+    }
+
+    /// Donate tokens to the protocol reserve. Callable by anyone.
+    ///
+    /// Returns the new reserve balance for the asset.
+    pub fn donate_to_reserves(
+        env: Env,
+        donor: Address,
+        asset: Option<Address>,
+        amount: i128,
+    ) -> Result<i128, crate::deposit::DepositError> {
+        let _guard = reentrancy::ReentrancyGuard::enter(&env)
+            .ok_or(crate::deposit::DepositError::Reentrancy)?;
+        deposit::donate_to_reserves(&env, donor, asset, amount)
+    }
+
+    /// Withdraw the user's entire balance of an asset, including accrued interest
+    ///
+    /// Fails rather than withdrawing part of the balance when health checks
+    /// would not allow a full exit. Returns the net amount received.
+    pub fn withdraw_all(
+        env: Env,
+        user: Address,
+        asset: Option<Address>,
+    ) -> Result<i128, crate::withdraw::WithdrawError> {
+        let _guard = reentrancy::ReentrancyGuard::enter(&env)
+            .ok_or(crate::withdraw::WithdrawError::Reentrancy)?;
+        withdraw::withdraw_all(&env, user, asset)
+    }
+
+    /// Redeem deposit shares, reverting if they are worth less than `min_amount_out`
+    ///
+    /// Returns the net amount received.
+    pub fn withdraw_with_min(
+        env: Env,
+        user: Address,
+        asset: Option<Address>,
+        shares: i128,
+        min_amount_out: i128,
+    ) -> Result<i128, crate::withdraw::WithdrawError> {
+        let _guard = reentrancy::ReentrancyGuard::enter(&env)
+            .ok_or(crate::withdraw::WithdrawError::Reentrancy)?;
+        withdraw::withdraw_with_min(&env, user, asset, shares, min_amount_out)
+    }
+
+    /// Withdraw several assets in one all-or-nothing call
+    ///
+    /// The collateral ratio is checked against the state after the whole batch.
+    /// Returns the net amount received for each `(asset, amount)` item.
+    pub fn withdraw_batch(
+        env: Env,
+        user: Address,
+        requests: Vec<(Option<Address>, i128)>,
+    ) -> Result<Vec<i128>, crate::withdraw::WithdrawError> {
+        let _guard = reentrancy::ReentrancyGuard::enter(&env)
+            .ok_or(crate::withdraw::WithdrawError::Reentrancy)?;
+        withdraw::withdraw_batch(&env, user, requests)
+    }
+
+    /// Withdraw the user's entire balance of an asset during an emergency pause
+    ///
+    /// Only available to users with no outstanding debt. Outside an emergency
+    /// pause this behaves like `withdraw_all`. Returns the net amount received.
+    pub fn emergency_withdraw(
+        env: Env,
+        user: Address,
+        asset: Option<Address>,
+    ) -> Result<i128, crate::withdraw::WithdrawError> {
+        let _guard = reentrancy::ReentrancyGuard::enter(&env)
+            .ok_or(crate::withdraw::WithdrawError::Reentrancy)?;
+        withdraw::emergency_withdraw(&env, user, asset)
+    }
+
+    /// Get the largest amount of an asset a user can withdraw right now
+    ///
+    /// Accounts for outstanding debt and the asset's available liquidity.
+    /// Returns 0 for users without a position.
+    pub fn get_max_withdraw(env: Env, user: Address, asset: Option<Address>) -> i128 {
+        withdraw::get_max_withdraw(&env, &user, &asset)
+    }
+
+    /// Queue a withdrawal to be paid when liquidity returns
+    ///
+    /// The queued amount stops earning interest but still counts as collateral
+    /// until paid. Returns the request id.
+    pub fn request_withdrawal(
+        env: Env,
+        user: Address,
+        asset: Option<Address>,
+        amount: i128,
+    ) -> Result<u64, crate::withdraw::WithdrawError> {
+        let _guard = reentrancy::ReentrancyGuard::enter(&env)
+            .ok_or(crate::withdraw::WithdrawError::Reentrancy)?;
+        withdraw_queue::request_withdrawal(&env, user, asset, amount)
+    }
+
+    /// Pay up to `max_items` queued withdrawals of an asset in FIFO order
+    ///
+    /// Callable by anyone. Returns the total amount paid out.
+    pub fn process_withdrawal_queue(
+        env: Env,
+        asset: Option<Address>,
+        max_items: u32,
+    ) -> Result<i128, crate::withdraw::WithdrawError> {
+        let _guard = reentrancy::ReentrancyGuard::enter(&env)
+            .ok_or(crate::withdraw::WithdrawError::Reentrancy)?;
+        withdraw_queue::process_withdrawal_queue(&env, asset, max_items)
+    }
+
+    /// Get up to `limit` pending withdrawal requests for an asset, oldest first
+    pub fn get_withdrawal_queue(
+        env: Env,
+        asset: Option<Address>,
+        limit: u32,
+    ) -> Vec<crate::withdraw_queue::WithdrawalRequest> {
+        withdraw_queue::get_withdrawal_queue(&env, &asset, limit)
+    }
+
+    /// Cancel a pending withdrawal request and return its amount to the deposit
+    pub fn cancel_withdrawal_request(
+        env: Env,
+        user: Address,
+        request_id: u64,
+    ) -> Result<i128, crate::withdraw::WithdrawError> {
+        let _guard = reentrancy::ReentrancyGuard::enter(&env)
+            .ok_or(crate::withdraw::WithdrawError::Reentrancy)?;
+        withdraw_queue::cancel_withdrawal_request(&env, user, request_id)
+    }
+
+    /// Schedule a withdrawal paid out in equal tranches, one every `interval_secs`
+    pub fn schedule_withdrawal(
+        env: Env,
+        user: Address,
+        asset: Option<Address>,
+        total_amount: i128,
+        tranches: u32,
+        interval_secs: u64,
+    ) -> Result<u64, crate::withdraw::WithdrawError> {
+        let _guard = reentrancy::ReentrancyGuard::enter(&env)
+            .ok_or(crate::withdraw::WithdrawError::Reentrancy)?;
+        withdraw_schedule::schedule_withdrawal(
+            &env,
+            user,
+            asset,
+            total_amount,
+            tranches,
+            interval_secs,
+        )
+    }
+
+    /// Pay every due tranche of a withdrawal schedule (callable by anyone)
+    pub fn execute_scheduled_withdrawal(
+        env: Env,
+        user: Address,
+        schedule_id: u64,
+    ) -> Result<i128, crate::withdraw::WithdrawError> {
+        let _guard = reentrancy::ReentrancyGuard::enter(&env)
+            .ok_or(crate::withdraw::WithdrawError::Reentrancy)?;
+        withdraw_schedule::execute_scheduled_withdrawal(&env, user, schedule_id)
+    }
+
+    /// Cancel a withdrawal schedule and return the amount left unpaid
+    pub fn cancel_scheduled_withdrawal(
+        env: Env,
+        user: Address,
+        schedule_id: u64,
+    ) -> Result<i128, crate::withdraw::WithdrawError> {
+        let _guard = reentrancy::ReentrancyGuard::enter(&env)
+            .ok_or(crate::withdraw::WithdrawError::Reentrancy)?;
+        withdraw_schedule::cancel_scheduled_withdrawal(&env, user, schedule_id)
+    }
+
+    /// Get an active withdrawal schedule by id
+    pub fn get_withdrawal_schedule(
+        env: Env,
+        schedule_id: u64,
+    ) -> Option<crate::withdraw_schedule::WithdrawalSchedule> {
+        withdraw_schedule::get_withdrawal_schedule(&env, schedule_id)
     }
 
-    /// Borrow assets from the protocol
-    pub fn borrow_asset(
+    /// Split a user's current debt in an asset into equal installments, one due every `interval_secs`
+    pub fn create_repayment_schedule(
         env: Env,
         user: Address,
         asset: Option<Address>,
-        amount: i128,
-    ) -> Result<i128, crate::borrow::BorrowError> {
-        borrow::borrow_asset(&env, user, asset, amount)
+        installments: u32,
+        interval_secs: u64,
+    ) -> Result<u64, crate::repay::RepayError> {
+        let _guard = reentrancy::ReentrancyGuard::enter(&env)
+            .ok_or(crate::repay::RepayError::Reentrancy)?;
+        repayment_schedule::create_repayment_schedule(
+            &env,
+            user,
+            asset,
+            installments,
+            interval_secs,
+        )
     }
 
-    /// Repay borrowed assets
-    pub fn repay_debt(
+    /// Pay the next installment of a repayment schedule
+    ///
+    /// # Returns
+    /// Tuple of (remaining_debt, amount_paid)
+    pub fn repay_installment(
         env: Env,
         user: Address,
-        asset: Option<Address>,
-        amount: i128,
-    ) -> Result<(i128, i128, i128), crate::repay::RepayError> {
-        repay::repay_debt(&env, user, asset, amount)
+        schedule_id: u64,
+    ) -> Result<(i128, i128), crate::repay::RepayError> {
+        let _guard =
+            reentrancy::ReentrancyGuard::enter(&env).ok_or(crate::repay::RepayError::Reentrancy)?;
+        repayment_schedule::repay_installment(&env, user, schedule_id)
     }
 
-    /// Liquidate an undercollateralized position
-    pub fn liquidate(
+    /// Get an active repayment schedule by id
+    pub fn get_repayment_schedule(
         env: Env,
-        caller: Address,
-        paused: bool,
-    ) -> Result<(), RiskManagementError> {
-        risk_management::set_emergency_pause(&env, caller, paused)
+        schedule_id: u64,
+    ) -> Option<crate::repayment_schedule::RepaymentSchedule> {
+        repayment_schedule::get_repayment_schedule(&env, schedule_id)
     }
 
-    /// Get current risk configuration
-    ///
-    /// # Returns
-    /// Returns the current risk configuration or None if not initialized
-    pub fn get_risk_config(env: Env) -> Option<RiskConfig> {
-        risk_management::get_risk_config(&env)
+    /// Set the penalty rate on overdue installments and the delinquency threshold (admin only)
+    pub fn set_repayment_penalty_config(
+        env: Env,
+        caller: Address,
+        penalty_rate_bps: i128,
+        delinquency_secs: u64,
+    ) -> Result<(), crate::repay::RepayError> {
+        repayment_schedule::set_repayment_penalty_config(
+            &env,
+            caller,
+            penalty_rate_bps,
+            delinquency_secs,
+        )
     }
 
-    /// Get minimum collateral ratio
-    ///
-    /// # Returns
-    /// Returns the minimum collateral ratio in basis points
-    pub fn get_min_collateral_ratio(env: Env) -> Result<i128, RiskManagementError> {
-        risk_params::get_min_collateral_ratio(&env).map_err(|_| RiskManagementError::InvalidParameter)
+    /// Get the penalty terms for late installments
+    pub fn get_repayment_penalty_config(
+        env: Env,
+    ) -> crate::repayment_schedule::RepaymentPenaltyConfig {
+        repayment_schedule::get_repayment_penalty_config(&env)
     }
 
-    /// Get liquidation threshold
-    ///
-    /// # Returns
-    /// Returns the liquidation threshold in basis points
-    pub fn get_liquidation_threshold(env: Env) -> Result<i128, RiskManagementError> {
-        risk_params::get_liquidation_threshold(&env).map_err(|_| RiskManagementError::InvalidParameter)
+    /// Whether a user has an installment overdue past the delinquency threshold
+    pub fn is_repayment_delinquent(env: Env, user: Address) -> bool {
+        repayment_schedule::is_delinquent(&env, &user)
     }
 
-    /// Get close factor
+    /// Borrow at the variable rate plus the term's premium, fixed for `term_secs`
     ///
     /// # Returns
-    /// Returns the close factor in basis points
-    pub fn get_close_factor(env: Env) -> Result<i128, RiskManagementError> {
-        risk_params::get_close_factor(&env).map_err(|_| RiskManagementError::InvalidParameter)
+    /// The user's total debt after the borrow
+    pub fn open_term_loan(
+        env: Env,
+        user: Address,
+        asset: Option<Address>,
+        amount: i128,
+        term_secs: u64,
+    ) -> Result<i128, crate::borrow::BorrowError> {
+        let _guard = reentrancy::ReentrancyGuard::enter(&env)
+            .ok_or(crate::borrow::BorrowError::Reentrancy)?;
+        term_loan::open_term_loan(&env, user, asset, amount, term_secs)
     }
 
-    /// Get liquidation incentive
-    ///
-    /// # Returns
-    /// Returns the liquidation incentive in basis points
-    pub fn get_liquidation_incentive(env: Env) -> Result<i128, RiskManagementError> {
-        risk_params::get_liquidation_incentive(&env).map_err(|_| RiskManagementError::InvalidParameter)
+    /// Offer a term length at a premium over the variable rate, or stop offering
+    /// it with `None` (admin only)
+    pub fn set_term_premium(
+        env: Env,
+        caller: Address,
+        term_secs: u64,
+        premium_bps: Option<i128>,
+    ) -> Result<(), crate::borrow::BorrowError> {
+        term_loan::set_term_premium(&env, caller, term_secs, premium_bps)
     }
 
-    /// Get current borrow rate (in basis points)
-    pub fn get_borrow_rate(env: Env) -> i128 {
-        interest_rate::calculate_borrow_rate(&env).unwrap_or(0)
+    /// Set the late penalty on matured term loans and the early repayment fee (admin only)
+    pub fn set_term_loan_config(
+        env: Env,
+        caller: Address,
+        late_penalty_bps: i128,
+        early_repayment_fee_bps: i128,
+    ) -> Result<(), crate::borrow::BorrowError> {
+        term_loan::set_term_loan_config(&env, caller, late_penalty_bps, early_repayment_fee_bps)
     }
 
-    /// Get current supply rate (in basis points)
-    pub fn get_supply_rate(env: Env) -> i128 {
-        interest_rate::calculate_supply_rate(&env).unwrap_or(0)
+    /// Get the terms on offer and the term loan fees
+    pub fn get_term_loan_config(env: Env) -> crate::term_loan::TermLoanConfig {
+        term_loan::get_term_loan_config(&env)
     }
 
-    /// Update interest rate model configuration (admin only)
-    #[allow(clippy::too_many_arguments)]
-    pub fn update_interest_rate_config(
+    /// Get a user's pending withdrawal intent for an asset with a cooldown
+    pub fn get_withdraw_intent(
         env: Env,
-        admin: Address,
-        base_rate: Option<i128>,
-        kink: Option<i128>,
-        multiplier: Option<i128>,
-        jump_multiplier: Option<i128>,
-        rate_floor: Option<i128>,
-        rate_ceiling: Option<i128>,
-        spread: Option<i128>,
-    ) -> Result<(), RiskManagementError> {
-        require_min_collateral_ratio(&env, collateral_value, debt_value).map_err(|_| RiskManagementError::InsufficientCollateralRatio)
+        user: Address,
+        asset: Address,
+    ) -> Option<crate::withdraw::WithdrawIntent> {
+        withdraw::get_withdraw_intent(&env, &user, &asset)
     }
 
-    /// Check if position can be liquidated
-    ///
-    /// # Arguments
-    /// * `collateral_value` - Total collateral value (in base units)
-    /// * `debt_value` - Total debt value (in base units)
-    ///
-    /// # Returns
-    /// Returns true if position can be liquidated
-    pub fn can_be_liquidated(
+    /// Cancel a user's pending withdrawal intent for an asset
+    pub fn cancel_withdraw_intent(
         env: Env,
-        collateral_value: i128,
-        debt_value: i128,
-    ) -> Result<bool, RiskManagementError> {
-        can_be_liquidated(&env, collateral_value, debt_value).map_err(|_| RiskManagementError::InvalidParameter)
+        user: Address,
+        asset: Address,
+    ) -> Result<(), crate::withdraw::WithdrawError> {
+        let _guard = reentrancy::ReentrancyGuard::enter(&env)
+            .ok_or(crate::withdraw::WithdrawError::Reentrancy)?;
+        withdraw::cancel_withdraw_intent(&env, user, asset)
     }
 
-    /// Manual emergency interest rate adjustment (admin only)
-    pub fn set_emergency_rate_adjustment(
+    /// Set an asset's deposit, collateral and fee parameters (admin only)
+    pub fn update_asset_params(
         env: Env,
-        debt_value: i128,
-    ) -> Result<i128, RiskManagementError> {
-        get_max_liquidatable_amount(&env, debt_value).map_err(|_| RiskManagementError::Overflow)
+        caller: Address,
+        asset: Address,
+        params: crate::deposit::AssetParams,
+    ) -> Result<(), crate::deposit::DepositError> {
+        let _guard = reentrancy::ReentrancyGuard::enter(&env)
+            .ok_or(crate::deposit::DepositError::Reentrancy)?;
+        deposit::update_asset_params(&env, caller, asset, params)
     }
 
-    /// Calculate liquidation incentive amount
-    ///
-    /// # Arguments
-    /// * `liquidated_amount` - Amount being liquidated (in base units)
+    /// Get the amount of an asset available for withdrawal
     ///
-    /// # Returns
-    /// Liquidation incentive amount
-    pub fn get_liquidation_incentive_amount(
-        env: Env,
-        liquidated_amount: i128,
-    ) -> Result<i128, RiskManagementError> {
-        get_liquidation_incentive_amount(&env, liquidated_amount).map_err(|_| RiskManagementError::Overflow)
+    /// Total supplied minus total borrowed plus reserves held.
+    pub fn get_available_liquidity(env: Env, asset: Option<Address>) -> i128 {
+        withdraw::get_available_liquidity(&env, &asset)
     }
 
-    /// Refresh analytics for a user
-    pub fn refresh_user_analytics(_env: Env, _user: Address) -> Result<(), RiskManagementError> {
-        Ok(())
+    /// Get the supplied, borrowed and reserve totals for an asset
+    pub fn get_asset_totals(env: Env, asset: Option<Address>) -> crate::deposit::AssetTotals {
+        deposit::get_asset_totals(&env, &asset)
     }
 
-    /// Claim accumulated protocol reserves (admin only)
-    pub fn claim_reserves(env: Env, caller: Address, asset: Option<Address>, to: Address, amount: i128) -> Result<(), RiskManagementError> {
-        require_admin(&env, &caller)?;
-        
-        let reserve_key = DepositDataKey::ProtocolReserve(asset.clone());
-        let mut reserve_balance = env.storage().persistent()
-            .get::<DepositDataKey, i128>(&reserve_key)
-            .unwrap_or(0);
-            
-        if amount > reserve_balance {
-            return Err(RiskManagementError::InvalidParameter);
-        }
-        
-        if let Some(_asset_addr) = asset {
-            #[cfg(not(test))]
-            {
-                let token_client = soroban_sdk::token::Client::new(&env, &_asset_addr);
-                token_client.transfer(&env.current_contract_address(), &to, &amount);
-            }
-        }
-        
-        reserve_balance -= amount;
-        env.storage().persistent().set(&reserve_key, &reserve_balance);
-        Ok(())
+    /// Get the utilization of a single asset (in basis points)
+    pub fn get_asset_utilization(
+        env: Env,
+        asset: Option<Address>,
+    ) -> Result<i128, InterestRateError> {
+        interest_rate::calculate_utilization(&env, &asset)
     }
 
     /// Get current protocol reserve balance for an asset
@@ -497,6 +1879,37 @@ impl HelloContract {
             .unwrap_or(0)
     }
 
+    /// Get the reserve share of borrow interest not yet credited by `accrue_interest`
+    pub fn get_pending_reserves(env: Env, asset: Option<Address>) -> i128 {
+        interest_rate::get_pending_reserves(&env, &asset)
+    }
+
+    /// Get the protocol reserve balance for an asset including pending reserves
+    pub fn get_total_reserves(env: Env, asset: Option<Address>) -> i128 {
+        interest_rate::get_total_reserves(&env, &asset)
+    }
+
+    /// Write off a borrower's unrecoverable debt against reserves, then as bad debt (admin only)
+    ///
+    /// # Returns
+    /// Returns (remaining_debt, from_reserve, bad_debt) for this write-off
+    pub fn write_off_debt(
+        env: Env,
+        admin: Address,
+        borrower: Address,
+        asset: Option<Address>,
+        amount: i128,
+    ) -> Result<(i128, i128, i128), crate::repay::RepayError> {
+        let _guard = reentrancy::ReentrancyGuard::enter(&env)
+            .ok_or(crate::repay::RepayError::Reentrancy)?;
+        repay::write_off_debt(&env, admin, borrower, asset, amount)
+    }
+
+    /// Get written-off debt in an asset that the reserve could not absorb
+    pub fn get_bad_debt(env: Env, asset: Option<Address>) -> i128 {
+        repay::get_bad_debt(&env, &asset)
+    }
+
     /// Generate a comprehensive protocol report.
     ///
     /// Aggregates TVL, utilization, average borrow rate, and user/transaction counts
@@ -569,23 +1982,66 @@ impl HelloContract {
     }
 
     /// Update price feed from oracle
+    ///
+    /// `caller` must be an oracle registered for `asset`; the price is attributed to it.
     pub fn update_price_feed(
         env: Env,
         caller: Address,
         asset: Address,
         price: i128,
         decimals: u32,
-        oracle: Address,
     ) -> i128 {
-        oracle::update_price_feed(&env, caller, asset, price, decimals, oracle)
-            .expect("Oracle error")
+        let _guard = reentrancy::ReentrancyGuard::enter(&env).unwrap_or_else(|| {
+            soroban_sdk::panic_with_error!(&env, crate::oracle::OracleError::Reentrancy)
+        });
+        oracle::update_price_feed(&env, caller, asset, price, decimals).expect("Oracle error")
+    }
+
+    /// Allow an oracle to submit prices for an asset (admin only)
+    pub fn register_oracle(env: Env, caller: Address, asset: Address, oracle: Address) {
+        oracle::register_oracle(&env, caller, asset, oracle).expect("Oracle error")
+    }
+
+    /// Stop an oracle from submitting prices for an asset (admin only)
+    ///
+    /// Prices it already submitted are no longer served.
+    pub fn revoke_oracle(env: Env, caller: Address, asset: Address, oracle: Address) {
+        oracle::revoke_oracle(&env, caller, asset, oracle).expect("Oracle error")
+    }
+
+    /// Oracles registered to submit prices for an asset
+    pub fn get_registered_oracles(env: Env, asset: Address) -> Vec<Address> {
+        oracle::get_registered_oracles(&env, &asset)
+    }
+
+    /// Set how many fresh oracle sources an asset's price needs (admin only)
+    pub fn set_price_quorum(env: Env, caller: Address, asset: Address, min_sources: u32) {
+        oracle::set_price_quorum(&env, caller, asset, min_sources).expect("Oracle error")
+    }
+
+    /// Fresh oracle sources an asset's price needs
+    pub fn get_price_quorum(env: Env, asset: Address) -> u32 {
+        oracle::get_price_quorum(&env, &asset)
+    }
+
+    /// Latest price and update time of each oracle source of an asset
+    pub fn get_price_sources(env: Env, asset: Address) -> Vec<oracle::PriceFeed> {
+        oracle::get_price_sources(&env, &asset)
     }
 
-    /// Get current price for an asset
+    /// Get current price for an asset, the median of its fresh oracle sources
     pub fn get_price(env: Env, asset: Address) -> i128 {
         oracle::get_price(&env, &asset).expect("Oracle error")
     }
 
+    /// Get the last primary feed price for an asset, however old
+    ///
+    /// For diagnostics and administration only; positions are always valued
+    /// through `get_price`, which rejects stale prices.
+    pub fn get_price_unchecked(env: Env, asset: Address) -> i128 {
+        oracle::get_price_unchecked(&env, &asset).expect("Oracle error")
+    }
+
     /// Configure oracle parameters (admin only)
     pub fn configure_oracle(
         env: Env,
@@ -639,8 +2095,12 @@ impl HelloContract {
         liquidation_threshold: Option<i128>,
         close_factor: Option<i128>,
         liquidation_incentive: Option<i128>,
+        liquidation_cooldown_ledgers: Option<u32>,
+        liquidation_protocol_fee_bps: Option<i128>,
     ) -> Result<(), RiskManagementError> {
-        risk_management::set_risk_params(&env, admin, min_collateral_ratio, liquidation_threshold, close_factor, liquidation_incentive)
+        let _guard = reentrancy::ReentrancyGuard::enter(&env)
+            .ok_or(RiskManagementError::Reentrancy)?;
+        risk_management::set_risk_params(&env, admin, min_collateral_ratio, liquidation_threshold, close_factor, liquidation_incentive, liquidation_cooldown_ledgers, liquidation_protocol_fee_bps)
     }
 
     /// Set a pause switch for an operation (admin only)
@@ -653,6 +2113,24 @@ impl HelloContract {
         risk_management::is_operation_paused(&env, operation)
     }
 
+    /// Pause or unpause an operation for a single asset (admin only)
+    ///
+    /// Uses the same operation symbols as `set_pause_switch` (e.g. `pause_deposit`).
+    pub fn set_asset_pause(
+        env: Env,
+        admin: Address,
+        asset: Option<Address>,
+        operation: Symbol,
+        paused: bool,
+    ) -> Result<(), RiskManagementError> {
+        risk_management::set_asset_pause(&env, admin, asset, operation, paused)
+    }
+
+    /// Check if an operation is paused for a single asset
+    pub fn is_asset_operation_paused(env: Env, asset: Option<Address>, operation: Symbol) -> bool {
+        risk_management::is_asset_operation_paused(&env, &asset, operation)
+    }
+
     /// Check if emergency pause is active
     pub fn is_emergency_paused(env: Env) -> bool {
         risk_management::is_emergency_paused(&env)
@@ -660,9 +2138,174 @@ impl HelloContract {
 
     /// Set emergency pause (admin only)
     pub fn set_emergency_pause(env: Env, admin: Address, paused: bool) -> Result<(), RiskManagementError> {
+        let _guard = reentrancy::ReentrancyGuard::enter(&env)
+            .ok_or(RiskManagementError::Reentrancy)?;
         risk_management::set_emergency_pause(&env, admin, paused)
     }
 
+    /// Let repayments bypass pause switches and emergency pause (admin only, default on)
+    pub fn set_repay_always_allowed(
+        env: Env,
+        admin: Address,
+        allowed: bool,
+    ) -> Result<(), RiskManagementError> {
+        risk_management::set_repay_always_allowed(&env, admin, allowed)
+    }
+
+    /// Check whether repayments bypass pause switches and emergency pause
+    pub fn is_repay_always_allowed(env: Env) -> bool {
+        risk_management::is_repay_always_allowed(&env)
+    }
+
+    /// Restrict liquidations to allowlisted liquidators, or open them to everyone (admin only)
+    pub fn set_liquidator_allowlist_enabled(
+        env: Env,
+        admin: Address,
+        enabled: bool,
+    ) -> Result<(), RiskManagementError> {
+        let _guard = reentrancy::ReentrancyGuard::enter(&env)
+            .ok_or(RiskManagementError::Reentrancy)?;
+        risk_management::set_liquidator_allowlist_enabled(&env, admin, enabled)
+    }
+
+    /// Check whether only allowlisted liquidators may liquidate
+    pub fn is_liquidator_allowlist_enabled(env: Env) -> bool {
+        risk_management::is_liquidator_allowlist_enabled(&env)
+    }
+
+    /// Add a liquidator to the allowlist (admin only)
+    pub fn add_liquidator(
+        env: Env,
+        admin: Address,
+        liquidator: Address,
+    ) -> Result<(), RiskManagementError> {
+        let _guard = reentrancy::ReentrancyGuard::enter(&env)
+            .ok_or(RiskManagementError::Reentrancy)?;
+        risk_management::add_liquidator(&env, admin, liquidator)
+    }
+
+    /// Remove a liquidator from the allowlist (admin only)
+    pub fn remove_liquidator(
+        env: Env,
+        admin: Address,
+        liquidator: Address,
+    ) -> Result<(), RiskManagementError> {
+        let _guard = reentrancy::ReentrancyGuard::enter(&env)
+            .ok_or(RiskManagementError::Reentrancy)?;
+        risk_management::remove_liquidator(&env, admin, liquidator)
+    }
+
+    /// Check whether a liquidator is on the allowlist
+    pub fn is_allowed_liquidator(env: Env, liquidator: Address) -> bool {
+        risk_management::is_allowed_liquidator(&env, &liquidator)
+    }
+
+    /// Register the AMM contract `liquidate_and_swap` swaps through (admin only)
+    pub fn set_liquidation_amm(
+        env: Env,
+        admin: Address,
+        amm: Address,
+    ) -> Result<(), RiskManagementError> {
+        let _guard = reentrancy::ReentrancyGuard::enter(&env)
+            .ok_or(RiskManagementError::Reentrancy)?;
+        risk_management::set_liquidation_amm(&env, admin, amm)
+    }
+
+    /// Get the AMM contract `liquidate_and_swap` swaps through, if one is registered
+    pub fn get_liquidation_amm(env: Env) -> Option<Address> {
+        risk_management::get_liquidation_amm(&env)
+    }
+
+    /// Cap the debt value any single account may borrow up to (admin only, 0 = unlimited)
+    pub fn set_max_user_debt_value(
+        env: Env,
+        admin: Address,
+        max_value: i128,
+    ) -> Result<(), RiskManagementError> {
+        risk_management::set_max_user_debt_value(&env, admin, max_value)
+    }
+
+    /// Get the per-account debt value limit (0 = unlimited)
+    pub fn get_max_user_debt_value(env: Env) -> i128 {
+        risk_management::get_max_user_debt_value(&env)
+    }
+
+    /// Set the liquidation grace window and the health factor below which it is
+    /// skipped (admin only, 0 disables either)
+    pub fn set_liquidation_grace(
+        env: Env,
+        admin: Address,
+        grace_secs: u64,
+        immediate_health_factor: i128,
+    ) -> Result<(), RiskManagementError> {
+        risk_management::set_liquidation_grace(&env, admin, grace_secs, immediate_health_factor)
+    }
+
+    /// Get the liquidation grace window and immediate-liquidation health factor
+    pub fn get_liquidation_grace(env: Env) -> (u64, i128) {
+        risk_management::get_liquidation_grace(&env)
+    }
+
+    /// Set how many liquidation snapshots are retained (admin only)
+    pub fn set_liquidation_snapshot_capacity(
+        env: Env,
+        admin: Address,
+        capacity: u32,
+    ) -> Result<(), RiskManagementError> {
+        risk_management::set_liquidation_snapshot_capacity(&env, admin, capacity)
+    }
+
+    /// Get how many liquidation snapshots are retained
+    pub fn get_liquidation_snapshot_capacity(env: Env) -> u32 {
+        risk_management::get_liquidation_snapshot_capacity(&env)
+    }
+
+    /// Set the minimum base-currency reward of a liquidation, topped up from
+    /// the collateral reserve (admin only, 0 disables)
+    pub fn set_min_liquidation_reward(
+        env: Env,
+        admin: Address,
+        min_reward: i128,
+    ) -> Result<(), RiskManagementError> {
+        risk_management::set_min_liquidation_reward(&env, admin, min_reward)
+    }
+
+    /// Get the minimum base-currency reward of a liquidation (0 = no floor)
+    pub fn get_min_liquidation_reward(env: Env) -> i128 {
+        risk_management::get_min_liquidation_reward(&env)
+    }
+
+    /// Define or update an e-mode category of correlated assets (admin only)
+    pub fn set_emode_category(
+        env: Env,
+        admin: Address,
+        category: risk_management::EModeCategory,
+    ) -> Result<(), RiskManagementError> {
+        risk_management::set_emode_category(&env, admin, category)
+    }
+
+    /// Get an e-mode category by id
+    pub fn get_emode_category(
+        env: Env,
+        category_id: u32,
+    ) -> Option<risk_management::EModeCategory> {
+        risk_management::get_emode_category(&env, category_id)
+    }
+
+    /// Opt into an e-mode category, or leave e-mode with `category_id` 0
+    pub fn set_user_emode(
+        env: Env,
+        user: Address,
+        category_id: u32,
+    ) -> Result<(), RiskManagementError> {
+        risk_management::set_user_emode(&env, user, category_id)
+    }
+
+    /// Get the e-mode category a user opted into (0 = none)
+    pub fn get_user_emode(env: Env, user: Address) -> u32 {
+        risk_management::get_user_emode(&env, &user)
+    }
+
     /// Get user analytics metrics
     pub fn get_user_analytics(env: Env, user: Address) -> Result<crate::analytics::UserMetrics, crate::analytics::AnalyticsError> {
         analytics::get_user_activity_summary(&env, &user)
@@ -702,6 +2345,8 @@ impl HelloContract {
 
     /// Execute swap through AMM
     pub fn amm_swap(env: Env, user: Address, params: SwapParams) -> Result<i128, AmmError> {
+        let _guard = reentrancy::ReentrancyGuard::enter(&env)
+            .ok_or(AmmError::Reentrancy)?;
         amm_swap(env, user, params)
     }
 
@@ -751,6 +2396,8 @@ impl HelloContract {
         asset: Option<Address>,
         amount: i128,
     ) -> Result<i128, BridgeError> {
+        let _guard = reentrancy::ReentrancyGuard::enter(&env)
+            .ok_or(BridgeError::Reentrancy)?;
         bridge::bridge_deposit(&env, user, network_id, asset, amount)
     }
 
@@ -768,6 +2415,8 @@ impl HelloContract {
         asset: Option<Address>,
         amount: i128,
     ) -> Result<i128, BridgeError> {
+        let _guard = reentrancy::ReentrancyGuard::enter(&env)
+            .ok_or(BridgeError::Reentrancy)?;
         bridge::bridge_withdraw(&env, user, network_id, asset, amount)
     }
 
@@ -979,6 +2628,8 @@ impl HelloContract {
         asset: Option<Address>,
         amount: i128,
     ) -> Result<AssetPosition, CrossAssetError> {
+        let _guard = reentrancy::ReentrancyGuard::enter(&env)
+            .ok_or(CrossAssetError::Reentrancy)?;
         cross_asset_deposit(&env, user, asset, amount)
     }
 
@@ -999,6 +2650,8 @@ impl HelloContract {
         asset: Option<Address>,
         amount: i128,
     ) -> Result<AssetPosition, CrossAssetError> {
+        let _guard = reentrancy::ReentrancyGuard::enter(&env)
+            .ok_or(CrossAssetError::Reentrancy)?;
         cross_asset_withdraw(&env, user, asset, amount)
     }
 
@@ -1019,6 +2672,8 @@ impl HelloContract {
         asset: Option<Address>,
         amount: i128,
     ) -> Result<AssetPosition, CrossAssetError> {
+        let _guard = reentrancy::ReentrancyGuard::enter(&env)
+            .ok_or(CrossAssetError::Reentrancy)?;
         cross_asset_borrow(&env, user, asset, amount)
     }
 
@@ -1039,6 +2694,8 @@ impl HelloContract {
         asset: Option<Address>,
         amount: i128,
     ) -> Result<AssetPosition, CrossAssetError> {
+        let _guard = reentrancy::ReentrancyGuard::enter(&env)
+            .ok_or(CrossAssetError::Reentrancy)?;
         cross_asset_repay(&env, user, asset, amount)
     }
 
@@ -1438,6 +3095,8 @@ impl HelloContract {
         asset: Option<Address>,
         amount: i128,
     ) -> Result<i128, BridgeError> {
+        let _guard = reentrancy::ReentrancyGuard::enter(&env)
+            .ok_or(BridgeError::Reentrancy)?;
         bridge_deposit(&env, user, network_id, asset, amount)
     }
 
@@ -1449,6 +3108,8 @@ impl HelloContract {
         asset: Option<Address>,
         amount: i128,
     ) -> Result<i128, BridgeError> {
+        let _guard = reentrancy::ReentrancyGuard::enter(&env)
+            .ok_or(BridgeError::Reentrancy)?;
         bridge_withdraw(&env, user, network_id, asset, amount)
     }
 }
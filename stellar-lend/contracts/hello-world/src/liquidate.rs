@@ -10,19 +10,134 @@
 //! - The liquidation incentive awarded to the liquidator
 //!
 //! ## Cross-Asset Liquidation
-//! When debt and collateral are different assets, oracle prices are used to
-//! convert between asset values. A default price of 1.0 (8 decimals) is used
-//! as fallback when oracle prices are not configured.
+//! Eligibility is decided on the whole account: every collateral-enabled deposit
+//! and every borrowed asset is valued at its oracle price, whichever pair the
+//! liquidator chooses to repay and seize. When debt and collateral are different
+//! assets, the same prices convert the repaid debt into collateral to seize. A
+//! default price of 1.0 (8 decimals) is used as fallback when oracle prices are
+//! not configured.
+//!
+//! [`can_liquidate`] makes that decision, and `liquidate` rejects accounts it
+//! finds healthy with `PositionHealthy` before doing anything else.
+//! [`can_liquidate`], [`get_health_factor`] and [`get_account_liquidity`] expose
+//! the same valuation as read-only views.
+//!
+//! [`get_liquidatable_positions`] walks the on-chain index of borrowers with
+//! open debt a page at a time and returns the ones [`can_liquidate`] accepts.
+//!
+//! ## Grace Window
+//! With a liquidation grace window configured in the risk config, an account
+//! below the threshold only becomes liquidatable once its breach, recorded by
+//! [`sync_liquidation_breach`], is at least `liquidation_grace_secs` old; until
+//! then `liquidate` fails with `GracePeriodActive`. Accounts below the immediate
+//! liquidation health factor, and delinquent accounts, skip the wait. The record
+//! is cleared when the account is seen above the threshold again: by a sync, a
+//! liquidation, a repayment or a collateral deposit.
+//!
+//! ## Cooldown
+//! With `liquidation_cooldown_ledgers` set in the risk config, a borrower cannot
+//! be liquidated again until that many ledgers have closed since their last
+//! liquidation (`LiquidationCooldown`), unless their health factor is below the
+//! immediate liquidation health factor.
+//!
+//! ## Liquidator Allowlist
+//! While the risk management liquidator allowlist is enabled, liquidators not on
+//! it are rejected with `LiquidatorNotAllowed` before the position is examined.
+//! Self-liquidation is not restricted.
+//!
+//! ## Snapshots
+//! Before any collateral moves, each liquidation stores a
+//! [`LiquidationSnapshot`] of the borrower's collateral and debt per asset, the
+//! prices and price timestamps they were valued at and the health factor, so
+//! disputes can be settled from on-chain state. [`get_liquidation_snapshot`]
+//! reads them back while they are within the risk config's ring buffer.
+//!
+//! ## Swapping Seized Collateral
+//! [`liquidate_and_swap`] sells the seized collateral to an AMM contract and pays
+//! the liquidator in the debt asset instead, failing with `SlippageExceeded`
+//! when the swap returns less than the liquidator's `min_out`.
+//!
+//! ## Pauses
+//! Liquidations stop while the `pause_liquidate` switch is set
+//! (`LiquidationPaused`) or the protocol is under emergency pause
+//! (`EmergencyPaused`). For incident response the admin can still close
+//! positions with [`force_liquidate`], which ignores both pauses and the
+//! allowlist and emits a `ForcedLiquidationEvent`.
+//!
+//! ## Close Factor
+//! A single liquidation may repay at most `close_factor` of the borrower's
+//! current debt in the chosen debt asset; larger amounts are rejected with
+//! `ExceedsCloseFactor` rather than clamped. Positions whose total debt value is
+//! below the configured dust threshold can be closed in full at once. Positions
+//! recorded before per-asset debt tracking are measured against their total debt.
+//!
+//! ## Choosing Collateral
+//! The liquidator names the collateral asset to seize, and only that deposit is
+//! touched. It must be enabled as collateral and hold a non-zero balance, or the
+//! call fails with `InvalidCollateralAsset`; [`get_seizable_collateral`] lists the
+//! candidates. [`liquidate_multi`] takes an ordered list of collateral assets
+//! instead and seizes from each in turn until the repaid value plus incentive
+//! is covered, all in one transaction.
+//!
+//! With `auto_collateral` set and no collateral asset named, `liquidate` picks
+//! the collateral itself from [`get_auto_collateral_order`]: the deposit whose
+//! asset parameters carry the highest `liquidity_score`, so illiquid collateral
+//! is only seized once the deeper markets are exhausted.
+//!
+//! ## Liquidation Incentive
+//! The repaid debt is converted into collateral of equal oracle value, taking
+//! each feed's price decimals into account, and the liquidator is granted
+//! `liquidation_incentive` (bps) of that value on top. The risk config's
+//! `liquidation_protocol_fee_bps` of the bonus is kept back and credited to
+//! `ProtocolReserve(collateral_asset)`; at 0, the default, the liquidator receives
+//! all of it. [`simulate_liquidation`] runs the same
+//! calculation without writing anything, so bots can see the outcome first.
+//!
+//! When the bonus the liquidator keeps is worth less than the risk config's
+//! `min_liquidation_reward`, [`liquidate`] and [`liquidate_and_swap`] pay the
+//! difference in collateral out of `ProtocolReserve(collateral_asset)`, as far
+//! as the reserve goes, and emit a `LiquidationRewardTopUpEvent`. The top-up is
+//! not part of the returned tuple.
+//!
+//! ## Bad Debt
+//! When the borrower's collateral in the chosen asset is worth less than the
+//! repaid debt plus bonus, all of it is seized and the repaid debt is reduced in
+//! proportion. If that leaves the borrower with no collateral at all, the debt
+//! they still owe in the debt asset is drawn from `ProtocolReserve(debt_asset)`
+//! and any residue is recorded as `BadDebt(debt_asset)`, surfaced through the
+//! protocol analytics.
+//!
+//! ## Events
+//! Every seizure emits a `LiquidationEvent` with the full breakdown: amounts
+//! repaid and seized, incentive and protocol fee, the borrower's health factor
+//! before and after, and both oracle prices used. Its topics are the borrower
+//! and the collateral asset, so indexers can filter on either.
+//!
+//! ## Self-Liquidation
+//! [`self_liquidate`] lets borrowers deleverage out of their own collateral in
+//! the debt asset, even while healthy, under the same close factor and at the
+//! reduced self-liquidation incentive.
+//!
+//! ## E-Mode
+//! Accounts whose assets all belong to their e-mode category are measured
+//! against the category's liquidation threshold instead of the global one.
 //!
 //! ## Invariants
-//! - Only undercollateralized positions (below liquidation threshold) can be liquidated.
+//! - Only undercollateralized positions (below liquidation threshold) or accounts
+//!   with a delinquent repayment schedule can be liquidated.
 //! - Liquidation amount cannot exceed the close factor percentage of total debt.
 //! - Collateral seized cannot exceed the borrower's available collateral.
+//! - Deposits the borrower disabled as collateral are neither valued nor seized.
 //! - Interest is accrued on the borrower's position before liquidation.
 
 #![allow(unused)]
-use crate::events::{emit_liquidation, LiquidationEvent};
-use soroban_sdk::{contracterror, Address, Env, IntoVal, Map, Symbol, Val, Vec};
+use crate::events::{
+    emit_forced_liquidation, emit_liquidation, emit_liquidation_bad_debt,
+    emit_liquidation_reward_top_up, ForcedLiquidationEvent, LiquidationBadDebtEvent,
+    LiquidationEvent, LiquidationRewardTopUpEvent,
+};
+use soroban_sdk::auth::{ContractContext, InvokerContractAuthEntry, SubContractInvocation};
+use soroban_sdk::{contracterror, contracttype, Address, Env, IntoVal, Map, Symbol, Val, Vec};
 
 use crate::deposit::{
     add_activity_log, emit_analytics_updated_event, emit_position_updated_event,
@@ -31,13 +146,20 @@ use crate::deposit::{
 };
 use crate::oracle::get_price;
 use crate::risk_management::{
-    is_emergency_paused, is_operation_paused, require_operation_not_paused, RiskManagementError,
+    can_liquidator_act, get_last_liquidation_ledger, get_liquidation_amm, get_liquidation_breach,
+    get_liquidation_cooldown_ledgers, get_liquidation_grace, get_liquidation_protocol_fee_bps,
+    get_liquidation_snapshot_capacity, get_min_liquidation_reward, is_emergency_paused,
+    is_operation_paused, record_liquidation_ledger, require_operation_not_paused,
+    set_liquidation_breach, RiskDataKey, RiskManagementError,
 };
 use crate::risk_params::{
-    can_be_liquidated, get_close_factor, get_liquidation_incentive,
-    get_liquidation_incentive_amount, get_max_liquidatable_amount,
+    can_be_liquidated, get_close_factor, get_liquidation_incentive, get_liquidation_threshold,
+    get_max_liquidatable_amount, get_self_liquidation_incentive,
 };
 
+/// Basis point scale for ratios and the health factor (100% = 10_000)
+const BASIS_POINTS: i128 = 10_000;
+
 /// Errors that can occur during liquidation operations
 #[contracterror]
 #[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
@@ -61,80 +183,143 @@ pub enum LiquidationError {
     InvalidCollateralAsset = 8,
     /// Invalid debt asset
     InvalidDebtAsset = 9,
-    /// Price not available for asset
+    /// Price not available for asset, or stale
     PriceNotAvailable = 10,
     /// Liquidation would leave position undercollateralized
     InsufficientLiquidation = 11,
+    /// Reentrancy detected
+    Reentrancy = 12,
+    /// Position is healthy and cannot be liquidated
+    PositionHealthy = 13,
+    /// Borrowers cannot liquidate their own position
+    SelfLiquidationNotAllowed = 14,
+    /// Borrower has no seizable collateral in the chosen asset
+    InsufficientCollateral = 15,
+    /// Position is below the liquidation threshold but still within its grace window
+    GracePeriodActive = 16,
+    /// Borrower was liquidated too recently, see `liquidation_cooldown_ledgers`
+    LiquidationCooldown = 17,
+    /// No collateral assets were given to seize from
+    EmptyCollateralOrder = 18,
+    /// The liquidator allowlist is enabled and the liquidator is not on it
+    LiquidatorNotAllowed = 19,
+    /// The protocol is under emergency pause
+    EmergencyPaused = 20,
+    /// Caller is not the protocol admin
+    Unauthorized = 21,
+    /// The AMM contract rejected the swap of seized collateral
+    SwapFailed = 22,
+    /// The swap of seized collateral returned less than `min_out`
+    SlippageExceeded = 23,
 }
 
-/// Annual interest rate in basis points (e.g., 500 = 5% per year)
-/// This matches the rate used in borrow.rs and repay.rs
-// Interest rate is now calculated dynamically based on utilization
-// See interest_rate module for details
-/// Calculate interest accrued since last accrual time
-/// Calculate accrued interest using dynamic interest rate
-/// Uses the current borrow rate based on protocol utilization
-fn calculate_accrued_interest(
-    env: &Env,
-    principal: i128,
-    last_accrual_time: u64,
-    current_time: u64,
-) -> Result<i128, LiquidationError> {
-    if principal == 0 {
-        return Ok(0);
-    }
-
-    if current_time <= last_accrual_time {
-        return Ok(0);
-    }
-
-    // Get current borrow rate (in basis points)
-    let rate_bps =
-        crate::interest_rate::calculate_borrow_rate(env).map_err(|_| LiquidationError::Overflow)?;
-
-    // Calculate interest using the dynamic rate
-    crate::interest_rate::calculate_accrued_interest(
-        principal,
-        last_accrual_time,
-        current_time,
-        rate_bps,
-    )
-    .map_err(|_| LiquidationError::Overflow)
+/// Projected outcome of a liquidation, see [`simulate_liquidation`]
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct LiquidationSimulation {
+    /// Whether `liquidate` would succeed with the same arguments in this ledger
+    pub would_succeed: bool,
+    /// `LiquidationError` code the liquidation would fail with (0 if it would succeed)
+    pub error_code: u32,
+    /// Debt the liquidator would repay, after clamping to the close factor and
+    /// the collateral available
+    pub debt_repaid: i128,
+    /// Collateral that would be taken from the borrower, incentive included
+    pub collateral_seized: i128,
+    /// Part of `collateral_seized` paid as liquidation incentive
+    pub incentive_amount: i128,
+    /// Part of the incentive kept as protocol reserve rather than paid out
+    pub protocol_fee: i128,
 }
 
-/// Accrue interest on a position
-fn accrue_interest(env: &Env, position: &mut Position) -> Result<(), LiquidationError> {
-    let current_time = env.ledger().timestamp();
-
-    if position.debt == 0 {
-        position.borrow_interest = 0;
-        position.last_accrual_time = current_time;
-        return Ok(());
-    }
+/// One asset of a [`LiquidationSnapshot`]
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct AssetSnapshot {
+    /// The asset (None for native XLM)
+    pub asset: Option<Address>,
+    /// Collateral deposited or debt owed in the asset
+    pub balance: i128,
+    /// Oracle price the account was valued at
+    pub price: i128,
+    /// When the asset's price feed was last updated (0 if it has none)
+    pub price_updated_at: u64,
+}
 
-    // Calculate new interest accrued using dynamic rate
-    let new_interest =
-        calculate_accrued_interest(env, position.debt, position.last_accrual_time, current_time)?;
+/// A borrower's position as it stood at the start of a liquidation
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct LiquidationSnapshot {
+    /// Sequential id, see [`get_liquidation_count`]
+    pub id: u64,
+    pub borrower: Address,
+    /// Collateral-enabled deposits, interest included
+    pub collateral: Vec<AssetSnapshot>,
+    /// Debt per borrowed asset, interest included
+    pub debt: Vec<AssetSnapshot>,
+    /// Health factor the liquidation was allowed on, scaled by 10_000
+    pub health_factor: i128,
+    pub timestamp: u64,
+}
 
-    // Add to existing interest
-    position.borrow_interest = position
-        .borrow_interest
-        .checked_add(new_interest)
-        .ok_or(LiquidationError::Overflow)?;
+/// Accrue interest on a position through the borrower's asset borrow indexes
+///
+/// With `persist` unset the borrower's index snapshots are left untouched, for
+/// read-only valuation.
+fn accrue_interest(
+    env: &Env,
+    user: &Address,
+    position: &mut Position,
+    persist: bool,
+) -> Result<(), LiquidationError> {
+    let result = if persist {
+        crate::borrow::accrue_position_interest(env, user, position)
+    } else {
+        crate::borrow::preview_position_interest(env, user, position)
+    };
+    result.map_err(|_| LiquidationError::Overflow)
+}
 
-    // Update last accrual time
-    position.last_accrual_time = current_time;
+/// Price assumed for assets the oracle has never priced: 1 XLM with 8 decimals
+///
+/// This allows liquidation to work even when prices aren't set up in tests.
+const DEFAULT_PRICE: (i128, u32) = (1_00000000i128, 8);
 
-    Ok(())
-}
+/// Decimals account values are expressed in, matching the cross-asset module
+const VALUE_DECIMALS: u32 = 7;
 
-/// Get asset price from oracle
-/// Returns price in base units (scaled by decimals)
-/// Falls back to default price if oracle doesn't have a price set
-fn get_asset_price(env: &Env, asset: &Address) -> i128 {
-    // Try to get price from oracle, but fallback to default if not available
-    // This allows liquidation to work even when prices aren't set up in tests
-    get_price(env, asset).unwrap_or(1_00000000i128) // Default: 1 XLM with 8 decimals
+/// Price and price decimals of an asset, refreshing the price cache if `persist`
+///
+/// Native XLM uses the feed of its registered token address. Assets without a
+/// price source use [`DEFAULT_PRICE`], but an asset that has one must be priced
+/// by it: a stale or otherwise unusable price fails with `PriceNotAvailable`
+/// rather than falling back, so no account is valued at an outdated price.
+fn resolve_price(
+    env: &Env,
+    asset: &Option<Address>,
+    persist: bool,
+) -> Result<(i128, u32), LiquidationError> {
+    let asset_addr = match asset {
+        Some(asset_addr) => Some(asset_addr.clone()),
+        None => env
+            .storage()
+            .persistent()
+            .get::<DepositDataKey, Address>(&DepositDataKey::NativeAssetAddress),
+    };
+    let Some(asset_addr) = asset_addr else {
+        return Ok(DEFAULT_PRICE);
+    };
+    if !crate::oracle::has_price_source(env, &asset_addr) {
+        return Ok(DEFAULT_PRICE);
+    }
+    let price = if persist {
+        get_price(env, &asset_addr)
+    } else {
+        crate::oracle::peek_price(env, &asset_addr)
+    }
+    .map_err(|_| LiquidationError::PriceNotAvailable)?;
+    let decimals = crate::oracle::get_price_decimals(env, &asset_addr).unwrap_or(DEFAULT_PRICE.1);
+    Ok((price, decimals))
 }
 
 /// Calculate collateral value in debt asset terms
@@ -156,12 +341,499 @@ fn calculate_collateral_value(
         .ok_or(LiquidationError::Overflow)
 }
 
+/// Price and price decimals used to convert between liquidated assets
+///
+/// See [`resolve_price`] for how unpriced and stale assets are handled.
+fn get_conversion_price(
+    env: &Env,
+    asset: &Option<Address>,
+) -> Result<(i128, u32), LiquidationError> {
+    resolve_price(env, asset, true)
+}
+
+/// Convert a debt amount into the collateral amount of equal oracle value
+///
+/// Solves `amount * debt_price / 10^debt_decimals =
+/// collateral * collateral_price / 10^collateral_decimals`, rounding down.
+fn convert_debt_to_collateral(
+    amount: i128,
+    (debt_price, debt_decimals): (i128, u32),
+    (collateral_price, collateral_decimals): (i128, u32),
+) -> Result<i128, LiquidationError> {
+    if collateral_price <= 0 {
+        return Err(LiquidationError::PriceNotAvailable);
+    }
+
+    let value = amount
+        .checked_mul(debt_price)
+        .ok_or(LiquidationError::Overflow)?;
+    let (numerator, denominator) = if collateral_decimals >= debt_decimals {
+        let scale = 10i128
+            .checked_pow(collateral_decimals - debt_decimals)
+            .ok_or(LiquidationError::Overflow)?;
+        (
+            value.checked_mul(scale).ok_or(LiquidationError::Overflow)?,
+            collateral_price,
+        )
+    } else {
+        let scale = 10i128
+            .checked_pow(debt_decimals - collateral_decimals)
+            .ok_or(LiquidationError::Overflow)?;
+        (
+            value,
+            collateral_price
+                .checked_mul(scale)
+                .ok_or(LiquidationError::Overflow)?,
+        )
+    };
+    numerator
+        .checked_div(denominator)
+        .ok_or(LiquidationError::Overflow)
+}
+
 /// Calculate debt value
 /// Returns debt_value = debt_amount + interest
 fn calculate_debt_value(debt: i128, interest: i128) -> Result<i128, LiquidationError> {
     debt.checked_add(interest).ok_or(LiquidationError::Overflow)
 }
 
+/// Price of an asset for valuing accounts, read without refreshing the price cache
+///
+/// See [`resolve_price`] for how unpriced and stale assets are handled.
+fn get_account_asset_price(env: &Env, asset: &Option<Address>) -> Result<i128, LiquidationError> {
+    resolve_price(env, asset, false).map(|(price, _decimals)| price)
+}
+
+/// Value of `amount` of an asset with [`VALUE_DECIMALS`] decimals
+///
+/// Prices are rescaled from their feed's decimals, as in
+/// [`convert_debt_to_collateral`], so values of assets priced at different
+/// precisions can be added up.
+fn get_account_asset_value(
+    env: &Env,
+    asset: &Option<Address>,
+    amount: i128,
+) -> Result<i128, LiquidationError> {
+    let (price, decimals) = resolve_price(env, asset, false)?;
+    let value = amount
+        .checked_mul(price)
+        .ok_or(LiquidationError::Overflow)?;
+    if decimals <= VALUE_DECIMALS {
+        let scale = 10i128
+            .checked_pow(VALUE_DECIMALS - decimals)
+            .ok_or(LiquidationError::Overflow)?;
+        value.checked_mul(scale).ok_or(LiquidationError::Overflow)
+    } else {
+        let scale = 10i128
+            .checked_pow(decimals - VALUE_DECIMALS)
+            .ok_or(LiquidationError::Overflow)?;
+        value.checked_div(scale).ok_or(LiquidationError::Overflow)
+    }
+}
+
+/// Collateral and debt value of a borrower's account in base-currency terms
+///
+/// This is the valuation [`liquidate`] checks against the liquidation threshold.
+/// Interest accrued since the last update is included without being written back.
+/// Collateral-enabled deposits are valued at their oracle price; total debt is
+/// attributed to each borrowed asset pro rata to its principal. Positions held in
+/// a single asset, or without per-asset records, are valued at that asset's price.
+/// Values carry [`VALUE_DECIMALS`] decimals whatever each feed's precision.
+///
+/// Returns `(0, 0)` for an address with no position.
+pub(crate) fn get_account_values(
+    env: &Env,
+    user: &Address,
+) -> Result<(i128, i128), LiquidationError> {
+    let mut position = match env
+        .storage()
+        .persistent()
+        .get::<DepositDataKey, Position>(&DepositDataKey::Position(user.clone()))
+    {
+        Some(position) => position,
+        None => return Ok((0, 0)),
+    };
+    accrue_interest(env, user, &mut position, false)?;
+    let total_debt = calculate_debt_value(position.debt, position.borrow_interest)?;
+    value_account(env, user, total_debt, None)
+}
+
+/// Values of `user`'s account as [`get_account_values`] would report them once
+/// `amount` more of `asset` is borrowed, bringing total debt to `total_debt`
+pub(crate) fn get_projected_account_values(
+    env: &Env,
+    user: &Address,
+    total_debt: i128,
+    asset: &Option<Address>,
+    amount: i128,
+) -> Result<(i128, i128), LiquidationError> {
+    value_account(env, user, total_debt, Some((asset, amount)))
+}
+
+/// Value `user`'s collateral and `total_debt`, optionally with a pending borrow
+/// of `(asset, amount)` added to the per-asset principals
+fn value_account(
+    env: &Env,
+    user: &Address,
+    total_debt: i128,
+    borrow: Option<(&Option<Address>, i128)>,
+) -> Result<(i128, i128), LiquidationError> {
+    let mut borrowed_assets = crate::borrow::get_user_borrowed_assets(env, user);
+    if let Some((asset, _)) = borrow {
+        if !borrowed_assets.contains(asset) {
+            borrowed_assets.push_back(asset.clone());
+        }
+    }
+    let principal_of = |asset: &Option<Address>| {
+        let principal = crate::borrow::get_user_debt_principal(env, user, asset);
+        match borrow {
+            Some((borrowed, amount)) if borrowed == asset => principal.saturating_add(amount),
+            _ => principal,
+        }
+    };
+
+    let mut total_principal: i128 = 0;
+    for asset in borrowed_assets.iter() {
+        total_principal = total_principal
+            .checked_add(principal_of(&asset))
+            .ok_or(LiquidationError::Overflow)?;
+    }
+
+    let mut collateral: Vec<(Option<Address>, i128)> = Vec::new(env);
+    let mut involved = borrowed_assets.clone();
+    for asset in crate::deposit::get_user_assets(env, user).iter() {
+        let balance = crate::deposit::get_user_deposit_balance(env, user, &asset);
+        if balance > 0 && crate::deposit::is_collateral_enabled(env, user, &asset) {
+            if !involved.contains(&asset) {
+                involved.push_back(asset.clone());
+            }
+            collateral.push_back((asset, balance));
+        }
+    }
+
+    if involved.len() < 2 || total_principal == 0 {
+        let asset = involved.first().unwrap_or(None);
+        let collateral_value = get_account_asset_value(
+            env,
+            &asset,
+            crate::deposit::get_effective_collateral(env, user),
+        )?;
+        let debt_value = get_account_asset_value(env, &asset, total_debt)?;
+        return Ok((collateral_value, debt_value));
+    }
+
+    let mut collateral_value: i128 = 0;
+    for (asset, balance) in collateral.iter() {
+        let value = get_account_asset_value(env, &asset, balance)?;
+        collateral_value = collateral_value
+            .checked_add(value)
+            .ok_or(LiquidationError::Overflow)?;
+    }
+
+    let mut debt_value: i128 = 0;
+    for asset in borrowed_assets.iter() {
+        let debt = total_debt
+            .checked_mul(principal_of(&asset))
+            .ok_or(LiquidationError::Overflow)?
+            / total_principal;
+        let value = get_account_asset_value(env, &asset, debt)?;
+        debt_value = debt_value
+            .checked_add(value)
+            .ok_or(LiquidationError::Overflow)?;
+    }
+
+    Ok((collateral_value, debt_value))
+}
+
+/// Liquidation threshold (basis points) that applies to `user`'s account
+///
+/// The user's e-mode threshold when their category covers the whole position,
+/// otherwise the global one.
+fn get_user_liquidation_threshold(env: &Env, user: &Address) -> Result<i128, LiquidationError> {
+    match crate::risk_management::get_active_emode_category(env, user, None) {
+        Some(category) => Ok(category.liquidation_threshold),
+        None => get_liquidation_threshold(env).map_err(|_| LiquidationError::Overflow),
+    }
+}
+
+/// Health factor of a borrower's account, scaled by 10_000 (1.0 = 10_000)
+///
+/// `collateral_value / (debt_value * liquidation_threshold)`, using the same
+/// valuation as [`liquidate`]: the account can be liquidated exactly when this
+/// is below 10_000. Returns `i128::MAX` when the account has no debt.
+pub fn get_health_factor(env: &Env, user: &Address) -> Result<i128, LiquidationError> {
+    get_account_health(env, user).map(|(health_factor, _, _)| health_factor)
+}
+
+/// Health factor of a borrower's account with the values it is computed from
+///
+/// # Returns
+/// `(health_factor, collateral_value, debt_value)`, as in [`get_health_factor`]
+/// and [`get_account_values`]
+pub(crate) fn get_account_health(
+    env: &Env,
+    user: &Address,
+) -> Result<(i128, i128, i128), LiquidationError> {
+    let (collateral_value, debt_value) = get_account_values(env, user)?;
+    health_from_values(env, user, collateral_value, debt_value)
+}
+
+/// [`get_account_health`] once `amount` more of `asset` is borrowed, bringing
+/// total debt to `total_debt`
+pub(crate) fn get_projected_account_health(
+    env: &Env,
+    user: &Address,
+    total_debt: i128,
+    asset: &Option<Address>,
+    amount: i128,
+) -> Result<(i128, i128, i128), LiquidationError> {
+    let (collateral_value, debt_value) =
+        get_projected_account_values(env, user, total_debt, asset, amount)?;
+    health_from_values(env, user, collateral_value, debt_value)
+}
+
+/// Health factor of an account with the given values, see [`get_health_factor`]
+fn health_from_values(
+    env: &Env,
+    user: &Address,
+    collateral_value: i128,
+    debt_value: i128,
+) -> Result<(i128, i128, i128), LiquidationError> {
+    if debt_value == 0 {
+        return Ok((i128::MAX, collateral_value, debt_value));
+    }
+    let threshold = get_user_liquidation_threshold(env, user)?;
+
+    let health_factor = collateral_value
+        .checked_mul(BASIS_POINTS * BASIS_POINTS)
+        .and_then(|v| v.checked_div(debt_value.checked_mul(threshold)?))
+        .ok_or(LiquidationError::Overflow)?;
+    Ok((health_factor, collateral_value, debt_value))
+}
+
+/// Collateral a liquidator may seize from a borrower, per asset
+///
+/// Lists every collateral-enabled deposit with a non-zero balance, each capped
+/// at the borrower's effective collateral. Positions recorded before per-asset
+/// deposits are listed as a single native entry for the aggregate balance.
+pub fn get_seizable_collateral(env: &Env, borrower: &Address) -> Vec<(Option<Address>, i128)> {
+    let effective_collateral = crate::deposit::get_effective_collateral(env, borrower);
+    let assets = crate::deposit::get_user_assets(env, borrower);
+    let mut seizable = Vec::new(env);
+    if assets.is_empty() {
+        if effective_collateral > 0 {
+            seizable.push_back((None, effective_collateral));
+        }
+        return seizable;
+    }
+
+    for asset in assets.iter() {
+        let amount = get_asset_seizable_collateral(env, borrower, &asset, effective_collateral);
+        if amount > 0 {
+            seizable.push_back((asset, amount));
+        }
+    }
+    seizable
+}
+
+/// Seizable collateral assets of a borrower, highest `liquidity_score` first
+///
+/// Used by `liquidate` in auto mode so that deep markets absorb liquidations
+/// before illiquid ones. Assets with equal scores keep the order of
+/// [`get_seizable_collateral`]; native XLM has no asset parameters and scores 0.
+pub fn get_auto_collateral_order(env: &Env, borrower: &Address) -> Vec<Option<Address>> {
+    let mut scored: Vec<(u32, Option<Address>)> = Vec::new(env);
+    for (asset, _) in get_seizable_collateral(env, borrower).iter() {
+        let score = asset
+            .as_ref()
+            .and_then(|asset_addr| {
+                env.storage()
+                    .persistent()
+                    .get::<DepositDataKey, AssetParams>(&DepositDataKey::AssetParams(
+                        asset_addr.clone(),
+                    ))
+            })
+            .map(|params| params.liquidity_score)
+            .unwrap_or(0);
+        // Insert after every asset scoring at least as high
+        let mut index = 0;
+        while index < scored.len() && scored.get_unchecked(index).0 >= score {
+            index += 1;
+        }
+        scored.insert(index, (score, asset));
+    }
+
+    let mut order = Vec::new(env);
+    for (_, asset) in scored.iter() {
+        order.push_back(asset);
+    }
+    order
+}
+
+/// Amount of one deposit that can be seized, zero if it is not collateral
+fn get_asset_seizable_collateral(
+    env: &Env,
+    borrower: &Address,
+    asset: &Option<Address>,
+    effective_collateral: i128,
+) -> i128 {
+    if !crate::deposit::is_collateral_enabled(env, borrower, asset) {
+        return 0;
+    }
+    crate::deposit::get_user_deposit_balance(env, borrower, asset)
+        .min(effective_collateral)
+        .max(0)
+}
+
+/// Most borrowers [`get_liquidatable_positions`] examines in one call
+pub const MAX_POSITIONS_PAGE: u32 = 50;
+
+/// Liquidatable borrowers in one page of the borrower index, with their health factors
+///
+/// Examines at most `limit` borrowers (capped at [`MAX_POSITIONS_PAGE`]) starting at
+/// `offset` in the index, so each call does bounded work; a page can therefore hold
+/// fewer entries than `limit`, and callers advance `offset` by `limit`. A borrower
+/// is listed when [`can_liquidate`] accepts them.
+pub fn get_liquidatable_positions(env: &Env, limit: u32, offset: u32) -> Vec<(Address, i128)> {
+    let borrowers = crate::borrow::get_borrowers(env);
+    let end = offset
+        .saturating_add(limit.min(MAX_POSITIONS_PAGE))
+        .min(borrowers.len());
+    let mut positions = Vec::new(env);
+    for index in offset..end {
+        let borrower = borrowers.get_unchecked(index);
+        if let Ok((true, health_factor)) = can_liquidate(env, &borrower) {
+            positions.push_back((borrower, health_factor));
+        }
+    }
+    positions
+}
+
+/// Whether a borrower's account can be liquidated, with its health factor
+///
+/// Prices all collateral and debt at oracle prices, as the borrow and withdraw
+/// checks do, and compares them against the liquidation threshold (the e-mode
+/// threshold for accounts in e-mode). The account is liquidatable when the
+/// health factor is below 10_000 and any grace window has expired, or when a
+/// repayment schedule is delinquent.
+///
+/// # Returns
+/// `(liquidatable, health_factor)`, the health factor as in [`get_health_factor`]
+pub fn can_liquidate(env: &Env, borrower: &Address) -> Result<(bool, i128), LiquidationError> {
+    let (_, liquidatable, health_factor) = get_liquidation_status(env, borrower)?;
+    Ok((liquidatable, health_factor))
+}
+
+/// Whether the account is below the liquidation threshold, whether it can be
+/// liquidated now, and its health factor
+fn get_liquidation_status(
+    env: &Env,
+    borrower: &Address,
+) -> Result<(bool, bool, i128), LiquidationError> {
+    let (health_factor, _, debt_value) = get_account_health(env, borrower)?;
+    let undercollateralized = debt_value > 0 && health_factor < BASIS_POINTS;
+    if crate::repayment_schedule::is_delinquent(env, borrower) {
+        return Ok((undercollateralized, true, health_factor));
+    }
+    if !undercollateralized {
+        return Ok((false, false, health_factor));
+    }
+
+    let (grace_secs, immediate_health_factor) = get_liquidation_grace(env);
+    let liquidatable = grace_secs == 0
+        || health_factor < immediate_health_factor
+        || get_liquidation_breach(env, borrower).is_some_and(|breached_at| {
+            env.ledger().timestamp() >= breached_at.saturating_add(grace_secs)
+        });
+    Ok((true, liquidatable, health_factor))
+}
+
+/// Record or clear the borrower's liquidation threshold breach
+///
+/// Starts the grace window the first time the account is seen below the
+/// liquidation threshold, and clears the record once it is back above. Anyone
+/// may call this, typically a keeper right after a price update, since a
+/// rejected liquidation cannot record the breach itself.
+///
+/// # Returns
+/// When the current breach was first recorded, or `None` if the account is healthy
+pub fn sync_liquidation_breach(
+    env: &Env,
+    borrower: &Address,
+) -> Result<Option<u64>, LiquidationError> {
+    let (undercollateralized, _, _) = get_liquidation_status(env, borrower)?;
+    let recorded = get_liquidation_breach(env, borrower);
+    let breached_at = match (undercollateralized, recorded) {
+        (false, _) => None,
+        (true, Some(breached_at)) => Some(breached_at),
+        (true, None) => Some(env.ledger().timestamp()),
+    };
+    if breached_at != recorded {
+        set_liquidation_breach(env, borrower, breached_at);
+    }
+    Ok(breached_at)
+}
+
+/// Whether the borrower was liquidated within the cooldown window
+///
+/// Accounts below the immediate liquidation health factor are never cooling down.
+fn is_liquidation_cooling_down(env: &Env, borrower: &Address, health_factor: i128) -> bool {
+    let cooldown = get_liquidation_cooldown_ledgers(env);
+    if cooldown == 0 {
+        return false;
+    }
+    let (_, immediate_health_factor) = get_liquidation_grace(env);
+    if health_factor < immediate_health_factor {
+        return false;
+    }
+    get_last_liquidation_ledger(env, borrower)
+        .is_some_and(|last| env.ledger().sequence() < last.saturating_add(cooldown))
+}
+
+/// Clear the borrower's breach record if their account has recovered
+///
+/// Cheap for accounts without a record; called after operations that can only
+/// improve the account's health.
+pub(crate) fn clear_recovered_breach(env: &Env, borrower: &Address) {
+    if get_liquidation_breach(env, borrower).is_some() {
+        let _ = sync_liquidation_breach(env, borrower);
+    }
+}
+
+/// Whether the borrower's total debt value is below the close factor dust threshold
+fn is_dust_position(env: &Env, borrower: &Address) -> Result<bool, LiquidationError> {
+    let threshold = crate::risk_params::get_close_factor_dust_threshold(env);
+    if threshold == 0 {
+        return Ok(false);
+    }
+    let (_, debt_value) = get_account_values(env, borrower)?;
+    Ok(debt_value < threshold)
+}
+
+/// Borrowing headroom and shortfall of a borrower's account in base-currency terms
+///
+/// Measured against the debt the collateral can carry before the account falls
+/// below the liquidation threshold, using the same valuation as [`liquidate`].
+/// At most one of the two is non-zero; a positive shortfall means the account
+/// can be liquidated.
+///
+/// # Returns
+/// `(liquidity, shortfall)`
+pub fn get_account_liquidity(env: &Env, user: &Address) -> Result<(i128, i128), LiquidationError> {
+    let (collateral_value, debt_value) = get_account_values(env, user)?;
+    let threshold = get_user_liquidation_threshold(env, user)?;
+
+    let max_debt = collateral_value
+        .checked_mul(BASIS_POINTS)
+        .ok_or(LiquidationError::Overflow)?
+        / threshold;
+    if debt_value > max_debt {
+        Ok((0, debt_value - max_debt))
+    } else {
+        Ok((max_debt - debt_value, 0))
+    }
+}
+
 /// Liquidate an undercollateralized position
 ///
 /// Allows liquidators to liquidate undercollateralized positions by:
@@ -174,27 +846,52 @@ fn calculate_debt_value(debt: i128, interest: i128) -> Result<i128, LiquidationE
 /// * `borrower` - The address of the borrower being liquidated
 /// * `debt_asset` - The address of the debt asset to repay (None for native XLM)
 /// * `collateral_asset` - The address of the collateral asset to receive (None for native XLM)
-/// * `debt_amount` - The amount of debt to liquidate
+/// * `debt_amount` - The amount of debt to liquidate, at most the close factor of
+///   the borrower's debt in `debt_asset`
 ///
 /// # Returns
-/// Returns a tuple (debt_liquidated, collateral_seized, incentive_amount)
+/// Returns a tuple (debt_liquidated, collateral_seized, incentive_amount,
+/// protocol_fee), where `debt_liquidated` is the amount actually repaid (reduced
+/// in proportion when the collateral cannot cover it plus the bonus),
+/// `collateral_seized` is the collateral taken from the borrower,
+/// `incentive_amount` is the part of it paid as bonus and `protocol_fee` the part
+/// of the bonus credited to the reserve, all in collateral units. The rest of
+/// `collateral_seized` goes to the liquidator.
 ///
 /// # Errors
+/// * `LiquidationError::LiquidatorNotAllowed` - If the allowlist is enabled and the
+///   liquidator is not on it
 /// * `LiquidationError::InvalidAmount` - If amount is zero or negative
-/// * `LiquidationError::NotLiquidatable` - If position is not undercollateralized
+/// * `LiquidationError::SelfLiquidationNotAllowed` - If the liquidator is the borrower
+/// * `LiquidationError::PositionHealthy` - If [`can_liquidate`] rejects the position
+/// * `LiquidationError::GracePeriodActive` - If the position is below the threshold
+///   but its grace window has not expired, see [`sync_liquidation_breach`]
+/// * `LiquidationError::LiquidationCooldown` - If the borrower was liquidated within
+///   the last `liquidation_cooldown_ledgers` ledgers
+/// * `LiquidationError::NotLiquidatable` - If the borrower has no position
 /// * `LiquidationError::LiquidationPaused` - If liquidations are paused
+/// * `LiquidationError::EmergencyPaused` - If the protocol is under emergency pause
 /// * `LiquidationError::ExceedsCloseFactor` - If liquidation exceeds close factor limit
+/// * `LiquidationError::InvalidDebtAsset` - If the borrower owes nothing in `debt_asset`
+/// * `LiquidationError::InvalidCollateralAsset` - If the borrower holds no collateral-enabled
+///   balance in `collateral_asset`, see [`get_seizable_collateral`]
+/// * `LiquidationError::InsufficientCollateral` - If a position without per-asset
+///   deposits has no collateral left
 /// * `LiquidationError::InsufficientBalance` - If liquidator doesn't have enough balance
 /// * `LiquidationError::Overflow` - If calculation overflow occurs
 ///
+/// With `auto_collateral` set and no `collateral_asset` named, the collateral is
+/// the first asset of [`get_auto_collateral_order`]; a named asset always wins.
+///
 /// # Security
 /// * Validates liquidation amount > 0
 /// * Checks pause switches
-/// * Validates position is undercollateralized
+/// * Validates position is undercollateralized via [`can_liquidate`]
 /// * Enforces close factor limits
 /// * Accrues interest before liquidation
 /// * Transfers debt asset from liquidator to contract
 /// * Transfers collateral asset from contract to liquidator (with incentive)
+/// * Credits the protocol share of the incentive to the collateral reserve
 /// * Updates debt and collateral balances
 /// * Emits events for tracking
 /// * Updates analytics
@@ -205,25 +902,488 @@ pub fn liquidate(
     debt_asset: Option<Address>,
     collateral_asset: Option<Address>,
     debt_amount: i128,
-) -> Result<(i128, i128, i128), LiquidationError> {
-    // Validate amount
-    if debt_amount <= 0 {
+    auto_collateral: bool,
+) -> Result<(i128, i128, i128, i128), LiquidationError> {
+    let collateral_asset = if auto_collateral && collateral_asset.is_none() {
+        get_auto_collateral_order(env, &borrower)
+            .first()
+            .ok_or(LiquidationError::InvalidCollateralAsset)?
+    } else {
+        collateral_asset
+    };
+    execute_liquidation(
+        env,
+        &liquidator,
+        &liquidator,
+        &borrower,
+        &debt_asset,
+        &collateral_asset,
+        debt_amount,
+        false,
+    )
+}
+
+/// Liquidate a position while liquidations are paused (admin only)
+///
+/// Incident-response override of [`liquidate`] with the admin as liquidator:
+/// the `pause_liquidate` switch, the emergency pause and the liquidator
+/// allowlist are ignored, every other check applies. Emits a
+/// `ForcedLiquidationEvent` recording which pauses were overridden.
+///
+/// # Returns
+/// The same tuple as [`liquidate`]
+///
+/// # Errors
+/// * `LiquidationError::Unauthorized` - If `admin` is not the protocol admin
+/// * Any error of [`liquidate`] other than the pause and allowlist errors
+pub fn force_liquidate(
+    env: &Env,
+    admin: Address,
+    borrower: Address,
+    debt_asset: Option<Address>,
+    collateral_asset: Option<Address>,
+    debt_amount: i128,
+) -> Result<(i128, i128, i128, i128), LiquidationError> {
+    admin.require_auth();
+    crate::admin::require_admin(env, &admin).map_err(|_| LiquidationError::Unauthorized)?;
+
+    let result = execute_liquidation(
+        env,
+        &admin,
+        &admin,
+        &borrower,
+        &debt_asset,
+        &collateral_asset,
+        debt_amount,
+        true,
+    )?;
+    let (debt_liquidated, collateral_seized, _, _) = result;
+    emit_forced_liquidation(
+        env,
+        ForcedLiquidationEvent {
+            admin,
+            borrower,
+            debt_asset,
+            collateral_asset,
+            debt_liquidated,
+            collateral_seized,
+            emergency_paused: is_emergency_paused(env),
+            operation_paused: is_operation_paused(env, Symbol::new(env, "pause_liquidate")),
+            timestamp: env.ledger().timestamp(),
+        },
+    );
+    Ok(result)
+}
+
+/// Liquidate and swap the seized collateral into the debt asset
+///
+/// For keepers without inventory in the collateral asset. Runs [`liquidate`],
+/// keeping the liquidator's share of the seized collateral here, and calls the
+/// `auto_swap_for_collateral` of `amm_contract` on behalf of this contract,
+/// authorizing it to pull exactly that share; the debt asset that arrives is
+/// forwarded to the liquidator. Only tokens actually received count towards
+/// `min_out`, whatever the AMM reports. Both assets must be tokens and differ,
+/// and `amm_contract` must be the AMM registered with
+/// [`crate::risk_management::set_liquidation_amm`].
+///
+/// # Returns
+/// The tuple of [`liquidate`] followed by the debt asset paid to the liquidator
+///
+/// # Errors
+/// * `LiquidationError::InvalidAmount` - If `debt_amount` or `min_out` is not positive
+/// * `LiquidationError::InvalidDebtAsset` - If the debt asset is native XLM
+/// * `LiquidationError::InvalidCollateralAsset` - If the collateral asset is
+///   native XLM or the debt asset
+/// * `LiquidationError::SwapFailed` - If `amm_contract` is not the registered
+///   AMM or rejects the swap
+/// * `LiquidationError::SlippageExceeded` - If less than `min_out` is received
+/// * Any error of [`liquidate`]
+#[allow(clippy::too_many_arguments)]
+pub fn liquidate_and_swap(
+    env: &Env,
+    liquidator: Address,
+    borrower: Address,
+    debt_asset: Option<Address>,
+    collateral_asset: Option<Address>,
+    debt_amount: i128,
+    amm_contract: Address,
+    min_out: i128,
+) -> Result<(i128, i128, i128, i128, i128), LiquidationError> {
+    liquidator.require_auth();
+
+    if min_out <= 0 {
         return Err(LiquidationError::InvalidAmount);
     }
+    let debt_addr = debt_asset
+        .clone()
+        .ok_or(LiquidationError::InvalidDebtAsset)?;
+    let collateral_addr = match collateral_asset {
+        Some(ref addr) if collateral_asset != debt_asset => addr.clone(),
+        _ => return Err(LiquidationError::InvalidCollateralAsset),
+    };
+    // The seized collateral is handed to the AMM, so only a vetted one may get it
+    if get_liquidation_amm(env).as_ref() != Some(&amm_contract) {
+        return Err(LiquidationError::SwapFailed);
+    }
+    let this = env.current_contract_address();
+
+    let (debt_liquidated, collateral_seized, incentive_amount, protocol_fee) = execute_liquidation(
+        env,
+        &liquidator,
+        &this,
+        &borrower,
+        &debt_asset,
+        &collateral_asset,
+        debt_amount,
+        false,
+    )?;
+
+    // The AMM pulls the collateral from and pays this contract, so the output
+    // can be measured before release
+    let swap_amount = collateral_seized - protocol_fee;
+    env.authorize_as_current_contract(soroban_sdk::vec![
+        env,
+        InvokerContractAuthEntry::Contract(SubContractInvocation {
+            context: ContractContext {
+                contract: collateral_addr,
+                fn_name: Symbol::new(env, "transfer"),
+                args: (this.clone(), amm_contract.clone(), swap_amount).into_val(env),
+            },
+            sub_invocations: Vec::new(env),
+        }),
+    ]);
+    let debt_token = soroban_sdk::token::Client::new(env, &debt_addr);
+    let balance_before = debt_token.balance(&this);
+    stellarlend_amm::AmmContractClient::new(env, &amm_contract)
+        .try_auto_swap_for_collateral(&this, &collateral_asset, &debt_asset, &swap_amount)
+        .map_err(|_| LiquidationError::SwapFailed)?
+        .map_err(|_| LiquidationError::SwapFailed)?;
+    let amount_out = debt_token
+        .balance(&this)
+        .checked_sub(balance_before)
+        .ok_or(LiquidationError::Overflow)?;
+    if amount_out < min_out {
+        return Err(LiquidationError::SlippageExceeded);
+    }
+    debt_token.transfer(&this, &liquidator, &amount_out);
 
-    // Check emergency pause
-    if is_emergency_paused(env) {
-        return Err(LiquidationError::LiquidationPaused);
+    Ok((
+        debt_liquidated,
+        collateral_seized,
+        incentive_amount,
+        protocol_fee,
+        amount_out,
+    ))
+}
+
+/// Single-collateral liquidation shared by [`liquidate`], [`force_liquidate`] and
+/// [`liquidate_and_swap`], sending the seized collateral to `collateral_recipient`
+#[allow(clippy::too_many_arguments)]
+fn execute_liquidation(
+    env: &Env,
+    liquidator: &Address,
+    collateral_recipient: &Address,
+    borrower: &Address,
+    debt_asset: &Option<Address>,
+    collateral_asset: &Option<Address>,
+    debt_amount: i128,
+    forced: bool,
+) -> Result<(i128, i128, i128, i128), LiquidationError> {
+    let (mut position, tracked_borrows, seizure) = plan_liquidation(
+        env,
+        liquidator,
+        borrower,
+        debt_asset,
+        collateral_asset,
+        debt_amount,
+        true,
+        forced,
+    )?;
+    let timestamp = env.ledger().timestamp();
+
+    let (debt_liquidated, collateral_seized, incentive_amount, protocol_fee) = execute_seizure(
+        env,
+        liquidator,
+        collateral_recipient,
+        borrower,
+        &mut position,
+        debt_asset,
+        collateral_asset,
+        seizure,
+        timestamp,
+    )?;
+    if !forced {
+        pay_liquidation_reward_top_up(
+            env,
+            liquidator,
+            borrower,
+            collateral_asset,
+            incentive_amount - protocol_fee,
+            timestamp,
+        )?;
     }
 
-    // Check if liquidations are paused
-    require_operation_not_paused(env, Symbol::new(env, "pause_liquidate")).map_err(
-        |e| match e {
-            RiskManagementError::OperationPaused => LiquidationError::LiquidationPaused,
-            RiskManagementError::EmergencyPaused => LiquidationError::LiquidationPaused,
-            _ => LiquidationError::LiquidationPaused,
+    finish_liquidation(
+        env,
+        liquidator,
+        borrower,
+        debt_asset,
+        &mut position,
+        tracked_borrows,
+        debt_liquidated,
+        collateral_seized,
+        timestamp,
+    )?;
+
+    Ok((
+        debt_liquidated,
+        collateral_seized,
+        incentive_amount,
+        protocol_fee,
+    ))
+}
+
+/// Check whether [`liquidate`] would succeed, and with what result, without liquidating
+///
+/// Runs the same checks and calculation as the real liquidation, including the
+/// liquidator's balance, and writes nothing to storage. Amounts are only filled
+/// in when the liquidation would succeed.
+pub fn simulate_liquidation(
+    env: &Env,
+    liquidator: Address,
+    borrower: Address,
+    debt_asset: Option<Address>,
+    collateral_asset: Option<Address>,
+    debt_amount: i128,
+) -> LiquidationSimulation {
+    match plan_liquidation(
+        env,
+        &liquidator,
+        &borrower,
+        &debt_asset,
+        &collateral_asset,
+        debt_amount,
+        false,
+        false,
+    ) {
+        Ok((_, _, (debt_repaid, collateral_seized, incentive_amount, protocol_fee))) => {
+            LiquidationSimulation {
+                would_succeed: true,
+                error_code: 0,
+                debt_repaid,
+                collateral_seized,
+                incentive_amount,
+                protocol_fee,
+            }
+        }
+        Err(err) => LiquidationSimulation {
+            would_succeed: false,
+            error_code: err as u32,
+            debt_repaid: 0,
+            collateral_seized: 0,
+            incentive_amount: 0,
+            protocol_fee: 0,
         },
+    }
+}
+
+/// Checks and seizure of a single-collateral liquidation, see [`plan_seizure`]
+///
+/// With `persist` unset nothing is written to storage; `forced` selects
+/// [`LiquidationKind::Forced`] over [`LiquidationKind::Keeper`].
+#[allow(clippy::too_many_arguments)]
+fn plan_liquidation(
+    env: &Env,
+    liquidator: &Address,
+    borrower: &Address,
+    debt_asset: &Option<Address>,
+    collateral_asset: &Option<Address>,
+    debt_amount: i128,
+    persist: bool,
+    forced: bool,
+) -> Result<(Position, bool, (i128, i128, i128, i128)), LiquidationError> {
+    if let Some(ref collateral_addr) = collateral_asset {
+        if collateral_addr == &env.current_contract_address() {
+            return Err(LiquidationError::InvalidCollateralAsset);
+        }
+    }
+
+    let kind = if forced {
+        LiquidationKind::Forced
+    } else {
+        LiquidationKind::Keeper
+    };
+    let (position, tracked_borrows, debt_amount) = prepare_liquidation(
+        env,
+        liquidator,
+        borrower,
+        debt_asset,
+        collateral_asset,
+        debt_amount,
+        persist,
+        kind,
+    )?;
+    let seizure = plan_seizure(
+        env,
+        liquidator,
+        borrower,
+        debt_asset,
+        collateral_asset,
+        debt_amount,
     )?;
+    Ok((position, tracked_borrows, seizure))
+}
+
+/// Liquidate a position against several collateral assets in one call
+///
+/// Repays up to `debt_amount` of the borrower's `debt_asset` debt under the same
+/// checks and close factor as [`liquidate`], then seizes the repaid value plus
+/// incentive from the assets in `collateral_order`, in order: each asset is
+/// drained before the next is touched. Every asset seized from emits its own
+/// liquidation event. If the listed collateral cannot cover the whole amount,
+/// only the debt it covers is repaid.
+///
+/// # Returns
+/// Returns the debt repaid and the collateral seized from each asset, as
+/// (asset, amount) pairs in the order given
+///
+/// # Errors
+/// * `LiquidationError::EmptyCollateralOrder` - If `collateral_order` is empty
+/// * `LiquidationError::InvalidCollateralAsset` - If a listed asset has no
+///   collateral-enabled balance by the time it is reached
+/// * Any error of [`liquidate`]
+pub fn liquidate_multi(
+    env: &Env,
+    liquidator: Address,
+    borrower: Address,
+    debt_asset: Option<Address>,
+    debt_amount: i128,
+    collateral_order: Vec<Option<Address>>,
+) -> Result<(i128, Vec<(Option<Address>, i128)>), LiquidationError> {
+    if collateral_order.is_empty() {
+        return Err(LiquidationError::EmptyCollateralOrder);
+    }
+    for collateral_asset in collateral_order.iter() {
+        if collateral_asset == Some(env.current_contract_address()) {
+            return Err(LiquidationError::InvalidCollateralAsset);
+        }
+    }
+
+    let (mut position, tracked_borrows, max_debt) = prepare_liquidation(
+        env,
+        &liquidator,
+        &borrower,
+        &debt_asset,
+        &collateral_order.get_unchecked(0),
+        debt_amount,
+        true,
+        LiquidationKind::Keeper,
+    )?;
+    let timestamp = env.ledger().timestamp();
+
+    let mut debt_repaid = 0i128;
+    let mut collateral_seized = 0i128;
+    let mut seized = Vec::new(env);
+    for collateral_asset in collateral_order.iter() {
+        let remaining = max_debt - debt_repaid;
+        if remaining <= 0 {
+            break;
+        }
+        let (leg_debt, leg_collateral, _incentive, _protocol_fee) = seize_collateral(
+            env,
+            &liquidator,
+            &borrower,
+            &mut position,
+            &debt_asset,
+            &collateral_asset,
+            remaining,
+            timestamp,
+        )?;
+        debt_repaid = debt_repaid
+            .checked_add(leg_debt)
+            .ok_or(LiquidationError::Overflow)?;
+        collateral_seized = collateral_seized
+            .checked_add(leg_collateral)
+            .ok_or(LiquidationError::Overflow)?;
+        seized.push_back((collateral_asset, leg_collateral));
+    }
+
+    finish_liquidation(
+        env,
+        &liquidator,
+        &borrower,
+        &debt_asset,
+        &mut position,
+        tracked_borrows,
+        debt_repaid,
+        collateral_seized,
+        timestamp,
+    )?;
+
+    Ok((debt_repaid, seized))
+}
+
+/// Which entrypoint a liquidation comes from, deciding the checks
+/// [`prepare_liquidation`] skips
+#[derive(Clone, Copy, PartialEq)]
+enum LiquidationKind {
+    /// A keeper liquidating through [`liquidate`] and its variants
+    Keeper,
+    /// The admin override of [`force_liquidate`]: pauses and the liquidator
+    /// allowlist are not checked
+    Forced,
+    /// The borrower deleveraging through [`self_liquidate`]: allowed on healthy
+    /// positions and outside the allowlist and cooldown
+    SelfLiquidation,
+}
+
+/// Checks shared by [`liquidate`], [`liquidate_multi`] and [`self_liquidate`]
+///
+/// Validates the call, the borrower's eligibility and the first collateral asset
+/// to seize from, accrues interest on the position and enforces the close factor.
+/// With `persist` unset nothing is written to storage. `kind` relaxes the
+/// checks as described on [`LiquidationKind`].
+///
+/// # Returns
+/// The borrower's position, whether their borrows are tracked per asset, and the
+/// debt that may be repaid
+#[allow(clippy::too_many_arguments)]
+fn prepare_liquidation(
+    env: &Env,
+    liquidator: &Address,
+    borrower: &Address,
+    debt_asset: &Option<Address>,
+    collateral_asset: &Option<Address>,
+    debt_amount: i128,
+    persist: bool,
+    kind: LiquidationKind,
+) -> Result<(Position, bool, i128), LiquidationError> {
+    // During a phased launch only vetted keepers may liquidate
+    if kind == LiquidationKind::Keeper && !can_liquidator_act(env, liquidator) {
+        return Err(LiquidationError::LiquidatorNotAllowed);
+    }
+
+    // Validate amount
+    if debt_amount <= 0 {
+        return Err(LiquidationError::InvalidAmount);
+    }
+
+    if kind != LiquidationKind::SelfLiquidation && liquidator == borrower {
+        return Err(LiquidationError::SelfLiquidationNotAllowed);
+    }
+
+    // Both pauses block liquidations unless the admin forces one through
+    if kind != LiquidationKind::Forced {
+        if is_emergency_paused(env) {
+            return Err(LiquidationError::EmergencyPaused);
+        }
+        require_operation_not_paused(env, Symbol::new(env, "pause_liquidate")).map_err(
+            |e| match e {
+                RiskManagementError::EmergencyPaused => LiquidationError::EmergencyPaused,
+                _ => LiquidationError::LiquidationPaused,
+            },
+        )?;
+    }
 
     // Validate assets
     if let Some(ref debt_addr) = debt_asset {
@@ -232,14 +1392,37 @@ pub fn liquidate(
         }
     }
 
-    if let Some(ref collateral_addr) = collateral_asset {
-        if collateral_addr == &env.current_contract_address() {
-            return Err(LiquidationError::InvalidCollateralAsset);
+    // Bring both assets' interest indexes up to date before anything is valued
+    if persist {
+        for asset in [debt_asset, collateral_asset] {
+            crate::interest_rate::accrue_interest(env, asset.clone())
+                .map_err(|_| LiquidationError::Overflow)?;
         }
     }
 
-    // Get current timestamp
-    let timestamp = env.ledger().timestamp();
+    // Eligibility is decided on the whole account, independent of the chosen pair.
+    // A borrower may deleverage whatever their health.
+    let (undercollateralized, liquidatable, health_factor) = get_liquidation_status(env, borrower)?;
+    if kind != LiquidationKind::SelfLiquidation {
+        if !liquidatable {
+            return Err(if undercollateralized {
+                LiquidationError::GracePeriodActive
+            } else {
+                LiquidationError::PositionHealthy
+            });
+        }
+        if is_liquidation_cooling_down(env, borrower, health_factor) {
+            return Err(LiquidationError::LiquidationCooldown);
+        }
+    }
+
+    // The close factor applies to the debt in the chosen asset, measured before
+    // this call books accrued interest on the position
+    let tracked_borrows = !crate::borrow::get_user_borrowed_assets(env, borrower).is_empty();
+    let asset_debt = crate::borrow::get_user_debt(env, borrower, debt_asset);
+    if tracked_borrows && asset_debt <= 0 {
+        return Err(LiquidationError::InvalidDebtAsset);
+    }
 
     // Get borrower position
     let position_key = DepositDataKey::Position(borrower.clone());
@@ -250,129 +1433,278 @@ pub fn liquidate(
         .ok_or(LiquidationError::NotLiquidatable)?;
 
     // Accrue interest before liquidation
-    accrue_interest(env, &mut position)?;
+    accrue_interest(env, borrower, &mut position, persist)?;
 
-    // Get collateral balance
-    let collateral_key = DepositDataKey::CollateralBalance(borrower.clone());
-    let collateral_balance = env
-        .storage()
-        .persistent()
-        .get::<DepositDataKey, i128>(&collateral_key)
-        .unwrap_or(0);
+    // Only the named collateral is touched, up to the borrower's balance in it
+    get_seizable_collateral_in(env, borrower, collateral_asset)?;
 
-    // Calculate total debt (principal + interest)
+    // Positions from before per-asset tracking are measured against their total debt
     let total_debt = calculate_debt_value(position.debt, position.borrow_interest)?;
-
-    // Get asset prices and calculate collateral value
-    // For native XLM (None), both assets are the same, so use 1:1 ratio
-    // For token assets, use oracle prices to convert between assets
-    let collateral_value = if debt_asset.is_none() && collateral_asset.is_none() {
-        // Both are native XLM - no price conversion needed
-        collateral_balance
+    let close_factor_base = if tracked_borrows {
+        asset_debt
     } else {
-        // Need to convert between different assets using prices
-        let debt_price = if let Some(ref debt_addr) = debt_asset {
-            get_asset_price(env, debt_addr)
-        } else {
-            // Default price for native XLM (1:1, no decimals)
-            1i128
-        };
+        total_debt
+    };
 
-        let collateral_price = if let Some(ref collateral_addr) = collateral_asset {
-            get_asset_price(env, collateral_addr)
-        } else {
-            // Default price for native XLM (1:1, no decimals)
-            1i128
-        };
+    // Validate liquidation amount doesn't exceed close factor
+    if debt_amount > get_max_liquidatable(env, borrower, close_factor_base)? {
+        return Err(LiquidationError::ExceedsCloseFactor);
+    }
+
+    if persist {
+        record_liquidation_snapshot(env, borrower, &position, health_factor)?;
+    }
 
-        // Calculate collateral value in debt asset terms
-        calculate_collateral_value(collateral_balance, collateral_price, debt_price)?
+    // Ensure we don't liquidate more than the debt
+    Ok((
+        position,
+        tracked_borrows,
+        debt_amount.min(close_factor_base),
+    ))
+}
+
+/// Store a [`LiquidationSnapshot`] of the borrower, evicting the oldest one
+/// beyond the configured capacity
+fn record_liquidation_snapshot(
+    env: &Env,
+    borrower: &Address,
+    position: &Position,
+    health_factor: i128,
+) -> Result<(), LiquidationError> {
+    let snapshot_of = |asset: Option<Address>, balance: i128| {
+        let asset_addr = asset.clone().or_else(|| {
+            env.storage()
+                .persistent()
+                .get::<DepositDataKey, Address>(&DepositDataKey::NativeAssetAddress)
+        });
+        Ok::<_, LiquidationError>(AssetSnapshot {
+            price: get_account_asset_price(env, &asset)?,
+            price_updated_at: asset_addr
+                .and_then(|asset_addr| crate::oracle::get_price_updated_at(env, &asset_addr))
+                .unwrap_or(0),
+            asset,
+            balance,
+        })
     };
 
-    // Check if position can be liquidated
-    let can_liquidate = can_be_liquidated(env, collateral_value, total_debt)
-        .map_err(|_| LiquidationError::NotLiquidatable)?;
+    // Positions from before per-asset tracking are recorded as native totals
+    let mut collateral = Vec::new(env);
+    let assets = crate::deposit::get_user_assets(env, borrower);
+    for asset in assets.iter() {
+        let balance = crate::deposit::get_user_deposit_balance(env, borrower, &asset);
+        if balance > 0 && crate::deposit::is_collateral_enabled(env, borrower, &asset) {
+            collateral.push_back(snapshot_of(asset, balance)?);
+        }
+    }
+    if assets.is_empty() {
+        collateral.push_back(snapshot_of(
+            None,
+            crate::deposit::get_effective_collateral(env, borrower),
+        )?);
+    }
+    let mut debt = Vec::new(env);
+    let borrowed_assets = crate::borrow::get_user_borrowed_assets(env, borrower);
+    for asset in borrowed_assets.iter() {
+        let balance = crate::borrow::get_user_debt(env, borrower, &asset);
+        debt.push_back(snapshot_of(asset, balance)?);
+    }
+    if borrowed_assets.is_empty() {
+        let total_debt = calculate_debt_value(position.debt, position.borrow_interest)?;
+        debt.push_back(snapshot_of(None, total_debt)?);
+    }
 
-    if !can_liquidate {
-        return Err(LiquidationError::NotLiquidatable);
+    let id = get_liquidation_count(env)
+        .checked_add(1)
+        .ok_or(LiquidationError::Overflow)?;
+    let storage = env.storage().persistent();
+    storage.set(
+        &RiskDataKey::LiquidationSnapshot(id),
+        &LiquidationSnapshot {
+            id,
+            borrower: borrower.clone(),
+            collateral,
+            debt,
+            health_factor,
+            timestamp: env.ledger().timestamp(),
+        },
+    );
+    storage.set(&RiskDataKey::LiquidationCount, &id);
+    let capacity = u64::from(get_liquidation_snapshot_capacity(env));
+    if id > capacity {
+        storage.remove(&RiskDataKey::LiquidationSnapshot(id - capacity));
     }
+    Ok(())
+}
 
-    // Get maximum liquidatable amount (close factor)
-    let max_liquidatable =
-        get_max_liquidatable_amount(env, total_debt).map_err(|_| LiquidationError::Overflow)?;
+/// Number of liquidation snapshots recorded so far, which is also the id of the
+/// latest
+pub fn get_liquidation_count(env: &Env) -> u64 {
+    env.storage()
+        .persistent()
+        .get::<RiskDataKey, u64>(&RiskDataKey::LiquidationCount)
+        .unwrap_or(0)
+}
 
-    // Validate liquidation amount doesn't exceed close factor
-    if debt_amount > max_liquidatable {
-        return Err(LiquidationError::ExceedsCloseFactor);
+/// Position snapshot taken at the start of liquidation `id`
+///
+/// Only the latest `liquidation_snapshot_capacity` snapshots are kept; older
+/// ids return `None`.
+pub fn get_liquidation_snapshot(env: &Env, id: u64) -> Option<LiquidationSnapshot> {
+    let capacity = u64::from(get_liquidation_snapshot_capacity(env));
+    if id == 0 || id.saturating_add(capacity) <= get_liquidation_count(env) {
+        return None;
     }
+    env.storage()
+        .persistent()
+        .get::<RiskDataKey, LiquidationSnapshot>(&RiskDataKey::LiquidationSnapshot(id))
+}
 
-    // Ensure we don't liquidate more than total debt
-    let actual_debt_liquidated = if debt_amount > total_debt {
-        total_debt
-    } else {
-        debt_amount
-    };
+/// Collateral to seize from one asset for `debt_amount` of debt, without
+/// moving anything
+///
+/// Checks that the liquidator can pay and the contract holds the collateral.
+///
+/// # Returns
+/// (debt_repaid, collateral_seized, incentive_amount, protocol_fee), less than
+/// asked for when the borrower's collateral in the asset runs out
+fn plan_seizure(
+    env: &Env,
+    liquidator: &Address,
+    borrower: &Address,
+    debt_asset: &Option<Address>,
+    collateral_asset: &Option<Address>,
+    debt_amount: i128,
+) -> Result<(i128, i128, i128, i128), LiquidationError> {
+    // Only the named collateral is touched, up to the borrower's balance in it
+    let seizable_collateral = get_seizable_collateral_in(env, borrower, collateral_asset)?;
 
-    // Calculate liquidation incentive
-    let incentive_bps = get_liquidation_incentive(env).map_err(|_| LiquidationError::Overflow)?;
-    let incentive_amount = get_liquidation_incentive_amount(env, actual_debt_liquidated)
-        .map_err(|_| LiquidationError::Overflow)?;
+    // Convert the repaid debt into collateral of equal oracle value
+    let collateral_value_liquidated =
+        convert_debt_to_collateral_asset(env, debt_asset, collateral_asset, debt_amount)?;
 
-    // Calculate collateral to seize
-    // Liquidator repays debt_liquidated amount of debt asset
-    // Liquidator receives collateral worth debt_liquidated (in debt terms) + incentive
-    // collateral_seized = (debt_liquidated * debt_price / collateral_price) * (1 + incentive_bps / 10000)
-    // First, convert debt amount to collateral terms: debt_liquidated * debt_price / collateral_price
-    let collateral_value_liquidated = if debt_asset.is_none() && collateral_asset.is_none() {
-        // Both are native XLM - no price conversion needed
-        actual_debt_liquidated
-    } else {
-        // Need to convert between different assets using prices
-        let debt_price = if let Some(ref debt_addr) = debt_asset {
-            get_asset_price(env, debt_addr)
-        } else {
-            1i128 // Native XLM
-        };
+    // The liquidator's bonus is incentive_bps of that value, paid in collateral
+    let incentive_bps = get_liquidation_incentive(env).map_err(|_| LiquidationError::Overflow)?;
+    let bonus = collateral_value_liquidated
+        .checked_mul(incentive_bps)
+        .ok_or(LiquidationError::Overflow)?
+        .checked_div(10000)
+        .ok_or(LiquidationError::Overflow)?;
+    let collateral_seized = collateral_value_liquidated
+        .checked_add(bonus)
+        .ok_or(LiquidationError::Overflow)?;
 
-        let collateral_price = if let Some(ref collateral_addr) = collateral_asset {
-            get_asset_price(env, collateral_addr)
+    // Collateral worth less than the debt plus bonus is seized in full, against a
+    // proportional share of the debt
+    let (actual_debt_liquidated, actual_collateral_seized, incentive_amount) =
+        if collateral_seized > seizable_collateral {
+            let scale = |amount: i128| {
+                amount
+                    .checked_mul(seizable_collateral)
+                    .and_then(|scaled| scaled.checked_div(collateral_seized))
+                    .ok_or(LiquidationError::Overflow)
+            };
+            (scale(debt_amount)?, seizable_collateral, scale(bonus)?)
         } else {
-            1i128 // Native XLM
+            (debt_amount, collateral_seized, bonus)
         };
 
-        actual_debt_liquidated
-            .checked_mul(debt_price)
-            .ok_or(LiquidationError::Overflow)?
-            .checked_div(collateral_price)
-            .ok_or(LiquidationError::Overflow)?
-    };
-
-    // Apply incentive: collateral_seized = collateral_value_liquidated * (1 + incentive_bps / 10000)
-    let collateral_seized = collateral_value_liquidated
-        .checked_mul(10000 + incentive_bps)
+    // The protocol's share of the bonus stays in the contract as reserve
+    let protocol_fee = incentive_amount
+        .checked_mul(get_liquidation_protocol_fee_bps(env))
         .ok_or(LiquidationError::Overflow)?
         .checked_div(10000)
         .ok_or(LiquidationError::Overflow)?;
+    let liquidator_collateral = actual_collateral_seized
+        .checked_sub(protocol_fee)
+        .ok_or(LiquidationError::Overflow)?;
 
-    // Ensure we don't seize more than available collateral
-    let actual_collateral_seized = if collateral_seized > collateral_balance {
-        collateral_balance
-    } else {
-        collateral_seized
-    };
+    // Check liquidator has sufficient balance to repay debt
+    if let Some(ref debt_addr) = debt_asset {
+        let token_client = soroban_sdk::token::Client::new(env, debt_addr);
+        if token_client.balance(liquidator) < actual_debt_liquidated {
+            return Err(LiquidationError::InsufficientBalance);
+        }
+    }
+
+    // Check contract has sufficient collateral to transfer
+    if let Some(ref collateral_addr) = collateral_asset {
+        let token_client = soroban_sdk::token::Client::new(env, collateral_addr);
+        if token_client.balance(&env.current_contract_address()) < liquidator_collateral {
+            return Err(LiquidationError::InsufficientBalance);
+        }
+    }
+
+    Ok((
+        actual_debt_liquidated,
+        actual_collateral_seized,
+        incentive_amount,
+        protocol_fee,
+    ))
+}
+
+/// Repay `debt_amount` out of the liquidator's funds and seize its value plus
+/// incentive from one collateral asset
+///
+/// # Returns
+/// (debt_repaid, collateral_seized, incentive_amount, protocol_fee), less than
+/// asked for when the borrower's collateral in the asset runs out
+#[allow(clippy::too_many_arguments)]
+fn seize_collateral(
+    env: &Env,
+    liquidator: &Address,
+    borrower: &Address,
+    position: &mut Position,
+    debt_asset: &Option<Address>,
+    collateral_asset: &Option<Address>,
+    debt_amount: i128,
+    timestamp: u64,
+) -> Result<(i128, i128, i128, i128), LiquidationError> {
+    let seizure = plan_seizure(
+        env,
+        liquidator,
+        borrower,
+        debt_asset,
+        collateral_asset,
+        debt_amount,
+    )?;
+    execute_seizure(
+        env,
+        liquidator,
+        liquidator,
+        borrower,
+        position,
+        debt_asset,
+        collateral_asset,
+        seizure,
+        timestamp,
+    )
+}
+
+/// Move the tokens for a seizure from [`plan_seizure`] and settle the position
+///
+/// The liquidator's share of the collateral goes to `collateral_recipient`, or
+/// stays here when that is this contract.
+#[allow(clippy::too_many_arguments)]
+fn execute_seizure(
+    env: &Env,
+    liquidator: &Address,
+    collateral_recipient: &Address,
+    borrower: &Address,
+    position: &mut Position,
+    debt_asset: &Option<Address>,
+    collateral_asset: &Option<Address>,
+    seizure: (i128, i128, i128, i128),
+    timestamp: u64,
+) -> Result<(i128, i128, i128, i128), LiquidationError> {
+    let (actual_debt_liquidated, actual_collateral_seized, incentive_amount, protocol_fee) =
+        seizure;
+    let health_factor_before = get_account_health(env, borrower)?.0;
 
-    // Check liquidator has sufficient balance to repay debt
+    // Transfer debt asset from liquidator to contract (liquidator repays debt)
     if let Some(ref debt_addr) = debt_asset {
-        let token_client = soroban_sdk::token::Client::new(env, debt_addr);
-        let liquidator_balance = token_client.balance(&liquidator);
-        if liquidator_balance < actual_debt_liquidated {
-            return Err(LiquidationError::InsufficientBalance);
-        }
-
-        // Transfer debt asset from liquidator to contract (liquidator repays debt)
-        token_client.transfer_from(
+        soroban_sdk::token::Client::new(env, debt_addr).transfer_from(
             &env.current_contract_address(), // spender (this contract)
-            &liquidator,                     // from (liquidator)
+            liquidator,                      // from (liquidator)
             &env.current_contract_address(), // to (this contract)
             &actual_debt_liquidated,
         );
@@ -380,72 +1712,173 @@ pub fn liquidate(
         // Native XLM handling - placeholder for now
     }
 
-    // Check contract has sufficient collateral to transfer
+    // Transfer collateral asset from contract to liquidator (with incentive),
+    // keeping the protocol fee. A swap keeps it here until the AMM pulls it.
     if let Some(ref collateral_addr) = collateral_asset {
-        let token_client = soroban_sdk::token::Client::new(env, collateral_addr);
-        let contract_balance = token_client.balance(&env.current_contract_address());
-        if contract_balance < actual_collateral_seized {
-            return Err(LiquidationError::InsufficientBalance);
+        if *collateral_recipient != env.current_contract_address() {
+            soroban_sdk::token::Client::new(env, collateral_addr).transfer(
+                &env.current_contract_address(), // from (this contract)
+                collateral_recipient,            // to (liquidator)
+                &(actual_collateral_seized - protocol_fee),
+            );
         }
-
-        // Transfer collateral asset from contract to liquidator (with incentive)
-        token_client.transfer(
-            &env.current_contract_address(), // from (this contract)
-            &liquidator,                     // to (liquidator)
-            &actual_collateral_seized,
-        );
     } else {
         // Native XLM handling - placeholder for now
     }
 
-    // Update borrower's debt (pay interest first, then principal)
-    let interest_to_pay = if actual_debt_liquidated <= position.borrow_interest {
-        actual_debt_liquidated
-    } else {
-        position.borrow_interest
-    };
+    settle_liquidation(
+        env,
+        borrower,
+        position,
+        debt_asset,
+        collateral_asset,
+        actual_debt_liquidated,
+        actual_collateral_seized,
+        timestamp,
+    )?;
+    crate::deposit::credit_protocol_reserve(env, borrower, collateral_asset, protocol_fee)
+        .map_err(|_| LiquidationError::Overflow)?;
 
-    let principal_to_pay = actual_debt_liquidated
-        .checked_sub(interest_to_pay)
+    // Emit liquidation event
+    emit_liquidation(
+        env,
+        LiquidationEvent {
+            liquidator: liquidator.clone(),
+            borrower: borrower.clone(),
+            debt_asset: debt_asset.clone(),
+            collateral_asset: collateral_asset.clone(),
+            debt_liquidated: actual_debt_liquidated,
+            collateral_seized: actual_collateral_seized,
+            incentive_amount,
+            protocol_fee,
+            health_factor_before,
+            health_factor_after: get_account_health(env, borrower)?.0,
+            debt_price: get_conversion_price(env, debt_asset)?.0,
+            collateral_price: get_conversion_price(env, collateral_asset)?.0,
+            timestamp,
+        },
+    );
+
+    Ok(seizure)
+}
+
+/// Top up the liquidator's share of the incentive to the minimum liquidation
+/// reward out of `ProtocolReserve(collateral_asset)`
+///
+/// The shortfall is valued at the collateral's account price, rounded up and
+/// capped by the reserve. Nothing is paid for native XLM collateral.
+///
+/// # Returns
+/// The collateral paid to the liquidator on top of `incentive_amount`
+fn pay_liquidation_reward_top_up(
+    env: &Env,
+    liquidator: &Address,
+    borrower: &Address,
+    collateral_asset: &Option<Address>,
+    incentive_amount: i128,
+    timestamp: u64,
+) -> Result<i128, LiquidationError> {
+    let min_reward = get_min_liquidation_reward(env);
+    let Some(collateral_addr) = collateral_asset.as_ref() else {
+        return Ok(0);
+    };
+    let price = get_account_asset_price(env, collateral_asset)?;
+    if min_reward <= 0 || price <= 0 {
+        return Ok(0);
+    }
+    let reward_value = incentive_amount
+        .checked_mul(price)
         .ok_or(LiquidationError::Overflow)?;
+    if reward_value >= min_reward {
+        return Ok(0);
+    }
 
-    position.borrow_interest = position
-        .borrow_interest
-        .checked_sub(interest_to_pay)
+    let reserve_key = DepositDataKey::ProtocolReserve(collateral_asset.clone());
+    let reserve = env
+        .storage()
+        .persistent()
+        .get::<DepositDataKey, i128>(&reserve_key)
         .unwrap_or(0);
-    position.debt = position.debt.checked_sub(principal_to_pay).unwrap_or(0);
-    position.last_accrual_time = timestamp;
+    let shortfall = (min_reward - reward_value)
+        .checked_add(price - 1)
+        .ok_or(LiquidationError::Overflow)?
+        / price;
+    let top_up = shortfall.min(reserve.max(0));
+    if top_up <= 0 {
+        return Ok(0);
+    }
 
-    // Update borrower's collateral balance
-    let new_collateral_balance = collateral_balance
-        .checked_sub(actual_collateral_seized)
-        .ok_or(LiquidationError::Overflow)?;
     env.storage()
         .persistent()
-        .set(&collateral_key, &new_collateral_balance);
-
-    // Update position collateral
-    position.collateral = new_collateral_balance;
+        .set(&reserve_key, &(reserve - top_up));
+    crate::deposit::update_asset_totals(env, collateral_asset, 0, 0, -top_up)
+        .map_err(|_| LiquidationError::Overflow)?;
+    soroban_sdk::token::Client::new(env, collateral_addr).transfer(
+        &env.current_contract_address(),
+        liquidator,
+        &top_up,
+    );
 
-    // Save updated position
-    env.storage().persistent().set(&position_key, &position);
+    emit_liquidation_reward_top_up(
+        env,
+        LiquidationRewardTopUpEvent {
+            liquidator: liquidator.clone(),
+            borrower: borrower.clone(),
+            collateral_asset: collateral_asset.clone(),
+            incentive_amount,
+            top_up_amount: top_up,
+            min_reward,
+            timestamp,
+        },
+    );
+    Ok(top_up)
+}
 
+/// Bookkeeping once a liquidation has seized its collateral
+///
+/// Updates analytics, writes off debt left unbacked, logs the activity and
+/// refreshes the borrower's breach and cooldown records.
+#[allow(clippy::too_many_arguments)]
+fn finish_liquidation(
+    env: &Env,
+    liquidator: &Address,
+    borrower: &Address,
+    debt_asset: &Option<Address>,
+    position: &mut Position,
+    tracked_borrows: bool,
+    debt_liquidated: i128,
+    collateral_seized: i128,
+    timestamp: u64,
+) -> Result<(), LiquidationError> {
     // Update analytics
     update_liquidation_analytics(
         env,
-        &borrower,
-        &liquidator,
-        actual_debt_liquidated,
-        actual_collateral_seized,
+        borrower,
+        liquidator,
+        debt_liquidated,
+        collateral_seized,
         timestamp,
     )?;
 
+    // With nothing left to seize, the rest of the debt in this asset is unbacked
+    if crate::deposit::get_effective_collateral(env, borrower) <= 0 {
+        write_off_uncovered_debt(
+            env,
+            liquidator,
+            borrower,
+            debt_asset,
+            position,
+            tracked_borrows,
+            timestamp,
+        )?;
+    }
+
     // Add to activity log
     add_activity_log(
         env,
-        &borrower,
+        borrower,
         Symbol::new(env, "liquidate"),
-        actual_debt_liquidated,
+        debt_liquidated,
         debt_asset.clone(),
         timestamp,
     )
@@ -454,47 +1887,322 @@ pub fn liquidate(
         _ => LiquidationError::Overflow,
     })?;
 
-    // Emit liquidation event
+    // Emit position updated event
+    emit_position_updated_event(env, borrower, position);
+
+    // Emit analytics updated event
+    emit_analytics_updated_event(env, borrower, "liquidate", debt_liquidated, timestamp);
+
+    // Emit user activity tracked event
+    emit_user_activity_tracked_event(
+        env,
+        borrower,
+        Symbol::new(env, "liquidate"),
+        debt_liquidated,
+        timestamp,
+    );
+
+    // Clear the breach record if the liquidation restored the account
+    clear_recovered_breach(env, borrower);
+    record_liquidation_ledger(env, borrower);
+
+    Ok(())
+}
+
+/// Deleverage a position by repaying debt out of the borrower's own collateral
+///
+/// Works like [`liquidate`] with the borrower as liquidator, but is allowed on
+/// healthy positions too and charges the self-liquidation incentive
+/// ([`get_self_liquidation_incentive`], zero by default) instead of the
+/// liquidation incentive. The same close factor, dust exemption and collateral
+/// checks apply. No tokens move, so the collateral must be the debt asset: the
+/// deposit taken repays the debt one for one. The liquidator's share of the
+/// incentive would be paid back to the borrower, so only the protocol's share
+/// ([`get_liquidation_protocol_fee_bps`]) leaves the deposit, credited to the
+/// protocol reserve.
+///
+/// # Returns
+/// Returns a tuple (debt_repaid, collateral_used, incentive_amount), the latter
+/// two in collateral units
+///
+/// # Errors
+/// * `LiquidationError::InvalidAmount` - If amount is zero or negative
+/// * `LiquidationError::LiquidationPaused` - If liquidations are paused
+/// * `LiquidationError::EmergencyPaused` - If the protocol is under emergency pause
+/// * `LiquidationError::NotLiquidatable` - If the user has no position
+/// * `LiquidationError::InvalidDebtAsset` - If the user owes nothing in `debt_asset`
+/// * `LiquidationError::InvalidCollateralAsset` - If `collateral_asset` is not
+///   `debt_asset` or the user holds no collateral-enabled balance in it
+/// * `LiquidationError::ExceedsCloseFactor` - If the amount exceeds the close factor
+/// * `LiquidationError::InsufficientCollateral` - If the collateral cannot cover
+///   the repayment and incentive
+pub fn self_liquidate(
+    env: &Env,
+    user: Address,
+    debt_asset: Option<Address>,
+    collateral_asset: Option<Address>,
+    debt_amount: i128,
+) -> Result<(i128, i128, i128), LiquidationError> {
+    user.require_auth();
+
+    // Another asset would have to be swapped into the debt asset to repay it
+    if collateral_asset != debt_asset {
+        return Err(LiquidationError::InvalidCollateralAsset);
+    }
+
+    let (mut position, tracked_borrows, debt_repaid) = prepare_liquidation(
+        env,
+        &user,
+        &user,
+        &debt_asset,
+        &collateral_asset,
+        debt_amount,
+        true,
+        LiquidationKind::SelfLiquidation,
+    )?;
+    let timestamp = env.ledger().timestamp();
+
+    let seizable_collateral = get_seizable_collateral_in(env, &user, &collateral_asset)?;
+    let collateral_value =
+        convert_debt_to_collateral_asset(env, &debt_asset, &collateral_asset, debt_repaid)?;
+    let incentive_amount = collateral_value
+        .checked_mul(get_self_liquidation_incentive(env))
+        .ok_or(LiquidationError::Overflow)?
+        .checked_div(10000)
+        .ok_or(LiquidationError::Overflow)?;
+    if collateral_value
+        .checked_add(incentive_amount)
+        .ok_or(LiquidationError::Overflow)?
+        > seizable_collateral
+    {
+        return Err(LiquidationError::InsufficientCollateral);
+    }
+    let protocol_fee = incentive_amount
+        .checked_mul(get_liquidation_protocol_fee_bps(env))
+        .ok_or(LiquidationError::Overflow)?
+        .checked_div(10000)
+        .ok_or(LiquidationError::Overflow)?;
+    let collateral_used = collateral_value
+        .checked_add(protocol_fee)
+        .ok_or(LiquidationError::Overflow)?;
+    let health_factor_before = get_account_health(env, &user)?.0;
+
+    settle_liquidation(
+        env,
+        &user,
+        &mut position,
+        &debt_asset,
+        &collateral_asset,
+        debt_repaid,
+        collateral_used,
+        timestamp,
+    )?;
+    crate::deposit::credit_protocol_reserve(env, &user, &collateral_asset, protocol_fee)
+        .map_err(|_| LiquidationError::Overflow)?;
+
     emit_liquidation(
         env,
         LiquidationEvent {
-            liquidator: liquidator.clone(),
-            borrower: borrower.clone(),
+            liquidator: user.clone(),
+            borrower: user.clone(),
+            debt_price: get_conversion_price(env, &debt_asset)?.0,
+            collateral_price: get_conversion_price(env, &collateral_asset)?.0,
             debt_asset: debt_asset.clone(),
-            collateral_asset: collateral_asset.clone(),
-            debt_liquidated: actual_debt_liquidated,
-            collateral_seized: actual_collateral_seized,
+            collateral_asset,
+            debt_liquidated: debt_repaid,
+            collateral_seized: collateral_used,
             incentive_amount,
+            protocol_fee,
+            health_factor_before,
+            health_factor_after: get_account_health(env, &user)?.0,
             timestamp,
         },
     );
 
-    // Emit position updated event
-    emit_position_updated_event(env, &borrower, &position);
-
-    // Emit analytics updated event
-    emit_analytics_updated_event(
+    finish_liquidation(
         env,
-        &borrower,
-        "liquidate",
-        actual_debt_liquidated,
+        &user,
+        &user,
+        &debt_asset,
+        &mut position,
+        tracked_borrows,
+        debt_repaid,
+        collateral_used,
         timestamp,
-    );
+    )?;
 
-    // Emit user activity tracked event
-    emit_user_activity_tracked_event(
+    Ok((debt_repaid, collateral_used, incentive_amount))
+}
+
+/// Collateral that can be seized from `borrower` in `collateral_asset`
+///
+/// Positions recorded before per-asset deposits seize from the aggregate balance.
+///
+/// # Errors
+/// * `LiquidationError::InvalidCollateralAsset` - If the borrower holds no
+///   collateral-enabled balance in the asset
+/// * `LiquidationError::InsufficientCollateral` - If a position without per-asset
+///   deposits has no collateral left
+fn get_seizable_collateral_in(
+    env: &Env,
+    borrower: &Address,
+    collateral_asset: &Option<Address>,
+) -> Result<i128, LiquidationError> {
+    let effective_collateral = crate::deposit::get_effective_collateral(env, borrower);
+    let seizable = if crate::deposit::get_user_assets(env, borrower).is_empty() {
+        effective_collateral
+    } else {
+        let amount =
+            get_asset_seizable_collateral(env, borrower, collateral_asset, effective_collateral);
+        if amount <= 0 {
+            return Err(LiquidationError::InvalidCollateralAsset);
+        }
+        amount
+    };
+    if seizable <= 0 {
+        return Err(LiquidationError::InsufficientCollateral);
+    }
+    Ok(seizable)
+}
+
+/// Most of `close_factor_base` one liquidation may repay: the close factor, or
+/// all of it for dust positions
+fn get_max_liquidatable(
+    env: &Env,
+    borrower: &Address,
+    close_factor_base: i128,
+) -> Result<i128, LiquidationError> {
+    if is_dust_position(env, borrower)? {
+        return Ok(close_factor_base);
+    }
+    get_max_liquidatable_amount(env, close_factor_base).map_err(|_| LiquidationError::Overflow)
+}
+
+/// Collateral of equal oracle value to `amount` of `debt_asset`
+fn convert_debt_to_collateral_asset(
+    env: &Env,
+    debt_asset: &Option<Address>,
+    collateral_asset: &Option<Address>,
+    amount: i128,
+) -> Result<i128, LiquidationError> {
+    if debt_asset == collateral_asset {
+        return Ok(amount);
+    }
+    convert_debt_to_collateral(
+        amount,
+        get_conversion_price(env, debt_asset)?,
+        get_conversion_price(env, collateral_asset)?,
+    )
+}
+
+/// Apply a liquidation to the borrower's books
+///
+/// Pays interest before principal out of `debt_repaid`, burns the shares backing
+/// `collateral_seized` and updates the position, per-asset debt and asset totals.
+#[allow(clippy::too_many_arguments)]
+fn settle_liquidation(
+    env: &Env,
+    borrower: &Address,
+    position: &mut Position,
+    debt_asset: &Option<Address>,
+    collateral_asset: &Option<Address>,
+    debt_repaid: i128,
+    collateral_seized: i128,
+    timestamp: u64,
+) -> Result<(), LiquidationError> {
+    let interest_to_pay = debt_repaid.min(position.borrow_interest);
+    let principal_to_pay = debt_repaid
+        .checked_sub(interest_to_pay)
+        .ok_or(LiquidationError::Overflow)?;
+    position.borrow_interest = position
+        .borrow_interest
+        .checked_sub(interest_to_pay)
+        .unwrap_or(0);
+    position.debt = position.debt.checked_sub(principal_to_pay).unwrap_or(0);
+    position.last_accrual_time = timestamp;
+
+    // Burn the borrower's shares backing the seized collateral
+    if crate::deposit::get_user_shares(env, borrower.clone(), collateral_asset.clone()) > 0
+        && collateral_seized > 0
+    {
+        crate::deposit::redeem_shares(env, borrower, collateral_asset, collateral_seized)
+            .map_err(|_| LiquidationError::Overflow)?;
+    }
+
+    let collateral_key = DepositDataKey::CollateralBalance(borrower.clone());
+    let new_collateral_balance = env
+        .storage()
+        .persistent()
+        .get::<DepositDataKey, i128>(&collateral_key)
+        .unwrap_or(0)
+        .saturating_sub(collateral_seized)
+        .max(0);
+    env.storage()
+        .persistent()
+        .set(&collateral_key, &new_collateral_balance);
+    position.collateral = new_collateral_balance;
+    env.storage()
+        .persistent()
+        .set(&DepositDataKey::Position(borrower.clone()), position);
+
+    crate::deposit::update_asset_totals(env, debt_asset, 0, -principal_to_pay, 0)
+        .map_err(|_| LiquidationError::Overflow)?;
+    crate::borrow::update_user_debt_principal(env, borrower, debt_asset, -principal_to_pay);
+    crate::deposit::update_asset_totals(env, collateral_asset, -collateral_seized, 0, 0)
+        .map_err(|_| LiquidationError::Overflow)?;
+    Ok(())
+}
+
+/// Book the debt a borrower still owes in `debt_asset` as a loss once their
+/// collateral is exhausted
+///
+/// The protocol reserve for the asset absorbs it first and the rest is recorded
+/// as bad debt, see [`crate::repay::absorb_loss`].
+fn write_off_uncovered_debt(
+    env: &Env,
+    liquidator: &Address,
+    borrower: &Address,
+    debt_asset: &Option<Address>,
+    position: &mut Position,
+    tracked_borrows: bool,
+    timestamp: u64,
+) -> Result<(), LiquidationError> {
+    let total_debt = calculate_debt_value(position.debt, position.borrow_interest)?;
+    let uncovered = if tracked_borrows {
+        crate::borrow::get_user_debt(env, borrower, debt_asset).min(total_debt)
+    } else {
+        total_debt
+    };
+    if uncovered <= 0 {
+        return Ok(());
+    }
+
+    let interest_part = uncovered.min(position.borrow_interest);
+    let principal_part = uncovered - interest_part;
+    position.borrow_interest -= interest_part;
+    position.debt = position.debt.saturating_sub(principal_part).max(0);
+    env.storage()
+        .persistent()
+        .set(&DepositDataKey::Position(borrower.clone()), position);
+    crate::deposit::update_asset_totals(env, debt_asset, 0, -principal_part, 0)
+        .map_err(|_| LiquidationError::Overflow)?;
+    crate::borrow::update_user_debt_principal(env, borrower, debt_asset, -principal_part);
+
+    let (from_reserve, bad_debt) = crate::repay::absorb_loss(env, debt_asset, uncovered)
+        .map_err(|_| LiquidationError::Overflow)?;
+    emit_liquidation_bad_debt(
         env,
-        &borrower,
-        Symbol::new(env, "liquidate"),
-        actual_debt_liquidated,
-        timestamp,
+        LiquidationBadDebtEvent {
+            liquidator: liquidator.clone(),
+            borrower: borrower.clone(),
+            debt_asset: debt_asset.clone(),
+            amount: uncovered,
+            from_reserve,
+            bad_debt,
+            timestamp,
+        },
     );
-
-    Ok((
-        actual_debt_liquidated,
-        actual_collateral_seized,
-        incentive_amount,
-    ))
+    Ok(())
 }
 
 /// Update analytics after liquidation
@@ -577,6 +2285,12 @@ fn update_liquidation_analytics(
         .checked_sub(collateral_seized)
         .unwrap_or(0);
 
+    // Update total borrows (subtract repaid debt)
+    protocol_analytics.total_borrows = protocol_analytics
+        .total_borrows
+        .checked_sub(debt_liquidated)
+        .unwrap_or(0);
+
     env.storage()
         .persistent()
         .set(&protocol_analytics_key, &protocol_analytics);
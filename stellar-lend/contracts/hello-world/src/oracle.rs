@@ -4,20 +4,52 @@
 //! guards, caching, and fallback oracle support.
 //!
 //! ## Price Resolution Order
-//! 1. **Cache**: returns a cached price if the TTL has not expired.
-//! 2. **Primary feed**: reads the on-chain `PriceFeed` entry; rejects if stale.
-//! 3. **Fallback oracle**: if the primary is stale or missing, queries a
-//!    configured fallback oracle address.
+//! 1. **Cache**: returns a cached price if neither the TTL nor the price itself
+//!    has expired.
+//! 2. **Primary sources**: every registered oracle other than the fallback
+//!    oracle is a source, and its latest price is kept under
+//!    `SourcePrice(asset, oracle)`. The primary price is the median of the
+//!    sources that are fresh and within the sanity bounds, provided at least
+//!    the asset's quorum of them are (one by default). Otherwise the primary is
+//!    rejected as missing, stale, out of bounds or below quorum.
+//! 3. **Fallback feed**: if the primary is rejected and a fallback oracle is
+//!    configured, reads the `FallbackFeed` entry that oracle submitted, subject
+//!    to the same checks. Each use emits a `FallbackPriceUsedEvent` naming the
+//!    primary's failure, so operators notice a degraded primary.
+//!
+//! When both feeds are rejected the error names both failures, e.g.
+//! `OracleError::PrimaryStaleFallbackMissing`. Without a fallback oracle the
+//! primary's failure is reported alone.
+//!
+//! ## Multiple Sources
+//! Up to [`MAX_ORACLES_PER_ASSET`] oracles can be registered per asset. With an
+//! even number of fresh sources the median is the mean of the middle two. The
+//! cache holds the median, so it is refreshed whenever a source submits. The
+//! asset's `PriceFeed` entry keeps the latest submission from any source for
+//! [`get_price_unchecked`] and the decimals; sources must all report in the
+//! same decimals.
+//!
+//! ## Staleness
+//! Every feed records when it was last updated. [`get_price`] fails with
+//! `OracleError::StalePrice` once that is more than `max_price_age_secs` ago
+//! and no fresh fallback price is available, and callers treat that as a price
+//! they cannot use: borrows and withdrawals that need it are refused and
+//! liquidations do not run. [`get_price_unchecked`] reads the last primary price
+//! whatever its age, for diagnostics and administration only.
 //!
 //! ## Safety
-//! - Price deviation between consecutive updates is bounded (default ±5%).
-//! - Staleness threshold defaults to 1 hour; configurable by admin.
+//! - Price deviation between consecutive updates of a source is bounded
+//!   (default ±5%).
+//! - Maximum price age defaults to 1 hour; configurable by admin.
 //! - Sanity-check bounds on min/max price are enforced on every update.
-//! - Only the admin or the designated oracle address may submit price updates.
+//! - Only oracles the admin registered for an asset may submit its prices; a
+//!   submission is attributed to the calling oracle.
 
 #![allow(unused)]
 use crate::deposit::DepositDataKey;
-use crate::events::{emit_price_updated, PriceUpdatedEvent};
+use crate::events::{
+    emit_fallback_price_used, emit_price_updated, FallbackPriceUsedEvent, PriceUpdatedEvent,
+};
 use crate::risk_management::get_admin;
 use soroban_sdk::{contracterror, contracttype, Address, Env, IntoVal, Map, Symbol, Val, Vec};
 
@@ -44,6 +76,54 @@ pub enum OracleError {
     AssetNotSupported = 8,
     /// Fallback oracle not configured
     FallbackNotConfigured = 9,
+    /// Neither the primary nor the fallback feed has a price
+    PrimaryMissingFallbackMissing = 10,
+    /// No primary price, and the fallback price is stale
+    PrimaryMissingFallbackStale = 11,
+    /// No primary price, and the fallback price is out of bounds
+    PrimaryMissingFallbackOutOfBounds = 12,
+    /// The primary price is stale, and the fallback feed has no price
+    PrimaryStaleFallbackMissing = 13,
+    /// Both the primary and the fallback price are stale
+    PrimaryStaleFallbackStale = 14,
+    /// The primary price is stale, and the fallback price is out of bounds
+    PrimaryStaleFallbackOutOfBounds = 15,
+    /// The primary price is out of bounds, and the fallback feed has no price
+    PrimaryOutOfBoundsFallbackMissing = 16,
+    /// The primary price is out of bounds, and the fallback price is stale
+    PrimaryOutOfBoundsFallbackStale = 17,
+    /// Both the primary and the fallback price are out of bounds
+    PrimaryOutOfBoundsFallbackOutOfBounds = 18,
+    /// The caller is not registered as an oracle for the asset
+    UnauthorizedOracle = 19,
+    /// Fewer primary sources than the asset's quorum have a usable price
+    QuorumNotMet = 20,
+    /// The asset already has the maximum number of registered oracles
+    TooManyOracles = 21,
+    /// The quorum is zero or above the maximum number of oracles
+    InvalidQuorum = 22,
+    /// The primary sources are below quorum, and the fallback feed has no price
+    PrimaryQuorumNotMetFallbackMissing = 23,
+    /// The primary sources are below quorum, and the fallback price is stale
+    PrimaryQuorumNotMetFallbackStale = 24,
+    /// The primary sources are below quorum, and the fallback price is out of bounds
+    PrimaryQuorumNotMetFallbackOutOfBounds = 25,
+    /// Reentrancy detected
+    Reentrancy = 26,
+}
+
+/// Why a price feed was rejected
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum PriceSourceFailure {
+    /// No price has been submitted to the feed by a registered oracle
+    Missing,
+    /// The price is older than `max_price_age_secs`
+    Stale,
+    /// The price is outside the configured `min_price`..=`max_price` bounds
+    OutOfBounds,
+    /// Some primary sources are usable, but fewer than the asset's quorum
+    BelowQuorum,
 }
 
 /// Storage keys for oracle-related data
@@ -57,6 +137,22 @@ pub enum OracleDataKey {
     /// Address of the designated fallback oracle for an asset
     /// Value type: Address
     FallbackOracle(Address),
+    /// Address of the primary oracle for an asset
+    /// Value type: Address
+    PrimaryOracle(Address),
+    /// Latest price submitted by an asset's fallback oracle
+    /// Value type: PriceFeed
+    FallbackFeed(Address),
+    /// Oracles allowed to submit prices for an asset
+    /// Value type: Vec<Address>
+    RegisteredOracles(Address),
+    /// Latest price an oracle submitted as a primary source for an asset
+    /// (asset, oracle)
+    /// Value type: PriceFeed
+    SourcePrice(Address, Address),
+    /// Fresh primary sources an asset's price needs
+    /// Value type: u32
+    PriceQuorum(Address),
     /// Transient price cache for improved gas efficiency
     /// Value type: CachedPrice
     PriceCache(Address),
@@ -91,6 +187,8 @@ pub struct CachedPrice {
     pub cached_at: u64,
     /// Cache TTL in seconds
     pub ttl: u64,
+    /// When the cached price was last updated by its feed
+    pub price_updated_at: u64,
 }
 
 /// Oracle configuration
@@ -99,8 +197,8 @@ pub struct CachedPrice {
 pub struct OracleConfig {
     /// Maximum price deviation in basis points (e.g., 500 = 5%)
     pub max_deviation_bps: i128,
-    /// Maximum staleness in seconds
-    pub max_staleness_seconds: u64,
+    /// Maximum age of a price in seconds before `get_price` rejects it
+    pub max_price_age_secs: u64,
     /// Cache TTL in seconds
     pub cache_ttl_seconds: u64,
     /// Minimum price sanity check
@@ -111,16 +209,20 @@ pub struct OracleConfig {
 
 /// Default configuration values
 const DEFAULT_MAX_DEVIATION_BPS: i128 = 500; // 5%
-const DEFAULT_MAX_STALENESS_SECONDS: u64 = 3600; // 1 hour
+const DEFAULT_MAX_PRICE_AGE_SECS: u64 = 3600; // 1 hour
 const DEFAULT_CACHE_TTL_SECONDS: u64 = 300; // 5 minutes
 const DEFAULT_MIN_PRICE: i128 = 1;
 const DEFAULT_MAX_PRICE: i128 = i128::MAX;
+const DEFAULT_PRICE_QUORUM: u32 = 1;
+
+/// Maximum number of oracles that can be registered for one asset
+pub const MAX_ORACLES_PER_ASSET: u32 = 10;
 
 /// Get default oracle configuration
 fn get_default_config() -> OracleConfig {
     OracleConfig {
         max_deviation_bps: DEFAULT_MAX_DEVIATION_BPS,
-        max_staleness_seconds: DEFAULT_MAX_STALENESS_SECONDS,
+        max_price_age_secs: DEFAULT_MAX_PRICE_AGE_SECS,
         cache_ttl_seconds: DEFAULT_CACHE_TTL_SECONDS,
         min_price: DEFAULT_MIN_PRICE,
         max_price: DEFAULT_MAX_PRICE,
@@ -176,7 +278,7 @@ fn is_price_stale(env: &Env, last_updated: u64) -> bool {
     }
 
     let age = current_time - last_updated;
-    age > config.max_staleness_seconds
+    age > config.max_price_age_secs
 }
 
 /// Check price deviation between two prices
@@ -211,7 +313,7 @@ fn check_price_deviation(env: &Env, new_price: i128, old_price: i128) -> Result<
     Ok(())
 }
 
-/// Get cached price if valid
+/// Get cached price if neither the cache entry nor the price has expired
 fn get_cached_price(env: &Env, asset: &Address) -> Option<i128> {
     let cache_key = OracleDataKey::PriceCache(asset.clone());
     if let Some(cached) = env
@@ -222,6 +324,7 @@ fn get_cached_price(env: &Env, asset: &Address) -> Option<i128> {
         let current_time = env.ledger().timestamp();
         if current_time >= cached.cached_at
             && current_time <= cached.cached_at.saturating_add(cached.ttl)
+            && !is_price_stale(env, cached.price_updated_at)
         {
             return Some(cached.price);
         }
@@ -229,38 +332,46 @@ fn get_cached_price(env: &Env, asset: &Address) -> Option<i128> {
     None
 }
 
-/// Cache price
-fn cache_price(env: &Env, asset: &Address, price: i128) {
+/// Cache a price last updated by its feed at `price_updated_at`
+fn cache_price(env: &Env, asset: &Address, price: i128, price_updated_at: u64) {
     let config = get_oracle_config(env);
     let cache_key = OracleDataKey::PriceCache(asset.clone());
     let cached = CachedPrice {
         price,
         cached_at: env.ledger().timestamp(),
         ttl: config.cache_ttl_seconds,
+        price_updated_at,
     };
     env.storage().persistent().set(&cache_key, &cached);
 }
 
 /// Update price feed from oracle
 ///
+/// The price is recorded as provided by `caller`: the asset's fallback oracle
+/// writes the fallback feed, any other registered oracle its own primary source.
+/// Deviation is checked against the price `caller` last submitted.
+///
 /// # Arguments
 /// * `env` - The Soroban environment
-/// * `caller` - The address calling this function (must be admin or oracle)
+/// * `caller` - The oracle submitting the price (must be registered for `asset`)
 /// * `asset` - The asset address
 /// * `price` - The new price
 /// * `decimals` - Price decimals
-/// * `oracle` - The oracle address providing this price
 ///
 /// # Returns
 /// Returns the updated price
+///
+/// # Errors
+/// * `OracleError::UnauthorizedOracle` - If `caller` is not registered for `asset`
 pub fn update_price_feed(
     env: &Env,
     caller: Address,
     asset: Address,
     price: i128,
     decimals: u32,
-    oracle: Address,
 ) -> Result<i128, OracleError> {
+    caller.require_auth();
+
     // Check if oracle updates are paused
     let pause_key = OracleDataKey::PauseSwitches;
     if let Some(pause_map) = env
@@ -276,30 +387,19 @@ pub fn update_price_feed(
     }
 
     // Validate caller authorization
-    let is_admin = get_admin(env).map(|admin| admin == caller).unwrap_or(false);
-    let primary = get_primary_oracle(env, &asset);
-    let fallback = get_fallback_oracle(env, &asset);
-
-    let is_primary = primary.map(|p| p == caller).unwrap_or(false);
-    let is_fallback = fallback.map(|f| f == caller).unwrap_or(false);
-
-    if !is_admin && !is_primary && !is_fallback {
-        return Err(OracleError::Unauthorized);
-    }
-
-    // Ensure oracle address matches caller if not admin
-    if !is_admin && caller != oracle {
-        return Err(OracleError::Unauthorized);
+    if !get_registered_oracles(env, &asset).contains(&caller) {
+        return Err(OracleError::UnauthorizedOracle);
     }
 
     // Validate price
     validate_price(env, price)?;
 
     // Determine target storage key and get current feed for deviation check
-    let feed_key = if is_fallback && !is_primary && !is_admin {
+    let submits_fallback = get_fallback_oracle(env, &asset).is_some_and(|f| f == caller);
+    let feed_key = if submits_fallback {
         OracleDataKey::FallbackFeed(asset.clone())
     } else {
-        OracleDataKey::PriceFeed(asset.clone())
+        OracleDataKey::SourcePrice(asset.clone(), caller.clone())
     };
 
     let current_feed = env
@@ -314,36 +414,39 @@ pub fn update_price_feed(
 
     // Create new price feed
     let timestamp = env.ledger().timestamp();
-    let oracle_clone = oracle.clone();
     let new_feed = PriceFeed {
         price,
         last_updated: timestamp,
-        oracle: oracle_clone.clone(),
+        oracle: caller.clone(),
         decimals,
     };
 
     // Update storage
     env.storage().persistent().set(&feed_key, &new_feed);
 
-    // When admin submits a price, register the oracle address as the primary oracle
-    // for the asset so subsequent calls from that oracle are authorized.
-    if is_admin {
-        let primary_key = OracleDataKey::PrimaryOracle(asset.clone());
-        env.storage().persistent().set(&primary_key, &oracle);
+    // Record the latest primary submission and re-cache the median; a fallback
+    // price is only served once the primary is rejected
+    if !submits_fallback {
+        env.storage()
+            .persistent()
+            .set(&OracleDataKey::PriceFeed(asset.clone()), &new_feed);
+        env.storage()
+            .persistent()
+            .remove(&OracleDataKey::PriceCache(asset.clone()));
+        if let Ok((median, price_updated_at)) = aggregate_sources(env, &asset) {
+            cache_price(env, &asset, median, price_updated_at);
+        }
     }
 
-    // Update cache
-    cache_price(env, &asset, price);
-
     // Emit price update event
     emit_price_updated(
         env,
         PriceUpdatedEvent {
-            actor: caller,
+            actor: caller.clone(),
             asset: asset.clone(),
             price,
             decimals,
-            oracle: oracle_clone,
+            oracle: caller,
             timestamp,
         },
     );
@@ -359,64 +462,349 @@ pub fn update_price_feed(
 ///
 /// # Returns
 /// Returns the current price, using cache or fallback if needed
+///
+/// # Errors
+/// * `OracleError::StalePrice` - If the primary price is older than
+///   `max_price_age_secs` and no fresh fallback price is available
+/// * `OracleError::QuorumNotMet` - If fewer primary sources than the asset's
+///   quorum are usable and no fallback oracle is configured
 pub fn get_price(env: &Env, asset: &Address) -> Result<i128, OracleError> {
+    lookup_price(env, asset, true)
+}
+
+/// Get the last price the primary feed reported for an asset, however old
+///
+/// Skips the cache, the fallback and the staleness check, so it must never
+/// value positions; it is meant for diagnostic and administrative reads.
+///
+/// # Errors
+/// * `OracleError::AssetNotSupported` - If the asset has no primary price feed
+pub fn get_price_unchecked(env: &Env, asset: &Address) -> Result<i128, OracleError> {
+    env.storage()
+        .persistent()
+        .get::<OracleDataKey, PriceFeed>(&OracleDataKey::PriceFeed(asset.clone()))
+        .map(|feed| feed.price)
+        .ok_or(OracleError::AssetNotSupported)
+}
+
+/// Whether an asset has a primary price feed or a fallback oracle configured
+///
+/// Prices of such an asset must come from the oracle; callers may only apply
+/// defaults to assets that were never priced.
+pub fn has_price_source(env: &Env, asset: &Address) -> bool {
+    let storage = env.storage().persistent();
+    storage.has(&OracleDataKey::PriceFeed(asset.clone()))
+        || storage.has(&OracleDataKey::FallbackOracle(asset.clone()))
+}
+
+/// Get the price [`get_price`] would return, without writing to the price cache
+///
+/// For read-only views that must not mutate state.
+pub fn peek_price(env: &Env, asset: &Address) -> Result<i128, OracleError> {
+    lookup_price(env, asset, false)
+}
+
+/// Decimals of an asset's primary price feed, if one has been set
+pub fn get_price_decimals(env: &Env, asset: &Address) -> Option<u32> {
+    env.storage()
+        .persistent()
+        .get::<OracleDataKey, PriceFeed>(&OracleDataKey::PriceFeed(asset.clone()))
+        .map(|feed| feed.decimals)
+}
+
+/// When an asset's primary price feed was last updated, if one has been set
+pub fn get_price_updated_at(env: &Env, asset: &Address) -> Option<u64> {
+    env.storage()
+        .persistent()
+        .get::<OracleDataKey, PriceFeed>(&OracleDataKey::PriceFeed(asset.clone()))
+        .map(|feed| feed.last_updated)
+}
+
+/// Resolve an asset's price from the cache, primary feed or fallback feed,
+/// caching a feed price when `cache` is set
+///
+/// A fallback price is only announced with a `FallbackPriceUsedEvent` when
+/// `cache` is set, so read-only views stay free of side effects.
+fn lookup_price(env: &Env, asset: &Address, cache: bool) -> Result<i128, OracleError> {
     // Try cache first
     if let Some(cached_price) = get_cached_price(env, asset) {
         return Ok(cached_price);
     }
 
-    // Get primary price feed
-    let feed_key = OracleDataKey::PriceFeed(asset.clone());
-    if let Some(feed) = env
-        .storage()
-        .persistent()
-        .get::<OracleDataKey, PriceFeed>(&feed_key)
-    {
-        // Check if price is stale
-        if is_price_stale(env, feed.last_updated) {
-            // Try fallback oracle
-            if let Ok(fallback_price) = get_fallback_price(env, asset) {
-                return Ok(fallback_price);
+    let (price, price_updated_at) = match aggregate_sources(env, asset) {
+        Ok(primary) => primary,
+        Err(primary_failure) => match get_fallback_feed(env, asset) {
+            None => return Err(single_source_error(primary_failure)),
+            Some(Err(fallback_failure)) => {
+                return Err(both_sources_error(primary_failure, fallback_failure))
             }
-            // If fallback failed or not configured, but we have a stale price,
-            // we could return it in emergency, but here we enforce staleness
-            return Err(OracleError::StalePrice);
-        }
+            Some(Ok(feed)) => {
+                if cache {
+                    emit_fallback_price_used(
+                        env,
+                        FallbackPriceUsedEvent {
+                            asset: asset.clone(),
+                            fallback_oracle: feed.oracle.clone(),
+                            primary_failure,
+                            price: feed.price,
+                            timestamp: env.ledger().timestamp(),
+                        },
+                    );
+                }
+                (feed.price, feed.last_updated)
+            }
+        },
+    };
 
-        // Cache the price
-        cache_price(env, asset, feed.price);
+    if cache {
+        cache_price(env, asset, price, price_updated_at);
+    }
+    Ok(price)
+}
 
-        return Ok(feed.price);
+/// The median of an asset's usable primary sources, with the oldest update
+/// time among them
+///
+/// When no source is usable the failure is `Stale` if any source is stale,
+/// otherwise `OutOfBounds` if any is out of bounds, otherwise `Missing`.
+fn aggregate_sources(env: &Env, asset: &Address) -> Result<(i128, u64), PriceSourceFailure> {
+    let fallback_oracle = get_fallback_oracle(env, asset);
+    let mut prices: Vec<i128> = Vec::new(env);
+    let mut oldest = u64::MAX;
+    let mut failure = PriceSourceFailure::Missing;
+    for oracle in get_registered_oracles(env, asset).iter() {
+        if fallback_oracle.as_ref() == Some(&oracle) {
+            continue;
+        }
+        let feed = env
+            .storage()
+            .persistent()
+            .get::<OracleDataKey, PriceFeed>(&OracleDataKey::SourcePrice(asset.clone(), oracle));
+        match check_feed(env, feed) {
+            Ok(feed) => {
+                // Keep `prices` sorted
+                let index = prices
+                    .iter()
+                    .position(|price| price > feed.price)
+                    .unwrap_or(prices.len() as usize);
+                prices.insert(index as u32, feed.price);
+                oldest = oldest.min(feed.last_updated);
+            }
+            Err(PriceSourceFailure::Stale) => failure = PriceSourceFailure::Stale,
+            Err(PriceSourceFailure::OutOfBounds) if failure == PriceSourceFailure::Missing => {
+                failure = PriceSourceFailure::OutOfBounds
+            }
+            Err(_) => {}
+        }
     }
 
-    // No primary price feed found, try fallback
-    get_fallback_price(env, asset)
+    let count = prices.len();
+    if count == 0 {
+        return Err(failure);
+    }
+    if count < get_price_quorum(env, asset) {
+        return Err(PriceSourceFailure::BelowQuorum);
+    }
+    let upper = prices.get_unchecked(count / 2);
+    let median = if count % 2 == 1 {
+        upper
+    } else {
+        let lower = prices.get_unchecked(count / 2 - 1);
+        // Mean of the middle two without overflowing
+        lower + (upper - lower) / 2
+    };
+    Ok((median, oldest))
 }
 
-/// Get price from fallback oracle
-fn get_fallback_price(env: &Env, asset: &Address) -> Result<i128, OracleError> {
-    let fallback_key = OracleDataKey::FallbackOracle(asset.clone());
-    if let Some(fallback_oracle) = env
+/// Check a feed is present, fresh and within the sanity bounds
+fn check_feed(env: &Env, feed: Option<PriceFeed>) -> Result<PriceFeed, PriceSourceFailure> {
+    let feed = feed.ok_or(PriceSourceFailure::Missing)?;
+    if is_price_stale(env, feed.last_updated) {
+        return Err(PriceSourceFailure::Stale);
+    }
+    if validate_price(env, feed.price).is_err() {
+        return Err(PriceSourceFailure::OutOfBounds);
+    }
+    Ok(feed)
+}
+
+/// The checked fallback feed of an asset, or `None` if no fallback oracle is
+/// configured
+///
+/// Only a price submitted by the currently configured fallback oracle, while it
+/// is registered, counts.
+fn get_fallback_feed(env: &Env, asset: &Address) -> Option<Result<PriceFeed, PriceSourceFailure>> {
+    let fallback_oracle = get_fallback_oracle(env, asset)?;
+    let feed = env
         .storage()
         .persistent()
-        .get::<OracleDataKey, Address>(&fallback_key)
-    {
-        // Get price from fallback oracle feed slot
-        let feed_key = OracleDataKey::FallbackFeed(asset.clone());
+        .get::<OracleDataKey, PriceFeed>(&OracleDataKey::FallbackFeed(asset.clone()))
+        .filter(|feed| {
+            feed.oracle == fallback_oracle
+                && get_registered_oracles(env, asset).contains(&feed.oracle)
+        });
+    Some(check_feed(env, feed))
+}
+
+/// Error for a rejected primary feed when no fallback oracle is configured
+fn single_source_error(failure: PriceSourceFailure) -> OracleError {
+    match failure {
+        PriceSourceFailure::Missing => OracleError::FallbackNotConfigured,
+        PriceSourceFailure::Stale => OracleError::StalePrice,
+        PriceSourceFailure::OutOfBounds => OracleError::InvalidPrice,
+        PriceSourceFailure::BelowQuorum => OracleError::QuorumNotMet,
+    }
+}
+
+/// Error naming why both the primary and the fallback feed were rejected
+fn both_sources_error(primary: PriceSourceFailure, fallback: PriceSourceFailure) -> OracleError {
+    use PriceSourceFailure::{BelowQuorum, Missing, OutOfBounds, Stale};
+    match (primary, fallback) {
+        (Missing, Missing) => OracleError::PrimaryMissingFallbackMissing,
+        (Missing, Stale) => OracleError::PrimaryMissingFallbackStale,
+        (Missing, OutOfBounds) => OracleError::PrimaryMissingFallbackOutOfBounds,
+        (Stale, Missing) => OracleError::PrimaryStaleFallbackMissing,
+        (Stale, Stale) => OracleError::PrimaryStaleFallbackStale,
+        (Stale, OutOfBounds) => OracleError::PrimaryStaleFallbackOutOfBounds,
+        (OutOfBounds, Missing) => OracleError::PrimaryOutOfBoundsFallbackMissing,
+        (OutOfBounds, Stale) => OracleError::PrimaryOutOfBoundsFallbackStale,
+        (OutOfBounds, OutOfBounds) => OracleError::PrimaryOutOfBoundsFallbackOutOfBounds,
+        (BelowQuorum, Missing) => OracleError::PrimaryQuorumNotMetFallbackMissing,
+        (BelowQuorum, Stale) => OracleError::PrimaryQuorumNotMetFallbackStale,
+        (BelowQuorum, OutOfBounds) => OracleError::PrimaryQuorumNotMetFallbackOutOfBounds,
+        // A single feed has no quorum to miss
+        (_, BelowQuorum) => single_source_error(primary),
+    }
+}
+
+/// Oracles registered to submit prices for an asset
+pub fn get_registered_oracles(env: &Env, asset: &Address) -> Vec<Address> {
+    env.storage()
+        .persistent()
+        .get::<OracleDataKey, Vec<Address>>(&OracleDataKey::RegisteredOracles(asset.clone()))
+        .unwrap_or(Vec::new(env))
+}
+
+/// Allow `oracle` to submit prices for `asset` (admin only)
+///
+/// Registering an oracle that is already registered is a no-op.
+///
+/// # Errors
+/// * `OracleError::Unauthorized` - If `caller` is not the admin
+/// * `OracleError::InvalidOracle` - If `oracle` is this contract
+/// * `OracleError::TooManyOracles` - If `asset` already has
+///   [`MAX_ORACLES_PER_ASSET`] oracles
+pub fn register_oracle(
+    env: &Env,
+    caller: Address,
+    asset: Address,
+    oracle: Address,
+) -> Result<(), OracleError> {
+    crate::admin::require_admin(env, &caller).map_err(|_| OracleError::Unauthorized)?;
+    caller.require_auth();
+
+    if oracle == env.current_contract_address() {
+        return Err(OracleError::InvalidOracle);
+    }
+    let mut oracles = get_registered_oracles(env, &asset);
+    if !oracles.contains(&oracle) {
+        if oracles.len() >= MAX_ORACLES_PER_ASSET {
+            return Err(OracleError::TooManyOracles);
+        }
+        oracles.push_back(oracle);
+        env.storage()
+            .persistent()
+            .set(&OracleDataKey::RegisteredOracles(asset), &oracles);
+    }
+    Ok(())
+}
+
+/// Stop `oracle` from submitting prices for `asset` (admin only)
+///
+/// Prices it already submitted are no longer served: the cached price is
+/// dropped and its feed entries are treated as missing.
+///
+/// # Errors
+/// * `OracleError::Unauthorized` - If `caller` is not the admin
+/// * `OracleError::InvalidOracle` - If `oracle` is not registered for `asset`
+pub fn revoke_oracle(
+    env: &Env,
+    caller: Address,
+    asset: Address,
+    oracle: Address,
+) -> Result<(), OracleError> {
+    crate::admin::require_admin(env, &caller).map_err(|_| OracleError::Unauthorized)?;
+    caller.require_auth();
+
+    let mut oracles = get_registered_oracles(env, &asset);
+    let index = oracles
+        .first_index_of(&oracle)
+        .ok_or(OracleError::InvalidOracle)?;
+    oracles.remove(index);
+    env.storage()
+        .persistent()
+        .set(&OracleDataKey::RegisteredOracles(asset.clone()), &oracles);
+    env.storage()
+        .persistent()
+        .remove(&OracleDataKey::PriceCache(asset));
+    Ok(())
+}
+
+/// Fresh primary sources needed to price an asset (one by default)
+pub fn get_price_quorum(env: &Env, asset: &Address) -> u32 {
+    env.storage()
+        .persistent()
+        .get::<OracleDataKey, u32>(&OracleDataKey::PriceQuorum(asset.clone()))
+        .unwrap_or(DEFAULT_PRICE_QUORUM)
+}
+
+/// Set how many fresh primary sources an asset's price needs (admin only)
+///
+/// # Errors
+/// * `OracleError::Unauthorized` - If `caller` is not the admin
+/// * `OracleError::InvalidQuorum` - If `min_sources` is outside
+///   1..=[`MAX_ORACLES_PER_ASSET`]
+pub fn set_price_quorum(
+    env: &Env,
+    caller: Address,
+    asset: Address,
+    min_sources: u32,
+) -> Result<(), OracleError> {
+    crate::admin::require_admin(env, &caller).map_err(|_| OracleError::Unauthorized)?;
+    caller.require_auth();
+
+    if !(1..=MAX_ORACLES_PER_ASSET).contains(&min_sources) {
+        return Err(OracleError::InvalidQuorum);
+    }
+    env.storage()
+        .persistent()
+        .set(&OracleDataKey::PriceQuorum(asset.clone()), &min_sources);
+    env.storage()
+        .persistent()
+        .remove(&OracleDataKey::PriceCache(asset));
+    Ok(())
+}
+
+/// Latest price and update time of each registered primary source of an asset
+///
+/// Sources that have not submitted yet are left out. Prices are listed however
+/// old they are.
+pub fn get_price_sources(env: &Env, asset: &Address) -> Vec<PriceFeed> {
+    let fallback_oracle = get_fallback_oracle(env, asset);
+    let mut sources = Vec::new(env);
+    for oracle in get_registered_oracles(env, asset).iter() {
+        if fallback_oracle.as_ref() == Some(&oracle) {
+            continue;
+        }
         if let Some(feed) = env
             .storage()
             .persistent()
-            .get::<OracleDataKey, PriceFeed>(&feed_key)
+            .get::<OracleDataKey, PriceFeed>(&OracleDataKey::SourcePrice(asset.clone(), oracle))
         {
-            // Check if fallback price is valid and from authorized oracle
-            if feed.oracle == fallback_oracle && !is_price_stale(env, feed.last_updated) {
-                cache_price(env, asset, feed.price);
-                return Ok(feed.price);
-            }
+            sources.push_back(feed);
         }
     }
-
-    Err(OracleError::FallbackNotConfigured)
+    sources
 }
 
 /// Set primary oracle for an asset
@@ -497,7 +885,7 @@ pub fn configure_oracle(
         return Err(OracleError::InvalidPrice);
     }
 
-    if config.max_staleness_seconds == 0 {
+    if config.max_price_age_secs == 0 {
         return Err(OracleError::InvalidPrice);
     }
 
@@ -0,0 +1,54 @@
+//! # Reentrancy Guard Module
+//!
+//! A contract-wide lock held for the duration of every state-changing
+//! entrypoint. A token contract called mid-operation cannot call back into
+//! another locked entrypoint while the first one is still running.
+//!
+//! The lock lives in instance storage and is released when the guard is
+//! dropped. If the call traps or returns an error its storage writes are rolled
+//! back, so a lock can never outlive the transaction that took it.
+//!
+//! ## Storage Keys
+//! - `ReentrancyDataKey::Locked` — present while a guarded call is executing
+
+use soroban_sdk::{contracttype, Env};
+
+#[contracttype]
+#[derive(Clone)]
+enum ReentrancyDataKey {
+    Locked,
+}
+
+/// Holds the reentrancy lock until dropped
+pub struct ReentrancyGuard {
+    env: Env,
+}
+
+impl ReentrancyGuard {
+    /// Take the lock, or return `None` if a guarded call is already executing
+    ///
+    /// Callers map `None` to their module's `Reentrancy` error.
+    pub fn enter(env: &Env) -> Option<Self> {
+        if is_locked(env) {
+            return None;
+        }
+        env.storage()
+            .instance()
+            .set(&ReentrancyDataKey::Locked, &true);
+        Some(Self { env: env.clone() })
+    }
+}
+
+impl Drop for ReentrancyGuard {
+    fn drop(&mut self) {
+        self.env
+            .storage()
+            .instance()
+            .remove(&ReentrancyDataKey::Locked);
+    }
+}
+
+/// Whether a guarded call is currently executing
+pub fn is_locked(env: &Env) -> bool {
+    env.storage().instance().has(&ReentrancyDataKey::Locked)
+}
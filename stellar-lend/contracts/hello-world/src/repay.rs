@@ -2,27 +2,72 @@
 //!
 //! Handles debt repayment operations for the lending protocol.
 //!
-//! Supports both partial and full repayments. Interest is accrued before
-//! repayment is applied. Repayment is allocated interest-first, then principal.
+//! Supports both partial and full repayments. Every repayment first brings the
+//! asset's interest indexes and the user's debt up to the current ledger
+//! (`interest_rate::accrue_interest`), then applies the payment interest-first, then
+//! principal, so a position left idle is never settled against stale debt.
 //!
 //! ## Repayment Order
-//! 1. Accrued interest is paid first.
+//! 1. Accrued interest is paid first. The interest rate config's
+//!    `reserve_factor_bps` of it was already credited to `ProtocolReserve(asset)`
+//!    as it accrued, less the referral share moved to the borrower's referrer
+//!    on payment; the rest belongs to suppliers, who earn it through the supply
+//!    rate.
 //! 2. Any remaining repayment amount reduces the principal debt.
 //!
+//! `repay_debt` returns the split. `RepayEvent` records it along with the payer,
+//! the borrower, the reserve share and the debt left, and is published under the
+//! `repay_event` topic followed by the asset.
+//!
+//! ## Repaying On Behalf
+//! [`repay_on_behalf`] lets any address pay down another user's debt from its own
+//! balance, with the same rules as a self-repayment.
+//!
+//! ## Full Repayment
+//! An amount above the debt accrued up to the current ledger repays it in full,
+//! and only the amount owed is transferred from the user. Passing `i128::MAX`
+//! repays everything without racing interest accrual. [`get_repay_quote`] reports
+//! that amount, optionally projected to a later ledger time.
+//!
+//! ## Depositing Surplus
+//! [`repay_and_deposit`] can supply whatever is left of `amount` after the debt
+//! is cleared through the regular deposit path, with its caps and pauses. The
+//! call emits the usual repay and deposit events, and a rejected deposit
+//! reverts the repayment too.
+//!
+//! ## Repaying With Collateral
+//! [`repay_with_collateral`] repays out of the user's own deposit in the debt
+//! asset instead of a token transfer, capped by the caller's `max_collateral_in`.
+//! The account must stay healthy or end no less healthy. Repaying from a
+//! different collateral asset is rejected until it can be swapped through a
+//! real AMM contract.
+//!
+//! ## Writing Off Debt
+//! [`write_off_debt`] lets the admin clear debt that will never be repaid
+//! without any tokens moving. The amount is absorbed by `ProtocolReserve(asset)`
+//! first; whatever the reserve cannot cover is recorded as `BadDebt(asset)`.
+//! Liquidations that exhaust a borrower's collateral book the debt left over the
+//! same way, through [`absorb_loss`].
+//!
+//! ## Pausing
+//! Repayments are never blocked by pause switches or the emergency pause while
+//! the admin flag `repay_always_allowed` is set, which is the default.
+//!
 //! ## Invariants
 //! - Repay amount must be strictly positive.
+//! - A partial repayment must leave at least the asset's `min_borrow` of principal.
 //! - User must have outstanding debt to repay.
 //! - Token transfers use `transfer_from`, requiring prior user approval.
 
 #![allow(unused)]
-use soroban_sdk::{contracterror, Address, Env, IntoVal, Map, Symbol, Val, Vec};
+use soroban_sdk::{contracterror, contracttype, Address, Env, IntoVal, Map, Symbol, Val, Vec};
 
 use crate::deposit::{
     add_activity_log, emit_analytics_updated_event, emit_position_updated_event,
     emit_user_activity_tracked_event, update_protocol_analytics, update_user_analytics, Activity,
     DepositDataKey, Position, ProtocolAnalytics, UserAnalytics,
 };
-use crate::events::{emit_repay, RepayEvent};
+use crate::events::{emit_debt_written_off, emit_repay, DebtWrittenOffEvent, RepayEvent};
 
 /// Errors that can occur during repay operations
 #[contracterror]
@@ -43,63 +88,56 @@ pub enum RepayError {
     Overflow = 6,
     /// Reentrancy detected
     Reentrancy = 7,
+    /// Partial repayment would leave debt below the asset's minimum borrow size
+    DustDebtRemaining = 8,
+    /// Repaying needs more collateral than the caller allowed
+    SlippageExceeded = 10,
+    /// Repaying with collateral would leave the account unhealthy
+    PositionUnhealthy = 12,
+    /// The surplus of a repayment could not be deposited
+    SurplusDepositRejected = 13,
+    /// No active repayment schedule matches the request
+    ScheduleNotFound = 14,
+    /// The user already has an active repayment schedule for the asset
+    ScheduleExists = 15,
+    /// Caller is not authorized for this operation
+    Unauthorized = 16,
 }
 
-/// Calculate interest accrued since last accrual time
-///
-/// Uses dynamic interest rate based on current protocol utilization.
-///
-/// # Arguments
-/// * `env` - The Soroban environment
-/// * `principal` - The principal amount to calculate interest on
-/// * `last_accrual_time` - The timestamp of the last interest accrual
-/// * `current_time` - The current ledger timestamp
-///
-/// # Returns
-/// * `Result<i128, RepayError>` - The accrued interest amount or an error
-fn calculate_accrued_interest(
-    env: &Env,
-    principal: i128,
-    last_accrual_time: u64,
-    current_time: u64,
-) -> Result<i128, RepayError> {
-    if principal == 0 {
-        return Ok(0);
-    }
-    if current_time <= last_accrual_time {
-        return Ok(0);
-    }
-    let rate_bps = crate::interest_rate::calculate_borrow_rate(env).map_err(|_| RepayError::Overflow)?;
-    crate::interest_rate::calculate_accrued_interest(
-        principal,
-        last_accrual_time,
-        current_time,
-        rate_bps,
-    ).map_err(|_| RepayError::Overflow)
+/// Amount needed to repay a user's debt in one asset in full
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RepayQuote {
+    /// Debt owed at the current ledger time
+    pub debt: i128,
+    /// Debt owed at `timestamp` if the current borrow rate holds until then
+    pub projected_debt: i128,
+    /// Time the projection is for (the current ledger time if none was requested)
+    pub timestamp: u64,
 }
 
 /// Accrue interest on a position
 ///
-/// Updates the position's borrow_interest and last_accrual_time based on elapsed time
-/// and the current interest rate.
+/// Brings the repaid asset's interest indexes up to the current ledger, then
+/// materializes the user's debt through each borrowed asset's borrow index and
+/// updates the position's borrow_interest and last_accrual_time.
 ///
 /// # Arguments
 /// * `env` - The Soroban environment
+/// * `user` - The owner of the position
+/// * `asset` - The asset being repaid
 /// * `position` - A mutable reference to the user's position
 ///
 /// # Returns
 /// * `Result<(), RepayError>` - Success or an error
-fn accrue_interest(env: &Env, position: &mut Position) -> Result<(), RepayError> {
-    let current_time = env.ledger().timestamp();
-    if position.debt == 0 {
-        position.borrow_interest = 0;
-        position.last_accrual_time = current_time;
-        return Ok(());
-    }
-    let new_interest = calculate_accrued_interest(env, position.debt, position.last_accrual_time, current_time)?;
-    position.borrow_interest = position.borrow_interest.checked_add(new_interest).ok_or(RepayError::Overflow)?;
-    position.last_accrual_time = current_time;
-    Ok(())
+fn accrue_interest(
+    env: &Env,
+    user: &Address,
+    asset: &Option<Address>,
+    position: &mut Position,
+) -> Result<(), RepayError> {
+    crate::interest_rate::accrue_interest(env, asset.clone()).map_err(|_| RepayError::Overflow)?;
+    crate::borrow::accrue_position_interest(env, user, position).map_err(|_| RepayError::Overflow)
 }
 
 /// Helper function to get the native asset contract address from storage
@@ -116,6 +154,26 @@ fn get_native_asset_address(env: &Env) -> Result<Address, RepayError> {
         .ok_or(RepayError::InvalidAsset)
 }
 
+/// Whether repayments are currently paused
+///
+/// Always false while `repay_always_allowed` is set. Otherwise repayments stop
+/// under the `pause_repay` switch or the emergency pause.
+fn is_repay_paused(env: &Env) -> bool {
+    if crate::risk_management::is_repay_always_allowed(env) {
+        return false;
+    }
+    let repay_op = Symbol::new(env, "pause_repay");
+    let legacy_paused = env
+        .storage()
+        .persistent()
+        .get::<DepositDataKey, Map<Symbol, bool>>(&DepositDataKey::PauseSwitches)
+        .and_then(|pause_map| pause_map.get(repay_op.clone()))
+        .unwrap_or(false);
+    legacy_paused
+        || crate::risk_management::is_operation_paused(env, repay_op)
+        || crate::risk_management::is_emergency_paused(env)
+}
+
 /// Repay debt function
 ///
 /// Allows users to repay their borrowed assets, reducing debt and accrued interest.
@@ -125,17 +183,23 @@ fn get_native_asset_address(env: &Env) -> Result<Address, RepayError> {
 /// * `env` - The Soroban environment
 /// * `user` - The address of the user repaying debt
 /// * `asset` - The address of the asset contract to repay (None for native XLM)
-/// * `amount` - The amount to repay
+/// * `amount` - The amount to repay; anything above the current debt (e.g. `i128::MAX`)
+///   repays it in full
 ///
 /// # Returns
-/// Returns a tuple (remaining_debt, interest_paid, principal_paid)
+/// Returns a tuple (remaining_debt, interest_paid, principal_paid), where
+/// `interest_paid + principal_paid` is the amount taken from the user, plus the
+/// early repayment fee on principal of a term loan repaid before maturity (see
+/// [`crate::term_loan`])
 ///
 /// # Errors
 /// * `RepayError::InvalidAmount` - If amount is zero or negative
 /// * `RepayError::InvalidAsset` - If asset address is invalid or not configured
 /// * `RepayError::InsufficientBalance` - If user doesn't have enough balance
-/// * `RepayError::RepayPaused` - If repayments are paused
+/// * `RepayError::RepayPaused` - If repayments are paused and not always allowed
 /// * `RepayError::NoDebt` - If user has no debt to repay
+/// * `RepayError::DustDebtRemaining` - If a partial repayment would leave principal
+///   between zero and the asset's minimum borrow size
 /// * `RepayError::Overflow` - If calculation overflow occurs
 ///
 /// # Security
@@ -154,90 +218,273 @@ pub fn repay_debt(
     asset: Option<Address>,
     amount: i128,
 ) -> Result<(i128, i128, i128), RepayError> {
+    repay_from(env, &user, user.clone(), asset, amount)
+}
+
+/// Repay another user's debt, paying from `payer`'s balance
+///
+/// Applies the payment to `borrower`'s position exactly like [`repay_debt`];
+/// only the source of the tokens differs.
+///
+/// # Returns
+/// Returns a tuple (remaining_debt, interest_paid, principal_paid) of `borrower`'s debt
+///
+/// # Errors
+/// * Any error of [`repay_debt`], with balance checks made against `payer`
+pub fn repay_on_behalf(
+    env: &Env,
+    payer: Address,
+    borrower: Address,
+    asset: Option<Address>,
+    amount: i128,
+) -> Result<(i128, i128, i128), RepayError> {
+    payer.require_auth();
+    repay_from(env, &payer, borrower, asset, amount)
+}
+
+/// Apply a repayment of `user`'s debt with tokens taken from `payer`
+fn repay_from(
+    env: &Env,
+    payer: &Address,
+    user: Address,
+    asset: Option<Address>,
+    amount: i128,
+) -> Result<(i128, i128, i128), RepayError> {
+    // Validate amount
     if amount <= 0 {
         return Err(RepayError::InvalidAmount);
     }
 
-    let pause_switches_key = DepositDataKey::PauseSwitches;
-    if let Some(pause_map) = env.storage().persistent().get::<DepositDataKey, Map<Symbol, bool>>(&pause_switches_key) {
-        if let Some(paused) = pause_map.get(Symbol::new(env, "pause_repay")) {
-            if paused { return Err(RepayError::RepayPaused); }
-        }
+    // Check if repayments are paused
+    if is_repay_paused(env) {
+        return Err(RepayError::RepayPaused);
     }
 
+    // Get current timestamp
     let timestamp = env.ledger().timestamp();
 
-    if let Some(ref asset_addr) = asset {
-        if asset_addr == &env.current_contract_address() {
-            return Err(RepayError::InvalidAsset);
-    // Determine the asset contract address to use
+    // Determine the asset contract address to use: the token itself, or the
+    // configured native asset contract for XLM (if any)
     let asset_addr = match &asset {
         Some(addr) => {
             // Validate asset address - ensure it's not the contract itself
             if addr == &env.current_contract_address() {
                 return Err(RepayError::InvalidAsset);
             }
-            addr.clone()
+            Some(addr.clone())
         }
-        None => get_native_asset_address(env)?,
-    };
-
-    let reserve_factor = if let Some(asset_addr) = asset.as_ref() {
-        let params_key = DepositDataKey::AssetParams(asset_addr.clone());
-        if let Some(params) = env.storage().persistent().get::<DepositDataKey, crate::deposit::AssetParams>(&params_key) {
-            1000 // Default 10%
-        } else {
-            1000
-        }
-    } else {
-        1000
+        None => get_native_asset_address(env).ok(),
     };
 
+    // Get user position
     let position_key = DepositDataKey::Position(user.clone());
-    let mut position = env.storage().persistent().get::<DepositDataKey, Position>(&position_key).ok_or(RepayError::NoDebt)?;
+    let mut position = env
+        .storage()
+        .persistent()
+        .get::<DepositDataKey, Position>(&position_key)
+        .ok_or(RepayError::NoDebt)?;
 
     if position.debt == 0 && position.borrow_interest == 0 {
         return Err(RepayError::NoDebt);
     }
 
-    accrue_interest(env, &mut position)?;
+    // Bring the debt up to the current ledger before applying the payment, so the
+    // remaining debt reflects interest accrued while the position sat idle
+    accrue_interest(env, &user, &asset, &mut position)?;
+
+    // Cap repayment at total debt, so overpayments and the `i128::MAX` sentinel
+    // only take what is owed
+    let total_debt = position
+        .debt
+        .checked_add(position.borrow_interest)
+        .ok_or(RepayError::Overflow)?;
+    let repay_amount = if amount >= total_debt {
+        total_debt
+    } else {
+        amount
+    };
 
-    let total_debt = position.debt.checked_add(position.borrow_interest).ok_or(RepayError::Overflow)?;
-    let repay_amount = if amount >= total_debt { total_debt } else { amount };
+    // Principal of a term loan repaid before maturity carries the early repayment fee
+    let principal_portion = repay_amount.saturating_sub(position.borrow_interest.min(repay_amount));
+    let early_fee = crate::term_loan::early_repayment_fee(env, &user, &asset, principal_portion)?;
+    let amount_due = repay_amount
+        .checked_add(early_fee)
+        .ok_or(RepayError::Overflow)?;
 
-    if let Some(ref asset_addr) = asset {
-        #[cfg(not(test))]
-        {
+    // Handle asset transfer - the payer pays the contract
+    if let Some(ref asset_addr) = asset_addr {
+        // Unit tests only transfer the native asset, whose token contract the test
+        // helpers register; other test assets are bare addresses without a contract
+        if cfg!(not(test)) || asset.is_none() {
             let token_client = soroban_sdk::token::Client::new(env, asset_addr);
-            let user_balance = token_client.balance(&user);
-            if user_balance < repay_amount {
+
+            // Check payer balance
+            let payer_balance = token_client.balance(payer);
+            if payer_balance < amount_due {
                 return Err(RepayError::InsufficientBalance);
             }
-            token_client.transfer_from(&env.current_contract_address(), &user, &env.current_contract_address(), &repay_amount);
+
+            // Transfer tokens from payer to contract
+            // The payer must have approved the contract to spend their tokens
+            token_client.transfer_from(
+                &env.current_contract_address(), // spender (this contract)
+                payer,                           // from (payer)
+                &env.current_contract_address(), // to (this contract)
+                &amount_due,
+            );
         }
     }
 
-    let interest_paid = if repay_amount <= position.borrow_interest { repay_amount } else { position.borrow_interest };
-    let principal_paid = repay_amount.checked_sub(interest_paid).ok_or(RepayError::Overflow)?;
-    // Handle asset transfer - user pays the contract
-    // We use the determined asset_addr (either token or native)
-    let token_client = soroban_sdk::token::Client::new(env, &asset_addr);
+    let (interest_paid, principal_paid, reserve_amount) =
+        apply_repayment(env, &user, &asset, &mut position, repay_amount, timestamp)?;
+    crate::term_loan::collect_early_repayment_fee(env, &user, &asset, principal_paid, early_fee)?;
+    env.storage().persistent().set(&position_key, &position);
+    let remaining_debt = position
+        .debt
+        .checked_add(position.borrow_interest)
+        .unwrap_or(0);
 
-    // Check user balance
-    let user_balance = token_client.balance(&user);
-    if user_balance < repay_amount {
-        return Err(RepayError::InsufficientBalance);
-    }
+    // Add to activity log
+    add_activity_log(
+        env,
+        &user,
+        Symbol::new(env, "repay"),
+        repay_amount,
+        asset.clone(),
+        timestamp,
+    )
+    .map_err(|e| match e {
+        crate::deposit::DepositError::Overflow => RepayError::Overflow,
+        _ => RepayError::Overflow,
+    })?;
+
+    // Emit repay event
+    emit_repay(
+        env,
+        RepayEvent {
+            payer: payer.clone(),
+            borrower: user.clone(),
+            asset: asset.clone(),
+            amount: repay_amount,
+            principal_paid,
+            interest_paid,
+            reserve_amount,
+            remaining_debt,
+            timestamp,
+        },
+    );
+
+    // Emit position updated event
+    emit_position_updated_event(env, &user, &position);
 
-    // Transfer tokens from user to contract
-    // The user must have approved the contract to spend their tokens
-    token_client.transfer_from(
-        &env.current_contract_address(), // spender (this contract)
-        &user,                           // from (user)
-        &env.current_contract_address(), // to (this contract)
-        &repay_amount,
+    // Emit analytics updated event
+    emit_analytics_updated_event(env, &user, "repay", repay_amount, timestamp);
+
+    // Emit user activity tracked event
+    emit_user_activity_tracked_event(
+        env,
+        &user,
+        Symbol::new(env, "repay"),
+        repay_amount,
+        timestamp,
     );
 
+    // Keep the touched entries from being archived
+    crate::ttl::bump_user_entries(env, &user);
+    crate::ttl::bump_protocol_entries(env, &asset);
+
+    crate::liquidate::clear_recovered_breach(env, &user);
+
+    Ok((remaining_debt, interest_paid, principal_paid))
+}
+
+/// Repay debt and optionally supply the rest of `amount` as collateral
+///
+/// Repays like [`repay_debt`]. With `deposit_surplus`, the part of `amount` above
+/// the debt is then deposited through [`crate::deposit::deposit_collateral`];
+/// otherwise only the owed amount is taken. `amount` should be what the user
+/// means to spend, since a surplus is pulled from their balance in full.
+///
+/// # Returns
+/// Returns a tuple (remaining_debt, interest_paid, principal_paid, deposited)
+///
+/// # Errors
+/// * Any error of [`repay_debt`]
+/// * `RepayError::SurplusDepositRejected` - If the deposit fails, e.g. on a
+///   supply cap or pause; the repayment is reverted with it
+pub fn repay_and_deposit(
+    env: &Env,
+    user: Address,
+    asset: Option<Address>,
+    amount: i128,
+    deposit_surplus: bool,
+) -> Result<(i128, i128, i128, i128), RepayError> {
+    let (remaining_debt, interest_paid, principal_paid) =
+        repay_debt(env, user.clone(), asset.clone(), amount)?;
+
+    let surplus = amount
+        .checked_sub(interest_paid)
+        .and_then(|v| v.checked_sub(principal_paid))
+        .ok_or(RepayError::Overflow)?;
+    let deposited = if deposit_surplus && surplus > 0 {
+        crate::deposit::deposit_collateral(env, user, asset, surplus)
+            .map_err(|_| RepayError::SurplusDepositRejected)?;
+        surplus
+    } else {
+        0
+    };
+
+    Ok((remaining_debt, interest_paid, principal_paid, deposited))
+}
+
+/// Quote the amount that repays `user`'s debt in `asset` in full
+///
+/// Uses the same accrual as [`repay_debt`], so the current debt is exactly what a
+/// full repayment in this ledger collects. With `at_timestamp` in the future, the
+/// projected debt also accrues up to that time at the current borrow rate (or the
+/// locked rate for stable debt); earlier timestamps quote the current debt.
+///
+/// # Arguments
+/// * `env` - The Soroban environment
+/// * `user` - The borrower
+/// * `asset` - The borrowed asset (None for native XLM)
+/// * `at_timestamp` - Optional future ledger time to project the debt to
+pub fn get_repay_quote(
+    env: &Env,
+    user: &Address,
+    asset: &Option<Address>,
+    at_timestamp: Option<u64>,
+) -> RepayQuote {
+    let now = env.ledger().timestamp();
+    let timestamp = at_timestamp.unwrap_or(now).max(now);
+    RepayQuote {
+        debt: crate::borrow::get_user_debt(env, user, asset),
+        projected_debt: crate::borrow::get_user_debt_at(env, user, asset, timestamp),
+        timestamp,
+    }
+}
+
+/// Apply `repay_amount` to a position that has already accrued interest
+///
+/// Pays accrued interest first, then principal, and books the repayment in the
+/// per-asset debt and analytics. The caller saves the position. The reserve
+/// share of the interest paid is reported but not credited again, since
+/// `interest_rate::accrue_interest` credited it as it accrued; only its referral
+/// share is moved to the borrower's referrer.
+///
+/// # Returns
+/// Returns a tuple (interest_paid, principal_paid, reserve_amount)
+fn apply_repayment(
+    env: &Env,
+    user: &Address,
+    asset: &Option<Address>,
+    position: &mut Position,
+    repay_amount: i128,
+    timestamp: u64,
+) -> Result<(i128, i128, i128), RepayError> {
+    // Reserve factor applied to interest income (basis points)
+    let reserve_factor = crate::interest_rate::get_reserve_factor_bps(env);
+
     // Calculate interest and principal portions
     // Interest is paid first, then principal
     let interest_paid = if repay_amount <= position.borrow_interest {
@@ -250,69 +497,347 @@ pub fn repay_debt(
         .checked_sub(interest_paid)
         .ok_or(RepayError::Overflow)?;
 
+    // Repay in full or leave at least the minimum, so no dust debt is left behind
+    let remaining_principal = crate::borrow::get_user_debt_principal(env, user, asset)
+        .saturating_sub(principal_paid)
+        .max(0);
+    let min_borrow = crate::borrow::get_min_borrow(env, asset);
+    if remaining_principal > 0 && remaining_principal < min_borrow {
+        return Err(RepayError::DustDebtRemaining);
+    }
+
     // Update position
     position.borrow_interest = position
         .borrow_interest
         .checked_sub(interest_paid)
         .unwrap_or(0); // Should not underflow, but handle gracefully
-
-    position.debt = position.debt.checked_sub(principal_paid).unwrap_or(0); // Should not underflow, but handle gracefully
-
-    position.borrow_interest = position.borrow_interest.checked_sub(interest_paid).unwrap_or(0);
     position.debt = position.debt.checked_sub(principal_paid).unwrap_or(0);
     position.last_accrual_time = timestamp;
 
-    env.storage().persistent().set(&position_key, &position);
+    crate::borrow::update_user_debt_principal(env, user, asset, -principal_paid);
 
-    if interest_paid > 0 {
-        let reserve_amount = interest_paid.checked_mul(reserve_factor).ok_or(RepayError::Overflow)?.checked_div(10000).ok_or(RepayError::Overflow)?;
-        if reserve_amount > 0 {
-            let reserve_key = DepositDataKey::ProtocolReserve(asset.clone());
-            let current_reserve = env.storage().persistent().get::<DepositDataKey, i128>(&reserve_key).unwrap_or(0);
-            env.storage().persistent().set(&reserve_key, &(current_reserve.checked_add(reserve_amount).ok_or(RepayError::Overflow)?));
-        }
-    }
+    // Reserve share of the interest paid, credited to the protocol on accrual
+    let reserve_amount = interest_paid
+        .checked_mul(reserve_factor)
+        .ok_or(RepayError::Overflow)?
+        .checked_div(10000)
+        .ok_or(RepayError::Overflow)?;
+    // Its referral share leaves the reserve now that the borrower is known
+    crate::deposit::divert_reserve_to_referrer(env, user, asset, reserve_amount)
+        .map_err(|_| RepayError::Overflow)?;
+
+    // Update user analytics
+    update_user_analytics_repay(env, user, repay_amount, timestamp)?;
 
-    update_user_analytics_repay(env, &user, repay_amount, timestamp)?;
+    // Update protocol analytics
     update_protocol_analytics_repay(env, repay_amount)?;
-    add_activity_log(env, &user, Symbol::new(env, "repay"), repay_amount, asset.clone(), timestamp).map_err(|e| RepayError::Overflow)?;
-    log_repay(env, RepayEvent { user: user.clone(), asset: asset.clone(), amount: repay_amount, timestamp });
+    crate::deposit::update_asset_totals(env, asset, 0, -principal_paid, 0)
+        .map_err(|_| RepayError::Overflow)?;
+
+    Ok((interest_paid, principal_paid, reserve_amount))
+}
+
+/// Repay debt out of the user's own collateral deposit
+///
+/// Burns `collateral_asset` from the user's deposit and applies it to their debt
+/// in `debt_asset`, so a position can be closed without bringing the borrowed
+/// asset in. Amounts above the current debt repay it in full.
+///
+/// The collateral must be the debt asset and is applied as-is. Repaying from a
+/// different asset would need the collateral swapped into the debt asset, and
+/// there is no AMM contract to do that yet.
+///
+/// # Arguments
+/// * `env` - The Soroban environment
+/// * `user` - The address of the user repaying debt
+/// * `debt_asset` - The asset to repay (None for native XLM)
+/// * `collateral_asset` - The deposit to repay from (None for native XLM)
+/// * `repay_amount` - The amount of debt to repay
+/// * `max_collateral_in` - The most collateral the user accepts to give up
+///
+/// # Returns
+/// Returns a tuple (remaining_debt, collateral_used)
+///
+/// # Errors
+/// * `RepayError::InvalidAmount` - If either amount is zero or negative
+/// * `RepayError::InvalidAsset` - If an asset is invalid, the deposit is not
+///   collateral or the assets differ
+/// * `RepayError::RepayPaused` - If repayments are paused
+/// * `RepayError::NoDebt` - If user has no debt to repay
+/// * `RepayError::SlippageExceeded` - If the repayment needs more than `max_collateral_in`
+/// * `RepayError::InsufficientBalance` - If the deposit cannot cover the collateral needed
+/// * `RepayError::PositionUnhealthy` - If the account ends up unhealthy and
+///   less healthy than before
+/// * `RepayError::DustDebtRemaining` - As in [`repay_debt`]
+pub fn repay_with_collateral(
+    env: &Env,
+    user: Address,
+    debt_asset: Option<Address>,
+    collateral_asset: Option<Address>,
+    repay_amount: i128,
+    max_collateral_in: i128,
+) -> Result<(i128, i128), RepayError> {
+    user.require_auth();
+
+    if repay_amount <= 0 || max_collateral_in <= 0 {
+        return Err(RepayError::InvalidAmount);
+    }
+    if is_repay_paused(env) {
+        return Err(RepayError::RepayPaused);
+    }
+    if debt_asset.as_ref() == Some(&env.current_contract_address()) {
+        return Err(RepayError::InvalidAsset);
+    }
+    if collateral_asset != debt_asset {
+        return Err(RepayError::InvalidAsset);
+    }
+    if !crate::deposit::is_collateral_enabled(env, &user, &collateral_asset) {
+        return Err(RepayError::InvalidAsset);
+    }
+
+    let timestamp = env.ledger().timestamp();
+    let position_key = DepositDataKey::Position(user.clone());
+    let mut position = env
+        .storage()
+        .persistent()
+        .get::<DepositDataKey, Position>(&position_key)
+        .ok_or(RepayError::NoDebt)?;
+    if position.debt == 0 && position.borrow_interest == 0 {
+        return Err(RepayError::NoDebt);
+    }
+
+    let health_before =
+        crate::liquidate::get_health_factor(env, &user).map_err(|_| RepayError::Overflow)?;
+    accrue_interest(env, &user, &debt_asset, &mut position)?;
+
+    let total_debt = position
+        .debt
+        .checked_add(position.borrow_interest)
+        .ok_or(RepayError::Overflow)?;
+    let repay_amount = repay_amount.min(total_debt);
+
+    // The deposit is applied to the debt one for one
+    let collateral_in = repay_amount;
+    if collateral_in > max_collateral_in {
+        return Err(RepayError::SlippageExceeded);
+    }
+
+    // Burn the shares backing the collateral used
+    if collateral_in > crate::deposit::get_user_deposit_balance(env, &user, &collateral_asset) {
+        return Err(RepayError::InsufficientBalance);
+    }
+    crate::deposit::redeem_shares(env, &user, &collateral_asset, collateral_in)
+        .map_err(|_| RepayError::InsufficientBalance)?;
+    let collateral_key = DepositDataKey::CollateralBalance(user.clone());
+    let new_collateral = env
+        .storage()
+        .persistent()
+        .get::<DepositDataKey, i128>(&collateral_key)
+        .unwrap_or(0)
+        .saturating_sub(collateral_in)
+        .max(0);
+    env.storage()
+        .persistent()
+        .set(&collateral_key, &new_collateral);
+    position.collateral = new_collateral;
+
+    let (interest_paid, principal_paid, reserve_amount) = apply_repayment(
+        env,
+        &user,
+        &debt_asset,
+        &mut position,
+        repay_amount,
+        timestamp,
+    )?;
+    env.storage().persistent().set(&position_key, &position);
+    let remaining_debt = position
+        .debt
+        .checked_add(position.borrow_interest)
+        .unwrap_or(0);
+    crate::deposit::update_asset_totals(env, &collateral_asset, -collateral_in, 0, 0)
+        .map_err(|_| RepayError::Overflow)?;
+    crate::withdraw::update_protocol_analytics_withdraw(env, collateral_in)
+        .map_err(|_| RepayError::Overflow)?;
+
+    // Deleveraging may not leave the account unhealthy and worse off
+    let health_after =
+        crate::liquidate::get_health_factor(env, &user).map_err(|_| RepayError::Overflow)?;
+    if health_after < 10000 && health_after < health_before {
+        return Err(RepayError::PositionUnhealthy);
+    }
 
-    // Add to activity log
     add_activity_log(
         env,
         &user,
-        Symbol::new(env, "repay"),
+        Symbol::new(env, "repay_collateral"),
         repay_amount,
-        asset.clone(),
+        debt_asset.clone(),
         timestamp,
     )
-    .map_err(|e| match e {
-        crate::deposit::DepositError::Overflow => RepayError::Overflow,
-        _ => RepayError::Overflow,
-    })?;
+    .map_err(|_| RepayError::Overflow)?;
 
-    // Emit repay event
     emit_repay(
         env,
         RepayEvent {
-            user: user.clone(),
-            asset: asset.clone(),
+            payer: user.clone(),
+            borrower: user.clone(),
+            asset: debt_asset.clone(),
             amount: repay_amount,
+            principal_paid,
+            interest_paid,
+            reserve_amount,
+            remaining_debt,
             timestamp,
         },
     );
-
-    // Emit position updated event
     emit_position_updated_event(env, &user, &position);
     emit_analytics_updated_event(env, &user, "repay", repay_amount, timestamp);
-    emit_user_activity_tracked_event(env, &user, Symbol::new(env, "repay"), repay_amount, timestamp);
 
-    let remaining_debt = position.debt.checked_add(position.borrow_interest).unwrap_or(0);
-    Ok((remaining_debt, interest_paid, principal_paid))
+    // Keep the touched entries from being archived
+    crate::ttl::bump_user_entries(env, &user);
+    crate::ttl::bump_protocol_entries(env, &debt_asset);
+    crate::ttl::bump_protocol_entries(env, &collateral_asset);
+
+    Ok((remaining_debt, collateral_in))
+}
+
+/// Written-off debt in `asset` that the protocol reserve could not absorb
+pub fn get_bad_debt(env: &Env, asset: &Option<Address>) -> i128 {
+    env.storage()
+        .persistent()
+        .get::<DepositDataKey, i128>(&DepositDataKey::BadDebt(asset.clone()))
+        .unwrap_or(0)
+}
+
+/// Bad debt summed across all assets
+pub fn get_total_bad_debt(env: &Env) -> i128 {
+    env.storage()
+        .persistent()
+        .get::<DepositDataKey, i128>(&DepositDataKey::TotalBadDebt)
+        .unwrap_or(0)
+}
+
+/// Write off part or all of a borrower's unrecoverable debt (admin only)
+///
+/// Clears up to `amount` of `borrower`'s debt in `asset`, accrued interest first,
+/// without any token transfer. The written-off amount is taken out of
+/// `ProtocolReserve(asset)` as far as it goes, and the shortfall is added to
+/// `BadDebt(asset)`. Amounts above the debt write it off in full.
+///
+/// # Returns
+/// Returns a tuple (remaining_debt, from_reserve, bad_debt) for this write-off
+///
+/// # Errors
+/// * `RepayError::Unauthorized` - If `admin` is not the protocol admin
+/// * `RepayError::InvalidAmount` - If amount is zero or negative
+/// * `RepayError::NoDebt` - If the borrower owes nothing in `asset`
+/// * `RepayError::Overflow` - If calculation overflow occurs
+pub fn write_off_debt(
+    env: &Env,
+    admin: Address,
+    borrower: Address,
+    asset: Option<Address>,
+    amount: i128,
+) -> Result<(i128, i128, i128), RepayError> {
+    admin.require_auth();
+    crate::admin::require_admin(env, &admin).map_err(|_| RepayError::Unauthorized)?;
+    if amount <= 0 {
+        return Err(RepayError::InvalidAmount);
+    }
+
+    let timestamp = env.ledger().timestamp();
+    let position_key = DepositDataKey::Position(borrower.clone());
+    let mut position = env
+        .storage()
+        .persistent()
+        .get::<DepositDataKey, Position>(&position_key)
+        .ok_or(RepayError::NoDebt)?;
+    accrue_interest(env, &borrower, &asset, &mut position)?;
+
+    let asset_debt = crate::borrow::get_user_debt(env, &borrower, &asset);
+    if asset_debt <= 0 {
+        return Err(RepayError::NoDebt);
+    }
+    let written_off = amount.min(asset_debt);
+    let interest_part = written_off.min(position.borrow_interest);
+    let principal_part = written_off - interest_part;
+
+    position.borrow_interest -= interest_part;
+    position.debt = position.debt.checked_sub(principal_part).unwrap_or(0);
+    position.last_accrual_time = timestamp;
+    env.storage().persistent().set(&position_key, &position);
+    crate::borrow::update_user_debt_principal(env, &borrower, &asset, -principal_part);
+    update_protocol_analytics_repay(env, written_off)?;
+    crate::deposit::update_asset_totals(env, &asset, 0, -principal_part, 0)
+        .map_err(|_| RepayError::Overflow)?;
+    let (from_reserve, bad_debt) = absorb_loss(env, &asset, written_off)?;
+
+    let remaining_debt = position
+        .debt
+        .checked_add(position.borrow_interest)
+        .unwrap_or(0);
+    emit_debt_written_off(
+        env,
+        DebtWrittenOffEvent {
+            admin,
+            borrower: borrower.clone(),
+            asset: asset.clone(),
+            amount: written_off,
+            from_reserve,
+            bad_debt,
+            remaining_debt,
+            timestamp,
+        },
+    );
+    emit_position_updated_event(env, &borrower, &position);
+
+    crate::ttl::bump_user_entries(env, &borrower);
+    crate::ttl::bump_protocol_entries(env, &asset);
+
+    Ok((remaining_debt, from_reserve, bad_debt))
+}
+
+/// Absorb a loss of `amount` in `asset` that no borrower will repay
+///
+/// The protocol reserve for the asset absorbs the loss first; the rest is
+/// recorded as bad debt. Returns (from_reserve, bad_debt).
+pub(crate) fn absorb_loss(
+    env: &Env,
+    asset: &Option<Address>,
+    amount: i128,
+) -> Result<(i128, i128), RepayError> {
+    let reserve_key = DepositDataKey::ProtocolReserve(asset.clone());
+    let reserve = env
+        .storage()
+        .persistent()
+        .get::<DepositDataKey, i128>(&reserve_key)
+        .unwrap_or(0);
+    let from_reserve = amount.min(reserve.max(0));
+    env.storage()
+        .persistent()
+        .set(&reserve_key, &(reserve - from_reserve));
+    crate::deposit::update_asset_totals(env, asset, 0, 0, -from_reserve)
+        .map_err(|_| RepayError::Overflow)?;
+
+    let bad_debt = amount - from_reserve;
+    if bad_debt > 0 {
+        let bad_debt_key = DepositDataKey::BadDebt(asset.clone());
+        let asset_bad_debt = get_bad_debt(env, asset)
+            .checked_add(bad_debt)
+            .ok_or(RepayError::Overflow)?;
+        env.storage()
+            .persistent()
+            .set(&bad_debt_key, &asset_bad_debt);
+        let total_bad_debt = get_total_bad_debt(env)
+            .checked_add(bad_debt)
+            .ok_or(RepayError::Overflow)?;
+        env.storage()
+            .persistent()
+            .set(&DepositDataKey::TotalBadDebt, &total_bad_debt);
+    }
+    Ok((from_reserve, bad_debt))
 }
 
-fn update_user_analytics_repay(env: &Env, user: &Address, amount: i128, timestamp: u64) -> Result<(), RepayError> {
 /// Update user analytics after repayment
 ///
 /// # Arguments
@@ -330,20 +855,40 @@ fn update_user_analytics_repay(
     timestamp: u64,
 ) -> Result<(), RepayError> {
     let analytics_key = DepositDataKey::UserAnalytics(user.clone());
-    let mut analytics = env.storage().persistent().get::<DepositDataKey, UserAnalytics>(&analytics_key)
+    #[allow(clippy::unnecessary_lazy_evaluations)]
+    let mut analytics = env
+        .storage()
+        .persistent()
+        .get::<DepositDataKey, UserAnalytics>(&analytics_key)
         .unwrap_or_else(|| UserAnalytics {
-            total_deposits: 0, total_borrows: 0, total_withdrawals: 0, total_repayments: 0,
-            collateral_value: 0, debt_value: 0, collateralization_ratio: 0, activity_score: 0,
-            transaction_count: 0, first_interaction: timestamp, last_activity: timestamp,
-            risk_level: 0, loyalty_tier: 0,
+            total_deposits: 0,
+            total_borrows: 0,
+            total_withdrawals: 0,
+            total_repayments: 0,
+            collateral_value: 0,
+            debt_value: 0,
+            collateralization_ratio: 0,
+            activity_score: 0,
+            transaction_count: 0,
+            first_interaction: timestamp,
+            last_activity: timestamp,
+            risk_level: 0,
+            loyalty_tier: 0,
         });
 
-    analytics.total_repayments = analytics.total_repayments.checked_add(amount).ok_or(RepayError::Overflow)?;
+    analytics.total_repayments = analytics
+        .total_repayments
+        .checked_add(amount)
+        .ok_or(RepayError::Overflow)?;
     analytics.debt_value = analytics.debt_value.checked_sub(amount).unwrap_or(0);
 
+    // Recalculate collateralization ratio
     if analytics.debt_value > 0 && analytics.collateral_value > 0 {
-        analytics.collateralization_ratio = analytics.collateral_value.checked_mul(10000)
-            .and_then(|v| v.checked_div(analytics.debt_value)).unwrap_or(0);
+        analytics.collateralization_ratio = analytics
+            .collateral_value
+            .checked_mul(10000)
+            .and_then(|v| v.checked_div(analytics.debt_value))
+            .unwrap_or(0);
     } else {
         analytics.collateralization_ratio = 0;
     }
@@ -355,10 +900,6 @@ fn update_user_analytics_repay(
     Ok(())
 }
 
-fn update_protocol_analytics_repay(env: &Env, amount: i128) -> Result<(), RepayError> {
-    let analytics_key = DepositDataKey::ProtocolAnalytics;
-    let mut analytics = env.storage().persistent().get::<DepositDataKey, ProtocolAnalytics>(&analytics_key)
-        .unwrap_or(ProtocolAnalytics { total_deposits: 0, total_borrows: 0, total_value_locked: 0 });
 /// Update protocol analytics after repayment
 ///
 /// # Arguments
@@ -0,0 +1,367 @@
+//! # Repayment Schedule Module
+//!
+//! Lets a borrower commit to paying down a debt in equal installments.
+//!
+//! `create_repayment_schedule` snapshots the user's current debt in one asset
+//! and splits it into `installments` payments, one becoming due every
+//! `interval_secs`. `repay_installment` pays the next installment through the
+//! regular repayment path, so interest is settled before principal. Paying early
+//! is allowed; skipped installments must be caught up one call at a time.
+//! Interest accruing after the snapshot is not part of the schedule and stays
+//! owed once it ends.
+//!
+//! ## Penalty Interest
+//! Once an installment is past its due time, its amount accrues penalty
+//! interest at the admin-configured `penalty_rate_bps` on top of the regular
+//! borrow rate. The penalty is booked with the rest of the position's interest
+//! whenever it accrues, and stops for an installment once it is paid.
+//!
+//! ## Delinquency
+//! A schedule whose next unpaid installment has been overdue for at least
+//! `delinquency_secs` makes the whole account eligible for liquidation,
+//! regardless of its health factor.
+//!
+//! A user has at most one active schedule per asset. The schedule is removed
+//! when its last installment is paid or the debt in the asset is cleared.
+//!
+//! ## Storage Keys
+//! - `DepositDataKey::RepaymentSchedule(id)` — an active [`RepaymentSchedule`]
+//! - `DepositDataKey::NextRepaymentScheduleId` — id counter
+//! - `DepositDataKey::UserRepaymentSchedule(user, asset)` — id of the user's schedule for an asset
+//! - `DepositDataKey::RepaymentPenaltyConfig` — the [`RepaymentPenaltyConfig`]
+
+use soroban_sdk::{contracttype, Address, Env};
+
+use crate::borrow::BorrowError;
+use crate::deposit::DepositDataKey;
+use crate::events::{
+    emit_installment_repaid, emit_repayment_scheduled, InstallmentRepaidEvent,
+    RepaymentScheduledEvent,
+};
+use crate::repay::RepayError;
+
+/// Upper bound on installments, keeping penalty accrual bounded
+const MAX_INSTALLMENTS: u32 = 120;
+
+/// Default time an installment may stay unpaid before the account is delinquent (30 days)
+const DEFAULT_DELINQUENCY_SECS: u64 = 30 * 86_400;
+
+/// A plan to repay `total_amount` of debt in equal installments
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct RepaymentSchedule {
+    /// Sequence number, unique across all users
+    pub id: u64,
+    /// Borrower the schedule belongs to
+    pub user: Address,
+    /// Debt asset (None for native XLM)
+    pub asset: Option<Address>,
+    /// Debt snapshotted when the schedule was created
+    pub total_amount: i128,
+    /// Number of installments
+    pub installments: u32,
+    /// Time between installments
+    pub interval_secs: u64,
+    /// Time the schedule was created; the first installment is due one interval later
+    pub start_time: u64,
+    /// Installments paid so far
+    pub installments_paid: u32,
+    /// Amount of the schedule paid so far
+    pub amount_paid: i128,
+    /// Time penalty interest was last booked up to
+    pub penalty_accrued_until: u64,
+}
+
+/// Penalty terms for late installments
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct RepaymentPenaltyConfig {
+    /// Annual penalty rate on overdue installments, in basis points
+    pub penalty_rate_bps: i128,
+    /// Time an installment may stay overdue before the account becomes liquidatable
+    pub delinquency_secs: u64,
+}
+
+/// Get the penalty terms for late installments (no penalty and 30 days by default)
+pub fn get_repayment_penalty_config(env: &Env) -> RepaymentPenaltyConfig {
+    env.storage()
+        .persistent()
+        .get::<DepositDataKey, RepaymentPenaltyConfig>(&DepositDataKey::RepaymentPenaltyConfig)
+        .unwrap_or(RepaymentPenaltyConfig {
+            penalty_rate_bps: 0,
+            delinquency_secs: DEFAULT_DELINQUENCY_SECS,
+        })
+}
+
+/// Set the penalty terms for late installments (admin only)
+///
+/// # Errors
+/// * `RepayError::Unauthorized` - If `caller` is not the admin
+/// * `RepayError::InvalidAmount` - If the rate is outside 0..=10000 or the threshold is zero
+pub fn set_repayment_penalty_config(
+    env: &Env,
+    caller: Address,
+    penalty_rate_bps: i128,
+    delinquency_secs: u64,
+) -> Result<(), RepayError> {
+    crate::admin::require_admin(env, &caller).map_err(|_| RepayError::Unauthorized)?;
+
+    if !(0..=10_000).contains(&penalty_rate_bps) || delinquency_secs == 0 {
+        return Err(RepayError::InvalidAmount);
+    }
+    env.storage().persistent().set(
+        &DepositDataKey::RepaymentPenaltyConfig,
+        &RepaymentPenaltyConfig {
+            penalty_rate_bps,
+            delinquency_secs,
+        },
+    );
+    Ok(())
+}
+
+/// Get an active repayment schedule by id
+pub fn get_repayment_schedule(env: &Env, schedule_id: u64) -> Option<RepaymentSchedule> {
+    env.storage()
+        .persistent()
+        .get::<DepositDataKey, RepaymentSchedule>(&DepositDataKey::RepaymentSchedule(schedule_id))
+}
+
+/// Get a user's active repayment schedule for an asset
+fn get_user_schedule(
+    env: &Env,
+    user: &Address,
+    asset: &Option<Address>,
+) -> Option<RepaymentSchedule> {
+    env.storage()
+        .persistent()
+        .get::<DepositDataKey, u64>(&DepositDataKey::UserRepaymentSchedule(
+            user.clone(),
+            asset.clone(),
+        ))
+        .and_then(|id| get_repayment_schedule(env, id))
+}
+
+/// Remove a user's schedule for an asset, if any
+pub(crate) fn clear_schedule(env: &Env, user: &Address, asset: &Option<Address>) {
+    let key = DepositDataKey::UserRepaymentSchedule(user.clone(), asset.clone());
+    if let Some(id) = env.storage().persistent().get::<DepositDataKey, u64>(&key) {
+        env.storage()
+            .persistent()
+            .remove(&DepositDataKey::RepaymentSchedule(id));
+        env.storage().persistent().remove(&key);
+    }
+}
+
+/// Cumulative amount due after `installments` payments
+///
+/// Rounds down, so the last installment picks up the remainder.
+fn cumulative_target(schedule: &RepaymentSchedule, installments: u32) -> Option<i128> {
+    schedule
+        .total_amount
+        .checked_mul(i128::from(installments))
+        .map(|amount| amount / i128::from(schedule.installments))
+}
+
+/// Time installment number `installment` (1-based) falls due
+fn due_time(schedule: &RepaymentSchedule, installment: u32) -> u64 {
+    schedule.start_time.saturating_add(
+        schedule
+            .interval_secs
+            .saturating_mul(u64::from(installment)),
+    )
+}
+
+/// Penalty interest accrued on a user's overdue installments in `asset` up to `current_time`
+///
+/// Each overdue installment accrues from its due time, or from the last time
+/// penalty was booked if later. With `persist` set the schedule records that the
+/// penalty has been booked up to `current_time`.
+pub(crate) fn accrue_penalty(
+    env: &Env,
+    user: &Address,
+    asset: &Option<Address>,
+    current_time: u64,
+    persist: bool,
+) -> Result<i128, BorrowError> {
+    let Some(mut schedule) = get_user_schedule(env, user, asset) else {
+        return Ok(0);
+    };
+    let rate_bps = get_repayment_penalty_config(env).penalty_rate_bps;
+
+    let mut penalty: i128 = 0;
+    if rate_bps > 0 {
+        for installment in (schedule.installments_paid + 1)..=schedule.installments {
+            let due = due_time(&schedule, installment);
+            if due >= current_time {
+                break;
+            }
+            let amount = cumulative_target(&schedule, installment)
+                .zip(cumulative_target(&schedule, installment - 1))
+                .map(|(target, previous)| target - previous)
+                .ok_or(BorrowError::Overflow)?;
+            let interest = crate::interest_rate::calculate_accrued_debt(
+                amount,
+                due.max(schedule.penalty_accrued_until),
+                current_time,
+                rate_bps,
+            )
+            .map_err(|_| BorrowError::Overflow)?;
+            penalty = penalty.checked_add(interest).ok_or(BorrowError::Overflow)?;
+        }
+    }
+
+    if persist && schedule.penalty_accrued_until < current_time {
+        schedule.penalty_accrued_until = current_time;
+        env.storage()
+            .persistent()
+            .set(&DepositDataKey::RepaymentSchedule(schedule.id), &schedule);
+    }
+    Ok(penalty)
+}
+
+/// Whether any of `user`'s schedules has an installment overdue past the delinquency threshold
+pub fn is_delinquent(env: &Env, user: &Address) -> bool {
+    let now = env.ledger().timestamp();
+    let delinquency_secs = get_repayment_penalty_config(env).delinquency_secs;
+    crate::borrow::get_user_borrowed_assets(env, user)
+        .iter()
+        .filter_map(|asset| get_user_schedule(env, user, &asset))
+        .any(|schedule| {
+            schedule.installments_paid < schedule.installments
+                && due_time(&schedule, schedule.installments_paid + 1)
+                    .saturating_add(delinquency_secs)
+                    <= now
+        })
+}
+
+/// Split a user's current debt in `asset` into `installments` equal payments
+///
+/// # Returns
+/// The schedule id
+///
+/// # Errors
+/// * `RepayError::InvalidAmount` - If the installment count or interval is zero, or
+///   there are more than 120 installments
+/// * `RepayError::ScheduleExists` - If the user already has a schedule for the asset
+/// * `RepayError::NoDebt` - If the user owes nothing in the asset
+pub fn create_repayment_schedule(
+    env: &Env,
+    user: Address,
+    asset: Option<Address>,
+    installments: u32,
+    interval_secs: u64,
+) -> Result<u64, RepayError> {
+    user.require_auth();
+
+    if installments == 0 || installments > MAX_INSTALLMENTS || interval_secs == 0 {
+        return Err(RepayError::InvalidAmount);
+    }
+    if get_user_schedule(env, &user, &asset).is_some() {
+        return Err(RepayError::ScheduleExists);
+    }
+    let total_amount = crate::borrow::get_user_debt(env, &user, &asset);
+    if total_amount <= 0 {
+        return Err(RepayError::NoDebt);
+    }
+
+    let id = env
+        .storage()
+        .persistent()
+        .get::<DepositDataKey, u64>(&DepositDataKey::NextRepaymentScheduleId)
+        .unwrap_or(1);
+    env.storage()
+        .persistent()
+        .set(&DepositDataKey::NextRepaymentScheduleId, &(id + 1));
+
+    let timestamp = env.ledger().timestamp();
+    let schedule = RepaymentSchedule {
+        id,
+        user: user.clone(),
+        asset: asset.clone(),
+        total_amount,
+        installments,
+        interval_secs,
+        start_time: timestamp,
+        installments_paid: 0,
+        amount_paid: 0,
+        penalty_accrued_until: timestamp,
+    };
+    env.storage()
+        .persistent()
+        .set(&DepositDataKey::RepaymentSchedule(id), &schedule);
+    env.storage().persistent().set(
+        &DepositDataKey::UserRepaymentSchedule(user.clone(), asset.clone()),
+        &id,
+    );
+
+    emit_repayment_scheduled(
+        env,
+        RepaymentScheduledEvent {
+            schedule_id: id,
+            user,
+            asset,
+            total_amount,
+            installments,
+            interval_secs,
+            timestamp,
+        },
+    );
+
+    Ok(id)
+}
+
+/// Pay the next installment of a schedule
+///
+/// The payment goes through [`crate::repay::repay_debt`], so accrued interest,
+/// including any penalty, is settled before principal.
+///
+/// # Returns
+/// Tuple of (remaining_debt, amount_paid)
+///
+/// # Errors
+/// * `RepayError::ScheduleNotFound` - If `user` has no active schedule with this id
+/// * Any error a regular repayment of the installment can return
+pub fn repay_installment(
+    env: &Env,
+    user: Address,
+    schedule_id: u64,
+) -> Result<(i128, i128), RepayError> {
+    user.require_auth();
+
+    let schedule = get_repayment_schedule(env, schedule_id)
+        .filter(|schedule| schedule.user == user)
+        .ok_or(RepayError::ScheduleNotFound)?;
+    let next = schedule.installments_paid + 1;
+    let target = cumulative_target(&schedule, next).ok_or(RepayError::Overflow)?;
+    let amount = target - schedule.amount_paid;
+
+    let (remaining_debt, interest_paid, principal_paid) =
+        crate::repay::repay_debt(env, user.clone(), schedule.asset.clone(), amount)?;
+    let paid = interest_paid + principal_paid;
+
+    // Reload: the repayment booked penalty interest on the schedule
+    if let Some(mut schedule) = get_repayment_schedule(env, schedule_id) {
+        if next == schedule.installments {
+            clear_schedule(env, &user, &schedule.asset);
+        } else {
+            schedule.installments_paid = next;
+            schedule.amount_paid = target;
+            env.storage()
+                .persistent()
+                .set(&DepositDataKey::RepaymentSchedule(schedule_id), &schedule);
+        }
+    }
+
+    emit_installment_repaid(
+        env,
+        InstallmentRepaidEvent {
+            schedule_id,
+            user,
+            amount: paid,
+            installments_paid: next,
+            timestamp: env.ledger().timestamp(),
+        },
+    );
+
+    Ok((remaining_debt, paid))
+}
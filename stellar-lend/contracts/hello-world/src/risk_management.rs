@@ -8,9 +8,65 @@
 //! - **Close factor** (default 50%): max percentage of debt liquidatable per transaction
 //! - **Liquidation incentive** (default 10%): bonus awarded to liquidators
 //!
+//! ## Efficiency Mode
+//! The admin can define e-mode categories of correlated assets with their own
+//! collateral factor and liquidation threshold. A user who opts into a category
+//! is measured against those instead of the global parameters whenever every
+//! asset in their position belongs to it, and cannot borrow assets outside it.
+//!
+//! ## Exposure Limit
+//! The admin can cap the oracle value of the debt any single account may hold
+//! (`max_user_debt_value`, 0 = unlimited). Only new borrows are checked, so a
+//! position pushed over the cap by price moves can still be repaid or liquidated.
+//!
+//! ## Liquidation Grace Window
+//! With `liquidation_grace_secs` set, an account that falls below the
+//! liquidation threshold only becomes liquidatable once the breach has been
+//! recorded for that long, so a short price wick does not wipe out the borrower.
+//! Accounts whose health factor is below `immediate_liquidation_health_factor`
+//! skip the wait. Breaches are recorded, and cleared on recovery, by anyone
+//! calling `sync_liquidation_breach`.
+//!
+//! ## Liquidation Cooldown
+//! `liquidation_cooldown_ledgers` is the number of ledgers that must pass after a
+//! liquidation before the same borrower can be liquidated again, so partial
+//! liquidations cannot be chained within a ledger past the close factor. Accounts
+//! below `immediate_liquidation_health_factor` are exempt.
+//!
+//! ## Liquidation Protocol Fee
+//! `liquidation_protocol_fee_bps` is the share of each liquidation incentive
+//! credited to the protocol reserve of the seized collateral instead of being
+//! paid to the liquidator, at most [`MAX_LIQUIDATION_PROTOCOL_FEE_BPS`].
+//!
+//! ## Liquidator Allowlist
+//! For a phased launch the admin can restrict liquidations to vetted keepers:
+//! while `LiquidatorAllowlistEnabled` is set, only addresses added with
+//! `add_liquidator` may liquidate. Removals take effect on the next call.
+//!
+//! ## Liquidation AMM
+//! `liquidate_and_swap` hands seized collateral to an AMM contract, so only the
+//! one the admin registered with `set_liquidation_amm` is accepted.
+//!
+//! ## Liquidation Snapshots
+//! Every liquidation records the borrower's position as it stood beforehand
+//! under `LiquidationSnapshot(id)`. Only the latest
+//! `liquidation_snapshot_capacity` are kept, at most
+//! [`MAX_LIQUIDATION_SNAPSHOT_CAPACITY`].
+//!
+//! ## Minimum Liquidation Reward
+//! So that small positions are still worth a keeper's while,
+//! `min_liquidation_reward` sets a floor, in base currency, on the incentive a
+//! liquidator keeps. Shortfalls are topped up from the protocol reserve of the
+//! seized collateral, as far as it goes.
+//!
 //! ## Pause Controls
 //! - Per-operation pause switches (deposit, withdraw, borrow, repay, liquidate)
 //! - Global emergency pause that halts all operations immediately
+//! - Per-asset pause switches that freeze a single asset's operations
+//!
+//! Repayments ignore all of these while `repay_always_allowed` is set (the
+//! default), so a pause never leaves borrowers unable to stop interest from
+//! accruing towards liquidation. Liquidations stay pausable either way.
 //!
 //! ## Safety
 //! - Parameter changes are limited to ±10% per update to prevent drastic shifts.
@@ -19,11 +75,25 @@
 
 #![allow(unused)]
 use crate::events::{
-    emit_admin_action, emit_pause_state_changed, emit_risk_params_updated, AdminActionEvent,
-    PauseStateChangedEvent, RiskParamsUpdatedEvent,
+    emit_admin_action, emit_asset_pause_changed, emit_emode_category_updated,
+    emit_pause_state_changed, emit_risk_params_updated, emit_user_emode_set, AdminActionEvent,
+    AssetPauseChangedEvent, EModeCategoryUpdatedEvent, PauseStateChangedEvent,
+    RiskParamsUpdatedEvent, UserEModeSetEvent,
 };
 use soroban_sdk::{contracterror, contracttype, Address, Env, IntoVal, Map, Symbol, Val, Vec};
 
+/// Basis points scale (100% = 10,000)
+const BASIS_POINTS: i128 = 10_000;
+
+/// Largest share of a liquidation incentive the protocol may keep (30%)
+pub const MAX_LIQUIDATION_PROTOCOL_FEE_BPS: i128 = 3_000;
+
+/// Liquidation snapshots kept until the admin configures otherwise
+pub const DEFAULT_LIQUIDATION_SNAPSHOT_CAPACITY: u32 = 100;
+
+/// Most liquidation snapshots that may be kept
+pub const MAX_LIQUIDATION_SNAPSHOT_CAPACITY: u32 = 1_000;
+
 /// Errors that can occur during risk management operations
 #[contracterror]
 #[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
@@ -55,6 +125,10 @@ pub enum RiskManagementError {
     GovernanceRequired = 12,
     /// Contract has already been initialized
     AlreadyInitialized = 13,
+    /// Reentrancy detected
+    Reentrancy = 14,
+    /// E-mode category does not exist
+    EModeCategoryNotFound = 15,
 }
 /// Storage keys for risk management data
 #[contracttype]
@@ -73,6 +147,39 @@ pub enum RiskDataKey {
     /// Timelock for safety of sensitive parameter changes
     /// Value type: u64 (timestamp)
     ParameterChangeTimelock,
+    /// Per-asset operation pause switches (None asset for native XLM)
+    /// Value type: Map<Symbol, bool>
+    AssetPauseSwitches(Option<Address>),
+    /// E-mode category by id
+    /// Value type: EModeCategory
+    EModeCategory(u32),
+    /// E-mode category a user opted into (absent = none)
+    /// Value type: u32
+    UserEMode(Address),
+    /// Whether repayments bypass pause switches and emergency pause (absent = true)
+    /// Value type: bool
+    RepayAlwaysAllowed,
+    /// When a borrower was first seen below the liquidation threshold
+    /// Value type: u64 (timestamp)
+    LiquidationBreach(Address),
+    /// Ledger sequence of the borrower's last liquidation
+    /// Value type: u32
+    LastLiquidationLedger(Address),
+    /// Whether only allowlisted liquidators may liquidate (absent = false)
+    /// Value type: bool
+    LiquidatorAllowlistEnabled,
+    /// Liquidator on the allowlist
+    /// Value type: bool
+    AllowedLiquidator(Address),
+    /// AMM contract `liquidate_and_swap` may swap seized collateral through
+    /// Value type: Address
+    LiquidationAmm,
+    /// Position state recorded at the start of a liquidation, by id
+    /// Value type: LiquidationSnapshot
+    LiquidationSnapshot(u64),
+    /// Number of liquidation snapshots ever recorded, the id of the latest
+    /// Value type: u64
+    LiquidationCount,
 }
 
 /// Risk configuration parameters for pause switches
@@ -83,6 +190,40 @@ pub struct RiskConfig {
     pub pause_switches: Map<Symbol, bool>,
     /// Last update timestamp
     pub last_update: u64,
+    /// Maximum value of a single account's debt, priced like liquidation
+    /// valuations (0 = unlimited)
+    pub max_user_debt_value: i128,
+    /// How long a threshold breach must last before liquidation (0 = no grace)
+    pub liquidation_grace_secs: u64,
+    /// Health factor (scaled by 10_000) below which the grace window is skipped
+    /// (0 = never)
+    pub immediate_liquidation_health_factor: i128,
+    /// Ledgers between liquidations of the same borrower (0 = no cooldown)
+    pub liquidation_cooldown_ledgers: u32,
+    /// Share of the liquidation incentive credited to the protocol reserve (bps)
+    pub liquidation_protocol_fee_bps: i128,
+    /// Most recent liquidation snapshots kept; older ones are evicted
+    pub liquidation_snapshot_capacity: u32,
+    /// Base-currency value the liquidator's incentive is topped up to from the
+    /// collateral reserve (0 = no floor)
+    pub min_liquidation_reward: i128,
+}
+
+/// Efficiency-mode category of correlated assets
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct EModeCategory {
+    /// Category id (0 is reserved for "no category")
+    pub id: u32,
+    /// Share of collateral that may be borrowed against, in basis points
+    /// (e.g., 9500 = 95%). Replaces the asset collateral factors and the
+    /// minimum collateral ratio.
+    pub collateral_factor: i128,
+    /// Collateral-to-debt ratio below which the position can be liquidated,
+    /// in basis points like the global liquidation threshold (e.g., 10200 = 102%)
+    pub liquidation_threshold: i128,
+    /// Member assets (None for native XLM)
+    pub assets: Vec<Option<Address>>,
 }
 
 /// Pause switch operation types
@@ -133,6 +274,13 @@ pub fn initialize_risk_management(env: &Env, admin: Address) -> Result<(), RiskM
     let default_config = RiskConfig {
         pause_switches: create_default_pause_switches(env),
         last_update: env.ledger().timestamp(),
+        max_user_debt_value: 0,
+        liquidation_grace_secs: 0,
+        immediate_liquidation_health_factor: 0,
+        liquidation_cooldown_ledgers: 0,
+        liquidation_protocol_fee_bps: 0,
+        liquidation_snapshot_capacity: DEFAULT_LIQUIDATION_SNAPSHOT_CAPACITY,
+        min_liquidation_reward: 0,
     };
 
     let config_key = RiskDataKey::RiskConfig;
@@ -298,6 +446,62 @@ pub fn check_operation_paused(env: &Env, operation: Symbol) -> bool {
     is_operation_paused(env, operation)
 }
 
+/// Set a pause switch for a single asset (admin only)
+///
+/// Asset-scoped switches use the same operation symbols as the global switches
+/// (e.g. `pause_deposit`, `pause_borrow`) and are checked in addition to them.
+///
+/// # Arguments
+/// * `env` - The Soroban environment
+/// * `caller` - The caller address (must be admin)
+/// * `asset` - The asset to pause (None for native XLM)
+/// * `operation` - The operation symbol
+/// * `paused` - Whether to pause (true) or unpause (false)
+///
+/// # Returns
+/// Returns Ok(()) on success
+pub fn set_asset_pause(
+    env: &Env,
+    caller: Address,
+    asset: Option<Address>,
+    operation: Symbol,
+    paused: bool,
+) -> Result<(), RiskManagementError> {
+    // Check admin
+    require_admin(env, &caller)?;
+
+    let key = RiskDataKey::AssetPauseSwitches(asset.clone());
+    let mut switches = env
+        .storage()
+        .persistent()
+        .get::<RiskDataKey, Map<Symbol, bool>>(&key)
+        .unwrap_or_else(|| Map::new(env));
+    switches.set(operation.clone(), paused);
+    env.storage().persistent().set(&key, &switches);
+
+    emit_asset_pause_changed(
+        env,
+        AssetPauseChangedEvent {
+            actor: caller,
+            asset,
+            operation,
+            paused,
+            timestamp: env.ledger().timestamp(),
+        },
+    );
+
+    Ok(())
+}
+
+/// Check if an operation is paused for a specific asset
+pub fn is_asset_operation_paused(env: &Env, asset: &Option<Address>, operation: Symbol) -> bool {
+    env.storage()
+        .persistent()
+        .get::<RiskDataKey, Map<Symbol, bool>>(&RiskDataKey::AssetPauseSwitches(asset.clone()))
+        .and_then(|switches| switches.get(operation))
+        .unwrap_or(false)
+}
+
 /// Set emergency pause (admin only)
 ///
 /// Emergency pause stops all operations immediately.
@@ -344,9 +548,584 @@ pub fn check_emergency_pause(env: &Env) -> Result<(), RiskManagementError> {
     Ok(())
 }
 
+/// Let repayments bypass pause switches and emergency pause, or subject them to
+/// those pauses again (admin only)
+///
+/// # Arguments
+/// * `env` - The Soroban environment
+/// * `caller` - The caller address (must be admin)
+/// * `allowed` - Whether repayments are always allowed
+pub fn set_repay_always_allowed(
+    env: &Env,
+    caller: Address,
+    allowed: bool,
+) -> Result<(), RiskManagementError> {
+    require_admin(env, &caller)?;
+    env.storage()
+        .persistent()
+        .set(&RiskDataKey::RepayAlwaysAllowed, &allowed);
+
+    emit_admin_action(
+        env,
+        AdminActionEvent {
+            actor: caller,
+            action: Symbol::new(env, "set_repay_allowed"),
+            timestamp: env.ledger().timestamp(),
+        },
+    );
+
+    Ok(())
+}
+
+/// Check whether repayments bypass pause switches and emergency pause
+pub fn is_repay_always_allowed(env: &Env) -> bool {
+    env.storage()
+        .persistent()
+        .get::<RiskDataKey, bool>(&RiskDataKey::RepayAlwaysAllowed)
+        .unwrap_or(true)
+}
 
+/// Restrict liquidations to allowlisted liquidators, or open them to everyone (admin only)
+///
+/// # Arguments
+/// * `env` - The Soroban environment
+/// * `caller` - The caller address (must be admin)
+/// * `enabled` - Whether only allowlisted liquidators may liquidate
+pub fn set_liquidator_allowlist_enabled(
+    env: &Env,
+    caller: Address,
+    enabled: bool,
+) -> Result<(), RiskManagementError> {
+    require_admin(env, &caller)?;
+    env.storage()
+        .persistent()
+        .set(&RiskDataKey::LiquidatorAllowlistEnabled, &enabled);
 
+    emit_admin_action(
+        env,
+        AdminActionEvent {
+            actor: caller,
+            action: Symbol::new(env, "set_liquidator_allowlist"),
+            timestamp: env.ledger().timestamp(),
+        },
+    );
 
+    Ok(())
+}
+
+/// Check whether only allowlisted liquidators may liquidate
+pub fn is_liquidator_allowlist_enabled(env: &Env) -> bool {
+    env.storage()
+        .persistent()
+        .get::<RiskDataKey, bool>(&RiskDataKey::LiquidatorAllowlistEnabled)
+        .unwrap_or(false)
+}
+
+/// Add a liquidator to the allowlist (admin only)
+pub fn add_liquidator(
+    env: &Env,
+    caller: Address,
+    liquidator: Address,
+) -> Result<(), RiskManagementError> {
+    require_admin(env, &caller)?;
+    env.storage()
+        .persistent()
+        .set(&RiskDataKey::AllowedLiquidator(liquidator), &true);
+
+    emit_admin_action(
+        env,
+        AdminActionEvent {
+            actor: caller,
+            action: Symbol::new(env, "add_liquidator"),
+            timestamp: env.ledger().timestamp(),
+        },
+    );
+
+    Ok(())
+}
+
+/// Remove a liquidator from the allowlist (admin only)
+pub fn remove_liquidator(
+    env: &Env,
+    caller: Address,
+    liquidator: Address,
+) -> Result<(), RiskManagementError> {
+    require_admin(env, &caller)?;
+    env.storage()
+        .persistent()
+        .remove(&RiskDataKey::AllowedLiquidator(liquidator));
+
+    emit_admin_action(
+        env,
+        AdminActionEvent {
+            actor: caller,
+            action: Symbol::new(env, "remove_liquidator"),
+            timestamp: env.ledger().timestamp(),
+        },
+    );
+
+    Ok(())
+}
+
+/// Check whether a liquidator is on the allowlist
+pub fn is_allowed_liquidator(env: &Env, liquidator: &Address) -> bool {
+    env.storage()
+        .persistent()
+        .get::<RiskDataKey, bool>(&RiskDataKey::AllowedLiquidator(liquidator.clone()))
+        .unwrap_or(false)
+}
+
+/// Register the AMM contract `liquidate_and_swap` swaps through (admin only)
+///
+/// # Errors
+/// * `RiskManagementError::Unauthorized` - If caller is not admin
+/// * `RiskManagementError::InvalidParameter` - If `amm` is this contract
+pub fn set_liquidation_amm(
+    env: &Env,
+    caller: Address,
+    amm: Address,
+) -> Result<(), RiskManagementError> {
+    require_admin(env, &caller)?;
+    if amm == env.current_contract_address() {
+        return Err(RiskManagementError::InvalidParameter);
+    }
+    env.storage()
+        .persistent()
+        .set(&RiskDataKey::LiquidationAmm, &amm);
+
+    emit_admin_action(
+        env,
+        AdminActionEvent {
+            actor: caller,
+            action: Symbol::new(env, "set_liquidation_amm"),
+            timestamp: env.ledger().timestamp(),
+        },
+    );
+
+    Ok(())
+}
+
+/// Get the AMM contract `liquidate_and_swap` swaps through, if one is registered
+pub fn get_liquidation_amm(env: &Env) -> Option<Address> {
+    env.storage()
+        .persistent()
+        .get::<RiskDataKey, Address>(&RiskDataKey::LiquidationAmm)
+}
+
+/// Check whether `liquidator` may liquidate: anyone while the allowlist is
+/// disabled, otherwise only allowlisted liquidators
+pub fn can_liquidator_act(env: &Env, liquidator: &Address) -> bool {
+    !is_liquidator_allowlist_enabled(env) || is_allowed_liquidator(env, liquidator)
+}
+
+/// Set the maximum debt value a single account may borrow up to (admin only)
+///
+/// # Arguments
+/// * `env` - The Soroban environment
+/// * `caller` - The caller address (must be admin)
+/// * `max_value` - Limit in base-currency value (0 = unlimited)
+///
+/// # Errors
+/// * `RiskManagementError::Unauthorized` - If caller is not admin
+/// * `RiskManagementError::InvalidParameter` - If `max_value` is negative
+pub fn set_max_user_debt_value(
+    env: &Env,
+    caller: Address,
+    max_value: i128,
+) -> Result<(), RiskManagementError> {
+    require_admin(env, &caller)?;
+    if max_value < 0 {
+        return Err(RiskManagementError::InvalidParameter);
+    }
+
+    let mut config = get_risk_config(env).ok_or(RiskManagementError::InvalidParameter)?;
+    config.max_user_debt_value = max_value;
+    config.last_update = env.ledger().timestamp();
+    env.storage()
+        .persistent()
+        .set(&RiskDataKey::RiskConfig, &config);
+
+    emit_admin_action(
+        env,
+        AdminActionEvent {
+            actor: caller,
+            action: Symbol::new(env, "set_max_user_debt"),
+            timestamp: env.ledger().timestamp(),
+        },
+    );
+
+    Ok(())
+}
+
+/// Get the maximum debt value a single account may borrow up to (0 = unlimited)
+pub fn get_max_user_debt_value(env: &Env) -> i128 {
+    get_risk_config(env)
+        .map(|config| config.max_user_debt_value)
+        .unwrap_or(0)
+}
+
+/// Set the liquidation grace window (admin only)
+///
+/// # Arguments
+/// * `env` - The Soroban environment
+/// * `caller` - The caller address (must be admin)
+/// * `grace_secs` - How long a threshold breach must last before liquidation (0 = no grace)
+/// * `immediate_health_factor` - Health factor below which the grace window is
+///   skipped, scaled by 10_000 (0 = never)
+///
+/// # Errors
+/// * `RiskManagementError::Unauthorized` - If caller is not admin
+/// * `RiskManagementError::InvalidParameter` - If `immediate_health_factor` is
+///   outside 0-9999
+pub fn set_liquidation_grace(
+    env: &Env,
+    caller: Address,
+    grace_secs: u64,
+    immediate_health_factor: i128,
+) -> Result<(), RiskManagementError> {
+    require_admin(env, &caller)?;
+    if !(0..10_000).contains(&immediate_health_factor) {
+        return Err(RiskManagementError::InvalidParameter);
+    }
+
+    let mut config = get_risk_config(env).ok_or(RiskManagementError::InvalidParameter)?;
+    config.liquidation_grace_secs = grace_secs;
+    config.immediate_liquidation_health_factor = immediate_health_factor;
+    config.last_update = env.ledger().timestamp();
+    env.storage()
+        .persistent()
+        .set(&RiskDataKey::RiskConfig, &config);
+
+    emit_admin_action(
+        env,
+        AdminActionEvent {
+            actor: caller,
+            action: Symbol::new(env, "set_liquidation_grace"),
+            timestamp: env.ledger().timestamp(),
+        },
+    );
+
+    Ok(())
+}
+
+/// Get the liquidation grace window and the immediate-liquidation health factor
+pub fn get_liquidation_grace(env: &Env) -> (u64, i128) {
+    get_risk_config(env)
+        .map(|config| {
+            (
+                config.liquidation_grace_secs,
+                config.immediate_liquidation_health_factor,
+            )
+        })
+        .unwrap_or((0, 0))
+}
+
+/// Set the number of ledgers between liquidations of the same borrower
+///
+/// Admin checks are left to the caller, `set_risk_params`.
+pub(crate) fn set_liquidation_cooldown_ledgers(
+    env: &Env,
+    ledgers: u32,
+) -> Result<(), RiskManagementError> {
+    let mut config = get_risk_config(env).ok_or(RiskManagementError::InvalidParameter)?;
+    config.liquidation_cooldown_ledgers = ledgers;
+    config.last_update = env.ledger().timestamp();
+    env.storage()
+        .persistent()
+        .set(&RiskDataKey::RiskConfig, &config);
+    Ok(())
+}
+
+/// Set the share of the liquidation incentive credited to the protocol reserve
+///
+/// Admin checks are left to the caller, `set_risk_params`.
+///
+/// # Errors
+/// * `RiskManagementError::InvalidParameter` - If `fee_bps` is outside
+///   0-[`MAX_LIQUIDATION_PROTOCOL_FEE_BPS`]
+pub(crate) fn set_liquidation_protocol_fee_bps(
+    env: &Env,
+    fee_bps: i128,
+) -> Result<(), RiskManagementError> {
+    if !(0..=MAX_LIQUIDATION_PROTOCOL_FEE_BPS).contains(&fee_bps) {
+        return Err(RiskManagementError::InvalidParameter);
+    }
+    let mut config = get_risk_config(env).ok_or(RiskManagementError::InvalidParameter)?;
+    config.liquidation_protocol_fee_bps = fee_bps;
+    config.last_update = env.ledger().timestamp();
+    env.storage()
+        .persistent()
+        .set(&RiskDataKey::RiskConfig, &config);
+    Ok(())
+}
+
+/// Get the share of the liquidation incentive credited to the protocol reserve (bps)
+pub fn get_liquidation_protocol_fee_bps(env: &Env) -> i128 {
+    get_risk_config(env)
+        .map(|config| config.liquidation_protocol_fee_bps)
+        .unwrap_or(0)
+}
+
+/// Set how many liquidation snapshots are kept (admin only)
+///
+/// Lowering the capacity hides the snapshots that fall outside the new window.
+///
+/// # Errors
+/// * `RiskManagementError::Unauthorized` - If caller is not admin
+/// * `RiskManagementError::InvalidParameter` - If `capacity` is outside
+///   1-[`MAX_LIQUIDATION_SNAPSHOT_CAPACITY`]
+pub fn set_liquidation_snapshot_capacity(
+    env: &Env,
+    caller: Address,
+    capacity: u32,
+) -> Result<(), RiskManagementError> {
+    require_admin(env, &caller)?;
+    if !(1..=MAX_LIQUIDATION_SNAPSHOT_CAPACITY).contains(&capacity) {
+        return Err(RiskManagementError::InvalidParameter);
+    }
+
+    let mut config = get_risk_config(env).ok_or(RiskManagementError::InvalidParameter)?;
+    config.liquidation_snapshot_capacity = capacity;
+    config.last_update = env.ledger().timestamp();
+    env.storage()
+        .persistent()
+        .set(&RiskDataKey::RiskConfig, &config);
+
+    emit_admin_action(
+        env,
+        AdminActionEvent {
+            actor: caller,
+            action: Symbol::new(env, "set_liq_snapshot_capacity"),
+            timestamp: env.ledger().timestamp(),
+        },
+    );
+
+    Ok(())
+}
+
+/// Get how many liquidation snapshots are kept
+pub fn get_liquidation_snapshot_capacity(env: &Env) -> u32 {
+    get_risk_config(env)
+        .map(|config| config.liquidation_snapshot_capacity)
+        .unwrap_or(DEFAULT_LIQUIDATION_SNAPSHOT_CAPACITY)
+}
+
+/// Set the minimum base-currency reward of a liquidation (admin only, 0 disables)
+///
+/// # Errors
+/// * `RiskManagementError::Unauthorized` - If caller is not admin
+/// * `RiskManagementError::InvalidParameter` - If `min_reward` is negative
+pub fn set_min_liquidation_reward(
+    env: &Env,
+    caller: Address,
+    min_reward: i128,
+) -> Result<(), RiskManagementError> {
+    require_admin(env, &caller)?;
+    if min_reward < 0 {
+        return Err(RiskManagementError::InvalidParameter);
+    }
+
+    let mut config = get_risk_config(env).ok_or(RiskManagementError::InvalidParameter)?;
+    config.min_liquidation_reward = min_reward;
+    config.last_update = env.ledger().timestamp();
+    env.storage()
+        .persistent()
+        .set(&RiskDataKey::RiskConfig, &config);
+
+    emit_admin_action(
+        env,
+        AdminActionEvent {
+            actor: caller,
+            action: Symbol::new(env, "set_min_liquidation_reward"),
+            timestamp: env.ledger().timestamp(),
+        },
+    );
+
+    Ok(())
+}
+
+/// Get the minimum base-currency reward of a liquidation (0 = no floor)
+pub fn get_min_liquidation_reward(env: &Env) -> i128 {
+    get_risk_config(env)
+        .map(|config| config.min_liquidation_reward)
+        .unwrap_or(0)
+}
+
+/// Get the number of ledgers between liquidations of the same borrower (0 = no cooldown)
+pub fn get_liquidation_cooldown_ledgers(env: &Env) -> u32 {
+    get_risk_config(env)
+        .map(|config| config.liquidation_cooldown_ledgers)
+        .unwrap_or(0)
+}
+
+/// Ledger sequence of the borrower's last liquidation, if any
+pub fn get_last_liquidation_ledger(env: &Env, user: &Address) -> Option<u32> {
+    env.storage()
+        .persistent()
+        .get::<RiskDataKey, u32>(&RiskDataKey::LastLiquidationLedger(user.clone()))
+}
+
+/// Record that `user` was liquidated in the current ledger
+pub(crate) fn record_liquidation_ledger(env: &Env, user: &Address) {
+    env.storage().persistent().set(
+        &RiskDataKey::LastLiquidationLedger(user.clone()),
+        &env.ledger().sequence(),
+    );
+}
+
+/// When `user` was first recorded below the liquidation threshold, if they still are
+pub fn get_liquidation_breach(env: &Env, user: &Address) -> Option<u64> {
+    env.storage()
+        .persistent()
+        .get::<RiskDataKey, u64>(&RiskDataKey::LiquidationBreach(user.clone()))
+}
+
+/// Record when `user` fell below the liquidation threshold, or clear the record
+pub(crate) fn set_liquidation_breach(env: &Env, user: &Address, breached_at: Option<u64>) {
+    let key = RiskDataKey::LiquidationBreach(user.clone());
+    match breached_at {
+        Some(timestamp) => env.storage().persistent().set(&key, &timestamp),
+        None => env.storage().persistent().remove(&key),
+    }
+}
+
+
+
+
+
+/// Define or update an e-mode category (admin only)
+///
+/// # Errors
+/// * `RiskManagementError::Unauthorized` - If caller is not admin
+/// * `RiskManagementError::InvalidParameter` - If the id is 0, the collateral factor
+///   is outside (0, 100%], the threshold is below 100%, or the threshold would be
+///   reached before the borrowing limit (`collateral_factor * liquidation_threshold`
+///   above 100% * 100%)
+pub fn set_emode_category(
+    env: &Env,
+    caller: Address,
+    category: EModeCategory,
+) -> Result<(), RiskManagementError> {
+    require_admin(env, &caller)?;
+
+    if category.id == 0
+        || category.collateral_factor <= 0
+        || category.collateral_factor > BASIS_POINTS
+        || category.liquidation_threshold < BASIS_POINTS
+        || category
+            .collateral_factor
+            .checked_mul(category.liquidation_threshold)
+            .map_or(true, |v| v > BASIS_POINTS * BASIS_POINTS)
+    {
+        return Err(RiskManagementError::InvalidParameter);
+    }
+
+    env.storage()
+        .persistent()
+        .set(&RiskDataKey::EModeCategory(category.id), &category);
+
+    emit_emode_category_updated(
+        env,
+        EModeCategoryUpdatedEvent {
+            actor: caller,
+            category_id: category.id,
+            collateral_factor: category.collateral_factor,
+            liquidation_threshold: category.liquidation_threshold,
+            timestamp: env.ledger().timestamp(),
+        },
+    );
+
+    Ok(())
+}
+
+/// Get an e-mode category by id
+pub fn get_emode_category(env: &Env, category_id: u32) -> Option<EModeCategory> {
+    env.storage()
+        .persistent()
+        .get::<RiskDataKey, EModeCategory>(&RiskDataKey::EModeCategory(category_id))
+}
+
+/// Get the e-mode category a user opted into (0 = none)
+pub fn get_user_emode(env: &Env, user: &Address) -> u32 {
+    env.storage()
+        .persistent()
+        .get::<RiskDataKey, u32>(&RiskDataKey::UserEMode(user.clone()))
+        .unwrap_or(0)
+}
+
+/// Get the user's e-mode category if it governs their position
+///
+/// That is the case when every enabled collateral and borrowed asset, plus
+/// `extra_asset` if given, belongs to the category. Otherwise the global
+/// parameters apply.
+pub fn get_active_emode_category(
+    env: &Env,
+    user: &Address,
+    extra_asset: Option<&Option<Address>>,
+) -> Option<EModeCategory> {
+    let category = get_emode_category(env, get_user_emode(env, user))?;
+    if let Some(asset) = extra_asset {
+        if !category.assets.contains(asset) {
+            return None;
+        }
+    }
+    for asset in crate::borrow::get_user_borrowed_assets(env, user).iter() {
+        if !category.assets.contains(&asset) {
+            return None;
+        }
+    }
+    for asset in crate::deposit::get_user_assets(env, user).iter() {
+        if crate::deposit::is_collateral_enabled(env, user, &asset)
+            && !category.assets.contains(&asset)
+        {
+            return None;
+        }
+    }
+    Some(category)
+}
+
+/// Opt into an e-mode category, or leave e-mode with `category_id` 0
+///
+/// # Errors
+/// * `RiskManagementError::EModeCategoryNotFound` - If the category does not exist
+/// * `RiskManagementError::InsufficientCollateralRatio` - If the position would be
+///   below its borrowing limit under the new parameters
+pub fn set_user_emode(
+    env: &Env,
+    user: Address,
+    category_id: u32,
+) -> Result<(), RiskManagementError> {
+    user.require_auth();
+
+    let key = RiskDataKey::UserEMode(user.clone());
+    if category_id == 0 {
+        env.storage().persistent().remove(&key);
+    } else {
+        if get_emode_category(env, category_id).is_none() {
+            return Err(RiskManagementError::EModeCategoryNotFound);
+        }
+        env.storage().persistent().set(&key, &category_id);
+    }
+
+    // Checked against the new parameters; the error reverts the switch
+    crate::withdraw::validate_collateral_ratio_after_withdraw(env, &user, 0, None).map_err(
+        |e| match e {
+            crate::withdraw::WithdrawError::Overflow => RiskManagementError::Overflow,
+            _ => RiskManagementError::InsufficientCollateralRatio,
+        },
+    )?;
+
+    emit_user_emode_set(
+        env,
+        UserEModeSetEvent {
+            user,
+            category_id,
+            timestamp: env.ledger().timestamp(),
+        },
+    );
+
+    Ok(())
+}
 
 /// Emit pause switch updated event
 fn emit_pause_switch_updated_event(env: &Env, caller: &Address, operation: &Symbol, paused: bool) {
@@ -29,6 +29,10 @@ pub enum RiskParamsError {
 pub enum RiskParamsDataKey {
     /// Risk configuration parameters
     RiskParamsConfig,
+    /// Debt value below which a liquidation may close the whole debt
+    CloseFactorDustThreshold,
+    /// Incentive charged on self-liquidations (bps)
+    SelfLiquidationIncentive,
 }
 
 /// Risk parameters
@@ -244,6 +248,62 @@ pub fn get_liquidation_incentive(env: &Env) -> Result<i128, RiskParamsError> {
     Ok(config.liquidation_incentive)
 }
 
+/// Get the close factor dust threshold
+///
+/// A liquidation may repay the whole debt, regardless of the close factor,
+/// while the borrower's total debt value (in the oracle's base units, as the
+/// liquidation valuation reports it) is below this. Zero, the default, disables
+/// the exemption.
+pub fn get_close_factor_dust_threshold(env: &Env) -> i128 {
+    env.storage()
+        .persistent()
+        .get::<RiskParamsDataKey, i128>(&RiskParamsDataKey::CloseFactorDustThreshold)
+        .unwrap_or(0)
+}
+
+/// Set the close factor dust threshold, see [`get_close_factor_dust_threshold`]
+///
+/// # Errors
+/// * `RiskParamsError::InvalidParameter` - If the threshold is negative
+pub fn set_close_factor_dust_threshold(env: &Env, threshold: i128) -> Result<(), RiskParamsError> {
+    if threshold < 0 {
+        return Err(RiskParamsError::InvalidParameter);
+    }
+    env.storage()
+        .persistent()
+        .set(&RiskParamsDataKey::CloseFactorDustThreshold, &threshold);
+    Ok(())
+}
+
+/// Incentive charged when borrowers liquidate their own position
+///
+/// In basis points of the collateral value of the repaid debt, kept by the
+/// protocol. Zero by default and never above the liquidation incentive.
+pub fn get_self_liquidation_incentive(env: &Env) -> i128 {
+    env.storage()
+        .persistent()
+        .get::<RiskParamsDataKey, i128>(&RiskParamsDataKey::SelfLiquidationIncentive)
+        .unwrap_or(0)
+}
+
+/// Set the self-liquidation incentive, see [`get_self_liquidation_incentive`]
+///
+/// # Errors
+/// * `RiskParamsError::InvalidLiquidationIncentive` - If the incentive is negative
+///   or above the liquidation incentive
+pub fn set_self_liquidation_incentive(
+    env: &Env,
+    incentive_bps: i128,
+) -> Result<(), RiskParamsError> {
+    if incentive_bps < 0 || incentive_bps > get_liquidation_incentive(env)? {
+        return Err(RiskParamsError::InvalidLiquidationIncentive);
+    }
+    env.storage()
+        .persistent()
+        .set(&RiskParamsDataKey::SelfLiquidationIncentive, &incentive_bps);
+    Ok(())
+}
+
 /// Calculate maximum liquidatable amount
 ///
 /// Uses close factor to determine maximum debt that can be liquidated.
@@ -0,0 +1,254 @@
+//! # Term Loan Module
+//!
+//! Fixed-term, fixed-rate borrowing.
+//!
+//! `open_term_loan` borrows through the regular borrow path, with the same
+//! checks, fees and events, then locks the debt at the variable rate plus the
+//! premium configured for the requested term, counting the borrow's own
+//! utilization as a stable borrow does. Terms are offered in buckets: only
+//! lengths with a premium in [`TermLoanConfig::term_premiums`] can be opened.
+//! The debt is a [`RateMode::Fixed`] [`crate::borrow::UserBorrow`] recording
+//! its maturity, and a user holds at most one term loan per asset, which cannot
+//! be topped up or mixed with variable or stable debt in the same asset.
+//!
+//! ## Maturity
+//! Until maturity the debt accrues at its locked rate. Past maturity it accrues
+//! at the current variable rate plus `late_penalty_bps` until repaid.
+//!
+//! ## Early Repayment
+//! Principal repaid by transfer before maturity is charged
+//! `early_repayment_fee_bps` on top, credited to the asset's protocol reserve.
+//! Interest is never charged the fee.
+//!
+//! Term debt is otherwise ordinary debt: it counts toward health checks and
+//! can be liquidated exactly like variable debt.
+//!
+//! ## Storage Keys
+//! - `DepositDataKey::TermLoanConfig` — the [`TermLoanConfig`]
+
+use soroban_sdk::{contracttype, Address, Env, Map};
+
+use crate::borrow::{BorrowError, RateMode};
+use crate::deposit::DepositDataKey;
+use crate::events::{
+    emit_early_repayment_fee, emit_term_loan_opened, EarlyRepaymentFeeEvent, TermLoanOpenedEvent,
+};
+use crate::repay::RepayError;
+
+/// Terms on offer and the fees around them
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct TermLoanConfig {
+    /// Annual premium over the variable rate, in basis points, by term length in seconds
+    pub term_premiums: Map<u64, i128>,
+    /// Annual penalty over the variable rate on debt past maturity, in basis points
+    pub late_penalty_bps: i128,
+    /// Fee on principal repaid before maturity, in basis points
+    pub early_repayment_fee_bps: i128,
+}
+
+/// Get the term loan configuration (no terms offered and no fees by default)
+pub fn get_term_loan_config(env: &Env) -> TermLoanConfig {
+    env.storage()
+        .persistent()
+        .get::<DepositDataKey, TermLoanConfig>(&DepositDataKey::TermLoanConfig)
+        .unwrap_or(TermLoanConfig {
+            term_premiums: Map::new(env),
+            late_penalty_bps: 0,
+            early_repayment_fee_bps: 0,
+        })
+}
+
+/// Set the late penalty and early repayment fee for term loans (admin only)
+///
+/// # Errors
+/// * `BorrowError::Unauthorized` - If `caller` is not the admin
+/// * `BorrowError::InvalidAmount` - If either value is outside 0..=10000
+pub fn set_term_loan_config(
+    env: &Env,
+    caller: Address,
+    late_penalty_bps: i128,
+    early_repayment_fee_bps: i128,
+) -> Result<(), BorrowError> {
+    crate::admin::require_admin(env, &caller).map_err(|_| BorrowError::Unauthorized)?;
+    caller.require_auth();
+
+    if !(0..=10_000).contains(&late_penalty_bps) || !(0..=10_000).contains(&early_repayment_fee_bps)
+    {
+        return Err(BorrowError::InvalidAmount);
+    }
+    let mut config = get_term_loan_config(env);
+    config.late_penalty_bps = late_penalty_bps;
+    config.early_repayment_fee_bps = early_repayment_fee_bps;
+    env.storage()
+        .persistent()
+        .set(&DepositDataKey::TermLoanConfig, &config);
+    Ok(())
+}
+
+/// Offer a term length at `premium_bps` over the variable rate, or stop offering
+/// it with `None` (admin only)
+///
+/// Open term loans keep the rate they were locked at.
+///
+/// # Errors
+/// * `BorrowError::Unauthorized` - If `caller` is not the admin
+/// * `BorrowError::InvalidTerm` - If `term_secs` is zero
+/// * `BorrowError::InvalidAmount` - If the premium is outside 0..=10000
+pub fn set_term_premium(
+    env: &Env,
+    caller: Address,
+    term_secs: u64,
+    premium_bps: Option<i128>,
+) -> Result<(), BorrowError> {
+    crate::admin::require_admin(env, &caller).map_err(|_| BorrowError::Unauthorized)?;
+    caller.require_auth();
+
+    if term_secs == 0 {
+        return Err(BorrowError::InvalidTerm);
+    }
+    let mut config = get_term_loan_config(env);
+    match premium_bps {
+        Some(premium) if !(0..=10_000).contains(&premium) => {
+            return Err(BorrowError::InvalidAmount)
+        }
+        Some(premium) => config.term_premiums.set(term_secs, premium),
+        None => {
+            config.term_premiums.remove(term_secs);
+        }
+    }
+    env.storage()
+        .persistent()
+        .set(&DepositDataKey::TermLoanConfig, &config);
+    Ok(())
+}
+
+/// Borrow `amount` of `asset` at a rate fixed for `term_secs`
+///
+/// Runs every check of [`crate::borrow::borrow_asset`], then locks the variable
+/// rate, including the utilization added by this borrow, plus the term's premium.
+///
+/// # Returns
+/// The user's total debt after the borrow
+///
+/// # Errors
+/// * `BorrowError::InvalidTerm` - If no premium is configured for `term_secs`
+/// * `BorrowError::TermLoanExists` - If the user already has a term loan in `asset`
+/// * `BorrowError::RateModeMismatch` - If the user has variable or stable debt in `asset`
+/// * Any error [`crate::borrow::borrow_asset`] can return
+pub fn open_term_loan(
+    env: &Env,
+    user: Address,
+    asset: Option<Address>,
+    amount: i128,
+    term_secs: u64,
+) -> Result<i128, BorrowError> {
+    user.require_auth();
+
+    let premium_bps = get_term_loan_config(env)
+        .term_premiums
+        .get(term_secs)
+        .ok_or(BorrowError::InvalidTerm)?;
+    if let Some(existing) = crate::borrow::get_user_borrow(env, &user, &asset) {
+        return Err(if existing.rate_mode == RateMode::Fixed {
+            BorrowError::TermLoanExists
+        } else {
+            BorrowError::RateModeMismatch
+        });
+    }
+
+    let recipient = user.clone();
+    let total_debt = crate::borrow::execute_borrow(
+        env,
+        user.clone(),
+        asset.clone(),
+        amount,
+        &recipient,
+        true,
+        RateMode::Fixed,
+    )?;
+
+    let rate_bps = crate::interest_rate::calculate_borrow_rate(env, &asset)
+        .map_err(|_| BorrowError::Overflow)?
+        .checked_add(premium_bps)
+        .ok_or(BorrowError::Overflow)?;
+    let timestamp = env.ledger().timestamp();
+    let maturity = timestamp
+        .checked_add(term_secs)
+        .ok_or(BorrowError::Overflow)?;
+    let mut record =
+        crate::borrow::get_user_borrow(env, &user, &asset).ok_or(BorrowError::Overflow)?;
+    record.rate_mode = RateMode::Fixed;
+    record.stable_rate_bps = rate_bps;
+    record.last_accrual_time = timestamp;
+    record.maturity = maturity;
+    env.storage().persistent().set(
+        &DepositDataKey::UserDebt(user.clone(), asset.clone()),
+        &record,
+    );
+
+    emit_term_loan_opened(
+        env,
+        TermLoanOpenedEvent {
+            user,
+            asset,
+            amount,
+            term_secs,
+            rate_bps,
+            maturity,
+            timestamp,
+        },
+    );
+
+    Ok(total_debt)
+}
+
+/// Fee owed for repaying `principal` of `user`'s debt in `asset` now
+///
+/// Zero unless the debt is a term loan that has not matured yet.
+pub(crate) fn early_repayment_fee(
+    env: &Env,
+    user: &Address,
+    asset: &Option<Address>,
+    principal: i128,
+) -> Result<i128, RepayError> {
+    let Some(record) = crate::borrow::get_user_borrow(env, user, asset) else {
+        return Ok(0);
+    };
+    if record.rate_mode != RateMode::Fixed
+        || env.ledger().timestamp() >= record.maturity
+        || principal <= 0
+    {
+        return Ok(0);
+    }
+    principal
+        .checked_mul(get_term_loan_config(env).early_repayment_fee_bps)
+        .map(|fee| fee / 10_000)
+        .ok_or(RepayError::Overflow)
+}
+
+/// Credit an early repayment fee already collected from `user` to reserves
+pub(crate) fn collect_early_repayment_fee(
+    env: &Env,
+    user: &Address,
+    asset: &Option<Address>,
+    principal: i128,
+    fee: i128,
+) -> Result<(), RepayError> {
+    if fee <= 0 {
+        return Ok(());
+    }
+    crate::deposit::credit_protocol_reserve(env, user, asset, fee)
+        .map_err(|_| RepayError::Overflow)?;
+    emit_early_repayment_fee(
+        env,
+        EarlyRepaymentFeeEvent {
+            user: user.clone(),
+            asset: asset.clone(),
+            principal,
+            fee,
+            timestamp: env.ledger().timestamp(),
+        },
+    );
+    Ok(())
+}
@@ -10,7 +10,7 @@
 //! - `set_emergency_rate_adjustment`: positive, negative, and out-of-range values
 //! - `configure_oracle`: full-config update and non-admin rejection
 //! - `set_fallback_oracle`: success, self-reference rejection, non-admin rejection
-//! - `update_price_feed`: admin and oracle-address access; non-admin rejection
+//! - `update_price_feed`: registered-oracle access; unregistered rejection
 //! - `set_flash_loan_fee` and `configure_flash_loan`: success and rejection
 //! - `set_pause_switch` and `set_pause_switches`: success and rejection
 //!
@@ -27,6 +27,7 @@ use crate::admin::{
 };
 use crate::flash_loan::FlashLoanConfig;
 use crate::oracle::OracleConfig;
+use crate::tests::test_helpers::set_oracle_price;
 use crate::{HelloContract, HelloContractClient};
 use soroban_sdk::{
     testutils::{Address as _, Events},
@@ -301,7 +302,15 @@ fn test_set_risk_params_only_min_collateral_ratio() {
     let (_id, admin, client) = setup(&e);
 
     // +10 % of 11 000 = +1 100 → new value 12 100 (valid)
-    client.set_risk_params(&admin, &Some(12_100_i128), &None, &None, &None);
+    client.set_risk_params(
+        &admin,
+        &Some(12_100_i128),
+        &None,
+        &None,
+        &None,
+        &None,
+        &None,
+    );
     assert_eq!(client.get_min_collateral_ratio(), 12_100);
     // Other params unchanged
     assert_eq!(client.get_liquidation_threshold(), 10_500);
@@ -319,7 +328,15 @@ fn test_set_risk_params_only_liquidation_threshold() {
     let (_id, admin, client) = setup(&e);
 
     // 10 900 < MCR (11 000) and change 400 ≤ max_change 1 050 — valid
-    client.set_risk_params(&admin, &None, &Some(10_900_i128), &None, &None);
+    client.set_risk_params(
+        &admin,
+        &None,
+        &Some(10_900_i128),
+        &None,
+        &None,
+        &None,
+        &None,
+    );
     assert_eq!(client.get_liquidation_threshold(), 10_900);
     assert_eq!(client.get_min_collateral_ratio(), 11_000);
 }
@@ -332,7 +349,7 @@ fn test_set_risk_params_only_close_factor() {
     let e = env();
     let (_id, admin, client) = setup(&e);
 
-    client.set_risk_params(&admin, &None, &None, &Some(4_500_i128), &None);
+    client.set_risk_params(&admin, &None, &None, &Some(4_500_i128), &None, &None, &None);
     assert_eq!(client.get_close_factor(), 4_500);
     assert_eq!(client.get_min_collateral_ratio(), 11_000);
 }
@@ -345,7 +362,7 @@ fn test_set_risk_params_only_liquidation_incentive() {
     let e = env();
     let (_id, admin, client) = setup(&e);
 
-    client.set_risk_params(&admin, &None, &None, &None, &Some(1_100_i128));
+    client.set_risk_params(&admin, &None, &None, &None, &Some(1_100_i128), &None, &None);
     assert_eq!(client.get_liquidation_incentive(), 1_100);
     assert_eq!(client.get_close_factor(), 5_000);
 }
@@ -362,7 +379,15 @@ fn test_set_risk_params_exactly_10pct_change_succeeds() {
     let e = env();
     let (_id, admin, client) = setup(&e);
 
-    client.set_risk_params(&admin, &Some(12_100_i128), &None, &None, &None);
+    client.set_risk_params(
+        &admin,
+        &Some(12_100_i128),
+        &None,
+        &None,
+        &None,
+        &None,
+        &None,
+    );
     assert_eq!(client.get_min_collateral_ratio(), 12_100);
 }
 
@@ -374,7 +399,15 @@ fn test_set_risk_params_exactly_10pct_change_succeeds() {
 fn test_set_risk_params_one_over_10pct_panics() {
     let e = env();
     let (_id, admin, client) = setup(&e);
-    client.set_risk_params(&admin, &Some(12_101_i128), &None, &None, &None);
+    client.set_risk_params(
+        &admin,
+        &Some(12_101_i128),
+        &None,
+        &None,
+        &None,
+        &None,
+        &None,
+    );
 }
 
 // ═══════════════════════════════════════════════════════════════════════════
@@ -389,15 +422,39 @@ fn test_set_risk_params_sequential_updates_accumulate() {
     let (_id, admin, client) = setup(&e);
 
     // Step 1: 11 000 → 12 100 (+10 %)
-    client.set_risk_params(&admin, &Some(12_100_i128), &None, &None, &None);
+    client.set_risk_params(
+        &admin,
+        &Some(12_100_i128),
+        &None,
+        &None,
+        &None,
+        &None,
+        &None,
+    );
     assert_eq!(client.get_min_collateral_ratio(), 12_100);
 
     // Step 2: 12 100 → 13 310 (+10 % of 12 100 = 1 210)
-    client.set_risk_params(&admin, &Some(13_310_i128), &None, &None, &None);
+    client.set_risk_params(
+        &admin,
+        &Some(13_310_i128),
+        &None,
+        &None,
+        &None,
+        &None,
+        &None,
+    );
     assert_eq!(client.get_min_collateral_ratio(), 13_310);
 
     // Step 3: 13 310 → 14 641 (+10 % of 13 310 = 1 331)
-    client.set_risk_params(&admin, &Some(14_641_i128), &None, &None, &None);
+    client.set_risk_params(
+        &admin,
+        &Some(14_641_i128),
+        &None,
+        &None,
+        &None,
+        &None,
+        &None,
+    );
     assert_eq!(client.get_min_collateral_ratio(), 14_641);
 }
 
@@ -413,7 +470,15 @@ fn test_set_risk_params_lt_equal_to_mcr_is_valid() {
     let (_id, admin, client) = setup(&e);
 
     // Lower MCR to 10 500 first (decrease of 500, within 10 % = 1 100)
-    client.set_risk_params(&admin, &Some(10_500_i128), &None, &None, &None);
+    client.set_risk_params(
+        &admin,
+        &Some(10_500_i128),
+        &None,
+        &None,
+        &None,
+        &None,
+        &None,
+    );
     // Now MCR == LT == 10 500 – valid
     assert_eq!(client.get_min_collateral_ratio(), 10_500);
     assert_eq!(client.get_liquidation_threshold(), 10_500);
@@ -429,7 +494,15 @@ fn test_set_risk_params_lt_above_mcr_panics() {
     // MCR default = 11 000, attempt to raise LT to 11 001
     // Change for LT: |11001 - 10500| = 501, max = 1050 (ok for change limit)
     // But MCR (11000) < LT (11001) → InvalidCollateralRatio
-    client.set_risk_params(&admin, &None, &Some(11_001_i128), &None, &None);
+    client.set_risk_params(
+        &admin,
+        &None,
+        &Some(11_001_i128),
+        &None,
+        &None,
+        &None,
+        &None,
+    );
 }
 
 // ═══════════════════════════════════════════════════════════════════════════
@@ -445,7 +518,7 @@ fn test_set_risk_params_close_factor_step_down() {
     let e = env();
     let (_id, admin, client) = setup(&e);
     // 5000 → 4500 (−10 %, valid)
-    client.set_risk_params(&admin, &None, &None, &Some(4_500_i128), &None);
+    client.set_risk_params(&admin, &None, &None, &Some(4_500_i128), &None, &None, &None);
     assert_eq!(client.get_close_factor(), 4_500);
 }
 
@@ -455,7 +528,7 @@ fn test_set_risk_params_close_factor_step_up() {
     let e = env();
     let (_id, admin, client) = setup(&e);
     // 5000 → 5500 (+10 %, valid)
-    client.set_risk_params(&admin, &None, &None, &Some(5_500_i128), &None);
+    client.set_risk_params(&admin, &None, &None, &Some(5_500_i128), &None, &None, &None);
     assert_eq!(client.get_close_factor(), 5_500);
 }
 
@@ -470,7 +543,15 @@ fn test_set_risk_params_non_admin_panics() {
     let e = env();
     let (_id, admin, client) = setup(&e);
     let attacker = other_addr(&e, &admin);
-    client.set_risk_params(&attacker, &Some(11_100_i128), &None, &None, &None);
+    client.set_risk_params(
+        &attacker,
+        &Some(11_100_i128),
+        &None,
+        &None,
+        &None,
+        &None,
+        &None,
+    );
 }
 
 // ═══════════════════════════════════════════════════════════════════════════
@@ -492,6 +573,8 @@ fn test_update_interest_rate_config_only_base_rate() {
         &None,
         &None,
         &None,
+        &None,
+        &false,
     );
     // Borrow rate at 0 % utilization = base rate = 110 bps (floor may apply)
     let rate = client.get_borrow_rate();
@@ -513,6 +596,8 @@ fn test_update_interest_rate_config_only_kink() {
         &None,
         &None,
         &None,
+        &None,
+        &false,
     );
     // If it doesn’t panic the update succeeded.
 }
@@ -532,6 +617,8 @@ fn test_update_interest_rate_config_only_multiplier() {
         &None,
         &None,
         &None,
+        &None,
+        &false,
     );
 }
 
@@ -550,6 +637,8 @@ fn test_update_interest_rate_config_only_jump_multiplier() {
         &None,
         &None,
         &None,
+        &None,
+        &false,
     );
 }
 
@@ -568,6 +657,8 @@ fn test_update_interest_rate_config_only_spread() {
         &None,
         &None,
         &Some(220_i128),
+        &None,
+        &false,
     );
     let borrow_rate = client.get_borrow_rate();
     let supply_rate = client.get_supply_rate();
@@ -593,6 +684,8 @@ fn test_update_interest_rate_config_non_admin_panics() {
         &None,
         &None,
         &None,
+        &None,
+        &false,
     );
 }
 
@@ -606,7 +699,7 @@ fn test_set_emergency_rate_adjustment_positive() {
     let e = env();
     let (_id, admin, client) = setup(&e);
     // +500 bps adjustment
-    client.set_emergency_rate_adjustment(&admin, &500_i128);
+    client.set_emergency_rate_adjustment(&admin, &500_i128, &86_400);
     // Rate should increase – verify by comparing borrow rate after adjustment.
     let rate = client.get_borrow_rate();
     // At 0 % utilization: base_rate (100) + emergency (500) = 600, but capped at floor (50).
@@ -623,7 +716,7 @@ fn test_set_emergency_rate_adjustment_negative() {
     let e = env();
     let (_id, admin, client) = setup(&e);
     // −50 bps adjustment (won’t push below floor)
-    client.set_emergency_rate_adjustment(&admin, &-50_i128);
+    client.set_emergency_rate_adjustment(&admin, &-50_i128, &86_400);
     let rate = client.get_borrow_rate();
     // Rate cannot go below the 50 bps floor.
     assert!(
@@ -637,8 +730,8 @@ fn test_set_emergency_rate_adjustment_negative() {
 fn test_set_emergency_rate_adjustment_zero() {
     let e = env();
     let (_id, admin, client) = setup(&e);
-    client.set_emergency_rate_adjustment(&admin, &500_i128);
-    client.set_emergency_rate_adjustment(&admin, &0_i128);
+    client.set_emergency_rate_adjustment(&admin, &500_i128, &86_400);
+    client.set_emergency_rate_adjustment(&admin, &0_i128, &86_400);
     // Rate should return to base level.
     let rate = client.get_borrow_rate();
     assert!(rate >= 50, "rate must still be above floor after reset");
@@ -650,7 +743,7 @@ fn test_set_emergency_rate_adjustment_zero() {
 fn test_set_emergency_rate_adjustment_too_large_panics() {
     let e = env();
     let (_id, admin, client) = setup(&e);
-    client.set_emergency_rate_adjustment(&admin, &20_000_i128);
+    client.set_emergency_rate_adjustment(&admin, &20_000_i128, &86_400);
 }
 
 /// A non-admin caller must be rejected.
@@ -660,7 +753,7 @@ fn test_set_emergency_rate_adjustment_non_admin_panics() {
     let e = env();
     let (_id, admin, client) = setup(&e);
     let attacker = other_addr(&e, &admin);
-    client.set_emergency_rate_adjustment(&attacker, &500_i128);
+    client.set_emergency_rate_adjustment(&attacker, &500_i128, &86_400);
 }
 
 // ═══════════════════════════════════════════════════════════════════════════
@@ -674,9 +767,9 @@ fn test_configure_oracle_all_params() {
     let (_id, admin, client) = setup(&e);
 
     let config = OracleConfig {
-        max_deviation_bps: 300,       // 3 % max deviation
-        max_staleness_seconds: 1_800, // 30-minute staleness window
-        cache_ttl_seconds: 120,       // 2-minute cache
+        max_deviation_bps: 300,    // 3 % max deviation
+        max_price_age_secs: 1_800, // 30-minute staleness window
+        cache_ttl_seconds: 120,    // 2-minute cache
         min_price: 1,
         max_price: 1_000_000_000_000,
     };
@@ -692,7 +785,7 @@ fn test_configure_oracle_tighter_staleness() {
 
     let config = OracleConfig {
         max_deviation_bps: 500,
-        max_staleness_seconds: 600, // 10-minute staleness (tighter than default 1 h)
+        max_price_age_secs: 600, // 10-minute staleness (tighter than default 1 h)
         cache_ttl_seconds: 60,
         min_price: 1,
         max_price: i128::MAX,
@@ -710,7 +803,7 @@ fn test_configure_oracle_non_admin_panics() {
 
     let config = OracleConfig {
         max_deviation_bps: 500,
-        max_staleness_seconds: 3_600,
+        max_price_age_secs: 3_600,
         cache_ttl_seconds: 300,
         min_price: 1,
         max_price: i128::MAX,
@@ -726,7 +819,7 @@ fn test_configure_oracle_zero_deviation_panics() {
     let (_id, admin, client) = setup(&e);
     let config = OracleConfig {
         max_deviation_bps: 0, // invalid
-        max_staleness_seconds: 3_600,
+        max_price_age_secs: 3_600,
         cache_ttl_seconds: 300,
         min_price: 1,
         max_price: i128::MAX,
@@ -742,7 +835,7 @@ fn test_configure_oracle_zero_staleness_panics() {
     let (_id, admin, client) = setup(&e);
     let config = OracleConfig {
         max_deviation_bps: 500,
-        max_staleness_seconds: 0, // invalid
+        max_price_age_secs: 0, // invalid
         cache_ttl_seconds: 300,
         min_price: 1,
         max_price: i128::MAX,
@@ -793,15 +886,15 @@ fn test_set_fallback_oracle_non_admin_panics() {
 // 12. update_price_feed
 // ═══════════════════════════════════════════════════════════════════════════
 
-/// Admin address can update the price feed for any asset.
+/// Once the admin registers an oracle for an asset, it can update the price feed.
 #[test]
-fn test_update_price_feed_by_admin() {
+fn test_update_price_feed_by_registered_oracle() {
     let e = env();
     let (_id, admin, client) = setup(&e);
     let asset = Address::generate(&e);
     let oracle = Address::generate(&e);
 
-    let price = client.update_price_feed(&admin, &asset, &1_000_i128, &8_u32, &oracle);
+    let price = set_oracle_price(&client, &admin, &asset, &1_000_i128, &8_u32, &oracle);
     assert_eq!(price, 1_000);
 }
 
@@ -813,25 +906,25 @@ fn test_update_price_feed_by_oracle_address() {
     let asset = Address::generate(&e);
     let oracle = Address::generate(&e);
 
-    // Admin sets the initial price, establishing the oracle address.
-    client.update_price_feed(&admin, &asset, &500_i128, &8_u32, &oracle);
+    // Admin registers the oracle address, which sets the initial price.
+    set_oracle_price(&client, &admin, &asset, &500_i128, &8_u32, &oracle);
     // The oracle address itself can now push an update.
-    let price = client.update_price_feed(&oracle, &asset, &510_i128, &8_u32, &oracle);
+    let price = client.update_price_feed(&oracle, &asset, &510_i128, &8_u32);
     assert_eq!(price, 510);
 }
 
 /// Prices must increase monotonically within deviation tolerance.
-/// Two successive price updates by the admin must both succeed.
+/// Two successive price updates by a registered oracle must both succeed.
 #[test]
-fn test_update_price_feed_successive_updates_by_admin() {
+fn test_update_price_feed_successive_updates() {
     let e = env();
     let (_id, admin, client) = setup(&e);
     let asset = Address::generate(&e);
     let oracle = Address::generate(&e);
 
-    client.update_price_feed(&admin, &asset, &1_000_i128, &8_u32, &oracle);
+    set_oracle_price(&client, &admin, &asset, &1_000_i128, &8_u32, &oracle);
     // Price moves within 5 % deviation (500 bps default)
-    let price = client.update_price_feed(&admin, &asset, &1_040_i128, &8_u32, &oracle);
+    let price = set_oracle_price(&client, &admin, &asset, &1_040_i128, &8_u32, &oracle);
     assert_eq!(price, 1_040);
 }
 
@@ -846,8 +939,8 @@ fn test_update_price_feed_unrelated_address_panics() {
     let attacker = other_addr(&e, &admin);
 
     // Establish oracle so attacker is definitely not it.
-    client.update_price_feed(&admin, &asset, &1_000_i128, &8_u32, &oracle);
-    client.update_price_feed(&attacker, &asset, &999_i128, &8_u32, &oracle);
+    set_oracle_price(&client, &admin, &asset, &1_000_i128, &8_u32, &oracle);
+    client.update_price_feed(&attacker, &asset, &999_i128, &8_u32);
 }
 
 // ═══════════════════════════════════════════════════════════════════════════
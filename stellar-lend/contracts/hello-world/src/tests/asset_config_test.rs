@@ -3,7 +3,7 @@
 //! Tests for collateral/asset configuration and config enforcement.
 //! Covers interest rate config, risk params, and per-parameter validation.
 
-use crate::deposit::{DepositDataKey, ProtocolAnalytics};
+use crate::deposit::{AssetTotals, DepositDataKey, ProtocolAnalytics};
 use crate::{HelloContract, HelloContractClient};
 use soroban_sdk::{testutils::Address as _, Address, Env};
 
@@ -35,6 +35,15 @@ fn set_protocol_analytics(
             total_value_locked: total_deposits,
         };
         env.storage().persistent().set(&key, &a);
+        // Keep the native asset's own totals in line for the utilization view
+        let totals = AssetTotals {
+            total_supplied: total_deposits,
+            total_borrowed: total_borrows,
+            total_reserves: 0,
+        };
+        env.storage()
+            .persistent()
+            .set(&DepositDataKey::AssetTotals(None), &totals);
     });
 }
 
@@ -55,6 +64,8 @@ fn test_update_interest_rate_config_base_rate() {
         &None,
         &None,
         &None,
+        &None,
+        &false,
     );
     let rate = client.get_borrow_rate();
     assert!(rate >= 200);
@@ -74,6 +85,8 @@ fn test_update_interest_rate_config_kink() {
         &None,
         &None,
         &None,
+        &None,
+        &false,
     );
     let util = client.get_utilization();
     assert_eq!(util, 5000);
@@ -92,6 +105,8 @@ fn test_update_interest_rate_config_spread() {
         &None,
         &None,
         &Some(300),
+        &None,
+        &false,
     );
     let borrow_rate = client.get_borrow_rate();
     let supply_rate = client.get_supply_rate();
@@ -112,6 +127,8 @@ fn test_interest_rate_config_floor_ceiling_enforcement() {
         &Some(10000),
         &None,
         &None,
+        &None,
+        &false,
     );
     let rate = client.get_borrow_rate();
     assert!(rate >= 100);
@@ -141,7 +158,7 @@ fn test_set_risk_params_success() {
     let config_before = client.get_risk_config().unwrap();
     let new_min_cr = config_before.min_collateral_ratio + 100;
     if new_min_cr <= 10_000 {
-        client.set_risk_params(&admin, &Some(new_min_cr), &None, &None, &None);
+        client.set_risk_params(&admin, &Some(new_min_cr), &None, &None, &None, &None, &None);
         let config_after = client.get_risk_config().unwrap();
         assert_eq!(config_after.min_collateral_ratio, new_min_cr);
     }
@@ -195,5 +212,7 @@ fn test_update_interest_rate_config_unauthorized() {
         &None,
         &None,
         &None,
+        &None,
+        &false,
     );
 }
@@ -160,11 +160,14 @@
 //! This test suite aims for **95%+ coverage** of the `borrow_asset` function,
 //! covering all code paths, error conditions, and edge cases.
 
+use crate::borrow::BorrowError;
 use crate::deposit::{DepositDataKey, Position, UserAnalytics};
+use crate::risk_management::RiskManagementError;
+use crate::tests::test_helpers::{asset_params, set_oracle_price};
 use crate::{deposit, HelloContract, HelloContractClient};
 use soroban_sdk::{
     testutils::{Address as _, Ledger},
-    Address, Env, Map, Symbol,
+    vec, Address, Env, Map, Symbol, Vec,
 };
 
 // ============================================================================
@@ -233,8 +236,8 @@ fn set_asset_params(
         use deposit::AssetParams;
         let params = AssetParams {
             deposit_enabled,
-            collateral_factor,
             max_deposit,
+            ..asset_params(collateral_factor)
         };
         let key = DepositDataKey::AssetParams(asset.clone());
         env.storage().persistent().set(&key, &params);
@@ -1164,11 +1167,15 @@ fn test_borrow_asset_zero_collateral_factor() {
     let contract_id = env.register(HelloContract, ());
     let client = HelloContractClient::new(&env, &contract_id);
 
+    let admin = Address::generate(&env);
+    client.initialize(&admin);
+
     let user = Address::generate(&env);
     let token = Address::generate(&env);
 
     // Set asset with 0% collateral factor
     set_asset_params(&env, &contract_id, &token, true, 0, 0);
+    set_oracle_price(&client, &admin, &token, &100, &7, &admin);
 
     // Deposit collateral
     client.deposit_collateral(&user, &None, &1000);
@@ -1500,3 +1507,719 @@ fn test_borrow_last_activity_updated() {
     let analytics_after = get_user_analytics(&env, &contract_id, &user).unwrap();
     assert!(analytics_after.last_activity > initial_activity);
 }
+
+// ============================================================================
+// CREDIT DELEGATION TESTS
+// ============================================================================
+
+/// Test delegated borrows book debt on the delegator and exhaust the allowance
+///
+/// Scenario: Delegator approves 500; delegatee borrows 300, then tries 300 more.
+/// Expected: Debt lands on the delegator; the second borrow exceeds the 200 left.
+#[test]
+fn test_borrow_on_behalf_draws_down_allowance() {
+    let env = create_test_env();
+    let contract_id = env.register(HelloContract, ());
+    let client = HelloContractClient::new(&env, &contract_id);
+
+    let delegator = Address::generate(&env);
+    let delegatee = Address::generate(&env);
+    client.deposit_collateral(&delegator, &None, &2000);
+    client.approve_credit_delegation(&delegator, &delegatee, &None, &500);
+
+    client.borrow_on_behalf(&delegatee, &delegator, &None, &300);
+    assert_eq!(
+        get_user_position(&env, &contract_id, &delegator)
+            .unwrap()
+            .debt,
+        300
+    );
+    assert!(get_user_position(&env, &contract_id, &delegatee).is_none());
+    assert_eq!(
+        client.get_credit_delegation(&delegator, &delegatee, &None),
+        200
+    );
+
+    let result = client.try_borrow_on_behalf(&delegatee, &delegator, &None, &300);
+    assert_eq!(result, Err(Ok(BorrowError::InsufficientDelegation)));
+
+    client.borrow_on_behalf(&delegatee, &delegator, &None, &200);
+    assert_eq!(
+        client.get_credit_delegation(&delegator, &delegatee, &None),
+        0
+    );
+    let result = client.try_borrow_on_behalf(&delegatee, &delegator, &None, &1);
+    assert_eq!(result, Err(Ok(BorrowError::InsufficientDelegation)));
+}
+
+/// Test revoking a delegation stops further borrows but keeps existing debt
+#[test]
+fn test_borrow_on_behalf_after_revocation() {
+    let env = create_test_env();
+    let contract_id = env.register(HelloContract, ());
+    let client = HelloContractClient::new(&env, &contract_id);
+
+    let delegator = Address::generate(&env);
+    let delegatee = Address::generate(&env);
+    let other = Address::generate(&env);
+    client.deposit_collateral(&delegator, &None, &2000);
+    client.approve_credit_delegation(&delegator, &delegatee, &None, &1000);
+
+    // Allowances are per delegatee
+    let result = client.try_borrow_on_behalf(&other, &delegator, &None, &100);
+    assert_eq!(result, Err(Ok(BorrowError::InsufficientDelegation)));
+
+    client.borrow_on_behalf(&delegatee, &delegator, &None, &400);
+    client.approve_credit_delegation(&delegator, &delegatee, &None, &0);
+
+    let result = client.try_borrow_on_behalf(&delegatee, &delegator, &None, &100);
+    assert_eq!(result, Err(Ok(BorrowError::InsufficientDelegation)));
+    assert_eq!(
+        get_user_position(&env, &contract_id, &delegator)
+            .unwrap()
+            .debt,
+        400
+    );
+}
+
+/// Test the delegator's collateral ratio bounds a delegated borrow
+///
+/// Expected: A borrow the delegator could not make fails and leaves the
+/// allowance untouched.
+#[test]
+fn test_borrow_on_behalf_checks_delegator_health() {
+    let env = create_test_env();
+    let contract_id = env.register(HelloContract, ());
+    let client = HelloContractClient::new(&env, &contract_id);
+
+    let delegator = Address::generate(&env);
+    let delegatee = Address::generate(&env);
+    client.deposit_collateral(&delegator, &None, &1500);
+    // The delegatee's own collateral does not count
+    client.deposit_collateral(&delegatee, &None, &100_000);
+    client.approve_credit_delegation(&delegator, &delegatee, &None, &10_000);
+
+    let max_borrow = calculate_expected_max_borrow(1500, 10000);
+    let result = client.try_borrow_on_behalf(&delegatee, &delegator, &None, &(max_borrow + 1));
+    assert!(result.is_err());
+    assert_eq!(
+        client.get_credit_delegation(&delegator, &delegatee, &None),
+        10_000
+    );
+
+    client.borrow_on_behalf(&delegatee, &delegator, &None, &max_borrow);
+    assert_eq!(
+        client.get_credit_delegation(&delegator, &delegatee, &None),
+        10_000 - max_borrow
+    );
+}
+
+/// Test a delegator's position is liquidated like any other
+///
+/// Scenario: Delegatee borrows 800 of B against 1000 of A; A's price falls.
+/// Expected: The delegator is liquidated; the delegatee has no debt.
+#[test]
+fn test_delegator_liquidated_normally() {
+    let env = create_test_env();
+    let contract_id = env.register(HelloContract, ());
+    let client = HelloContractClient::new(&env, &contract_id);
+    let admin = Address::generate(&env);
+    client.initialize(&admin);
+
+    let delegator = Address::generate(&env);
+    let delegatee = Address::generate(&env);
+    let liquidator = Address::generate(&env);
+    let oracle = Address::generate(&env);
+
+    let collateral = env.register_stellar_asset_contract(Address::generate(&env));
+    soroban_sdk::token::StellarAssetClient::new(&env, &collateral).mint(&delegator, &1000);
+    soroban_sdk::token::Client::new(&env, &collateral).approve(
+        &delegator,
+        &contract_id,
+        &1000,
+        &(env.ledger().sequence() + 100),
+    );
+    let debt = env.register_stellar_asset_contract(Address::generate(&env));
+    soroban_sdk::token::StellarAssetClient::new(&env, &debt).mint(&liquidator, &1000);
+    soroban_sdk::token::Client::new(&env, &debt).approve(
+        &liquidator,
+        &contract_id,
+        &1000,
+        &(env.ledger().sequence() + 100),
+    );
+
+    client.deposit_collateral(&delegator, &Some(collateral.clone()), &1000);
+    client.approve_credit_delegation(&delegator, &delegatee, &Some(debt.clone()), &800);
+    set_oracle_price(&client, &admin, &collateral, &100, &7, &oracle);
+    set_oracle_price(&client, &admin, &debt, &100, &7, &oracle);
+    client.borrow_on_behalf(&delegatee, &delegator, &Some(debt.clone()), &800);
+
+    for price in [95, 91, 87, 84, 80] {
+        set_oracle_price(&client, &admin, &collateral, &price, &7, &oracle);
+    }
+    assert!(client.get_health_factor(&delegator) < 10_000);
+
+    let (debt_liquidated, _, _, _) = client.liquidate(
+        &liquidator,
+        &delegator,
+        &Some(debt.clone()),
+        &Some(collateral),
+        &400,
+        &false,
+    );
+    assert_eq!(debt_liquidated, 400);
+    assert_eq!(
+        get_user_position(&env, &contract_id, &delegator)
+            .unwrap()
+            .debt,
+        400
+    );
+    assert!(get_user_position(&env, &contract_id, &delegatee).is_none());
+}
+
+// ============================================================================
+// ORIGINATION FEE TESTS
+// ============================================================================
+
+/// Borrows 1000 of an asset charging `fee_bps`, then repays the full debt
+///
+/// Returns (reserve after borrow, debt after borrow, debt after repay)
+fn borrow_and_repay_with_fee(fee_bps: i128) -> (i128, i128, i128) {
+    let env = create_test_env();
+    let contract_id = env.register(HelloContract, ());
+    let client = HelloContractClient::new(&env, &contract_id);
+    let admin = Address::generate(&env);
+    client.initialize(&admin);
+
+    let user = Address::generate(&env);
+    let asset = Address::generate(&env);
+    client.update_asset_params(&admin, &asset, &origination_fee_params(fee_bps));
+    set_oracle_price(&client, &admin, &asset, &100, &7, &admin);
+    client.deposit_collateral(&user, &None, &10_000);
+
+    client.borrow_asset(&user, &Some(asset.clone()), &1000);
+    let reserve = client.get_reserve_balance(&Some(asset.clone()));
+    let debt = get_user_position(&env, &contract_id, &user).unwrap().debt;
+
+    client.repay_debt(&user, &Some(asset), &debt);
+    let debt_after_repay = get_user_position(&env, &contract_id, &user).unwrap().debt;
+    (reserve, debt, debt_after_repay)
+}
+
+fn origination_fee_params(borrow_fee_bps: i128) -> deposit::AssetParams {
+    deposit::AssetParams {
+        borrow_fee_bps,
+        ..asset_params(10000)
+    }
+}
+
+/// Test borrows without an origination fee leave reserves untouched
+#[test]
+fn test_borrow_origination_fee_zero() {
+    assert_eq!(borrow_and_repay_with_fee(0), (0, 1000, 0));
+}
+
+/// Test a 50 bps fee is credited to reserves and the full amount is owed
+#[test]
+fn test_borrow_origination_fee_50_bps() {
+    assert_eq!(borrow_and_repay_with_fee(50), (5, 1000, 0));
+}
+
+/// Test the maximum fee is accepted and charged
+#[test]
+fn test_borrow_origination_fee_maximum() {
+    assert_eq!(
+        borrow_and_repay_with_fee(deposit::MAX_BORROW_FEE_BPS),
+        (50, 1000, 0)
+    );
+}
+
+/// Test fees above the maximum are rejected
+#[test]
+fn test_borrow_origination_fee_above_maximum_rejected() {
+    let env = create_test_env();
+    let contract_id = env.register(HelloContract, ());
+    let client = HelloContractClient::new(&env, &contract_id);
+    let admin = Address::generate(&env);
+    client.initialize(&admin);
+
+    let asset = Address::generate(&env);
+    let result = client.try_update_asset_params(
+        &admin,
+        &asset,
+        &origination_fee_params(deposit::MAX_BORROW_FEE_BPS + 1),
+    );
+    assert_eq!(result, Err(Ok(deposit::DepositError::InvalidAssetParams)));
+}
+
+/// Test the borrow event reports the fee withheld from the disbursement
+#[test]
+fn test_borrow_event_reports_origination_fee() {
+    use soroban_sdk::testutils::Events;
+    use soroban_sdk::TryFromVal;
+
+    let env = create_test_env();
+    let contract_id = env.register(HelloContract, ());
+    let client = HelloContractClient::new(&env, &contract_id);
+    let admin = Address::generate(&env);
+    client.initialize(&admin);
+
+    let user = Address::generate(&env);
+    let asset = Address::generate(&env);
+    client.update_asset_params(&admin, &asset, &origination_fee_params(50));
+    set_oracle_price(&client, &admin, &asset, &100, &7, &admin);
+    client.deposit_collateral(&user, &None, &10_000);
+    client.borrow_asset(&user, &Some(asset), &2000);
+
+    let borrow = env
+        .events()
+        .all()
+        .iter()
+        .find_map(|(_, _, data)| {
+            crate::tests::events_test::TestBorrowEvent::try_from_val(&env, &data).ok()
+        })
+        .unwrap();
+    assert_eq!(borrow.user, user);
+    assert_eq!(borrow.amount, 2000);
+    assert_eq!(borrow.fee, 10);
+}
+
+// ============================================================================
+// PRICE AVAILABILITY TESTS
+// ============================================================================
+
+/// Test a token with no price feed cannot be borrowed
+#[test]
+fn test_borrow_unpriced_asset_rejected() {
+    let env = create_test_env();
+    let contract_id = env.register(HelloContract, ());
+    let client = HelloContractClient::new(&env, &contract_id);
+    let admin = Address::generate(&env);
+    client.initialize(&admin);
+
+    let user = Address::generate(&env);
+    let asset = Address::generate(&env);
+    client.deposit_collateral(&user, &None, &10_000);
+
+    let result = client.try_borrow_asset(&user, &Some(asset.clone()), &1000);
+    assert_eq!(result, Err(Ok(BorrowError::PriceUnavailable)));
+    assert_eq!(
+        get_user_position(&env, &contract_id, &user).unwrap().debt,
+        0
+    );
+}
+
+/// Test a stale price blocks new borrows but not repayment of open debt
+#[test]
+fn test_stale_price_blocks_borrow_but_not_repay() {
+    let env = create_test_env();
+    let contract_id = env.register(HelloContract, ());
+    let client = HelloContractClient::new(&env, &contract_id);
+    let admin = Address::generate(&env);
+    client.initialize(&admin);
+
+    let user = Address::generate(&env);
+    let asset = Address::generate(&env);
+    set_oracle_price(&client, &admin, &asset, &100, &7, &admin);
+    client.deposit_collateral(&user, &None, &10_000);
+    client.borrow_asset(&user, &Some(asset.clone()), &1000);
+
+    // Past the default one hour staleness window
+    env.ledger().with_mut(|li| li.timestamp += 3601);
+    let result = client.try_borrow_asset(&user, &Some(asset.clone()), &100);
+    assert_eq!(result, Err(Ok(BorrowError::PriceUnavailable)));
+
+    let (remaining, _, principal_paid) = client.repay_debt(&user, &Some(asset.clone()), &400);
+    assert!(principal_paid > 0);
+    assert!(remaining < 1000);
+
+    // A fresh price reopens borrowing
+    set_oracle_price(&client, &admin, &asset, &100, &7, &admin);
+    client.borrow_asset(&user, &Some(asset), &100);
+}
+
+// ============================================================================
+// RATE AND DEADLINE LIMIT TESTS
+// ============================================================================
+
+/// Borrows 1000 native in a fresh contract, with or without limits
+///
+/// Returns the borrow's return value and the resulting position.
+fn borrow_in_fresh_contract(with_limits: bool) -> (i128, Position) {
+    let env = create_test_env();
+    env.ledger().with_mut(|li| li.timestamp = 1000);
+    let contract_id = env.register(HelloContract, ());
+    let client = HelloContractClient::new(&env, &contract_id);
+    client.initialize(&Address::generate(&env));
+
+    let user = Address::generate(&env);
+    client.deposit_collateral(&user, &None, &10_000);
+    let result = if with_limits {
+        client.borrow_asset_with_limits(&user, &None, &1000, &10_000, &1000)
+    } else {
+        client.borrow_asset(&user, &None, &1000)
+    };
+    (
+        result,
+        get_user_position(&env, &contract_id, &user).unwrap(),
+    )
+}
+
+/// Test a borrow within its limits behaves exactly like `borrow_asset`
+#[test]
+fn test_borrow_with_limits_matches_plain_borrow() {
+    assert_eq!(
+        borrow_in_fresh_contract(true),
+        borrow_in_fresh_contract(false)
+    );
+}
+
+/// Test a borrow submitted past its deadline is rejected
+#[test]
+fn test_borrow_with_limits_past_deadline_rejected() {
+    let env = create_test_env();
+    env.ledger().with_mut(|li| li.timestamp = 1000);
+    let contract_id = env.register(HelloContract, ());
+    let client = HelloContractClient::new(&env, &contract_id);
+    client.initialize(&Address::generate(&env));
+
+    let user = Address::generate(&env);
+    client.deposit_collateral(&user, &None, &10_000);
+    let result = client.try_borrow_asset_with_limits(&user, &None, &1000, &10_000, &999);
+    assert_eq!(result, Err(Ok(BorrowError::DeadlineExceeded)));
+    assert_eq!(
+        get_user_position(&env, &contract_id, &user).unwrap().debt,
+        0
+    );
+}
+
+/// Test an emergency rate increase pushes a borrow over its rate cap
+#[test]
+fn test_borrow_with_limits_rate_above_cap_rejected() {
+    let env = create_test_env();
+    let contract_id = env.register(HelloContract, ());
+    let client = HelloContractClient::new(&env, &contract_id);
+    let admin = Address::generate(&env);
+    client.initialize(&admin);
+
+    let user = Address::generate(&env);
+    client.deposit_collateral(&user, &None, &10_000);
+    let deadline = env.ledger().timestamp() + 60;
+    client.borrow_asset_with_limits(&user, &None, &500, &10_000, &deadline);
+    let quoted_rate = client.get_borrow_rate();
+
+    client.set_emergency_rate_adjustment(&admin, &500, &86_400);
+    let result = client.try_borrow_asset_with_limits(&user, &None, &1, &quoted_rate, &deadline);
+    assert_eq!(result, Err(Ok(BorrowError::BorrowRateTooHigh)));
+    assert_eq!(
+        get_user_position(&env, &contract_id, &user).unwrap().debt,
+        500
+    );
+}
+
+// ============================================================================
+// BATCH BORROW TESTS
+// ============================================================================
+
+/// Initializes the contract, deposits 10_000 native collateral for `user` and
+/// lists two tokens priced at 100
+fn setup_batch_borrow<'a>(
+    env: &'a Env,
+    user: &Address,
+) -> (Address, HelloContractClient<'a>, Address, Address) {
+    let contract_id = env.register(HelloContract, ());
+    let client = HelloContractClient::new(env, &contract_id);
+    let admin = Address::generate(env);
+    client.initialize(&admin);
+    client.deposit_collateral(user, &None, &10_000);
+
+    let first = Address::generate(env);
+    let second = Address::generate(env);
+    set_oracle_price(&client, &admin, &first, &100, &7, &admin);
+    set_oracle_price(&client, &admin, &second, &100, &7, &admin);
+    (contract_id, client, first, second)
+}
+
+/// Test a batch whose items each fit but together exceed the limit reverts entirely
+#[test]
+fn test_borrow_batch_checks_combined_debt() {
+    let env = create_test_env();
+    let user = Address::generate(&env);
+    let (contract_id, client, first, second) = setup_batch_borrow(&env, &user);
+
+    // 10_000 at the 110% minimum ratio supports 9_090 of debt in total
+    let requests: Vec<(Option<Address>, i128)> = vec![
+        &env,
+        (Some(first.clone()), 5_000),
+        (Some(second.clone()), 5_000),
+    ];
+    let result = client.try_borrow_batch(&user, &requests);
+    assert_eq!(
+        result,
+        Err(Ok(BorrowError::InsufficientCollateralRatio.into()))
+    );
+
+    // Nothing from the batch was applied
+    assert_eq!(
+        get_user_position(&env, &contract_id, &user).unwrap().debt,
+        0
+    );
+    assert_eq!(
+        get_protocol_analytics(&env, &contract_id)
+            .unwrap()
+            .total_borrows,
+        0
+    );
+
+    // Either item alone is fine
+    let requests: Vec<(Option<Address>, i128)> = vec![&env, (Some(first), 5_000)];
+    assert_eq!(client.borrow_batch(&user, &requests), vec![&env, 5_000]);
+    let requests: Vec<(Option<Address>, i128)> = vec![&env, (Some(second), 4_090)];
+    assert_eq!(client.borrow_batch(&user, &requests), vec![&env, 9_090]);
+}
+
+/// Test a failing item is reported with its index
+#[test]
+fn test_borrow_batch_item_error_carries_index() {
+    let env = create_test_env();
+    let user = Address::generate(&env);
+    let (contract_id, client, first, _second) = setup_batch_borrow(&env, &user);
+    let unpriced = Address::generate(&env);
+
+    let requests: Vec<(Option<Address>, i128)> =
+        vec![&env, (Some(first), 1_000), (Some(unpriced), 1_000)];
+    let result = client.try_borrow_batch(&user, &requests);
+    let expected = deposit::batch_item_error(1, BorrowError::PriceUnavailable as u32);
+    assert_eq!(result, Err(Ok(expected)));
+    let code = result.unwrap_err().unwrap().get_code();
+    assert_eq!(
+        deposit::decode_batch_item_error(code),
+        Some((1, BorrowError::PriceUnavailable as u32))
+    );
+    assert_eq!(
+        get_user_position(&env, &contract_id, &user).unwrap().debt,
+        0
+    );
+
+    let empty: Vec<(Option<Address>, i128)> = Vec::new(&env);
+    let result = client.try_borrow_batch(&user, &empty);
+    assert_eq!(result, Err(Ok(BorrowError::InvalidAmount.into())));
+}
+
+// ============================================================================
+// SILOED BORROWING TESTS
+// ============================================================================
+
+/// Initializes the contract, deposits 10_000 native collateral for `user` and
+/// lists a siloed token and a regular one, both priced at 100
+fn setup_siloed_market<'a>(
+    env: &'a Env,
+    user: &Address,
+) -> (HelloContractClient<'a>, Address, Address) {
+    let contract_id = env.register(HelloContract, ());
+    let client = HelloContractClient::new(env, &contract_id);
+    let admin = Address::generate(env);
+    client.initialize(&admin);
+    client.deposit_collateral(user, &None, &10_000);
+
+    let siloed = Address::generate(env);
+    let regular = Address::generate(env);
+    let siloed_params = deposit::AssetParams {
+        siloed: true,
+        ..origination_fee_params(0)
+    };
+    client.update_asset_params(&admin, &siloed, &siloed_params);
+    client.update_asset_params(&admin, &regular, &origination_fee_params(0));
+    set_oracle_price(&client, &admin, &siloed, &100, &7, &admin);
+    set_oracle_price(&client, &admin, &regular, &100, &7, &admin);
+    (client, siloed, regular)
+}
+
+/// Test siloed debt blocks borrowing other assets, native included
+#[test]
+fn test_siloed_debt_blocks_other_borrows() {
+    let env = create_test_env();
+    let user = Address::generate(&env);
+    let (client, siloed, regular) = setup_siloed_market(&env, &user);
+
+    client.borrow_asset(&user, &Some(siloed.clone()), &1_000);
+    let result = client.try_borrow_asset(&user, &Some(regular), &1_000);
+    assert_eq!(result, Err(Ok(BorrowError::SiloViolation)));
+    let result = client.try_borrow_asset(&user, &None, &1_000);
+    assert_eq!(result, Err(Ok(BorrowError::SiloViolation)));
+
+    // Adding to the siloed debt itself is fine
+    client.borrow_asset(&user, &Some(siloed), &1_000);
+}
+
+/// Test a siloed asset cannot be borrowed on top of other debt
+#[test]
+fn test_siloed_borrow_rejected_with_existing_debt() {
+    let env = create_test_env();
+    let user = Address::generate(&env);
+    let (client, siloed, regular) = setup_siloed_market(&env, &user);
+
+    client.borrow_asset(&user, &Some(regular), &1_000);
+    let result = client.try_borrow_asset(&user, &Some(siloed), &1_000);
+    assert_eq!(result, Err(Ok(BorrowError::SiloViolation)));
+}
+
+/// Test repaying the siloed debt in full lifts the restriction
+#[test]
+fn test_siloed_restriction_lifted_after_full_repayment() {
+    let env = create_test_env();
+    let user = Address::generate(&env);
+    let (client, siloed, regular) = setup_siloed_market(&env, &user);
+
+    client.borrow_asset(&user, &Some(siloed.clone()), &1_000);
+    client.repay_debt(&user, &Some(siloed.clone()), &400);
+    let result = client.try_borrow_asset(&user, &Some(regular.clone()), &1_000);
+    assert_eq!(result, Err(Ok(BorrowError::SiloViolation)));
+
+    client.repay_debt(&user, &Some(siloed), &600);
+    client.borrow_asset(&user, &Some(regular), &1_000);
+}
+
+/// Test the exposure limit counts debt across assets and only blocks new borrows
+#[test]
+fn test_user_exposure_limit_spans_assets() {
+    let env = create_test_env();
+    let user = Address::generate(&env);
+    let contract_id = env.register(HelloContract, ());
+    let client = HelloContractClient::new(&env, &contract_id);
+    let admin = Address::generate(&env);
+    client.initialize(&admin);
+    client.deposit_collateral(&user, &None, &10_000);
+    let first = Address::generate(&env);
+    let second = Address::generate(&env);
+    set_oracle_price(&client, &admin, &first, &100, &7, &admin);
+    set_oracle_price(&client, &admin, &second, &100, &7, &admin);
+
+    let result = client.try_set_max_user_debt_value(&user, &500_000);
+    assert_eq!(result, Err(Ok(RiskManagementError::Unauthorized)));
+    client.set_max_user_debt_value(&admin, &500_000);
+    assert_eq!(client.get_max_user_debt_value(), 500_000);
+
+    // Each borrow fits on its own; together they are worth 2_000 * 100 + 3_500 * 100
+    client.borrow_asset(&user, &Some(first.clone()), &2_000);
+    client.borrow_asset(&user, &Some(second.clone()), &2_000);
+    let result = client.try_borrow_asset(&user, &Some(second.clone()), &1_500);
+    assert_eq!(result, Err(Ok(BorrowError::UserExposureExceeded)));
+    client.borrow_asset(&user, &Some(second.clone()), &1_000);
+
+    // A position left over the limit can still be repaid
+    set_oracle_price(&client, &admin, &first, &105, &7, &admin);
+    let result = client.try_borrow_asset(&user, &Some(second), &1);
+    assert_eq!(result, Err(Ok(BorrowError::UserExposureExceeded)));
+    client.repay_debt(&user, &Some(first.clone()), &500);
+    client.borrow_asset(&user, &Some(first), &1);
+}
+
+/// Test collateral flagged against same-ledger borrows only backs a borrow from
+/// the next ledger
+#[test]
+fn test_same_ledger_deposit_cannot_back_borrow() {
+    let env = create_test_env();
+    let contract_id = env.register(HelloContract, ());
+    let client = HelloContractClient::new(&env, &contract_id);
+    let admin = Address::generate(&env);
+    let user = Address::generate(&env);
+    client.initialize(&admin);
+
+    let collateral = env.register_stellar_asset_contract(Address::generate(&env));
+    soroban_sdk::token::StellarAssetClient::new(&env, &collateral).mint(&user, &20_000);
+    soroban_sdk::token::Client::new(&env, &collateral).approve(
+        &user,
+        &contract_id,
+        &20_000,
+        &(env.ledger().sequence() + 100),
+    );
+    let blocked_params = deposit::AssetParams {
+        same_ledger_borrow_blocked: true,
+        ..origination_fee_params(0)
+    };
+    client.update_asset_params(&admin, &collateral, &blocked_params);
+    let debt = Address::generate(&env);
+    set_oracle_price(&client, &admin, &collateral, &100, &7, &admin);
+    set_oracle_price(&client, &admin, &debt, &100, &7, &admin);
+
+    client.deposit_collateral(&user, &Some(collateral.clone()), &10_000);
+    let result = client.try_borrow_asset(&user, &Some(debt.clone()), &1_000);
+    assert_eq!(result, Err(Ok(BorrowError::SameLedgerDeposit)));
+
+    env.ledger().with_mut(|li| li.sequence_number += 1);
+    client.borrow_asset(&user, &Some(debt.clone()), &1_000);
+
+    // A fresh deposit blocks new borrows again, but not repayment
+    client.deposit_collateral(&user, &Some(collateral), &10_000);
+    let result = client.try_borrow_asset(&user, &Some(debt.clone()), &1_000);
+    assert_eq!(result, Err(Ok(BorrowError::SameLedgerDeposit)));
+    client.repay_debt(&user, &Some(debt), &500);
+}
+
+/// Simulate a borrow, then make it, and check the simulation predicted the outcome
+fn assert_simulation_matches(
+    client: &HelloContractClient,
+    user: &Address,
+    asset: &Option<Address>,
+    amount: i128,
+) -> crate::borrow::BorrowSimulation {
+    let simulation = client.simulate_borrow(user, asset, &amount);
+    match client.try_borrow_asset(user, asset, &amount) {
+        Ok(Ok(total_debt)) => {
+            assert!(simulation.would_succeed);
+            assert_eq!(simulation.error_code, 0);
+            assert_eq!(simulation.total_debt, total_debt);
+        }
+        Err(Ok(err)) => {
+            assert!(!simulation.would_succeed);
+            assert_eq!(simulation.error_code, err as u32);
+        }
+        other => panic!("unexpected borrow result: {:?}", other),
+    }
+    simulation
+}
+
+/// Test simulate_borrow agrees with borrow_asset on success and on each kind of failure
+#[test]
+fn test_simulate_borrow_matches_borrow_outcome() {
+    let env = create_test_env();
+    let contract_id = env.register(HelloContract, ());
+    let client = HelloContractClient::new(&env, &contract_id);
+    let admin = Address::generate(&env);
+    let user = Address::generate(&env);
+    client.initialize(&admin);
+    client.deposit_collateral(&user, &None, &10_000);
+    let token = Some(Address::generate(&env));
+    set_oracle_price(&client, &admin, token.as_ref().unwrap(), &100, &7, &admin);
+
+    let simulation = assert_simulation_matches(&client, &user, &token, 1_000);
+    assert!(simulation.would_succeed);
+    assert_eq!(simulation.total_debt, 1_000);
+    assert_eq!(simulation.health_factor, client.get_health_factor(&user));
+    assert_eq!(simulation.borrow_rate, client.get_borrow_rate());
+
+    // Missing price
+    let unpriced = Some(Address::generate(&env));
+    let simulation = assert_simulation_matches(&client, &user, &unpriced, 1_000);
+    assert_eq!(simulation.error_code, BorrowError::PriceUnavailable as u32);
+
+    // Undercollateralized
+    let simulation = assert_simulation_matches(&client, &user, &token, 9_000);
+    assert_eq!(simulation.error_code, BorrowError::MaxBorrowExceeded as u32);
+
+    // Exposure cap
+    client.set_max_user_debt_value(&admin, &150_000);
+    let simulation = assert_simulation_matches(&client, &user, &token, 1_000);
+    assert_eq!(
+        simulation.error_code,
+        BorrowError::UserExposureExceeded as u32
+    );
+    assert_simulation_matches(&client, &user, &token, 500);
+
+    // Paused
+    let pause_borrow = Symbol::new(&env, "pause_borrow");
+    client.set_asset_pause(&admin, &token, &pause_borrow, &true);
+    let simulation = assert_simulation_matches(&client, &user, &token, 100);
+    assert_eq!(simulation.error_code, BorrowError::AssetPaused as u32);
+}
@@ -234,6 +234,8 @@ fn test_admin_can_update_interest_rate_config() {
         &None,           // rate_floor_bps
         &None,           // rate_ceiling_bps
         &Some(210_i128), // spread_bps
+        &None,           // reserve_factor_bps
+        &false,
     );
 }
 
@@ -249,7 +251,7 @@ fn test_set_risk_params_unauthorized_caller_panics() {
     let (_id, _admin, client) = setup(&e);
 
     let attacker = Address::generate(&e);
-    client.set_risk_params(&attacker, &None, &None, &None, &None);
+    client.set_risk_params(&attacker, &None, &None, &None, &None, &None, &None);
 }
 
 /// A non-admin caller must NOT be able to trigger emergency pause; must panic.
@@ -1,8 +1,9 @@
 use crate::deposit::{AssetParams, DepositDataKey, Position, ProtocolAnalytics, UserAnalytics};
+use crate::tests::test_helpers::{asset_params, set_oracle_price};
 use crate::{deposit, HelloContract, HelloContractClient};
 use soroban_sdk::{
     contracttype,
-    testutils::{Address as _, Events},
+    testutils::{Address as _, Events, Ledger},
     vec, Address, Env, IntoVal, Map, Symbol, TryFromVal, Val, Vec,
 };
 
@@ -42,8 +43,8 @@ fn set_asset_params(
     env.as_contract(contract_id, || {
         let params = AssetParams {
             deposit_enabled,
-            collateral_factor,
             max_deposit,
+            ..asset_params(collateral_factor)
         };
         let key = DepositDataKey::AssetParams(asset.clone());
         env.storage().persistent().set(&key, &params);
@@ -91,6 +92,23 @@ fn get_protocol_analytics(env: &Env, contract_id: &Address) -> Option<ProtocolAn
     })
 }
 
+/// Helper function to record native borrows, so deposits earn their share of
+/// the interest on them
+fn set_total_borrows(env: &Env, contract_id: &Address, total_borrows: i128) {
+    let mut analytics = get_protocol_analytics(env, contract_id).unwrap();
+    analytics.total_borrows = total_borrows;
+    env.as_contract(contract_id, || {
+        env.storage()
+            .persistent()
+            .set(&DepositDataKey::ProtocolAnalytics, &analytics);
+        let mut totals = crate::deposit::get_asset_totals(env, &None);
+        totals.total_borrowed = total_borrows;
+        env.storage()
+            .persistent()
+            .set(&DepositDataKey::AssetTotals(None), &totals);
+    });
+}
+
 /// Helper function to set pause switch
 fn set_pause_switch(env: &Env, contract_id: &Address, operation: &str, paused: bool) {
     env.as_contract(contract_id, || {
@@ -434,3 +452,755 @@ fn test_deposit_collateral_activity_log_limit() {
         assert_eq!(log.len(), 1000); // Verify limit was maintained
     });
 }
+
+// ============================================================================
+// BATCH DEPOSIT TESTS
+// ============================================================================
+
+#[test]
+fn test_deposit_collateral_batch_mixed_assets() {
+    let env = create_test_env();
+    let contract_id = env.register(HelloContract, ());
+    let client = HelloContractClient::new(&env, &contract_id);
+
+    let user = Address::generate(&env);
+    let admin = Address::generate(&env);
+    let token = create_token_contract(&env, &admin);
+
+    set_asset_params(&env, &contract_id, &token, true, 7500, 0);
+    mint_tokens(&env, &token, &admin, &user, 1000);
+    allow_tokens(&env, &token, &user, &contract_id, 1000);
+
+    let deposits: Vec<(Option<Address>, i128)> =
+        vec![&env, (None, 500), (Some(token.clone()), 300), (None, 200)];
+    let balances = client.deposit_collateral_batch(&user, &deposits);
+
+    assert_eq!(balances, vec![&env, 500, 800, 1000]);
+    assert_eq!(get_collateral_balance(&env, &contract_id, &user), 1000);
+
+    let token_client = soroban_sdk::token::Client::new(&env, &token);
+    assert_eq!(token_client.balance(&contract_id), 300);
+    assert_eq!(token_client.balance(&user), 700);
+
+    let analytics = get_user_analytics(&env, &contract_id, &user).unwrap();
+    assert_eq!(analytics.transaction_count, 3);
+}
+
+#[test]
+fn test_deposit_collateral_batch_second_item_fails_reverts_all() {
+    let env = create_test_env();
+    let contract_id = env.register(HelloContract, ());
+    let client = HelloContractClient::new(&env, &contract_id);
+
+    let user = Address::generate(&env);
+    let admin = Address::generate(&env);
+    let token = create_token_contract(&env, &admin);
+
+    set_asset_params(&env, &contract_id, &token, true, 7500, 0);
+    mint_tokens(&env, &token, &admin, &user, 1000);
+    allow_tokens(&env, &token, &user, &contract_id, 1000);
+
+    // Second entry has a zero amount
+    let deposits: Vec<(Option<Address>, i128)> =
+        vec![&env, (Some(token.clone()), 400), (None, 0), (None, 100)];
+    let result = client.try_deposit_collateral_batch(&user, &deposits);
+
+    let expected = deposit::batch_item_error(1, deposit::DepositError::InvalidAmount as u32);
+    assert_eq!(result, Err(Ok(expected)));
+    let code = result.unwrap_err().unwrap().get_code();
+    assert_eq!(
+        deposit::decode_batch_item_error(code),
+        Some((1, deposit::DepositError::InvalidAmount as u32))
+    );
+
+    // Nothing from the batch was applied
+    assert_eq!(get_collateral_balance(&env, &contract_id, &user), 0);
+    assert!(get_user_position(&env, &contract_id, &user).is_none());
+    let token_client = soroban_sdk::token::Client::new(&env, &token);
+    assert_eq!(token_client.balance(&contract_id), 0);
+    assert_eq!(token_client.balance(&user), 1000);
+}
+
+#[test]
+fn test_deposit_collateral_batch_empty() {
+    let env = create_test_env();
+    let contract_id = env.register(HelloContract, ());
+    let client = HelloContractClient::new(&env, &contract_id);
+
+    let user = Address::generate(&env);
+    let deposits: Vec<(Option<Address>, i128)> = Vec::new(&env);
+    let result = client.try_deposit_collateral_batch(&user, &deposits);
+
+    assert_eq!(result, Err(Ok(deposit::DepositError::InvalidAmount.into())));
+}
+
+#[test]
+fn test_deposit_collateral_batch_too_large() {
+    let env = create_test_env();
+    let contract_id = env.register(HelloContract, ());
+    let client = HelloContractClient::new(&env, &contract_id);
+
+    let user = Address::generate(&env);
+    let mut deposits: Vec<(Option<Address>, i128)> = Vec::new(&env);
+    for _ in 0..=deposit::MAX_BATCH_SIZE {
+        deposits.push_back((None, 10));
+    }
+    let result = client.try_deposit_collateral_batch(&user, &deposits);
+
+    assert_eq!(result, Err(Ok(deposit::DepositError::BatchTooLarge.into())));
+    assert_eq!(get_collateral_balance(&env, &contract_id, &user), 0);
+}
+
+#[test]
+fn test_decode_batch_item_error() {
+    let last = deposit::MAX_BATCH_SIZE - 1;
+    let code = deposit::batch_item_error(last, 99).get_code();
+    assert_eq!(deposit::decode_batch_item_error(code), Some((last, 99)));
+    assert_eq!(deposit::decode_batch_item_error(7), None);
+    let past_end =
+        deposit::BATCH_ERROR_BASE + deposit::MAX_BATCH_SIZE * deposit::BATCH_ITEM_CODE_LIMIT;
+    assert_eq!(deposit::decode_batch_item_error(past_end), None);
+}
+
+#[test]
+#[should_panic(expected = "batch error code out of range")]
+fn test_batch_item_error_rejects_code_outside_slot() {
+    deposit::batch_item_error(1, deposit::BATCH_ITEM_CODE_LIMIT);
+}
+
+// ============================================================================
+// SHARE ACCOUNTING TESTS
+// ============================================================================
+
+const ONE_YEAR: u64 = 365 * 86400;
+const RATE_SCALE: i128 = 1_000_000_000_000_000_000;
+
+#[test]
+fn test_first_deposit_mints_shares_one_to_one() {
+    let env = create_test_env();
+    let contract_id = env.register(HelloContract, ());
+    let client = HelloContractClient::new(&env, &contract_id);
+
+    let user = Address::generate(&env);
+    client.deposit_collateral(&user, &None, &1000);
+
+    assert_eq!(client.get_user_shares(&user, &None), 1000);
+    assert_eq!(client.get_exchange_rate(&None), RATE_SCALE);
+}
+
+#[test]
+fn test_early_depositor_redeems_accrued_interest() {
+    let env = create_test_env();
+    let contract_id = env.register(HelloContract, ());
+    let client = HelloContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    client.initialize(&admin);
+
+    let user = Address::generate(&env);
+    let deposit_amount = 1_000_000;
+    client.deposit_collateral(&user, &None, &deposit_amount);
+    set_total_borrows(&env, &contract_id, 500_000);
+
+    env.ledger().with_mut(|li| li.timestamp += ONE_YEAR);
+
+    // The 13.5% charged on the 500_000 borrowed at 50% utilization, net of the
+    // 10% reserve factor
+    let rate = client.get_exchange_rate(&None);
+    assert!(rate > RATE_SCALE);
+
+    let shares = client.get_user_shares(&user, &None);
+    let redeemable = shares * rate / RATE_SCALE;
+    assert_eq!(redeemable, 1_060_750);
+
+    // Borrowers repay, so the pool can pay the interest out
+    client.accrue_interest(&None);
+    set_total_borrows(&env, &contract_id, 0);
+
+    // Cannot take more than the shares are worth
+    let over = client.try_withdraw_collateral(&user, &None, &(redeemable + 1));
+    assert!(over.is_err());
+
+    client.withdraw_collateral(&user, &None, &redeemable);
+    assert!(redeemable > deposit_amount);
+    assert_eq!(client.get_user_shares(&user, &None), 0);
+    assert_eq!(get_collateral_balance(&env, &contract_id, &user), 0);
+}
+
+#[test]
+fn test_depositors_split_pool_proportionally() {
+    let env = create_test_env();
+    let contract_id = env.register(HelloContract, ());
+    let client = HelloContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    client.initialize(&admin);
+
+    let user1 = Address::generate(&env);
+    let user2 = Address::generate(&env);
+    client.deposit_collateral(&user1, &None, &1_000_000);
+    client.deposit_collateral(&user2, &None, &3_000_000);
+    set_total_borrows(&env, &contract_id, 2_000_000);
+
+    env.ledger().with_mut(|li| li.timestamp += ONE_YEAR);
+
+    let rate = client.get_exchange_rate(&None);
+    let owed1 = client.get_user_shares(&user1, &None) * rate / RATE_SCALE;
+    let owed2 = client.get_user_shares(&user2, &None) * rate / RATE_SCALE;
+    assert_eq!(owed1, 1_060_750);
+    assert_eq!(owed2, 3_182_250);
+    assert_eq!(owed2, owed1 * 3);
+
+    client.accrue_interest(&None);
+    set_total_borrows(&env, &contract_id, 0);
+
+    client.withdraw_collateral(&user1, &None, &owed1);
+    client.withdraw_collateral(&user2, &None, &owed2);
+    assert_eq!(client.get_user_shares(&user1, &None), 0);
+    assert_eq!(client.get_user_shares(&user2, &None), 0);
+}
+
+#[test]
+fn test_late_depositor_receives_fewer_shares() {
+    let env = create_test_env();
+    let contract_id = env.register(HelloContract, ());
+    let client = HelloContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    client.initialize(&admin);
+
+    let early = Address::generate(&env);
+    let late = Address::generate(&env);
+    client.deposit_collateral(&early, &None, &1_000_000);
+    set_total_borrows(&env, &contract_id, 500_000);
+
+    env.ledger().with_mut(|li| li.timestamp += ONE_YEAR);
+
+    // Pool is now worth 1_060_750, so the same value buys the same shares
+    client.deposit_collateral(&late, &None, &1_060_750);
+    assert_eq!(client.get_user_shares(&late, &None), 1_000_000);
+    assert_eq!(
+        client.get_user_shares(&early, &None),
+        client.get_user_shares(&late, &None)
+    );
+}
+
+// ============================================================================
+// FEE-ON-TRANSFER TOKEN TESTS
+// ============================================================================
+
+#[contracttype]
+#[derive(Clone)]
+enum FeeTokenKey {
+    Balance(Address),
+    FeeBps,
+}
+
+/// Minimal token that skims `fee_bps` of every `transfer_from`
+#[soroban_sdk::contract]
+pub struct FeeOnTransferToken;
+
+#[soroban_sdk::contractimpl]
+impl FeeOnTransferToken {
+    pub fn mint(env: Env, to: Address, amount: i128) {
+        let balance = Self::balance(env.clone(), to.clone());
+        env.storage()
+            .persistent()
+            .set(&FeeTokenKey::Balance(to), &(balance + amount));
+    }
+
+    pub fn set_fee_bps(env: Env, fee_bps: i128) {
+        env.storage()
+            .persistent()
+            .set(&FeeTokenKey::FeeBps, &fee_bps);
+    }
+
+    pub fn balance(env: Env, id: Address) -> i128 {
+        env.storage()
+            .persistent()
+            .get(&FeeTokenKey::Balance(id))
+            .unwrap_or(0)
+    }
+
+    pub fn transfer(env: Env, from: Address, to: Address, amount: i128) {
+        from.require_auth();
+        Self::move_balance(&env, from, to, amount);
+    }
+
+    pub fn transfer_from(env: Env, spender: Address, from: Address, to: Address, amount: i128) {
+        spender.require_auth();
+        Self::move_balance(&env, from, to, amount);
+    }
+}
+
+impl FeeOnTransferToken {
+    fn move_balance(env: &Env, from: Address, to: Address, amount: i128) {
+        let fee_bps: i128 = env
+            .storage()
+            .persistent()
+            .get(&FeeTokenKey::FeeBps)
+            .unwrap_or(100);
+        let fee = amount * fee_bps / 10_000;
+
+        let from_balance = Self::balance(env.clone(), from.clone());
+        env.storage()
+            .persistent()
+            .set(&FeeTokenKey::Balance(from), &(from_balance - amount));
+        let to_balance = Self::balance(env.clone(), to.clone());
+        env.storage()
+            .persistent()
+            .set(&FeeTokenKey::Balance(to), &(to_balance + amount - fee));
+    }
+}
+
+#[test]
+fn test_deposit_fee_on_transfer_credits_received_amount() {
+    let env = create_test_env();
+    let contract_id = env.register(HelloContract, ());
+    let client = HelloContractClient::new(&env, &contract_id);
+
+    let user = Address::generate(&env);
+    let token = env.register(FeeOnTransferToken, ());
+    let token_client = FeeOnTransferTokenClient::new(&env, &token);
+    token_client.mint(&user, &10_000);
+
+    // 1% is skimmed, so only 990 reaches the contract
+    let result = client.deposit_collateral(&user, &Some(token.clone()), &1_000);
+    assert_eq!(result, 990);
+    assert_eq!(token_client.balance(&contract_id), 990);
+    assert_eq!(get_collateral_balance(&env, &contract_id, &user), 990);
+    assert_eq!(client.get_user_shares(&user, &Some(token.clone())), 990);
+
+    let analytics = get_user_analytics(&env, &contract_id, &user).unwrap();
+    assert_eq!(analytics.total_deposits, 990);
+
+    // The deposit event reports the credited amount
+    let deposit_event = env
+        .events()
+        .all()
+        .iter()
+        .find_map(|(_c, _t, data)| {
+            crate::tests::events_test::TestDepositEvent::try_from_val(&env, &data).ok()
+        })
+        .expect("deposit event not emitted");
+    assert_eq!(deposit_event.amount, 990);
+
+    // The pool can pay out everything it credited
+    client.withdraw_collateral(&user, &Some(token.clone()), &990);
+    assert_eq!(client.get_user_shares(&user, &Some(token)), 0);
+}
+
+#[test]
+fn test_deposit_fee_on_transfer_nothing_received() {
+    let env = create_test_env();
+    let contract_id = env.register(HelloContract, ());
+    let client = HelloContractClient::new(&env, &contract_id);
+
+    let user = Address::generate(&env);
+    let token = env.register(FeeOnTransferToken, ());
+    let token_client = FeeOnTransferTokenClient::new(&env, &token);
+    token_client.mint(&user, &10_000);
+    token_client.set_fee_bps(&10_000);
+
+    let result = client.try_deposit_collateral(&user, &Some(token), &1_000);
+    assert_eq!(result, Err(Ok(deposit::DepositError::TransferShortfall)));
+    assert_eq!(get_collateral_balance(&env, &contract_id, &user), 0);
+}
+
+// ============================================================================
+// PER-ASSET DEPOSIT VIEW TESTS
+// ============================================================================
+
+#[test]
+fn test_get_user_deposit_per_asset() {
+    let env = create_test_env();
+    let contract_id = env.register(HelloContract, ());
+    let client = HelloContractClient::new(&env, &contract_id);
+
+    let user = Address::generate(&env);
+    let admin = Address::generate(&env);
+    let token = create_token_contract(&env, &admin);
+    mint_tokens(&env, &token, &admin, &user, 1000);
+    allow_tokens(&env, &token, &user, &contract_id, 1000);
+
+    client.deposit_collateral(&user, &None, &500);
+    client.deposit_collateral(&user, &Some(token.clone()), &300);
+
+    assert_eq!(client.get_user_deposit(&user, &None), 500);
+    assert_eq!(client.get_user_deposit(&user, &Some(token)), 300);
+    assert_eq!(client.get_user_deposit(&Address::generate(&env), &None), 0);
+}
+
+#[test]
+fn test_get_user_deposits_drops_emptied_asset() {
+    let env = create_test_env();
+    let contract_id = env.register(HelloContract, ());
+    let client = HelloContractClient::new(&env, &contract_id);
+
+    let user = Address::generate(&env);
+    let admin = Address::generate(&env);
+    let token = create_token_contract(&env, &admin);
+    mint_tokens(&env, &token, &admin, &user, 1000);
+    allow_tokens(&env, &token, &user, &contract_id, 1000);
+
+    client.deposit_collateral(&user, &None, &500);
+    client.deposit_collateral(&user, &Some(token.clone()), &300);
+    client.deposit_collateral(&user, &None, &100);
+
+    let deposits = client.get_user_deposits(&user);
+    assert_eq!(
+        deposits,
+        vec![&env, (None, 600), (Some(token.clone()), 300)]
+    );
+
+    client.withdraw_collateral(&user, &Some(token.clone()), &300);
+
+    let deposits = client.get_user_deposits(&user);
+    assert_eq!(deposits, vec![&env, (None, 600)]);
+    assert_eq!(client.get_user_deposit(&user, &Some(token)), 0);
+}
+
+// ============================================================================
+// Per-User Deposit Cap Tests
+// ============================================================================
+
+/// Helper function to set the per-user deposit cap on an asset
+fn set_user_cap(env: &Env, contract_id: &Address, asset: &Address, cap: i128) {
+    env.as_contract(contract_id, || {
+        let key = DepositDataKey::AssetParams(asset.clone());
+        let mut params = env
+            .storage()
+            .persistent()
+            .get::<DepositDataKey, AssetParams>(&key)
+            .unwrap();
+        params.max_deposit_per_user = cap;
+        env.storage().persistent().set(&key, &params);
+    });
+}
+
+#[test]
+fn test_user_cap_accumulates_across_deposits() {
+    let env = create_test_env();
+    let contract_id = env.register(HelloContract, ());
+    let client = HelloContractClient::new(&env, &contract_id);
+
+    let user = Address::generate(&env);
+    let other = Address::generate(&env);
+    let admin = Address::generate(&env);
+    let token = create_token_contract(&env, &admin);
+    set_asset_params(&env, &contract_id, &token, true, 7500, 0);
+    set_user_cap(&env, &contract_id, &token, 1000);
+    mint_tokens(&env, &token, &admin, &user, 2000);
+    allow_tokens(&env, &token, &user, &contract_id, 2000);
+    mint_tokens(&env, &token, &admin, &other, 1000);
+    allow_tokens(&env, &token, &other, &contract_id, 1000);
+
+    client.deposit_collateral(&user, &Some(token.clone()), &400);
+    client.deposit_collateral(&user, &Some(token.clone()), &400);
+
+    let result = client.try_deposit_collateral(&user, &Some(token.clone()), &300);
+    assert_eq!(result, Err(Ok(deposit::DepositError::UserCapExceeded)));
+
+    // Filling up to exactly the cap is allowed
+    client.deposit_collateral(&user, &Some(token.clone()), &200);
+    assert_eq!(client.get_user_deposit(&user, &Some(token.clone())), 1000);
+
+    // The cap is per user, not per asset
+    client.deposit_collateral(&other, &Some(token.clone()), &1000);
+}
+
+#[test]
+fn test_lowered_user_cap_does_not_block_withdrawals() {
+    let env = create_test_env();
+    let contract_id = env.register(HelloContract, ());
+    let client = HelloContractClient::new(&env, &contract_id);
+
+    let user = Address::generate(&env);
+    let admin = Address::generate(&env);
+    let token = create_token_contract(&env, &admin);
+    set_asset_params(&env, &contract_id, &token, true, 7500, 0);
+    mint_tokens(&env, &token, &admin, &user, 1000);
+    allow_tokens(&env, &token, &user, &contract_id, 1000);
+
+    client.deposit_collateral(&user, &Some(token.clone()), &800);
+    set_user_cap(&env, &contract_id, &token, 500);
+
+    let result = client.try_deposit_collateral(&user, &Some(token.clone()), &1);
+    assert_eq!(result, Err(Ok(deposit::DepositError::UserCapExceeded)));
+
+    client.withdraw_collateral(&user, &Some(token.clone()), &300);
+    assert_eq!(client.get_user_deposit(&user, &Some(token.clone())), 500);
+}
+
+// ============================================================================
+// Deposit Allowlist Tests
+// ============================================================================
+
+/// Helper function to toggle the deposit allowlist on an asset
+fn set_allowlist_enabled(env: &Env, contract_id: &Address, asset: &Address, enabled: bool) {
+    env.as_contract(contract_id, || {
+        let key = DepositDataKey::AssetParams(asset.clone());
+        let mut params = env
+            .storage()
+            .persistent()
+            .get::<DepositDataKey, AssetParams>(&key)
+            .unwrap();
+        params.allowlist_enabled = enabled;
+        env.storage().persistent().set(&key, &params);
+    });
+}
+
+#[test]
+fn test_allowlist_rejects_unlisted_depositors() {
+    let env = create_test_env();
+    let contract_id = env.register(HelloContract, ());
+    let client = HelloContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    client.initialize(&admin);
+    let listed = Address::generate(&env);
+    let unlisted = Address::generate(&env);
+    let token = create_token_contract(&env, &admin);
+    set_asset_params(&env, &contract_id, &token, true, 7500, 0);
+    set_allowlist_enabled(&env, &contract_id, &token, true);
+    for user in [&listed, &unlisted] {
+        mint_tokens(&env, &token, &admin, user, 1000);
+        allow_tokens(&env, &token, user, &contract_id, 1000);
+    }
+
+    client.add_to_deposit_allowlist(&admin, &token, &listed);
+    assert!(client.is_deposit_allowlisted(&token, &listed));
+
+    client.deposit_collateral(&listed, &Some(token.clone()), &500);
+    let result = client.try_deposit_collateral(&unlisted, &Some(token.clone()), &500);
+    assert_eq!(result, Err(Ok(deposit::DepositError::NotAllowlisted)));
+
+    // Other assets are unaffected
+    client.deposit_collateral(&unlisted, &None, &500);
+}
+
+#[test]
+fn test_allowlist_enabled_on_asset_with_open_deposits() {
+    let env = create_test_env();
+    let contract_id = env.register(HelloContract, ());
+    let client = HelloContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    client.initialize(&admin);
+    let user = Address::generate(&env);
+    let token = create_token_contract(&env, &admin);
+    set_asset_params(&env, &contract_id, &token, true, 7500, 0);
+    mint_tokens(&env, &token, &admin, &user, 1000);
+    allow_tokens(&env, &token, &user, &contract_id, 1000);
+
+    client.deposit_collateral(&user, &Some(token.clone()), &600);
+    set_allowlist_enabled(&env, &contract_id, &token, true);
+
+    // New deposits are gated, but the existing balance stays withdrawable
+    let result = client.try_deposit_collateral(&user, &Some(token.clone()), &100);
+    assert_eq!(result, Err(Ok(deposit::DepositError::NotAllowlisted)));
+    client.withdraw_collateral(&user, &Some(token.clone()), &600);
+    assert_eq!(client.get_user_deposit(&user, &Some(token.clone())), 0);
+
+    // Listing and then delisting the user gates them again
+    client.add_to_deposit_allowlist(&admin, &token, &user);
+    client.deposit_collateral(&user, &Some(token.clone()), &100);
+    client.remove_from_deposit_allowlist(&admin, &token, &user);
+    let result = client.try_deposit_collateral(&user, &Some(token.clone()), &100);
+    assert_eq!(result, Err(Ok(deposit::DepositError::NotAllowlisted)));
+
+    // Turning the flag off restores open deposits
+    set_allowlist_enabled(&env, &contract_id, &token, false);
+    client.deposit_collateral(&user, &Some(token.clone()), &100);
+}
+
+#[test]
+fn test_allowlist_management_requires_admin() {
+    let env = create_test_env();
+    let contract_id = env.register(HelloContract, ());
+    let client = HelloContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    client.initialize(&admin);
+    let stranger = Address::generate(&env);
+    let token = create_token_contract(&env, &admin);
+
+    let result = client.try_add_to_deposit_allowlist(&stranger, &token, &stranger);
+    assert_eq!(result, Err(Ok(deposit::DepositError::Unauthorized)));
+}
+
+// ============================================================================
+// Deposit All Tests
+// ============================================================================
+
+#[test]
+fn test_deposit_all_token_deposits_full_balance() {
+    let env = create_test_env();
+    let contract_id = env.register(HelloContract, ());
+    let client = HelloContractClient::new(&env, &contract_id);
+
+    let user = Address::generate(&env);
+    let admin = Address::generate(&env);
+    let token = create_token_contract(&env, &admin);
+    mint_tokens(&env, &token, &admin, &user, 1234);
+    allow_tokens(&env, &token, &user, &contract_id, 1234);
+
+    let deposited = client.deposit_all(&user, &Some(token.clone()), &None);
+    assert_eq!(deposited, 1234);
+    assert_eq!(client.get_user_deposit(&user, &Some(token.clone())), 1234);
+
+    // Nothing left to sweep
+    let result = client.try_deposit_all(&user, &Some(token), &None);
+    assert_eq!(result, Err(Ok(deposit::DepositError::BelowSafetyBuffer)));
+}
+
+#[test]
+fn test_deposit_all_native_below_buffer_rejected() {
+    let (env, _contract_id, client, admin, user, native_asset) =
+        crate::tests::test_helpers::setup_env_with_native_asset();
+    client.set_native_deposit_buffer(&admin, &100);
+    soroban_sdk::token::StellarAssetClient::new(&env, &native_asset).mint(&user, &100);
+
+    let result = client.try_deposit_all(&user, &None, &Some(1_000));
+    assert_eq!(result, Err(Ok(deposit::DepositError::BelowSafetyBuffer)));
+    assert_eq!(client.get_user_deposit(&user, &None), 0);
+}
+
+#[test]
+fn test_deposit_all_native_buffer_plus_one() {
+    let (env, _contract_id, client, admin, user, native_asset) =
+        crate::tests::test_helpers::setup_env_with_native_asset();
+    client.set_native_deposit_buffer(&admin, &100);
+    soroban_sdk::token::StellarAssetClient::new(&env, &native_asset).mint(&user, &101);
+
+    let deposited = client.deposit_all(&user, &None, &Some(1_000));
+    assert_eq!(deposited, 1);
+    assert_eq!(client.get_user_deposit(&user, &None), 1);
+}
+
+#[test]
+fn test_deposit_all_native_requires_amount() {
+    let (_env, _contract_id, client, _admin, user, _native_asset) =
+        crate::tests::test_helpers::setup_env_with_native_asset();
+
+    let result = client.try_deposit_all(&user, &None, &None);
+    assert_eq!(result, Err(Ok(deposit::DepositError::InvalidAmount)));
+}
+
+// ============================================================================
+// Re-Deposit After Full Withdrawal Tests
+// ============================================================================
+
+#[test]
+fn test_redeposit_after_full_withdrawal_credits_no_phantom_interest() {
+    let env = create_test_env();
+    let contract_id = env.register(HelloContract, ());
+    let client = HelloContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    client.initialize(&admin);
+    let user = Address::generate(&env);
+
+    // Deposit, accrue a year of supply interest, then withdraw everything
+    client.deposit_collateral(&user, &None, &1_000_000);
+    set_total_borrows(&env, &contract_id, 500_000);
+    env.ledger().with_mut(|li| li.timestamp += ONE_YEAR);
+    let balance = client.get_user_deposit(&user, &None);
+    assert_eq!(balance, 1_060_750);
+    client.accrue_interest(&None);
+    set_total_borrows(&env, &contract_id, 0);
+    client.withdraw_collateral(&user, &None, &balance);
+
+    // The exited position is cleared rather than left with stale state
+    assert!(get_user_position(&env, &contract_id, &user).is_none());
+    env.as_contract(&contract_id, || {
+        assert!(!env
+            .storage()
+            .persistent()
+            .has(&DepositDataKey::CollateralBalance(user.clone())));
+        assert!(!env
+            .storage()
+            .persistent()
+            .has(&DepositDataKey::UserDeposit(user.clone(), None)));
+    });
+
+    // More time passes while the user holds nothing
+    env.ledger().with_mut(|li| li.timestamp += ONE_YEAR);
+
+    // A fresh deposit snapshots the current rate and is credited at face value
+    client.deposit_collateral(&user, &None, &1_000_000);
+    assert_eq!(client.get_user_deposit(&user, &None), 1_000_000);
+    assert_eq!(client.get_user_shares(&user, &None), 1_000_000);
+    assert_eq!(get_collateral_balance(&env, &contract_id, &user), 1_000_000);
+
+    let record = env.as_contract(&contract_id, || {
+        env.storage()
+            .persistent()
+            .get::<DepositDataKey, deposit::UserDeposit>(&DepositDataKey::UserDeposit(
+                user.clone(),
+                None,
+            ))
+            .unwrap()
+    });
+    assert_eq!(record.entry_exchange_rate, client.get_exchange_rate(&None));
+
+    let position = get_user_position(&env, &contract_id, &user).unwrap();
+    assert_eq!(position.collateral, 1_000_000);
+    assert_eq!(position.last_accrual_time, env.ledger().timestamp());
+}
+
+// ============================================================================
+// Supply Interest Distribution Tests
+// ============================================================================
+
+#[test]
+fn test_supplier_withdraws_share_of_repaid_borrow_interest() {
+    let env = create_test_env();
+    let contract_id = env.register(HelloContract, ());
+    let client = HelloContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let oracle = Address::generate(&env);
+    client.initialize(&admin);
+    let supplier = Address::generate(&env);
+    let borrower = Address::generate(&env);
+
+    // The supplier provides B, the borrower backs a B loan with A
+    let collateral = create_token_contract(&env, &admin);
+    let asset = create_token_contract(&env, &admin);
+    mint_tokens(&env, &asset, &admin, &supplier, 1_000_000);
+    allow_tokens(&env, &asset, &supplier, &contract_id, 1_000_000);
+    mint_tokens(&env, &collateral, &admin, &borrower, 2_000_000);
+    allow_tokens(&env, &collateral, &borrower, &contract_id, 2_000_000);
+    set_oracle_price(&client, &admin, &collateral, &100, &7, &oracle);
+    set_oracle_price(&client, &admin, &asset, &100, &7, &oracle);
+
+    client.deposit_collateral(&supplier, &Some(asset.clone()), &1_000_000);
+    client.deposit_collateral(&borrower, &Some(collateral), &2_000_000);
+    client.borrow_asset(&borrower, &Some(asset.clone()), &500_000);
+
+    // Pay the loan out, as outside of tests, and fund the interest
+    let token = soroban_sdk::token::Client::new(&env, &asset);
+    token.transfer(&contract_id, &borrower, &500_000);
+    mint_tokens(&env, &asset, &admin, &borrower, 100_000);
+    allow_tokens(&env, &asset, &borrower, &contract_id, 600_000);
+
+    env.ledger().with_mut(|li| li.timestamp += ONE_YEAR);
+    let reserve_before = client.get_reserve_balance(&Some(asset.clone()));
+    let (remaining_debt, interest_paid, _principal_paid) =
+        client.repay_debt(&borrower, &Some(asset.clone()), &i128::MAX);
+    assert_eq!(remaining_debt, 0);
+    assert!(interest_paid > 0);
+    let reserve_growth = client.get_reserve_balance(&Some(asset.clone())) - reserve_before;
+    assert!(reserve_growth > 0);
+
+    // The supplier is credited the interest net of the reserve's cut
+    let owed = client.get_user_deposit(&supplier, &Some(asset.clone()));
+    assert!((owed - 1_000_000 - (interest_paid - reserve_growth)).abs() <= 1);
+    client.withdraw_collateral(&supplier, &Some(asset.clone()), &owed);
+    assert_eq!(token.balance(&supplier), owed);
+
+    // Only the reserve, and at most rounding dust, is left behind
+    let left = token.balance(&contract_id) - client.get_reserve_balance(&Some(asset));
+    assert!((0..=2).contains(&left));
+}
@@ -22,7 +22,7 @@ fn edge_unauthorized_set_risk_params() {
     let admin = Address::generate(&env);
     let non_admin = Address::generate(&env);
     client.initialize(&admin);
-    client.set_risk_params(&non_admin, &Some(12_000), &None, &None, &None);
+    client.set_risk_params(&non_admin, &Some(12_000), &None, &None, &None, &None, &None);
 }
 
 /// Non-admin cannot set pause switch (authorization).
@@ -0,0 +1,121 @@
+//! Tests for e-mode categories.
+//!
+//! # Coverage
+//! - Opting into a category raises borrowing power to its collateral factor
+//! - Users in e-mode cannot borrow assets outside their category
+//! - Leaving e-mode is rejected while the position relies on it
+//! - Liquidation eligibility follows the category's liquidation threshold
+
+use crate::borrow::BorrowError;
+use crate::liquidate::LiquidationError;
+use crate::risk_management::{EModeCategory, RiskManagementError};
+use crate::tests::test_helpers::{funded_token, set_oracle_price, setup};
+use crate::HelloContractClient;
+use soroban_sdk::{testutils::Address as _, vec, Address, Env};
+
+/// Two correlated tokens priced at 100 in category 1 (95% LTV, 104% threshold);
+/// `user` deposits 10_000 of the first
+fn setup_emode<'a>(
+    env: &'a Env,
+    user: &Address,
+    liquidator: &Address,
+) -> (Address, HelloContractClient<'a>, Address, Address) {
+    let (contract_id, admin, client) = setup(env);
+    let collateral = funded_token(env, &contract_id, &[user], 10_000);
+    let debt = funded_token(env, &contract_id, &[liquidator], 10_000);
+    set_oracle_price(&client, &admin, &collateral, &100, &7, &admin);
+    set_oracle_price(&client, &admin, &debt, &100, &7, &admin);
+    let category = EModeCategory {
+        id: 1,
+        collateral_factor: 9500,
+        liquidation_threshold: 10400,
+        assets: vec![env, Some(collateral.clone()), Some(debt.clone())],
+    };
+    client.set_emode_category(&admin, &category);
+
+    client.deposit_collateral(user, &Some(collateral.clone()), &10_000);
+    (admin, client, collateral, debt)
+}
+
+#[test]
+fn test_emode_raises_borrowing_power() {
+    let env = Env::default();
+    let user = Address::generate(&env);
+    let (_admin, client, _collateral, debt) = setup_emode(&env, &user, &user);
+
+    // The global 110% minimum ratio caps 10_000 of collateral at 9_090
+    let result = client.try_borrow_asset(&user, &Some(debt.clone()), &9_500);
+    assert_eq!(result, Err(Ok(BorrowError::MaxBorrowExceeded)));
+
+    client.set_user_emode(&user, &1);
+    assert_eq!(client.get_user_emode(&user), 1);
+    client.borrow_asset(&user, &Some(debt.clone()), &9_500);
+    let result = client.try_borrow_asset(&user, &Some(debt), &1);
+    assert_eq!(result, Err(Ok(BorrowError::MaxBorrowExceeded)));
+
+    // The position now depends on e-mode and cannot leave it
+    let result = client.try_set_user_emode(&user, &0);
+    assert_eq!(
+        result,
+        Err(Ok(RiskManagementError::InsufficientCollateralRatio))
+    );
+    assert_eq!(client.get_user_emode(&user), 1);
+}
+
+#[test]
+fn test_emode_rejects_borrowing_outside_category() {
+    let env = Env::default();
+    let user = Address::generate(&env);
+    let (admin, client, _collateral, _debt) = setup_emode(&env, &user, &user);
+    let other = Address::generate(&env);
+    set_oracle_price(&client, &admin, &other, &100, &7, &admin);
+    client.set_user_emode(&user, &1);
+
+    let result = client.try_borrow_asset(&user, &Some(other.clone()), &100);
+    assert_eq!(result, Err(Ok(BorrowError::AssetNotInEMode)));
+    let result = client.try_borrow_asset(&user, &None, &100);
+    assert_eq!(result, Err(Ok(BorrowError::AssetNotInEMode)));
+
+    // Unknown categories cannot be joined; leaving e-mode lifts the restriction
+    let result = client.try_set_user_emode(&user, &2);
+    assert_eq!(result, Err(Ok(RiskManagementError::EModeCategoryNotFound)));
+    client.set_user_emode(&user, &0);
+    client.borrow_asset(&user, &Some(other), &100);
+}
+
+#[test]
+fn test_emode_liquidation_uses_category_threshold() {
+    let env = Env::default();
+    let user = Address::generate(&env);
+    let liquidator = Address::generate(&env);
+    let (admin, client, collateral, debt) = setup_emode(&env, &user, &liquidator);
+    client.set_user_emode(&user, &1);
+    client.borrow_asset(&user, &Some(debt.clone()), &9_500);
+
+    // 9_900 / 9_500 = 104.2%: below the global 105% threshold but above e-mode's 104%
+    set_oracle_price(&client, &admin, &collateral, &99, &7, &admin);
+    assert_eq!(client.get_health_factor(&user), 10_020);
+    let result = client.try_liquidate(
+        &liquidator,
+        &user,
+        &Some(debt.clone()),
+        &Some(collateral.clone()),
+        &1_000,
+        &false,
+    );
+    assert_eq!(result, Err(Ok(LiquidationError::PositionHealthy)));
+
+    // 9_800 / 9_500 = 103.2% is below e-mode's threshold
+    set_oracle_price(&client, &admin, &collateral, &98, &7, &admin);
+    assert_eq!(client.get_health_factor(&user), 9_919);
+    assert!(client.get_account_liquidity(&user).1 > 0);
+    let (debt_liquidated, _collateral_seized, _incentive, _protocol_fee) = client.liquidate(
+        &liquidator,
+        &user,
+        &Some(debt),
+        &Some(collateral),
+        &1_000,
+        &false,
+    );
+    assert_eq!(debt_liquidated, 1_000);
+}
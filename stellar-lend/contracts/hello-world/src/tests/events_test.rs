@@ -17,11 +17,12 @@ use crate::events::{
     PauseStateChangedEvent, PriceUpdatedEvent, RepayEvent, RiskParamsUpdatedEvent, WithdrawalEvent,
 };
 
+use crate::tests::test_helpers::set_oracle_price;
 use crate::{HelloContract, HelloContractClient};
 
 use soroban_sdk::{
     contracttype,
-    testutils::{Address as _, Events},
+    testutils::{Address as _, Events, Ledger},
     Address, Env, Symbol, TryFromVal,
 };
 
@@ -32,9 +33,13 @@ use soroban_sdk::{
 #[contracttype]
 #[derive(Clone, Debug)]
 pub struct TestDepositEvent {
+    pub version: u32,
     pub user: Address,
     pub asset: Option<Address>,
     pub amount: i128,
+    pub balance: i128,
+    pub total_collateral: i128,
+    pub exchange_rate: i128,
     pub timestamp: u64,
 }
 
@@ -42,8 +47,11 @@ pub struct TestDepositEvent {
 #[derive(Clone, Debug)]
 pub struct TestWithdrawalEvent {
     pub user: Address,
+    pub to: Address,
     pub asset: Option<Address>,
     pub amount: i128,
+    pub fee: i128,
+    pub receipt_id: u64,
     pub timestamp: u64,
 }
 
@@ -53,15 +61,36 @@ pub struct TestBorrowEvent {
     pub user: Address,
     pub asset: Option<Address>,
     pub amount: i128,
+    pub fee: i128,
+    pub health_factor: i128,
+    pub collateral_value: i128,
+    pub debt_value: i128,
     pub timestamp: u64,
 }
 
 #[contracttype]
 #[derive(Clone, Debug)]
 pub struct TestRepayEvent {
-    pub user: Address,
-    pub asset: Option<Address>,
+    pub payer: Address,
+    pub borrower: Address,
     pub amount: i128,
+    pub principal_paid: i128,
+    pub interest_paid: i128,
+    pub reserve_amount: i128,
+    pub remaining_debt: i128,
+    pub timestamp: u64,
+}
+
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct TestAccrueEvent {
+    pub elapsed_secs: u64,
+    pub borrow_index_before: i128,
+    pub borrow_index_after: i128,
+    pub supply_index_before: i128,
+    pub supply_index_after: i128,
+    pub interest_accrued: i128,
+    pub reserve_portion: i128,
     pub timestamp: u64,
 }
 
@@ -69,12 +98,15 @@ pub struct TestRepayEvent {
 #[derive(Clone, Debug)]
 pub struct TestLiquidationEvent {
     pub liquidator: Address,
-    pub borrower: Address,
     pub debt_asset: Option<Address>,
-    pub collateral_asset: Option<Address>,
     pub debt_liquidated: i128,
     pub collateral_seized: i128,
     pub incentive_amount: i128,
+    pub protocol_fee: i128,
+    pub health_factor_before: i128,
+    pub health_factor_after: i128,
+    pub debt_price: i128,
+    pub collateral_price: i128,
     pub timestamp: u64,
 }
 
@@ -169,9 +201,13 @@ fn test_deposit_event_structure() {
         emit_deposit(
             &env,
             DepositEvent {
+                version: 2,
                 user: user.clone(),
                 asset: None,
                 amount: 1_000,
+                balance: 1_000,
+                total_collateral: 1_000,
+                exchange_rate: 1_000_000_000_000_000_000,
                 timestamp: 100,
             },
         );
@@ -205,8 +241,11 @@ fn test_withdrawal_event_structure() {
             &env,
             WithdrawalEvent {
                 user: user.clone(),
+                to: user.clone(),
                 asset: Some(asset.clone()),
                 amount: 500,
+                fee: 5,
+                receipt_id: 7,
                 timestamp: 200,
             },
         );
@@ -220,6 +259,8 @@ fn test_withdrawal_event_structure() {
         assert_eq!(decoded.user, user);
         assert_eq!(decoded.asset, Some(asset));
         assert_eq!(decoded.amount, 500);
+        assert_eq!(decoded.fee, 5);
+        assert_eq!(decoded.receipt_id, 7);
         assert_eq!(decoded.timestamp, 200);
     });
 }
@@ -240,6 +281,10 @@ fn test_borrow_event_structure() {
                 user: user.clone(),
                 asset: None,
                 amount: 5_000,
+                fee: 25,
+                health_factor: 12_000,
+                collateral_value: 7_000,
+                debt_value: 5_000,
                 timestamp: 300,
             },
         );
@@ -252,6 +297,10 @@ fn test_borrow_event_structure() {
 
         assert_eq!(decoded.user, user);
         assert_eq!(decoded.amount, 5_000);
+        assert_eq!(decoded.fee, 25);
+        assert_eq!(decoded.health_factor, 12_000);
+        assert_eq!(decoded.collateral_value, 7_000);
+        assert_eq!(decoded.debt_value, 5_000);
         assert_eq!(decoded.timestamp, 300);
     });
 }
@@ -269,9 +318,14 @@ fn test_repay_event_structure() {
         emit_repay(
             &env,
             RepayEvent {
-                user: user.clone(),
+                payer: user.clone(),
+                borrower: user.clone(),
                 asset: None,
                 amount: 2_000,
+                principal_paid: 1_850,
+                interest_paid: 150,
+                reserve_amount: 15,
+                remaining_debt: 3_000,
                 timestamp: 400,
             },
         );
@@ -282,8 +336,13 @@ fn test_repay_event_structure() {
         let decoded: TestRepayEvent =
             TestRepayEvent::try_from_val(&env, &data).expect("Failed to decode RepayEvent");
 
-        assert_eq!(decoded.user, user);
+        assert_eq!(decoded.payer, user);
+        assert_eq!(decoded.borrower, user);
         assert_eq!(decoded.amount, 2_000);
+        assert_eq!(decoded.principal_paid, 1_850);
+        assert_eq!(decoded.interest_paid, 150);
+        assert_eq!(decoded.reserve_amount, 15);
+        assert_eq!(decoded.remaining_debt, 3_000);
         assert_eq!(decoded.timestamp, 400);
     });
 }
@@ -309,26 +368,40 @@ fn test_liquidation_event_structure() {
                 debt_liquidated: 1_000,
                 collateral_seized: 1_100,
                 incentive_amount: 100,
+                protocol_fee: 0,
+                health_factor_before: 9_000,
+                health_factor_after: 10_500,
+                debt_price: 100,
+                collateral_price: 80,
                 timestamp: 999,
             },
         );
 
         let all = env.events().all();
         assert_eq!(all.len(), 1);
-        let (_c, _t, data) = all.get_unchecked(0);
+        let (_c, topics, data) = all.get_unchecked(0);
         let decoded: TestLiquidationEvent = TestLiquidationEvent::try_from_val(&env, &data)
             .expect("Failed to decode LiquidationEvent");
 
+        // Borrower and collateral asset are topics, not payload
+        assert_eq!(topics.len(), 3);
+        let topic_borrower = Address::try_from_val(&env, &topics.get_unchecked(1)).unwrap();
+        let topic_collateral =
+            Option::<Address>::try_from_val(&env, &topics.get_unchecked(2)).unwrap();
         assert_eq!(decoded.liquidator, liquidator);
-        assert_eq!(decoded.borrower, borrower);
+        assert_eq!(topic_borrower, borrower);
         assert!(decoded.debt_asset.is_none());
-        assert!(decoded.collateral_asset.is_none());
+        assert!(topic_collateral.is_none());
         assert_eq!(decoded.debt_liquidated, 1_000);
         assert_eq!(decoded.collateral_seized, 1_100);
         assert_eq!(decoded.incentive_amount, 100);
+        assert_eq!(decoded.health_factor_before, 9_000);
+        assert_eq!(decoded.health_factor_after, 10_500);
+        assert_eq!(decoded.debt_price, 100);
+        assert_eq!(decoded.collateral_price, 80);
         assert_eq!(decoded.timestamp, 999);
         // Security: liquidator ≠ borrower
-        assert_ne!(decoded.liquidator, decoded.borrower);
+        assert_ne!(decoded.liquidator, topic_borrower);
     });
 }
 
@@ -355,17 +428,24 @@ fn test_liquidation_event_with_token_assets() {
                 debt_liquidated: 2_000,
                 collateral_seized: 2_200,
                 incentive_amount: 200,
+                protocol_fee: 0,
+                health_factor_before: 9_000,
+                health_factor_after: 10_500,
+                debt_price: 100,
+                collateral_price: 80,
                 timestamp: 500,
             },
         );
 
         let all = env.events().all();
-        let (_c, _t, data) = all.get_unchecked(0);
+        let (_c, topics, data) = all.get_unchecked(0);
         let decoded: TestLiquidationEvent =
             TestLiquidationEvent::try_from_val(&env, &data).unwrap();
+        let topic_collateral =
+            Option::<Address>::try_from_val(&env, &topics.get_unchecked(2)).unwrap();
 
         assert_eq!(decoded.debt_asset, Some(debt_asset));
-        assert_eq!(decoded.collateral_asset, Some(collateral_asset));
+        assert_eq!(topic_collateral, Some(collateral_asset));
     });
 }
 
@@ -617,9 +697,13 @@ fn test_all_event_helpers_emit_one_event_each() {
         emit_deposit(
             &env,
             DepositEvent {
+                version: 2,
                 user: a.clone(),
                 asset: None,
                 amount: 1,
+                balance: 1,
+                total_collateral: 1,
+                exchange_rate: 1_000_000_000_000_000_000,
                 timestamp: 0,
             },
         );
@@ -627,8 +711,11 @@ fn test_all_event_helpers_emit_one_event_each() {
             &env,
             WithdrawalEvent {
                 user: a.clone(),
+                to: a.clone(),
                 asset: None,
                 amount: 1,
+                fee: 0,
+                receipt_id: 1,
                 timestamp: 0,
             },
         );
@@ -638,15 +725,24 @@ fn test_all_event_helpers_emit_one_event_each() {
                 user: a.clone(),
                 asset: None,
                 amount: 1,
+                fee: 0,
+                health_factor: 0,
+                collateral_value: 0,
+                debt_value: 0,
                 timestamp: 0,
             },
         );
         emit_repay(
             &env,
             RepayEvent {
-                user: a.clone(),
+                payer: a.clone(),
+                borrower: a.clone(),
                 asset: None,
                 amount: 1,
+                principal_paid: 1,
+                interest_paid: 0,
+                reserve_amount: 0,
+                remaining_debt: 0,
                 timestamp: 0,
             },
         );
@@ -660,6 +756,11 @@ fn test_all_event_helpers_emit_one_event_each() {
                 debt_liquidated: 1,
                 collateral_seized: 1,
                 incentive_amount: 0,
+                protocol_fee: 0,
+                health_factor_before: 9_000,
+                health_factor_after: 10_500,
+                debt_price: 100,
+                collateral_price: 80,
                 timestamp: 0,
             },
         );
@@ -745,9 +846,13 @@ fn test_event_with_none_asset_native_xlm() {
         emit_deposit(
             &env,
             DepositEvent {
+                version: 2,
                 user: user.clone(),
                 asset: None,
                 amount: 0,
+                balance: 0,
+                total_collateral: 0,
+                exchange_rate: 1_000_000_000_000_000_000,
                 timestamp: 0,
             },
         );
@@ -778,9 +883,13 @@ fn test_no_sensitive_data_in_deposit_event() {
         emit_deposit(
             &env,
             DepositEvent {
+                version: 2,
                 user: user.clone(),
                 asset: None,
                 amount: 1_000,
+                balance: 1_000,
+                total_collateral: 1_000,
+                exchange_rate: 1_000_000_000_000_000_000,
                 timestamp: 123,
             },
         );
@@ -819,19 +928,25 @@ fn test_no_sensitive_data_in_liquidation_event() {
                 debt_liquidated: 500,
                 collateral_seized: 550,
                 incentive_amount: 50,
+                protocol_fee: 0,
+                health_factor_before: 9_000,
+                health_factor_after: 10_500,
+                debt_price: 100,
+                collateral_price: 80,
                 timestamp: 777,
             },
         );
 
         let all = env.events().all();
-        let (_c, _t, data) = all.get_unchecked(0);
+        let (_c, topics, data) = all.get_unchecked(0);
         let decoded: TestLiquidationEvent =
             TestLiquidationEvent::try_from_val(&env, &data).unwrap();
+        let topic_borrower = Address::try_from_val(&env, &topics.get_unchecked(1)).unwrap();
 
         assert_eq!(decoded.liquidator, liquidator);
-        assert_eq!(decoded.borrower, borrower);
+        assert_eq!(topic_borrower, borrower);
         assert_ne!(decoded.liquidator, uninvolved);
-        assert_ne!(decoded.borrower, uninvolved);
+        assert_ne!(topic_borrower, uninvolved);
     });
 }
 
@@ -872,3 +987,321 @@ fn test_event_sequence_deposit_borrow_repay() {
         "Repay should emit additional events"
     );
 }
+
+/// `borrow_asset` reports the post-borrow account health in its BorrowEvent,
+/// matching the `get_health_factor` view.
+#[test]
+fn test_borrow_event_carries_health_factor() {
+    let (env, _contract_id, client) = setup();
+    let admin = Address::generate(&env);
+    init(&client, &admin);
+    let user = Address::generate(&env);
+
+    client.deposit_collateral(&user, &None, &10_000);
+    client.borrow_asset(&user, &None, &5_000);
+
+    let decoded = env
+        .events()
+        .all()
+        .iter()
+        .rev()
+        .find_map(|(_c, _t, data)| TestBorrowEvent::try_from_val(&env, &data).ok())
+        .expect("borrow event not emitted");
+
+    assert_eq!(decoded.user, user);
+    assert_eq!(decoded.amount, 5_000);
+    assert_eq!(decoded.health_factor, client.get_health_factor(&user));
+    // 10_000 / (5_000 * 105%) at the same price on both sides
+    assert_eq!(decoded.health_factor, 19_047);
+    assert_eq!(decoded.collateral_value, decoded.debt_value * 2);
+    assert!(decoded.debt_value > 0);
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Integration: versioned deposit receipt
+// ─────────────────────────────────────────────────────────────────────────────
+
+/// `deposit_collateral` emits a v2 DepositEvent carrying the resulting balances
+/// and the supply exchange rate, under the unchanged `deposit_event` topic.
+#[test]
+fn test_deposit_event_v2_payload_from_deposit_collateral() {
+    let (env, _contract_id, client) = setup();
+    let admin = Address::generate(&env);
+    init(&client, &admin);
+    let user = Address::generate(&env);
+    env.ledger().with_mut(|li| li.timestamp = 1_000);
+
+    client.deposit_collateral(&user, &None, &500);
+    client.deposit_collateral(&user, &None, &300);
+
+    let (topics, decoded) = env
+        .events()
+        .all()
+        .iter()
+        .rev()
+        .find_map(|(_c, topics, data)| {
+            TestDepositEvent::try_from_val(&env, &data)
+                .ok()
+                .map(|event| (topics, event))
+        })
+        .expect("deposit event not emitted");
+
+    let topic = Symbol::try_from_val(&env, &topics.get_unchecked(0)).unwrap();
+    assert_eq!(topic, Symbol::new(&env, "deposit_event"));
+
+    assert_eq!(decoded.version, crate::events::DEPOSIT_EVENT_VERSION);
+    assert_eq!(decoded.user, user);
+    assert_eq!(decoded.asset, None);
+    assert_eq!(decoded.amount, 300);
+    assert_eq!(decoded.balance, 800);
+    assert_eq!(decoded.total_collateral, 800);
+    assert_eq!(decoded.exchange_rate, crate::deposit::EXCHANGE_RATE_SCALE);
+    assert_eq!(decoded.timestamp, 1_000);
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Integration: repay event details
+// ─────────────────────────────────────────────────────────────────────────────
+
+/// Latest RepayEvent with its topics
+fn last_repay_event(env: &Env) -> (soroban_sdk::Vec<soroban_sdk::Val>, TestRepayEvent) {
+    env.events()
+        .all()
+        .iter()
+        .rev()
+        .find_map(|(_c, topics, data)| {
+            TestRepayEvent::try_from_val(env, &data)
+                .ok()
+                .map(|event| (topics, event))
+        })
+        .expect("repay event not emitted")
+}
+
+/// A native borrow of 10_000 by `user`, left to accrue for a year; `user` and
+/// `payer` each hold 10_000 approved for the contract
+fn setup_accrued_native_debt() -> (Env, HelloContractClient<'static>, Address, Address, Address) {
+    let (env, contract_id, client, _admin, user, native_asset) =
+        crate::tests::test_helpers::setup_env_with_native_asset();
+    let payer = Address::generate(&env);
+    let token_client = soroban_sdk::token::StellarAssetClient::new(&env, &native_asset);
+    for holder in [&user, &payer] {
+        token_client.mint(holder, &10_000);
+        token_client.approve(
+            holder,
+            &contract_id,
+            &10_000,
+            &(env.ledger().sequence() + 100),
+        );
+    }
+
+    client.deposit_collateral(&user, &None, &50_000);
+    client.borrow_asset(&user, &None, &10_000);
+    env.ledger().with_mut(|li| li.timestamp += 31_536_000);
+    (env, client, user, payer, native_asset)
+}
+
+/// A self-repayment reports the split, the reserve share and the remaining debt
+/// under the `repay_event` topic followed by the asset.
+#[test]
+fn test_repay_event_details_for_self_repay() {
+    let (env, client, user, _payer, _native_asset) = setup_accrued_native_debt();
+
+    let (remaining_debt, interest_paid, principal_paid) = client.repay_debt(&user, &None, &5_000);
+    let (topics, decoded) = last_repay_event(&env);
+
+    assert_eq!(topics.len(), 2);
+    let topic = Symbol::try_from_val(&env, &topics.get_unchecked(0)).unwrap();
+    assert_eq!(topic, Symbol::new(&env, "repay_event"));
+    let asset = Option::<Address>::try_from_val(&env, &topics.get_unchecked(1)).unwrap();
+    assert_eq!(asset, None);
+
+    assert!(interest_paid > 0);
+    assert_eq!(decoded.payer, user);
+    assert_eq!(decoded.borrower, user);
+    assert_eq!(decoded.amount, 5_000);
+    assert_eq!(decoded.interest_paid, interest_paid);
+    assert_eq!(decoded.principal_paid, principal_paid);
+    assert_eq!(decoded.reserve_amount, interest_paid / 10);
+    assert_eq!(decoded.remaining_debt, remaining_debt);
+    assert_eq!(remaining_debt, client.get_user_debt(&user, &None));
+}
+
+/// Repaying on behalf pulls from the payer and names both addresses in the event.
+#[test]
+fn test_repay_event_details_for_repay_on_behalf() {
+    let (env, client, user, payer, native_asset) = setup_accrued_native_debt();
+
+    let (remaining_debt, interest_paid, principal_paid) =
+        client.repay_on_behalf(&payer, &user, &None, &5_000);
+    let (topics, decoded) = last_repay_event(&env);
+
+    let topic = Symbol::try_from_val(&env, &topics.get_unchecked(0)).unwrap();
+    assert_eq!(topic, Symbol::new(&env, "repay_event"));
+    assert_eq!(decoded.payer, payer);
+    assert_eq!(decoded.borrower, user);
+    assert_eq!(decoded.amount, 5_000);
+    assert_eq!(decoded.interest_paid, interest_paid);
+    assert_eq!(decoded.principal_paid, principal_paid);
+    assert_eq!(decoded.reserve_amount, interest_paid / 10);
+    assert_eq!(decoded.remaining_debt, remaining_debt);
+
+    let token_client = soroban_sdk::token::Client::new(&env, &native_asset);
+    assert_eq!(token_client.balance(&payer), 5_000);
+    assert_eq!(token_client.balance(&user), 10_000);
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Integration: liquidation event breakdown
+// ─────────────────────────────────────────────────────────────────────────────
+
+/// A liquidation publishes its full breakdown under the borrower and collateral
+/// asset topics, matching what `liquidate` returns and the views around it.
+#[test]
+fn test_liquidation_event_matches_liquidate_result() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register(HelloContract, ());
+    let client = HelloContractClient::new(&env, &contract_id);
+    let admin = Address::generate(&env);
+    client.initialize(&admin);
+
+    let borrower = Address::generate(&env);
+    let liquidator = Address::generate(&env);
+    let oracle = Address::generate(&env);
+    let funded = |holder: &Address| {
+        let token = env.register_stellar_asset_contract(Address::generate(&env));
+        soroban_sdk::token::StellarAssetClient::new(&env, &token).mint(holder, &1_000);
+        soroban_sdk::token::Client::new(&env, &token).approve(
+            holder,
+            &contract_id,
+            &1_000,
+            &(env.ledger().sequence() + 100),
+        );
+        token
+    };
+    let collateral = funded(&borrower);
+    let debt = funded(&liquidator);
+
+    // 1000 of collateral at 100 backs 800 of debt at 100, then falls to 80
+    client.deposit_collateral(&borrower, &Some(collateral.clone()), &1_000);
+    set_oracle_price(&client, &admin, &collateral, &100, &7, &oracle);
+    set_oracle_price(&client, &admin, &debt, &100, &7, &oracle);
+    client.borrow_asset(&borrower, &Some(debt.clone()), &800);
+    for price in [95, 91, 87, 84, 80] {
+        set_oracle_price(&client, &admin, &collateral, &price, &7, &oracle);
+    }
+    let health_factor_before = client.get_health_factor(&borrower);
+
+    let (debt_liquidated, collateral_seized, incentive_amount, protocol_fee) = client.liquidate(
+        &liquidator,
+        &borrower,
+        &Some(debt.clone()),
+        &Some(collateral.clone()),
+        &400,
+        &false,
+    );
+
+    let (topics, decoded) = env
+        .events()
+        .all()
+        .iter()
+        .rev()
+        .find_map(|(_c, topics, data)| {
+            TestLiquidationEvent::try_from_val(&env, &data)
+                .ok()
+                .map(|event| (topics, event))
+        })
+        .expect("liquidation event not emitted");
+    assert_eq!(topics.len(), 3);
+    let topic = Symbol::try_from_val(&env, &topics.get_unchecked(0)).unwrap();
+    assert_eq!(topic, Symbol::new(&env, "liquidation_event"));
+    let topic_borrower = Address::try_from_val(&env, &topics.get_unchecked(1)).unwrap();
+    assert_eq!(topic_borrower, borrower);
+    let topic_collateral = Option::<Address>::try_from_val(&env, &topics.get_unchecked(2)).unwrap();
+    assert_eq!(topic_collateral, Some(collateral));
+
+    assert_eq!(decoded.liquidator, liquidator);
+    assert_eq!(decoded.debt_asset, Some(debt));
+    assert_eq!(decoded.debt_liquidated, debt_liquidated);
+    assert_eq!(decoded.collateral_seized, collateral_seized);
+    assert_eq!(decoded.incentive_amount, incentive_amount);
+    assert_eq!(decoded.protocol_fee, protocol_fee);
+    assert!(health_factor_before < 10_000);
+    assert_eq!(decoded.health_factor_before, health_factor_before);
+    assert_eq!(
+        decoded.health_factor_after,
+        client.get_health_factor(&borrower)
+    );
+    assert_eq!(decoded.debt_price, 100);
+    assert_eq!(decoded.collateral_price, 80);
+    assert_eq!(decoded.timestamp, env.ledger().timestamp());
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Integration: accrual deltas
+// ─────────────────────────────────────────────────────────────────────────────
+
+/// AccrueEvents published by the last invocation, with their asset topic
+fn accrue_events(env: &Env) -> soroban_sdk::Vec<(Option<Address>, TestAccrueEvent)> {
+    let mut events = soroban_sdk::Vec::new(env);
+    for (_c, topics, data) in env.events().all().iter() {
+        if Symbol::try_from_val(env, &topics.get_unchecked(0)).ok()
+            == Some(Symbol::new(env, "accrue"))
+        {
+            let asset = Option::<Address>::try_from_val(env, &topics.get_unchecked(1)).unwrap();
+            events.push_back((asset, TestAccrueEvent::try_from_val(env, &data).unwrap()));
+        }
+    }
+    events
+}
+
+/// Each accrual over elapsed time reports its deltas, chained from the previous
+/// period; a second accrual in the same ledger publishes nothing.
+#[test]
+fn test_accrue_event_reports_each_period() {
+    let (env, _contract_id, client) = setup();
+    let admin = Address::generate(&env);
+    init(&client, &admin);
+    let user = Address::generate(&env);
+    env.ledger().with_mut(|li| li.timestamp = 1_000);
+    client.deposit_collateral(&user, &None, &10_000_000);
+    client.borrow_asset(&user, &None, &4_000_000);
+
+    env.ledger().with_mut(|li| li.timestamp += 86_400);
+    let debt_before = client.get_user_debt(&user, &None);
+    let accrual = client.accrue_interest(&None);
+    let events = accrue_events(&env);
+    assert_eq!(events.len(), 1);
+    let (asset, first) = events.get_unchecked(0);
+    assert_eq!(asset, None);
+    assert_eq!(first.elapsed_secs, 86_400);
+    assert_eq!(first.borrow_index_before, crate::borrow::BORROW_INDEX_SCALE);
+    assert_eq!(first.borrow_index_after, accrual.borrow_index);
+    assert_eq!(
+        first.supply_index_before,
+        crate::deposit::EXCHANGE_RATE_SCALE
+    );
+    assert_eq!(first.supply_index_after, accrual.supply_index);
+    assert_eq!(first.reserve_portion, accrual.reserves_accrued);
+    assert_eq!(first.timestamp, 1_000 + 86_400);
+    assert!(first.interest_accrued > 0);
+    assert!(first.supply_index_after > first.supply_index_before);
+    // 10% reserve factor
+    assert!((first.reserve_portion - first.interest_accrued / 10).abs() <= 1);
+    assert!((debt_before - 4_000_000 - first.interest_accrued).abs() <= 1);
+
+    // Nothing elapsed since: no event
+    client.accrue_interest(&None);
+    assert!(accrue_events(&env).is_empty());
+
+    env.ledger().with_mut(|li| li.timestamp += 2 * 86_400);
+    client.accrue_interest(&None);
+    let events = accrue_events(&env);
+    assert_eq!(events.len(), 1);
+    let (_asset, second) = events.get_unchecked(0);
+    assert_eq!(second.elapsed_secs, 2 * 86_400);
+    assert_eq!(second.borrow_index_before, first.borrow_index_after);
+    assert_eq!(second.supply_index_before, first.supply_index_after);
+    assert!(second.borrow_index_after > second.borrow_index_before);
+    assert!(second.interest_accrued > first.interest_accrued);
+}
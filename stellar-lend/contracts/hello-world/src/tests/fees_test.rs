@@ -1,4 +1,5 @@
-use crate::{HelloContract, HelloContractClient, deposit::DepositDataKey, deposit::AssetParams};
+use crate::{HelloContract, HelloContractClient, deposit::DepositDataKey, deposit::AssetParams, deposit::DepositError};
+use crate::tests::test_helpers::{asset_params, set_oracle_price};
 use soroban_sdk::{testutils::{Address as _, Ledger}, Address, Env};
 
 /// Helper function to create a test environment
@@ -23,10 +24,8 @@ fn test_borrow_fee_collection() {
     // Setup asset params with 2% borrow fee (200 bps)
     env.as_contract(&contract_id, || {
         let params = AssetParams {
-            deposit_enabled: true,
-            collateral_factor: 7000,
-            max_deposit: 0,
             borrow_fee_bps: 200,
+            ..asset_params(7000)
         };
         env.storage().persistent().set(&DepositDataKey::AssetParams(asset.clone()), &params);
         
@@ -39,6 +38,7 @@ fn test_borrow_fee_collection() {
         env.storage().persistent().set(&DepositDataKey::Position(user.clone()), &position);
         env.storage().persistent().set(&DepositDataKey::CollateralBalance(user.clone()), &10000i128);
     });
+    set_oracle_price(&client, &admin, &asset, &100, &7, &admin);
     
     client.borrow_asset(&user, &Some(asset.clone()), &1000);
     
@@ -59,12 +59,7 @@ fn test_interest_reserve_factor() {
     client.initialize(&admin);
     
     env.as_contract(&contract_id, || {
-        let params = AssetParams {
-            deposit_enabled: true,
-            collateral_factor: 7000,
-            max_deposit: 0,
-            borrow_fee_bps: 0,
-        };
+        let params = asset_params(7000);
         env.storage().persistent().set(&DepositDataKey::AssetParams(asset.clone()), &params);
         
         let position = crate::deposit::Position {
@@ -107,3 +102,51 @@ fn test_admin_claim_reserves() {
     
     assert_eq!(client.get_reserve_balance(&Some(asset.clone())), 300);
 }
+
+#[test]
+fn test_donate_to_reserves_then_claim() {
+    let env = create_test_env();
+    let contract_id = env.register(HelloContract, ());
+    let client = HelloContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let donor = Address::generate(&env);
+    let treasury = Address::generate(&env);
+    client.initialize(&admin);
+
+    let token = env.register_stellar_asset_contract(admin.clone());
+    let token_client = soroban_sdk::token::Client::new(&env, &token);
+    soroban_sdk::token::StellarAssetClient::new(&env, &token).mint(&donor, &1000);
+    token_client.approve(&donor, &contract_id, &1000, &(env.ledger().sequence() + 100));
+
+    let reserve = client.donate_to_reserves(&donor, &Some(token.clone()), &400);
+    assert_eq!(reserve, 400);
+    assert_eq!(client.get_reserve_balance(&Some(token.clone())), 400);
+    assert_eq!(token_client.balance(&donor), 600);
+    assert_eq!(token_client.balance(&contract_id), 400);
+
+    // Donations stack on top of existing reserve income
+    client.donate_to_reserves(&donor, &Some(token.clone()), &100);
+    assert_eq!(client.get_reserve_balance(&Some(token.clone())), 500);
+
+    client.claim_reserves(&admin, &Some(token.clone()), &treasury, &500);
+    assert_eq!(client.get_reserve_balance(&Some(token.clone())), 0);
+}
+
+#[test]
+fn test_donate_to_reserves_rejects_non_positive_amount() {
+    let env = create_test_env();
+    let contract_id = env.register(HelloContract, ());
+    let client = HelloContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let donor = Address::generate(&env);
+    client.initialize(&admin);
+    let token = env.register_stellar_asset_contract(admin.clone());
+
+    let result = client.try_donate_to_reserves(&donor, &Some(token.clone()), &0);
+    assert_eq!(result, Err(Ok(DepositError::InvalidAmount)));
+    let result = client.try_donate_to_reserves(&donor, &Some(token.clone()), &-5);
+    assert_eq!(result, Err(Ok(DepositError::InvalidAmount)));
+    assert_eq!(client.get_reserve_balance(&Some(token)), 0);
+}
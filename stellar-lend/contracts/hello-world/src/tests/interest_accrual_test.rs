@@ -1,10 +1,18 @@
 //! # Interest Accrual and Index Tests (#310)
 //!
 //! Tests for interest accrual, index updates, and consistency.
-//! Covers accrual over time, zero principal/zero time, rate used in accrual.
+//! Covers accrual over time, zero principal/zero time, rate used in accrual,
+//! explicit `accrue_interest` calls against closed-form expectations, rounding
+//! drift between many short accruals and one long one, the split of borrow
+//! interest between suppliers and the protocol reserve, previews of pending
+//! reserves, the rate history each accrual records, and the checkpoint every
+//! rate change accrues before it applies.
 
 use crate::deposit::{DepositDataKey, ProtocolAnalytics};
-use crate::interest_rate::{calculate_accrued_interest, get_interest_rate_config};
+use crate::interest_rate::{
+    calculate_accrued_debt, calculate_accrued_interest, get_interest_rate_config, InterestAccrual,
+    InterestRateDataKey, InterestRateError, RateHistoryEntry, RateStrategy,
+};
 use crate::{HelloContract, HelloContractClient};
 use soroban_sdk::{testutils::Address as _, testutils::Ledger, Address, Env};
 
@@ -97,6 +105,30 @@ fn test_accrued_interest_partial_year() {
     });
 }
 
+#[test]
+fn test_accrued_debt_rounds_up_and_credit_rounds_down() {
+    // Ten days at 10%: 1_000_000 * 10% * 10 / 365 = 2_739.7
+    let ten_days = 10 * 86_400;
+    assert_eq!(
+        calculate_accrued_interest(1_000_000, 0, ten_days, 1000).unwrap(),
+        2_739
+    );
+    assert_eq!(
+        calculate_accrued_debt(1_000_000, 0, ten_days, 1000).unwrap(),
+        2_740
+    );
+
+    // One second on a small balance still owes a unit, but credits nothing
+    assert_eq!(calculate_accrued_interest(1_000, 0, 1, 1000).unwrap(), 0);
+    assert_eq!(calculate_accrued_debt(1_000, 0, 1, 1000).unwrap(), 1);
+
+    // Exact amounts are not rounded either way
+    assert_eq!(
+        calculate_accrued_debt(100_000, 0, SECONDS_PER_YEAR, 500).unwrap(),
+        5_000
+    );
+}
+
 // =============================================================================
 // Accrual over time via repay flow
 // =============================================================================
@@ -187,7 +219,412 @@ fn test_accrual_index_consistency_after_config_update() {
         &None,
         &None,
         &None,
+        &None,
+        &false,
     );
     let rate_after = client.get_borrow_rate();
     assert!(rate_after >= rate_before || rate_after >= 0);
 }
+
+// =============================================================================
+// Per-asset borrow index
+// =============================================================================
+
+/// Interest owed on `principal` between two borrow index values, rounded up
+fn indexed_interest(principal: i128, index_from: i128, index_to: i128) -> i128 {
+    (principal * (index_to - index_from) + index_from - 1) / index_from
+}
+
+#[test]
+fn test_borrow_index_tracks_entry_time_and_rate_per_borrower() {
+    let (env, contract_id, client, _admin, early, native_asset) =
+        crate::tests::test_helpers::setup_env_with_native_asset();
+    let late = Address::generate(&env);
+    let token_client = soroban_sdk::token::StellarAssetClient::new(&env, &native_asset);
+    for borrower in [&early, &late] {
+        token_client.mint(borrower, &100_000);
+        token_client.approve(
+            borrower,
+            &contract_id,
+            &100_000,
+            &(env.ledger().sequence() + 100),
+        );
+    }
+    client.deposit_collateral(&early, &None, &100_000);
+    client.deposit_collateral(&late, &None, &100_000);
+
+    client.borrow_asset(&early, &None, &10_000);
+    let index_early_entry = client.get_borrow_index(&None);
+    let rate_before = client.get_borrow_rate();
+
+    env.ledger()
+        .with_mut(|li| li.timestamp += SECONDS_PER_YEAR / 2);
+    client.borrow_asset(&late, &None, &60_000);
+    let index_late_entry = client.get_borrow_index(&None);
+    assert!(index_late_entry > index_early_entry);
+    // The second borrow raised utilization, so the rest of the year accrues faster
+    assert!(client.get_borrow_rate() > rate_before);
+
+    env.ledger()
+        .with_mut(|li| li.timestamp += SECONDS_PER_YEAR / 2);
+    let index_now = client.get_borrow_index(&None);
+
+    let (_, early_interest, _) = client.repay_debt(&early, &None, &100_000);
+    let (_, late_interest, late_principal) = client.repay_debt(&late, &None, &10_000);
+    assert_eq!(
+        early_interest,
+        indexed_interest(10_000, index_early_entry, index_now)
+    );
+    let late_owed = indexed_interest(60_000, index_late_entry, index_now);
+    assert_eq!(late_interest, late_owed);
+    assert_eq!(late_principal, 10_000 - late_owed);
+
+    // Per unit borrowed, the early borrower paid for the low-rate half year too
+    assert!(early_interest * 60_000 > late_interest * 10_000);
+
+    // After a partial repay the remaining principal accrues from the repayment onwards
+    env.ledger()
+        .with_mut(|li| li.timestamp += SECONDS_PER_YEAR / 4);
+    let index_later = client.get_borrow_index(&None);
+    let remaining = 60_000 - late_principal;
+    let (_, interest_after_partial, _) = client.repay_debt(&late, &None, &100_000);
+    assert_eq!(
+        interest_after_partial,
+        indexed_interest(remaining, index_now, index_later)
+    );
+}
+
+// =============================================================================
+// Explicit accrual
+// =============================================================================
+
+/// `user` deposits 100_000 native and borrows 40_000 of it
+fn setup_native_borrow() -> (Env, HelloContractClient<'static>, Address) {
+    setup_native_borrow_of(100_000, 40_000)
+}
+
+/// `user` deposits `deposit` native and borrows `borrow` of it
+fn setup_native_borrow_of(
+    deposit: i128,
+    borrow: i128,
+) -> (Env, HelloContractClient<'static>, Address) {
+    let (env, contract_id, client, _admin, user, native_asset) =
+        crate::tests::test_helpers::setup_env_with_native_asset();
+    let token_client = soroban_sdk::token::StellarAssetClient::new(&env, &native_asset);
+    token_client.mint(&user, &deposit);
+    token_client.approve(
+        &user,
+        &contract_id,
+        &(deposit * 2),
+        &(env.ledger().sequence() + 100),
+    );
+    client.deposit_collateral(&user, &None, &deposit);
+    client.borrow_asset(&user, &None, &borrow);
+    (env, client, user)
+}
+
+/// `index` grown by `rate_bps` a year over `elapsed` seconds
+fn grown(index: i128, rate_bps: i128, elapsed: u64) -> i128 {
+    index + index * rate_bps * elapsed as i128 / (10_000 * SECONDS_PER_YEAR as i128)
+}
+
+#[test]
+fn test_accrue_interest_compounds_indexes_over_time() {
+    let (env, client, _user) = setup_native_borrow();
+    let start = client.accrue_interest(&None);
+    let reserve_before = client.get_reserve_balance(&None);
+    let borrow_rate = client.get_borrow_rate();
+    let supply_rate = client.get_supply_rate();
+    assert!(borrow_rate > 0);
+
+    let elapsed = SECONDS_PER_YEAR / 4;
+    env.ledger().with_mut(|li| li.timestamp += elapsed);
+    let accrual = client.accrue_interest(&None);
+    assert_eq!(accrual.last_accrual, env.ledger().timestamp());
+
+    let expected_borrow_index = grown(start.borrow_index, borrow_rate, elapsed);
+    assert!((accrual.borrow_index - expected_borrow_index).abs() <= 1);
+    assert_eq!(client.get_borrow_index(&None), accrual.borrow_index);
+
+    // The pool's underlying is rounded to whole units, so allow 0.01%
+    let expected_supply_index = grown(start.supply_index, supply_rate, elapsed);
+    assert!((accrual.supply_index - expected_supply_index).abs() <= start.supply_index / 10_000);
+    assert!(accrual.supply_index > start.supply_index);
+
+    // 10% of the interest on the 40_000 borrowed is credited to reserves
+    let interest = 40_000 * (accrual.borrow_index - start.borrow_index) / start.borrow_index;
+    let expected_reserves = interest * client.get_reserve_factor() / 10_000;
+    assert!((accrual.reserves_accrued - expected_reserves).abs() <= 1);
+    assert!(accrual.reserves_accrued > 0);
+    assert_eq!(
+        client.get_reserve_balance(&None),
+        reserve_before + accrual.reserves_accrued
+    );
+}
+
+#[test]
+fn test_accrue_interest_without_elapsed_time_is_noop() {
+    let (env, client, _user) = setup_native_borrow();
+    env.ledger()
+        .with_mut(|li| li.timestamp += SECONDS_PER_YEAR / 2);
+
+    let first = client.accrue_interest(&None);
+    let reserve = client.get_reserve_balance(&None);
+    let second = client.accrue_interest(&None);
+    assert!(first.reserves_accrued > 0);
+    assert_eq!(
+        second,
+        InterestAccrual {
+            reserves_accrued: 0,
+            ..first.clone()
+        }
+    );
+    assert_eq!(client.get_borrow_index(&None), first.borrow_index);
+    assert_eq!(client.get_reserve_balance(&None), reserve);
+}
+
+#[test]
+fn test_operations_accrue_before_moving_balances() {
+    let (env, client, user) = setup_native_borrow();
+    let reserve_start = client.get_reserve_balance(&None);
+
+    // A deposit after time has passed credits the reserve share of the interest
+    env.ledger()
+        .with_mut(|li| li.timestamp += SECONDS_PER_YEAR / 2);
+    client.deposit_collateral(&user, &None, &1);
+    let reserve_after_deposit = client.get_reserve_balance(&None);
+    assert!(reserve_after_deposit > reserve_start);
+
+    // Repaying the interest in the same ledger does not credit it again
+    client.repay_debt(&user, &None, &10_000);
+    assert_eq!(client.get_reserve_balance(&None), reserve_after_deposit);
+}
+
+#[test]
+fn test_supply_interest_and_reserves_add_up_to_borrow_interest() {
+    let (env, client, user) = setup_native_borrow();
+    let debt_before = client.get_user_debt(&user, &None);
+    let deposit_before = client.get_user_deposit(&user, &None);
+    let reserve_before = client.get_reserve_balance(&None);
+
+    // 40% utilization: borrowers pay 11%, suppliers earn 11% * 40% * 90% = 3.96%
+    assert_eq!(client.get_borrow_rate(), 1100);
+    assert_eq!(client.get_supply_rate(), 396);
+
+    env.ledger().with_mut(|li| li.timestamp += SECONDS_PER_YEAR);
+    client.accrue_interest(&None);
+
+    let borrow_interest = client.get_user_debt(&user, &None) - debt_before;
+    let supply_interest = client.get_user_deposit(&user, &None) - deposit_before;
+    let reserve_growth = client.get_reserve_balance(&None) - reserve_before;
+    assert_eq!(borrow_interest, 4_400);
+    assert_eq!(reserve_growth, 440);
+    assert_eq!(supply_interest + reserve_growth, borrow_interest);
+}
+
+#[test]
+fn test_one_second_accruals_match_single_long_accrual() {
+    // At 40% utilization each second accrues about 0.01 units to suppliers and
+    // 0.001 to reserves, so per-step truncation would lose all of it
+    const STEPS: u64 = 1_000;
+    let (stepped_env, stepped, stepped_user) = setup_native_borrow_of(10_000_000, 4_000_000);
+    let (single_env, single, single_user) = setup_native_borrow_of(10_000_000, 4_000_000);
+    let reserve_start = single.get_reserve_balance(&None);
+    stepped.accrue_interest(&None);
+    single.accrue_interest(&None);
+
+    for _ in 0..STEPS {
+        stepped_env.ledger().with_mut(|li| li.timestamp += 1);
+        stepped.accrue_interest(&None);
+    }
+    single_env.ledger().with_mut(|li| li.timestamp += STEPS);
+    single.accrue_interest(&None);
+
+    let single_debt = single.get_user_debt(&single_user, &None);
+    let single_deposit = single.get_user_deposit(&single_user, &None);
+    let single_reserve = single.get_reserve_balance(&None);
+    assert!(single_debt > 4_000_000);
+    assert!(single_deposit > 10_000_000);
+    assert!(single_reserve > reserve_start);
+
+    let stepped_debt = stepped.get_user_debt(&stepped_user, &None);
+    let stepped_deposit = stepped.get_user_deposit(&stepped_user, &None);
+    let stepped_reserve = stepped.get_reserve_balance(&None);
+    assert!((stepped_debt - single_debt).abs() <= 1);
+    assert!((stepped_deposit - single_deposit).abs() <= 1);
+    assert!((stepped_reserve - single_reserve).abs() <= 1);
+}
+
+#[test]
+fn test_pending_reserves_predict_next_accrual() {
+    let (env, client, _user) = setup_native_borrow();
+    client.accrue_interest(&None);
+    let reserve_before = client.get_reserve_balance(&None);
+    assert_eq!(client.get_pending_reserves(&None), 0);
+    assert_eq!(client.get_total_reserves(&None), reserve_before);
+
+    env.ledger()
+        .with_mut(|li| li.timestamp += SECONDS_PER_YEAR / 3);
+    let pending = client.get_pending_reserves(&None);
+    let total = client.get_total_reserves(&None);
+    assert!(pending > 0);
+    assert_eq!(total, reserve_before + pending);
+    // Previewing writes nothing
+    assert_eq!(client.get_reserve_balance(&None), reserve_before);
+
+    let accrual = client.accrue_interest(&None);
+    assert_eq!(accrual.reserves_accrued, pending);
+    assert_eq!(client.get_reserve_balance(&None), total);
+    assert_eq!(client.get_pending_reserves(&None), 0);
+    assert_eq!(client.get_total_reserves(&None), total);
+}
+
+// =============================================================================
+// Rate history
+// =============================================================================
+
+#[test]
+fn test_rate_history_evicts_oldest_and_lists_newest_first() {
+    let env = create_test_env();
+    let (contract_id, admin, client) = setup_contract_with_admin(&env);
+    client.set_rate_history_capacity(&admin, &3);
+    set_protocol_analytics(&env, &contract_id, 10_000, 4_000);
+
+    // Five accruals at 40% utilization; a repeat in the same ledger records nothing
+    for timestamp in [1_000, 2_000, 3_000, 4_000, 5_000] {
+        env.ledger().with_mut(|li| li.timestamp = timestamp);
+        client.accrue_interest(&None);
+    }
+    client.accrue_interest(&None);
+
+    // A sixth at 80% utilization
+    set_protocol_analytics(&env, &contract_id, 10_000, 8_000);
+    env.ledger().with_mut(|li| li.timestamp = 6_000);
+    client.accrue_interest(&None);
+
+    let history = client.get_rate_history(&None, &10, &0);
+    assert_eq!(history.len(), 3);
+    assert_eq!(
+        history.get(0).unwrap(),
+        RateHistoryEntry {
+            timestamp: 6_000,
+            utilization_bps: 8_000,
+            borrow_rate_bps: 2_100,
+            supply_rate_bps: 1_512,
+        }
+    );
+    assert_eq!(
+        history.get(1).unwrap(),
+        RateHistoryEntry {
+            timestamp: 5_000,
+            utilization_bps: 4_000,
+            borrow_rate_bps: 1_100,
+            supply_rate_bps: 396,
+        }
+    );
+    assert_eq!(history.get(2).unwrap().timestamp, 4_000);
+
+    let page = client.get_rate_history(&None, &2, &1);
+    assert_eq!(page.len(), 2);
+    assert_eq!(page.get(0).unwrap().timestamp, 5_000);
+    assert_eq!(page.get(1).unwrap().timestamp, 4_000);
+    assert_eq!(client.get_rate_history(&None, &10, &3).len(), 0);
+
+    // Evicted entries are removed from storage, not just hidden
+    env.as_contract(&contract_id, || {
+        let storage = env.storage().persistent();
+        assert!(!storage.has(&InterestRateDataKey::RateHistory(None, 3)));
+        assert!(storage.has(&InterestRateDataKey::RateHistory(None, 4)));
+    });
+}
+
+#[test]
+fn test_rate_history_capacity_requires_admin_and_bounds() {
+    let env = create_test_env();
+    let (_contract_id, admin, client) = setup_contract_with_admin(&env);
+    assert_eq!(client.get_rate_history_capacity(), 100);
+
+    let user = Address::generate(&env);
+    let result = client.try_set_rate_history_capacity(&user, &10);
+    assert_eq!(result, Err(Ok(InterestRateError::Unauthorized)));
+    let result = client.try_set_rate_history_capacity(&admin, &0);
+    assert_eq!(result, Err(Ok(InterestRateError::InvalidParameter)));
+    let result = client.try_set_rate_history_capacity(&admin, &1_001);
+    assert_eq!(result, Err(Ok(InterestRateError::InvalidParameter)));
+
+    client.set_rate_history_capacity(&admin, &1_000);
+    assert_eq!(client.get_rate_history_capacity(), 1_000);
+}
+
+// =============================================================================
+// Checkpoints on rate changes
+// =============================================================================
+
+/// `user` owes 1_000_000 native at 10% utilization, 3.5% on the default model
+fn setup_checkpointed_borrow() -> (Env, HelloContractClient<'static>, Address, Address) {
+    let (env, contract_id, client, admin, user, native_asset) =
+        crate::tests::test_helpers::setup_env_with_native_asset();
+    let token_client = soroban_sdk::token::StellarAssetClient::new(&env, &native_asset);
+    token_client.mint(&user, &10_000_000);
+    token_client.approve(
+        &user,
+        &contract_id,
+        &10_000_000,
+        &(env.ledger().sequence() + 100),
+    );
+    client.deposit_collateral(&user, &None, &10_000_000);
+    client.borrow_asset(&user, &None, &1_000_000);
+    (env, client, admin, user)
+}
+
+/// Regression: a config update used to reprice the interest elapsed since the
+/// last accrual at the new rate
+#[test]
+fn test_config_update_does_not_reprice_elapsed_interest() {
+    let (env, client, admin, user) = setup_checkpointed_borrow();
+    let start = client.get_borrow_index(&None);
+    assert_eq!(client.get_borrow_rate(), 350);
+
+    env.ledger().with_mut(|li| li.timestamp += SECONDS_PER_YEAR);
+    client.update_interest_rate_config(
+        &admin,
+        &Some(5000),
+        &None,
+        &None,
+        &None,
+        &None,
+        &None,
+        &None,
+        &None,
+        &false,
+    );
+    assert_eq!(client.get_borrow_rate(), 5250);
+    let config = client.get_interest_rate_config(&None);
+    assert_eq!(config.last_update, env.ledger().timestamp());
+
+    // Materialized right after the change, the whole year is owed at 3.5%
+    let index = grown(start, 350, SECONDS_PER_YEAR);
+    assert!((client.get_borrow_index(&None) - index).abs() <= 1);
+    let interest = indexed_interest(1_000_000, start, index);
+    assert!((client.get_user_debt(&user, &None) - 1_000_000 - interest).abs() <= 1);
+}
+
+#[test]
+fn test_emergency_adjustment_and_strategy_change_checkpoint_accrual() {
+    let (env, client, admin, _user) = setup_checkpointed_borrow();
+    let start = client.get_borrow_index(&None);
+
+    env.ledger().with_mut(|li| li.timestamp += SECONDS_PER_YEAR);
+    client.set_emergency_rate_adjustment(&admin, &5000, &(7 * 86_400));
+    let index = client.get_borrow_index(&None);
+    assert!((index - grown(start, 350, SECONDS_PER_YEAR)).abs() <= 1);
+    assert_eq!(client.get_borrow_rate(), 5350);
+
+    // The day since the adjustment is priced with it, not at the new strategy
+    env.ledger().with_mut(|li| li.timestamp += 86_400);
+    client.set_rate_strategy(&admin, &None, &RateStrategy::Fixed(9000));
+    assert!((client.get_borrow_index(&None) - grown(index, 5350, 86_400)).abs() <= 1);
+    assert_eq!(client.get_borrow_rate(), 10_000);
+    let config = client.get_interest_rate_config(&None);
+    assert_eq!(config.last_update, env.ledger().timestamp());
+}
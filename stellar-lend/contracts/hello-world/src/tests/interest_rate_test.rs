@@ -5,23 +5,45 @@
 //! - Utilization-based rate calculations
 //! - Rate behavior below and above kink
 //! - Rate floor and ceiling enforcement
-//! - Emergency rate adjustments
-//! - Configuration updates
+//! - Emergency rate adjustments, their expiry and bounds
+//! - Configuration updates, phased in at the maximum rate delta per day or forced
+//! - Linear, kinked and fixed rate strategies at identical utilizations
+//! - APY conversion against a floating point reference
+//! - The utilization-spike circuit breaker and its release hysteresis
 //! - Edge cases (0%, 100% utilization)
 //! - Security and authorization scenarios
 
-use crate::deposit::{DepositDataKey, ProtocolAnalytics};
+extern crate std;
+
+use crate::deposit::{AssetTotals, DepositDataKey, ProtocolAnalytics};
 use crate::interest_rate::{
-    calculate_accrued_interest, get_interest_rate_config, InterestRateConfig,
+    annual_percentage_yield, calculate_accrued_interest, get_interest_rate_config,
+    EmergencyRateAdjustment, InterestRateConfig, InterestRateError, RateStrategy,
 };
 use crate::{HelloContract, HelloContractClient};
-use soroban_sdk::{testutils::Address as _, Address, Env};
+use soroban_sdk::{
+    contracttype,
+    testutils::{Address as _, Events, Ledger},
+    Address, Env, Symbol, TryFromVal,
+};
 
 // =============================================================================
 // CONSTANTS
 // =============================================================================
 
 const SECONDS_PER_YEAR: u64 = 365 * 86400;
+const DAY: u64 = 86400;
+
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct TestRateConfigForcedEvent {
+    pub admin: Address,
+    pub utilization_bps: i128,
+    pub previous_rate_bps: i128,
+    pub new_rate_bps: i128,
+    pub max_rate_delta_bps_per_day: i128,
+    pub timestamp: u64,
+}
 
 // =============================================================================
 // HELPER FUNCTIONS
@@ -64,6 +86,26 @@ fn set_protocol_analytics(
     });
 }
 
+/// Helper to set the supplied and borrowed totals of a single asset
+fn set_asset_totals(
+    env: &Env,
+    contract_id: &Address,
+    asset: &Option<Address>,
+    total_supplied: i128,
+    total_borrowed: i128,
+) {
+    env.as_contract(contract_id, || {
+        let totals = AssetTotals {
+            total_supplied,
+            total_borrowed,
+            total_reserves: 0,
+        };
+        env.storage()
+            .persistent()
+            .set(&DepositDataKey::AssetTotals(asset.clone()), &totals);
+    });
+}
+
 /// Helper to get interest rate config from storage
 fn get_config(env: &Env, contract_id: &Address) -> Option<InterestRateConfig> {
     env.as_contract(contract_id, || get_interest_rate_config(env))
@@ -80,7 +122,7 @@ fn test_utilization_zero_borrows() {
     let (contract_id, _admin, client) = setup_contract_with_admin(&env);
 
     // Set deposits but no borrows
-    set_protocol_analytics(&env, &contract_id, 10000, 0);
+    set_asset_totals(&env, &contract_id, &None, 10000, 0);
 
     let utilization = client.get_utilization();
     assert_eq!(utilization, 0);
@@ -93,7 +135,7 @@ fn test_utilization_fifty_percent() {
     let (contract_id, _admin, client) = setup_contract_with_admin(&env);
 
     // Set 50% utilization
-    set_protocol_analytics(&env, &contract_id, 10000, 5000);
+    set_asset_totals(&env, &contract_id, &None, 10000, 5000);
 
     let utilization = client.get_utilization();
     assert_eq!(utilization, 5000); // 50% = 5000 basis points
@@ -106,7 +148,7 @@ fn test_utilization_at_kink() {
     let (contract_id, _admin, client) = setup_contract_with_admin(&env);
 
     // Set 80% utilization (default kink)
-    set_protocol_analytics(&env, &contract_id, 10000, 8000);
+    set_asset_totals(&env, &contract_id, &None, 10000, 8000);
 
     let utilization = client.get_utilization();
     assert_eq!(utilization, 8000); // 80% = 8000 basis points
@@ -119,7 +161,7 @@ fn test_utilization_full() {
     let (contract_id, _admin, client) = setup_contract_with_admin(&env);
 
     // Set 100% utilization
-    set_protocol_analytics(&env, &contract_id, 10000, 10000);
+    set_asset_totals(&env, &contract_id, &None, 10000, 10000);
 
     let utilization = client.get_utilization();
     assert_eq!(utilization, 10000); // 100% = 10000 basis points
@@ -132,7 +174,7 @@ fn test_utilization_capped_at_100() {
     let (contract_id, _admin, client) = setup_contract_with_admin(&env);
 
     // Set more borrows than deposits (shouldn't happen normally)
-    set_protocol_analytics(&env, &contract_id, 10000, 15000);
+    set_asset_totals(&env, &contract_id, &None, 10000, 15000);
 
     let utilization = client.get_utilization();
     assert_eq!(utilization, 10000); // Capped at 100%
@@ -144,13 +186,41 @@ fn test_utilization_no_deposits() {
     let env = create_test_env();
     let (contract_id, _admin, client) = setup_contract_with_admin(&env);
 
-    // No deposits
-    set_protocol_analytics(&env, &contract_id, 0, 0);
+    // Nothing supplied, even with borrows recorded, must not divide by zero
+    set_asset_totals(&env, &contract_id, &None, 0, 5000);
 
     let utilization = client.get_utilization();
     assert_eq!(utilization, 0);
 }
 
+/// Test utilization is computed from each asset's own totals
+#[test]
+fn test_utilization_per_asset() {
+    let env = create_test_env();
+    let (contract_id, _admin, client) = setup_contract_with_admin(&env);
+    let token_a = Some(Address::generate(&env));
+    let token_b = Some(Address::generate(&env));
+
+    set_asset_totals(&env, &contract_id, &token_a, 10000, 2500);
+    set_asset_totals(&env, &contract_id, &token_b, 3000, 3000);
+    set_asset_totals(&env, &contract_id, &None, 10000, 6000);
+
+    assert_eq!(client.get_asset_utilization(&token_a), 2500);
+    assert_eq!(client.get_asset_utilization(&token_b), 10000);
+    assert_eq!(client.get_asset_utilization(&None), 6000);
+    // The parameterless view reports the native asset
+    assert_eq!(client.get_utilization(), 6000);
+
+    // Protocol-wide analytics do not leak into per-asset utilization
+    set_protocol_analytics(&env, &contract_id, 10000, 9000);
+    assert_eq!(client.get_asset_utilization(&token_a), 2500);
+    assert_eq!(client.get_utilization(), 6000);
+
+    // An asset nobody has supplied reports 0%
+    let token_c = Some(Address::generate(&env));
+    assert_eq!(client.get_asset_utilization(&token_c), 0);
+}
+
 // =============================================================================
 // BORROW RATE CALCULATION TESTS
 // =============================================================================
@@ -251,25 +321,26 @@ fn test_supply_rate_calculation() {
     let borrow_rate = client.get_borrow_rate();
     let supply_rate = client.get_supply_rate();
 
-    // Supply rate = borrow_rate - spread (200 bps = 2%)
-    // Expected: 1100 - 200 = 900 bps (9%)
-    assert_eq!(supply_rate, borrow_rate - 200);
-    assert_eq!(supply_rate, 900);
+    // Supply rate = borrow_rate * utilization * (1 - reserve factor)
+    // Expected: 1100 * 40% * 90% = 396 bps
+    assert_eq!(borrow_rate, 1100);
+    assert_eq!(supply_rate, 396);
 }
 
-/// Test supply rate at low utilization respects floor
+/// Test supply rate falls to zero with utilization, with no floor
 #[test]
-fn test_supply_rate_floor_enforcement() {
+fn test_supply_rate_follows_utilization_without_floor() {
     let env = create_test_env();
     let (contract_id, _admin, client) = setup_contract_with_admin(&env);
 
-    // Very low utilization
+    // 1% utilization: 125 * 1% * 90% rounds down to 1 bps
     set_protocol_analytics(&env, &contract_id, 10000, 100);
+    assert_eq!(client.get_supply_rate(), 1);
 
-    let supply_rate = client.get_supply_rate();
-
-    // Supply rate should not go below floor (50 bps)
-    assert!(supply_rate >= 50);
+    // Nothing is borrowed, so suppliers earn nothing
+    set_protocol_analytics(&env, &contract_id, 10000, 0);
+    assert_eq!(client.get_borrow_rate(), 100);
+    assert_eq!(client.get_supply_rate(), 0);
 }
 
 // =============================================================================
@@ -295,6 +366,8 @@ fn test_rate_floor_enforcement() {
         &Some(100), // Floor: 1%
         &None,
         &None,
+        &None,
+        &false,
     );
 
     let borrow_rate = client.get_borrow_rate();
@@ -319,6 +392,43 @@ fn test_rate_ceiling_enforcement() {
     assert_eq!(borrow_rate, 10000);
 }
 
+/// The curve preview at 0, the kink and 100% utilization, clamped to the ceiling
+#[test]
+fn test_rate_at_utilization_boundaries() {
+    let env = create_test_env();
+    let (_contract_id, _admin, client) = setup_contract_with_admin(&env);
+
+    // Base rate at 0%; with nothing borrowed suppliers earn nothing
+    assert_eq!(client.get_rate_at_utilization(&None, &0), (100, 0));
+    // Base rate plus the full multiplier at the 80% kink
+    assert_eq!(client.get_rate_at_utilization(&None, &8000), (2100, 1512));
+    // 12_100 at 100% is clamped to the 100% ceiling
+    assert_eq!(
+        client.get_rate_at_utilization(&None, &10_000),
+        (10_000, 9000)
+    );
+    // Utilization outside 0-100% is clamped too
+    assert_eq!(
+        client.get_rate_at_utilization(&None, &15_000),
+        (10_000, 9000)
+    );
+    assert_eq!(client.get_rate_at_utilization(&None, &-1), (100, 0));
+}
+
+/// The preview agrees with the live rates and applies the emergency adjustment
+#[test]
+fn test_rate_at_utilization_matches_live_rates() {
+    let env = create_test_env();
+    let (contract_id, admin, client) = setup_contract_with_admin(&env);
+    set_protocol_analytics(&env, &contract_id, 10000, 4000);
+    client.set_emergency_rate_adjustment(&admin, &500, &86_400);
+
+    let (borrow_rate, supply_rate) = client.get_rate_at_utilization(&None, &4000);
+    assert_eq!(borrow_rate, 1600);
+    assert_eq!(borrow_rate, client.get_borrow_rate());
+    assert_eq!(supply_rate, client.get_supply_rate());
+}
+
 // =============================================================================
 // EMERGENCY RATE ADJUSTMENT TESTS
 // =============================================================================
@@ -335,7 +445,7 @@ fn test_emergency_rate_adjustment_positive() {
     let rate_before = client.get_borrow_rate();
 
     // Apply positive emergency adjustment (500 bps = 5%)
-    client.set_emergency_rate_adjustment(&admin, &500);
+    client.set_emergency_rate_adjustment(&admin, &500, &86_400);
 
     // Get rate after adjustment
     let rate_after = client.get_borrow_rate();
@@ -356,7 +466,7 @@ fn test_emergency_rate_adjustment_negative() {
     let rate_before = client.get_borrow_rate();
 
     // Apply negative emergency adjustment (-300 bps = -3%)
-    client.set_emergency_rate_adjustment(&admin, &(-300));
+    client.set_emergency_rate_adjustment(&admin, &(-300), &86_400);
 
     // Get rate after adjustment
     let rate_after = client.get_borrow_rate();
@@ -373,7 +483,7 @@ fn test_emergency_rate_adjustment_unauthorized() {
     let (_contract_id, _admin, client) = setup_contract_with_admin(&env);
     let unauthorized = Address::generate(&env);
 
-    client.set_emergency_rate_adjustment(&unauthorized, &500);
+    client.set_emergency_rate_adjustment(&unauthorized, &500, &86_400);
 }
 
 /// Test emergency adjustment exceeds bounds
@@ -384,7 +494,105 @@ fn test_emergency_rate_adjustment_exceeds_bounds() {
     let (_contract_id, admin, client) = setup_contract_with_admin(&env);
 
     // Try to set adjustment > 100% (10000 bps)
-    client.set_emergency_rate_adjustment(&admin, &15000);
+    client.set_emergency_rate_adjustment(&admin, &15000, &86_400);
+}
+
+/// Test emergency adjustment stops applying once its duration has passed
+#[test]
+fn test_emergency_rate_adjustment_expires() {
+    let env = create_test_env();
+    let (contract_id, admin, client) = setup_contract_with_admin(&env);
+
+    set_protocol_analytics(&env, &contract_id, 10000, 4000);
+    env.ledger().with_mut(|li| li.timestamp = 1_000);
+
+    // +500 bps for one hour
+    client.set_emergency_rate_adjustment(&admin, &500, &3_600);
+    assert_eq!(
+        client.get_emergency_rate_adjustment(),
+        Some(EmergencyRateAdjustment {
+            adjustment_bps: 500,
+            expires_at: 4_600,
+        })
+    );
+    assert_eq!(client.get_borrow_rate(), 1600);
+
+    // Still active in the last second
+    env.ledger().with_mut(|li| li.timestamp = 4_599);
+    assert_eq!(client.get_borrow_rate(), 1600);
+
+    // Expired: the stored value is ignored
+    env.ledger().with_mut(|li| li.timestamp = 4_600);
+    assert_eq!(client.get_borrow_rate(), 1100);
+    assert_eq!(client.get_emergency_rate_adjustment(), None);
+    let config = get_config(&env, &contract_id).unwrap();
+    assert_eq!(config.emergency_adjustment_bps, 500);
+    assert_eq!(config.emergency_adjustment_expires_at, 4_600);
+}
+
+/// Test admin can clear the emergency adjustment early
+#[test]
+fn test_clear_emergency_rate_adjustment() {
+    let env = create_test_env();
+    let (contract_id, admin, client) = setup_contract_with_admin(&env);
+
+    set_protocol_analytics(&env, &contract_id, 10000, 4000);
+    client.set_emergency_rate_adjustment(&admin, &500, &86_400);
+    assert_eq!(client.get_borrow_rate(), 1600);
+
+    let unauthorized = Address::generate(&env);
+    let result = client.try_clear_emergency_rate_adjustment(&unauthorized);
+    assert_eq!(result, Err(Ok(InterestRateError::Unauthorized)));
+
+    client.clear_emergency_rate_adjustment(&admin);
+    assert_eq!(client.get_borrow_rate(), 1100);
+    assert_eq!(client.get_emergency_rate_adjustment(), None);
+    let config = get_config(&env, &contract_id).unwrap();
+    assert_eq!(config.emergency_adjustment_bps, 0);
+    assert_eq!(config.emergency_adjustment_expires_at, 0);
+}
+
+/// Test adjustments and durations beyond the configured limits are rejected
+#[test]
+fn test_emergency_rate_adjustment_out_of_bounds() {
+    let env = create_test_env();
+    let (_contract_id, admin, client) = setup_contract_with_admin(&env);
+
+    // Defaults: ±100% for up to 7 days
+    let limits = client.get_emergency_adjustment_limits();
+    assert_eq!(limits.max_adjustment_bps, 10_000);
+    assert_eq!(limits.max_duration_secs, 7 * 86_400);
+    let result = client.try_set_emergency_rate_adjustment(&admin, &500, &(7 * 86_400 + 1));
+    assert_eq!(result, Err(Ok(InterestRateError::InvalidParameter)));
+    let result = client.try_set_emergency_rate_adjustment(&admin, &500, &0);
+    assert_eq!(result, Err(Ok(InterestRateError::InvalidParameter)));
+
+    // Tightened to ±10% for up to a day
+    client.set_emergency_adjustment_limits(&admin, &1_000, &86_400);
+    let result = client.try_set_emergency_rate_adjustment(&admin, &1_001, &3_600);
+    assert_eq!(result, Err(Ok(InterestRateError::InvalidParameter)));
+    let result = client.try_set_emergency_rate_adjustment(&admin, &(-1_001), &3_600);
+    assert_eq!(result, Err(Ok(InterestRateError::InvalidParameter)));
+    let result = client.try_set_emergency_rate_adjustment(&admin, &1_000, &86_401);
+    assert_eq!(result, Err(Ok(InterestRateError::InvalidParameter)));
+    assert_eq!(client.get_emergency_rate_adjustment(), None);
+
+    client.set_emergency_rate_adjustment(&admin, &(-1_000), &86_400);
+    assert_eq!(
+        client
+            .get_emergency_rate_adjustment()
+            .unwrap()
+            .adjustment_bps,
+        -1_000
+    );
+
+    // The limits themselves are bounded
+    let result = client.try_set_emergency_adjustment_limits(&admin, &10_001, &86_400);
+    assert_eq!(result, Err(Ok(InterestRateError::InvalidParameter)));
+    let result = client.try_set_emergency_adjustment_limits(&admin, &1_000, &0);
+    assert_eq!(result, Err(Ok(InterestRateError::InvalidParameter)));
+    let result = client.try_set_emergency_adjustment_limits(&admin, &-1, &86_400);
+    assert_eq!(result, Err(Ok(InterestRateError::InvalidParameter)));
 }
 
 // =============================================================================
@@ -409,6 +617,8 @@ fn test_update_config_base_rate() {
         &None,
         &None,
         &None,
+        &None,
+        &false,
     );
 
     let borrow_rate = client.get_borrow_rate();
@@ -434,6 +644,8 @@ fn test_update_config_kink() {
         &None,
         &None,
         &None,
+        &None,
+        &false,
     );
 
     // Now 70% is above kink, so rate should be higher
@@ -463,6 +675,8 @@ fn test_update_config_multiplier() {
         &None,
         &None,
         &None,
+        &None,
+        &false,
     );
 
     let borrow_rate = client.get_borrow_rate();
@@ -490,6 +704,8 @@ fn test_update_config_jump_multiplier() {
         &None,
         &None,
         &None,
+        &None,
+        &false,
     );
 
     let borrow_rate = client.get_borrow_rate();
@@ -498,15 +714,15 @@ fn test_update_config_jump_multiplier() {
     assert_eq!(borrow_rate, 4600);
 }
 
-/// Test updating spread
+/// Test updating the reserve factor through the config
 #[test]
-fn test_update_config_spread() {
+fn test_update_config_reserve_factor() {
     let env = create_test_env();
     let (contract_id, admin, client) = setup_contract_with_admin(&env);
 
     set_protocol_analytics(&env, &contract_id, 10000, 4000);
 
-    // Change spread from 200 to 500 (5%)
+    // Change reserve factor from 1000 to 2000 (20%)
     client.update_interest_rate_config(
         &admin,
         &None,
@@ -515,14 +731,60 @@ fn test_update_config_spread() {
         &None,
         &None,
         &None,
-        &Some(500), // new spread
+        &None,
+        &Some(2000), // new reserve factor
+        &false,
     );
 
     let borrow_rate = client.get_borrow_rate();
     let supply_rate = client.get_supply_rate();
 
-    // Supply rate should now be borrow rate - 500
-    assert_eq!(supply_rate, borrow_rate - 500);
+    // Borrowers pay the same; suppliers keep 80% of it: 1100 * 40% * 80%
+    assert_eq!(client.get_reserve_factor(), 2000);
+    assert_eq!(borrow_rate, 1100);
+    assert_eq!(supply_rate, 352);
+}
+
+/// The config view reflects a partial update, leaving the other fields as they were
+#[test]
+fn test_get_interest_rate_config_after_partial_update() {
+    let env = create_test_env();
+    let (_contract_id, admin, client) = setup_contract_with_admin(&env);
+    let asset = Some(Address::generate(&env));
+    let before = client.get_interest_rate_config(&asset);
+    assert_eq!(before.base_rate_bps, 100);
+    assert_eq!(before.emergency_adjustment_bps, 0);
+
+    env.ledger().with_mut(|li| li.timestamp = 1_000);
+    client.update_interest_rate_config(
+        &admin,
+        &Some(200),
+        &None,
+        &Some(3000),
+        &None,
+        &None,
+        &None,
+        &Some(500),
+        &None,
+        &false,
+    );
+    env.ledger().with_mut(|li| li.timestamp = 2_000);
+    client.set_emergency_rate_adjustment(&admin, &150, &86_400);
+
+    let config = client.get_interest_rate_config(&asset);
+    assert_eq!(
+        config,
+        InterestRateConfig {
+            base_rate_bps: 200,
+            multiplier_bps: 3000,
+            spread_bps: 500,
+            emergency_adjustment_bps: 150,
+            emergency_adjustment_expires_at: 2_000 + 86_400,
+            last_update: 2_000,
+            ..before
+        }
+    );
+    assert_eq!(client.get_interest_rate_config(&None), config);
 }
 
 /// Test config update unauthorized
@@ -542,6 +804,8 @@ fn test_update_config_unauthorized() {
         &None,
         &None,
         &None,
+        &None,
+        &false,
     );
 }
 
@@ -551,7 +815,7 @@ fn test_update_config_unauthorized() {
 
 /// Test negative base rate is rejected
 #[test]
-#[should_panic(expected = "HostError")]
+#[should_panic(expected = "Error(Contract, #9)")]
 fn test_invalid_base_rate_negative() {
     let env = create_test_env();
     let (_contract_id, admin, client) = setup_contract_with_admin(&env);
@@ -565,12 +829,14 @@ fn test_invalid_base_rate_negative() {
         &None,
         &None,
         &None,
+        &None,
+        &false,
     );
 }
 
 /// Test base rate > 100% is rejected
 #[test]
-#[should_panic(expected = "HostError")]
+#[should_panic(expected = "Error(Contract, #2)")]
 fn test_invalid_base_rate_too_high() {
     let env = create_test_env();
     let (_contract_id, admin, client) = setup_contract_with_admin(&env);
@@ -584,12 +850,14 @@ fn test_invalid_base_rate_too_high() {
         &None,
         &None,
         &None,
+        &None,
+        &false,
     );
 }
 
 /// Test kink = 0 is rejected
 #[test]
-#[should_panic(expected = "HostError")]
+#[should_panic(expected = "Error(Contract, #7)")]
 fn test_invalid_kink_zero() {
     let env = create_test_env();
     let (_contract_id, admin, client) = setup_contract_with_admin(&env);
@@ -603,31 +871,35 @@ fn test_invalid_kink_zero() {
         &None,
         &None,
         &None,
+        &None,
+        &false,
     );
 }
 
-/// Test kink = 100% is rejected
+/// Test kink above 100% is rejected
 #[test]
-#[should_panic(expected = "HostError")]
-fn test_invalid_kink_100_percent() {
+#[should_panic(expected = "Error(Contract, #7)")]
+fn test_invalid_kink_above_100_percent() {
     let env = create_test_env();
     let (_contract_id, admin, client) = setup_contract_with_admin(&env);
 
     client.update_interest_rate_config(
         &admin,
         &None,
-        &Some(10000), // Invalid: kink must be < 100%
+        &Some(10001), // Invalid: kink must be <= 100%
+        &None,
         &None,
         &None,
         &None,
         &None,
         &None,
+        &false,
     );
 }
 
 /// Test negative multiplier is rejected
 #[test]
-#[should_panic(expected = "HostError")]
+#[should_panic(expected = "Error(Contract, #9)")]
 fn test_invalid_multiplier_negative() {
     let env = create_test_env();
     let (_contract_id, admin, client) = setup_contract_with_admin(&env);
@@ -641,12 +913,14 @@ fn test_invalid_multiplier_negative() {
         &None,
         &None,
         &None,
+        &None,
+        &false,
     );
 }
 
 /// Test floor > ceiling is rejected
 #[test]
-#[should_panic(expected = "HostError")]
+#[should_panic(expected = "Error(Contract, #8)")]
 fn test_invalid_floor_above_ceiling() {
     let env = create_test_env();
     let (_contract_id, admin, client) = setup_contract_with_admin(&env);
@@ -660,6 +934,119 @@ fn test_invalid_floor_above_ceiling() {
         &Some(5000), // Floor: 50%
         &Some(3000), // Ceiling: 30% - Invalid: < floor
         &None,
+        &None,
+        &false,
+    );
+}
+
+/// Test negative jump multiplier is rejected
+#[test]
+#[should_panic(expected = "Error(Contract, #9)")]
+fn test_invalid_jump_multiplier_negative() {
+    let env = create_test_env();
+    let (_contract_id, admin, client) = setup_contract_with_admin(&env);
+
+    client.update_interest_rate_config(
+        &admin,
+        &None,
+        &None,
+        &None,
+        &Some(-1), // Invalid: negative
+        &None,
+        &None,
+        &None,
+        &None,
+        &false,
+    );
+}
+
+/// Test spread above the 50% bound is rejected
+#[test]
+#[should_panic(expected = "Error(Contract, #10)")]
+fn test_invalid_spread_too_large() {
+    let env = create_test_env();
+    let (_contract_id, admin, client) = setup_contract_with_admin(&env);
+
+    client.update_interest_rate_config(
+        &admin,
+        &None,
+        &None,
+        &None,
+        &None,
+        &None,
+        &None,
+        &Some(5001), // Invalid: > MAX_SPREAD_BPS
+        &None,
+        &false,
+    );
+}
+
+/// Test a multiplier whose rate cannot be computed is rejected
+#[test]
+#[should_panic(expected = "Error(Contract, #11)")]
+fn test_invalid_multiplier_overflows_rate() {
+    let env = create_test_env();
+    let (_contract_id, admin, client) = setup_contract_with_admin(&env);
+
+    client.update_interest_rate_config(
+        &admin,
+        &None,
+        &None,
+        &Some(i128::MAX), // Invalid: utilization * multiplier overflows
+        &None,
+        &None,
+        &None,
+        &None,
+        &None,
+        &false,
+    );
+}
+
+/// Test every parameter at the edge of its valid range is accepted together
+#[test]
+fn test_config_boundary_values_accepted() {
+    let env = create_test_env();
+    let (contract_id, admin, client) = setup_contract_with_admin(&env);
+
+    client.update_interest_rate_config(
+        &admin,
+        &Some(0),     // base rate: 0%
+        &Some(10000), // kink: 100%
+        &Some(10000), // multiplier
+        &Some(0),     // jump multiplier
+        &Some(10000), // floor equal to the ceiling
+        &Some(10000), // ceiling: 100%
+        &Some(5000),  // spread: MAX_SPREAD_BPS
+        &Some(10000), // reserve factor: 100%
+        &false,
+    );
+
+    let config = get_config(&env, &contract_id).unwrap();
+    assert_eq!(config.kink_utilization_bps, 10000);
+    assert_eq!(config.rate_floor_bps, config.rate_ceiling_bps);
+    // The floor pins the rate at 100%; reserves take all of it
+    assert_eq!(client.get_rate_at_utilization(&None, &0), (10000, 0));
+    assert_eq!(client.get_rate_at_utilization(&None, &10000), (10000, 0));
+}
+
+/// Test reserve factor above 100% is rejected
+#[test]
+#[should_panic(expected = "Error(Contract, #2)")]
+fn test_invalid_reserve_factor_too_high() {
+    let env = create_test_env();
+    let (_contract_id, admin, client) = setup_contract_with_admin(&env);
+
+    client.update_interest_rate_config(
+        &admin,
+        &None,
+        &None,
+        &None,
+        &None,
+        &None,
+        &None,
+        &None,
+        &Some(10_001),
+        &false,
     );
 }
 
@@ -779,6 +1166,402 @@ fn test_rate_jump_at_kink() {
     );
 }
 
+/// Raise the base rate from 1% to 11% without forcing it
+fn raise_base_rate(client: &HelloContractClient, admin: &Address) {
+    client.update_interest_rate_config(
+        admin,
+        &Some(1100),
+        &None,
+        &None,
+        &None,
+        &None,
+        &None,
+        &None,
+        &None,
+        &false,
+    );
+}
+
+/// Test a config update is phased in at the maximum rate delta per day
+#[test]
+fn test_rate_update_phased_in_at_max_delta() {
+    let env = create_test_env();
+    let (contract_id, admin, client) = setup_contract_with_admin(&env);
+    set_protocol_analytics(&env, &contract_id, 10000, 4000);
+    client.set_max_rate_delta_per_day(&admin, &100);
+    assert_eq!(client.get_borrow_rate(), 1100);
+
+    // The target is 2100 at 40% utilization; 1000 bps at 100 a day takes ten days
+    raise_base_rate(&client, &admin);
+    assert_eq!(client.get_borrow_rate(), 1100);
+    let transition = client.get_rate_transition().unwrap();
+    assert_eq!(transition.previous.base_rate_bps, 100);
+    assert_eq!(transition.started_at, 0);
+    assert_eq!(transition.ends_at, 10 * DAY);
+
+    env.ledger().with_mut(|li| li.timestamp = DAY);
+    assert_eq!(client.get_borrow_rate(), 1200);
+    assert_eq!(client.get_rate_at_utilization(&None, &4000).0, 1200);
+
+    // Halfway through the sixth day
+    env.ledger().with_mut(|li| li.timestamp = 5 * DAY + DAY / 2);
+    assert_eq!(client.get_borrow_rate(), 1650);
+
+    // No further update until the transition completes
+    let result = client.try_update_interest_rate_config(
+        &admin,
+        &Some(100),
+        &None,
+        &None,
+        &None,
+        &None,
+        &None,
+        &None,
+        &None,
+        &false,
+    );
+    assert_eq!(result, Err(Ok(InterestRateError::RateTransitionInProgress)));
+
+    env.ledger().with_mut(|li| li.timestamp = 10 * DAY);
+    assert_eq!(client.get_borrow_rate(), 2100);
+    assert!(client.get_rate_transition().is_none());
+
+    // Moving back down is limited the same way
+    client.update_interest_rate_config(
+        &admin,
+        &Some(100),
+        &None,
+        &None,
+        &None,
+        &None,
+        &None,
+        &None,
+        &None,
+        &false,
+    );
+    env.ledger().with_mut(|li| li.timestamp += 2 * DAY);
+    assert_eq!(client.get_borrow_rate(), 1900);
+}
+
+/// Test a forced update applies at once and emits an event
+#[test]
+fn test_forced_rate_update_bypasses_transition() {
+    let env = create_test_env();
+    let (contract_id, admin, client) = setup_contract_with_admin(&env);
+    set_protocol_analytics(&env, &contract_id, 10000, 4000);
+    client.set_max_rate_delta_per_day(&admin, &100);
+    raise_base_rate(&client, &admin);
+    env.ledger().with_mut(|li| li.timestamp = DAY);
+    assert_eq!(client.get_borrow_rate(), 1200);
+
+    client.update_interest_rate_config(
+        &admin,
+        &Some(600),
+        &None,
+        &None,
+        &None,
+        &None,
+        &None,
+        &None,
+        &None,
+        &true,
+    );
+    assert_eq!(client.get_borrow_rate(), 1600);
+    assert!(client.get_rate_transition().is_none());
+
+    let (topics, event) = env
+        .events()
+        .all()
+        .iter()
+        .rev()
+        .find_map(|(_c, topics, data)| {
+            TestRateConfigForcedEvent::try_from_val(&env, &data)
+                .ok()
+                .map(|event| (topics, event))
+        })
+        .expect("forced update event not emitted");
+    let topic = Symbol::try_from_val(&env, &topics.get_unchecked(0)).unwrap();
+    assert_eq!(topic, Symbol::new(&env, "rate_config_forced_event"));
+    assert_eq!(event.admin, admin);
+    assert_eq!(event.utilization_bps, 4000);
+    assert_eq!(event.previous_rate_bps, 1200);
+    assert_eq!(event.new_rate_bps, 1600);
+    assert_eq!(event.max_rate_delta_bps_per_day, 100);
+    assert_eq!(event.timestamp, DAY);
+}
+
+/// Test only the admin can set the maximum rate delta, and not below zero
+#[test]
+fn test_max_rate_delta_requires_admin_and_bounds() {
+    let env = create_test_env();
+    let (_contract_id, admin, client) = setup_contract_with_admin(&env);
+    assert_eq!(client.get_max_rate_delta_per_day(), 0);
+
+    let user = Address::generate(&env);
+    let result = client.try_set_max_rate_delta_per_day(&user, &100);
+    assert_eq!(result, Err(Ok(InterestRateError::Unauthorized)));
+    let result = client.try_set_max_rate_delta_per_day(&admin, &-1);
+    assert_eq!(result, Err(Ok(InterestRateError::InvalidParameter)));
+
+    // Without a maximum, updates apply immediately
+    raise_base_rate(&client, &admin);
+    assert!(client.get_rate_transition().is_none());
+
+    client.set_max_rate_delta_per_day(&admin, &250);
+    assert_eq!(client.get_max_rate_delta_per_day(), 250);
+}
+
+// =============================================================================
+// RATE STRATEGY TESTS
+// =============================================================================
+
+/// Linear, kinked and fixed strategies priced at the same utilizations
+#[test]
+fn test_rate_strategies_at_identical_utilizations() {
+    let env = create_test_env();
+    let (_contract_id, admin, client) = setup_contract_with_admin(&env);
+    let linear = Some(Address::generate(&env));
+    let fixed = Some(Address::generate(&env));
+    client.set_rate_strategy(&admin, &linear, &RateStrategy::Linear);
+    client.set_rate_strategy(&admin, &fixed, &RateStrategy::Fixed(700));
+
+    // Assets default to the kinked curve
+    assert_eq!(client.get_rate_strategy(&None), RateStrategy::Kinked);
+    assert_eq!(client.get_rate_strategy(&linear), RateStrategy::Linear);
+
+    // 40%: the kinked curve spreads the multiplier up to the 80% kink, the
+    // linear one over the whole range
+    assert_eq!(client.get_rate_at_utilization(&None, &4000), (1100, 396));
+    assert_eq!(client.get_rate_at_utilization(&linear, &4000), (900, 324));
+    assert_eq!(client.get_rate_at_utilization(&fixed, &4000), (700, 252));
+
+    // 90%: only the kinked curve applies the jump multiplier
+    assert_eq!(client.get_rate_at_utilization(&None, &9000), (7100, 5751));
+    assert_eq!(client.get_rate_at_utilization(&linear, &9000), (1900, 1539));
+    assert_eq!(client.get_rate_at_utilization(&fixed, &9000), (700, 567));
+
+    // Every strategy starts from nothing supplied at 0%
+    assert_eq!(client.get_rate_at_utilization(&None, &0), (100, 0));
+    assert_eq!(client.get_rate_at_utilization(&linear, &0), (100, 0));
+    assert_eq!(client.get_rate_at_utilization(&fixed, &0), (700, 0));
+}
+
+/// The floor, ceiling and emergency adjustment apply whatever the strategy
+#[test]
+fn test_rate_strategy_bounds_and_adjustment() {
+    let env = create_test_env();
+    let (_contract_id, admin, client) = setup_contract_with_admin(&env);
+    let asset = Some(Address::generate(&env));
+
+    client.set_rate_strategy(&admin, &asset, &RateStrategy::Fixed(20));
+    assert_eq!(client.get_rate_at_utilization(&asset, &5000).0, 50);
+    client.set_rate_strategy(&admin, &asset, &RateStrategy::Fixed(20_000));
+    assert_eq!(client.get_rate_at_utilization(&asset, &5000).0, 10_000);
+
+    client.set_rate_strategy(&admin, &asset, &RateStrategy::Fixed(700));
+    client.set_emergency_rate_adjustment(&admin, &500, &86_400);
+    assert_eq!(client.get_rate_at_utilization(&asset, &5000).0, 1200);
+}
+
+/// Interest up to the switch accrues under the old strategy
+#[test]
+fn test_set_rate_strategy_accrues_first() {
+    let env = create_test_env();
+    let (contract_id, admin, client) = setup_contract_with_admin(&env);
+    set_protocol_analytics(&env, &contract_id, 10000, 4000);
+    client.accrue_interest(&None);
+
+    env.ledger().with_mut(|li| li.timestamp += SECONDS_PER_YEAR);
+    let index = client.get_borrow_index(&None);
+    client.set_rate_strategy(&admin, &None, &RateStrategy::Fixed(700));
+
+    // The index was compounded at the kinked 11% and persisted at the switch
+    assert_eq!(client.get_borrow_index(&None), index);
+    let entry = client.get_rate_history(&None, &1, &0).get(0).unwrap();
+    assert_eq!(entry.timestamp, env.ledger().timestamp());
+    assert_eq!(entry.borrow_rate_bps, 1100);
+    assert_eq!(client.get_borrow_rate(), 700);
+}
+
+#[test]
+fn test_set_rate_strategy_rejections() {
+    let env = create_test_env();
+    let (_contract_id, admin, client) = setup_contract_with_admin(&env);
+    let user = Address::generate(&env);
+
+    let result = client.try_set_rate_strategy(&user, &None, &RateStrategy::Linear);
+    assert_eq!(result, Err(Ok(InterestRateError::Unauthorized)));
+    let result = client.try_set_rate_strategy(&admin, &None, &RateStrategy::Fixed(-1));
+    assert_eq!(result, Err(Ok(InterestRateError::NegativeRateParameter)));
+    assert_eq!(client.get_rate_strategy(&None), RateStrategy::Kinked);
+}
+
+// =============================================================================
+// APY TESTS
+// =============================================================================
+
+/// `(1 + r / n) ^ n - 1` for n seconds a year in floating point, in basis points
+fn reference_apy_bps(rate_bps: i128) -> i128 {
+    let n = SECONDS_PER_YEAR as f64;
+    let rate = rate_bps as f64 / 10_000.0;
+    ((n * (rate / n).ln_1p()).exp_m1() * 10_000.0) as i128
+}
+
+#[test]
+fn test_apy_matches_reference() {
+    // 1%, 10% and 100% APR compound to about 1.005%, 10.517% and 171.828%
+    for (rate_bps, apy_bps) in [(100, 100), (1000, 1051), (10_000, 17_182)] {
+        assert_eq!(annual_percentage_yield(rate_bps), Ok(apy_bps));
+        assert_eq!(reference_apy_bps(rate_bps), apy_bps);
+    }
+    assert_eq!(annual_percentage_yield(0), Ok(0));
+    assert_eq!(
+        annual_percentage_yield(-1),
+        Err(InterestRateError::NegativeRateParameter)
+    );
+}
+
+#[test]
+fn test_apy_monotonic_in_rate() {
+    let mut previous = 0;
+    for rate_bps in (0..=10_000).step_by(7) {
+        let apy = annual_percentage_yield(rate_bps).unwrap();
+        assert!(apy >= previous);
+        assert!(apy >= rate_bps);
+        assert!((apy - reference_apy_bps(rate_bps)).abs() <= 1);
+        previous = apy;
+    }
+}
+
+/// The views convert the asset's current rates
+#[test]
+fn test_apy_views() {
+    let env = create_test_env();
+    let (contract_id, admin, client) = setup_contract_with_admin(&env);
+    set_protocol_analytics(&env, &contract_id, 10000, 4000);
+    client.set_rate_strategy(&admin, &None, &RateStrategy::Fixed(1000));
+
+    assert_eq!(client.get_borrow_apy(&None), 1051);
+    // 10% at 40% utilization net of the 10% reserve factor
+    assert_eq!(client.get_supply_rate(), 360);
+    assert_eq!(
+        client.get_supply_apy(&None),
+        annual_percentage_yield(360).unwrap()
+    );
+}
+
+// =============================================================================
+// CIRCUIT BREAKER TESTS
+// =============================================================================
+
+/// Whether the last invocation emitted an event with topic `name`
+fn emitted(env: &Env, name: &str) -> bool {
+    env.events().all().iter().any(|(_c, topics, _data)| {
+        Symbol::try_from_val(env, &topics.get_unchecked(0)).ok() == Some(Symbol::new(env, name))
+    })
+}
+
+/// Borrows past the trigger trip the breaker; it is released only after a full
+/// cool-off below the release threshold
+#[test]
+fn test_circuit_breaker_trips_and_releases_with_hysteresis() {
+    let env = create_test_env();
+    let (_contract_id, admin, client) = setup_contract_with_admin(&env);
+    let borrower = Address::generate(&env);
+    let supplier = Address::generate(&env);
+    client.set_circuit_breaker_config(&admin, &9000, &7000, &2000, &DAY);
+
+    // 80% stays below the trigger
+    client.deposit_collateral(&borrower, &None, &10_000);
+    client.borrow_asset(&borrower, &None, &8000);
+    client.accrue_interest(&None);
+    assert!(!client.get_circuit_breaker_state().tripped);
+    assert_eq!(client.get_borrow_rate(), 2100);
+
+    // The next accrual sees the borrow that lifts utilization to 90%
+    client.borrow_asset(&borrower, &None, &1000);
+    client.accrue_interest(&None);
+    assert!(emitted(&env, "circuit_breaker_tripped_event"));
+    let state = client.get_circuit_breaker_state();
+    assert!(state.tripped);
+    assert_eq!(state.tripped_at, env.ledger().timestamp());
+    // 7100 from the jump multiplier plus the 20% bonus
+    assert_eq!(client.get_borrow_rate(), 9100);
+    assert_eq!(client.get_rate_at_utilization(&None, &9000).0, 9100);
+
+    // Falling to 75%, below the trigger but not the release threshold, keeps it
+    // tripped however long it lasts
+    client.deposit_collateral(&supplier, &None, &2000);
+    client.accrue_interest(&None);
+    assert_eq!(client.get_rate_at_utilization(&None, &7500).0, 3975);
+    env.ledger().with_mut(|li| li.timestamp += 2 * DAY);
+    client.accrue_interest(&None);
+    let state = client.get_circuit_breaker_state();
+    assert!(state.tripped);
+    assert_eq!(state.release_pending_since, None);
+
+    // Below 70% the cool-off starts
+    client.deposit_collateral(&supplier, &None, &3000);
+    client.accrue_interest(&None);
+    let start = env.ledger().timestamp();
+    assert_eq!(
+        client.get_circuit_breaker_state().release_pending_since,
+        Some(start)
+    );
+    env.ledger().with_mut(|li| li.timestamp += DAY - 1);
+    client.accrue_interest(&None);
+    assert!(client.get_circuit_breaker_state().tripped);
+
+    env.ledger().with_mut(|li| li.timestamp += 1);
+    client.accrue_interest(&None);
+    assert!(emitted(&env, "circuit_breaker_released_event"));
+    let state = client.get_circuit_breaker_state();
+    assert!(!state.tripped);
+    assert_eq!(state.release_pending_since, None);
+    assert_eq!(client.get_rate_at_utilization(&None, &6000).0, 1600);
+}
+
+/// Without a config the breaker never trips
+#[test]
+fn test_circuit_breaker_disabled_by_default() {
+    let env = create_test_env();
+    let (contract_id, _admin, client) = setup_contract_with_admin(&env);
+    set_protocol_analytics(&env, &contract_id, 10000, 10000);
+
+    client.accrue_interest(&None);
+    assert_eq!(client.get_circuit_breaker_config(), None);
+    assert!(!client.get_circuit_breaker_state().tripped);
+    assert_eq!(client.get_borrow_rate(), 10_000);
+}
+
+#[test]
+fn test_circuit_breaker_config_rejections() {
+    let env = create_test_env();
+    let (_contract_id, admin, client) = setup_contract_with_admin(&env);
+    let user = Address::generate(&env);
+
+    let result = client.try_set_circuit_breaker_config(&user, &9800, &9000, &1000, &DAY);
+    assert_eq!(result, Err(Ok(InterestRateError::Unauthorized)));
+    for (trigger, release, bonus) in [
+        (9000, 9500, 1000),
+        (10_001, 9000, 1000),
+        (9800, 0, 1000),
+        (9800, 9000, -1),
+        (9800, 9000, 10_001),
+    ] {
+        let result =
+            client.try_set_circuit_breaker_config(&admin, &trigger, &release, &bonus, &DAY);
+        assert_eq!(result, Err(Ok(InterestRateError::InvalidParameter)));
+    }
+
+    client.set_circuit_breaker_config(&admin, &9800, &9000, &1000, &DAY);
+    let config = client.get_circuit_breaker_config().unwrap();
+    assert_eq!(config.trigger_utilization_bps, 9800);
+    assert_eq!(config.release_utilization_bps, 9000);
+    assert_eq!(config.bonus_bps, 1000);
+    assert_eq!(config.cooloff_secs, DAY);
+}
+
 // =============================================================================
 // EDGE CASE TESTS
 // =============================================================================
@@ -812,6 +1595,13 @@ fn test_large_values() {
         1_000_000_000_000i128,
         500_000_000_000i128,
     );
+    set_asset_totals(
+        &env,
+        &contract_id,
+        &None,
+        1_000_000_000_000i128,
+        500_000_000_000i128,
+    );
 
     let utilization = client.get_utilization();
     let rate = client.get_borrow_rate();
@@ -869,6 +1659,8 @@ fn test_full_interest_rate_workflow() {
         &None,
         &None,
         &None,
+        &None,
+        &false,
     );
 
     // 4. Verify rate changed
@@ -876,7 +1668,7 @@ fn test_full_interest_rate_workflow() {
     assert!(new_rate > initial_rate);
 
     // 5. Apply emergency adjustment
-    client.set_emergency_rate_adjustment(&admin, &300);
+    client.set_emergency_rate_adjustment(&admin, &300, &86_400);
 
     // 6. Verify emergency adjustment applied
     let emergency_rate = client.get_borrow_rate();
@@ -0,0 +1,121 @@
+//! Tests for isolation mode.
+//!
+//! # Coverage
+//! - Debt against an isolated asset is capped across all of its users
+//! - Isolated users may only borrow assets flagged borrowable in isolation
+//! - Isolated and other collateral cannot be combined on one account
+//! - Un-isolating an asset lifts the restrictions
+
+use crate::borrow::BorrowError;
+use crate::deposit::{AssetParams, DepositError};
+use crate::tests::test_helpers::{asset_params, funded_token, set_oracle_price, setup};
+use crate::HelloContractClient;
+use soroban_sdk::{testutils::Address as _, Address, Env};
+
+fn isolation_params(isolated: bool, debt_ceiling: i128, borrowable: bool) -> AssetParams {
+    AssetParams {
+        isolated,
+        debt_ceiling,
+        borrowable_in_isolation: borrowable,
+        ..asset_params(10000)
+    }
+}
+
+/// Lists an isolated asset with a ceiling of 1000 and a stablecoin borrowable in isolation
+fn setup_isolated_market<'a>(
+    env: &'a Env,
+    users: &[&Address],
+) -> (Address, HelloContractClient<'a>, Address, Address) {
+    let (contract_id, admin, client) = setup(env);
+    let isolated = funded_token(env, &contract_id, users, 10_000);
+    let stable = Address::generate(env);
+    client.update_asset_params(&admin, &isolated, &isolation_params(true, 1000, false));
+    client.update_asset_params(&admin, &stable, &isolation_params(false, 0, true));
+    set_oracle_price(&client, &admin, &stable, &100, &7, &admin);
+    (admin, client, isolated, stable)
+}
+
+#[test]
+fn test_debt_ceiling_shared_across_users() {
+    let env = Env::default();
+    let alice = Address::generate(&env);
+    let bob = Address::generate(&env);
+    let (_admin, client, isolated, stable) = setup_isolated_market(&env, &[&alice, &bob]);
+    client.deposit_collateral(&alice, &Some(isolated.clone()), &5_000);
+    client.deposit_collateral(&bob, &Some(isolated.clone()), &5_000);
+
+    client.borrow_asset(&alice, &Some(stable.clone()), &600);
+    let result = client.try_borrow_asset(&bob, &Some(stable.clone()), &500);
+    assert_eq!(result, Err(Ok(BorrowError::DebtCeilingExceeded)));
+
+    client.borrow_asset(&bob, &Some(stable.clone()), &400);
+    assert_eq!(client.get_isolated_debt(&isolated), 1000);
+    let result = client.try_borrow_asset(&alice, &Some(stable.clone()), &1);
+    assert_eq!(result, Err(Ok(BorrowError::DebtCeilingExceeded)));
+
+    // Repaid principal frees room under the ceiling
+    client.repay_debt(&alice, &Some(stable.clone()), &200);
+    assert_eq!(client.get_isolated_debt(&isolated), 800);
+    client.borrow_asset(&bob, &Some(stable), &200);
+    assert_eq!(client.get_isolated_debt(&isolated), 1000);
+}
+
+#[test]
+fn test_isolated_user_limited_to_borrowable_assets() {
+    let env = Env::default();
+    let user = Address::generate(&env);
+    let (admin, client, isolated, _stable) = setup_isolated_market(&env, &[&user]);
+    let other = Address::generate(&env);
+    set_oracle_price(&client, &admin, &other, &100, &7, &admin);
+    client.deposit_collateral(&user, &Some(isolated), &5_000);
+
+    let result = client.try_borrow_asset(&user, &Some(other), &100);
+    assert_eq!(result, Err(Ok(BorrowError::NotBorrowableInIsolation)));
+    let result = client.try_borrow_asset(&user, &None, &100);
+    assert_eq!(result, Err(Ok(BorrowError::NotBorrowableInIsolation)));
+}
+
+#[test]
+fn test_isolated_and_other_collateral_cannot_mix() {
+    let env = Env::default();
+    let user = Address::generate(&env);
+    let other_user = Address::generate(&env);
+    let (_admin, client, isolated, _stable) = setup_isolated_market(&env, &[&user, &other_user]);
+
+    client.deposit_collateral(&user, &Some(isolated.clone()), &1_000);
+    let result = client.try_deposit_collateral(&user, &None, &1_000);
+    assert_eq!(result, Err(Ok(DepositError::IsolationModeConflict)));
+
+    client.deposit_collateral(&other_user, &None, &1_000);
+    let result = client.try_deposit_collateral(&other_user, &Some(isolated.clone()), &1_000);
+    assert_eq!(result, Err(Ok(DepositError::IsolationModeConflict)));
+
+    // A deposit kept out of collateral does not conflict until it is re-enabled
+    client.set_use_as_collateral(&user, &Some(isolated.clone()), &false);
+    client.deposit_collateral(&user, &None, &1_000);
+    let result = client.try_set_use_as_collateral(&user, &Some(isolated), &true);
+    assert_eq!(result, Err(Ok(DepositError::IsolationModeConflict)));
+}
+
+#[test]
+fn test_unisolating_asset_lifts_restrictions() {
+    let env = Env::default();
+    let user = Address::generate(&env);
+    let (admin, client, isolated, stable) = setup_isolated_market(&env, &[&user]);
+    client.deposit_collateral(&user, &Some(isolated.clone()), &5_000);
+    client.borrow_asset(&user, &Some(stable.clone()), &1000);
+
+    assert_eq!(
+        client.try_borrow_asset(&user, &None, &100),
+        Err(Ok(BorrowError::NotBorrowableInIsolation))
+    );
+    assert_eq!(
+        client.try_deposit_collateral(&user, &None, &1_000),
+        Err(Ok(DepositError::IsolationModeConflict))
+    );
+
+    client.update_asset_params(&admin, &isolated, &isolation_params(false, 1000, false));
+    client.borrow_asset(&user, &Some(stable), &500);
+    client.borrow_asset(&user, &None, &100);
+    client.deposit_collateral(&user, &None, &1_000);
+}
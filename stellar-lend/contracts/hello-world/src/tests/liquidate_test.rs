@@ -6,19 +6,27 @@
 //! - Close factor enforcement
 //! - Liquidation incentive calculations
 //! - Undercollateralization validation
-//! - Pause functionality
+//! - Pause functionality and the admin force_liquidate override
 //! - Interest accrual during liquidation
-//! - Multi-asset liquidations
+//! - Multi-asset liquidations and swapping seized collateral through an AMM
+//! - Position snapshots recorded for each liquidation
+//! - Reserve top-ups up to the minimum liquidation reward
 //! - Edge cases and security scenarios
 //!
 //! Note: Many tests are marked #[ignore] because native XLM liquidation
 //! is not yet fully supported. These tests document expected behavior.
 
-use crate::deposit::{DepositDataKey, Position, ProtocolAnalytics};
+use crate::deposit::{AssetParams, DepositDataKey, Position, ProtocolAnalytics};
+use crate::liquidate::{AssetSnapshot, LiquidationError};
+use crate::risk_management::RiskManagementError;
+use crate::tests::test_helpers::{
+    advance, advance_ledgers, asset_params, funded_token, set_oracle_price,
+};
 use crate::{HelloContract, HelloContractClient};
 use soroban_sdk::{
-    testutils::{Address as _, Ledger},
-    Address, Env, Symbol,
+    contract, contractimpl, contracttype,
+    testutils::{Address as _, Events, Ledger},
+    Address, Env, Symbol, TryFromVal,
 };
 
 // =============================================================================
@@ -130,8 +138,14 @@ fn test_liquidate_partial_liquidation() {
 
     // Liquidate 50% of debt (within close factor of 50%)
     let debt_to_liquidate = 500;
-    let (debt_liquidated, collateral_seized, incentive) =
-        client.liquidate(&liquidator, &borrower, &None, &None, &debt_to_liquidate);
+    let (debt_liquidated, collateral_seized, incentive, _protocol_fee) = client.liquidate(
+        &liquidator,
+        &borrower,
+        &None,
+        &None,
+        &debt_to_liquidate,
+        &false,
+    );
 
     // Verify liquidation occurred
     assert_eq!(debt_liquidated, debt_to_liquidate);
@@ -159,8 +173,14 @@ fn test_liquidate_full_liquidation() {
 
     // Liquidate exactly at close factor (50%)
     let max_liquidatable = 500; // 50% of 1000
-    let (debt_liquidated, collateral_seized, _incentive) =
-        client.liquidate(&liquidator, &borrower, &None, &None, &max_liquidatable);
+    let (debt_liquidated, collateral_seized, _incentive, _protocol_fee) = client.liquidate(
+        &liquidator,
+        &borrower,
+        &None,
+        &None,
+        &max_liquidatable,
+        &false,
+    );
 
     assert_eq!(debt_liquidated, max_liquidatable);
     assert!(collateral_seized > 0);
@@ -190,7 +210,14 @@ fn test_liquidate_exceeds_close_factor() {
 
     // Try to liquidate more than close factor allows (50%)
     let excessive_amount = 600; // > 50% of 1000
-    client.liquidate(&liquidator, &borrower, &None, &None, &excessive_amount);
+    client.liquidate(
+        &liquidator,
+        &borrower,
+        &None,
+        &None,
+        &excessive_amount,
+        &false,
+    );
 }
 
 /// Test close factor edge case - exactly at limit
@@ -208,8 +235,8 @@ fn test_liquidate_close_factor_edge_case() {
 
     // Liquidate exactly at close factor (50%)
     let exact_max = 500;
-    let (debt_liquidated, _collateral_seized, _incentive) =
-        client.liquidate(&liquidator, &borrower, &None, &None, &exact_max);
+    let (debt_liquidated, _collateral_seized, _incentive, _protocol_fee) =
+        client.liquidate(&liquidator, &borrower, &None, &None, &exact_max, &false);
 
     assert_eq!(debt_liquidated, exact_max);
 }
@@ -232,8 +259,14 @@ fn test_liquidate_incentive_calculation() {
     create_liquidatable_position(&env, &contract_id, &borrower, 2000, 1000);
 
     let debt_to_liquidate = 500;
-    let (_debt_liquidated, collateral_seized, incentive) =
-        client.liquidate(&liquidator, &borrower, &None, &None, &debt_to_liquidate);
+    let (_debt_liquidated, collateral_seized, incentive, _protocol_fee) = client.liquidate(
+        &liquidator,
+        &borrower,
+        &None,
+        &None,
+        &debt_to_liquidate,
+        &false,
+    );
 
     // Default incentive is 10% (1000 bps)
     // Collateral seized should be debt_liquidated * (1 + incentive%)
@@ -251,7 +284,7 @@ fn test_liquidate_incentive_calculation() {
 /// Test liquidation of healthy position fails
 #[test]
 #[ignore] // Native XLM liquidation not yet supported
-#[should_panic(expected = "NotLiquidatable")]
+#[should_panic(expected = "PositionHealthy")]
 fn test_liquidate_not_undercollateralized() {
     let env = create_test_env();
     let (contract_id, _admin, client) = setup_contract_with_admin(&env);
@@ -263,12 +296,11 @@ fn test_liquidate_not_undercollateralized() {
     create_healthy_position(&env, &contract_id, &borrower, 1500, 1000);
 
     // Try to liquidate - should fail
-    client.liquidate(&liquidator, &borrower, &None, &None, &500);
+    client.liquidate(&liquidator, &borrower, &None, &None, &500, &false);
 }
 
 /// Test liquidation at exact threshold boundary
 #[test]
-#[should_panic(expected = "Liquidation error")]
 fn test_liquidate_at_threshold_boundary() {
     let env = create_test_env();
     let (contract_id, _admin, client) = setup_contract_with_admin(&env);
@@ -280,7 +312,8 @@ fn test_liquidate_at_threshold_boundary() {
     // This should NOT be liquidatable (need to be below threshold)
     create_healthy_position(&env, &contract_id, &borrower, 1050, 1000);
 
-    client.liquidate(&liquidator, &borrower, &None, &None, &500);
+    let result = client.try_liquidate(&liquidator, &borrower, &None, &None, &500, &false);
+    assert_eq!(result, Err(Ok(LiquidationError::PositionHealthy)));
 }
 
 /// Test liquidation just below threshold
@@ -296,8 +329,8 @@ fn test_liquidate_just_below_threshold() {
     // Create position just below liquidation threshold (104%)
     create_liquidatable_position(&env, &contract_id, &borrower, 1040, 1000);
 
-    let (debt_liquidated, _collateral_seized, _incentive) =
-        client.liquidate(&liquidator, &borrower, &None, &None, &500);
+    let (debt_liquidated, _collateral_seized, _incentive, _protocol_fee) =
+        client.liquidate(&liquidator, &borrower, &None, &None, &500, &false);
 
     assert_eq!(debt_liquidated, 500);
 }
@@ -308,7 +341,6 @@ fn test_liquidate_just_below_threshold() {
 
 /// Test liquidation when paused
 #[test]
-#[should_panic(expected = "Liquidation error")]
 fn test_liquidate_paused() {
     let env = create_test_env();
     let (contract_id, admin, client) = setup_contract_with_admin(&env);
@@ -323,12 +355,12 @@ fn test_liquidate_paused() {
     client.set_pause_switch(&admin, &Symbol::new(&env, "pause_liquidate"), &true);
 
     // Try to liquidate - should fail
-    client.liquidate(&liquidator, &borrower, &None, &None, &500);
+    let result = client.try_liquidate(&liquidator, &borrower, &None, &None, &500, &false);
+    assert_eq!(result, Err(Ok(LiquidationError::LiquidationPaused)));
 }
 
 /// Test liquidation with emergency pause
 #[test]
-#[should_panic(expected = "Liquidation error")]
 fn test_liquidate_emergency_paused() {
     let env = create_test_env();
     let (contract_id, admin, client) = setup_contract_with_admin(&env);
@@ -343,7 +375,8 @@ fn test_liquidate_emergency_paused() {
     client.set_emergency_pause(&admin, &true);
 
     // Try to liquidate - should fail
-    client.liquidate(&liquidator, &borrower, &None, &None, &500);
+    let result = client.try_liquidate(&liquidator, &borrower, &None, &None, &500, &false);
+    assert_eq!(result, Err(Ok(LiquidationError::EmergencyPaused)));
 }
 
 /// Test liquidation after unpause
@@ -364,8 +397,8 @@ fn test_liquidate_after_unpause() {
     client.set_pause_switch(&admin, &Symbol::new(&env, "pause_liquidate"), &false);
 
     // Should succeed after unpause
-    let (debt_liquidated, _collateral_seized, _incentive) =
-        client.liquidate(&liquidator, &borrower, &None, &None, &500);
+    let (debt_liquidated, _collateral_seized, _incentive, _protocol_fee) =
+        client.liquidate(&liquidator, &borrower, &None, &None, &500, &false);
 
     assert_eq!(debt_liquidated, 500);
 }
@@ -414,8 +447,8 @@ fn test_liquidate_with_interest() {
 
     // Total debt = principal + interest
     // Liquidate up to 50% of total debt
-    let (debt_liquidated, _collateral_seized, _incentive) =
-        client.liquidate(&liquidator, &borrower, &None, &None, &500);
+    let (debt_liquidated, _collateral_seized, _incentive, _protocol_fee) =
+        client.liquidate(&liquidator, &borrower, &None, &None, &500, &false);
 
     // Should succeed
     assert!(debt_liquidated > 0);
@@ -456,8 +489,8 @@ fn test_liquidate_interest_paid_first() {
     });
 
     // Liquidate 300 (should cover interest first)
-    let (_debt_liquidated, _collateral_seized, _incentive) =
-        client.liquidate(&liquidator, &borrower, &None, &None, &300);
+    let (_debt_liquidated, _collateral_seized, _incentive, _protocol_fee) =
+        client.liquidate(&liquidator, &borrower, &None, &None, &300, &false);
 
     // Check position - interest should be reduced first
     let position = get_user_position(&env, &contract_id, &borrower).unwrap();
@@ -487,7 +520,8 @@ fn test_liquidate_multiple_liquidations() {
     create_liquidatable_position(&env, &contract_id, &borrower, 2000, 2000);
 
     // First liquidation (500 = 25% of 2000)
-    let (debt1, _col1, _inc1) = client.liquidate(&liquidator1, &borrower, &None, &None, &500);
+    let (debt1, _col1, _inc1, _protocol_fee) =
+        client.liquidate(&liquidator1, &borrower, &None, &None, &500, &false);
     assert_eq!(debt1, 500);
 
     // Verify remaining debt
@@ -495,7 +529,8 @@ fn test_liquidate_multiple_liquidations() {
     assert_eq!(position1.debt, 1500);
 
     // Second liquidation (up to 50% of remaining = 750)
-    let (debt2, _col2, _inc2) = client.liquidate(&liquidator2, &borrower, &None, &None, &750);
+    let (debt2, _col2, _inc2, _protocol_fee) =
+        client.liquidate(&liquidator2, &borrower, &None, &None, &750, &false);
     assert_eq!(debt2, 750);
 
     // Verify final position
@@ -509,7 +544,6 @@ fn test_liquidate_multiple_liquidations() {
 
 /// Test liquidation with zero amount
 #[test]
-#[should_panic(expected = "Liquidation error")]
 fn test_liquidate_zero_amount() {
     let env = create_test_env();
     let (contract_id, _admin, client) = setup_contract_with_admin(&env);
@@ -519,12 +553,12 @@ fn test_liquidate_zero_amount() {
 
     create_liquidatable_position(&env, &contract_id, &borrower, 1000, 1000);
 
-    client.liquidate(&liquidator, &borrower, &None, &None, &0);
+    let result = client.try_liquidate(&liquidator, &borrower, &None, &None, &0, &false);
+    assert_eq!(result, Err(Ok(LiquidationError::InvalidAmount)));
 }
 
 /// Test liquidation with negative amount
 #[test]
-#[should_panic(expected = "Liquidation error")]
 fn test_liquidate_negative_amount() {
     let env = create_test_env();
     let (contract_id, _admin, client) = setup_contract_with_admin(&env);
@@ -534,12 +568,12 @@ fn test_liquidate_negative_amount() {
 
     create_liquidatable_position(&env, &contract_id, &borrower, 1000, 1000);
 
-    client.liquidate(&liquidator, &borrower, &None, &None, &(-100));
+    let result = client.try_liquidate(&liquidator, &borrower, &None, &None, &(-100), &false);
+    assert_eq!(result, Err(Ok(LiquidationError::InvalidAmount)));
 }
 
 /// Test liquidation of user with no debt
 #[test]
-#[should_panic(expected = "Liquidation error")]
 fn test_liquidate_no_debt() {
     let env = create_test_env();
     let (contract_id, _admin, client) = setup_contract_with_admin(&env);
@@ -562,12 +596,12 @@ fn test_liquidate_no_debt() {
         env.storage().persistent().set(&position_key, &position);
     });
 
-    client.liquidate(&liquidator, &borrower, &None, &None, &500);
+    let result = client.try_liquidate(&liquidator, &borrower, &None, &None, &500, &false);
+    assert_eq!(result, Err(Ok(LiquidationError::PositionHealthy)));
 }
 
 /// Test liquidation of non-existent position
 #[test]
-#[should_panic(expected = "Liquidation error")]
 fn test_liquidate_no_position() {
     let env = create_test_env();
     let (_contract_id, _admin, client) = setup_contract_with_admin(&env);
@@ -576,7 +610,37 @@ fn test_liquidate_no_position() {
     let liquidator = Address::generate(&env);
 
     // Borrower has no position at all
-    client.liquidate(&liquidator, &borrower, &None, &None, &500);
+    let result = client.try_liquidate(&liquidator, &borrower, &None, &None, &500, &false);
+    assert_eq!(result, Err(Ok(LiquidationError::PositionHealthy)));
+}
+
+/// Test a borrower cannot liquidate their own position
+#[test]
+fn test_liquidate_self_not_allowed() {
+    let env = create_test_env();
+    let (contract_id, _admin, client) = setup_contract_with_admin(&env);
+
+    let borrower = Address::generate(&env);
+
+    create_liquidatable_position(&env, &contract_id, &borrower, 1000, 1000);
+
+    let result = client.try_liquidate(&borrower, &borrower, &None, &None, &500, &false);
+    assert_eq!(result, Err(Ok(LiquidationError::SelfLiquidationNotAllowed)));
+}
+
+/// Test liquidation of a position with nothing left to seize
+#[test]
+fn test_liquidate_without_collateral() {
+    let env = create_test_env();
+    let (contract_id, _admin, client) = setup_contract_with_admin(&env);
+
+    let borrower = Address::generate(&env);
+    let liquidator = Address::generate(&env);
+
+    create_liquidatable_position(&env, &contract_id, &borrower, 0, 1000);
+
+    let result = client.try_liquidate(&liquidator, &borrower, &None, &None, &500, &false);
+    assert_eq!(result, Err(Ok(LiquidationError::InsufficientCollateral)));
 }
 
 // =============================================================================
@@ -600,8 +664,14 @@ fn test_collateral_seizure() {
     assert_eq!(initial_collateral, 2000);
 
     let debt_to_liquidate = 500;
-    let (_debt_liquidated, collateral_seized, _incentive) =
-        client.liquidate(&liquidator, &borrower, &None, &None, &debt_to_liquidate);
+    let (_debt_liquidated, collateral_seized, _incentive, _protocol_fee) = client.liquidate(
+        &liquidator,
+        &borrower,
+        &None,
+        &None,
+        &debt_to_liquidate,
+        &false,
+    );
 
     // Verify collateral was reduced
     let final_collateral = get_collateral_balance(&env, &contract_id, &borrower);
@@ -622,8 +692,8 @@ fn test_collateral_seizure_capped() {
     create_liquidatable_position(&env, &contract_id, &borrower, 500, 1000);
 
     // Try to liquidate - should seize all available collateral at most
-    let (debt_liquidated, collateral_seized, _incentive) =
-        client.liquidate(&liquidator, &borrower, &None, &None, &500);
+    let (debt_liquidated, collateral_seized, _incentive, _protocol_fee) =
+        client.liquidate(&liquidator, &borrower, &None, &None, &500, &false);
 
     assert!(debt_liquidated > 0);
     assert!(collateral_seized <= 500); // Cannot exceed available
@@ -647,8 +717,8 @@ fn test_liquidate_analytics_updated() {
     create_liquidatable_position(&env, &contract_id, &borrower, 2000, 1500);
 
     // Perform liquidation
-    let (_debt_liquidated, collateral_seized, _incentive) =
-        client.liquidate(&liquidator, &borrower, &None, &None, &500);
+    let (_debt_liquidated, collateral_seized, _incentive, _protocol_fee) =
+        client.liquidate(&liquidator, &borrower, &None, &None, &500, &false);
 
     // Check protocol analytics updated
     env.as_contract(&contract_id, || {
@@ -678,7 +748,7 @@ fn test_liquidate_activity_log() {
     create_liquidatable_position(&env, &contract_id, &borrower, 2000, 1500);
 
     // Perform liquidation
-    client.liquidate(&liquidator, &borrower, &None, &None, &500);
+    client.liquidate(&liquidator, &borrower, &None, &None, &500, &false);
 
     // Check activity was logged
     let activities = client.get_recent_activity(&10, &0);
@@ -711,8 +781,8 @@ fn test_liquidate_small_amount() {
     create_liquidatable_position(&env, &contract_id, &borrower, 10000, 10000);
 
     // Liquidate very small amount
-    let (debt_liquidated, collateral_seized, _incentive) =
-        client.liquidate(&liquidator, &borrower, &None, &None, &1);
+    let (debt_liquidated, collateral_seized, _incentive, _protocol_fee) =
+        client.liquidate(&liquidator, &borrower, &None, &None, &1, &false);
 
     assert_eq!(debt_liquidated, 1);
     assert!(collateral_seized >= 1); // At least 1 collateral seized
@@ -736,8 +806,8 @@ fn test_liquidate_large_values() {
 
     // Liquidate 50%
     let to_liquidate = debt / 2;
-    let (debt_liquidated, collateral_seized, incentive) =
-        client.liquidate(&liquidator, &borrower, &None, &None, &to_liquidate);
+    let (debt_liquidated, collateral_seized, incentive, _protocol_fee) =
+        client.liquidate(&liquidator, &borrower, &None, &None, &to_liquidate, &false);
 
     assert_eq!(debt_liquidated, to_liquidate);
     assert!(collateral_seized > to_liquidate); // Includes incentive
@@ -765,8 +835,8 @@ fn test_liquidate_position_consistency() {
         initial_debt,
     );
 
-    let (debt_liquidated, collateral_seized, _) =
-        client.liquidate(&liquidator, &borrower, &None, &None, &500);
+    let (debt_liquidated, collateral_seized, _, _) =
+        client.liquidate(&liquidator, &borrower, &None, &None, &500, &false);
 
     // Verify position is consistent
     let position = get_user_position(&env, &contract_id, &borrower).unwrap();
@@ -781,3 +851,1668 @@ fn test_liquidate_position_consistency() {
     // Collateral should be reduced
     assert_eq!(collateral_balance, initial_collateral - collateral_seized);
 }
+
+// =============================================================================
+// HEALTH FACTOR VIEW TESTS
+// =============================================================================
+
+/// Health factor view agrees with `liquidate` as the collateral price falls
+#[test]
+fn test_health_factor_matches_liquidation_across_price_move() {
+    let env = create_test_env();
+    let (contract_id, admin, client) = setup_contract_with_admin(&env);
+    let borrower = Address::generate(&env);
+    let liquidator = Address::generate(&env);
+    let oracle = Address::generate(&env);
+
+    // 1000 of A backs 800 of B, both priced at 100
+    let collateral = funded_token(&env, &contract_id, &[&borrower], 1000);
+    let debt = funded_token(&env, &contract_id, &[&liquidator], 1000);
+    client.deposit_collateral(&borrower, &Some(collateral.clone()), &1000);
+    set_oracle_price(&client, &admin, &collateral, &100, &7, &oracle);
+    set_oracle_price(&client, &admin, &debt, &100, &7, &oracle);
+    client.borrow_asset(&borrower, &Some(debt.clone()), &800);
+
+    // 1000 * 100 / (800 * 100 * 105%)
+    assert_eq!(client.get_health_factor(&borrower), 11_904);
+    assert_eq!(client.get_account_liquidity(&borrower), (15_238, 0));
+
+    // Prices move at most 5% per update; 84 puts the account exactly at the
+    // 105% threshold, which is still not liquidatable
+    for price in [95, 91, 87, 84] {
+        set_oracle_price(&client, &admin, &collateral, &price, &7, &oracle);
+        assert!(client.get_health_factor(&borrower) >= 10_000);
+        assert_eq!(client.get_account_liquidity(&borrower).1, 0);
+        let result = client.try_liquidate(
+            &liquidator,
+            &borrower,
+            &Some(debt.clone()),
+            &Some(collateral.clone()),
+            &400,
+            &false,
+        );
+        assert_eq!(result, Err(Ok(LiquidationError::PositionHealthy)));
+    }
+    assert_eq!(client.get_health_factor(&borrower), 10_000);
+
+    set_oracle_price(&client, &admin, &collateral, &80, &7, &oracle);
+    assert_eq!(client.get_health_factor(&borrower), 9_523);
+    assert_eq!(client.get_account_liquidity(&borrower), (0, 3_810));
+
+    let (debt_liquidated, _collateral_seized, _incentive, _protocol_fee) = client.liquidate(
+        &liquidator,
+        &borrower,
+        &Some(debt),
+        &Some(collateral),
+        &400,
+        &false,
+    );
+    assert_eq!(debt_liquidated, 400);
+}
+
+/// Assets priced with different decimals are valued on a common scale
+#[test]
+fn test_health_factor_with_mixed_price_decimals() {
+    let env = create_test_env();
+    let (contract_id, admin, client) = setup_contract_with_admin(&env);
+    let borrower = Address::generate(&env);
+    let liquidator = Address::generate(&env);
+    let oracle = Address::generate(&env);
+
+    // 1000 of A backs 800 of B, both worth 0.00001: A quoted with 7 decimals,
+    // B with 8
+    let collateral = funded_token(&env, &contract_id, &[&borrower], 1000);
+    let debt = funded_token(&env, &contract_id, &[&liquidator], 1000);
+    client.deposit_collateral(&borrower, &Some(collateral.clone()), &1000);
+    set_oracle_price(&client, &admin, &collateral, &100, &7, &oracle);
+    set_oracle_price(&client, &admin, &debt, &1_000, &8, &oracle);
+    client.borrow_asset(&borrower, &Some(debt.clone()), &800);
+
+    // Same health as when both are quoted with 7 decimals
+    assert_eq!(client.get_health_factor(&borrower), 11_904);
+    assert_eq!(client.get_account_liquidity(&borrower), (15_238, 0));
+    let result = client.try_liquidate(
+        &liquidator,
+        &borrower,
+        &Some(debt),
+        &Some(collateral),
+        &400,
+        &false,
+    );
+    assert_eq!(result, Err(Ok(LiquidationError::PositionHealthy)));
+}
+
+/// `can_liquidate` turns true, and `liquidate` goes through, only once a price
+/// drop pushes the account below the threshold
+#[test]
+fn test_can_liquidate_only_after_price_drop() {
+    let env = create_test_env();
+    let (contract_id, admin, client) = setup_contract_with_admin(&env);
+    let borrower = Address::generate(&env);
+    let liquidator = Address::generate(&env);
+    let oracle = Address::generate(&env);
+
+    let collateral = funded_token(&env, &contract_id, &[&borrower], 1000);
+    let debt = funded_token(&env, &contract_id, &[&liquidator], 1000);
+    client.deposit_collateral(&borrower, &Some(collateral.clone()), &1000);
+    set_oracle_price(&client, &admin, &collateral, &100, &7, &oracle);
+    set_oracle_price(&client, &admin, &debt, &100, &7, &oracle);
+    client.borrow_asset(&borrower, &Some(debt.clone()), &800);
+
+    assert_eq!(client.can_liquidate(&borrower), (false, 11_904));
+    let result = client.try_liquidate(
+        &liquidator,
+        &borrower,
+        &Some(debt.clone()),
+        &Some(collateral.clone()),
+        &400,
+        &false,
+    );
+    assert_eq!(result, Err(Ok(LiquidationError::PositionHealthy)));
+
+    for price in [95, 91, 87, 84, 80] {
+        set_oracle_price(&client, &admin, &collateral, &price, &7, &oracle);
+    }
+    assert_eq!(client.can_liquidate(&borrower), (true, 9_523));
+    let (debt_liquidated, _collateral_seized, _incentive, _protocol_fee) = client.liquidate(
+        &liquidator,
+        &borrower,
+        &Some(debt),
+        &Some(collateral),
+        &400,
+        &false,
+    );
+    assert_eq!(debt_liquidated, 400);
+
+    // Accounts without debt are never liquidatable
+    let lender = Address::generate(&env);
+    assert_eq!(client.can_liquidate(&lender), (false, i128::MAX));
+}
+
+// =============================================================================
+// CLOSE FACTOR TESTS
+// =============================================================================
+
+/// 1000 of A backs 800 of B, and A has fallen from 100 to 80, leaving the
+/// account liquidatable. Returns (client, admin, borrower, liquidator, collateral, debt).
+fn setup_underwater_position(
+    env: &Env,
+) -> (
+    HelloContractClient<'_>,
+    Address,
+    Address,
+    Address,
+    Address,
+    Address,
+) {
+    let (contract_id, admin, client) = setup_contract_with_admin(env);
+    let borrower = Address::generate(env);
+    let liquidator = Address::generate(env);
+    let oracle = Address::generate(env);
+
+    let collateral = funded_token(env, &contract_id, &[&borrower], 1000);
+    let debt = funded_token(env, &contract_id, &[&liquidator], 1000);
+    client.deposit_collateral(&borrower, &Some(collateral.clone()), &1000);
+    set_oracle_price(&client, &admin, &collateral, &100, &7, &oracle);
+    set_oracle_price(&client, &admin, &debt, &100, &7, &oracle);
+    client.borrow_asset(&borrower, &Some(debt.clone()), &800);
+    for price in [95, 91, 87, 84, 80] {
+        set_oracle_price(&client, &admin, &collateral, &price, &7, &oracle);
+    }
+    (client, admin, borrower, liquidator, collateral, debt)
+}
+
+/// The 50% close factor caps a liquidation at 400 of the 800 owed in the debt asset
+#[test]
+fn test_close_factor_boundary_and_one_over() {
+    let env = create_test_env();
+    let (client, _admin, borrower, liquidator, collateral, debt) = setup_underwater_position(&env);
+    let debt_asset = Some(debt);
+    let collateral_asset = Some(collateral);
+
+    let result = client.try_liquidate(
+        &liquidator,
+        &borrower,
+        &debt_asset,
+        &collateral_asset,
+        &401,
+        &false,
+    );
+    assert_eq!(result, Err(Ok(LiquidationError::ExceedsCloseFactor)));
+
+    let (debt_liquidated, _collateral_seized, _incentive, _protocol_fee) = client.liquidate(
+        &liquidator,
+        &borrower,
+        &debt_asset,
+        &collateral_asset,
+        &400,
+        &false,
+    );
+    assert_eq!(debt_liquidated, 400);
+    assert_eq!(client.get_user_debt(&borrower, &debt_asset), 400);
+
+    // Debt the borrower does not owe cannot be liquidated
+    let result = client.try_liquidate(
+        &liquidator,
+        &borrower,
+        &None,
+        &collateral_asset,
+        &100,
+        &false,
+    );
+    assert_eq!(result, Err(Ok(LiquidationError::InvalidDebtAsset)));
+}
+
+/// Below the dust threshold the whole debt can be repaid in one liquidation
+#[test]
+fn test_dust_position_exempt_from_close_factor() {
+    let env = create_test_env();
+    let (client, admin, borrower, liquidator, collateral, debt) = setup_underwater_position(&env);
+    let debt_asset = Some(debt);
+    let collateral_asset = Some(collateral);
+
+    // 800 of debt at a price of 100 is worth 80_000
+    let result = client.try_liquidate(
+        &liquidator,
+        &borrower,
+        &debt_asset,
+        &collateral_asset,
+        &800,
+        &false,
+    );
+    assert_eq!(result, Err(Ok(LiquidationError::ExceedsCloseFactor)));
+    client.set_close_factor_dust_threshold(&admin, &80_000);
+    let result = client.try_liquidate(
+        &liquidator,
+        &borrower,
+        &debt_asset,
+        &collateral_asset,
+        &800,
+        &false,
+    );
+    assert_eq!(result, Err(Ok(LiquidationError::ExceedsCloseFactor)));
+
+    client.set_close_factor_dust_threshold(&admin, &80_001);
+    assert_eq!(client.get_close_factor_dust_threshold(), 80_001);
+    let (debt_liquidated, _collateral_seized, _incentive, _protocol_fee) = client.liquidate(
+        &liquidator,
+        &borrower,
+        &debt_asset,
+        &collateral_asset,
+        &800,
+        &false,
+    );
+    assert_eq!(debt_liquidated, 800);
+    assert_eq!(client.get_user_debt(&borrower, &debt_asset), 0);
+}
+
+/// Accounts without debt report the no-debt sentinel and no shortfall
+#[test]
+fn test_health_factor_without_debt() {
+    let env = create_test_env();
+    let (_contract_id, _admin, client) = setup_contract_with_admin(&env);
+    let user = Address::generate(&env);
+
+    assert_eq!(client.get_health_factor(&user), i128::MAX);
+    assert_eq!(client.get_account_liquidity(&user), (0, 0));
+
+    client.deposit_collateral(&user, &None, &1050);
+    assert_eq!(client.get_health_factor(&user), i128::MAX);
+    assert_eq!(client.get_account_liquidity(&user).1, 0);
+}
+
+/// Same-asset positions use the same threshold boundary as `liquidate`
+#[test]
+fn test_health_factor_at_threshold_boundary() {
+    let env = create_test_env();
+    let (contract_id, _admin, client) = setup_contract_with_admin(&env);
+    let borrower = Address::generate(&env);
+
+    create_healthy_position(&env, &contract_id, &borrower, 1050, 1000);
+    assert_eq!(client.get_health_factor(&borrower), 10_000);
+
+    create_liquidatable_position(&env, &contract_id, &borrower, 1049, 1000);
+    assert_eq!(client.get_health_factor(&borrower), 9_990);
+    assert!(client.get_account_liquidity(&borrower).1 > 0);
+}
+
+// =============================================================================
+// LIQUIDATION INCENTIVE TESTS
+// =============================================================================
+
+/// Step the liquidation incentive from its 10% default down to 5%, within the
+/// 10% per-update change limit
+fn set_five_percent_incentive(client: &HelloContractClient, admin: &Address) {
+    for incentive in [900, 810, 729, 657, 592, 533, 500] {
+        client.set_risk_params(admin, &None, &None, &None, &Some(incentive), &None, &None);
+    }
+    assert_eq!(client.get_liquidation_incentive(), 500);
+}
+
+fn set_protocol_fee(client: &HelloContractClient, admin: &Address, fee_bps: i128) {
+    client.set_risk_params(admin, &None, &None, &None, &None, &None, &Some(fee_bps));
+}
+
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct TestLiquidationEvent {
+    pub liquidator: Address,
+    pub debt_asset: Option<Address>,
+    pub debt_liquidated: i128,
+    pub collateral_seized: i128,
+    pub incentive_amount: i128,
+    pub protocol_fee: i128,
+    pub health_factor_before: i128,
+    pub health_factor_after: i128,
+    pub debt_price: i128,
+    pub collateral_price: i128,
+    pub timestamp: u64,
+}
+
+fn last_liquidation_event(env: &Env) -> TestLiquidationEvent {
+    env.events()
+        .all()
+        .iter()
+        .rev()
+        .find_map(|(_c, _topics, data)| TestLiquidationEvent::try_from_val(env, &data).ok())
+        .expect("liquidation event not emitted")
+}
+
+/// 400 of B at 100 buys 500 of A at 80; the liquidator gets 5% on top, less
+/// the protocol's 10% share of that bonus
+#[test]
+fn test_liquidation_incentive_with_protocol_share() {
+    let env = create_test_env();
+    let (client, admin, borrower, liquidator, collateral, debt) = setup_underwater_position(&env);
+    set_five_percent_incentive(&client, &admin);
+    set_protocol_fee(&client, &admin, 1_000);
+    assert_eq!(
+        client
+            .get_risk_config()
+            .unwrap()
+            .liquidation_protocol_fee_bps,
+        1_000
+    );
+
+    let reserve_before = client.get_reserve_balance(&Some(collateral.clone()));
+    let (debt_liquidated, collateral_seized, incentive, protocol_fee) = client.liquidate(
+        &liquidator,
+        &borrower,
+        &Some(debt),
+        &Some(collateral.clone()),
+        &400,
+        &false,
+    );
+    assert_eq!(debt_liquidated, 400);
+    assert_eq!(incentive, 25);
+    assert_eq!(collateral_seized, 500 + incentive);
+
+    // 10% of the 25 bonus, rounded down, is kept as reserve
+    assert_eq!(protocol_fee, 2);
+    let collateral_token = soroban_sdk::token::Client::new(&env, &collateral);
+    assert_eq!(collateral_token.balance(&liquidator), 523);
+    assert_eq!(
+        client.get_reserve_balance(&Some(collateral)),
+        reserve_before + protocol_fee
+    );
+
+    let event = last_liquidation_event(&env);
+    assert_eq!(event.incentive_amount, 25);
+    assert_eq!(event.protocol_fee, 2);
+}
+
+/// With no protocol fee the liquidator keeps the whole incentive, as before the
+/// fee existed
+#[test]
+fn test_zero_protocol_fee_pays_whole_incentive() {
+    let env = create_test_env();
+    let (client, admin, borrower, liquidator, collateral, debt) = setup_underwater_position(&env);
+    set_five_percent_incentive(&client, &admin);
+    set_protocol_fee(&client, &admin, 0);
+
+    let (debt_liquidated, collateral_seized, incentive, protocol_fee) = client.liquidate(
+        &liquidator,
+        &borrower,
+        &Some(debt),
+        &Some(collateral.clone()),
+        &400,
+        &false,
+    );
+    assert_eq!(
+        (debt_liquidated, collateral_seized, incentive, protocol_fee),
+        (400, 525, 25, 0)
+    );
+    let collateral_token = soroban_sdk::token::Client::new(&env, &collateral);
+    assert_eq!(collateral_token.balance(&liquidator), 525);
+    assert_eq!(client.get_reserve_balance(&Some(collateral)), 0);
+    assert_eq!(last_liquidation_event(&env).protocol_fee, 0);
+}
+
+/// When the borrower's collateral cannot cover the repaid debt plus the full
+/// bonus, all of it is seized against a proportional share of the debt
+#[test]
+fn test_liquidation_incentive_capped_by_available_collateral() {
+    let env = create_test_env();
+    let (client, admin, borrower, liquidator, collateral, debt) = setup_underwater_position(&env);
+    set_five_percent_incentive(&client, &admin);
+    set_protocol_fee(&client, &admin, 1_000);
+    client.set_close_factor_dust_threshold(&admin, &80_001);
+
+    // 800 of B at 100 is worth 975 of A at 82; 975 + 48 exceeds the 1000 held,
+    // so 1000 / 1023 of the debt and of the bonus go through
+    set_oracle_price(&client, &admin, &collateral, &82, &7, &admin);
+    let (debt_liquidated, collateral_seized, incentive, _protocol_fee) = client.liquidate(
+        &liquidator,
+        &borrower,
+        &Some(debt.clone()),
+        &Some(collateral.clone()),
+        &800,
+        &false,
+    );
+    assert_eq!(debt_liquidated, 782);
+    assert_eq!(collateral_seized, 1000);
+    assert_eq!(incentive, 46);
+
+    let collateral_token = soroban_sdk::token::Client::new(&env, &collateral);
+    assert_eq!(collateral_token.balance(&liquidator), 996);
+    assert_eq!(client.get_reserve_balance(&Some(collateral)), 4);
+
+    // The 18 left unbacked is booked as bad debt
+    assert_eq!(client.get_user_debt(&borrower, &Some(debt.clone())), 0);
+    assert_eq!(client.get_bad_debt(&Some(debt)), 18);
+
+    let result =
+        client.try_set_risk_params(&admin, &None, &None, &None, &None, &None, &Some(3_001));
+    assert_eq!(result, Err(Ok(RiskManagementError::InvalidParameter)));
+}
+
+// =============================================================================
+// COLLATERAL CHOICE TESTS
+// =============================================================================
+
+/// A borrower holding 1000 each of A and C, both falling from 100 to 90, against
+/// 1800 of B at 100. The liquidator drains A over two liquidations, after which
+/// only C can be named.
+#[test]
+fn test_liquidator_chooses_collateral_until_depleted() {
+    let env = create_test_env();
+    let (contract_id, admin, client) = setup_contract_with_admin(&env);
+    let borrower = Address::generate(&env);
+    let liquidator = Address::generate(&env);
+    let oracle = Address::generate(&env);
+
+    let collateral_a = funded_token(&env, &contract_id, &[&borrower], 1000);
+    let collateral_c = funded_token(&env, &contract_id, &[&borrower], 1000);
+    let debt = funded_token(&env, &contract_id, &[&liquidator], 1000);
+    let (a, c, b) = (
+        Some(collateral_a.clone()),
+        Some(collateral_c.clone()),
+        Some(debt.clone()),
+    );
+    client.deposit_collateral(&borrower, &a, &1000);
+    client.deposit_collateral(&borrower, &c, &1000);
+    for token in [&collateral_a, &collateral_c, &debt] {
+        set_oracle_price(&client, &admin, token, &100, &7, &oracle);
+    }
+    client.borrow_asset(&borrower, &b, &1800);
+    for price in [95, 91, 90] {
+        set_oracle_price(&client, &admin, &collateral_a, &price, &7, &oracle);
+        set_oracle_price(&client, &admin, &collateral_c, &price, &7, &oracle);
+    }
+
+    let seizable = client.get_seizable_collateral(&borrower);
+    assert_eq!(seizable.len(), 2);
+    assert_eq!(seizable.get_unchecked(0), (a.clone(), 1000));
+    assert_eq!(seizable.get_unchecked(1), (c.clone(), 1000));
+
+    // 400 of B buys 444 of A plus a 44 bonus; C is untouched
+    let (_debt_liquidated, collateral_seized, _incentive, _protocol_fee) =
+        client.liquidate(&liquidator, &borrower, &b, &a, &400, &false);
+    assert_eq!(collateral_seized, 488);
+    let seizable = client.get_seizable_collateral(&borrower);
+    assert_eq!(seizable.get_unchecked(0), (a.clone(), 512));
+    assert_eq!(seizable.get_unchecked(1), (c.clone(), 1000));
+
+    // The next 500 of B is worth more A than is left, so A is emptied against a
+    // proportional share of the debt and bonus
+    let (debt_liquidated, collateral_seized, incentive, _protocol_fee) =
+        client.liquidate(&liquidator, &borrower, &b, &a, &500, &false);
+    assert_eq!(debt_liquidated, 419);
+    assert_eq!(collateral_seized, 512);
+    assert_eq!(incentive, 46);
+    let seizable = client.get_seizable_collateral(&borrower);
+    assert_eq!(seizable.len(), 1);
+    assert_eq!(seizable.get_unchecked(0), (c.clone(), 1000));
+
+    // The depleted asset, and assets never deposited, can no longer be named
+    let result = client.try_liquidate(&liquidator, &borrower, &b, &a, &100, &false);
+    assert_eq!(result, Err(Ok(LiquidationError::InvalidCollateralAsset)));
+    let result = client.try_liquidate(&liquidator, &borrower, &b, &b, &100, &false);
+    assert_eq!(result, Err(Ok(LiquidationError::InvalidCollateralAsset)));
+
+    let (debt_liquidated, _collateral_seized, _incentive, _protocol_fee) =
+        client.liquidate(&liquidator, &borrower, &b, &c, &100, &false);
+    assert_eq!(debt_liquidated, 100);
+    assert_eq!(
+        soroban_sdk::token::Client::new(&env, &collateral_c).balance(&liquidator),
+        122
+    );
+}
+
+// =============================================================================
+// LIQUIDATABLE POSITION INDEX TESTS
+// =============================================================================
+
+/// Five borrowers each back a different amount of B with 1000 of A. Once A falls
+/// from 100 to 80, the three largest borrowers are under water.
+#[test]
+fn test_liquidatable_positions_paginated() {
+    let env = create_test_env();
+    let (contract_id, admin, client) = setup_contract_with_admin(&env);
+    let oracle = Address::generate(&env);
+    let token_admin = Address::generate(&env);
+    let collateral = env.register_stellar_asset_contract(token_admin.clone());
+    let debt = env.register_stellar_asset_contract(token_admin);
+    set_oracle_price(&client, &admin, &collateral, &100, &7, &oracle);
+    set_oracle_price(&client, &admin, &debt, &100, &7, &oracle);
+
+    let mut borrowers = soroban_sdk::Vec::new(&env);
+    for amount in [500, 600, 800, 850, 900] {
+        let borrower = Address::generate(&env);
+        soroban_sdk::token::StellarAssetClient::new(&env, &collateral).mint(&borrower, &1000);
+        soroban_sdk::token::Client::new(&env, &collateral).approve(
+            &borrower,
+            &contract_id,
+            &1000,
+            &(env.ledger().sequence() + 100),
+        );
+        client.deposit_collateral(&borrower, &Some(collateral.clone()), &1000);
+        client.borrow_asset(&borrower, &Some(debt.clone()), &amount);
+        borrowers.push_back(borrower);
+    }
+    assert_eq!(client.get_liquidatable_positions(&10, &0).len(), 0);
+
+    for price in [95, 91, 87, 84, 80] {
+        set_oracle_price(&client, &admin, &collateral, &price, &7, &oracle);
+    }
+
+    let positions = client.get_liquidatable_positions(&10, &0);
+    assert_eq!(positions.len(), 3);
+    assert_eq!(
+        positions.get_unchecked(0),
+        (borrowers.get_unchecked(2), 9_523)
+    );
+    assert_eq!(
+        positions.get_unchecked(1),
+        (borrowers.get_unchecked(3), 8_963)
+    );
+    assert_eq!(
+        positions.get_unchecked(2),
+        (borrowers.get_unchecked(4), 8_465)
+    );
+
+    // Each page examines `limit` borrowers, liquidatable or not
+    assert_eq!(client.get_liquidatable_positions(&2, &0).len(), 0);
+    let page = client.get_liquidatable_positions(&2, &2);
+    assert_eq!(page.len(), 2);
+    assert_eq!(page.get_unchecked(0).0, borrowers.get_unchecked(2));
+    assert_eq!(page.get_unchecked(1).0, borrowers.get_unchecked(3));
+    let page = client.get_liquidatable_positions(&2, &4);
+    assert_eq!(page.len(), 1);
+    assert_eq!(page.get_unchecked(0).0, borrowers.get_unchecked(4));
+    assert_eq!(client.get_liquidatable_positions(&2, &6).len(), 0);
+
+    // Repaying in full drops a borrower from the index
+    client.repay_debt(&borrowers.get_unchecked(0), &Some(debt), &500);
+    let page = client.get_liquidatable_positions(&2, &1);
+    assert_eq!(page.len(), 2);
+    assert_eq!(page.get_unchecked(0).0, borrowers.get_unchecked(2));
+    assert_eq!(page.get_unchecked(1).0, borrowers.get_unchecked(3));
+}
+
+// =============================================================================
+// BAD DEBT TESTS
+// =============================================================================
+
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct TestLiquidationBadDebtEvent {
+    pub liquidator: Address,
+    pub borrower: Address,
+    pub debt_asset: Option<Address>,
+    pub amount: i128,
+    pub from_reserve: i128,
+    pub bad_debt: i128,
+    pub timestamp: u64,
+}
+
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct TestForcedLiquidationEvent {
+    pub admin: Address,
+    pub borrower: Address,
+    pub debt_asset: Option<Address>,
+    pub collateral_asset: Option<Address>,
+    pub debt_liquidated: i128,
+    pub collateral_seized: i128,
+    pub emergency_paused: bool,
+    pub operation_paused: bool,
+    pub timestamp: u64,
+}
+
+/// Seed the B reserve with `reserve`, then crash A to 40, 60% below where it
+/// started, 5% a step, and liquidate 400 of the 800 B owed. At 40, 400 of B is worth all 1000 of A
+/// before the 10% bonus, so 1000 / 1100 of the 400 is repaid and the borrower
+/// is left with 437 of B and no collateral.
+fn liquidate_after_crash(
+    env: &Env,
+    reserve: i128,
+) -> (
+    HelloContractClient<'_>,
+    Address,
+    Address,
+    (i128, i128, i128, i128),
+) {
+    let (client, admin, borrower, liquidator, collateral, debt) = setup_underwater_position(env);
+    let donor = Address::generate(env);
+    soroban_sdk::token::StellarAssetClient::new(env, &debt).mint(&donor, &reserve);
+    soroban_sdk::token::Client::new(env, &debt).approve(
+        &donor,
+        &client.address,
+        &reserve,
+        &(env.ledger().sequence() + 100),
+    );
+    client.donate_to_reserves(&donor, &Some(debt.clone()), &reserve);
+
+    let mut price = 80;
+    while price > 40 {
+        price = ((price * 95 + 99) / 100).max(40);
+        set_oracle_price(&client, &admin, &collateral, &price, &7, &admin);
+    }
+
+    let result = client.liquidate(
+        &liquidator,
+        &borrower,
+        &Some(debt.clone()),
+        &Some(collateral),
+        &400,
+        &false,
+    );
+    (client, borrower, debt, result)
+}
+
+/// The reserve absorbs the unbacked debt first and only the residue is bad debt
+#[test]
+fn test_price_crash_books_residue_as_bad_debt() {
+    let env = create_test_env();
+    let (client, borrower, debt, (debt_liquidated, collateral_seized, incentive, _protocol_fee)) =
+        liquidate_after_crash(&env, 100);
+    let debt_asset = Some(debt);
+    assert_eq!(debt_liquidated, 363);
+    assert_eq!(collateral_seized, 1000);
+    assert_eq!(incentive, 90);
+
+    assert_eq!(client.get_user_debt(&borrower, &debt_asset), 0);
+    assert_eq!(client.get_reserve_balance(&debt_asset), 0);
+    assert_eq!(client.get_bad_debt(&debt_asset), 337);
+    assert_eq!(client.get_protocol_analytics().total_bad_debt, 337);
+
+    let (topics, event) = env
+        .events()
+        .all()
+        .iter()
+        .rev()
+        .find_map(|(_c, topics, data)| {
+            TestLiquidationBadDebtEvent::try_from_val(&env, &data)
+                .ok()
+                .map(|event| (topics, event))
+        })
+        .expect("bad debt event not emitted");
+    let topic = Symbol::try_from_val(&env, &topics.get_unchecked(0)).unwrap();
+    assert_eq!(topic, Symbol::new(&env, "liquidation_bad_debt_event"));
+    assert_eq!(event.borrower, borrower);
+    assert_eq!(event.debt_asset, debt_asset);
+    assert_eq!(event.amount, 437);
+    assert_eq!(event.from_reserve, 100);
+    assert_eq!(event.bad_debt, 337);
+}
+
+/// A reserve deep enough to cover the unbacked debt leaves no bad debt
+#[test]
+fn test_price_crash_covered_by_reserve() {
+    let env = create_test_env();
+    let (client, borrower, debt, (debt_liquidated, _collateral_seized, _incentive, _protocol_fee)) =
+        liquidate_after_crash(&env, 1000);
+    let debt_asset = Some(debt);
+    assert_eq!(debt_liquidated, 363);
+
+    assert_eq!(client.get_user_debt(&borrower, &debt_asset), 0);
+    assert_eq!(client.get_reserve_balance(&debt_asset), 563);
+    assert_eq!(client.get_bad_debt(&debt_asset), 0);
+    assert_eq!(client.get_protocol_analytics().total_bad_debt, 0);
+}
+
+// =============================================================================
+// SELF-LIQUIDATION TESTS
+// =============================================================================
+
+/// Totals of `asset` as `(supplied, borrowed, reserves)`
+fn asset_totals(client: &HelloContractClient, asset: &Option<Address>) -> (i128, i128, i128) {
+    let totals = client.get_asset_totals(asset);
+    (
+        totals.total_supplied,
+        totals.total_borrowed,
+        totals.total_reserves,
+    )
+}
+
+/// A healthy borrower deleverages out of a deposit in the debt asset, free by
+/// default; with an incentive only the protocol's share of it is charged
+#[test]
+fn test_self_liquidate_healthy_position() {
+    let env = create_test_env();
+    let (contract_id, admin, client) = setup_contract_with_admin(&env);
+    let borrower = Address::generate(&env);
+    let oracle = Address::generate(&env);
+
+    // 1000 of A backs 800 of A
+    let collateral = funded_token(&env, &contract_id, &[&borrower], 1000);
+    let a = Some(collateral.clone());
+    client.deposit_collateral(&borrower, &a, &1000);
+    set_oracle_price(&client, &admin, &collateral, &100, &7, &oracle);
+    client.borrow_asset(&borrower, &a, &800);
+    assert_eq!(client.can_liquidate(&borrower), (false, 11_904));
+
+    let result = client.try_self_liquidate(&borrower, &a, &a, &401);
+    assert_eq!(result, Err(Ok(LiquidationError::ExceedsCloseFactor)));
+    assert_eq!(
+        client.self_liquidate(&borrower, &a, &a, &400),
+        (400, 400, 0)
+    );
+    assert_eq!(client.get_user_debt(&borrower, &a), 400);
+    assert_eq!(
+        client.get_seizable_collateral(&borrower).get_unchecked(0),
+        (a.clone(), 600)
+    );
+    assert_eq!(client.get_health_factor(&borrower), 14_285);
+
+    // The deposit repaid the debt one for one; the reserve gained nothing
+    assert_eq!(asset_totals(&client, &a), (600, 400, 0));
+    assert_eq!(client.get_reserve_balance(&a), 0);
+
+    // The incentive is capped by the liquidation incentive
+    let result = client.try_set_self_liquidation_incentive(&admin, &1_001);
+    assert!(result.is_err());
+    client.set_self_liquidation_incentive(&admin, &1_000);
+    assert_eq!(client.get_self_liquidation_incentive(), 1_000);
+    set_protocol_fee(&client, &admin, 3_000);
+    assert_eq!(
+        client.self_liquidate(&borrower, &a, &a, &200),
+        (200, 206, 20)
+    );
+    let event = last_liquidation_event(&env);
+    assert_eq!(
+        (
+            event.collateral_seized,
+            event.incentive_amount,
+            event.protocol_fee
+        ),
+        (206, 20, 6)
+    );
+
+    // 30% of the 20 incentive goes to the reserve, the rest stays deposited
+    assert_eq!(asset_totals(&client, &a), (394, 200, 6));
+    assert_eq!(client.get_reserve_balance(&a), 6);
+}
+
+/// Repaying from another asset would need a swap, so it is rejected and
+/// leaves both assets' totals and reserves untouched
+#[test]
+fn test_self_liquidate_rejects_cross_asset() {
+    let env = create_test_env();
+    let (client, _admin, borrower, _liquidator, collateral, debt) = setup_underwater_position(&env);
+    let (a, b) = (Some(collateral), Some(debt));
+    assert!(client.can_liquidate(&borrower).0);
+    let totals_a = asset_totals(&client, &a);
+    let totals_b = asset_totals(&client, &b);
+
+    let result = client.try_self_liquidate(&borrower, &b, &a, &400);
+    assert_eq!(result, Err(Ok(LiquidationError::InvalidCollateralAsset)));
+    assert_eq!(client.get_user_debt(&borrower, &b), 800);
+    assert_eq!(
+        client.get_seizable_collateral(&borrower).get_unchecked(0),
+        (a.clone(), 1000)
+    );
+    assert_eq!(asset_totals(&client, &a), totals_a);
+    assert_eq!(asset_totals(&client, &b), totals_b);
+    assert_eq!(client.get_reserve_balance(&a), 0);
+    assert_eq!(client.get_reserve_balance(&b), 0);
+}
+
+/// Deposits disabled as collateral cannot be used to deleverage
+#[test]
+fn test_self_liquidate_rejects_disabled_collateral() {
+    let env = create_test_env();
+    let (contract_id, admin, client) = setup_contract_with_admin(&env);
+    let borrower = Address::generate(&env);
+    let oracle = Address::generate(&env);
+
+    let collateral = funded_token(&env, &contract_id, &[&borrower], 1000);
+    let disabled = funded_token(&env, &contract_id, &[&borrower], 1000);
+    let (a, c) = (Some(collateral.clone()), Some(disabled.clone()));
+    client.deposit_collateral(&borrower, &a, &1000);
+    client.deposit_collateral(&borrower, &c, &1000);
+    for token in [&collateral, &disabled] {
+        set_oracle_price(&client, &admin, token, &100, &7, &oracle);
+    }
+    client.borrow_asset(&borrower, &c, &400);
+    client.set_use_as_collateral(&borrower, &c, &false);
+
+    let result = client.try_self_liquidate(&borrower, &c, &c, &100);
+    assert_eq!(result, Err(Ok(LiquidationError::InvalidCollateralAsset)));
+    assert_eq!(client.get_user_debt(&borrower, &c), 400);
+
+    client.set_use_as_collateral(&borrower, &c, &true);
+    assert_eq!(
+        client.self_liquidate(&borrower, &c, &c, &100),
+        (100, 100, 0)
+    );
+}
+
+/// A breach that recovers within the grace window is cleared and never liquidated
+#[test]
+fn test_grace_window_clears_on_recovery() {
+    let env = create_test_env();
+    let (client, admin, borrower, liquidator, collateral, debt) = setup_underwater_position(&env);
+    let (a, b) = (Some(collateral.clone()), Some(debt));
+    let oracle = Address::generate(&env);
+    client.set_liquidation_grace(&admin, &600, &0);
+    assert_eq!(client.get_liquidation_grace(), (600, 0));
+    assert_eq!(client.can_liquidate(&borrower), (false, 9_523));
+
+    // The window only starts once the breach is recorded
+    let result = client.try_liquidate(&liquidator, &borrower, &b, &a, &400, &false);
+    assert_eq!(result, Err(Ok(LiquidationError::GracePeriodActive)));
+    let breached_at = env.ledger().timestamp();
+    assert_eq!(client.sync_liquidation_breach(&borrower), Some(breached_at));
+
+    advance(&env, 300);
+    assert_eq!(client.sync_liquidation_breach(&borrower), Some(breached_at));
+    for price in [84, 87] {
+        set_oracle_price(&client, &admin, &collateral, &price, &7, &oracle);
+    }
+    assert_eq!(client.sync_liquidation_breach(&borrower), None);
+    assert_eq!(client.get_liquidation_breach(&borrower), None);
+
+    // A later breach starts a fresh window
+    advance(&env, 400);
+    for price in [84, 80] {
+        set_oracle_price(&client, &admin, &collateral, &price, &7, &oracle);
+    }
+    let result = client.try_liquidate(&liquidator, &borrower, &b, &a, &400, &false);
+    assert_eq!(result, Err(Ok(LiquidationError::GracePeriodActive)));
+    assert_eq!(
+        client.sync_liquidation_breach(&borrower),
+        Some(breached_at + 700)
+    );
+}
+
+/// Once the grace window has elapsed the position can be liquidated
+#[test]
+fn test_grace_window_expiry_allows_liquidation() {
+    let env = create_test_env();
+    let (client, admin, borrower, liquidator, collateral, debt) = setup_underwater_position(&env);
+    let (a, b) = (Some(collateral), Some(debt));
+    client.set_liquidation_grace(&admin, &600, &0);
+    let breached_at = client.sync_liquidation_breach(&borrower).unwrap();
+    assert!(client.get_liquidatable_positions(&10, &0).is_empty());
+
+    advance(&env, 599);
+    let result = client.try_liquidate(&liquidator, &borrower, &b, &a, &400, &false);
+    assert_eq!(result, Err(Ok(LiquidationError::GracePeriodActive)));
+
+    advance(&env, 1);
+    assert_eq!(client.can_liquidate(&borrower), (true, 9_523));
+    let (debt_liquidated, collateral_seized, _incentive, _protocol_fee) =
+        client.liquidate(&liquidator, &borrower, &b, &a, &400, &false);
+    assert_eq!((debt_liquidated, collateral_seized), (400, 500));
+
+    // Still below the threshold, so the breach stands
+    assert_eq!(client.get_liquidation_breach(&borrower), Some(breached_at));
+}
+
+/// A stale price blocks liquidation rather than falling back to a default price
+#[test]
+fn test_stale_price_blocks_liquidation() {
+    let env = create_test_env();
+    let (client, admin, borrower, liquidator, collateral, debt) = setup_underwater_position(&env);
+    let (a, b) = (Some(collateral.clone()), Some(debt.clone()));
+    let oracle = Address::generate(&env);
+
+    // Past the default one hour price age, with only the collateral refreshed
+    advance(&env, 3601);
+    set_oracle_price(&client, &admin, &collateral, &80, &7, &oracle);
+    let result = client.try_liquidate(&liquidator, &borrower, &b, &a, &400, &false);
+    assert_eq!(result, Err(Ok(LiquidationError::PriceNotAvailable)));
+    let result = client.try_can_liquidate(&borrower);
+    assert_eq!(result, Err(Ok(LiquidationError::PriceNotAvailable)));
+
+    set_oracle_price(&client, &admin, &debt, &100, &7, &oracle);
+    let (debt_liquidated, _collateral_seized, _incentive, _protocol_fee) =
+        client.liquidate(&liquidator, &borrower, &b, &a, &400, &false);
+    assert_eq!(debt_liquidated, 400);
+}
+
+/// Health factors below the immediate threshold skip the grace window
+#[test]
+fn test_immediate_threshold_bypasses_grace_window() {
+    let env = create_test_env();
+    let (client, admin, borrower, liquidator, collateral, debt) = setup_underwater_position(&env);
+    let (a, b) = (Some(collateral.clone()), Some(debt));
+    let oracle = Address::generate(&env);
+
+    let result = client.try_set_liquidation_grace(&admin, &600, &10_000);
+    assert_eq!(result, Err(Ok(RiskManagementError::InvalidParameter)));
+    let result = client.try_set_liquidation_grace(&borrower, &600, &9_000);
+    assert_eq!(result, Err(Ok(RiskManagementError::Unauthorized)));
+    client.set_liquidation_grace(&admin, &600, &9_000);
+
+    let result = client.try_liquidate(&liquidator, &borrower, &b, &a, &400, &false);
+    assert_eq!(result, Err(Ok(LiquidationError::GracePeriodActive)));
+
+    // No breach was ever recorded, but the crash makes the wait moot
+    for price in [76, 73] {
+        set_oracle_price(&client, &admin, &collateral, &price, &7, &oracle);
+    }
+    let (liquidatable, health_factor) = client.can_liquidate(&borrower);
+    assert!(liquidatable);
+    assert!(health_factor < 9_000);
+    let (debt_liquidated, _collateral_seized, _incentive, _protocol_fee) =
+        client.liquidate(&liquidator, &borrower, &b, &a, &400, &false);
+    assert_eq!(debt_liquidated, 400);
+    assert_eq!(client.get_liquidation_breach(&borrower), None);
+}
+
+/// A borrower cannot be liquidated twice within the cooldown
+#[test]
+fn test_liquidation_cooldown_blocks_same_ledger() {
+    let env = create_test_env();
+    let (client, admin, borrower, liquidator, collateral, debt) = setup_underwater_position(&env);
+    let (a, b) = (Some(collateral), Some(debt));
+    client.set_risk_params(&admin, &None, &None, &None, &None, &Some(1), &None);
+    assert_eq!(
+        client
+            .get_risk_config()
+            .unwrap()
+            .liquidation_cooldown_ledgers,
+        1
+    );
+
+    client.liquidate(&liquidator, &borrower, &b, &a, &200, &false);
+    let result = client.try_liquidate(&liquidator, &borrower, &b, &a, &200, &false);
+    assert_eq!(result, Err(Ok(LiquidationError::LiquidationCooldown)));
+
+    advance_ledgers(&env, 1);
+    let (debt_liquidated, _collateral_seized, _incentive, _protocol_fee) =
+        client.liquidate(&liquidator, &borrower, &b, &a, &200, &false);
+    assert_eq!(debt_liquidated, 200);
+    assert_eq!(client.get_user_debt(&borrower, &b), 400);
+}
+
+/// Health factors below the immediate threshold are not held back by the cooldown
+#[test]
+fn test_liquidation_cooldown_hard_threshold_bypass() {
+    let env = create_test_env();
+    let (client, admin, borrower, liquidator, collateral, debt) = setup_underwater_position(&env);
+    let (a, b) = (Some(collateral.clone()), Some(debt));
+    let oracle = Address::generate(&env);
+    client.set_risk_params(&admin, &None, &None, &None, &None, &Some(10), &None);
+    client.set_liquidation_grace(&admin, &0, &9_000);
+
+    client.liquidate(&liquidator, &borrower, &b, &a, &100, &false);
+    let result = client.try_liquidate(&liquidator, &borrower, &b, &a, &100, &false);
+    assert_eq!(result, Err(Ok(LiquidationError::LiquidationCooldown)));
+
+    for price in [76, 73] {
+        set_oracle_price(&client, &admin, &collateral, &price, &7, &oracle);
+    }
+    assert!(client.get_health_factor(&borrower) < 9_000);
+    let (debt_liquidated, _collateral_seized, _incentive, _protocol_fee) =
+        client.liquidate(&liquidator, &borrower, &b, &a, &100, &false);
+    assert_eq!(debt_liquidated, 100);
+}
+
+/// One call seizes from the listed collateral in order, spilling into the next
+#[test]
+fn test_liquidate_multi_spills_into_next_collateral() {
+    let env = create_test_env();
+    let (contract_id, admin, client) = setup_contract_with_admin(&env);
+    let borrower = Address::generate(&env);
+    let liquidator = Address::generate(&env);
+    let oracle = Address::generate(&env);
+
+    let collateral_a = funded_token(&env, &contract_id, &[&borrower], 1000);
+    let collateral_c = funded_token(&env, &contract_id, &[&borrower], 1000);
+    let debt = funded_token(&env, &contract_id, &[&liquidator], 1000);
+    let (a, c, b) = (
+        Some(collateral_a.clone()),
+        Some(collateral_c.clone()),
+        Some(debt.clone()),
+    );
+    client.deposit_collateral(&borrower, &a, &1000);
+    client.deposit_collateral(&borrower, &c, &1000);
+    for token in [&collateral_a, &collateral_c, &debt] {
+        set_oracle_price(&client, &admin, token, &100, &7, &oracle);
+    }
+    client.borrow_asset(&borrower, &b, &1800);
+    for price in [95, 91, 90] {
+        set_oracle_price(&client, &admin, &collateral_a, &price, &7, &oracle);
+        set_oracle_price(&client, &admin, &collateral_c, &price, &7, &oracle);
+    }
+
+    // 900 of B buys 1000 of A plus a 100 bonus; A covers 818 of it with the
+    // bonus, and the remaining 82 is seized from C as 91 plus 9
+    let mut order = soroban_sdk::Vec::new(&env);
+    order.push_back(a.clone());
+    order.push_back(c.clone());
+    let (debt_liquidated, seized) =
+        client.liquidate_multi(&liquidator, &borrower, &b, &900, &order);
+    assert_eq!(debt_liquidated, 900);
+    assert_eq!(seized.len(), 2);
+    assert_eq!(seized.get_unchecked(0), (a.clone(), 1000));
+    assert_eq!(seized.get_unchecked(1), (c.clone(), 100));
+
+    assert_eq!(client.get_user_debt(&borrower, &b), 900);
+    let remaining = client.get_seizable_collateral(&borrower);
+    assert_eq!(remaining.len(), 1);
+    assert_eq!(remaining.get_unchecked(0), (c, 900));
+    assert_eq!(
+        soroban_sdk::token::Client::new(&env, &collateral_a).balance(&liquidator),
+        1000
+    );
+    assert_eq!(
+        soroban_sdk::token::Client::new(&env, &collateral_c).balance(&liquidator),
+        100
+    );
+    assert_eq!(
+        soroban_sdk::token::Client::new(&env, &debt).balance(&liquidator),
+        100
+    );
+}
+
+/// At least one collateral asset must be named
+#[test]
+fn test_liquidate_multi_rejects_empty_order() {
+    let env = create_test_env();
+    let (client, _admin, borrower, liquidator, _collateral, debt) = setup_underwater_position(&env);
+
+    let order = soroban_sdk::Vec::new(&env);
+    let result = client.try_liquidate_multi(&liquidator, &borrower, &Some(debt), &400, &order);
+    assert_eq!(result, Err(Ok(LiquidationError::EmptyCollateralOrder)));
+}
+
+/// The simulation predicts exactly what the liquidation in the same ledger does
+#[test]
+fn test_simulate_liquidation_matches_outcome() {
+    let env = create_test_env();
+    let (client, admin, borrower, liquidator, collateral, debt) = setup_underwater_position(&env);
+    let (a, b) = (Some(collateral.clone()), Some(debt));
+    set_five_percent_incentive(&client, &admin);
+    set_protocol_fee(&client, &admin, 1_000);
+
+    let rejected = client.simulate_liquidation(&liquidator, &borrower, &b, &a, &401);
+    assert!(!rejected.would_succeed);
+    assert_eq!(
+        rejected.error_code,
+        LiquidationError::ExceedsCloseFactor as u32
+    );
+    assert_eq!(rejected.debt_repaid, 0);
+
+    let simulation = client.simulate_liquidation(&liquidator, &borrower, &b, &a, &400);
+    assert!(simulation.would_succeed);
+    assert_eq!(simulation.error_code, 0);
+    assert_eq!(client.get_user_debt(&borrower, &b), 800);
+
+    let (debt_liquidated, collateral_seized, incentive, _protocol_fee) =
+        client.liquidate(&liquidator, &borrower, &b, &a, &400, &false);
+    assert_eq!(simulation.debt_repaid, debt_liquidated);
+    assert_eq!(simulation.collateral_seized, collateral_seized);
+    assert_eq!(simulation.incentive_amount, incentive);
+    assert_eq!(simulation.protocol_fee, client.get_reserve_balance(&a));
+    assert_eq!(
+        soroban_sdk::token::Client::new(&env, &collateral).balance(&liquidator),
+        simulation.collateral_seized - simulation.protocol_fee
+    );
+    assert_eq!(
+        (
+            simulation.debt_repaid,
+            simulation.collateral_seized,
+            simulation.incentive_amount,
+            simulation.protocol_fee
+        ),
+        (400, 525, 25, 2)
+    );
+}
+
+/// While the allowlist is on only listed liquidators may liquidate, and removal
+/// is immediate
+#[test]
+fn test_liquidator_allowlist() {
+    let env = create_test_env();
+    let (client, admin, borrower, liquidator, collateral, debt) = setup_underwater_position(&env);
+    let (a, b) = (Some(collateral), Some(debt));
+    assert!(!client.is_liquidator_allowlist_enabled());
+
+    client.set_liquidator_allowlist_enabled(&admin, &true);
+    let result = client.try_liquidate(&liquidator, &borrower, &b, &a, &100, &false);
+    assert_eq!(result, Err(Ok(LiquidationError::LiquidatorNotAllowed)));
+
+    client.add_liquidator(&admin, &liquidator);
+    assert!(client.is_allowed_liquidator(&liquidator));
+    client.liquidate(&liquidator, &borrower, &b, &a, &100, &false);
+
+    client.remove_liquidator(&admin, &liquidator);
+    assert!(!client.is_allowed_liquidator(&liquidator));
+    let result = client.try_liquidate(&liquidator, &borrower, &b, &a, &100, &false);
+    assert_eq!(result, Err(Ok(LiquidationError::LiquidatorNotAllowed)));
+
+    // Turning the allowlist off opens liquidations to everyone again
+    client.set_liquidator_allowlist_enabled(&admin, &false);
+    let (debt_liquidated, _collateral_seized, _incentive, _protocol_fee) =
+        client.liquidate(&liquidator, &borrower, &b, &a, &100, &false);
+    assert_eq!(debt_liquidated, 100);
+}
+
+/// Only the admin can manage the liquidator allowlist
+#[test]
+fn test_liquidator_allowlist_admin_only() {
+    let env = create_test_env();
+    let (client, _admin, borrower, liquidator, _collateral, _debt) =
+        setup_underwater_position(&env);
+
+    let result = client.try_set_liquidator_allowlist_enabled(&liquidator, &true);
+    assert_eq!(result, Err(Ok(RiskManagementError::Unauthorized)));
+    let result = client.try_add_liquidator(&liquidator, &liquidator);
+    assert_eq!(result, Err(Ok(RiskManagementError::Unauthorized)));
+    let result = client.try_remove_liquidator(&borrower, &liquidator);
+    assert_eq!(result, Err(Ok(RiskManagementError::Unauthorized)));
+    assert!(!client.is_liquidator_allowlist_enabled());
+    assert!(!client.is_allowed_liquidator(&liquidator));
+}
+
+/// Mints 400 of `token` to the admin and lets the contract pull it
+fn fund_admin(env: &Env, client: &HelloContractClient, admin: &Address, token: &Address) {
+    soroban_sdk::token::StellarAssetClient::new(env, token).mint(admin, &400);
+    soroban_sdk::token::Client::new(env, token).approve(
+        admin,
+        &client.address,
+        &400,
+        &(env.ledger().sequence() + 100),
+    );
+}
+
+/// The liquidation switch and the emergency pause each block liquidations with
+/// their own error, and the admin can force one through either
+#[test]
+fn test_force_liquidate_overrides_pauses() {
+    let env = create_test_env();
+    let (client, admin, borrower, liquidator, collateral, debt) = setup_underwater_position(&env);
+    let (a, b) = (Some(collateral.clone()), Some(debt.clone()));
+    fund_admin(&env, &client, &admin, &debt);
+
+    client.set_pause_switch(&admin, &Symbol::new(&env, "pause_liquidate"), &true);
+    let result = client.try_liquidate(&liquidator, &borrower, &b, &a, &100, &false);
+    assert_eq!(result, Err(Ok(LiquidationError::LiquidationPaused)));
+    let (debt_liquidated, collateral_seized, _incentive, _protocol_fee) =
+        client.force_liquidate(&admin, &borrower, &b, &a, &100);
+    assert_eq!(debt_liquidated, 100);
+    assert_eq!(
+        soroban_sdk::token::Client::new(&env, &collateral).balance(&admin),
+        collateral_seized
+    );
+
+    client.set_emergency_pause(&admin, &true);
+    let result = client.try_liquidate(&liquidator, &borrower, &b, &a, &100, &false);
+    assert_eq!(result, Err(Ok(LiquidationError::EmergencyPaused)));
+    client.set_pause_switch(&admin, &Symbol::new(&env, "pause_liquidate"), &false);
+    let result = client.try_liquidate(&liquidator, &borrower, &b, &a, &100, &false);
+    assert_eq!(result, Err(Ok(LiquidationError::EmergencyPaused)));
+    client.force_liquidate(&admin, &borrower, &b, &a, &100);
+    assert_eq!(client.get_user_debt(&borrower, &b), 600);
+
+    let (topics, event) = env
+        .events()
+        .all()
+        .iter()
+        .rev()
+        .find_map(|(_c, topics, data)| {
+            TestForcedLiquidationEvent::try_from_val(&env, &data)
+                .ok()
+                .map(|event| (topics, event))
+        })
+        .expect("forced liquidation event not emitted");
+    let topic = Symbol::try_from_val(&env, &topics.get_unchecked(0)).unwrap();
+    assert_eq!(topic, Symbol::new(&env, "forced_liquidation_event"));
+    assert_eq!(event.admin, admin);
+    assert_eq!(event.debt_liquidated, 100);
+    assert!(event.emergency_paused);
+    assert!(!event.operation_paused);
+
+    // With both pauses lifted anyone can liquidate again
+    client.set_emergency_pause(&admin, &false);
+    let (debt_liquidated, _collateral_seized, _incentive, _protocol_fee) =
+        client.liquidate(&liquidator, &borrower, &b, &a, &100, &false);
+    assert_eq!(debt_liquidated, 100);
+}
+
+/// Only the admin can force a liquidation, and a forced one still needs an
+/// unhealthy position
+#[test]
+fn test_force_liquidate_admin_only() {
+    let env = create_test_env();
+    let (client, admin, borrower, liquidator, collateral, debt) = setup_underwater_position(&env);
+    let (a, b) = (Some(collateral), Some(debt));
+    client.set_emergency_pause(&admin, &true);
+
+    let result = client.try_force_liquidate(&liquidator, &borrower, &b, &a, &100);
+    assert_eq!(result, Err(Ok(LiquidationError::Unauthorized)));
+    assert_eq!(client.get_user_debt(&borrower, &b), 800);
+
+    let healthy = Address::generate(&env);
+    let result = client.try_force_liquidate(&admin, &healthy, &b, &a, &100);
+    assert_eq!(result, Err(Ok(LiquidationError::PositionHealthy)));
+}
+
+#[contracttype]
+enum MockAmmKey {
+    RateBps,
+}
+
+/// AMM that pulls the amount swapped and pays `rate_bps` of it in the target
+/// token, from its own balance
+#[contract]
+pub struct MockSwapAmm;
+
+#[contractimpl]
+impl MockSwapAmm {
+    pub fn set_rate_bps(env: Env, rate_bps: i128) {
+        env.storage()
+            .instance()
+            .set(&MockAmmKey::RateBps, &rate_bps);
+    }
+
+    pub fn auto_swap_for_collateral(
+        env: Env,
+        user: Address,
+        token_in: Option<Address>,
+        target_token: Option<Address>,
+        amount: i128,
+    ) -> i128 {
+        let rate_bps: i128 = env.storage().instance().get(&MockAmmKey::RateBps).unwrap();
+        let amount_out = amount * rate_bps / 10_000;
+        let this = env.current_contract_address();
+        soroban_sdk::token::Client::new(&env, &token_in.unwrap()).transfer(&user, &this, &amount);
+        soroban_sdk::token::Client::new(&env, &target_token.unwrap()).transfer(
+            &this,
+            &user,
+            &amount_out,
+        );
+        amount_out
+    }
+}
+
+/// The seized collateral is swapped into the debt asset for the liquidator in
+/// the same call, and a swap short of `min_out` undoes the whole liquidation
+#[test]
+fn test_liquidate_and_swap_pays_liquidator_in_debt_asset() {
+    let env = create_test_env();
+    let (client, admin, borrower, liquidator, collateral, debt) = setup_underwater_position(&env);
+    set_five_percent_incentive(&client, &admin);
+    let amm = env.register(MockSwapAmm, ());
+    client.set_liquidation_amm(&admin, &amm);
+    MockSwapAmmClient::new(&env, &amm).set_rate_bps(&8_000);
+    soroban_sdk::token::StellarAssetClient::new(&env, &debt).mint(&amm, &1_000);
+    let (a, b) = (Some(collateral.clone()), Some(debt.clone()));
+    let debt_token = soroban_sdk::token::Client::new(&env, &debt);
+    let collateral_token = soroban_sdk::token::Client::new(&env, &collateral);
+
+    // 400 of B seizes 525 of A, which the AMM turns into 420 of B
+    let result = client.try_liquidate_and_swap(&liquidator, &borrower, &b, &a, &400, &amm, &421);
+    assert_eq!(result, Err(Ok(LiquidationError::SlippageExceeded)));
+    assert_eq!(client.get_user_debt(&borrower, &b), 800);
+    assert_eq!(debt_token.balance(&liquidator), 1_000);
+
+    let (debt_liquidated, collateral_seized, _incentive, protocol_fee, amount_out) =
+        client.liquidate_and_swap(&liquidator, &borrower, &b, &a, &400, &amm, &420);
+    assert_eq!(
+        (debt_liquidated, collateral_seized, protocol_fee, amount_out),
+        (400, 525, 0, 420)
+    );
+    assert_eq!(client.get_user_debt(&borrower, &b), 400);
+    assert_eq!(debt_token.balance(&liquidator), 1_000 - 400 + 420);
+    assert_eq!(collateral_token.balance(&liquidator), 0);
+    assert_eq!(collateral_token.balance(&amm), 525);
+}
+
+/// Through the protocol's own AMM contract the seized collateral really changes
+/// hands: the AMM pulls it from this contract and pays the debt asset out of its
+/// reserves
+#[test]
+fn test_liquidate_and_swap_through_amm_contract() {
+    let env = create_test_env();
+    let (client, admin, borrower, liquidator, collateral, debt) = setup_underwater_position(&env);
+    set_five_percent_incentive(&client, &admin);
+    let amm = env.register(stellarlend_amm::AmmContract, ());
+    let amm_client = stellarlend_amm::AmmContractClient::new(&env, &amm);
+    amm_client.initialize_amm_settings(&admin, &100, &1_000, &100);
+    let mut supported_pairs = soroban_sdk::Vec::new(&env);
+    supported_pairs.push_back(stellarlend_amm::TokenPair {
+        token_a: Some(collateral.clone()),
+        token_b: Some(debt.clone()),
+        pool_address: Address::generate(&env),
+    });
+    amm_client.add_amm_protocol(
+        &admin,
+        &stellarlend_amm::AmmProtocolConfig {
+            protocol_address: Address::generate(&env),
+            protocol_name: Symbol::new(&env, "LiqAMM"),
+            enabled: true,
+            fee_tier: 30,
+            min_swap_amount: 1,
+            max_swap_amount: 1_000_000,
+            supported_pairs,
+        },
+    );
+    soroban_sdk::token::StellarAssetClient::new(&env, &debt).mint(&amm, &1_000);
+    client.set_liquidation_amm(&admin, &amm);
+    let (a, b) = (Some(collateral.clone()), Some(debt.clone()));
+    let debt_token = soroban_sdk::token::Client::new(&env, &debt);
+    let collateral_token = soroban_sdk::token::Client::new(&env, &collateral);
+    let pool_collateral = collateral_token.balance(&client.address);
+
+    // 400 of B seizes 525 of A, which the AMM sells for 525 less 1% slippage
+    let (debt_liquidated, collateral_seized, _incentive, protocol_fee, amount_out) =
+        client.liquidate_and_swap(&liquidator, &borrower, &b, &a, &400, &amm, &519);
+    assert_eq!(
+        (debt_liquidated, collateral_seized, protocol_fee, amount_out),
+        (400, 525, 0, 519)
+    );
+    assert_eq!(client.get_user_debt(&borrower, &b), 400);
+    assert_eq!(debt_token.balance(&liquidator), 1_000 - 400 + 519);
+    assert_eq!(debt_token.balance(&amm), 1_000 - 519);
+    assert_eq!(collateral_token.balance(&amm), 525);
+    assert_eq!(collateral_token.balance(&liquidator), 0);
+    assert_eq!(
+        collateral_token.balance(&client.address),
+        pool_collateral - 525
+    );
+}
+
+/// Both assets must be distinct tokens and the AMM the registered one
+#[test]
+fn test_liquidate_and_swap_rejections() {
+    let env = create_test_env();
+    let (client, admin, borrower, liquidator, collateral, debt) = setup_underwater_position(&env);
+    let amm = env.register(MockSwapAmm, ());
+    let (a, b) = (Some(collateral), Some(debt));
+
+    let result = client.try_liquidate_and_swap(&liquidator, &borrower, &b, &a, &400, &amm, &0);
+    assert_eq!(result, Err(Ok(LiquidationError::InvalidAmount)));
+    let result = client.try_liquidate_and_swap(&liquidator, &borrower, &b, &b, &400, &amm, &1);
+    assert_eq!(result, Err(Ok(LiquidationError::InvalidCollateralAsset)));
+    let result = client.try_liquidate_and_swap(&liquidator, &borrower, &None, &a, &400, &amm, &1);
+    assert_eq!(result, Err(Ok(LiquidationError::InvalidDebtAsset)));
+
+    // Only the AMM the admin registered may receive seized collateral
+    let result = client.try_liquidate_and_swap(&liquidator, &borrower, &b, &a, &400, &amm, &1);
+    assert_eq!(result, Err(Ok(LiquidationError::SwapFailed)));
+    let result = client.try_set_liquidation_amm(&liquidator, &amm);
+    assert_eq!(result, Err(Ok(RiskManagementError::Unauthorized)));
+    let result = client.try_set_liquidation_amm(&admin, &client.address);
+    assert_eq!(result, Err(Ok(RiskManagementError::InvalidParameter)));
+    client.set_liquidation_amm(&admin, &amm);
+    assert_eq!(client.get_liquidation_amm(), Some(amm.clone()));
+    let other_amm = env.register(MockSwapAmm, ());
+    let result =
+        client.try_liquidate_and_swap(&liquidator, &borrower, &b, &a, &400, &other_amm, &1);
+    assert_eq!(result, Err(Ok(LiquidationError::SwapFailed)));
+    assert_eq!(client.get_user_debt(&borrower, &b), 800);
+}
+
+/// Nobody can liquidate and swap with another keeper's funds
+#[test]
+fn test_liquidate_and_swap_requires_liquidator_auth() {
+    let env = create_test_env();
+    let (client, admin, borrower, liquidator, collateral, debt) = setup_underwater_position(&env);
+    let amm = env.register(MockSwapAmm, ());
+    client.set_liquidation_amm(&admin, &amm);
+    let (a, b) = (Some(collateral), Some(debt));
+
+    env.mock_auths(&[]);
+    let result = client.try_liquidate_and_swap(&liquidator, &borrower, &b, &a, &400, &amm, &1);
+    assert!(result.is_err());
+    assert_eq!(client.get_user_debt(&borrower, &b), 800);
+}
+
+/// Asset parameters carrying only a liquidity score
+fn liquidity_score_params(liquidity_score: u32) -> AssetParams {
+    AssetParams {
+        liquidity_score,
+        ..asset_params(7500)
+    }
+}
+
+/// Auto mode seizes the deepest collateral first and moves on once it is gone,
+/// while a named collateral asset still takes precedence
+#[test]
+fn test_auto_collateral_seizes_highest_liquidity_score_first() {
+    let env = create_test_env();
+    let (contract_id, admin, client) = setup_contract_with_admin(&env);
+    let borrower = Address::generate(&env);
+    let liquidator = Address::generate(&env);
+    let oracle = Address::generate(&env);
+
+    let shallow = funded_token(&env, &contract_id, &[&borrower], 1000);
+    let deep = funded_token(&env, &contract_id, &[&borrower], 1000);
+    let medium = funded_token(&env, &contract_id, &[&borrower], 1000);
+    let debt = funded_token(&env, &contract_id, &[&liquidator], 1000);
+    let b = Some(debt.clone());
+    for token in [&shallow, &deep, &medium] {
+        client.deposit_collateral(&borrower, &Some(token.clone()), &1000);
+        set_oracle_price(&client, &admin, token, &100, &7, &oracle);
+    }
+    set_oracle_price(&client, &admin, &debt, &100, &7, &oracle);
+    client.borrow_asset(&borrower, &b, &2700);
+    for price in [95, 91, 90] {
+        for token in [&shallow, &deep, &medium] {
+            set_oracle_price(&client, &admin, token, &price, &7, &oracle);
+        }
+    }
+    for (token, score) in [(&shallow, 10), (&deep, 50), (&medium, 30)] {
+        client.update_asset_params(&admin, token, &liquidity_score_params(score));
+    }
+
+    let order = client.get_auto_collateral_order(&borrower);
+    assert_eq!(order.len(), 3);
+    assert_eq!(order.get_unchecked(0), Some(deep.clone()));
+    assert_eq!(order.get_unchecked(1), Some(medium.clone()));
+    assert_eq!(order.get_unchecked(2), Some(shallow.clone()));
+
+    // The first auto liquidation drains the deepest asset
+    let balance =
+        |token: &Address| soroban_sdk::token::Client::new(&env, token).balance(&liquidator);
+    client.liquidate(&liquidator, &borrower, &b, &None, &900, &true);
+    assert_eq!(balance(&deep), 1000);
+    assert_eq!(balance(&medium), 0);
+    assert_eq!(balance(&shallow), 0);
+
+    // With it gone the next one moves to the medium asset
+    let (_debt_liquidated, collateral_seized, _incentive, _protocol_fee) =
+        client.liquidate(&liquidator, &borrower, &b, &None, &50, &true);
+    assert_eq!(balance(&medium), collateral_seized);
+    assert_eq!(balance(&shallow), 0);
+
+    // A named asset overrides auto mode
+    let (_debt_liquidated, collateral_seized, _incentive, _protocol_fee) = client.liquidate(
+        &liquidator,
+        &borrower,
+        &b,
+        &Some(shallow.clone()),
+        &50,
+        &true,
+    );
+    assert_eq!(balance(&shallow), collateral_seized);
+}
+
+/// Each liquidation records the position it was allowed on
+#[test]
+fn test_liquidation_snapshot_matches_pre_state() {
+    let env = create_test_env();
+    let (client, _admin, borrower, liquidator, collateral, debt) = setup_underwater_position(&env);
+    let health_factor = client.get_health_factor(&borrower);
+    assert_eq!(client.get_liquidation_count(), 0);
+
+    client.liquidate(
+        &liquidator,
+        &borrower,
+        &Some(debt.clone()),
+        &Some(collateral.clone()),
+        &400,
+        &false,
+    );
+
+    assert_eq!(client.get_liquidation_count(), 1);
+    let snapshot = client.get_liquidation_snapshot(&1).unwrap();
+    let now = env.ledger().timestamp();
+    assert_eq!(snapshot.id, 1);
+    assert_eq!(snapshot.borrower, borrower);
+    assert_eq!(snapshot.health_factor, health_factor);
+    assert_eq!(snapshot.timestamp, now);
+    assert_eq!(snapshot.collateral.len(), 1);
+    assert_eq!(
+        snapshot.collateral.get_unchecked(0),
+        AssetSnapshot {
+            asset: Some(collateral),
+            balance: 1000,
+            price: 80,
+            price_updated_at: now,
+        }
+    );
+    assert_eq!(snapshot.debt.len(), 1);
+    assert_eq!(
+        snapshot.debt.get_unchecked(0),
+        AssetSnapshot {
+            asset: Some(debt),
+            balance: 800,
+            price: 100,
+            price_updated_at: now,
+        }
+    );
+    assert_eq!(client.get_liquidation_snapshot(&0), None);
+    assert_eq!(client.get_liquidation_snapshot(&2), None);
+}
+
+/// Only the latest `capacity` snapshots are kept
+#[test]
+fn test_liquidation_snapshot_ring_buffer_evicts_oldest() {
+    let env = create_test_env();
+    let (client, admin, borrower, liquidator, collateral, debt) = setup_underwater_position(&env);
+    assert_eq!(client.get_liquidation_snapshot_capacity(), 100);
+
+    let result = client.try_set_liquidation_snapshot_capacity(&liquidator, &2);
+    assert_eq!(result, Err(Ok(RiskManagementError::Unauthorized)));
+    let result = client.try_set_liquidation_snapshot_capacity(&admin, &0);
+    assert_eq!(result, Err(Ok(RiskManagementError::InvalidParameter)));
+    client.set_liquidation_snapshot_capacity(&admin, &2);
+    assert_eq!(client.get_liquidation_snapshot_capacity(), 2);
+
+    let debt_asset = Some(debt);
+    let collateral_asset = Some(collateral);
+    for _ in 0..3 {
+        client.liquidate(
+            &liquidator,
+            &borrower,
+            &debt_asset,
+            &collateral_asset,
+            &100,
+            &false,
+        );
+    }
+
+    assert_eq!(client.get_liquidation_count(), 3);
+    assert_eq!(client.get_liquidation_snapshot(&1), None);
+    assert_eq!(client.get_liquidation_snapshot(&2).unwrap().id, 2);
+    let latest = client.get_liquidation_snapshot(&3).unwrap();
+    assert_eq!(latest.id, 3);
+    assert_eq!(latest.debt.get_unchecked(0).balance, 600);
+}
+
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct TestLiquidationRewardTopUpEvent {
+    pub liquidator: Address,
+    pub borrower: Address,
+    pub collateral_asset: Option<Address>,
+    pub incentive_amount: i128,
+    pub top_up_amount: i128,
+    pub min_reward: i128,
+    pub timestamp: u64,
+}
+
+fn last_top_up_event(env: &Env) -> Option<TestLiquidationRewardTopUpEvent> {
+    env.events()
+        .all()
+        .iter()
+        .rev()
+        .find_map(|(_c, _topics, data)| {
+            TestLiquidationRewardTopUpEvent::try_from_val(env, &data).ok()
+        })
+}
+
+/// Donate `amount` of `asset` to its protocol reserve
+fn fund_reserve(env: &Env, client: &HelloContractClient, asset: &Address, amount: i128) {
+    let donor = Address::generate(env);
+    soroban_sdk::token::StellarAssetClient::new(env, asset).mint(&donor, &amount);
+    soroban_sdk::token::Client::new(env, asset).approve(
+        &donor,
+        &client.address,
+        &amount,
+        &(env.ledger().sequence() + 100),
+    );
+    client.donate_to_reserves(&donor, &Some(asset.clone()), &amount);
+}
+
+/// 40 of B buys 50 of A with a 2 A bonus, worth 160; a floor of 800 adds 8 A
+#[test]
+fn test_min_liquidation_reward_tops_up_small_liquidation() {
+    let env = create_test_env();
+    let (client, admin, borrower, liquidator, collateral, debt) = setup_underwater_position(&env);
+    set_five_percent_incentive(&client, &admin);
+    fund_reserve(&env, &client, &collateral, 100);
+
+    let result = client.try_set_min_liquidation_reward(&liquidator, &800);
+    assert_eq!(result, Err(Ok(RiskManagementError::Unauthorized)));
+    let result = client.try_set_min_liquidation_reward(&admin, &-1);
+    assert_eq!(result, Err(Ok(RiskManagementError::InvalidParameter)));
+    client.set_min_liquidation_reward(&admin, &800);
+    assert_eq!(client.get_min_liquidation_reward(), 800);
+
+    let a = Some(collateral.clone());
+    let (debt_liquidated, collateral_seized, incentive_amount, _protocol_fee) =
+        client.liquidate(&liquidator, &borrower, &Some(debt), &a, &40, &false);
+    assert_eq!(debt_liquidated, 40);
+    assert_eq!(collateral_seized, 52);
+    assert_eq!(incentive_amount, 2);
+
+    let event = last_top_up_event(&env).expect("top-up event not emitted");
+    assert_eq!(event.liquidator, liquidator);
+    assert_eq!(event.borrower, borrower);
+    assert_eq!(event.collateral_asset, a);
+    assert_eq!(event.incentive_amount, 2);
+    assert_eq!(event.top_up_amount, 8);
+    assert_eq!(event.min_reward, 800);
+
+    let collateral_token = soroban_sdk::token::Client::new(&env, &collateral);
+    assert_eq!(collateral_token.balance(&liquidator), 60);
+    assert_eq!(client.get_reserve_balance(&a), 92);
+}
+
+/// A bonus already worth the floor is paid without a top-up
+#[test]
+fn test_min_liquidation_reward_skips_large_liquidation() {
+    let env = create_test_env();
+    let (client, admin, borrower, liquidator, collateral, debt) = setup_underwater_position(&env);
+    set_five_percent_incentive(&client, &admin);
+    fund_reserve(&env, &client, &collateral, 100);
+    client.set_min_liquidation_reward(&admin, &800);
+
+    // 400 of B earns a 25 A bonus, worth 2000
+    let a = Some(collateral.clone());
+    client.liquidate(&liquidator, &borrower, &Some(debt), &a, &400, &false);
+    assert!(last_top_up_event(&env).is_none());
+
+    let collateral_token = soroban_sdk::token::Client::new(&env, &collateral);
+    assert_eq!(collateral_token.balance(&liquidator), 525);
+    assert_eq!(client.get_reserve_balance(&a), 100);
+}
+
+/// The top-up stops at what the reserve holds, and an empty reserve pays nothing
+#[test]
+fn test_min_liquidation_reward_capped_by_reserve() {
+    let env = create_test_env();
+    let (client, admin, borrower, liquidator, collateral, debt) = setup_underwater_position(&env);
+    set_five_percent_incentive(&client, &admin);
+    fund_reserve(&env, &client, &collateral, 5);
+    client.set_min_liquidation_reward(&admin, &800);
+
+    let (a, b) = (Some(collateral.clone()), Some(debt));
+    let collateral_token = soroban_sdk::token::Client::new(&env, &collateral);
+    client.liquidate(&liquidator, &borrower, &b, &a, &40, &false);
+    assert_eq!(last_top_up_event(&env).unwrap().top_up_amount, 5);
+    assert_eq!(collateral_token.balance(&liquidator), 57);
+    assert_eq!(client.get_reserve_balance(&a), 0);
+
+    client.liquidate(&liquidator, &borrower, &b, &a, &40, &false);
+    assert!(last_top_up_event(&env).is_none());
+    assert_eq!(collateral_token.balance(&liquidator), 109);
+}
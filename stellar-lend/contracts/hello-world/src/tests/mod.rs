@@ -1,25 +1,38 @@
 pub mod admin_test;
 pub mod analytics_test;
 pub mod asset_config_test;
+pub mod asset_totals_test;
 pub mod borrow_test;
+pub mod collateral_toggle_test;
 pub mod config_test;
 pub mod deploy_test;
 pub mod edge_cases_test;
+pub mod emode_test;
 pub mod events_test;
 pub mod integration_test;
 pub mod interest_accrual_test;
 pub mod interest_rate_test;
+pub mod isolation_test;
 pub mod liquidate_test;
 pub mod oracle_test;
 pub mod pause_test;
+pub mod rate_mode_test;
 pub mod recovery_multisig_test;
+pub mod reentrancy_test;
+pub mod referral_test;
 pub mod repay_test;
+pub mod repayment_schedule_test;
 pub mod risk_params_test;
 pub mod security_test;
+pub mod term_loan_test;
 pub mod test;
 pub mod test_helpers;
+pub mod ttl_test;
 pub mod views_test;
+pub mod withdraw_queue_test;
+pub mod withdraw_schedule_test;
 pub mod withdraw_test;
+pub mod write_off_test;
 // Cross-asset tests disabled - contract methods not yet implemented
 pub mod governance_test;
 pub mod views_test;
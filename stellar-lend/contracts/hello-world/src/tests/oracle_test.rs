@@ -7,28 +7,41 @@
 //! - Price deviation validation
 //! - Price caching with TTL
 //! - Fallback oracle support with separate storage
+//! - Median of multiple oracle sources with a minimum quorum
 //! - Admin authorization and oracle registration
 //! - Edge cases and security scenarios
 //!
 //! # Security Assumptions
 //! - Only admin can configure oracles and system parameters.
-//! - Only registered primary/fallback oracles or admin can update prices.
+//! - Only oracles the admin registered for an asset can update its price.
 //! - Price deviation checks prevent flash crash/manipulation attacks.
 //! - Stale prices are rejected to prevent using outdated market data.
 //!
 //! # Test Scenarios
-//! - `test_update_price_feed_success`: Basic price update by admin.
+//! - `test_update_price_feed_success`: Basic price update by a registered oracle.
 //! - `test_update_price_feed_by_oracle`: Update by registered primary oracle.
 //! - `test_update_price_feed_malicious_caller`: Rejection of unauthorized oracle.
+//! - `test_oracle_registry_*`: Registered, revoked and never-registered callers, and
+//!   admin-only registry management.
+//! - `test_multi_source_*`: Median of three sources, a stale source left out, the quorum
+//!   of fresh sources, and the limits on quorum and sources.
 //! - `test_get_price_with_successful_fallback`: Fallback to secondary source when primary is stale.
+//! - `test_failover_*`: Primary served while usable, fallback served and announced when the
+//!   primary is stale, and an error naming both failures when neither is usable.
+//! - `test_get_price_stale_error_and_unchecked_read`: `StalePrice` past the maximum age, and
+//!   the unchecked diagnostic read.
 //! - `test_price_deviation_*`: Validation of price change limits.
 //! - `test_cache_*`: Validation of price caching and TTL.
 
-use crate::oracle::{CachedPrice, OracleConfig, OracleDataKey, PriceFeed};
+use crate::oracle::{
+    CachedPrice, OracleConfig, OracleDataKey, OracleError, PriceFeed, PriceSourceFailure,
+};
+use crate::tests::test_helpers::set_oracle_price;
 use crate::{HelloContract, HelloContractClient};
 use soroban_sdk::{
-    testutils::{Address as _, Ledger},
-    Address, Env, Map, Symbol,
+    contracttype,
+    testutils::{Address as _, Events, Ledger},
+    vec, Address, Env, Map, Symbol, TryFromVal,
 };
 
 // =============================================================================
@@ -88,7 +101,7 @@ fn set_oracle_paused(env: &Env, contract_id: &Address, paused: bool) {
 // BASIC FUNCTIONALITY TESTS
 // =============================================================================
 
-/// Test successful price feed update by admin
+/// Test successful price feed update by a registered oracle
 #[test]
 fn test_update_price_feed_success() {
     let env = create_test_env();
@@ -99,7 +112,7 @@ fn test_update_price_feed_success() {
     let price = 100_000_000i128; // $1.00 with 8 decimals
     let decimals = 8u32;
 
-    let result = client.update_price_feed(&admin, &asset, &price, &decimals, &oracle);
+    let result = set_oracle_price(&client, &admin, &asset, &price, &decimals, &oracle);
     assert_eq!(result, price);
 
     // Verify price feed was stored
@@ -118,7 +131,7 @@ fn test_get_price_success() {
     let oracle = Address::generate(&env);
 
     let price = 250_000_000i128; // $2.50 with 8 decimals
-    client.update_price_feed(&admin, &asset, &price, &8, &oracle);
+    set_oracle_price(&client, &admin, &asset, &price, &8, &oracle);
 
     let retrieved_price = client.get_price(&asset);
     assert_eq!(retrieved_price, price);
@@ -135,13 +148,13 @@ fn test_update_price_feed_by_oracle() {
     // Set primary oracle
     client.set_primary_oracle(&admin, &asset, &oracle);
 
-    // First update by admin
+    // First update registers the oracle
     let initial_price = 100_000_000i128;
-    client.update_price_feed(&admin, &asset, &initial_price, &8, &oracle);
+    set_oracle_price(&client, &admin, &asset, &initial_price, &8, &oracle);
 
     // Second update by oracle itself
     let new_price = 101_000_000i128;
-    let result = client.update_price_feed(&oracle, &asset, &new_price, &8, &oracle);
+    let result = client.update_price_feed(&oracle, &asset, &new_price, &8);
     assert_eq!(result, new_price);
 }
 
@@ -158,7 +171,7 @@ fn test_update_price_feed_zero_price() {
     let asset = Address::generate(&env);
     let oracle = Address::generate(&env);
 
-    client.update_price_feed(&admin, &asset, &0, &8, &oracle);
+    set_oracle_price(&client, &admin, &asset, &0, &8, &oracle);
 }
 
 /// Test negative price rejection
@@ -170,7 +183,7 @@ fn test_update_price_feed_negative_price() {
     let asset = Address::generate(&env);
     let oracle = Address::generate(&env);
 
-    client.update_price_feed(&admin, &asset, &(-100_000_000), &8, &oracle);
+    set_oracle_price(&client, &admin, &asset, &(-100_000_000), &8, &oracle);
 }
 
 /// Test unauthorized caller rejection
@@ -180,10 +193,9 @@ fn test_update_price_feed_unauthorized() {
     let env = create_test_env();
     let (_contract_id, _admin, client) = setup_contract_with_admin(&env);
     let asset = Address::generate(&env);
-    let oracle = Address::generate(&env);
     let unauthorized = Address::generate(&env);
 
-    client.update_price_feed(&unauthorized, &asset, &100_000_000, &8, &oracle);
+    client.update_price_feed(&unauthorized, &asset, &100_000_000, &8);
 }
 
 /// Test malicious update where caller passes themselves as oracle
@@ -198,12 +210,119 @@ fn test_update_price_feed_malicious_caller() {
 
     // Establish primary oracle first
     client.set_primary_oracle(&admin, &asset, &oracle);
-    client.update_price_feed(&admin, &asset, &100_000_000, &8, &oracle);
+    set_oracle_price(&client, &admin, &asset, &100_000_000, &8, &oracle);
 
-    // Malicious user tries to overwrite price by claiming to be a new oracle for this asset
-    // This now fails because malicious user is not authorized.
+    // Malicious user tries to overwrite the price without being a registered oracle.
     // Use a price within deviation range to ensure it fails due to authorization, not deviation.
-    client.update_price_feed(&malicious, &asset, &101_000_000, &8, &malicious);
+    client.update_price_feed(&malicious, &asset, &101_000_000, &8);
+}
+
+// =============================================================================
+// ORACLE REGISTRY TESTS
+// =============================================================================
+
+/// Test a registered oracle can submit prices and is listed once
+#[test]
+fn test_oracle_registry_registered_caller() {
+    let env = create_test_env();
+    let (contract_id, admin, client) = setup_contract_with_admin(&env);
+    let asset = Address::generate(&env);
+    let oracle = Address::generate(&env);
+
+    client.register_oracle(&admin, &asset, &oracle);
+    client.register_oracle(&admin, &asset, &oracle);
+    assert_eq!(
+        client.get_registered_oracles(&asset),
+        vec![&env, oracle.clone()]
+    );
+
+    assert_eq!(
+        client.update_price_feed(&oracle, &asset, &100_000_000, &8),
+        100_000_000
+    );
+    assert_eq!(client.get_price(&asset), 100_000_000);
+    let stored_feed = get_price_feed(&env, &contract_id, &asset).unwrap();
+    assert_eq!(stored_feed.oracle, oracle);
+}
+
+/// Test a revoked oracle can no longer submit prices and its price is no longer served
+#[test]
+fn test_oracle_registry_revoked_caller() {
+    let env = create_test_env();
+    let (contract_id, admin, client) = setup_contract_with_admin(&env);
+    let asset = Address::generate(&env);
+    let oracle = Address::generate(&env);
+
+    set_oracle_price(&client, &admin, &asset, &100_000_000, &8, &oracle);
+    client.revoke_oracle(&admin, &asset, &oracle);
+    assert!(client.get_registered_oracles(&asset).is_empty());
+    assert!(get_cached_price(&env, &contract_id, &asset).is_none());
+
+    env.as_contract(&contract_id, || {
+        assert_eq!(
+            crate::oracle::update_price_feed(&env, oracle.clone(), asset.clone(), 101_000_000, 8),
+            Err(OracleError::UnauthorizedOracle)
+        );
+        assert_eq!(
+            crate::oracle::get_price(&env, &asset),
+            Err(OracleError::FallbackNotConfigured)
+        );
+    });
+
+    // Registering it again restores both
+    client.register_oracle(&admin, &asset, &oracle);
+    assert_eq!(client.get_price(&asset), 100_000_000);
+}
+
+/// Test addresses never registered for the asset, the admin included, are rejected
+#[test]
+fn test_oracle_registry_never_registered_caller() {
+    let env = create_test_env();
+    let (contract_id, admin, client) = setup_contract_with_admin(&env);
+    let asset = Address::generate(&env);
+    let other_asset = Address::generate(&env);
+    let oracle = Address::generate(&env);
+    client.register_oracle(&admin, &other_asset, &oracle);
+
+    env.as_contract(&contract_id, || {
+        for caller in [oracle.clone(), admin.clone(), Address::generate(&env)] {
+            assert_eq!(
+                crate::oracle::update_price_feed(&env, caller, asset.clone(), 100_000_000, 8),
+                Err(OracleError::UnauthorizedOracle)
+            );
+        }
+    });
+    assert!(get_price_feed(&env, &contract_id, &asset).is_none());
+}
+
+/// Test only the admin manages the registry
+#[test]
+fn test_oracle_registry_admin_only() {
+    let env = create_test_env();
+    let (contract_id, admin, client) = setup_contract_with_admin(&env);
+    let asset = Address::generate(&env);
+    let oracle = Address::generate(&env);
+    client.register_oracle(&admin, &asset, &oracle);
+
+    env.as_contract(&contract_id, || {
+        assert_eq!(
+            crate::oracle::register_oracle(&env, oracle.clone(), asset.clone(), oracle.clone()),
+            Err(OracleError::Unauthorized)
+        );
+        assert_eq!(
+            crate::oracle::revoke_oracle(&env, oracle.clone(), asset.clone(), oracle.clone()),
+            Err(OracleError::Unauthorized)
+        );
+        assert_eq!(
+            crate::oracle::revoke_oracle(&env, admin.clone(), asset.clone(), admin.clone()),
+            Err(OracleError::InvalidOracle)
+        );
+        assert_eq!(
+            crate::oracle::register_oracle(&env, admin.clone(), asset.clone(), contract_id.clone()),
+            Err(OracleError::InvalidOracle)
+        );
+    });
+    assert_eq!(client.get_registered_oracles(&asset), vec![&env, oracle]);
 }
 
 // =============================================================================
@@ -220,11 +339,11 @@ fn test_price_deviation_within_range() {
 
     // Initial price
     let initial_price = 100_000_000i128;
-    client.update_price_feed(&admin, &asset, &initial_price, &8, &oracle);
+    set_oracle_price(&client, &admin, &asset, &initial_price, &8, &oracle);
 
     // Price change within 5% (default max deviation)
     let new_price = 104_000_000i128; // 4% increase
-    let result = client.update_price_feed(&admin, &asset, &new_price, &8, &oracle);
+    let result = set_oracle_price(&client, &admin, &asset, &new_price, &8, &oracle);
     assert_eq!(result, new_price);
 }
 
@@ -239,11 +358,11 @@ fn test_price_deviation_exceeds_maximum() {
 
     // Initial price
     let initial_price = 100_000_000i128;
-    client.update_price_feed(&admin, &asset, &initial_price, &8, &oracle);
+    set_oracle_price(&client, &admin, &asset, &initial_price, &8, &oracle);
 
     // Price change exceeds 5% (default max deviation)
     let new_price = 110_000_000i128; // 10% increase
-    client.update_price_feed(&admin, &asset, &new_price, &8, &oracle);
+    set_oracle_price(&client, &admin, &asset, &new_price, &8, &oracle);
 }
 
 /// Test price deviation with price decrease
@@ -256,11 +375,11 @@ fn test_price_deviation_decrease_within_range() {
 
     // Initial price
     let initial_price = 100_000_000i128;
-    client.update_price_feed(&admin, &asset, &initial_price, &8, &oracle);
+    set_oracle_price(&client, &admin, &asset, &initial_price, &8, &oracle);
 
     // Price decrease within 5%
     let new_price = 96_000_000i128; // 4% decrease
-    let result = client.update_price_feed(&admin, &asset, &new_price, &8, &oracle);
+    let result = set_oracle_price(&client, &admin, &asset, &new_price, &8, &oracle);
     assert_eq!(result, new_price);
 }
 
@@ -279,7 +398,7 @@ fn test_price_caching() {
     env.ledger().with_mut(|li| li.timestamp = 1000);
 
     let price = 100_000_000i128;
-    client.update_price_feed(&admin, &asset, &price, &8, &oracle);
+    set_oracle_price(&client, &admin, &asset, &price, &8, &oracle);
 
     // Verify cache was created
     let cached = get_cached_price(&env, &contract_id, &asset).unwrap();
@@ -298,7 +417,7 @@ fn test_get_price_from_cache() {
     env.ledger().with_mut(|li| li.timestamp = 1000);
 
     let price = 100_000_000i128;
-    client.update_price_feed(&admin, &asset, &price, &8, &oracle);
+    set_oracle_price(&client, &admin, &asset, &price, &8, &oracle);
 
     // Move time forward but still within TTL (default 300 seconds)
     env.ledger().with_mut(|li| li.timestamp = 1200);
@@ -319,7 +438,7 @@ fn test_cache_expiration() {
     env.ledger().with_mut(|li| li.timestamp = 1000);
 
     let price = 100_000_000i128;
-    client.update_price_feed(&admin, &asset, &price, &8, &oracle);
+    set_oracle_price(&client, &admin, &asset, &price, &8, &oracle);
 
     // Move time forward beyond TTL (default 300 seconds)
     env.ledger().with_mut(|li| li.timestamp = 1400);
@@ -381,9 +500,9 @@ fn test_configure_oracle() {
     let (_contract_id, admin, client) = setup_contract_with_admin(&env);
 
     let config = OracleConfig {
-        max_deviation_bps: 1000,     // 10%
-        max_staleness_seconds: 7200, // 2 hours
-        cache_ttl_seconds: 600,      // 10 minutes
+        max_deviation_bps: 1000,  // 10%
+        max_price_age_secs: 7200, // 2 hours
+        cache_ttl_seconds: 600,   // 10 minutes
         min_price: 1,
         max_price: i128::MAX,
     };
@@ -402,7 +521,7 @@ fn test_configure_oracle_unauthorized() {
 
     let config = OracleConfig {
         max_deviation_bps: 1000,
-        max_staleness_seconds: 7200,
+        max_price_age_secs: 7200,
         cache_ttl_seconds: 600,
         min_price: 1,
         max_price: i128::MAX,
@@ -420,7 +539,7 @@ fn test_configure_oracle_invalid_deviation_zero() {
 
     let config = OracleConfig {
         max_deviation_bps: 0, // Invalid - must be > 0
-        max_staleness_seconds: 3600,
+        max_price_age_secs: 3600,
         cache_ttl_seconds: 300,
         min_price: 1,
         max_price: i128::MAX,
@@ -438,7 +557,7 @@ fn test_configure_oracle_invalid_deviation_too_high() {
 
     let config = OracleConfig {
         max_deviation_bps: 15000, // Invalid - > 10000 (100%)
-        max_staleness_seconds: 3600,
+        max_price_age_secs: 3600,
         cache_ttl_seconds: 300,
         min_price: 1,
         max_price: i128::MAX,
@@ -456,7 +575,7 @@ fn test_configure_oracle_invalid_staleness_zero() {
 
     let config = OracleConfig {
         max_deviation_bps: 500,
-        max_staleness_seconds: 0, // Invalid - must be > 0
+        max_price_age_secs: 0, // Invalid - must be > 0
         cache_ttl_seconds: 300,
         min_price: 1,
         max_price: i128::MAX,
@@ -482,7 +601,7 @@ fn test_update_price_feed_when_paused() {
     set_oracle_paused(&env, &contract_id, true);
 
     // Try to update price - should fail
-    client.update_price_feed(&admin, &asset, &100_000_000, &8, &oracle);
+    set_oracle_price(&client, &admin, &asset, &100_000_000, &8, &oracle);
 }
 
 /// Test oracle operations after unpausing
@@ -499,7 +618,7 @@ fn test_update_price_feed_after_unpause() {
 
     // Should succeed after unpause
     let price = 100_000_000i128;
-    let result = client.update_price_feed(&admin, &asset, &price, &8, &oracle);
+    let result = set_oracle_price(&client, &admin, &asset, &price, &8, &oracle);
     assert_eq!(result, price);
 }
 
@@ -519,7 +638,7 @@ fn test_get_price_stale_no_fallback() {
     env.ledger().with_mut(|li| li.timestamp = 1000);
 
     let price = 100_000_000i128;
-    client.update_price_feed(&admin, &asset, &price, &8, &oracle);
+    set_oracle_price(&client, &admin, &asset, &price, &8, &oracle);
 
     // Clear cache
     env.as_contract(&contract_id, || {
@@ -554,20 +673,15 @@ fn test_get_price_with_successful_fallback() {
     client.set_primary_oracle(&admin, &asset, &primary_oracle);
 
     // Set primary price
-    client.update_price_feed(&admin, &asset, &100_000_000, &8, &primary_oracle);
+    set_oracle_price(&client, &admin, &asset, &100_000_000, &8, &primary_oracle);
 
     // Move time forward beyond staleness threshold (3600s)
     env.ledger().with_mut(|li| li.timestamp = 5000);
 
     // Set fallback price
     let fallback_price = 105_000_000i128;
-    client.update_price_feed(
-        &fallback_oracle,
-        &asset,
-        &fallback_price,
-        &8,
-        &fallback_oracle,
-    );
+    client.register_oracle(&admin, &asset, &fallback_oracle);
+    client.update_price_feed(&fallback_oracle, &asset, &fallback_price, &8);
 
     // Should return fallback price
     let retrieved_price = client.get_price(&asset);
@@ -588,8 +702,8 @@ fn test_get_price_both_stale() {
     client.set_fallback_oracle(&admin, &asset, &fallback_oracle);
 
     env.ledger().with_mut(|li| li.timestamp = 1000);
-    client.update_price_feed(&admin, &asset, &100_000_000, &8, &primary_oracle);
-    client.update_price_feed(&fallback_oracle, &asset, &105_000_000, &8, &fallback_oracle);
+    set_oracle_price(&client, &admin, &asset, &100_000_000, &8, &primary_oracle);
+    set_oracle_price(&client, &admin, &asset, &105_000_000, &8, &fallback_oracle);
 
     // Move time beyond staleness threshold for both
     env.ledger().with_mut(|li| li.timestamp = 10000);
@@ -597,6 +711,293 @@ fn test_get_price_both_stale() {
     client.get_price(&asset);
 }
 
+/// Test `get_price` fails with `StalePrice` once the price is older than the
+/// maximum age, while the unchecked read still serves it
+#[test]
+fn test_get_price_stale_error_and_unchecked_read() {
+    let env = create_test_env();
+    let (contract_id, admin, client) = setup_contract_with_admin(&env);
+    let asset = Address::generate(&env);
+    let oracle = Address::generate(&env);
+
+    env.ledger().with_mut(|li| li.timestamp = 1000);
+    set_oracle_price(&client, &admin, &asset, &100_000_000, &8, &oracle);
+
+    // Exactly at the maximum age the price is still served
+    env.ledger().with_mut(|li| li.timestamp = 1000 + 3600);
+    assert_eq!(client.get_price(&asset), 100_000_000);
+
+    env.ledger().with_mut(|li| li.timestamp = 1000 + 3601);
+    env.as_contract(&contract_id, || {
+        assert_eq!(
+            crate::oracle::get_price(&env, &asset),
+            Err(OracleError::StalePrice)
+        );
+        assert_eq!(
+            crate::oracle::get_price_unchecked(&env, &Address::generate(&env)),
+            Err(OracleError::AssetNotSupported)
+        );
+    });
+    assert_eq!(client.get_price_unchecked(&asset), 100_000_000);
+}
+
+/// Test a cache entry does not outlive the maximum age of the price it holds
+#[test]
+fn test_cached_price_expires_with_price_age() {
+    let env = create_test_env();
+    let (contract_id, admin, client) = setup_contract_with_admin(&env);
+    let asset = Address::generate(&env);
+    let oracle = Address::generate(&env);
+
+    let config = OracleConfig {
+        max_deviation_bps: 500,
+        max_price_age_secs: 3600,
+        cache_ttl_seconds: 7200, // Longer than the price may live
+        min_price: 1,
+        max_price: i128::MAX,
+    };
+    client.configure_oracle(&admin, &config);
+
+    env.ledger().with_mut(|li| li.timestamp = 1000);
+    set_oracle_price(&client, &admin, &asset, &100_000_000, &8, &oracle);
+    let cached = get_cached_price(&env, &contract_id, &asset).unwrap();
+    assert_eq!(cached.price_updated_at, 1000);
+
+    env.ledger().with_mut(|li| li.timestamp = 1000 + 3601);
+    env.as_contract(&contract_id, || {
+        assert_eq!(
+            crate::oracle::get_price(&env, &asset),
+            Err(OracleError::StalePrice)
+        );
+    });
+}
+
+// =============================================================================
+// FAILOVER TESTS
+// =============================================================================
+
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct TestFallbackPriceUsedEvent {
+    pub asset: Address,
+    pub fallback_oracle: Address,
+    pub primary_failure: PriceSourceFailure,
+    pub price: i128,
+    pub timestamp: u64,
+}
+
+/// FallbackPriceUsedEvent published by the last invocation, if any
+fn fallback_used_event(env: &Env) -> Option<TestFallbackPriceUsedEvent> {
+    env.events()
+        .all()
+        .iter()
+        .find(|(_c, topics, _data)| {
+            Symbol::try_from_val(env, &topics.get_unchecked(0)).ok()
+                == Some(Symbol::new(env, "fallback_price_used_event"))
+        })
+        .map(|(_c, _topics, data)| TestFallbackPriceUsedEvent::try_from_val(env, &data).unwrap())
+}
+
+/// An asset priced at 100_000_000 by its primary and 101_000_000 by its
+/// fallback oracle at t=1000, with the primary's cache entry cleared
+fn setup_failover(env: &Env) -> (Address, HelloContractClient<'_>, Address, Address) {
+    let (contract_id, admin, client) = setup_contract_with_admin(env);
+    let asset = Address::generate(env);
+    let primary_oracle = Address::generate(env);
+    let fallback_oracle = Address::generate(env);
+    client.set_fallback_oracle(&admin, &asset, &fallback_oracle);
+
+    env.ledger().with_mut(|li| li.timestamp = 1000);
+    set_oracle_price(&client, &admin, &asset, &100_000_000, &8, &primary_oracle);
+    set_oracle_price(&client, &admin, &asset, &101_000_000, &8, &fallback_oracle);
+    env.as_contract(&contract_id, || {
+        let cache_key = OracleDataKey::PriceCache(asset.clone());
+        env.storage().persistent().remove(&cache_key);
+    });
+    (contract_id, client, asset, fallback_oracle)
+}
+
+/// Test a usable primary price is served without consulting the fallback
+#[test]
+fn test_failover_primary_ok() {
+    let env = create_test_env();
+    let (_contract_id, client, asset, _fallback_oracle) = setup_failover(&env);
+
+    assert_eq!(client.get_price(&asset), 100_000_000);
+    assert!(fallback_used_event(&env).is_none());
+}
+
+/// Test a stale primary fails over to the fallback price and announces it
+#[test]
+fn test_failover_primary_stale_fallback_ok() {
+    let env = create_test_env();
+    let (_contract_id, client, asset, fallback_oracle) = setup_failover(&env);
+
+    env.ledger().with_mut(|li| li.timestamp = 5000);
+    client.update_price_feed(&fallback_oracle, &asset, &102_000_000, &8);
+
+    assert_eq!(client.get_price(&asset), 102_000_000);
+    let event = fallback_used_event(&env).expect("fallback use not announced");
+    assert_eq!(event.asset, asset);
+    assert_eq!(event.fallback_oracle, fallback_oracle);
+    assert_eq!(event.primary_failure, PriceSourceFailure::Stale);
+    assert_eq!(event.price, 102_000_000);
+    assert_eq!(event.timestamp, 5000);
+}
+
+/// Test the error names both failures when neither feed is usable
+#[test]
+fn test_failover_both_stale() {
+    let env = create_test_env();
+    let (contract_id, _client, asset, _fallback_oracle) = setup_failover(&env);
+
+    env.ledger().with_mut(|li| li.timestamp = 5000);
+    env.as_contract(&contract_id, || {
+        assert_eq!(
+            crate::oracle::get_price(&env, &asset),
+            Err(OracleError::PrimaryStaleFallbackStale)
+        );
+    });
+}
+
+// =============================================================================
+// MULTI-SOURCE TESTS
+// =============================================================================
+
+/// An asset with three registered sources that submitted 100_000_000,
+/// 104_000_000 and 101_000_000 at t=1000
+fn setup_sources(
+    env: &Env,
+) -> (
+    Address,
+    Address,
+    HelloContractClient<'_>,
+    Address,
+    [Address; 3],
+) {
+    let (contract_id, admin, client) = setup_contract_with_admin(env);
+    let asset = Address::generate(env);
+    let sources = [
+        Address::generate(env),
+        Address::generate(env),
+        Address::generate(env),
+    ];
+
+    env.ledger().with_mut(|li| li.timestamp = 1000);
+    for (source, price) in sources.iter().zip([100_000_000, 104_000_000, 101_000_000]) {
+        set_oracle_price(&client, &admin, &asset, &price, &8, source);
+    }
+    (contract_id, admin, client, asset, sources)
+}
+
+/// Test the price is the median of the sources, each listed with its latest value
+#[test]
+fn test_multi_source_median() {
+    let env = create_test_env();
+    let (_contract_id, admin, client, asset, sources) = setup_sources(&env);
+
+    assert_eq!(client.get_price(&asset), 101_000_000);
+    let listed = client.get_price_sources(&asset);
+    assert_eq!(listed.len(), 3);
+    for (feed, (source, price)) in
+        listed
+            .iter()
+            .zip(sources.iter().zip([100_000_000, 104_000_000, 101_000_000]))
+    {
+        assert_eq!(feed.oracle, *source);
+        assert_eq!(feed.price, price);
+        assert_eq!(feed.last_updated, 1000);
+    }
+
+    // With an even number of sources the middle two are averaged
+    client.revoke_oracle(&admin, &asset, &sources[2]);
+    assert_eq!(client.get_price(&asset), 102_000_000);
+    assert_eq!(client.get_price_sources(&asset).len(), 2);
+}
+
+/// Test a stale source is left out of the median
+#[test]
+fn test_multi_source_stale_source_ignored() {
+    let env = create_test_env();
+    let (_contract_id, admin, client, asset, sources) = setup_sources(&env);
+    client.set_price_quorum(&admin, &asset, &2);
+
+    env.ledger().with_mut(|li| li.timestamp = 3000);
+    client.update_price_feed(&sources[0], &asset, &101_000_000, &8);
+    client.update_price_feed(&sources[1], &asset, &105_000_000, &8);
+
+    // The third source's price from t=1000 is past the one-hour maximum age
+    env.ledger().with_mut(|li| li.timestamp = 5000);
+    assert_eq!(client.get_price(&asset), 103_000_000);
+    let stale = client.get_price_sources(&asset).get_unchecked(2);
+    assert_eq!((stale.price, stale.last_updated), (101_000_000, 1000));
+}
+
+/// Test no price is served until the quorum of fresh sources is met
+#[test]
+fn test_multi_source_quorum_not_met() {
+    let env = create_test_env();
+    let (contract_id, admin, client, asset, sources) = setup_sources(&env);
+    client.set_price_quorum(&admin, &asset, &3);
+    assert_eq!(client.get_price_quorum(&asset), 3);
+    assert_eq!(client.get_price(&asset), 101_000_000);
+
+    env.ledger().with_mut(|li| li.timestamp = 3000);
+    client.update_price_feed(&sources[0], &asset, &101_000_000, &8);
+    client.update_price_feed(&sources[1], &asset, &105_000_000, &8);
+
+    env.ledger().with_mut(|li| li.timestamp = 5000);
+    env.as_contract(&contract_id, || {
+        assert_eq!(
+            crate::oracle::get_price(&env, &asset),
+            Err(OracleError::QuorumNotMet)
+        );
+    });
+
+    // An unpriced fallback cannot stand in either
+    set_oracle_price(&client, &admin, &asset, &100_000_000, &8, &sources[2]);
+    client.set_price_quorum(&admin, &asset, &4);
+    let fallback_oracle = Address::generate(&env);
+    client.set_fallback_oracle(&admin, &asset, &fallback_oracle);
+    client.register_oracle(&admin, &asset, &fallback_oracle);
+    env.as_contract(&contract_id, || {
+        assert_eq!(
+            crate::oracle::get_price(&env, &asset),
+            Err(OracleError::PrimaryQuorumNotMetFallbackMissing)
+        );
+    });
+}
+
+/// Test the quorum and the number of oracles per asset are bounded
+#[test]
+fn test_multi_source_limits() {
+    let env = create_test_env();
+    let (contract_id, admin, client) = setup_contract_with_admin(&env);
+    let asset = Address::generate(&env);
+    for _ in 0..crate::oracle::MAX_ORACLES_PER_ASSET {
+        client.register_oracle(&admin, &asset, &Address::generate(&env));
+    }
+
+    env.as_contract(&contract_id, || {
+        assert_eq!(
+            crate::oracle::register_oracle(
+                &env,
+                admin.clone(),
+                asset.clone(),
+                Address::generate(&env)
+            ),
+            Err(OracleError::TooManyOracles)
+        );
+        for min_sources in [0, crate::oracle::MAX_ORACLES_PER_ASSET + 1] {
+            assert_eq!(
+                crate::oracle::set_price_quorum(&env, admin.clone(), asset.clone(), min_sources),
+                Err(OracleError::InvalidQuorum)
+            );
+        }
+    });
+    assert_eq!(client.get_price_quorum(&asset), 1);
+}
+
 // =============================================================================
 // MULTIPLE ASSETS TESTS
 // =============================================================================
@@ -616,9 +1017,9 @@ fn test_multiple_asset_prices() {
     let price2 = 250_000_000i128; // Asset 2: $2.50
     let price3 = 50_000_000i128; // Asset 3: $0.50
 
-    client.update_price_feed(&admin, &asset1, &price1, &8, &oracle);
-    client.update_price_feed(&admin, &asset2, &price2, &8, &oracle);
-    client.update_price_feed(&admin, &asset3, &price3, &8, &oracle);
+    set_oracle_price(&client, &admin, &asset1, &price1, &8, &oracle);
+    set_oracle_price(&client, &admin, &asset2, &price2, &8, &oracle);
+    set_oracle_price(&client, &admin, &asset3, &price3, &8, &oracle);
 
     assert_eq!(client.get_price(&asset1), price1);
     assert_eq!(client.get_price(&asset2), price2);
@@ -639,8 +1040,8 @@ fn test_different_oracles_per_asset() {
     let price1 = 100_000_000i128;
     let price2 = 200_000_000i128;
 
-    client.update_price_feed(&admin, &asset1, &price1, &8, &oracle1);
-    client.update_price_feed(&admin, &asset2, &price2, &8, &oracle2);
+    set_oracle_price(&client, &admin, &asset1, &price1, &8, &oracle1);
+    set_oracle_price(&client, &admin, &asset2, &price2, &8, &oracle2);
 
     // Verify different oracles are stored
     let feed1 = get_price_feed(&env, &contract_id, &asset1).unwrap();
@@ -663,7 +1064,7 @@ fn test_price_minimum_valid() {
     let oracle = Address::generate(&env);
 
     let min_price = 1i128;
-    let result = client.update_price_feed(&admin, &asset, &min_price, &8, &oracle);
+    let result = set_oracle_price(&client, &admin, &asset, &min_price, &8, &oracle);
     assert_eq!(result, min_price);
 }
 
@@ -677,7 +1078,7 @@ fn test_price_maximum_valid() {
 
     // Use a large but safe price value
     let large_price = 1_000_000_000_000_000_000i128;
-    let result = client.update_price_feed(&admin, &asset, &large_price, &8, &oracle);
+    let result = set_oracle_price(&client, &admin, &asset, &large_price, &8, &oracle);
     assert_eq!(result, large_price);
 }
 
@@ -692,9 +1093,17 @@ fn test_different_decimals() {
     let asset_8_decimals = Address::generate(&env);
     let asset_18_decimals = Address::generate(&env);
 
-    client.update_price_feed(&admin, &asset_6_decimals, &1_000_000, &6, &oracle);
-    client.update_price_feed(&admin, &asset_8_decimals, &100_000_000, &8, &oracle);
-    client.update_price_feed(
+    set_oracle_price(&client, &admin, &asset_6_decimals, &1_000_000, &6, &oracle);
+    set_oracle_price(
+        &client,
+        &admin,
+        &asset_8_decimals,
+        &100_000_000,
+        &8,
+        &oracle,
+    );
+    set_oracle_price(
+        &client,
         &admin,
         &asset_18_decimals,
         &1_000_000_000_000_000_000i128,
@@ -722,11 +1131,11 @@ fn test_same_timestamp_update() {
     env.ledger().with_mut(|li| li.timestamp = 1000);
 
     let price1 = 100_000_000i128;
-    client.update_price_feed(&admin, &asset, &price1, &8, &oracle);
+    set_oracle_price(&client, &admin, &asset, &price1, &8, &oracle);
 
     // Update again with same timestamp but different price (within deviation)
     let price2 = 101_000_000i128;
-    let result = client.update_price_feed(&admin, &asset, &price2, &8, &oracle);
+    let result = set_oracle_price(&client, &admin, &asset, &price2, &8, &oracle);
     assert_eq!(result, price2);
 }
 
@@ -756,7 +1165,7 @@ fn test_price_bounds_enforcement() {
     // Configure with specific price bounds
     let config = OracleConfig {
         max_deviation_bps: 500,
-        max_staleness_seconds: 3600,
+        max_price_age_secs: 3600,
         cache_ttl_seconds: 300,
         min_price: 1_000_000,         // Minimum $0.01 with 8 decimals
         max_price: 1_000_000_000_000, // Maximum $10,000 with 8 decimals
@@ -765,7 +1174,7 @@ fn test_price_bounds_enforcement() {
 
     // Valid price within bounds
     let valid_price = 100_000_000i128;
-    let result = client.update_price_feed(&admin, &asset, &valid_price, &8, &oracle);
+    let result = set_oracle_price(&client, &admin, &asset, &valid_price, &8, &oracle);
     assert_eq!(result, valid_price);
 }
 
@@ -781,7 +1190,7 @@ fn test_price_below_minimum_bound() {
     // Configure with specific price bounds
     let config = OracleConfig {
         max_deviation_bps: 500,
-        max_staleness_seconds: 3600,
+        max_price_age_secs: 3600,
         cache_ttl_seconds: 300,
         min_price: 1_000_000, // Minimum $0.01 with 8 decimals
         max_price: 1_000_000_000_000,
@@ -790,7 +1199,7 @@ fn test_price_below_minimum_bound() {
 
     // Price below minimum
     let below_min_price = 100i128; // Way below $0.01
-    client.update_price_feed(&admin, &asset, &below_min_price, &8, &oracle);
+    set_oracle_price(&client, &admin, &asset, &below_min_price, &8, &oracle);
 }
 
 /// Test sequential price updates maintain consistency
@@ -813,7 +1222,7 @@ fn test_sequential_price_updates() {
     for (i, price) in prices.iter().enumerate() {
         env.ledger()
             .with_mut(|li| li.timestamp = (i as u64 + 1) * 100);
-        let result = client.update_price_feed(&admin, &asset, price, &8, &oracle);
+        let result = set_oracle_price(&client, &admin, &asset, price, &8, &oracle);
         assert_eq!(result, *price);
     }
 }
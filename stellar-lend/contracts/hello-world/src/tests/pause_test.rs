@@ -15,6 +15,7 @@
 //! - Non-admin callers cannot pause any operation
 //! - `is_operation_paused` and `is_emergency_paused` reflect the correct state
 //!   throughout the full pause lifecycle
+//! - Repayments bypass every pause while `repay_always_allowed` is on (default)
 //!
 //! # Security notes
 //! - Only the stored admin address may activate pause switches.
@@ -23,6 +24,7 @@
 //! - Pause state is stored persistently; restarting the host or re-reading
 //!   the state always reflects the last write.
 
+use crate::tests::test_helpers::funded_token;
 use crate::{HelloContract, HelloContractClient};
 use soroban_sdk::{testutils::Address as _, Address, Env, Map, Symbol};
 
@@ -168,8 +170,9 @@ fn test_unpause_withdraw_allows_withdrawal() {
     pause_op(&client, &e, &admin, "pause_withdraw");
     unpause_op(&client, &e, &admin, "pause_withdraw");
 
-    let remaining = client.withdraw_collateral(&user, &None, &1_000_i128);
-    assert_eq!(remaining, 4_000, "withdraw should succeed after unpause");
+    let received = client.withdraw_collateral(&user, &None, &1_000_i128);
+    assert_eq!(received, 1_000, "withdraw should succeed after unpause");
+    assert_eq!(client.get_user_deposit(&user, &None), 4_000);
 }
 
 // ═══════════════════════════════════════════════════════════════════════════
@@ -279,8 +282,9 @@ fn test_emergency_pause_does_not_block_withdrawal() {
     client.deposit_collateral(&user, &None, &5_000_i128);
     client.set_emergency_pause(&admin, &true);
     // Must NOT panic – withdrawal checks only pause_withdraw, not emergency pause.
-    let remaining = client.withdraw_collateral(&user, &None, &1_000_i128);
-    assert_eq!(remaining, 4_000);
+    let received = client.withdraw_collateral(&user, &None, &1_000_i128);
+    assert_eq!(received, 1_000);
+    assert_eq!(client.get_user_deposit(&user, &None), 4_000);
 }
 
 /// Emergency pause does NOT block `borrow_asset`.
@@ -334,7 +338,15 @@ fn test_emergency_pause_blocks_risk_param_changes() {
     let (_id, admin, client) = setup(&e);
 
     client.set_emergency_pause(&admin, &true);
-    client.set_risk_params(&admin, &Some(11_100_i128), &None, &None, &None);
+    client.set_risk_params(
+        &admin,
+        &Some(11_100_i128),
+        &None,
+        &None,
+        &None,
+        &None,
+        &None,
+    );
 }
 
 // ═══════════════════════════════════════════════════════════════════════════
@@ -416,7 +428,15 @@ fn test_lift_emergency_pause_restores_risk_param_changes() {
     client.set_emergency_pause(&admin, &false);
 
     // Small valid change: 11 000 → 12 100 (+10 %)
-    client.set_risk_params(&admin, &Some(12_100_i128), &None, &None, &None);
+    client.set_risk_params(
+        &admin,
+        &Some(12_100_i128),
+        &None,
+        &None,
+        &None,
+        &None,
+        &None,
+    );
     assert_eq!(client.get_min_collateral_ratio(), 12_100);
 }
 
@@ -824,3 +844,195 @@ fn test_computation_helpers_unaffected_by_all_pauses() {
     let _ = client.get_max_liquidatable_amount(&1_000_i128);
     let _ = client.get_liquidation_incentive_amount(&1_000_i128);
 }
+
+// ═══════════════════════════════════════════════════════════════════════════
+// 18. Per-asset pause switches
+// ═══════════════════════════════════════════════════════════════════════════
+
+/// Pausing deposits for asset A blocks A while asset B keeps working.
+#[test]
+fn test_asset_pause_blocks_only_that_asset() {
+    let e = env();
+    let (id, admin, client) = setup(&e);
+    let user = Address::generate(&e);
+    let token_a = funded_token(&e, &id, &[&user], 10_000);
+    let token_b = funded_token(&e, &id, &[&user], 10_000);
+    let op = Symbol::new(&e, "pause_deposit");
+
+    client.set_asset_pause(&admin, &Some(token_a.clone()), &op, &true);
+    assert!(client.is_asset_operation_paused(&Some(token_a.clone()), &op));
+    assert!(!client.is_asset_operation_paused(&Some(token_b.clone()), &op));
+    // The global switch is untouched
+    assert!(!client.is_operation_paused(&op));
+
+    let result = client.try_deposit_collateral(&user, &Some(token_a.clone()), &1_000);
+    assert_eq!(result, Err(Ok(crate::deposit::DepositError::AssetPaused)));
+
+    let balance = client.deposit_collateral(&user, &Some(token_b), &1_000);
+    assert_eq!(balance, 1_000);
+}
+
+/// Unpausing an asset restores deposits for it.
+#[test]
+fn test_asset_unpause_restores_deposit() {
+    let e = env();
+    let (id, admin, client) = setup(&e);
+    let user = Address::generate(&e);
+    let token = funded_token(&e, &id, &[&user], 10_000);
+    let op = Symbol::new(&e, "pause_deposit");
+
+    client.set_asset_pause(&admin, &Some(token.clone()), &op, &true);
+    client.set_asset_pause(&admin, &Some(token.clone()), &op, &false);
+    assert!(!client.is_asset_operation_paused(&Some(token.clone()), &op));
+
+    let balance = client.deposit_collateral(&user, &Some(token), &1_000);
+    assert_eq!(balance, 1_000);
+}
+
+/// An asset-scoped borrow pause rejects borrows of that asset.
+#[test]
+fn test_asset_pause_blocks_borrow_of_that_asset() {
+    let e = env();
+    let (_id, admin, client) = setup(&e);
+    let user = Address::generate(&e);
+    let op = Symbol::new(&e, "pause_borrow");
+
+    client.deposit_collateral(&user, &None, &10_000);
+    client.set_asset_pause(&admin, &None, &op, &true);
+
+    let result = client.try_borrow_asset(&user, &None, &1_000);
+    assert_eq!(result, Err(Ok(crate::borrow::BorrowError::AssetPaused)));
+}
+
+/// Only the admin may toggle asset pause switches.
+#[test]
+fn test_asset_pause_requires_admin() {
+    let e = env();
+    let (_id, admin, client) = setup(&e);
+    let stranger = other_addr(&e, &admin);
+
+    let result =
+        client.try_set_asset_pause(&stranger, &None, &Symbol::new(&e, "pause_deposit"), &true);
+    assert!(result.is_err());
+}
+
+/// Pausing withdrawals of asset A leaves asset B and deposits of A working,
+/// and unpausing lets A's depositors out again.
+#[test]
+fn test_asset_withdraw_pause_freezes_only_that_asset() {
+    let e = env();
+    let (id, admin, client) = setup(&e);
+    let user = Address::generate(&e);
+    let token_a = funded_token(&e, &id, &[&user], 10_000);
+    let token_b = funded_token(&e, &id, &[&user], 10_000);
+    let op = Symbol::new(&e, "pause_withdraw");
+
+    client.deposit_collateral(&user, &Some(token_a.clone()), &5_000);
+    client.deposit_collateral(&user, &Some(token_b.clone()), &5_000);
+    client.withdraw_collateral(&user, &Some(token_a.clone()), &1_000);
+
+    client.set_asset_pause(&admin, &Some(token_a.clone()), &op, &true);
+    assert!(!client.is_operation_paused(&op));
+
+    let result = client.try_withdraw_collateral(&user, &Some(token_a.clone()), &1_000);
+    assert_eq!(result, Err(Ok(crate::withdraw::WithdrawError::AssetPaused)));
+
+    // Other assets and other operations on A are unaffected
+    assert_eq!(
+        client.withdraw_collateral(&user, &Some(token_b.clone()), &1_000),
+        1_000
+    );
+    assert_eq!(
+        client.deposit_collateral(&user, &Some(token_a.clone()), &1_000),
+        5_000
+    );
+
+    client.set_asset_pause(&admin, &Some(token_a.clone()), &op, &false);
+    assert_eq!(
+        client.withdraw_collateral(&user, &Some(token_a.clone()), &1_000),
+        1_000
+    );
+    assert_eq!(client.get_user_deposit(&user, &Some(token_a)), 4_000);
+}
+
+// ═══════════════════════════════════════════════════════════════════════════
+// 19. Repayments bypass pauses unless the admin opts out
+// ═══════════════════════════════════════════════════════════════════════════
+
+/// Register the native asset and give `user` 1_000 of native debt with 1_000
+/// tokens approved for repayment.
+fn native_debt(
+    e: &Env,
+    id: &Address,
+    client: &HelloContractClient<'_>,
+    admin: &Address,
+    user: &Address,
+) {
+    let native_asset = e.register_stellar_asset_contract(admin.clone());
+    client.set_native_asset_address(admin, &native_asset);
+    let token = soroban_sdk::token::StellarAssetClient::new(e, &native_asset);
+    token.mint(user, &1000);
+    token.approve(user, id, &1000, &(e.ledger().sequence() + 100));
+
+    client.deposit_collateral(user, &None, &10_000_i128);
+    client.borrow_asset(user, &None, &1_000_i128);
+}
+
+/// Repay goes through under emergency pause and every repay switch while
+/// `repay_always_allowed` is on, its default.
+#[test]
+fn test_repay_allowed_under_emergency_and_repay_pause() {
+    let e = env();
+    let (id, admin, client) = setup(&e);
+    let user = Address::generate(&e);
+    native_debt(&e, &id, &client, &admin, &user);
+    assert!(client.is_repay_always_allowed());
+
+    client.set_emergency_pause(&admin, &true);
+    pause_op(&client, &e, &admin, "pause_repay");
+    e.as_contract(&id, || {
+        let mut pause_map: Map<Symbol, bool> = Map::new(&e);
+        pause_map.set(Symbol::new(&e, "pause_repay"), true);
+        e.storage()
+            .persistent()
+            .set(&crate::deposit::DepositDataKey::PauseSwitches, &pause_map);
+    });
+    assert!(client.is_emergency_paused());
+    assert!(client.is_operation_paused(&Symbol::new(&e, "pause_repay")));
+
+    let (remaining, _interest, principal) = client.repay_debt(&user, &None, &500_i128);
+    assert_eq!(principal, 500);
+    assert_eq!(remaining, 500);
+}
+
+/// With `repay_always_allowed` off, the repay switch and emergency pause block
+/// repayments, while liquidation pausing is unaffected either way.
+#[test]
+fn test_repay_pausable_once_no_longer_always_allowed() {
+    let e = env();
+    let (id, admin, client) = setup(&e);
+    let user = Address::generate(&e);
+    native_debt(&e, &id, &client, &admin, &user);
+    client.set_repay_always_allowed(&admin, &false);
+    assert!(!client.is_repay_always_allowed());
+
+    pause_op(&client, &e, &admin, "pause_repay");
+    let result = client.try_repay_debt(&user, &None, &500_i128);
+    assert_eq!(result, Err(Ok(crate::repay::RepayError::RepayPaused)));
+
+    unpause_op(&client, &e, &admin, "pause_repay");
+    client.set_emergency_pause(&admin, &true);
+    let result = client.try_repay_debt(&user, &None, &500_i128);
+    assert_eq!(result, Err(Ok(crate::repay::RepayError::RepayPaused)));
+
+    // Emergency pause still stops liquidations
+    let liquidator = Address::generate(&e);
+    let result = client.try_liquidate(&liquidator, &user, &None, &None, &100_i128, &false);
+    assert_eq!(
+        result,
+        Err(Ok(crate::liquidate::LiquidationError::EmergencyPaused))
+    );
+
+    client.set_emergency_pause(&admin, &false);
+    client.repay_debt(&user, &None, &500_i128);
+}
@@ -0,0 +1,120 @@
+//! Tests for stable and variable borrow rate modes.
+//!
+//! # Coverage
+//! - Stable debt accrues at the rate locked at origination while the variable rate moves
+//! - Swapping between modes, and rejecting borrows in the other mode
+//! - Admin rebalancing only once the locked rate drifts past the threshold
+
+use crate::borrow::{BorrowError, RateMode};
+use crate::tests::test_helpers::{advance, set_oracle_price, setup};
+use crate::HelloContractClient;
+use soroban_sdk::{
+    testutils::{Address as _, Ledger},
+    Address, Env,
+};
+
+/// A priced token; `user` deposits 10_000 native collateral
+fn setup_rate_modes<'a>(
+    env: &'a Env,
+    user: &Address,
+) -> (Address, HelloContractClient<'a>, Address) {
+    env.ledger().with_mut(|li| li.timestamp = 1000);
+    let (_contract_id, admin, client) = setup(env);
+
+    let token = Address::generate(env);
+    set_oracle_price(&client, &admin, &token, &100, &7, &admin);
+    client.deposit_collateral(user, &None, &10_000);
+    (admin, client, token)
+}
+
+/// A second user borrows native XLM, pushing utilization from 10% to 45%
+fn raise_utilization(env: &Env, client: &HelloContractClient) {
+    let other = Address::generate(env);
+    client.deposit_collateral(&other, &None, &10_000);
+    client.borrow_asset(&other, &None, &8_000);
+}
+
+#[test]
+fn test_stable_debt_accrues_at_locked_rate() {
+    let env = Env::default();
+    let user = Address::generate(&env);
+    let (_admin, client, token) = setup_rate_modes(&env, &user);
+    let asset = Some(token);
+
+    client.borrow_asset_with_mode(&user, &asset, &1_000, &RateMode::Stable);
+    let locked = client.get_borrow_rate() + 100;
+    let record = client.get_user_borrow(&user, &asset).unwrap();
+    assert_eq!(record.rate_mode, RateMode::Stable);
+    assert_eq!(record.stable_rate_bps, locked);
+    assert_eq!(client.get_stable_borrow_rate(), locked);
+
+    // The variable rate rises, but the stable borrow keeps its rate
+    raise_utilization(&env, &client);
+    assert!(client.get_borrow_rate() > locked);
+
+    advance(&env, 31_536_000);
+    let debt = client.get_user_debt(&user, &asset);
+    assert_eq!(debt, 1_000 + 1_000 * locked / 10_000);
+
+    let (remaining_debt, _interest_paid, principal_paid) = client.repay_debt(&user, &asset, &debt);
+    assert_eq!(remaining_debt, 0);
+    assert_eq!(principal_paid, 1_000);
+    assert_eq!(client.get_user_borrow(&user, &asset), None);
+}
+
+#[test]
+fn test_swap_rate_mode() {
+    let env = Env::default();
+    let user = Address::generate(&env);
+    let (_admin, client, token) = setup_rate_modes(&env, &user);
+    let asset = Some(token);
+
+    let result = client.try_swap_rate_mode(&user, &asset);
+    assert_eq!(result, Err(Ok(BorrowError::NoDebt)));
+
+    client.borrow_asset(&user, &asset, &1_000);
+    let result = client.try_borrow_asset_with_mode(&user, &asset, &100, &RateMode::Stable);
+    assert_eq!(result, Err(Ok(BorrowError::RateModeMismatch)));
+
+    assert_eq!(client.swap_rate_mode(&user, &asset), RateMode::Stable);
+    let record = client.get_user_borrow(&user, &asset).unwrap();
+    assert_eq!(record.stable_rate_bps, client.get_stable_borrow_rate());
+    client.borrow_asset_with_mode(&user, &asset, &100, &RateMode::Stable);
+    let result = client.try_borrow_asset(&user, &asset, &100);
+    assert_eq!(result, Err(Ok(BorrowError::RateModeMismatch)));
+
+    assert_eq!(client.swap_rate_mode(&user, &asset), RateMode::Variable);
+    let record = client.get_user_borrow(&user, &asset).unwrap();
+    assert_eq!(record.rate_mode, RateMode::Variable);
+    assert_eq!(record.stable_rate_bps, 0);
+    assert_eq!(record.principal, 1_100);
+}
+
+#[test]
+fn test_rebalance_stable_rate() {
+    let env = Env::default();
+    let user = Address::generate(&env);
+    let (admin, client, token) = setup_rate_modes(&env, &user);
+    let asset = Some(token);
+    client.borrow_asset_with_mode(&user, &asset, &1_000, &RateMode::Stable);
+    let locked = client
+        .get_user_borrow(&user, &asset)
+        .unwrap()
+        .stable_rate_bps;
+
+    // The locked rate sits above the variable rate
+    let result = client.try_rebalance_stable_rate(&admin, &user, &asset);
+    assert_eq!(result, Err(Ok(BorrowError::RebalanceNotAllowed)));
+
+    // Past the 5% threshold below the variable rate, only the admin may re-lock it
+    raise_utilization(&env, &client);
+    assert!(locked + 500 < client.get_borrow_rate());
+    let result = client.try_rebalance_stable_rate(&user, &user, &asset);
+    assert_eq!(result, Err(Ok(BorrowError::Unauthorized)));
+
+    let rebalanced = client.rebalance_stable_rate(&admin, &user, &asset);
+    assert_eq!(rebalanced, client.get_stable_borrow_rate());
+    assert!(rebalanced > locked);
+    let record = client.get_user_borrow(&user, &asset).unwrap();
+    assert_eq!(record.stable_rate_bps, rebalanced);
+}
@@ -0,0 +1,241 @@
+//! Tests for the contract-wide reentrancy lock.
+//!
+//! # Coverage
+//! - A token that calls back into `withdraw_asset_to` from `transfer` is blocked
+//! - A token that calls back into `bridge_withdraw` from `transfer` is blocked
+//! - Guarded entrypoints reject calls while the lock is held
+//! - A failed call does not leave the lock behind
+
+use crate::bridge::BridgeError;
+use crate::cross_asset::{initialize as init_cross_asset, initialize_asset, AssetConfig};
+use crate::reentrancy::{is_locked, ReentrancyGuard};
+use crate::tests::test_helpers::setup;
+use crate::withdraw::WithdrawError;
+use crate::HelloContractClient;
+use soroban_sdk::{
+    contract, contractimpl, contracttype, testutils::Address as _, Address, Env, MuxedAddress,
+};
+
+#[contracttype]
+enum MockTokenKey {
+    Balance(Address),
+    Target,
+    BridgeTarget,
+    ReentryBlocked,
+}
+
+/// Token whose `transfer` tries to withdraw from the lending contract again,
+/// directly or through a bridge
+#[contract]
+pub struct ReentrantToken;
+
+#[contractimpl]
+impl ReentrantToken {
+    pub fn set_target(env: Env, target: Address) {
+        env.storage().instance().set(&MockTokenKey::Target, &target);
+    }
+
+    /// Re-enter through `bridge_withdraw` of `asset` on `network_id` instead
+    pub fn set_bridge_target(env: Env, target: Address, network_id: u32, asset: Address) {
+        env.storage()
+            .instance()
+            .set(&MockTokenKey::BridgeTarget, &(target, network_id, asset));
+    }
+
+    pub fn mint(env: Env, to: Address, amount: i128) {
+        let balance = Self::balance(env.clone(), to.clone());
+        env.storage()
+            .instance()
+            .set(&MockTokenKey::Balance(to), &(balance + amount));
+    }
+
+    pub fn balance(env: Env, id: Address) -> i128 {
+        env.storage()
+            .instance()
+            .get(&MockTokenKey::Balance(id))
+            .unwrap_or(0)
+    }
+
+    pub fn transfer_from(env: Env, _spender: Address, from: Address, to: Address, amount: i128) {
+        move_balance(&env, from, to, amount);
+    }
+
+    pub fn transfer(env: Env, from: Address, to: MuxedAddress, amount: i128) {
+        let to = to.address();
+        move_balance(&env, from, to.clone(), amount);
+
+        if let Some(target) = env
+            .storage()
+            .instance()
+            .get::<MockTokenKey, Address>(&MockTokenKey::Target)
+        {
+            let lending = HelloContractClient::new(&env, &target);
+            let result =
+                lending.try_withdraw_asset_to(&to, &Some(env.current_contract_address()), &1, &to);
+            env.storage()
+                .instance()
+                .set(&MockTokenKey::ReentryBlocked, &result.is_err());
+        }
+
+        if let Some((target, network_id, asset)) = env
+            .storage()
+            .instance()
+            .get::<MockTokenKey, (Address, u32, Address)>(&MockTokenKey::BridgeTarget)
+        {
+            let lending = HelloContractClient::new(&env, &target);
+            let result = lending.try_bridge_withdraw(&to, &network_id, &Some(asset), &1);
+            env.storage().instance().set(
+                &MockTokenKey::ReentryBlocked,
+                &(result == Err(Ok(BridgeError::Reentrancy))),
+            );
+        }
+    }
+
+    pub fn reentry_blocked(env: Env) -> Option<bool> {
+        env.storage().instance().get(&MockTokenKey::ReentryBlocked)
+    }
+}
+
+fn move_balance(env: &Env, from: Address, to: Address, amount: i128) {
+    let from_balance = ReentrantToken::balance(env.clone(), from.clone());
+    let to_balance = ReentrantToken::balance(env.clone(), to.clone());
+    env.storage()
+        .instance()
+        .set(&MockTokenKey::Balance(from), &(from_balance - amount));
+    env.storage()
+        .instance()
+        .set(&MockTokenKey::Balance(to), &(to_balance + amount));
+}
+
+#[test]
+fn test_token_cannot_reenter_withdraw_during_transfer() {
+    let env = Env::default();
+    let (contract_id, _admin, client) = setup(&env);
+    env.mock_all_auths_allowing_non_root_auth();
+    let user = Address::generate(&env);
+
+    let token = env.register(ReentrantToken, ());
+    let token_client = ReentrantTokenClient::new(&env, &token);
+    token_client.mint(&user, &1_000);
+    client.deposit_collateral(&user, &Some(token.clone()), &1_000);
+
+    token_client.set_target(&contract_id);
+    assert_eq!(
+        client.withdraw_collateral(&user, &Some(token.clone()), &500),
+        500
+    );
+
+    // The nested withdrawal failed and only the outer one was paid
+    assert_eq!(token_client.reentry_blocked(), Some(true));
+    assert_eq!(token_client.balance(&user), 500);
+    assert_eq!(client.get_user_deposit(&user, &Some(token)), 500);
+    env.as_contract(&contract_id, || assert!(!is_locked(&env)));
+}
+
+#[test]
+fn test_token_cannot_reenter_bridge_withdraw_during_transfer() {
+    let env = Env::default();
+    let (contract_id, admin, client) = setup(&env);
+    env.mock_all_auths_allowing_non_root_auth();
+    let user = Address::generate(&env);
+    let bridged = Address::generate(&env);
+
+    env.as_contract(&contract_id, || {
+        init_cross_asset(&env, admin.clone()).unwrap();
+        let config = AssetConfig {
+            asset: Some(bridged.clone()),
+            collateral_factor: 7500,
+            liquidation_threshold: 8000,
+            reserve_factor: 1000,
+            max_supply: 1_000_000,
+            max_borrow: 1_000_000,
+            can_collateralize: true,
+            can_borrow: true,
+            price: 1_000_000,
+            price_updated_at: env.ledger().timestamp(),
+        };
+        initialize_asset(&env, Some(bridged.clone()), config).unwrap();
+    });
+    client.register_bridge(&admin, &1, &Address::generate(&env), &0);
+    client.bridge_deposit(&user, &1, &Some(bridged.clone()), &1_000);
+
+    let token = env.register(ReentrantToken, ());
+    let token_client = ReentrantTokenClient::new(&env, &token);
+    token_client.mint(&user, &1_000);
+    client.deposit_collateral(&user, &Some(token.clone()), &1_000);
+
+    token_client.set_bridge_target(&contract_id, &1, &bridged);
+    assert_eq!(
+        client.withdraw_collateral(&user, &Some(token.clone()), &500),
+        500
+    );
+
+    // The bridge withdrawal was rejected by the lock and moved nothing
+    assert_eq!(token_client.reentry_blocked(), Some(true));
+    assert_eq!(
+        client
+            .get_user_asset_position(&user, &Some(bridged.clone()))
+            .collateral,
+        1_000
+    );
+    assert_eq!(client.bridge_withdraw(&user, &1, &Some(bridged), &1), 1);
+    env.as_contract(&contract_id, || assert!(!is_locked(&env)));
+}
+
+#[test]
+fn test_guarded_entrypoints_reject_calls_while_locked() {
+    let env = Env::default();
+    let (contract_id, _admin, client) = setup(&env);
+    let user = Address::generate(&env);
+    client.deposit_collateral(&user, &None, &1_000);
+
+    // Simulate a call in progress by taking the lock and never releasing it
+    env.as_contract(&contract_id, || {
+        core::mem::forget(ReentrancyGuard::enter(&env).unwrap());
+    });
+
+    let result = client.try_withdraw_collateral(&user, &None, &100);
+    assert_eq!(result, Err(Ok(WithdrawError::Reentrancy)));
+    let result = client.try_deposit_collateral(&user, &None, &100);
+    assert_eq!(result, Err(Ok(crate::deposit::DepositError::Reentrancy)));
+    let result = client.try_borrow_asset(&user, &None, &100);
+    assert_eq!(result, Err(Ok(crate::borrow::BorrowError::Reentrancy)));
+    let result = client.try_claim_referral_rewards(&user);
+    assert_eq!(result, Err(Ok(crate::deposit::DepositError::Reentrancy)));
+    let result = client.try_set_use_as_collateral(&user, &None, &false);
+    assert_eq!(result, Err(Ok(crate::deposit::DepositError::Reentrancy)));
+    let result = client.try_accrue_interest(&None);
+    assert_eq!(
+        result,
+        Err(Ok(crate::interest_rate::InterestRateError::Reentrancy))
+    );
+    let result = client.try_transfer_admin(&user, &user);
+    assert_eq!(result, Err(Ok(crate::admin::AdminError::Reentrancy)));
+    let result = client.try_set_emergency_pause(&user, &true);
+    assert_eq!(
+        result,
+        Err(Ok(crate::risk_management::RiskManagementError::Reentrancy))
+    );
+    let result = client.try_bridge_withdraw(&user, &1, &None, &100);
+    assert_eq!(result, Err(Ok(BridgeError::Reentrancy)));
+    let result = client.try_cross_asset_deposit(&user, &None, &100);
+    assert_eq!(
+        result,
+        Err(Ok(crate::cross_asset::CrossAssetError::Reentrancy))
+    );
+    assert!(client.try_touch_position(&user).is_err());
+}
+
+#[test]
+fn test_failed_call_releases_lock() {
+    let env = Env::default();
+    let (contract_id, _admin, client) = setup(&env);
+    let user = Address::generate(&env);
+    client.deposit_collateral(&user, &None, &1_000);
+
+    let result = client.try_withdraw_collateral(&user, &None, &5_000);
+    assert_eq!(result, Err(Ok(WithdrawError::InsufficientCollateral)));
+    env.as_contract(&contract_id, || assert!(!is_locked(&env)));
+
+    assert_eq!(client.withdraw_collateral(&user, &None, &1_000), 1_000);
+}
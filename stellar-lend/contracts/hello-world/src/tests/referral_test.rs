@@ -0,0 +1,177 @@
+//! Tests for deposit referral attribution and referral rewards.
+//!
+//! # Coverage
+//! - Referred deposit volume accrues to the referrer
+//! - The first recorded referrer is kept
+//! - Self-referral is rejected
+//! - A referral fee diverts part of reserve income to the referrer, both borrow
+//!   fees and the reserve share of interest once the borrower pays it
+//! - Rewards are paid out in tokens and can only be claimed once
+
+use crate::deposit::{AssetParams, DepositDataKey, DepositError};
+use crate::tests::test_helpers::{
+    advance, asset_params, set_oracle_price, setup, setup_env_with_native_asset,
+};
+use crate::HelloContractClient;
+use soroban_sdk::{testutils::Address as _, token, Address, Env};
+
+#[test]
+fn test_referral_volume_accrues() {
+    let env = Env::default();
+    let (_contract_id, _admin, client) = setup(&env);
+    let user = Address::generate(&env);
+    let referrer = Address::generate(&env);
+
+    client.deposit_collateral_with_referral(&user, &None, &500, &Some(referrer.clone()));
+    client.deposit_collateral_with_referral(&user, &None, &300, &Some(referrer.clone()));
+
+    assert_eq!(client.get_referral_volume(&referrer), 800);
+    assert_eq!(client.get_user_deposit(&user, &None), 800);
+}
+
+#[test]
+fn test_first_referrer_is_kept() {
+    let env = Env::default();
+    let (_contract_id, _admin, client) = setup(&env);
+    let user = Address::generate(&env);
+    let first = Address::generate(&env);
+    let second = Address::generate(&env);
+
+    client.deposit_collateral_with_referral(&user, &None, &500, &Some(first.clone()));
+    client.deposit_collateral_with_referral(&user, &None, &200, &Some(second.clone()));
+
+    assert_eq!(client.get_referral_volume(&first), 700);
+    assert_eq!(client.get_referral_volume(&second), 0);
+}
+
+#[test]
+fn test_self_referral_rejected() {
+    let env = Env::default();
+    let (_contract_id, _admin, client) = setup(&env);
+    let user = Address::generate(&env);
+
+    let result =
+        client.try_deposit_collateral_with_referral(&user, &None, &500, &Some(user.clone()));
+    assert_eq!(result, Err(Ok(DepositError::SelfReferral)));
+    assert_eq!(client.get_referral_volume(&user), 0);
+}
+
+#[test]
+fn test_referral_cut_of_borrow_fee() {
+    let env = Env::default();
+    let (contract_id, admin, client) = setup(&env);
+    let user = Address::generate(&env);
+    let referrer = Address::generate(&env);
+    let asset = Address::generate(&env);
+
+    // 2% borrow fee on the borrowed asset, 20% of it goes to the referrer
+    env.as_contract(&contract_id, || {
+        let params = AssetParams {
+            borrow_fee_bps: 200,
+            ..asset_params(7000)
+        };
+        env.storage()
+            .persistent()
+            .set(&DepositDataKey::AssetParams(asset.clone()), &params);
+    });
+    client.set_referral_fee_bps(&admin, &2000);
+    set_oracle_price(&client, &admin, &asset, &100, &7, &admin);
+
+    client.deposit_collateral_with_referral(&user, &None, &10_000, &Some(referrer.clone()));
+    client.borrow_asset(&user, &Some(asset.clone()), &1000);
+
+    // Fee of 20: 4 to the referrer, 16 to the reserve
+    assert_eq!(client.get_reserve_balance(&Some(asset.clone())), 16);
+    assert_eq!(
+        client
+            .get_referral_rewards(&referrer)
+            .get(Some(asset.clone())),
+        Some(4)
+    );
+}
+
+/// A referred user borrows 3_000_000 native for a year with a 20% referral fee,
+/// then repays in full. Returns the referrer and the referral share of the
+/// interest's reserve portion, which the repayment moved out of the reserve.
+fn repaid_referred_borrow() -> (
+    Env,
+    Address,
+    HelloContractClient<'static>,
+    Address,
+    Address,
+    i128,
+) {
+    let (env, contract_id, client, admin, user, native_asset) = setup_env_with_native_asset();
+    let referrer = Address::generate(&env);
+    client.set_referral_fee_bps(&admin, &2000);
+    client.deposit_collateral_with_referral(&user, &None, &10_000_000, &Some(referrer.clone()));
+    client.borrow_asset(&user, &None, &3_000_000);
+
+    token::StellarAssetClient::new(&env, &native_asset).mint(&user, &3_500_000);
+    token::Client::new(&env, &native_asset).approve(
+        &user,
+        &contract_id,
+        &3_500_000,
+        &(env.ledger().sequence() + 100),
+    );
+    advance(&env, 365 * 86_400);
+
+    // The reserve share of the year's interest is booked as it accrues
+    client.accrue_interest(&None);
+    let reserves = client.get_reserve_balance(&None);
+    let (remaining_debt, interest_paid, _principal_paid) =
+        client.repay_debt(&user, &None, &i128::MAX);
+    assert_eq!(remaining_debt, 0);
+
+    // 10% of the interest went to the reserve; 20% of that is the referrer's
+    let referral_cut = interest_paid * 1000 / 10_000 * 2000 / 10_000;
+    assert!(referral_cut > 0);
+    assert_eq!(client.get_reserve_balance(&None), reserves - referral_cut);
+    (
+        env,
+        contract_id,
+        client,
+        referrer,
+        native_asset,
+        referral_cut,
+    )
+}
+
+#[test]
+fn test_referral_cut_of_interest() {
+    let (_env, _contract_id, client, referrer, _native_asset, referral_cut) =
+        repaid_referred_borrow();
+
+    assert_eq!(
+        client.get_referral_rewards(&referrer).get(None),
+        Some(referral_cut)
+    );
+}
+
+#[test]
+fn test_referral_rewards_claimable_once() {
+    let (env, _contract_id, client, referrer, native_asset, referral_cut) =
+        repaid_referred_borrow();
+
+    let claimed = client.claim_referral_rewards(&referrer);
+    assert_eq!(claimed.len(), 1);
+    assert_eq!(claimed.get(0).unwrap(), (None, referral_cut));
+    assert_eq!(
+        token::Client::new(&env, &native_asset).balance(&referrer),
+        referral_cut
+    );
+    assert!(client.get_referral_rewards(&referrer).is_empty());
+
+    let second = client.try_claim_referral_rewards(&referrer);
+    assert_eq!(second, Err(Ok(DepositError::NoReferralRewards)));
+}
+
+#[test]
+fn test_set_referral_fee_requires_admin() {
+    let env = Env::default();
+    let (_contract_id, _admin, client) = setup(&env);
+    let stranger = Address::generate(&env);
+
+    let result = client.try_set_referral_fee_bps(&stranger, &1000);
+    assert_eq!(result, Err(Ok(DepositError::Unauthorized)));
+}
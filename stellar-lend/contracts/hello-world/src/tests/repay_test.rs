@@ -1,8 +1,10 @@
 use crate::deposit::{DepositDataKey, Position, ProtocolAnalytics, UserAnalytics};
+use crate::repay::RepayError;
+use crate::tests::test_helpers::{asset_params, set_oracle_price};
 use crate::{HelloContract, HelloContractClient};
 use soroban_sdk::{
     testutils::{Address as _, Ledger},
-    Address, Env,
+    vec, Address, Env,
 };
 
 /// Helper function to create a test environment
@@ -220,3 +222,437 @@ fn test_repay_interest_accrual() {
         "Interest should be tracked accurately"
     );
 }
+
+/// Initializes the contract and lists `asset` with a minimum borrow of `min_borrow`
+fn setup_min_borrow<'a>(
+    env: &'a Env,
+    asset: &Address,
+    min_borrow: i128,
+) -> (Address, HelloContractClient<'a>) {
+    let contract_id = env.register(HelloContract, ());
+    let client = HelloContractClient::new(env, &contract_id);
+    let admin = Address::generate(env);
+    client.initialize(&admin);
+
+    let params = crate::deposit::AssetParams {
+        min_borrow,
+        ..asset_params(10000)
+    };
+    client.update_asset_params(&admin, asset, &params);
+    set_oracle_price(&client, &admin, asset, &100, &7, &admin);
+    (contract_id, client)
+}
+
+#[test]
+fn test_borrow_below_min_borrow_rejected() {
+    let env = create_test_env();
+    let asset = Address::generate(&env);
+    let (contract_id, client) = setup_min_borrow(&env, &asset, 100);
+    let user = Address::generate(&env);
+    client.deposit_collateral(&user, &None, &10_000);
+
+    let result = client.try_borrow_asset(&user, &Some(asset.clone()), &99);
+    assert_eq!(result, Err(Ok(crate::borrow::BorrowError::BelowMinBorrow)));
+
+    client.borrow_asset(&user, &Some(asset.clone()), &100);
+    // Once at the minimum, smaller top-ups are fine
+    client.borrow_asset(&user, &Some(asset.clone()), &10);
+    assert_eq!(
+        get_user_position(&env, &contract_id, &user).unwrap().debt,
+        110
+    );
+}
+
+#[test]
+fn test_partial_repay_leaving_dust_rejected() {
+    let env = create_test_env();
+    let asset = Address::generate(&env);
+    let (contract_id, client) = setup_min_borrow(&env, &asset, 100);
+    let user = Address::generate(&env);
+    client.deposit_collateral(&user, &None, &10_000);
+    client.borrow_asset(&user, &Some(asset.clone()), &300);
+
+    let result = client.try_repay_debt(&user, &Some(asset.clone()), &250);
+    assert_eq!(result, Err(Ok(crate::repay::RepayError::DustDebtRemaining)));
+    assert_eq!(
+        get_user_position(&env, &contract_id, &user).unwrap().debt,
+        300
+    );
+
+    // Leaving exactly the minimum is allowed
+    client.repay_debt(&user, &Some(asset.clone()), &200);
+    assert_eq!(
+        get_user_position(&env, &contract_id, &user).unwrap().debt,
+        100
+    );
+
+    let result = client.try_repay_debt(&user, &Some(asset.clone()), &1);
+    assert_eq!(result, Err(Ok(crate::repay::RepayError::DustDebtRemaining)));
+}
+
+#[test]
+fn test_full_repay_always_allowed_with_min_borrow() {
+    let env = create_test_env();
+    let asset = Address::generate(&env);
+    let (contract_id, client) = setup_min_borrow(&env, &asset, 100);
+    let user = Address::generate(&env);
+    client.deposit_collateral(&user, &None, &10_000);
+
+    client.borrow_asset(&user, &Some(asset.clone()), &150);
+    client.repay_debt(&user, &Some(asset.clone()), &150);
+    assert_eq!(
+        get_user_position(&env, &contract_id, &user).unwrap().debt,
+        0
+    );
+
+    // Overpaying is capped at the debt and also clears it
+    client.borrow_asset(&user, &Some(asset.clone()), &150);
+    client.repay_debt(&user, &Some(asset.clone()), &1_000);
+    assert_eq!(
+        get_user_position(&env, &contract_id, &user).unwrap().debt,
+        0
+    );
+}
+
+#[test]
+fn test_user_debt_view_matches_full_repayment() {
+    let env = create_test_env();
+    env.ledger().with_mut(|li| li.timestamp = 1000);
+    let asset = Address::generate(&env);
+    let (_contract_id, client) = setup_min_borrow(&env, &asset, 0);
+    let user = Address::generate(&env);
+    client.deposit_collateral(&user, &None, &10_000);
+    client.borrow_asset(&user, &Some(asset.clone()), &1_000);
+
+    env.ledger().with_mut(|li| li.timestamp += 31_536_000);
+    let debt = client.get_user_debt(&user, &Some(asset.clone()));
+    assert!(debt > 1_000, "Interest should be included");
+    assert_eq!(
+        client.get_user_debts(&user),
+        vec![&env, (Some(asset.clone()), debt)]
+    );
+    assert_eq!(client.get_user_debt(&user, &None), 0);
+
+    // The view does not accrue anything itself
+    assert_eq!(client.get_user_debt(&user, &Some(asset.clone())), debt);
+
+    let (remaining_debt, interest_paid, principal_paid) =
+        client.repay_debt(&user, &Some(asset.clone()), &debt);
+    assert_eq!(remaining_debt, 0);
+    assert_eq!(principal_paid, 1_000);
+    assert_eq!(interest_paid + principal_paid, debt);
+    assert_eq!(client.get_user_debt(&user, &Some(asset)), 0);
+    assert!(client.get_user_debts(&user).is_empty());
+}
+
+/// Borrows 1_000 native XLM against 10_000 and lets a year of interest accrue;
+/// the user holds 2_000 of the native token, approved for the contract
+fn setup_accrued_native_debt() -> (Env, Address, HelloContractClient<'static>, Address, Address) {
+    let (env, contract_id, client, _admin, user, native_asset) =
+        crate::tests::test_helpers::setup_env_with_native_asset();
+    env.ledger().with_mut(|li| li.timestamp = 1000);
+    client.deposit_collateral(&user, &None, &10_000);
+    client.borrow_asset(&user, &None, &1_000);
+
+    let token_client = soroban_sdk::token::StellarAssetClient::new(&env, &native_asset);
+    token_client.mint(&user, &2_000);
+    token_client.approve(
+        &user,
+        &contract_id,
+        &2_000,
+        &(env.ledger().sequence() + 100),
+    );
+    env.ledger().with_mut(|li| li.timestamp += 31_536_000);
+    (env, contract_id, client, user, native_asset)
+}
+
+#[test]
+fn test_repay_max_sentinel_takes_exact_debt() {
+    let (env, contract_id, client, user, native_asset) = setup_accrued_native_debt();
+    let token = soroban_sdk::token::Client::new(&env, &native_asset);
+    let owed = client.get_user_debt(&user, &None);
+    assert!(owed > 1_000, "Interest should have accrued");
+
+    let (remaining_debt, interest_paid, principal_paid) =
+        client.repay_debt(&user, &None, &i128::MAX);
+    assert_eq!(remaining_debt, 0);
+    assert_eq!(principal_paid, 1_000);
+    assert_eq!(interest_paid + principal_paid, owed);
+    assert_eq!(token.balance(&user), 2_000 - owed);
+    assert_eq!(token.balance(&contract_id), owed);
+
+    let position = get_user_position(&env, &contract_id, &user).unwrap();
+    assert_eq!(position.debt, 0);
+    assert_eq!(position.borrow_interest, 0);
+    assert!(client.get_user_debts(&user).is_empty());
+}
+
+#[test]
+fn test_repay_overpayment_only_takes_owed_amount() {
+    let (env, contract_id, client, user, native_asset) = setup_accrued_native_debt();
+    let token = soroban_sdk::token::Client::new(&env, &native_asset);
+    let owed = client.get_user_debt(&user, &None);
+
+    let (remaining_debt, interest_paid, principal_paid) =
+        client.repay_debt(&user, &None, &(owed + 500));
+    assert_eq!(remaining_debt, 0);
+    assert_eq!(interest_paid + principal_paid, owed);
+
+    // The surplus never leaves the user's account
+    assert_eq!(token.balance(&user), 2_000 - owed);
+    assert_eq!(token.balance(&contract_id), owed);
+}
+
+/// `user` deposits 10_000 native XLM and borrows 4_000 of the returned debt
+/// asset: native, or a token priced like the native asset when `cross_asset`
+fn setup_collateral_repay(
+    cross_asset: bool,
+) -> (
+    Env,
+    Address,
+    HelloContractClient<'static>,
+    Address,
+    Address,
+    Option<Address>,
+) {
+    let (env, contract_id, client, admin, user, native_asset) =
+        crate::tests::test_helpers::setup_env_with_native_asset();
+    set_oracle_price(&client, &admin, &native_asset, &100, &7, &admin);
+    let debt_asset = if cross_asset {
+        let token = Address::generate(&env);
+        set_oracle_price(&client, &admin, &token, &100, &7, &admin);
+        Some(token)
+    } else {
+        None
+    };
+    client.deposit_collateral(&user, &None, &10_000);
+    client.borrow_asset(&user, &debt_asset, &4_000);
+    (env, contract_id, client, admin, user, debt_asset)
+}
+
+#[test]
+fn test_repay_with_same_asset_collateral() {
+    let (env, contract_id, client, _admin, user, _) = setup_collateral_repay(false);
+    let health_before = client.get_health_factor(&user);
+
+    // No swap: the deposit is applied to the debt one for one
+    let (remaining_debt, collateral_used) =
+        client.repay_with_collateral(&user, &None, &None, &1_500, &1_500);
+    assert_eq!(remaining_debt, 2_500);
+    assert_eq!(collateral_used, 1_500);
+    assert_eq!(client.get_user_debt(&user, &None), 2_500);
+    assert_eq!(client.get_user_deposit(&user, &None), 8_500);
+    assert!(client.get_health_factor(&user) > health_before);
+
+    let position = get_user_position(&env, &contract_id, &user).unwrap();
+    assert_eq!(position.collateral, 8_500);
+    assert_eq!(position.debt, 2_500);
+}
+
+#[test]
+fn test_repay_with_collateral_rejects_cross_asset() {
+    let (env, contract_id, client, _admin, user, asset) = setup_collateral_repay(true);
+
+    // Native collateral would have to be swapped into the debt token
+    let result = client.try_repay_with_collateral(&user, &asset, &None, &2_000, &2_100);
+    assert_eq!(result, Err(Ok(RepayError::InvalidAsset)));
+
+    // Nothing was seized or repaid
+    assert_eq!(client.get_user_debt(&user, &asset), 4_000);
+    assert_eq!(client.get_user_deposit(&user, &None), 10_000);
+    let position = get_user_position(&env, &contract_id, &user).unwrap();
+    assert_eq!(position.collateral, 10_000);
+}
+
+#[test]
+fn test_repay_with_collateral_rejects_above_max_collateral_in() {
+    let (env, contract_id, client, _admin, user, _) = setup_collateral_repay(false);
+
+    let result = client.try_repay_with_collateral(&user, &None, &None, &1_500, &1_499);
+    assert_eq!(result, Err(Ok(RepayError::SlippageExceeded)));
+
+    // Nothing was seized or repaid
+    assert_eq!(client.get_user_debt(&user, &None), 4_000);
+    assert_eq!(client.get_user_deposit(&user, &None), 10_000);
+    let position = get_user_position(&env, &contract_id, &user).unwrap();
+    assert_eq!(position.collateral, 10_000);
+}
+
+#[test]
+fn test_repaid_interest_splits_between_reserve_and_suppliers() {
+    let (env, contract_id, client, admin, user, native_asset) =
+        crate::tests::test_helpers::setup_env_with_native_asset();
+    env.ledger().with_mut(|li| li.timestamp = 1000);
+    client.set_reserve_factor(&admin, &2000);
+    assert_eq!(client.get_reserve_factor(), 2000);
+
+    let supplier = Address::generate(&env);
+    client.deposit_collateral(&supplier, &None, &10_000);
+    client.deposit_collateral(&user, &None, &10_000);
+    client.borrow_asset(&user, &None, &1_000);
+
+    let token_client = soroban_sdk::token::StellarAssetClient::new(&env, &native_asset);
+    token_client.mint(&user, &2_000);
+    token_client.approve(
+        &user,
+        &contract_id,
+        &2_000,
+        &(env.ledger().sequence() + 100),
+    );
+    env.ledger().with_mut(|li| li.timestamp += 31_536_000);
+
+    let reserve_before = client.get_reserve_balance(&None);
+    let (remaining_debt, interest_paid, principal_paid) =
+        client.repay_debt(&user, &None, &i128::MAX);
+    assert_eq!(remaining_debt, 0);
+    assert_eq!(principal_paid, 1_000);
+    assert!(interest_paid > 0, "Interest should have accrued");
+
+    // 20% of the interest accrued to the reserve on the way in, suppliers earn
+    // the rest; the two roundings may differ by a unit
+    let reserve_growth = client.get_reserve_balance(&None) - reserve_before;
+    assert!((reserve_growth - interest_paid * 2000 / 10_000).abs() <= 1);
+    assert!(reserve_growth > 0);
+    assert!(client.get_user_deposit(&supplier, &None) > 10_000);
+}
+
+#[test]
+fn test_repay_quote_now_matches_full_repayment() {
+    let (env, _contract_id, client, user, native_asset) = setup_accrued_native_debt();
+    let token = soroban_sdk::token::Client::new(&env, &native_asset);
+
+    let quote = client.get_repay_quote(&user, &None, &None);
+    assert_eq!(quote.timestamp, env.ledger().timestamp());
+    assert_eq!(quote.projected_debt, quote.debt);
+    assert!(quote.debt > 1_000, "Interest should be included");
+
+    let (remaining_debt, interest_paid, principal_paid) =
+        client.repay_debt(&user, &None, &i128::MAX);
+    assert_eq!(remaining_debt, 0);
+    assert_eq!(interest_paid + principal_paid, quote.debt);
+    assert_eq!(token.balance(&user), 2_000 - quote.debt);
+}
+
+#[test]
+fn test_repay_quote_projects_interest_at_current_rate() {
+    let (env, _contract_id, client, _admin, user, _native_asset) =
+        crate::tests::test_helpers::setup_env_with_native_asset();
+    env.ledger().with_mut(|li| li.timestamp = 1000);
+    client.deposit_collateral(&user, &None, &10_000_000_000);
+    client.borrow_asset(&user, &None, &1_000_000_000);
+
+    // One day of index growth at the current rate; debt rounds up at each step
+    let day = 86_400;
+    let rate = client.get_borrow_rate();
+    let scale = crate::borrow::BORROW_INDEX_SCALE;
+    let year = 10_000 * 31_536_000;
+    let growth = (scale * rate * day as i128 + year - 1) / year;
+    let expected_interest = (1_000_000_000 * growth + scale - 1) / scale;
+    assert!(expected_interest > 0);
+
+    let at = env.ledger().timestamp() + day;
+    let quote = client.get_repay_quote(&user, &None, &Some(at));
+    assert_eq!(quote.debt, 1_000_000_000);
+    assert_eq!(quote.timestamp, at);
+    assert_eq!(quote.projected_debt - quote.debt, expected_interest);
+
+    // Past timestamps quote the current debt
+    let past = client.get_repay_quote(&user, &None, &Some(1));
+    assert_eq!(past.projected_debt, past.debt);
+
+    // A day later the spot quote is what was projected
+    env.ledger().with_mut(|li| li.timestamp = at);
+    assert_eq!(
+        client.get_repay_quote(&user, &None, &None).debt,
+        quote.projected_debt
+    );
+}
+
+/// Repaying the original principal after a long idle period leaves the interest
+/// accrued meanwhile, not zero
+#[test]
+fn test_repay_after_idle_period_settles_accrued_debt() {
+    let (_env, _contract_id, client, user, _native_asset) = setup_accrued_native_debt();
+
+    let (remaining_debt, interest_paid, principal_paid) = client.repay_debt(&user, &None, &1_000);
+    assert!(interest_paid > 0, "Interest should have accrued while idle");
+    assert_eq!(interest_paid + principal_paid, 1_000);
+    assert_eq!(remaining_debt, interest_paid);
+    assert_eq!(client.get_user_debt(&user, &None), remaining_debt);
+}
+
+/// `user` owes 1_000 of a registered token against native collateral and holds
+/// 2_000 of it, approved for the contract
+fn setup_token_debt<'a>(
+    env: &'a Env,
+    user: &Address,
+) -> (Address, HelloContractClient<'a>, Address) {
+    let token = env.register_stellar_asset_contract(Address::generate(env));
+    let (contract_id, client) = setup_min_borrow(env, &token, 0);
+    client.deposit_collateral(user, &None, &10_000);
+    client.borrow_asset(user, &Some(token.clone()), &1_000);
+
+    soroban_sdk::token::StellarAssetClient::new(env, &token).mint(user, &2_000);
+    soroban_sdk::token::Client::new(env, &token).approve(
+        user,
+        &contract_id,
+        &2_000,
+        &(env.ledger().sequence() + 100),
+    );
+    (contract_id, client, token)
+}
+
+#[test]
+fn test_repay_and_deposit_supplies_surplus() {
+    let env = create_test_env();
+    let user = Address::generate(&env);
+    let (_contract_id, client, token) = setup_token_debt(&env, &user);
+    let asset = Some(token);
+
+    let result = client.repay_and_deposit(&user, &asset, &1_500, &true);
+    assert_eq!(result, (0, 0, 1_000, 500));
+    assert_eq!(client.get_user_debt(&user, &asset), 0);
+    assert_eq!(client.get_user_deposit(&user, &asset), 500);
+}
+
+#[test]
+fn test_repay_and_deposit_reverts_when_surplus_exceeds_cap() {
+    let env = create_test_env();
+    let user = Address::generate(&env);
+    let (contract_id, client, token) = setup_token_debt(&env, &user);
+    env.as_contract(&contract_id, || {
+        let key = DepositDataKey::AssetParams(token.clone());
+        let mut params = env
+            .storage()
+            .persistent()
+            .get::<DepositDataKey, crate::deposit::AssetParams>(&key)
+            .unwrap();
+        params.max_deposit_per_user = 400;
+        env.storage().persistent().set(&key, &params);
+    });
+    let asset = Some(token);
+
+    let result = client.try_repay_and_deposit(&user, &asset, &1_500, &true);
+    assert_eq!(result, Err(Ok(RepayError::SurplusDepositRejected)));
+
+    // The repayment was reverted along with the deposit
+    assert_eq!(client.get_user_debt(&user, &asset), 1_000);
+    assert_eq!(client.get_user_deposit(&user, &asset), 0);
+}
+
+#[test]
+fn test_repay_and_deposit_without_surplus() {
+    let env = create_test_env();
+    let user = Address::generate(&env);
+    let (_contract_id, client, token) = setup_token_debt(&env, &user);
+    let asset = Some(token);
+
+    // A partial repayment leaves nothing to deposit
+    let result = client.repay_and_deposit(&user, &asset, &400, &true);
+    assert_eq!(result, (600, 0, 400, 0));
+
+    // Without the flag the surplus is neither taken nor deposited
+    let result = client.repay_and_deposit(&user, &asset, &1_000, &false);
+    assert_eq!(result, (0, 0, 600, 0));
+    assert_eq!(client.get_user_deposit(&user, &asset), 0);
+}
@@ -0,0 +1,141 @@
+//! Tests for installment repayment schedules.
+//!
+//! # Coverage
+//! - Installments paid on time carry no penalty and advance the schedule
+//! - A late installment accrues penalty interest on its amount until it is paid
+//! - Installments overdue past the delinquency threshold make a healthy account liquidatable
+//! - Creating a schedule without debt, or a second one for the same asset, is rejected
+
+use crate::liquidate::LiquidationError;
+use crate::repay::RepayError;
+use crate::tests::test_helpers::{advance, setup_env_with_native_asset};
+use crate::HelloContractClient;
+use soroban_sdk::{testutils::Address as _, Address, Env};
+
+const DAY: u64 = 86_400;
+
+/// `user` and `control` each borrow 3_000_000 native against 10_000_000; only
+/// `user` has a schedule of three installments, one every 30 days. Overdue
+/// installments carry a 10% penalty and become delinquent after 15 days.
+fn setup() -> (
+    Env,
+    HelloContractClient<'static>,
+    Address,
+    Address,
+    Address,
+    u64,
+) {
+    let (env, contract_id, client, admin, user, native_asset) = setup_env_with_native_asset();
+    let control = Address::generate(&env);
+    let token = soroban_sdk::token::StellarAssetClient::new(&env, &native_asset);
+    token.mint(&user, &3_000_000);
+    token.approve(
+        &user,
+        &contract_id,
+        &3_000_000,
+        &(env.ledger().sequence() + 100),
+    );
+
+    client.set_repayment_penalty_config(&admin, &1000, &(15 * DAY));
+    for borrower in [&user, &control] {
+        client.deposit_collateral(borrower, &None, &10_000_000);
+        client.borrow_asset(borrower, &None, &3_000_000);
+    }
+    let id = client.create_repayment_schedule(&user, &None, &3, &(30 * DAY));
+    (env, client, admin, user, control, id)
+}
+
+/// How much more `user` owes than `control`
+fn debt_gap(client: &HelloContractClient, user: &Address, control: &Address) -> i128 {
+    client.get_user_debt(user, &None) - client.get_user_debt(control, &None)
+}
+
+#[test]
+fn test_installment_paid_on_time_carries_no_penalty() {
+    let (env, client, _admin, user, control, id) = setup();
+    let schedule = client.get_repayment_schedule(&id).unwrap();
+    assert_eq!(schedule.total_amount, 3_000_000);
+    assert_eq!(schedule.installments_paid, 0);
+
+    advance(&env, 30 * DAY);
+    assert_eq!(debt_gap(&client, &user, &control), 0);
+
+    let (remaining_debt, paid) = client.repay_installment(&user, &id);
+    assert_eq!(paid, 1_000_000);
+    assert_eq!(
+        remaining_debt,
+        client.get_user_debt(&control, &None) - 1_000_000
+    );
+    let schedule = client.get_repayment_schedule(&id).unwrap();
+    assert_eq!(schedule.installments_paid, 1);
+    assert_eq!(schedule.amount_paid, 1_000_000);
+    assert!(!client.is_repayment_delinquent(&user));
+}
+
+#[test]
+fn test_late_installment_accrues_penalty_until_paid() {
+    let (env, client, _admin, user, control, id) = setup();
+
+    // Ten days late: 1_000_000 * 10% * 10 / 365, rounded up
+    advance(&env, 40 * DAY);
+    assert_eq!(debt_gap(&client, &user, &control), 2_740);
+    assert!(!client.is_repayment_delinquent(&user));
+
+    // The payment settles the penalty first and stops it accruing
+    let control_debt = client.get_user_debt(&control, &None);
+    let (remaining_debt, paid) = client.repay_installment(&user, &id);
+    assert_eq!(paid, 1_000_000);
+    assert_eq!(remaining_debt, control_debt + 2_740 - 1_000_000);
+    let schedule = client.get_repayment_schedule(&id).unwrap();
+    assert_eq!(schedule.installments_paid, 1);
+    assert_eq!(schedule.penalty_accrued_until, env.ledger().timestamp());
+
+    // The second installment is not due yet, so the account stays safe
+    advance(&env, 10 * DAY);
+    assert!(!client.is_repayment_delinquent(&user));
+}
+
+#[test]
+fn test_delinquent_schedule_makes_account_liquidatable() {
+    let (env, client, _admin, user, control, id) = setup();
+    let liquidator = Address::generate(&env);
+
+    // One day short of the 15-day threshold on the first installment
+    advance(&env, 44 * DAY);
+    assert!(!client.is_repayment_delinquent(&user));
+    let result = client.try_liquidate(&liquidator, &user, &None, &None, &1_000_000, &false);
+    assert_eq!(result, Err(Ok(LiquidationError::PositionHealthy)));
+
+    // Every installment is due; the first is 60 days late and the second 30
+    advance(&env, 46 * DAY);
+    assert!(client.is_repayment_delinquent(&user));
+    assert_eq!(debt_gap(&client, &user, &control), 16_439 + 8_220);
+    assert!(client.get_health_factor(&user) > 10_000);
+
+    let (debt_liquidated, _collateral_seized, _incentive, _protocol_fee) =
+        client.liquidate(&liquidator, &user, &None, &None, &1_000_000, &false);
+    assert_eq!(debt_liquidated, 1_000_000);
+
+    // Liquidation does not count towards the schedule
+    let schedule = client.get_repayment_schedule(&id).unwrap();
+    assert_eq!(schedule.installments_paid, 0);
+}
+
+#[test]
+fn test_create_repayment_schedule_rejections() {
+    let (env, client, admin, user, _control, _id) = setup();
+
+    let result = client.try_create_repayment_schedule(&user, &None, &3, &(30 * DAY));
+    assert_eq!(result, Err(Ok(RepayError::ScheduleExists)));
+    let result = client.try_create_repayment_schedule(&user, &None, &0, &(30 * DAY));
+    assert_eq!(result, Err(Ok(RepayError::InvalidAmount)));
+
+    let lender = Address::generate(&env);
+    let result = client.try_create_repayment_schedule(&lender, &None, &3, &(30 * DAY));
+    assert_eq!(result, Err(Ok(RepayError::NoDebt)));
+
+    let result = client.try_set_repayment_penalty_config(&user, &1000, &DAY);
+    assert_eq!(result, Err(Ok(RepayError::Unauthorized)));
+    let result = client.try_set_repayment_penalty_config(&admin, &10_001, &DAY);
+    assert_eq!(result, Err(Ok(RepayError::InvalidAmount)));
+}
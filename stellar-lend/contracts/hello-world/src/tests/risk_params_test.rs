@@ -31,7 +31,15 @@ fn test_set_risk_params_success() {
     
     // Change parameters within allowed limit (e.g. 1% or less)
     // Default 11_000, 1% change is 110. Let's use 11_100.
-    client.set_risk_params(&admin, &Some(11_100), &Some(10_600), &Some(5_100), &Some(1_050));
+    client.set_risk_params(
+        &admin,
+        &Some(11_100),
+        &Some(10_600),
+        &Some(5_100),
+        &Some(1_050),
+        &None,
+        &None,
+    );
     
     assert_eq!(client.get_min_collateral_ratio(), 11_100);
     assert_eq!(client.get_liquidation_threshold(), 10_600);
@@ -44,7 +52,15 @@ fn test_set_risk_params_unauthorized() {
     let (env, client, _admin) = setup_test();
     let not_admin = Address::generate(&env);
     
-    let result = client.try_set_risk_params(&not_admin, &Some(11_100), &None, &None, &None);
+    let result = client.try_set_risk_params(
+        &not_admin,
+        &Some(11_100),
+        &None,
+        &None,
+        &None,
+        &None,
+        &None,
+    );
     match result {
         Err(Ok(RiskManagementError::Unauthorized)) => {},
         _ => panic!("Expected Unauthorized error, got {:?}", result),
@@ -57,7 +73,15 @@ fn test_set_risk_params_exceeds_change_limit() {
     
     // Default is 11_000, 10% change max is 1_100, so new value <= 12_100
     // Try setting to 12_200, should fail with ParameterChangeTooLarge
-    let result = client.try_set_risk_params(&admin, &Some(12_200), &None, &None, &None);
+    let result = client.try_set_risk_params(
+        &admin,
+        &Some(12_200),
+        &None,
+        &None,
+        &None,
+        &None,
+        &None,
+    );
     match result {
         Err(Ok(RiskManagementError::ParameterChangeTooLarge)) => {},
         _ => panic!("Expected ParameterChangeTooLarge error, got {:?}", result),
@@ -72,7 +96,15 @@ fn test_set_risk_params_invalid_collateral_ratio() {
     // Try to set liquidation_threshold to 11_500, which is over min_cr
     // Fail with InvalidCollateralRatio
     // Note: 11_500 is within 10% change limit from 10_500 (1050 max change)
-    let result = client.try_set_risk_params(&admin, &None, &Some(11_500), &None, &None);
+    let result = client.try_set_risk_params(
+        &admin,
+        &None,
+        &Some(11_500),
+        &None,
+        &None,
+        &None,
+        &None,
+    );
     match result {
         Err(Ok(RiskManagementError::InvalidCollateralRatio)) => {},
         _ => panic!("Expected InvalidCollateralRatio error, got {:?}", result),
@@ -114,7 +146,7 @@ fn test_get_liquidation_incentive_amount() {
 //! - Min collateral ratio must be >= liquidation threshold.
 //! - Close factor in [0, 100%], liquidation incentive in [0, 50%].
 
-use crate::{HelloContract, HelloContractClient};
+use crate::tests::test_helpers::setup;
 use soroban_sdk::{testutils::Address as _, Address, Env, Symbol};
 
 // =============================================================================
@@ -128,15 +160,6 @@ fn create_test_env() -> Env {
     env
 }
 
-/// Sets up contract and admin, initializes. Pass env from create_test_env() so client outlives env.
-fn setup(env: &Env) -> (Address, Address, HelloContractClient<'_>) {
-    let contract_id = env.register(HelloContract, ());
-    let client = HelloContractClient::new(env, &contract_id);
-    let admin = Address::generate(env);
-    client.initialize(&admin);
-    (contract_id, admin, client)
-}
-
 // =============================================================================
 // SET / GET PARAMS
 // =============================================================================
@@ -180,6 +203,8 @@ fn risk_params_set_all_and_get() {
         &Some(11_000),
         &Some(5_500),
         &Some(1_100),
+        &None,
+        &None,
     );
 
     let config = client.get_risk_config().unwrap();
@@ -200,7 +225,7 @@ fn risk_params_set_partial_min_cr_only() {
     let env = create_test_env();
     let (_cid, admin, client) = setup(&env);
 
-    client.set_risk_params(&admin, &Some(12_000), &None, &None, &None);
+    client.set_risk_params(&admin, &Some(12_000), &None, &None, &None, &None, &None);
 
     assert_eq!(client.get_min_collateral_ratio(), 12_000);
     assert_eq!(client.get_liquidation_threshold(), 10_500);
@@ -214,7 +239,7 @@ fn risk_params_set_partial_liq_threshold_only() {
     let env = create_test_env();
     let (_cid, admin, client) = setup(&env);
 
-    client.set_risk_params(&admin, &None, &Some(11_000), &None, &None);
+    client.set_risk_params(&admin, &None, &Some(11_000), &None, &None, &None, &None);
 
     assert_eq!(client.get_min_collateral_ratio(), 11_000);
     assert_eq!(client.get_liquidation_threshold(), 11_000);
@@ -226,7 +251,7 @@ fn risk_params_set_partial_close_factor_and_incentive() {
     let env = create_test_env();
     let (_cid, admin, client) = setup(&env);
 
-    client.set_risk_params(&admin, &None, &None, &Some(4_500), &Some(900));
+    client.set_risk_params(&admin, &None, &None, &Some(4_500), &Some(900), &None, &None);
 
     assert_eq!(client.get_close_factor(), 4_500);
     assert_eq!(client.get_liquidation_incentive(), 900);
@@ -243,7 +268,7 @@ fn risk_params_set_unauthorized() {
     let env = create_test_env();
     let (_cid, _admin, client) = setup(&env);
     let non_admin = Address::generate(&env);
-    client.set_risk_params(&non_admin, &Some(12_000), &None, &None, &None);
+    client.set_risk_params(&non_admin, &Some(12_000), &None, &None, &None, &None, &None);
 }
 
 /// Min collateral ratio below allowed minimum (10_000) or change too large leads to error.
@@ -254,7 +279,7 @@ fn risk_params_set_change_too_large_min_cr() {
     let env = create_test_env();
     let (_cid, admin, client) = setup(&env);
     // Default 11_000; 10% max change = 1_100; 15_000 is +4_000
-    client.set_risk_params(&admin, &Some(15_000), &None, &None, &None);
+    client.set_risk_params(&admin, &Some(15_000), &None, &None, &None, &None, &None);
 }
 
 /// Min collateral ratio below liquidation threshold returns InvalidCollateralRatio (#7).
@@ -269,6 +294,8 @@ fn risk_params_set_min_cr_below_liquidation_threshold() {
         &Some(10_500), // threshold > min_cr
         &None,
         &None,
+        &None,
+        &None,
     );
 }
 
@@ -278,7 +305,7 @@ fn risk_params_set_min_cr_below_liquidation_threshold() {
 fn risk_params_set_close_factor_over_max() {
     let env = create_test_env();
     let (_cid, admin, client) = setup(&env);
-    client.set_risk_params(&admin, &None, &None, &Some(10_001), &None);
+    client.set_risk_params(&admin, &None, &None, &Some(10_001), &None, &None, &None);
 }
 
 /// Liquidation incentive above 50% (5_001 bps) fails; large change triggers ParameterChangeTooLarge.
@@ -287,7 +314,7 @@ fn risk_params_set_close_factor_over_max() {
 fn risk_params_set_liquidation_incentive_over_max() {
     let env = create_test_env();
     let (_cid, admin, client) = setup(&env);
-    client.set_risk_params(&admin, &None, &None, &None, &Some(5_001));
+    client.set_risk_params(&admin, &None, &None, &None, &Some(5_001), &None, &None);
 }
 
 /// Multiple steps within 10% each can reach new target (e.g. min_cr from 11_000 to 13_000 in two steps).
@@ -297,11 +324,11 @@ fn risk_params_multiple_steps_within_change_limit() {
     let (_cid, admin, client) = setup(&env);
 
     // 11_000 -> 12_100 (10% increase)
-    client.set_risk_params(&admin, &Some(12_100), &None, &None, &None);
+    client.set_risk_params(&admin, &Some(12_100), &None, &None, &None, &None, &None);
     assert_eq!(client.get_min_collateral_ratio(), 12_100);
 
     // 12_100 -> 13_310 (10% increase)
-    client.set_risk_params(&admin, &Some(13_310), &None, &None, &None);
+    client.set_risk_params(&admin, &Some(13_310), &None, &None, &None, &None, &None);
     assert_eq!(client.get_min_collateral_ratio(), 13_310);
 }
 
@@ -337,7 +364,7 @@ fn risk_params_enforcement_require_min_collateral_ratio_fail() {
 fn risk_params_enforcement_require_min_cr_after_param_change() {
     let env = create_test_env();
     let (_cid, admin, client) = setup(&env);
-    client.set_risk_params(&admin, &Some(12_000), &None, &None, &None);
+    client.set_risk_params(&admin, &Some(12_000), &None, &None, &None, &None, &None);
     client.require_min_collateral_ratio(&1_150, &1_000);
 }
 
@@ -359,7 +386,7 @@ fn risk_params_enforcement_can_be_liquidated() {
 fn risk_params_enforcement_can_be_liquidated_after_threshold_change() {
     let env = create_test_env();
     let (_cid, admin, client) = setup(&env);
-    client.set_risk_params(&admin, &Some(12_000), &Some(11_500), &None, &None);
+    client.set_risk_params(&admin, &Some(12_000), &Some(11_500), &None, &None, &None, &None);
     // 110% < 115% threshold
     assert!(client.can_be_liquidated(&1_100, &1_000));
 }
@@ -372,10 +399,10 @@ fn risk_params_enforcement_max_liquidatable_amount() {
 
     assert_eq!(client.get_max_liquidatable_amount(&1_000), 500);
     // 50% -> 55% (10% increase)
-    client.set_risk_params(&admin, &None, &None, &Some(5_500), &None);
+    client.set_risk_params(&admin, &None, &None, &Some(5_500), &None, &None, &None);
     assert_eq!(client.get_max_liquidatable_amount(&1_000), 550);
     // 55% -> 49.5% (10% decrease from 5_500 = 550, so 4_950)
-    client.set_risk_params(&admin, &None, &None, &Some(4_950), &None);
+    client.set_risk_params(&admin, &None, &None, &Some(4_950), &None, &None, &None);
     assert_eq!(client.get_max_liquidatable_amount(&1_000), 495);
 }
 
@@ -386,7 +413,7 @@ fn risk_params_enforcement_liquidation_incentive_amount() {
     let (_cid, admin, client) = setup(&env);
 
     assert_eq!(client.get_liquidation_incentive_amount(&1_000), 100);
-    client.set_risk_params(&admin, &None, &None, &None, &Some(1_100));
+    client.set_risk_params(&admin, &None, &None, &None, &Some(1_100), &None, &None);
     assert_eq!(client.get_liquidation_incentive_amount(&1_000), 110);
 }
 
@@ -448,7 +475,7 @@ fn risk_params_emergency_pause_blocks_set_risk_params() {
     let env = create_test_env();
     let (_cid, admin, client) = setup(&env);
     client.set_emergency_pause(&admin, &true);
-    client.set_risk_params(&admin, &Some(12_000), &None, &None, &None);
+    client.set_risk_params(&admin, &Some(12_000), &None, &None, &None, &None, &None);
 }
 
 // =============================================================================
@@ -467,6 +494,8 @@ fn risk_params_edge_at_minimum_bounds() {
         &Some(10_000),
         &Some(4_500),
         &Some(900),
+        &None,
+        &None,
     );
     assert_eq!(client.get_min_collateral_ratio(), 10_000);
     assert_eq!(client.get_liquidation_threshold(), 10_000);
@@ -481,11 +510,11 @@ fn risk_params_edge_close_factor_boundaries() {
     let (_cid, admin, client) = setup(&env);
 
     // 50% -> 45% (10% decrease)
-    client.set_risk_params(&admin, &None, &None, &Some(4_500), &None);
+    client.set_risk_params(&admin, &None, &None, &Some(4_500), &None, &None, &None);
     assert_eq!(client.get_max_liquidatable_amount(&1_000), 450);
 
     // 45% -> 40.5% -> ... we can step down; 0% requires multiple steps
-    client.set_risk_params(&admin, &None, &None, &Some(4_050), &None);
+    client.set_risk_params(&admin, &None, &None, &Some(4_050), &None, &None, &None);
     assert_eq!(client.get_max_liquidatable_amount(&1_000), 405);
 }
 
@@ -35,7 +35,7 @@ fn security_unauthorized_set_risk_params() {
     let admin = Address::generate(&env);
     let non_admin = Address::generate(&env);
     client.initialize(&admin);
-    client.set_risk_params(&non_admin, &Some(12_000), &None, &None, &None);
+    client.set_risk_params(&non_admin, &Some(12_000), &None, &None, &None, &None, &None);
 }
 
 /// Negative amount rejected on deposit (invalid input).
@@ -88,5 +88,5 @@ fn security_risk_param_change_too_large() {
     let client = HelloContractClient::new(&env, &contract_id);
     let admin = Address::generate(&env);
     client.initialize(&admin);
-    client.set_risk_params(&admin, &Some(20_000), &None, &None, &None);
+    client.set_risk_params(&admin, &Some(20_000), &None, &None, &None, &None, &None);
 }
@@ -3,6 +3,7 @@ use crate::risk_management::RiskDataKey;
 use crate::analytics::{AnalyticsDataKey, ProtocolMetrics, ActivityEntry};
 use crate::oracle::{OracleDataKey, PriceFeed, OracleConfig};
 use crate::interest_rate::InterestRateDataKey;
+use crate::tests::test_helpers::set_oracle_price;
 use crate::{HelloContract, HelloContractClient};
 use soroban_sdk::{
     testutils::Address as _,
@@ -76,7 +77,7 @@ fn test_oracle_storage_layout() {
     client.initialize(&admin);
     
     let price = 100_000_0000000i128; // $100
-    client.update_price_feed(&admin, &asset, &price, &7, &oracle);
+    set_oracle_price(&client, &admin, &asset, &price, &7, &oracle);
 
     env.as_contract(&contract_id, || {
         // Check PriceFeed
@@ -87,7 +88,7 @@ fn test_oracle_storage_layout() {
         // Check OracleConfig defaults
         let config: OracleConfig = env.storage().persistent().get(&OracleDataKey::OracleConfig).unwrap_or(OracleConfig {
             max_deviation_bps: 500,
-            max_staleness_seconds: 3600,
+            max_price_age_secs: 3600,
             cache_ttl_seconds: 300,
             min_price: 1,
             max_price: i128::MAX,
@@ -0,0 +1,172 @@
+//! Tests for fixed-term, fixed-rate loans.
+//!
+//! # Coverage
+//! - A term loan locks the variable rate plus the term premium, and repaying
+//!   principal before maturity charges the early repayment fee to reserves
+//! - Past maturity the debt accrues at the variable rate plus the late penalty
+//! - Term debt counts toward health and is liquidated like variable debt
+//! - Unoffered terms, second term loans and mixing modes are rejected
+
+use crate::borrow::{BorrowError, RateMode};
+use crate::interest_rate::calculate_accrued_debt;
+use crate::tests::test_helpers::{
+    advance, funded_token, set_oracle_price, setup, setup_env_with_native_asset,
+};
+use crate::HelloContractClient;
+use soroban_sdk::{testutils::Address as _, token, Address, Env};
+
+const DAY: u64 = 86_400;
+const TERM: u64 = 90 * DAY;
+
+/// 90-day loans carry a 2% premium, a 5% late penalty and a 1% early repayment
+/// fee. `user` deposits 10_000_000 native and holds 3_500_000 to repay with.
+fn setup() -> (
+    Env,
+    HelloContractClient<'static>,
+    Address,
+    token::Client<'static>,
+) {
+    let (env, contract_id, client, admin, user, native_asset) = setup_env_with_native_asset();
+    token::StellarAssetClient::new(&env, &native_asset).mint(&user, &3_500_000);
+    let token = token::Client::new(&env, &native_asset);
+    token.approve(
+        &user,
+        &contract_id,
+        &3_500_000,
+        &(env.ledger().sequence() + 100),
+    );
+
+    client.set_term_premium(&admin, &TERM, &Some(200));
+    client.set_term_loan_config(&admin, &500, &100);
+    client.deposit_collateral(&user, &None, &10_000_000);
+    (env, client, user, token)
+}
+
+#[test]
+fn test_term_loan_repaid_mid_term_pays_early_fee() {
+    let (env, client, user, token) = setup();
+    client.open_term_loan(&user, &None, &3_000_000, &TERM);
+    let locked = client.get_borrow_rate() + 200;
+    let record = client.get_user_borrow(&user, &None).unwrap();
+    assert_eq!(record.rate_mode, RateMode::Fixed);
+    assert_eq!(record.stable_rate_bps, locked);
+    assert_eq!(record.maturity, env.ledger().timestamp() + TERM);
+
+    advance(&env, 45 * DAY);
+    let interest = calculate_accrued_debt(3_000_000, 0, 45 * DAY, locked).unwrap();
+    assert_eq!(client.get_user_debt(&user, &None), 3_000_000 + interest);
+
+    // The interest is settled first; only the principal part is charged the 1% fee
+    let reserves = client.get_total_reserves(&None);
+    let balance = token.balance(&user);
+    let (remaining_debt, interest_paid, principal_paid) =
+        client.repay_debt(&user, &None, &1_000_000);
+    let fee = principal_paid / 100;
+    assert_eq!(interest_paid, interest);
+    assert_eq!(remaining_debt, 3_000_000 - principal_paid);
+    assert_eq!(token.balance(&user), balance - 1_000_000 - fee);
+    assert_eq!(client.get_reserve_balance(&None), reserves + fee);
+
+    // The rest of the loan keeps its rate and maturity
+    let record = client.get_user_borrow(&user, &None).unwrap();
+    assert_eq!(record.rate_mode, RateMode::Fixed);
+    assert_eq!(record.stable_rate_bps, locked);
+    assert_eq!(record.maturity, env.ledger().timestamp() + TERM - 45 * DAY);
+}
+
+#[test]
+fn test_matured_term_loan_accrues_late_penalty() {
+    let (env, client, user, token) = setup();
+    client.open_term_loan(&user, &None, &3_000_000, &TERM);
+    let locked = client.get_borrow_rate() + 200;
+
+    advance(&env, TERM);
+    let fixed = calculate_accrued_debt(3_000_000, 0, TERM, locked).unwrap();
+    assert_eq!(client.get_user_debt(&user, &None), 3_000_000 + fixed);
+
+    // Thirty days late: the variable rate plus the 5% penalty
+    let late_rate = client.get_borrow_rate() + 500;
+    advance(&env, 30 * DAY);
+    let late = calculate_accrued_debt(3_000_000, 0, 30 * DAY, late_rate).unwrap();
+    assert!(late_rate > locked);
+    assert_eq!(client.get_user_debt(&user, &None), 3_000_000 + fixed + late);
+
+    // Repaying after maturity carries no fee
+    let reserves = client.get_total_reserves(&None);
+    let balance = token.balance(&user);
+    let (remaining_debt, _interest_paid, _principal_paid) =
+        client.repay_debt(&user, &None, &i128::MAX);
+    assert_eq!(remaining_debt, 0);
+    assert_eq!(token.balance(&user), balance - 3_000_000 - fixed - late);
+    assert_eq!(client.get_reserve_balance(&None), reserves);
+    assert_eq!(client.get_user_borrow(&user, &None), None);
+}
+
+#[test]
+fn test_term_loan_liquidated_like_variable_debt() {
+    let env = Env::default();
+    let (contract_id, admin, client) = setup(&env);
+    let borrower = Address::generate(&env);
+    let liquidator = Address::generate(&env);
+    let oracle = Address::generate(&env);
+    client.set_term_premium(&admin, &TERM, &Some(200));
+    client.set_term_loan_config(&admin, &500, &100);
+
+    // 1000 of A backs a term loan of 800 of B, both priced at 100
+    let collateral = funded_token(&env, &contract_id, &[&borrower], 1000);
+    let debt = funded_token(&env, &contract_id, &[&liquidator], 1000);
+    client.deposit_collateral(&borrower, &Some(collateral.clone()), &1000);
+    set_oracle_price(&client, &admin, &collateral, &100, &7, &oracle);
+    set_oracle_price(&client, &admin, &debt, &100, &7, &oracle);
+    client.open_term_loan(&borrower, &Some(debt.clone()), &800, &TERM);
+
+    // Same health as an 800 variable borrow: 1000 * 100 / (800 * 100 * 105%)
+    assert_eq!(client.get_health_factor(&borrower), 11_904);
+    for price in [95, 91, 87, 84, 80] {
+        set_oracle_price(&client, &admin, &collateral, &price, &7, &oracle);
+    }
+    assert_eq!(client.can_liquidate(&borrower), (true, 9_523));
+
+    let (debt_liquidated, _collateral_seized, _incentive, _protocol_fee) = client.liquidate(
+        &liquidator,
+        &borrower,
+        &Some(debt.clone()),
+        &Some(collateral),
+        &400,
+        &false,
+    );
+    assert_eq!(debt_liquidated, 400);
+    let record = client.get_user_borrow(&borrower, &Some(debt)).unwrap();
+    assert_eq!(record.principal, 400);
+    assert_eq!(record.rate_mode, RateMode::Fixed);
+}
+
+#[test]
+fn test_term_loan_rejections() {
+    let (env, client, user, _token) = setup();
+
+    let result = client.try_open_term_loan(&user, &None, &1_000_000, &(30 * DAY));
+    assert_eq!(result, Err(Ok(BorrowError::InvalidTerm)));
+    let result = client.try_borrow_asset_with_mode(&user, &None, &1_000_000, &RateMode::Fixed);
+    assert_eq!(result, Err(Ok(BorrowError::InvalidTerm)));
+
+    client.open_term_loan(&user, &None, &1_000_000, &TERM);
+    let result = client.try_open_term_loan(&user, &None, &1_000_000, &TERM);
+    assert_eq!(result, Err(Ok(BorrowError::TermLoanExists)));
+    let result = client.try_borrow_asset(&user, &None, &1_000_000);
+    assert_eq!(result, Err(Ok(BorrowError::RateModeMismatch)));
+    let result = client.try_swap_rate_mode(&user, &None);
+    assert_eq!(result, Err(Ok(BorrowError::RateModeMismatch)));
+
+    // Variable debt cannot be turned into a term loan either
+    let other = Address::generate(&env);
+    client.deposit_collateral(&other, &None, &10_000_000);
+    client.borrow_asset(&other, &None, &1_000_000);
+    let result = client.try_open_term_loan(&other, &None, &1_000_000, &TERM);
+    assert_eq!(result, Err(Ok(BorrowError::RateModeMismatch)));
+
+    let result = client.try_set_term_premium(&user, &TERM, &Some(100));
+    assert_eq!(result, Err(Ok(BorrowError::Unauthorized)));
+    let result = client.try_set_term_loan_config(&user, &500, &100);
+    assert_eq!(result, Err(Ok(BorrowError::Unauthorized)));
+}
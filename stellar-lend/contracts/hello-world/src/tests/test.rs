@@ -48,11 +48,12 @@ fn set_asset_params(
     collateral_factor: i128,
     max_deposit: i128,
 ) {
+    use crate::tests::test_helpers::asset_params;
     use deposit::AssetParams;
     let params = AssetParams {
         deposit_enabled,
-        collateral_factor,
         max_deposit,
+        ..asset_params(collateral_factor)
     };
     let key = DepositDataKey::AssetParams(asset.clone());
     env.storage().persistent().set(&key, &params);
@@ -560,6 +561,8 @@ fn test_set_risk_params_success() {
         &Some(11_000), // liquidation_threshold: 110% (4.76% increase from 10,500)
         &Some(5_500),  // close_factor: 55% (10% increase from 5,000)
         &Some(1_100),  // liquidation_incentive: 11% (10% increase from 1,000)
+        &None,
+        &None,
     );
 
     // Verify updated values
@@ -582,7 +585,7 @@ fn test_set_risk_params_unauthorized() {
     client.initialize(&admin);
 
     // Try to set risk params as non-admin
-    client.set_risk_params(&non_admin, &Some(12_000), &None, &None, &None);
+    client.set_risk_params(&non_admin, &Some(12_000), &None, &None, &None, &None, &None);
 }
 
 #[test]
@@ -604,6 +607,8 @@ fn test_set_risk_params_invalid_min_collateral_ratio() {
         &None,
         &None,
         &None,
+        &None,
+        &None,
     );
 }
 
@@ -624,6 +629,8 @@ fn test_set_risk_params_min_cr_below_liquidation_threshold() {
         &Some(10_500), // liquidation_threshold: 105% (higher than min_cr)
         &None,
         &None,
+        &None,
+        &None,
     );
 }
 
@@ -651,6 +658,8 @@ fn test_set_risk_params_invalid_close_factor() {
         &None,
         &Some(10_001), // 100.01% (over 100% max, but change from 5,000 is 5,001 which exceeds limit)
         &None,
+        &None,
+        &None,
     );
 }
 
@@ -674,6 +683,8 @@ fn test_set_risk_params_invalid_liquidation_incentive() {
         &None,
         &None,
         &Some(5_001), // 50.01% (over 50% max, but change from 1,000 is 4,001 which exceeds limit)
+        &None,
+        &None,
     );
 }
 
@@ -696,6 +707,8 @@ fn test_set_risk_params_change_too_large() {
         &None,
         &None,
         &None,
+        &None,
+        &None,
     );
 }
 
@@ -871,6 +884,8 @@ fn test_get_max_liquidatable_amount() {
         &None,
         &Some(5_500), // 55% (10% increase from 50%)
         &None,
+        &None,
+        &None,
     );
 
     // Debt: 1,000 -> Max liquidatable: 550 (55%)
@@ -899,6 +914,8 @@ fn test_get_liquidation_incentive_amount() {
         &None,
         &None,
         &Some(1_100), // 11% (10% increase from 10%)
+        &None,
+        &None,
     );
 
     // Liquidated amount: 1,000 -> Incentive: 110 (11%)
@@ -922,6 +939,8 @@ fn test_risk_params_partial_update() {
         &None,
         &None,
         &None,
+        &None,
+        &None,
     );
 
     // Verify only min_collateral_ratio changed
@@ -953,6 +972,8 @@ fn test_risk_params_edge_cases() {
         &Some(10_000), // 100% (minimum allowed, 4.76% decrease from 10,500)
         &Some(4_500),  // 45% (10% decrease from 5,000 = 500, so 5,000 - 500 = 4,500)
         &Some(900),    // 9% (10% decrease from 1,000 = 100, so 1,000 - 100 = 900)
+        &None,
+        &None,
     );
 
     assert_eq!(client.get_min_collateral_ratio(), 10_000);
@@ -1063,8 +1084,8 @@ fn test_withdraw_collateral_success() {
     let withdraw_amount = 500;
     let result = client.withdraw_collateral(&user, &None, &withdraw_amount);
 
-    // Verify result
-    assert_eq!(result, deposit_amount - withdraw_amount);
+    // Verify result (net amount received)
+    assert_eq!(result, withdraw_amount);
 
     // Verify collateral balance
     let balance = get_collateral_balance(&env, &contract_id, &user);
@@ -1138,7 +1159,7 @@ fn test_withdraw_collateral_maximum_withdrawal() {
     // Withdraw all (maximum withdrawal when no debt)
     let result = client.withdraw_collateral(&user, &None, &deposit_amount);
 
-    assert_eq!(result, 0);
+    assert_eq!(result, deposit_amount);
 
     // Verify collateral balance
     let balance = get_collateral_balance(&env, &contract_id, &user);
@@ -1461,12 +1482,12 @@ fn test_withdraw_collateral_multiple_withdrawals() {
     // First withdrawal
     let withdraw1 = 300;
     let result1 = client.withdraw_collateral(&user, &None, &withdraw1);
-    assert_eq!(result1, deposit_amount - withdraw1);
+    assert_eq!(result1, withdraw1);
 
     // Second withdrawal
     let withdraw2 = 200;
     let result2 = client.withdraw_collateral(&user, &None, &withdraw2);
-    assert_eq!(result2, deposit_amount - withdraw1 - withdraw2);
+    assert_eq!(result2, withdraw2);
 
     // Verify final balance
     let balance = get_collateral_balance(&env, &contract_id, &user);
@@ -1570,7 +1591,11 @@ fn test_withdraw_collateral_with_debt_collateral_ratio() {
     // After withdrawing 500, ratio = 1500/500 = 300% (still above minimum)
     let withdraw_amount = 500;
     let result = client.withdraw_collateral(&user, &None, &withdraw_amount);
-    assert_eq!(result, collateral - withdraw_amount);
+    assert_eq!(result, withdraw_amount);
+    assert_eq!(
+        get_collateral_balance(&env, &contract_id, &user),
+        collateral - withdraw_amount
+    );
 }
 
 #[test]
@@ -1744,6 +1769,10 @@ fn test_repay_debt_pause_switch() {
     let env = create_test_env();
     let contract_id = env.register(HelloContract, ());
     let client = HelloContractClient::new(&env, &contract_id);
+    let admin = Address::generate(&env);
+    client.initialize(&admin);
+    // Repayments only honor the pause switch once they are no longer always allowed
+    client.set_repay_always_allowed(&admin, &false);
 
     let user = Address::generate(&env);
 
@@ -2463,7 +2492,7 @@ fn test_update_price_feed_success() {
     // Update price feed
     let price = 10000;
     let decimals = 8;
-    let result = client.update_price_feed(&admin, &asset, &price, &decimals, &oracle);
+    let result = set_oracle_price(&client, &admin, &asset, &price, &decimals, &oracle);
 
     assert_eq!(result, price);
 }
@@ -2484,7 +2513,7 @@ fn test_get_price_success() {
     // Update price feed
     let price = 50000;
     let decimals = 8;
-    client.update_price_feed(&admin, &asset, &price, &decimals, &oracle);
+    set_oracle_price(&client, &admin, &asset, &price, &decimals, &oracle);
 
     // Get price
     let result = client.get_price(&asset);
@@ -2505,7 +2534,7 @@ fn test_update_price_feed_zero_price() {
     client.initialize(&admin);
 
     // Try to update with zero price
-    client.update_price_feed(&admin, &asset, &0, &8, &oracle);
+    set_oracle_price(&client, &admin, &asset, &0, &8, &oracle);
 }
 
 #[test]
@@ -2522,7 +2551,7 @@ fn test_update_price_feed_negative_price() {
     client.initialize(&admin);
 
     // Try to update with negative price
-    client.update_price_feed(&admin, &asset, &(-100), &8, &oracle);
+    set_oracle_price(&client, &admin, &asset, &(-100), &8, &oracle);
 }
 
 #[test]
@@ -2535,12 +2564,11 @@ fn test_update_price_feed_unauthorized() {
     let admin = Address::generate(&env);
     let user = Address::generate(&env);
     let asset = Address::generate(&env);
-    let oracle = Address::generate(&env);
 
     client.initialize(&admin);
 
-    // Try to update price as non-admin, non-oracle
-    client.update_price_feed(&user, &asset, &10000, &8, &oracle);
+    // Try to update price as an unregistered address
+    client.update_price_feed(&user, &asset, &10000, &8);
 }
 
 #[test]
@@ -2555,10 +2583,11 @@ fn test_update_price_feed_by_oracle() {
 
     client.initialize(&admin);
     client.set_primary_oracle(&admin, &asset, &oracle);
+    client.register_oracle(&admin, &asset, &oracle);
 
     // Oracle can update its own price
     let price = 20000;
-    let result = client.update_price_feed(&oracle, &asset, &price, &8, &oracle);
+    let result = client.update_price_feed(&oracle, &asset, &price, &8);
     assert_eq!(result, price);
 }
 
@@ -2576,7 +2605,7 @@ fn test_price_caching() {
 
     // Update price
     let price = 30000;
-    client.update_price_feed(&admin, &asset, &price, &8, &oracle);
+    set_oracle_price(&client, &admin, &asset, &price, &8, &oracle);
 
     // Get price multiple times (should use cache)
     let price1 = client.get_price(&asset);
@@ -2632,9 +2661,9 @@ fn test_configure_oracle() {
     // Configure oracle
     use crate::oracle::OracleConfig;
     let config = OracleConfig {
-        max_deviation_bps: 1000,     // 10%
-        max_staleness_seconds: 7200, // 2 hours
-        cache_ttl_seconds: 600,      // 10 minutes
+        max_deviation_bps: 1000,  // 10%
+        max_price_age_secs: 7200, // 2 hours
+        cache_ttl_seconds: 600,   // 10 minutes
         min_price: 1,
         max_price: i128::MAX,
     };
@@ -2976,12 +3005,13 @@ fn test_liquidate_partial_liquidation() {
     // Liquidate partial amount (50% of debt with 50% close factor)
     // Default close_factor is 50% (5000 bps), so max liquidatable = 500
     let debt_amount = 300; // Less than max (500)
-    let (debt_liquidated, collateral_seized, incentive) = client.liquidate(
+    let (debt_liquidated, collateral_seized, incentive, _protocol_fee) = client.liquidate(
         &liquidator,
         &borrower,
         &None, // debt_asset (native XLM)
         &None, // collateral_asset (native XLM)
         &debt_amount,
+        &false,
     );
 
     // Verify liquidation amounts
@@ -3025,8 +3055,8 @@ fn test_liquidate_full_liquidation() {
 
     // Liquidate maximum amount (close factor = 50%, so max = 500)
     let max_liquidatable = 500;
-    let (debt_liquidated, collateral_seized, incentive) =
-        client.liquidate(&liquidator, &borrower, &None, &None, &max_liquidatable);
+    let (debt_liquidated, collateral_seized, incentive, _protocol_fee) =
+        client.liquidate(&liquidator, &borrower, &None, &None, &max_liquidatable, &false);
 
     // Verify full liquidation within close factor
     assert_eq!(debt_liquidated, max_liquidatable);
@@ -3070,7 +3100,7 @@ fn test_liquidate_exceeds_close_factor() {
     });
 
     // Try to liquidate more than close factor (max is 500, try 600)
-    client.liquidate(&liquidator, &borrower, &None, &None, &600);
+    client.liquidate(&liquidator, &borrower, &None, &None, &600, &false);
 }
 
 #[test]
@@ -3106,8 +3136,8 @@ fn test_liquidate_incentive_calculation() {
 
     // Liquidate 500 debt (within close factor limit)
     let debt_amount = 500;
-    let (debt_liquidated, collateral_seized, incentive) =
-        client.liquidate(&liquidator, &borrower, &None, &None, &debt_amount);
+    let (debt_liquidated, collateral_seized, incentive, _protocol_fee) =
+        client.liquidate(&liquidator, &borrower, &None, &None, &debt_amount, &false);
 
     // Verify incentive calculation
     // incentive = 500 * 1000 / 10000 = 50
@@ -3119,7 +3149,7 @@ fn test_liquidate_incentive_calculation() {
 
 #[test]
 #[ignore] // Native XLM liquidation not yet supported
-#[should_panic(expected = "NotLiquidatable")]
+#[should_panic(expected = "PositionHealthy")]
 fn test_liquidate_not_undercollateralized() {
     let env = create_test_env();
     let contract_id = env.register(HelloContract, ());
@@ -3148,7 +3178,7 @@ fn test_liquidate_not_undercollateralized() {
     });
 
     // Try to liquidate (should fail - position is healthy)
-    client.liquidate(&liquidator, &borrower, &None, &None, &500);
+    client.liquidate(&liquidator, &borrower, &None, &None, &500, &false);
 }
 
 #[test]
@@ -3181,7 +3211,7 @@ fn test_liquidate_zero_amount() {
     });
 
     // Try to liquidate zero amount
-    client.liquidate(&liquidator, &borrower, &None, &None, &0);
+    client.liquidate(&liquidator, &borrower, &None, &None, &0, &false);
 }
 
 #[test]
@@ -3214,7 +3244,7 @@ fn test_liquidate_negative_amount() {
     });
 
     // Try to liquidate negative amount
-    client.liquidate(&liquidator, &borrower, &None, &None, &(-100));
+    client.liquidate(&liquidator, &borrower, &None, &None, &(-100), &false);
 }
 
 #[test]
@@ -3251,7 +3281,7 @@ fn test_liquidate_paused() {
     });
 
     // Try to liquidate (should fail - paused)
-    client.liquidate(&liquidator, &borrower, &None, &None, &500);
+    client.liquidate(&liquidator, &borrower, &None, &None, &500, &false);
 }
 
 #[test]
@@ -3287,8 +3317,8 @@ fn test_liquidate_with_interest() {
     // Max liquidatable = 1000 * 50% = 500
 
     let debt_amount = 400;
-    let (debt_liquidated, collateral_seized, incentive) =
-        client.liquidate(&liquidator, &borrower, &None, &None, &debt_amount);
+    let (debt_liquidated, collateral_seized, incentive, _protocol_fee) =
+        client.liquidate(&liquidator, &borrower, &None, &None, &debt_amount, &false);
 
     // Verify liquidation
     assert_eq!(debt_liquidated, debt_amount);
@@ -3331,16 +3361,16 @@ fn test_liquidate_multiple_liquidations() {
     });
 
     // First liquidation (max is 1000, liquidate 300)
-    let (debt1, collateral1, incentive1) =
-        client.liquidate(&liquidator1, &borrower, &None, &None, &300);
+    let (debt1, collateral1, incentive1, _protocol_fee) =
+        client.liquidate(&liquidator1, &borrower, &None, &None, &300, &false);
 
     assert_eq!(debt1, 300);
     assert!(collateral1 > 0);
     assert!(incentive1 > 0);
 
     // Second liquidation (remaining max is 700, liquidate 200)
-    let (debt2, collateral2, incentive2) =
-        client.liquidate(&liquidator2, &borrower, &None, &None, &200);
+    let (debt2, collateral2, incentive2, _protocol_fee) =
+        client.liquidate(&liquidator2, &borrower, &None, &None, &200, &false);
 
     assert_eq!(debt2, 200);
     assert!(collateral2 > 0);
@@ -3380,8 +3410,8 @@ fn test_liquidate_events_emitted() {
     });
 
     // Liquidate
-    let (debt_liquidated, collateral_seized, incentive) =
-        client.liquidate(&liquidator, &borrower, &None, &None, &300);
+    let (debt_liquidated, collateral_seized, incentive, _protocol_fee) =
+        client.liquidate(&liquidator, &borrower, &None, &None, &300, &false);
 
     // Verify liquidation succeeded (implies events were emitted)
     assert_eq!(debt_liquidated, 300);
@@ -3438,7 +3468,7 @@ fn test_liquidate_analytics_updated() {
 
     // Liquidate
     let debt_amount = 300;
-    client.liquidate(&liquidator, &borrower, &None, &None, &debt_amount);
+    client.liquidate(&liquidator, &borrower, &None, &None, &debt_amount, &false);
 
     // Verify analytics updated
     let analytics = get_user_analytics(&env, &contract_id, &borrower).unwrap();
@@ -3464,7 +3494,7 @@ fn test_liquidate_close_factor_edge_case() {
     // Actually, max change is 10% = 500, so we can only go to 5500
     // Let's test with a smaller change: 6000 (20% increase, but let's test the logic)
     // Actually, let's test with exactly the max: 5500
-    client.set_risk_params(&admin, &None, &None, &Some(5500), &None);
+    client.set_risk_params(&admin, &None, &None, &Some(5500), &None, &None, &None);
 
     // Set up undercollateralized position
     env.as_contract(&contract_id, || {
@@ -3483,8 +3513,8 @@ fn test_liquidate_close_factor_edge_case() {
 
     // With 55% close factor, max liquidatable = 1000 * 55% = 550
     let max_liquidatable = 550;
-    let (debt_liquidated, collateral_seized, incentive) =
-        client.liquidate(&liquidator, &borrower, &None, &None, &max_liquidatable);
+    let (debt_liquidated, collateral_seized, incentive, _protocol_fee) =
+        client.liquidate(&liquidator, &borrower, &None, &None, &max_liquidatable, &false);
 
     assert_eq!(debt_liquidated, max_liquidatable);
     assert!(collateral_seized > 0);
@@ -3505,7 +3535,7 @@ fn test_liquidate_incentive_edge_cases() {
     client.initialize(&admin);
 
     // Update liquidation incentive to 5% (500 bps, within 10% change limit)
-    client.set_risk_params(&admin, &None, &None, &None, &Some(500));
+    client.set_risk_params(&admin, &None, &None, &None, &Some(500), &None, &None);
 
     // Set up undercollateralized position
     env.as_contract(&contract_id, || {
@@ -3525,8 +3555,8 @@ fn test_liquidate_incentive_edge_cases() {
     // Liquidate 500 debt
     // With 5% incentive: incentive = 500 * 500 / 10000 = 25
     let debt_amount = 500;
-    let (debt_liquidated, collateral_seized, incentive) =
-        client.liquidate(&liquidator, &borrower, &None, &None, &debt_amount);
+    let (debt_liquidated, collateral_seized, incentive, _protocol_fee) =
+        client.liquidate(&liquidator, &borrower, &None, &None, &debt_amount, &false);
 
     assert_eq!(debt_liquidated, debt_amount);
     assert_eq!(incentive, 25); // 500 * 500 / 10000 = 25
@@ -3535,7 +3565,7 @@ fn test_liquidate_incentive_edge_cases() {
 
 #[test]
 #[ignore] // Native XLM liquidation not yet supported
-#[should_panic(expected = "NotLiquidatable")]
+#[should_panic(expected = "PositionHealthy")]
 fn test_liquidate_no_debt() {
     let env = create_test_env();
     let contract_id = env.register(HelloContract, ());
@@ -3563,7 +3593,7 @@ fn test_liquidate_no_debt() {
     });
 
     // Try to liquidate (should fail - no debt)
-    client.liquidate(&liquidator, &borrower, &None, &None, &100);
+    client.liquidate(&liquidator, &borrower, &None, &None, &100, &false);
 }
 
 #[test]
@@ -3595,7 +3625,7 @@ fn test_liquidate_activity_log() {
     });
 
     // Liquidate
-    client.liquidate(&liquidator, &borrower, &None, &None, &300);
+    client.liquidate(&liquidator, &borrower, &None, &None, &300, &false);
 
     // Verify activity log was updated
     let log = env.as_contract(&contract_id, || {
@@ -3809,9 +3839,10 @@ fn test_supply_rate_calculation() {
     let borrow_rate = client.get_borrow_rate();
     let supply_rate = client.get_supply_rate();
 
-    // Supply rate = borrow rate - spread (default spread = 200 bps)
-    assert_eq!(supply_rate, borrow_rate - 200);
-    assert!(supply_rate >= 50); // Should be at least floor (50 bps)
+    // Supply rate = borrow rate * utilization * (1 - reserve factor)
+    // = 1350 * 50% * 90% = 607.5, rounded down
+    assert_eq!(borrow_rate, 1350);
+    assert_eq!(supply_rate, 607);
 }
 
 #[test]
@@ -3834,8 +3865,10 @@ fn test_rate_floor_enforcement() {
         &Some(50),
         &None,
         &None,
+        &None,
+        &false,
     );
-    client.set_emergency_rate_adjustment(&admin, &(-100));
+    client.set_emergency_rate_adjustment(&admin, &(-100), &86_400);
 
     // Rate should still be at least floor (50 bps)
     let rate = client.get_borrow_rate();
@@ -3863,6 +3896,8 @@ fn test_rate_ceiling_enforcement() {
         &None,
         &Some(5000),
         &None,
+        &None,
+        &false,
     );
 
     // Deposit and borrow to max utilization
@@ -3901,7 +3936,7 @@ fn test_emergency_rate_adjustment() {
     // So rate_before should be around 1350
 
     // Apply emergency adjustment of +500 bps
-    client.set_emergency_rate_adjustment(&admin, &500);
+    client.set_emergency_rate_adjustment(&admin, &500, &86_400);
 
     let rate_after = client.get_borrow_rate();
     // Rate should increase by 500 (unless capped)
@@ -3909,7 +3944,7 @@ fn test_emergency_rate_adjustment() {
     assert_eq!(rate_after, rate_before + 500);
 
     // Apply negative adjustment (replaces the previous +500)
-    client.set_emergency_rate_adjustment(&admin, &(-300));
+    client.set_emergency_rate_adjustment(&admin, &(-300), &86_400);
 
     let rate_final = client.get_borrow_rate();
     // Emergency adjustment replaces the previous one, so:
@@ -3937,6 +3972,8 @@ fn test_update_interest_rate_config() {
         &None,
         &None,
         &None,
+        &None,
+        &false,
     );
 
     // Verify rate changed
@@ -3966,6 +4003,8 @@ fn test_update_interest_rate_config_unauthorized() {
         &None,
         &None,
         &None,
+        &None,
+        &false,
     );
 }
 
@@ -3990,6 +4029,8 @@ fn test_update_kink_utilization() {
         &None,
         &None,
         &None,
+        &None,
+        &false,
     );
 
     // Deposit and borrow to 50% utilization (at new kink)
@@ -4022,6 +4063,8 @@ fn test_update_multiplier() {
         &None,
         &None,
         &None,
+        &None,
+        &false,
     );
 
     // Deposit and borrow to 40% utilization (below kink)
@@ -4036,7 +4079,7 @@ fn test_update_multiplier() {
 }
 
 #[test]
-fn test_update_spread() {
+fn test_update_reserve_factor() {
     let env = create_test_env();
     let contract_id = env.register(HelloContract, ());
     let client = HelloContractClient::new(&env, &contract_id);
@@ -4053,7 +4096,7 @@ fn test_update_spread() {
     let borrow_rate = client.get_borrow_rate();
     let supply_rate_before = client.get_supply_rate();
 
-    // Update spread to 500 bps
+    // Update reserve factor to 2000 bps
     client.update_interest_rate_config(
         &admin,
         &None,
@@ -4062,14 +4105,17 @@ fn test_update_spread() {
         &None,
         &None,
         &None,
-        &Some(500),
+        &None,
+        &Some(2000),
+        &false,
     );
 
     let supply_rate_after = client.get_supply_rate();
 
-    // Supply rate should decrease by 300 bps (500 - 200)
-    assert_eq!(supply_rate_after, supply_rate_before - 300);
-    assert_eq!(supply_rate_after, borrow_rate - 500);
+    // Borrowers pay the same; suppliers keep 80% instead of 90% of it
+    assert_eq!(client.get_borrow_rate(), borrow_rate);
+    assert_eq!(supply_rate_before, 607);
+    assert_eq!(supply_rate_after, 540);
 }
 
 #[test]
@@ -4108,7 +4154,7 @@ fn test_rate_changes_with_utilization() {
 }
 
 #[test]
-#[should_panic(expected = "Error(Contract, #2)")]
+#[should_panic(expected = "Error(Contract, #9)")]
 fn test_invalid_interest_rate_negative_base() {
     let env = create_test_env();
     let contract_id = env.register(HelloContract, ());
@@ -4127,6 +4173,8 @@ fn test_invalid_interest_rate_negative_base() {
         &None,
         &None,
         &None,
+        &None,
+        &false,
     );
 }
 
@@ -4150,11 +4198,13 @@ fn test_invalid_interest_rate_base_too_high() {
         &None,
         &None,
         &None,
+        &None,
+        &false,
     );
 }
 
 #[test]
-#[should_panic(expected = "Error(Contract, #2)")]
+#[should_panic(expected = "Error(Contract, #7)")]
 fn test_invalid_interest_rate_kink_zero() {
     let env = create_test_env();
     let contract_id = env.register(HelloContract, ());
@@ -4164,12 +4214,23 @@ fn test_invalid_interest_rate_kink_zero() {
     client.initialize(&admin);
 
     // Invalid: kink at 0%
-    client.update_interest_rate_config(&admin, &None, &Some(0), &None, &None, &None, &None, &None);
+    client.update_interest_rate_config(
+        &admin,
+        &None,
+        &Some(0),
+        &None,
+        &None,
+        &None,
+        &None,
+        &None,
+        &None,
+        &false,
+    );
 }
 
 #[test]
-#[should_panic(expected = "Error(Contract, #2)")]
-fn test_invalid_interest_rate_kink_100() {
+#[should_panic(expected = "Error(Contract, #7)")]
+fn test_invalid_interest_rate_kink_above_100() {
     let env = create_test_env();
     let contract_id = env.register(HelloContract, ());
     let client = HelloContractClient::new(&env, &contract_id);
@@ -4177,21 +4238,23 @@ fn test_invalid_interest_rate_kink_100() {
     let admin = Address::generate(&env);
     client.initialize(&admin);
 
-    // Invalid: kink at 100%
+    // Invalid: kink above 100%
     client.update_interest_rate_config(
         &admin,
         &None,
-        &Some(10000),
+        &Some(10001),
         &None,
         &None,
         &None,
         &None,
         &None,
+        &None,
+        &false,
     );
 }
 
 #[test]
-#[should_panic(expected = "Error(Contract, #2)")]
+#[should_panic(expected = "Error(Contract, #8)")]
 fn test_invalid_interest_rate_floor_above_ceiling() {
     let env = create_test_env();
     let contract_id = env.register(HelloContract, ());
@@ -4210,6 +4273,8 @@ fn test_invalid_interest_rate_floor_above_ceiling() {
         &Some(5000),
         &Some(3000),
         &None,
+        &None,
+        &false,
     );
 }
 
@@ -4223,7 +4288,7 @@ fn test_emergency_adjustment_valid() {
     client.initialize(&admin);
 
     // Valid: adjustment within bounds
-    client.set_emergency_rate_adjustment(&admin, &500);
+    client.set_emergency_rate_adjustment(&admin, &500, &86_400);
 }
 
 #[test]
@@ -4237,7 +4302,7 @@ fn test_emergency_adjustment_too_large() {
     client.initialize(&admin);
 
     // Invalid: adjustment too large
-    client.set_emergency_rate_adjustment(&admin, &20000);
+    client.set_emergency_rate_adjustment(&admin, &20000, &86_400);
 }
 
 // ============================================================================
@@ -1,8 +1,12 @@
 //! Shared test helpers for contract tests. Use setup_env_with_native_asset() when a test
 //! performs deposit/borrow/repay with asset = None, so that NativeAssetAddress is set.
 
+use crate::deposit::AssetParams;
 use crate::{HelloContract, HelloContractClient};
-use soroban_sdk::{testutils::Address as _, Address, Env};
+use soroban_sdk::{
+    testutils::{Address as _, Ledger},
+    token, Address, Env,
+};
 
 /// Full setup: env, contract, client, admin, user, and native asset address set.
 /// Use this for tests that call deposit_collateral/borrow_asset/repay_debt with None.
@@ -25,3 +29,82 @@ pub fn setup_env_with_native_asset() -> (
     client.set_native_asset_address(&admin, &native_asset);
     (env, contract_id, client, admin, user, native_asset)
 }
+
+/// Registers and initializes the contract with all auths mocked.
+/// Returns the contract id, the admin and a client; `env` must outlive the client.
+pub fn setup(env: &Env) -> (Address, Address, HelloContractClient<'_>) {
+    env.mock_all_auths();
+    let contract_id = env.register(HelloContract, ());
+    let client = HelloContractClient::new(env, &contract_id);
+    let admin = Address::generate(env);
+    client.initialize(&admin);
+    (contract_id, admin, client)
+}
+
+/// Asset params with deposits enabled at `collateral_factor` and every cap, fee
+/// and restriction off. Override fields with `AssetParams { .., ..asset_params(cf) }`.
+pub fn asset_params(collateral_factor: i128) -> AssetParams {
+    AssetParams {
+        deposit_enabled: true,
+        collateral_factor,
+        max_deposit: 0,
+        borrow_fee_bps: 0,
+        max_deposit_per_user: 0,
+        allowlist_enabled: false,
+        withdraw_fee_bps: 0,
+        withdraw_cooldown_secs: 0,
+        withdraw_intent_window_secs: 0,
+        min_borrow: 0,
+        isolated: false,
+        debt_ceiling: 0,
+        borrowable_in_isolation: false,
+        siloed: false,
+        same_ledger_borrow_blocked: false,
+        liquidity_score: 0,
+    }
+}
+
+/// Registers a Stellar asset contract, mints `amount` to each of `holders`
+/// and approves `contract_id` to pull it.
+pub fn funded_token(
+    env: &Env,
+    contract_id: &Address,
+    holders: &[&Address],
+    amount: i128,
+) -> Address {
+    let token = env.register_stellar_asset_contract(Address::generate(env));
+    for holder in holders {
+        token::StellarAssetClient::new(env, &token).mint(holder, &amount);
+        token::Client::new(env, &token).approve(
+            holder,
+            contract_id,
+            &amount,
+            &(env.ledger().sequence() + 100),
+        );
+    }
+    token
+}
+
+/// Moves the ledger clock forward by `secs` seconds.
+pub fn advance(env: &Env, secs: u64) {
+    env.ledger().with_mut(|li| li.timestamp += secs);
+}
+
+/// Moves the ledger sequence forward by `ledgers`.
+pub fn advance_ledgers(env: &Env, ledgers: u32) {
+    env.ledger().with_mut(|li| li.sequence_number += ledgers);
+}
+
+/// Registers `oracle` for `asset` as the admin, then submits `price` from it.
+/// Use this wherever a test needs a price but is not about oracle authorization.
+pub fn set_oracle_price(
+    client: &HelloContractClient,
+    admin: &Address,
+    asset: &Address,
+    price: &i128,
+    decimals: &u32,
+    oracle: &Address,
+) -> i128 {
+    client.register_oracle(admin, asset, oracle);
+    client.update_price_feed(oracle, asset, price, decimals)
+}
@@ -0,0 +1,95 @@
+//! Tests for TTL bumping of persistent position entries.
+//!
+//! # Coverage
+//! - Deposits extend position, analytics and asset entries to `extend_to`
+//! - `touch_position` refreshes entries after ledgers have passed
+//! - Entries above the threshold are left alone
+//! - Only the admin can change the TTL configuration
+
+use crate::deposit::{DepositDataKey, DepositError};
+use crate::tests::test_helpers::{advance_ledgers, asset_params, funded_token, setup};
+use soroban_sdk::{
+    testutils::{storage::Persistent as _, Address as _},
+    Address, Env,
+};
+
+fn ttl_of(env: &Env, contract_id: &Address, key: &DepositDataKey) -> u32 {
+    env.as_contract(contract_id, || env.storage().persistent().get_ttl(key))
+}
+
+#[test]
+fn test_deposit_extends_entry_ttls() {
+    let env = Env::default();
+    let (contract_id, admin, client) = setup(&env);
+    client.set_storage_ttl_config(&admin, &10_000, &50_000);
+
+    let user = Address::generate(&env);
+    let token = funded_token(&env, &contract_id, &[&user], 1_000);
+    env.as_contract(&contract_id, || {
+        let params = asset_params(7500);
+        env.storage()
+            .persistent()
+            .set(&DepositDataKey::AssetParams(token.clone()), &params);
+    });
+
+    client.deposit_collateral(&user, &Some(token.clone()), &1_000);
+
+    for key in [
+        DepositDataKey::Position(user.clone()),
+        DepositDataKey::CollateralBalance(user.clone()),
+        DepositDataKey::UserAnalytics(user.clone()),
+        DepositDataKey::UserDeposit(user.clone(), Some(token.clone())),
+        DepositDataKey::ProtocolAnalytics,
+        DepositDataKey::AssetParams(token.clone()),
+    ] {
+        assert_eq!(ttl_of(&env, &contract_id, &key), 50_000);
+    }
+}
+
+#[test]
+fn test_touch_position_refreshes_ttl() {
+    let env = Env::default();
+    let (contract_id, admin, client) = setup(&env);
+    client.set_storage_ttl_config(&admin, &10_000, &50_000);
+
+    let user = Address::generate(&env);
+    client.deposit_collateral(&user, &None, &1_000);
+    let position_key = DepositDataKey::Position(user.clone());
+
+    // Still above the threshold: touching does nothing
+    advance_ledgers(&env, 30_000);
+    client.touch_position(&user);
+    assert_eq!(ttl_of(&env, &contract_id, &position_key), 20_000);
+
+    // Below the threshold: touching extends back to `extend_to`
+    advance_ledgers(&env, 15_000);
+    assert_eq!(ttl_of(&env, &contract_id, &position_key), 5_000);
+    client.touch_position(&user);
+    assert_eq!(ttl_of(&env, &contract_id, &position_key), 50_000);
+    assert_eq!(
+        ttl_of(
+            &env,
+            &contract_id,
+            &DepositDataKey::UserDeposit(user.clone(), None)
+        ),
+        50_000
+    );
+}
+
+#[test]
+fn test_set_storage_ttl_config_validation() {
+    let env = Env::default();
+    let (_contract_id, admin, client) = setup(&env);
+    let stranger = Address::generate(&env);
+
+    let result = client.try_set_storage_ttl_config(&stranger, &10_000, &50_000);
+    assert_eq!(result, Err(Ok(DepositError::Unauthorized)));
+
+    let result = client.try_set_storage_ttl_config(&admin, &50_000, &10_000);
+    assert_eq!(result, Err(Ok(DepositError::InvalidAmount)));
+
+    client.set_storage_ttl_config(&admin, &10_000, &50_000);
+    let config = client.get_storage_ttl_config();
+    assert_eq!(config.threshold, 10_000);
+    assert_eq!(config.extend_to, 50_000);
+}
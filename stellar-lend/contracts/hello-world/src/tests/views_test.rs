@@ -4,7 +4,7 @@
 //! Covers get_user_report (position), get_health_factor via report, collateral/debt balances,
 //! and edge cases (no debt, boundary health, risk getters).
 
-use crate::deposit::{DepositDataKey, Position, ProtocolAnalytics};
+use crate::deposit::{AssetTotals, DepositDataKey, Position};
 use crate::{HelloContract, HelloContractClient};
 use soroban_sdk::{testutils::Address as _, Address, Env};
 
@@ -210,13 +210,13 @@ fn test_get_utilization_view() {
     let (contract_id, _admin, client) = setup_contract_with_admin(&env);
 
     env.as_contract(&contract_id, || {
-        let key = DepositDataKey::ProtocolAnalytics;
-        let a = ProtocolAnalytics {
-            total_deposits: 10000,
-            total_borrows: 3000,
-            total_value_locked: 10000,
+        let key = DepositDataKey::AssetTotals(None);
+        let totals = AssetTotals {
+            total_supplied: 10000,
+            total_borrowed: 3000,
+            total_reserves: 0,
         };
-        env.storage().persistent().set(&key, &a);
+        env.storage().persistent().set(&key, &totals);
     });
 
     let util = client.get_utilization();
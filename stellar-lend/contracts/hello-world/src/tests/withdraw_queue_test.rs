@@ -0,0 +1,155 @@
+//! Tests for the withdrawal queue.
+//!
+//! # Coverage
+//! - Queued requests are paid FIFO, partially filled as liquidity returns
+//! - Queued amounts are reserved ahead of direct withdrawals
+//! - Cancelling returns the unpaid amount to the user's deposit
+//! - Queued amounts still count as collateral until paid
+//! - Only the owner can cancel a request
+
+use crate::tests::test_helpers::{funded_token, set_oracle_price, setup};
+use crate::withdraw::WithdrawError;
+use crate::HelloContractClient;
+use soroban_sdk::{testutils::Address as _, Address, Env};
+
+/// A lender supplies 1000 of a token and a borrower takes 900 of it
+fn setup_drained_pool(env: &Env) -> (HelloContractClient<'_>, Address, Address, Address) {
+    let (contract_id, admin, client) = setup(env);
+    let lender = Address::generate(env);
+    let borrower = Address::generate(env);
+
+    let token = funded_token(env, &contract_id, &[&lender], 1000);
+    client.deposit_collateral(&lender, &Some(token.clone()), &1000);
+
+    client.deposit_collateral(&borrower, &None, &10_000);
+    set_oracle_price(&client, &admin, &token, &100, &7, &admin);
+    client.borrow_asset(&borrower, &Some(token.clone()), &900);
+
+    (client, lender, borrower, token)
+}
+
+#[test]
+fn test_queued_withdrawal_partially_filled_as_liquidity_returns() {
+    let env = Env::default();
+    let (client, lender, borrower, token) = setup_drained_pool(&env);
+    let token_client = soroban_sdk::token::Client::new(&env, &token);
+
+    let id = client.request_withdrawal(&lender, &Some(token.clone()), &500);
+    assert_eq!(client.get_user_deposit(&lender, &Some(token.clone())), 500);
+
+    // The queue has first claim on the 100 of cash on hand
+    assert_eq!(client.get_available_liquidity(&Some(token.clone())), 0);
+    let result = client.try_withdraw_collateral(&lender, &Some(token.clone()), &50);
+    assert_eq!(result, Err(Ok(WithdrawError::InsufficientLiquidity)));
+
+    assert_eq!(
+        client.process_withdrawal_queue(&Some(token.clone()), &10),
+        100
+    );
+    assert_eq!(token_client.balance(&lender), 100);
+    let queue = client.get_withdrawal_queue(&Some(token.clone()), &10);
+    assert_eq!(queue.len(), 1);
+    assert_eq!(queue.get(0).unwrap().id, id);
+    assert_eq!(queue.get(0).unwrap().amount, 400);
+
+    // Nothing more to pay until borrowers repay
+    assert_eq!(
+        client.process_withdrawal_queue(&Some(token.clone()), &10),
+        0
+    );
+
+    client.repay_debt(&borrower, &Some(token.clone()), &300);
+    assert_eq!(
+        client.process_withdrawal_queue(&Some(token.clone()), &10),
+        300
+    );
+    assert_eq!(token_client.balance(&lender), 400);
+    assert_eq!(
+        client
+            .get_withdrawal_queue(&Some(token.clone()), &10)
+            .get(0)
+            .unwrap()
+            .amount,
+        100
+    );
+
+    client.repay_debt(&borrower, &Some(token.clone()), &600);
+    assert_eq!(
+        client.process_withdrawal_queue(&Some(token.clone()), &10),
+        100
+    );
+    assert_eq!(token_client.balance(&lender), 500);
+    assert_eq!(client.get_withdrawal_queue(&Some(token), &10).len(), 0);
+}
+
+#[test]
+fn test_queue_pays_in_request_order() {
+    let env = Env::default();
+    let (client, lender, _borrower, token) = setup_drained_pool(&env);
+
+    let first = client.request_withdrawal(&lender, &Some(token.clone()), &60);
+    let second = client.request_withdrawal(&lender, &Some(token.clone()), &60);
+    assert!(second > first);
+
+    // 100 of liquidity: the first request is paid in full, the second in part
+    assert_eq!(
+        client.process_withdrawal_queue(&Some(token.clone()), &10),
+        100
+    );
+    let queue = client.get_withdrawal_queue(&Some(token), &10);
+    assert_eq!(queue.len(), 1);
+    assert_eq!(queue.get(0).unwrap().id, second);
+    assert_eq!(queue.get(0).unwrap().amount, 20);
+}
+
+#[test]
+fn test_cancel_withdrawal_request_restores_deposit() {
+    let env = Env::default();
+    let (client, lender, _borrower, token) = setup_drained_pool(&env);
+
+    let id = client.request_withdrawal(&lender, &Some(token.clone()), &500);
+    client.process_withdrawal_queue(&Some(token.clone()), &10);
+
+    // Only the unpaid 400 goes back into the deposit
+    assert_eq!(client.cancel_withdrawal_request(&lender, &id), 400);
+    assert_eq!(client.get_user_deposit(&lender, &Some(token.clone())), 900);
+    assert_eq!(
+        client.get_withdrawal_queue(&Some(token.clone()), &10).len(),
+        0
+    );
+
+    let result = client.try_cancel_withdrawal_request(&lender, &id);
+    assert_eq!(result, Err(Ok(WithdrawError::RequestNotFound)));
+}
+
+#[test]
+fn test_cancel_withdrawal_request_requires_owner() {
+    let env = Env::default();
+    let (client, lender, borrower, token) = setup_drained_pool(&env);
+
+    let id = client.request_withdrawal(&lender, &Some(token.clone()), &500);
+    let result = client.try_cancel_withdrawal_request(&borrower, &id);
+    assert_eq!(result, Err(Ok(WithdrawError::Unauthorized)));
+    assert_eq!(client.get_withdrawal_queue(&Some(token), &10).len(), 1);
+}
+
+#[test]
+fn test_queued_amount_counts_as_collateral_until_paid() {
+    let env = Env::default();
+    let (_contract_id, _admin, client) = setup(&env);
+    let user = Address::generate(&env);
+
+    client.deposit_collateral(&user, &None, &10_000);
+    client.borrow_asset(&user, &None, &6_000);
+
+    // 9_000 / 6_000 keeps the position at exactly 150%
+    client.request_withdrawal(&user, &None, &1_000);
+    assert_eq!(client.get_user_deposit(&user, &None), 9_000);
+
+    // The queued 1_000 still backs borrowing
+    client.borrow_asset(&user, &None, &600);
+
+    // Paying it now would leave 9_000 against 6_600, so the request waits
+    assert_eq!(client.process_withdrawal_queue(&None, &10), 0);
+    assert_eq!(client.get_withdrawal_queue(&None, &10).len(), 1);
+}
@@ -0,0 +1,162 @@
+//! Tests for scheduled tranche withdrawals.
+//!
+//! # Coverage
+//! - Tranches are paid one per interval, and not before they are due
+//! - Skipped intervals catch up in a single execution
+//! - The last tranche pays the rounding remainder and removes the plan
+//! - Anyone can execute; only the owner can cancel
+//! - Each execution is health-checked against the position at that time
+
+use crate::deposit::{DepositDataKey, Position};
+use crate::tests::test_helpers::{advance, setup};
+use crate::withdraw::WithdrawError;
+use soroban_sdk::{testutils::Address as _, Address, Env};
+
+const DAY: u64 = 86_400;
+
+#[test]
+fn test_scheduled_withdrawal_pays_one_tranche_per_interval() {
+    let env = Env::default();
+    let (_, _, client) = setup(&env);
+    let user = Address::generate(&env);
+    client.deposit_collateral(&user, &None, &1_000);
+
+    let id = client.schedule_withdrawal(&user, &None, &900, &3, &DAY);
+    let result = client.try_execute_scheduled_withdrawal(&user, &id);
+    assert_eq!(result, Err(Ok(WithdrawError::WithdrawalLocked)));
+
+    advance(&env, DAY);
+    assert_eq!(client.execute_scheduled_withdrawal(&user, &id), 300);
+    assert_eq!(client.get_user_deposit(&user, &None), 700);
+
+    // The second tranche is not due until the next interval
+    advance(&env, DAY / 2);
+    let result = client.try_execute_scheduled_withdrawal(&user, &id);
+    assert_eq!(result, Err(Ok(WithdrawError::WithdrawalLocked)));
+
+    advance(&env, DAY / 2);
+    assert_eq!(client.execute_scheduled_withdrawal(&user, &id), 300);
+    let schedule = client.get_withdrawal_schedule(&id).unwrap();
+    assert_eq!(schedule.tranches_paid, 2);
+    assert_eq!(schedule.amount_paid, 600);
+
+    advance(&env, DAY);
+    assert_eq!(client.execute_scheduled_withdrawal(&user, &id), 300);
+    assert_eq!(client.get_user_deposit(&user, &None), 100);
+    assert!(client.get_withdrawal_schedule(&id).is_none());
+}
+
+#[test]
+fn test_scheduled_withdrawal_catches_up_skipped_intervals() {
+    let env = Env::default();
+    let (_, _, client) = setup(&env);
+    let user = Address::generate(&env);
+    client.deposit_collateral(&user, &None, &1_000);
+
+    // 1000 / 3 does not divide evenly
+    let id = client.schedule_withdrawal(&user, &None, &1_000, &3, &DAY);
+
+    advance(&env, 2 * DAY + 10);
+    assert_eq!(client.execute_scheduled_withdrawal(&user, &id), 666);
+
+    // Waiting past the end only releases what is left
+    advance(&env, 5 * DAY);
+    assert_eq!(client.execute_scheduled_withdrawal(&user, &id), 334);
+    assert_eq!(client.get_user_deposit(&user, &None), 0);
+    assert!(client.get_withdrawal_schedule(&id).is_none());
+}
+
+#[test]
+fn test_scheduled_withdrawal_executable_by_anyone_for_owner() {
+    let env = Env::default();
+    let (_, _, client) = setup(&env);
+    let user = Address::generate(&env);
+    let other = Address::generate(&env);
+    client.deposit_collateral(&user, &None, &1_000);
+    client.deposit_collateral(&other, &None, &1_000);
+
+    let id = client.schedule_withdrawal(&user, &None, &500, &2, &DAY);
+    advance(&env, DAY);
+
+    // The schedule belongs to `user`, not to `other`
+    let result = client.try_execute_scheduled_withdrawal(&other, &id);
+    assert_eq!(result, Err(Ok(WithdrawError::RequestNotFound)));
+    assert_eq!(client.get_user_deposit(&other, &None), 1_000);
+
+    assert_eq!(client.execute_scheduled_withdrawal(&user, &id), 250);
+    assert_eq!(client.get_user_deposit(&user, &None), 750);
+}
+
+#[test]
+fn test_cancel_scheduled_withdrawal() {
+    let env = Env::default();
+    let (_, _, client) = setup(&env);
+    let user = Address::generate(&env);
+    let other = Address::generate(&env);
+    client.deposit_collateral(&user, &None, &1_000);
+
+    let id = client.schedule_withdrawal(&user, &None, &800, &4, &DAY);
+    advance(&env, DAY);
+    assert_eq!(client.execute_scheduled_withdrawal(&user, &id), 200);
+
+    let result = client.try_cancel_scheduled_withdrawal(&other, &id);
+    assert_eq!(result, Err(Ok(WithdrawError::Unauthorized)));
+
+    assert_eq!(client.cancel_scheduled_withdrawal(&user, &id), 600);
+    assert!(client.get_withdrawal_schedule(&id).is_none());
+    assert_eq!(client.get_user_deposit(&user, &None), 800);
+
+    advance(&env, DAY);
+    let result = client.try_execute_scheduled_withdrawal(&user, &id);
+    assert_eq!(result, Err(Ok(WithdrawError::RequestNotFound)));
+}
+
+#[test]
+fn test_schedule_withdrawal_rejects_invalid_plans() {
+    let env = Env::default();
+    let (_, _, client) = setup(&env);
+    let user = Address::generate(&env);
+    client.deposit_collateral(&user, &None, &1_000);
+
+    let result = client.try_schedule_withdrawal(&user, &None, &0, &3, &DAY);
+    assert_eq!(result, Err(Ok(WithdrawError::InvalidAmount)));
+    let result = client.try_schedule_withdrawal(&user, &None, &500, &0, &DAY);
+    assert_eq!(result, Err(Ok(WithdrawError::InvalidAmount)));
+    let result = client.try_schedule_withdrawal(&user, &None, &500, &3, &0);
+    assert_eq!(result, Err(Ok(WithdrawError::InvalidAmount)));
+    let result = client.try_schedule_withdrawal(&user, &None, &1_001, &3, &DAY);
+    assert_eq!(result, Err(Ok(WithdrawError::InsufficientCollateral)));
+}
+
+#[test]
+fn test_scheduled_tranche_blocked_when_position_unhealthy() {
+    let env = Env::default();
+    let (contract_id, _, client) = setup(&env);
+    let user = Address::generate(&env);
+    client.deposit_collateral(&user, &None, &1_000);
+
+    let id = client.schedule_withdrawal(&user, &None, &900, &3, &DAY);
+
+    // Debt taken after scheduling leaves no room for the second tranche
+    advance(&env, DAY);
+    assert_eq!(client.execute_scheduled_withdrawal(&user, &id), 300);
+    env.as_contract(&contract_id, || {
+        let key = DepositDataKey::Position(user.clone());
+        let mut position = env
+            .storage()
+            .persistent()
+            .get::<DepositDataKey, Position>(&key)
+            .unwrap();
+        position.debt = 400;
+        env.storage().persistent().set(&key, &position);
+    });
+
+    advance(&env, DAY);
+    let result = client.try_execute_scheduled_withdrawal(&user, &id);
+    assert_eq!(result, Err(Ok(WithdrawError::InsufficientCollateralRatio)));
+    assert_eq!(client.get_user_deposit(&user, &None), 700);
+    assert_eq!(
+        client.get_withdrawal_schedule(&id).unwrap().tranches_paid,
+        1
+    );
+}
@@ -1,9 +1,14 @@
 #![cfg(test)]
 
+use crate::tests::test_helpers::{asset_params, set_oracle_price};
 use crate::{HelloContract, HelloContractClient};
 use soroban_sdk::{testutils::Address as _, Address, Env, Symbol};
 
-use crate::deposit::{DepositDataKey, Position, ProtocolAnalytics, UserAnalytics};
+use crate::deposit::{
+    AssetParams, DepositDataKey, DepositError, Position, ProtocolAnalytics, SupplyPool,
+    UserAnalytics,
+};
+use crate::withdraw::WithdrawError;
 
 // Helper functions
 fn create_test_env() -> Env {
@@ -40,6 +45,21 @@ fn get_protocol_analytics(env: &Env, contract_id: &Address) -> Option<ProtocolAn
     })
 }
 
+/// Record native borrows, so deposits earn their share of the interest on them
+fn set_total_borrows(env: &Env, contract_id: &Address, total_borrows: i128) {
+    let mut analytics = get_protocol_analytics(env, contract_id).unwrap();
+    analytics.total_borrows = total_borrows;
+    env.as_contract(contract_id, || {
+        let key = DepositDataKey::ProtocolAnalytics;
+        env.storage().persistent().set(&key, &analytics);
+        let mut totals = crate::deposit::get_asset_totals(env, &None);
+        totals.total_borrowed = total_borrows;
+        env.storage()
+            .persistent()
+            .set(&DepositDataKey::AssetTotals(None), &totals);
+    });
+}
+
 // ==================== BASIC WITHDRAW TESTS ====================
 
 #[test]
@@ -58,8 +78,8 @@ fn test_withdraw_success() {
     let withdraw_amount = 500;
     let result = client.withdraw_collateral(&user, &None, &withdraw_amount);
 
-    // Verify result
-    assert_eq!(result, deposit_amount - withdraw_amount);
+    // Verify result (net amount received)
+    assert_eq!(result, withdraw_amount);
 
     // Verify collateral balance
     let balance = get_collateral_balance(&env, &contract_id, &user);
@@ -85,7 +105,7 @@ fn test_withdraw_full_amount_no_debt() {
     // Withdraw all (maximum withdrawal when no debt)
     let result = client.withdraw_collateral(&user, &None, &deposit_amount);
 
-    assert_eq!(result, 0);
+    assert_eq!(result, deposit_amount);
 
     // Verify collateral balance is zero
     let balance = get_collateral_balance(&env, &contract_id, &user);
@@ -107,17 +127,17 @@ fn test_withdraw_multiple_times() {
     // First withdrawal
     let withdraw1 = 300;
     let result1 = client.withdraw_collateral(&user, &None, &withdraw1);
-    assert_eq!(result1, deposit_amount - withdraw1);
+    assert_eq!(result1, withdraw1);
 
     // Second withdrawal
     let withdraw2 = 200;
     let result2 = client.withdraw_collateral(&user, &None, &withdraw2);
-    assert_eq!(result2, deposit_amount - withdraw1 - withdraw2);
+    assert_eq!(result2, withdraw2);
 
     // Third withdrawal
     let withdraw3 = 100;
     let result3 = client.withdraw_collateral(&user, &None, &withdraw3);
-    assert_eq!(result3, deposit_amount - withdraw1 - withdraw2 - withdraw3);
+    assert_eq!(result3, withdraw3);
 
     // Verify final balance
     let balance = get_collateral_balance(&env, &contract_id, &user);
@@ -218,7 +238,11 @@ fn test_withdraw_with_debt_maintains_ratio() {
     // After: 1500/500 = 300% (still > 150%)
     let withdraw_amount = 500;
     let result = client.withdraw_collateral(&user, &None, &withdraw_amount);
-    assert_eq!(result, collateral - withdraw_amount);
+    assert_eq!(result, withdraw_amount);
+    assert_eq!(
+        get_collateral_balance(&env, &contract_id, &user),
+        collateral - withdraw_amount
+    );
 }
 
 #[test]
@@ -313,7 +337,11 @@ fn test_withdraw_with_interest_accrued() {
     // After: 2000/600 = 333% (still > 150%)
     let withdraw_amount = 1000;
     let result = client.withdraw_collateral(&user, &None, &withdraw_amount);
-    assert_eq!(result, collateral - withdraw_amount);
+    assert_eq!(result, withdraw_amount);
+    assert_eq!(
+        get_collateral_balance(&env, &contract_id, &user),
+        collateral - withdraw_amount
+    );
 }
 
 // ==================== PAUSE MECHANISM TESTS ====================
@@ -468,7 +496,11 @@ fn test_withdraw_large_amounts() {
     // Withdraw large amount
     let withdraw_amount = large_amount / 2;
     let result = client.withdraw_collateral(&user, &None, &withdraw_amount);
-    assert_eq!(result, large_amount - withdraw_amount);
+    assert_eq!(result, withdraw_amount);
+    assert_eq!(
+        get_collateral_balance(&env, &contract_id, &user),
+        large_amount - withdraw_amount
+    );
 }
 
 #[test]
@@ -490,7 +522,8 @@ fn test_withdraw_after_multiple_deposits() {
 
     // Withdraw
     let result = client.withdraw_collateral(&user, &None, &400);
-    assert_eq!(result, 200);
+    assert_eq!(result, 400);
+    assert_eq!(get_collateral_balance(&env, &contract_id, &user), 200);
 }
 
 #[test]
@@ -538,9 +571,10 @@ fn test_withdraw_deposit_withdraw_cycle() {
 
     // Withdraw again
     let result = client.withdraw_collateral(&user, &None, &400);
+    assert_eq!(result, 400);
 
     // Final balance: 1000 - 500 + 300 - 400 = 400
-    assert_eq!(result, 400);
+    assert_eq!(get_collateral_balance(&env, &contract_id, &user), 400);
 }
 
 #[test]
@@ -585,3 +619,880 @@ fn test_withdraw_collateralization_ratio_calculation() {
     // Ratio = (1500 * 10000) / 500 = 30000 (300%)
     assert_eq!(analytics.collateralization_ratio, 30000);
 }
+
+// ==================== MAX WITHDRAW VIEW TESTS ====================
+
+/// Set the user's principal debt directly in storage
+fn set_debt(env: &Env, contract_id: &Address, user: &Address, debt: i128) {
+    env.as_contract(contract_id, || {
+        let position_key = DepositDataKey::Position(user.clone());
+        let mut position = env
+            .storage()
+            .persistent()
+            .get::<DepositDataKey, Position>(&position_key)
+            .unwrap();
+        position.debt = debt;
+        env.storage().persistent().set(&position_key, &position);
+    });
+}
+
+#[test]
+fn test_get_max_withdraw_no_position() {
+    let env = create_test_env();
+    let contract_id = env.register(HelloContract, ());
+    let client = HelloContractClient::new(&env, &contract_id);
+
+    let user = Address::generate(&env);
+    assert_eq!(client.get_max_withdraw(&user, &None), 0);
+}
+
+#[test]
+fn test_get_max_withdraw_no_debt_returns_full_balance() {
+    let env = create_test_env();
+    let contract_id = env.register(HelloContract, ());
+    let client = HelloContractClient::new(&env, &contract_id);
+
+    let user = Address::generate(&env);
+    client.deposit_collateral(&user, &None, &1000);
+
+    assert_eq!(client.get_max_withdraw(&user, &None), 1000);
+}
+
+#[test]
+fn test_get_max_withdraw_with_debt_is_exact() {
+    let env = create_test_env();
+    let contract_id = env.register(HelloContract, ());
+    let client = HelloContractClient::new(&env, &contract_id);
+
+    let user = Address::generate(&env);
+    client.deposit_collateral(&user, &None, &10_000);
+    set_debt(&env, &contract_id, &user, 1000);
+
+    // 1000 debt at the 150% minimum ratio needs 1500 collateral
+    let max = client.get_max_withdraw(&user, &None);
+    assert_eq!(max, 8500);
+
+    let result = client.try_withdraw_collateral(&user, &None, &(max + 1));
+    assert_eq!(result, Err(Ok(WithdrawError::InsufficientCollateralRatio)));
+
+    client.withdraw_collateral(&user, &None, &max);
+    assert_eq!(client.get_max_withdraw(&user, &None), 0);
+}
+
+// ==================== WITHDRAW ALL TESTS ====================
+
+#[test]
+fn test_withdraw_all_includes_accrued_interest() {
+    use soroban_sdk::testutils::Ledger;
+
+    let env = create_test_env();
+    let contract_id = env.register(HelloContract, ());
+    let client = HelloContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    client.initialize(&admin);
+    let user = Address::generate(&env);
+    client.deposit_collateral(&user, &None, &1_000_000);
+    set_total_borrows(&env, &contract_id, 500_000);
+
+    // A year of interest at 13.5% on the 500_000 borrowed, 90% of it credited
+    // to the supplier, before the borrowers repay
+    env.ledger().with_mut(|li| li.timestamp += 365 * 86400);
+    client.accrue_interest(&None);
+    set_total_borrows(&env, &contract_id, 0);
+
+    let withdrawn = client.withdraw_all(&user, &None);
+    assert_eq!(withdrawn, 1_060_750);
+    assert_eq!(client.get_user_shares(&user, &None), 0);
+    assert_eq!(client.get_user_deposit(&user, &None), 0);
+    assert!(get_user_position(&env, &contract_id, &user).is_none());
+}
+
+#[test]
+fn test_withdraw_collateral_sentinel_withdraws_everything() {
+    let env = create_test_env();
+    let contract_id = env.register(HelloContract, ());
+    let client = HelloContractClient::new(&env, &contract_id);
+
+    let user = Address::generate(&env);
+    client.deposit_collateral(&user, &None, &1000);
+
+    let received = client.withdraw_collateral(&user, &None, &crate::withdraw::WITHDRAW_ALL);
+    assert_eq!(received, 1000);
+    assert_eq!(get_collateral_balance(&env, &contract_id, &user), 0);
+    assert!(get_user_position(&env, &contract_id, &user).is_none());
+}
+
+#[test]
+fn test_withdraw_all_fails_when_only_partial_exit_is_healthy() {
+    let env = create_test_env();
+    let contract_id = env.register(HelloContract, ());
+    let client = HelloContractClient::new(&env, &contract_id);
+
+    let user = Address::generate(&env);
+    client.deposit_collateral(&user, &None, &10_000);
+    set_debt(&env, &contract_id, &user, 1000);
+    assert_eq!(client.get_max_withdraw(&user, &None), 8500);
+
+    let result = client.try_withdraw_all(&user, &None);
+    assert_eq!(result, Err(Ok(WithdrawError::InsufficientCollateralRatio)));
+    let result = client.try_withdraw_collateral(&user, &None, &crate::withdraw::WITHDRAW_ALL);
+    assert_eq!(result, Err(Ok(WithdrawError::InsufficientCollateralRatio)));
+
+    // Nothing was withdrawn
+    assert_eq!(get_collateral_balance(&env, &contract_id, &user), 10_000);
+    assert_eq!(client.get_user_deposit(&user, &None), 10_000);
+}
+
+#[test]
+fn test_withdraw_all_without_balance() {
+    let env = create_test_env();
+    let contract_id = env.register(HelloContract, ());
+    let client = HelloContractClient::new(&env, &contract_id);
+
+    let user = Address::generate(&env);
+    let result = client.try_withdraw_all(&user, &None);
+    assert_eq!(result, Err(Ok(WithdrawError::InsufficientCollateral)));
+}
+
+// ==================== WITHDRAW TO RECIPIENT TESTS ====================
+
+/// Register a token and deposit `amount` of it for `user`
+fn deposit_token(
+    env: &Env,
+    contract_id: &Address,
+    client: &HelloContractClient<'_>,
+    user: &Address,
+    amount: i128,
+) -> Address {
+    let token_admin = Address::generate(env);
+    let token = env.register_stellar_asset_contract(token_admin);
+    soroban_sdk::token::StellarAssetClient::new(env, &token).mint(user, &amount);
+    soroban_sdk::token::Client::new(env, &token).approve(
+        user,
+        contract_id,
+        &amount,
+        &(env.ledger().sequence() + 100),
+    );
+    client.deposit_collateral(user, &Some(token.clone()), &amount);
+    token
+}
+
+#[test]
+fn test_withdraw_asset_to_sends_tokens_to_recipient() {
+    use soroban_sdk::testutils::Events;
+    use soroban_sdk::TryFromVal;
+
+    let env = create_test_env();
+    let contract_id = env.register(HelloContract, ());
+    let client = HelloContractClient::new(&env, &contract_id);
+
+    let user = Address::generate(&env);
+    let hot_wallet = Address::generate(&env);
+    let token = deposit_token(&env, &contract_id, &client, &user, 1000);
+    let token_client = soroban_sdk::token::Client::new(&env, &token);
+
+    let received = client.withdraw_asset_to(&user, &Some(token.clone()), &400, &hot_wallet);
+    assert_eq!(received, 400);
+
+    // The owner authorized; collateral came from the owner, tokens went to the recipient
+    assert!(env.auths().iter().any(|(addr, _)| *addr == user));
+    assert_eq!(token_client.balance(&hot_wallet), 400);
+    assert_eq!(token_client.balance(&user), 0);
+    assert_eq!(get_collateral_balance(&env, &contract_id, &user), 600);
+    assert_eq!(get_collateral_balance(&env, &contract_id, &hot_wallet), 0);
+
+    let event = env
+        .events()
+        .all()
+        .iter()
+        .find_map(|(_c, _t, data)| {
+            crate::tests::events_test::TestWithdrawalEvent::try_from_val(&env, &data).ok()
+        })
+        .expect("withdrawal event not emitted");
+    assert_eq!(event.user, user);
+    assert_eq!(event.to, hot_wallet);
+    assert_eq!(event.amount, 400);
+}
+
+#[test]
+fn test_withdraw_asset_to_self_matches_withdraw_collateral() {
+    let env = create_test_env();
+    let contract_id = env.register(HelloContract, ());
+    let client = HelloContractClient::new(&env, &contract_id);
+
+    let user_a = Address::generate(&env);
+    let user_b = Address::generate(&env);
+    let token_a = deposit_token(&env, &contract_id, &client, &user_a, 1000);
+    let token_b = deposit_token(&env, &contract_id, &client, &user_b, 1000);
+
+    let result_a = client.withdraw_collateral(&user_a, &Some(token_a.clone()), &300);
+    let result_b = client.withdraw_asset_to(&user_b, &Some(token_b.clone()), &300, &user_b);
+
+    assert_eq!(result_a, result_b);
+    assert_eq!(
+        soroban_sdk::token::Client::new(&env, &token_a).balance(&user_a),
+        soroban_sdk::token::Client::new(&env, &token_b).balance(&user_b)
+    );
+    assert_eq!(
+        get_user_position(&env, &contract_id, &user_a),
+        get_user_position(&env, &contract_id, &user_b)
+    );
+}
+
+// ==================== CROSS-ASSET HEALTH TESTS ====================
+
+/// Two priced collateral assets (A at 200, B at 100) backing 1000 of debt in
+/// asset C (priced at 100)
+fn setup_cross_asset_position(
+    env: &Env,
+) -> (Address, HelloContractClient<'_>, Address, Address, Address) {
+    let contract_id = env.register(HelloContract, ());
+    let client = HelloContractClient::new(env, &contract_id);
+    let admin = Address::generate(env);
+    client.initialize(&admin);
+
+    let user = Address::generate(env);
+    let token_a = deposit_token(env, &contract_id, &client, &user, 1000);
+    let token_b = deposit_token(env, &contract_id, &client, &user, 1000);
+    let debt_asset = Address::generate(env);
+
+    let oracle = Address::generate(env);
+    set_oracle_price(&client, &admin, &token_a, &200, &7, &oracle);
+    set_oracle_price(&client, &admin, &token_b, &100, &7, &oracle);
+    set_oracle_price(&client, &admin, &debt_asset, &100, &7, &oracle);
+    client.borrow_asset(&user, &Some(debt_asset.clone()), &1000);
+
+    (contract_id, client, user, token_a, token_b)
+}
+
+#[test]
+fn test_cross_asset_withdraw_allows_only_partial_amount() {
+    let env = create_test_env();
+    let (_contract_id, client, user, token_a, token_b) = setup_cross_asset_position(&env);
+
+    // Collateral worth 300_000 against 100_000 of debt. At 150% the position
+    // needs 150_000, so 50_000 must stay in A: 250 units at a price of 200.
+    assert_eq!(client.get_max_withdraw(&user, &Some(token_a.clone())), 750);
+
+    let result = client.try_withdraw_collateral(&user, &Some(token_a.clone()), &751);
+    assert_eq!(result, Err(Ok(WithdrawError::WouldBeUndercollateralized)));
+
+    client.withdraw_collateral(&user, &Some(token_a.clone()), &750);
+    assert_eq!(client.get_max_withdraw(&user, &Some(token_a)), 0);
+
+    // Exactly at the minimum: nothing else can leave
+    let result = client.try_withdraw_collateral(&user, &Some(token_b), &1);
+    assert_eq!(result, Err(Ok(WithdrawError::WouldBeUndercollateralized)));
+}
+
+#[test]
+fn test_cross_asset_withdraw_blocked_by_stale_price() {
+    use soroban_sdk::testutils::Ledger;
+
+    let env = create_test_env();
+    let (_contract_id, client, user, token_a, _token_b) = setup_cross_asset_position(&env);
+
+    env.ledger().with_mut(|li| li.timestamp += 3601);
+
+    let result = client.try_withdraw_collateral(&user, &Some(token_a.clone()), &10);
+    assert_eq!(result, Err(Ok(WithdrawError::PriceUnavailable)));
+    assert_eq!(client.get_max_withdraw(&user, &Some(token_a)), 0);
+}
+
+#[test]
+fn test_cross_asset_withdraw_without_debt_needs_no_prices() {
+    let env = create_test_env();
+    let contract_id = env.register(HelloContract, ());
+    let client = HelloContractClient::new(&env, &contract_id);
+
+    let user = Address::generate(&env);
+    let token_a = deposit_token(&env, &contract_id, &client, &user, 1000);
+    deposit_token(&env, &contract_id, &client, &user, 1000);
+
+    // No price feeds exist, but without debt none are needed
+    client.withdraw_collateral(&user, &Some(token_a), &1000);
+}
+
+// ==================== BATCH WITHDRAW TESTS ====================
+
+#[test]
+fn test_withdraw_batch_checks_health_after_whole_batch() {
+    let env = create_test_env();
+    let (_contract_id, client, user, token_a, token_b) = setup_cross_asset_position(&env);
+
+    // Each item alone leaves the position healthy, together they leave
+    // 100_000 of collateral against the 150_000 required
+    let requests = soroban_sdk::vec![
+        &env,
+        (Some(token_a.clone()), 500i128),
+        (Some(token_b.clone()), 1000i128),
+    ];
+    let result = client.try_withdraw_batch(&user, &requests);
+    assert_eq!(result, Err(Ok(WithdrawError::WouldBeUndercollateralized)));
+
+    let reversed = soroban_sdk::vec![
+        &env,
+        (Some(token_b.clone()), 1000i128),
+        (Some(token_a.clone()), 500i128),
+    ];
+    let result = client.try_withdraw_batch(&user, &reversed);
+    assert_eq!(result, Err(Ok(WithdrawError::WouldBeUndercollateralized)));
+
+    assert_eq!(client.get_user_deposit(&user, &Some(token_a)), 1000);
+    assert_eq!(client.get_user_deposit(&user, &Some(token_b)), 1000);
+}
+
+#[test]
+fn test_withdraw_batch_healthy_in_any_order() {
+    let env = create_test_env();
+    let (_contract_id, client, user, token_a, token_b) = setup_cross_asset_position(&env);
+
+    // 150_000 + 50_000 of collateral remains against the 150_000 required
+    let requests = soroban_sdk::vec![
+        &env,
+        (Some(token_b.clone()), 500i128),
+        (Some(token_a.clone()), 250i128),
+    ];
+    let received = client.withdraw_batch(&user, &requests);
+    assert_eq!(received, soroban_sdk::vec![&env, 500i128, 250i128]);
+    assert_eq!(client.get_user_deposit(&user, &Some(token_a)), 750);
+    assert_eq!(client.get_user_deposit(&user, &Some(token_b)), 500);
+}
+
+#[test]
+fn test_withdraw_batch_is_all_or_nothing() {
+    let env = create_test_env();
+    let (_contract_id, client, user, token_a, token_b) = setup_cross_asset_position(&env);
+    let token_client = soroban_sdk::token::Client::new(&env, &token_a);
+
+    let requests = soroban_sdk::vec![
+        &env,
+        (Some(token_a.clone()), 100i128),
+        (Some(token_b.clone()), 2000i128),
+    ];
+    let result = client.try_withdraw_batch(&user, &requests);
+    assert_eq!(result, Err(Ok(WithdrawError::InsufficientCollateral)));
+    assert_eq!(client.get_user_deposit(&user, &Some(token_a)), 1000);
+    assert_eq!(token_client.balance(&user), 0);
+
+    let empty = soroban_sdk::Vec::new(&env);
+    let result = client.try_withdraw_batch(&user, &empty);
+    assert_eq!(result, Err(Ok(WithdrawError::InvalidAmount)));
+}
+
+// ==================== LIQUIDITY TESTS ====================
+
+#[test]
+fn test_withdraw_exceeding_liquidity_fails() {
+    let env = create_test_env();
+    let contract_id = env.register(HelloContract, ());
+    let client = HelloContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    client.initialize(&admin);
+
+    let lender = Address::generate(&env);
+    let borrower = Address::generate(&env);
+    let token = deposit_token(&env, &contract_id, &client, &lender, 1000);
+    client.deposit_collateral(&borrower, &None, &10_000);
+    set_oracle_price(&client, &admin, &token, &100, &7, &admin);
+
+    // Borrow 90% of the pool
+    client.borrow_asset(&borrower, &Some(token.clone()), &900);
+    assert_eq!(client.get_available_liquidity(&Some(token.clone())), 100);
+    assert_eq!(client.get_max_withdraw(&lender, &Some(token.clone())), 100);
+
+    // 20% of the pool is more than is on hand
+    let result = client.try_withdraw_collateral(&lender, &Some(token.clone()), &200);
+    assert_eq!(result, Err(Ok(WithdrawError::InsufficientLiquidity)));
+    assert_eq!(client.get_user_deposit(&lender, &Some(token.clone())), 1000);
+
+    client.withdraw_collateral(&lender, &Some(token.clone()), &100);
+    assert_eq!(client.get_available_liquidity(&Some(token.clone())), 0);
+    assert_eq!(
+        soroban_sdk::token::Client::new(&env, &token).balance(&lender),
+        100
+    );
+}
+
+#[test]
+fn test_available_liquidity_includes_reserves() {
+    let env = create_test_env();
+    let contract_id = env.register(HelloContract, ());
+    let client = HelloContractClient::new(&env, &contract_id);
+
+    let lender = Address::generate(&env);
+    let token = deposit_token(&env, &contract_id, &client, &lender, 1000);
+    assert_eq!(client.get_available_liquidity(&Some(token.clone())), 1000);
+
+    let donor = Address::generate(&env);
+    soroban_sdk::token::StellarAssetClient::new(&env, &token).mint(&donor, &50);
+    soroban_sdk::token::Client::new(&env, &token).approve(
+        &donor,
+        &contract_id,
+        &50,
+        &(env.ledger().sequence() + 100),
+    );
+    client.donate_to_reserves(&donor, &Some(token.clone()), &50);
+    assert_eq!(client.get_available_liquidity(&Some(token)), 1050);
+}
+
+// ==================== WITHDRAWAL FEE TESTS ====================
+
+fn fee_params(withdraw_fee_bps: i128) -> AssetParams {
+    AssetParams {
+        withdraw_fee_bps,
+        ..asset_params(7500)
+    }
+}
+
+/// Deposit 10_000 of a fresh token whose withdrawal fee is `withdraw_fee_bps`
+fn setup_withdraw_fee(
+    env: &Env,
+    withdraw_fee_bps: i128,
+) -> (HelloContractClient<'_>, Address, Address) {
+    setup_asset_with_params(env, fee_params(withdraw_fee_bps))
+}
+
+/// Deposit 10_000 of a fresh token configured with `params`
+fn setup_asset_with_params(
+    env: &Env,
+    params: AssetParams,
+) -> (HelloContractClient<'_>, Address, Address) {
+    let contract_id = env.register(HelloContract, ());
+    let client = HelloContractClient::new(env, &contract_id);
+    let admin = Address::generate(env);
+    client.initialize(&admin);
+
+    let user = Address::generate(env);
+    let token_admin = Address::generate(env);
+    let token = env.register_stellar_asset_contract(token_admin);
+    client.update_asset_params(&admin, &token, &params);
+
+    soroban_sdk::token::StellarAssetClient::new(env, &token).mint(&user, &10_000);
+    soroban_sdk::token::Client::new(env, &token).approve(
+        &user,
+        &contract_id,
+        &10_000,
+        &(env.ledger().sequence() + 100),
+    );
+    client.deposit_collateral(&user, &Some(token.clone()), &10_000);
+    (client, user, token)
+}
+
+#[test]
+fn test_withdraw_without_fee_pays_full_amount() {
+    let env = create_test_env();
+    let (client, user, token) = setup_withdraw_fee(&env, 0);
+
+    let received = client.withdraw_collateral(&user, &Some(token.clone()), &10_000);
+    assert_eq!(received, 10_000);
+    assert_eq!(
+        soroban_sdk::token::Client::new(&env, &token).balance(&user),
+        10_000
+    );
+    assert_eq!(client.get_reserve_balance(&Some(token)), 0);
+}
+
+#[test]
+fn test_withdraw_fee_credited_to_reserves() {
+    use soroban_sdk::testutils::Events;
+    use soroban_sdk::TryFromVal;
+
+    let env = create_test_env();
+    let (client, user, token) = setup_withdraw_fee(&env, 100);
+
+    let received = client.withdraw_collateral(&user, &Some(token.clone()), &10_000);
+    assert_eq!(received, 9_900);
+    assert_eq!(
+        soroban_sdk::token::Client::new(&env, &token).balance(&user),
+        9_900
+    );
+    assert_eq!(client.get_reserve_balance(&Some(token.clone())), 100);
+    assert_eq!(client.get_asset_totals(&Some(token)).total_reserves, 100);
+
+    let event = env
+        .events()
+        .all()
+        .iter()
+        .find_map(|(_c, _t, data)| {
+            crate::tests::events_test::TestWithdrawalEvent::try_from_val(&env, &data).ok()
+        })
+        .expect("withdrawal event not emitted");
+    assert_eq!(event.amount, 10_000);
+    assert_eq!(event.fee, 100);
+}
+
+#[test]
+fn test_withdraw_fee_above_max_rejected() {
+    let env = create_test_env();
+    let contract_id = env.register(HelloContract, ());
+    let client = HelloContractClient::new(&env, &contract_id);
+    let admin = Address::generate(&env);
+    client.initialize(&admin);
+    let token = Address::generate(&env);
+
+    let result = client.try_update_asset_params(&admin, &token, &fee_params(501));
+    assert_eq!(result, Err(Ok(DepositError::InvalidAssetParams)));
+
+    client.update_asset_params(&admin, &token, &fee_params(500));
+}
+
+// ==================== EMERGENCY WITHDRAW TESTS ====================
+
+fn setup_emergency(env: &Env) -> (Address, HelloContractClient<'_>, Address) {
+    let contract_id = env.register(HelloContract, ());
+    let client = HelloContractClient::new(env, &contract_id);
+    let admin = Address::generate(env);
+    client.initialize(&admin);
+    (contract_id, client, admin)
+}
+
+fn pause_withdrawals(env: &Env, contract_id: &Address) {
+    env.as_contract(contract_id, || {
+        let mut pause_map = soroban_sdk::Map::new(env);
+        pause_map.set(Symbol::new(env, "pause_withdraw"), true);
+        env.storage()
+            .persistent()
+            .set(&DepositDataKey::PauseSwitches, &pause_map);
+    });
+}
+
+#[test]
+fn test_emergency_withdraw_debt_free_user_during_emergency() {
+    let env = create_test_env();
+    let (contract_id, client, admin) = setup_emergency(&env);
+    let user = Address::generate(&env);
+
+    client.deposit_collateral(&user, &None, &1_000);
+    client.set_emergency_pause(&admin, &true);
+
+    // The emergency pause alone closes the regular withdrawal paths
+    let result = client.try_withdraw_collateral(&user, &None, &1_000);
+    assert_eq!(result, Err(Ok(WithdrawError::WithdrawPaused)));
+    let result = client.try_withdraw_all(&user, &None);
+    assert_eq!(result, Err(Ok(WithdrawError::WithdrawPaused)));
+
+    assert_eq!(client.emergency_withdraw(&user, &None), 1_000);
+    assert_eq!(client.get_user_deposit(&user, &None), 0);
+    assert_eq!(get_collateral_balance(&env, &contract_id, &user), 0);
+}
+
+#[test]
+fn test_emergency_withdraw_rejects_user_with_debt() {
+    let env = create_test_env();
+    let (contract_id, client, admin) = setup_emergency(&env);
+    let user = Address::generate(&env);
+
+    client.deposit_collateral(&user, &None, &10_000);
+    client.borrow_asset(&user, &None, &1);
+    client.set_emergency_pause(&admin, &true);
+
+    let result = client.try_emergency_withdraw(&user, &None);
+    assert_eq!(result, Err(Ok(WithdrawError::HasOutstandingDebt)));
+    assert_eq!(get_collateral_balance(&env, &contract_id, &user), 10_000);
+}
+
+#[test]
+fn test_emergency_withdraw_uses_regular_path_when_not_paused() {
+    let env = create_test_env();
+    let (contract_id, client, _admin) = setup_emergency(&env);
+    let user = Address::generate(&env);
+
+    client.deposit_collateral(&user, &None, &1_000);
+    pause_withdrawals(&env, &contract_id);
+
+    // Without an emergency the regular pause switch applies
+    let result = client.try_emergency_withdraw(&user, &None);
+    assert_eq!(result, Err(Ok(WithdrawError::WithdrawPaused)));
+
+    env.as_contract(&contract_id, || {
+        env.storage()
+            .persistent()
+            .remove(&DepositDataKey::PauseSwitches);
+    });
+    assert_eq!(client.emergency_withdraw(&user, &None), 1_000);
+    assert_eq!(client.get_user_deposit(&user, &None), 0);
+}
+
+// ==================== WITHDRAWAL COOLDOWN TESTS ====================
+
+const COOLDOWN: u64 = 86_400;
+const INTENT_WINDOW: u64 = 3_600;
+
+/// Deposit 10_000 of a fresh token with a one-day withdrawal cooldown
+fn setup_withdraw_cooldown(env: &Env) -> (HelloContractClient<'_>, Address, Address) {
+    let mut params = fee_params(0);
+    params.withdraw_cooldown_secs = COOLDOWN;
+    params.withdraw_intent_window_secs = INTENT_WINDOW;
+    setup_asset_with_params(env, params)
+}
+
+#[test]
+fn test_withdraw_cooldown_rejects_early_execution() {
+    use soroban_sdk::testutils::Ledger;
+
+    let env = create_test_env();
+    let (client, user, token) = setup_withdraw_cooldown(&env);
+    let token_client = soroban_sdk::token::Client::new(&env, &token);
+    let start = env.ledger().timestamp();
+
+    // The first call only registers the intent
+    assert_eq!(
+        client.withdraw_collateral(&user, &Some(token.clone()), &4_000),
+        0
+    );
+    assert_eq!(token_client.balance(&user), 0);
+    let intent = client.get_withdraw_intent(&user, &token).unwrap();
+    assert_eq!(intent.amount, 4_000);
+    assert_eq!(intent.unlock_at, start + COOLDOWN);
+    assert_eq!(intent.expires_at, start + COOLDOWN + INTENT_WINDOW);
+
+    env.ledger().with_mut(|li| li.timestamp += COOLDOWN - 1);
+    let result = client.try_withdraw_collateral(&user, &Some(token.clone()), &4_000);
+    assert_eq!(result, Err(Ok(WithdrawError::WithdrawalLocked)));
+
+    // Amounts beyond the intent are rejected once it matures
+    env.ledger().with_mut(|li| li.timestamp += 1);
+    let result = client.try_withdraw_collateral(&user, &Some(token), &4_001);
+    assert_eq!(result, Err(Ok(WithdrawError::IntentMismatch)));
+}
+
+#[test]
+fn test_withdraw_cooldown_executes_within_window() {
+    use soroban_sdk::testutils::Ledger;
+
+    let env = create_test_env();
+    let (client, user, token) = setup_withdraw_cooldown(&env);
+    let token_client = soroban_sdk::token::Client::new(&env, &token);
+
+    client.withdraw_collateral(&user, &Some(token.clone()), &4_000);
+    env.ledger()
+        .with_mut(|li| li.timestamp += COOLDOWN + INTENT_WINDOW);
+
+    assert_eq!(
+        client.withdraw_collateral(&user, &Some(token.clone()), &4_000),
+        4_000
+    );
+    assert_eq!(token_client.balance(&user), 4_000);
+    assert_eq!(client.get_user_deposit(&user, &Some(token.clone())), 6_000);
+    assert!(client.get_withdraw_intent(&user, &token).is_none());
+}
+
+#[test]
+fn test_withdraw_cooldown_intent_expires() {
+    use soroban_sdk::testutils::Ledger;
+
+    let env = create_test_env();
+    let (client, user, token) = setup_withdraw_cooldown(&env);
+    let token_client = soroban_sdk::token::Client::new(&env, &token);
+
+    client.withdraw_collateral(&user, &Some(token.clone()), &4_000);
+    env.ledger()
+        .with_mut(|li| li.timestamp += COOLDOWN + INTENT_WINDOW + 1);
+
+    // The lapsed intent is replaced and the cooldown starts over
+    assert_eq!(
+        client.withdraw_collateral(&user, &Some(token.clone()), &4_000),
+        0
+    );
+    assert_eq!(token_client.balance(&user), 0);
+    let intent = client.get_withdraw_intent(&user, &token).unwrap();
+    assert_eq!(intent.unlock_at, env.ledger().timestamp() + COOLDOWN);
+}
+
+#[test]
+fn test_cancel_withdraw_intent() {
+    let env = create_test_env();
+    let (client, user, token) = setup_withdraw_cooldown(&env);
+
+    client.withdraw_collateral(&user, &Some(token.clone()), &4_000);
+    client.cancel_withdraw_intent(&user, &token);
+    assert!(client.get_withdraw_intent(&user, &token).is_none());
+    assert_eq!(client.get_user_deposit(&user, &Some(token.clone())), 10_000);
+
+    let result = client.try_cancel_withdraw_intent(&user, &token);
+    assert_eq!(result, Err(Ok(WithdrawError::RequestNotFound)));
+}
+
+// ==================== MINIMUM RECEIVED TESTS ====================
+
+/// Scale the native supply pool's underlying, as interest or a loss would
+fn set_pool_underlying(env: &Env, contract_id: &Address, total_underlying: i128) {
+    env.as_contract(contract_id, || {
+        let key = DepositDataKey::SupplyPool(None);
+        let mut pool: SupplyPool = env.storage().persistent().get(&key).unwrap();
+        pool.total_underlying = total_underlying;
+        env.storage().persistent().set(&key, &pool);
+    });
+}
+
+#[test]
+fn test_withdraw_with_min_reverts_when_rate_drops() {
+    let env = create_test_env();
+    let contract_id = env.register(HelloContract, ());
+    let client = HelloContractClient::new(&env, &contract_id);
+    let user = Address::generate(&env);
+
+    client.deposit_collateral(&user, &None, &1_000);
+    let shares = client.get_user_shares(&user, &None);
+    let quoted = client.get_user_deposit(&user, &None);
+    assert_eq!(quoted, 1_000);
+
+    // The rate falls between the quote and execution
+    set_pool_underlying(&env, &contract_id, 900);
+    let result = client.try_withdraw_with_min(&user, &None, &shares, &quoted);
+    assert_eq!(result, Err(Ok(WithdrawError::SlippageExceeded)));
+    assert_eq!(client.get_user_shares(&user, &None), shares);
+
+    assert_eq!(client.withdraw_with_min(&user, &None, &shares, &900), 900);
+    assert_eq!(client.get_user_shares(&user, &None), 0);
+}
+
+#[test]
+fn test_withdraw_with_min_receives_accrued_value() {
+    let env = create_test_env();
+    let contract_id = env.register(HelloContract, ());
+    let client = HelloContractClient::new(&env, &contract_id);
+    let user = Address::generate(&env);
+
+    client.deposit_collateral(&user, &None, &1_000);
+
+    // Redeeming half the shares after the rate rises returns more than quoted
+    set_pool_underlying(&env, &contract_id, 1_200);
+    assert_eq!(client.withdraw_with_min(&user, &None, &500, &500), 600);
+    assert_eq!(client.get_user_shares(&user, &None), 500);
+
+    let result = client.try_withdraw_with_min(&user, &None, &501, &0);
+    assert_eq!(result, Err(Ok(WithdrawError::InsufficientCollateral)));
+}
+
+// ==================== WITHDRAWAL RECEIPT TESTS ====================
+
+#[test]
+fn test_withdrawal_receipt_ids_increase_across_users() {
+    use soroban_sdk::testutils::Ledger;
+
+    let env = create_test_env();
+    let contract_id = env.register(HelloContract, ());
+    let client = HelloContractClient::new(&env, &contract_id);
+    let alice = Address::generate(&env);
+    let bob = Address::generate(&env);
+
+    client.deposit_collateral(&alice, &None, &1_000);
+    client.deposit_collateral(&bob, &None, &1_000);
+
+    let first = client.withdraw_with_receipt(&alice, &None, &100);
+    client.deposit_collateral(&bob, &None, &500);
+    env.ledger().with_mut(|li| li.timestamp += 10);
+    let second = client.withdraw_with_receipt(&bob, &None, &200);
+    client.deposit_collateral(&alice, &None, &500);
+    client.withdraw_collateral(&alice, &None, &50);
+    let fourth = client.withdraw_with_receipt(&alice, &None, &300);
+
+    assert_eq!(first.receipt_id, 1);
+    assert_eq!(second.receipt_id, 2);
+    // The plain withdrawal in between took receipt 3
+    assert_eq!(fourth.receipt_id, 4);
+    assert_eq!(second.amount, 200);
+
+    let receipt = client.get_withdrawal_receipt(&2).unwrap();
+    assert_eq!(receipt.id, 2);
+    assert_eq!(receipt.user, bob);
+    assert_eq!(receipt.to, bob);
+    assert_eq!(receipt.asset, None);
+    assert_eq!(receipt.amount, 200);
+    assert_eq!(receipt.fee, 0);
+    assert_eq!(receipt.timestamp, env.ledger().timestamp());
+
+    assert_eq!(client.get_withdrawal_receipt(&3).unwrap().amount, 50);
+    assert!(client.get_withdrawal_receipt(&5).is_none());
+}
+
+#[test]
+fn test_withdrawal_receipts_kept_in_ring_buffer() {
+    let env = create_test_env();
+    let contract_id = env.register(HelloContract, ());
+    let client = HelloContractClient::new(&env, &contract_id);
+    let admin = Address::generate(&env);
+    client.initialize(&admin);
+    let user = Address::generate(&env);
+
+    let result = client.try_set_withdrawal_receipt_capacity(&user, &2);
+    assert_eq!(result, Err(Ok(WithdrawError::Unauthorized)));
+    client.set_withdrawal_receipt_capacity(&admin, &2);
+    assert_eq!(client.get_withdrawal_receipt_capacity(), 2);
+
+    client.deposit_collateral(&user, &None, &1_000);
+    for _ in 0..3 {
+        client.withdraw_collateral(&user, &None, &100);
+    }
+
+    // Only the two most recent receipts remain
+    assert!(client.get_withdrawal_receipt(&1).is_none());
+    assert_eq!(client.get_withdrawal_receipt(&2).unwrap().id, 2);
+    assert_eq!(client.get_withdrawal_receipt(&3).unwrap().id, 3);
+    env.as_contract(&contract_id, || {
+        assert!(!env
+            .storage()
+            .persistent()
+            .has(&DepositDataKey::WithdrawalReceipt(1)));
+    });
+}
+
+// ==================== STORAGE CLEANUP TESTS ====================
+
+#[test]
+fn test_full_withdrawal_removes_user_storage() {
+    let env = create_test_env();
+    let contract_id = env.register(HelloContract, ());
+    let client = HelloContractClient::new(&env, &contract_id);
+    let user = Address::generate(&env);
+
+    client.deposit_collateral(&user, &None, &1_000);
+    client.withdraw_collateral(&user, &None, &1_000);
+
+    assert_eq!(client.get_user_deposit(&user, &None), 0);
+    env.as_contract(&contract_id, || {
+        let storage = env.storage().persistent();
+        assert!(!storage.has(&DepositDataKey::Position(user.clone())));
+        assert!(!storage.has(&DepositDataKey::CollateralBalance(user.clone())));
+        assert!(!storage.has(&DepositDataKey::UserDeposit(user.clone(), None)));
+        assert!(!storage.has(&DepositDataKey::UserAssets(user.clone())));
+        assert!(!storage.has(&DepositDataKey::UserAnalytics(user.clone())));
+    });
+
+    // A later deposit starts from scratch
+    client.deposit_collateral(&user, &None, &500);
+    let analytics = get_user_analytics(&env, &contract_id, &user).unwrap();
+    assert_eq!(analytics.total_deposits, 500);
+    assert_eq!(analytics.total_withdrawals, 0);
+}
+
+#[test]
+fn test_emptied_asset_dropped_while_position_remains() {
+    let env = create_test_env();
+    let contract_id = env.register(HelloContract, ());
+    let client = HelloContractClient::new(&env, &contract_id);
+    let user = Address::generate(&env);
+
+    let token = deposit_token(&env, &contract_id, &client, &user, 1_000);
+    client.set_use_as_collateral(&user, &Some(token.clone()), &false);
+    client.deposit_collateral(&user, &None, &10_000);
+    client.borrow_asset(&user, &None, &1_000);
+
+    client.withdraw_collateral(&user, &Some(token.clone()), &1_000);
+
+    assert_eq!(client.get_user_deposits(&user).len(), 1);
+    env.as_contract(&contract_id, || {
+        let storage = env.storage().persistent();
+        assert!(!storage.has(&DepositDataKey::UserDeposit(
+            user.clone(),
+            Some(token.clone())
+        )));
+        // Debt is still owed, so the position stays
+        assert!(storage.has(&DepositDataKey::Position(user.clone())));
+        assert!(storage.has(&DepositDataKey::UserAnalytics(user.clone())));
+    });
+}
@@ -0,0 +1,141 @@
+//! Tests for governance-approved debt write-offs.
+//!
+//! # Coverage
+//! - A partial write-off covered by the reserve leaves no bad debt
+//! - A full write-off beyond the reserve records the shortfall as bad debt,
+//!   exposed through `get_protocol_analytics`
+//! - Only the admin can write off, and only existing debt
+//! - Each write-off emits an auditable event
+
+use crate::repay::RepayError;
+use crate::tests::test_helpers::setup_env_with_native_asset;
+use crate::HelloContractClient;
+use soroban_sdk::{
+    contracttype,
+    testutils::{Address as _, Events},
+    Address, Env, Symbol, TryFromVal,
+};
+
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct TestDebtWrittenOffEvent {
+    pub admin: Address,
+    pub borrower: Address,
+    pub asset: Option<Address>,
+    pub amount: i128,
+    pub from_reserve: i128,
+    pub bad_debt: i128,
+    pub remaining_debt: i128,
+    pub timestamp: u64,
+}
+
+/// `user` borrows 4_000 native against 10_000; the native reserve holds 1_000
+fn setup() -> (Env, HelloContractClient<'static>, Address, Address) {
+    let (env, contract_id, client, admin, user, native_asset) = setup_env_with_native_asset();
+    let donor = Address::generate(&env);
+    let token = soroban_sdk::token::StellarAssetClient::new(&env, &native_asset);
+    token.mint(&donor, &1_000);
+    token.approve(
+        &donor,
+        &contract_id,
+        &1_000,
+        &(env.ledger().sequence() + 100),
+    );
+    client.donate_to_reserves(&donor, &None, &1_000);
+
+    client.deposit_collateral(&user, &None, &10_000);
+    client.borrow_asset(&user, &None, &4_000);
+    (env, client, admin, user)
+}
+
+#[test]
+fn test_partial_write_off_drawn_from_reserve() {
+    let (env, client, admin, user) = setup();
+    let borrowed_before = client.get_asset_totals(&None).total_borrowed;
+
+    let (remaining_debt, from_reserve, bad_debt) =
+        client.write_off_debt(&admin, &user, &None, &600);
+    assert_eq!(remaining_debt, 3_400);
+    assert_eq!(from_reserve, 600);
+    assert_eq!(bad_debt, 0);
+    assert_eq!(client.get_user_debt(&user, &None), 3_400);
+    assert_eq!(client.get_reserve_balance(&None), 400);
+    assert_eq!(client.get_bad_debt(&None), 0);
+    assert_eq!(
+        client.get_asset_totals(&None).total_borrowed,
+        borrowed_before - 600
+    );
+
+    let (topics, event) = env
+        .events()
+        .all()
+        .iter()
+        .rev()
+        .find_map(|(_c, topics, data)| {
+            TestDebtWrittenOffEvent::try_from_val(&env, &data)
+                .ok()
+                .map(|event| (topics, event))
+        })
+        .expect("write-off event not emitted");
+    let topic = Symbol::try_from_val(&env, &topics.get_unchecked(0)).unwrap();
+    assert_eq!(topic, Symbol::new(&env, "debt_written_off_event"));
+    assert_eq!(event.admin, admin);
+    assert_eq!(event.borrower, user);
+    assert_eq!(event.amount, 600);
+    assert_eq!(event.from_reserve, 600);
+    assert_eq!(event.remaining_debt, 3_400);
+}
+
+#[test]
+fn test_full_write_off_beyond_reserve_records_bad_debt() {
+    let (_env, client, admin, user) = setup();
+
+    // Amounts above the debt write it off in full
+    let (remaining_debt, from_reserve, bad_debt) =
+        client.write_off_debt(&admin, &user, &None, &i128::MAX);
+    assert_eq!(remaining_debt, 0);
+    assert_eq!(from_reserve, 1_000);
+    assert_eq!(bad_debt, 3_000);
+    assert_eq!(client.get_user_debt(&user, &None), 0);
+    assert_eq!(client.get_reserve_balance(&None), 0);
+    assert_eq!(client.get_bad_debt(&None), 3_000);
+    assert_eq!(client.get_protocol_analytics().total_bad_debt, 3_000);
+
+    let result = client.try_write_off_debt(&admin, &user, &None, &1);
+    assert_eq!(result, Err(Ok(RepayError::NoDebt)));
+}
+
+#[test]
+fn test_write_off_reserve_insufficient_accumulates_bad_debt() {
+    let (_env, client, admin, user) = setup();
+
+    // The first write-off drains the reserve; later ones are all bad debt
+    client.write_off_debt(&admin, &user, &None, &1_500);
+    assert_eq!(client.get_reserve_balance(&None), 0);
+    assert_eq!(client.get_bad_debt(&None), 500);
+
+    let (remaining_debt, from_reserve, bad_debt) =
+        client.write_off_debt(&admin, &user, &None, &1_000);
+    assert_eq!(remaining_debt, 1_500);
+    assert_eq!(from_reserve, 0);
+    assert_eq!(bad_debt, 1_000);
+    assert_eq!(client.get_bad_debt(&None), 1_500);
+    assert_eq!(client.get_protocol_analytics().total_bad_debt, 1_500);
+}
+
+#[test]
+fn test_write_off_rejections() {
+    let (env, client, admin, user) = setup();
+
+    let result = client.try_write_off_debt(&user, &user, &None, &100);
+    assert_eq!(result, Err(Ok(RepayError::Unauthorized)));
+
+    let result = client.try_write_off_debt(&admin, &user, &None, &0);
+    assert_eq!(result, Err(Ok(RepayError::InvalidAmount)));
+
+    let lender = Address::generate(&env);
+    client.deposit_collateral(&lender, &None, &1_000);
+    let result = client.try_write_off_debt(&admin, &lender, &None, &100);
+    assert_eq!(result, Err(Ok(RepayError::NoDebt)));
+    assert_eq!(client.get_user_debt(&user, &None), 4_000);
+}
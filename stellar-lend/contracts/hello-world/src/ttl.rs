@@ -0,0 +1,141 @@
+//! # Storage TTL Module
+//!
+//! Keeps persistent position data from being archived.
+//!
+//! Every deposit, withdraw, borrow and repay bumps the TTL of the user's position
+//! entries, `ProtocolAnalytics`, and the `AssetParams` of the asset involved.
+//! Entries are only extended once their remaining TTL drops below `threshold`
+//! ledgers; they are then extended to `extend_to` ledgers.
+//!
+//! ## Storage Keys
+//! - `DepositDataKey::StorageTtlConfig` — admin-configured [`TtlConfig`]
+//!
+//! ## Public Refresh
+//! `touch_position` can be called by anyone to refresh a user's entries, so
+//! keepers can keep idle positions alive without the user's involvement.
+
+use soroban_sdk::{contracttype, Address, Env};
+
+use crate::deposit::{DepositDataKey, DepositError};
+
+/// Default threshold: ~30 days of 5-second ledgers
+pub const DEFAULT_TTL_THRESHOLD: u32 = 518_400;
+/// Default extension: ~180 days of 5-second ledgers
+pub const DEFAULT_TTL_EXTEND_TO: u32 = 3_110_400;
+
+/// TTL bump configuration, in ledgers
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct TtlConfig {
+    /// Entries with fewer remaining ledgers than this are extended
+    pub threshold: u32,
+    /// Remaining ledgers after an extension
+    pub extend_to: u32,
+}
+
+/// Get the TTL configuration, falling back to the defaults
+pub fn get_ttl_config(env: &Env) -> TtlConfig {
+    env.storage()
+        .persistent()
+        .get::<DepositDataKey, TtlConfig>(&DepositDataKey::StorageTtlConfig)
+        .unwrap_or(TtlConfig {
+            threshold: DEFAULT_TTL_THRESHOLD,
+            extend_to: DEFAULT_TTL_EXTEND_TO,
+        })
+}
+
+/// Set the TTL configuration (admin only).
+///
+/// # Errors
+/// * `DepositError::Unauthorized` - If caller is not the admin
+/// * `DepositError::InvalidAmount` - If `threshold > extend_to` or `extend_to`
+///   is zero or above the network maximum
+pub fn set_storage_ttl_config(
+    env: &Env,
+    admin: Address,
+    threshold: u32,
+    extend_to: u32,
+) -> Result<(), DepositError> {
+    let current_admin = crate::admin::get_admin(env).ok_or(DepositError::Unauthorized)?;
+    if admin != current_admin {
+        return Err(DepositError::Unauthorized);
+    }
+    admin.require_auth();
+
+    if extend_to == 0 || threshold > extend_to || extend_to > env.storage().max_ttl() {
+        return Err(DepositError::InvalidAmount);
+    }
+
+    let config = TtlConfig {
+        threshold,
+        extend_to,
+    };
+    env.storage()
+        .persistent()
+        .set(&DepositDataKey::StorageTtlConfig, &config);
+    bump(env, &config, &DepositDataKey::StorageTtlConfig);
+    Ok(())
+}
+
+/// Extend a persistent entry's TTL if it exists
+fn bump(env: &Env, config: &TtlConfig, key: &DepositDataKey) {
+    if env.storage().persistent().has(key) {
+        env.storage()
+            .persistent()
+            .extend_ttl(key, config.threshold, config.extend_to);
+    }
+}
+
+/// Extend the TTL of all position entries belonging to `user`
+pub fn bump_user_entries(env: &Env, user: &Address) {
+    let config = get_ttl_config(env);
+    bump(env, &config, &DepositDataKey::Position(user.clone()));
+    bump(
+        env,
+        &config,
+        &DepositDataKey::CollateralBalance(user.clone()),
+    );
+    bump(env, &config, &DepositDataKey::UserAnalytics(user.clone()));
+    bump(env, &config, &DepositDataKey::UserAssets(user.clone()));
+    for asset in crate::deposit::get_user_assets(env, user).iter() {
+        bump(
+            env,
+            &config,
+            &DepositDataKey::UserDeposit(user.clone(), asset),
+        );
+    }
+    bump(
+        env,
+        &config,
+        &DepositDataKey::UserBorrowedAssets(user.clone()),
+    );
+    bump(
+        env,
+        &config,
+        &DepositDataKey::QueuedWithdrawals(user.clone()),
+    );
+    for asset in crate::borrow::get_user_borrowed_assets(env, user).iter() {
+        bump(env, &config, &DepositDataKey::UserDebt(user.clone(), asset));
+    }
+}
+
+/// Extend the TTL of the protocol-wide entries touched by an operation on `asset`
+pub fn bump_protocol_entries(env: &Env, asset: &Option<Address>) {
+    let config = get_ttl_config(env);
+    bump(env, &config, &DepositDataKey::ProtocolAnalytics);
+    bump(env, &config, &DepositDataKey::StorageTtlConfig);
+    bump(env, &config, &DepositDataKey::BorrowIndex(asset.clone()));
+    if let Some(asset_addr) = asset {
+        bump(
+            env,
+            &config,
+            &DepositDataKey::AssetParams(asset_addr.clone()),
+        );
+    }
+}
+
+/// Refresh a user's position entries. Callable by anyone.
+pub fn touch_position(env: &Env, user: &Address) {
+    bump_user_entries(env, user);
+    bump_protocol_entries(env, &None);
+}
@@ -1,11 +1,15 @@
-use soroban_sdk::{contracterror, Address, Env, Map, Symbol};
+use soroban_sdk::{contracterror, contracttype, Address, Env, Map, Symbol, Vec};
 
 use crate::deposit::{
     add_activity_log, emit_analytics_updated_event, emit_position_updated_event,
     emit_user_activity_tracked_event, AssetParams, DepositDataKey, Position, ProtocolAnalytics,
     UserAnalytics,
 };
-use crate::events::{emit_withdrawal, WithdrawalEvent};
+use crate::events::{
+    emit_emergency_withdrawal, emit_withdraw_intent_cancelled, emit_withdraw_intent_registered,
+    emit_withdrawal, EmergencyWithdrawalEvent, WithdrawIntentCancelledEvent,
+    WithdrawIntentRegisteredEvent, WithdrawalEvent,
+};
 
 /// Errors that can occur during withdraw operations
 #[contracterror]
@@ -28,6 +32,74 @@ pub enum WithdrawError {
     Reentrancy = 7,
     /// Position would become undercollateralized
     Undercollateralized = 8,
+    /// Oracle-priced collateral would fall below the minimum collateral ratio
+    WouldBeUndercollateralized = 9,
+    /// A price needed to value the position is missing or stale
+    PriceUnavailable = 10,
+    /// The asset's available liquidity is below the requested amount
+    InsufficientLiquidity = 11,
+    /// No pending withdrawal request has this id
+    RequestNotFound = 12,
+    /// Caller does not own the withdrawal request
+    Unauthorized = 13,
+    /// Emergency withdrawals are only open to users without debt
+    HasOutstandingDebt = 14,
+    /// The withdrawal intent has not reached its unlock time
+    WithdrawalLocked = 15,
+    /// Amount exceeds the registered withdrawal intent
+    IntentMismatch = 16,
+    /// Shares redeem for less than the caller's minimum
+    SlippageExceeded = 17,
+    /// Withdrawals are paused for this asset
+    AssetPaused = 18,
+}
+
+/// Sentinel amount meaning "withdraw the entire current balance"
+pub const WITHDRAW_ALL: i128 = i128::MAX;
+
+/// Default number of withdrawal receipts kept in storage
+pub const DEFAULT_WITHDRAWAL_RECEIPT_CAPACITY: u32 = 1000;
+
+/// Outcome of a withdrawal
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct WithdrawalResult {
+    /// Net amount received after the withdrawal fee
+    pub amount: i128,
+    /// Receipt id of the withdrawal (0 when nothing was withdrawn)
+    pub receipt_id: u64,
+}
+
+/// Stored record of a completed withdrawal
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct WithdrawalReceipt {
+    /// Sequence number, strictly increasing across all users and assets
+    pub id: u64,
+    /// Account whose collateral was debited
+    pub user: Address,
+    /// Account that received the tokens
+    pub to: Address,
+    /// Asset withdrawn (None for native XLM)
+    pub asset: Option<Address>,
+    /// Amount debited from the position
+    pub amount: i128,
+    /// Withdrawal fee kept as reserves
+    pub fee: i128,
+    /// Time of the withdrawal
+    pub timestamp: u64,
+}
+
+/// Notice given for a withdrawal of an asset with a cooldown
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct WithdrawIntent {
+    /// Largest amount the intent allows to be withdrawn
+    pub amount: i128,
+    /// Earliest time the withdrawal can execute
+    pub unlock_at: u64,
+    /// Time after which the intent lapses (0 = never)
+    pub expires_at: u64,
 }
 
 // Minimum collateral ratio is now managed by the risk_params module
@@ -56,8 +128,618 @@ fn calculate_collateral_ratio(
     collateral_value.checked_mul(10000)?.checked_div(total_debt)
 }
 
+/// Withdrawal fee (basis points) charged on `asset`, 0 when not configured
+fn get_withdraw_fee_bps(env: &Env, asset: &Option<Address>) -> i128 {
+    asset
+        .as_ref()
+        .and_then(|asset_addr| {
+            env.storage()
+                .persistent()
+                .get::<DepositDataKey, AssetParams>(&DepositDataKey::AssetParams(
+                    asset_addr.clone(),
+                ))
+        })
+        .map(|params| params.withdraw_fee_bps)
+        .unwrap_or(0)
+}
+
+/// Withdrawal cooldown and intent window (seconds) of `asset`, zero when not configured
+fn get_withdraw_cooldown(env: &Env, asset: &Address) -> (u64, u64) {
+    env.storage()
+        .persistent()
+        .get::<DepositDataKey, AssetParams>(&DepositDataKey::AssetParams(asset.clone()))
+        .map(|params| {
+            (
+                params.withdraw_cooldown_secs,
+                params.withdraw_intent_window_secs,
+            )
+        })
+        .unwrap_or((0, 0))
+}
+
+/// Pending withdrawal intent of `user` for `asset`, including lapsed ones
+pub fn get_withdraw_intent(env: &Env, user: &Address, asset: &Address) -> Option<WithdrawIntent> {
+    env.storage()
+        .persistent()
+        .get::<DepositDataKey, WithdrawIntent>(&DepositDataKey::WithdrawIntent(
+            user.clone(),
+            asset.clone(),
+        ))
+}
+
+/// Consume a matured intent covering `amount`, or register a new one
+///
+/// Returns `true` when the withdrawal may execute now and `false` when an
+/// intent was registered instead. A lapsed intent is replaced, restarting the
+/// cooldown.
+fn consume_withdraw_intent(
+    env: &Env,
+    user: &Address,
+    asset: &Address,
+    amount: i128,
+    cooldown: u64,
+    window: u64,
+) -> Result<bool, WithdrawError> {
+    let key = DepositDataKey::WithdrawIntent(user.clone(), asset.clone());
+    let now = env.ledger().timestamp();
+
+    if let Some(intent) = get_withdraw_intent(env, user, asset) {
+        let expired = intent.expires_at != 0 && now > intent.expires_at;
+        if !expired {
+            if now < intent.unlock_at {
+                return Err(WithdrawError::WithdrawalLocked);
+            }
+            if amount > intent.amount {
+                return Err(WithdrawError::IntentMismatch);
+            }
+            env.storage().persistent().remove(&key);
+            return Ok(true);
+        }
+    }
+
+    let unlock_at = now.saturating_add(cooldown);
+    let expires_at = if window == 0 {
+        0
+    } else {
+        unlock_at.saturating_add(window)
+    };
+    env.storage().persistent().set(
+        &key,
+        &WithdrawIntent {
+            amount,
+            unlock_at,
+            expires_at,
+        },
+    );
+    emit_withdraw_intent_registered(
+        env,
+        WithdrawIntentRegisteredEvent {
+            user: user.clone(),
+            asset: asset.clone(),
+            amount,
+            unlock_at,
+            expires_at,
+        },
+    );
+    Ok(false)
+}
+
+/// Cancel `user`'s pending withdrawal intent for `asset`
+///
+/// # Errors
+/// * `WithdrawError::RequestNotFound` - If there is no intent to cancel
+pub fn cancel_withdraw_intent(
+    env: &Env,
+    user: Address,
+    asset: Address,
+) -> Result<(), WithdrawError> {
+    user.require_auth();
+
+    let intent = get_withdraw_intent(env, &user, &asset).ok_or(WithdrawError::RequestNotFound)?;
+    env.storage()
+        .persistent()
+        .remove(&DepositDataKey::WithdrawIntent(user.clone(), asset.clone()));
+
+    emit_withdraw_intent_cancelled(
+        env,
+        WithdrawIntentCancelledEvent {
+            user,
+            asset,
+            amount: intent.amount,
+            timestamp: env.ledger().timestamp(),
+        },
+    );
+    Ok(())
+}
+
+/// Collateral factor applied when `user` withdraws `asset`
+///
+/// Uses the user's e-mode category when it governs their position. Otherwise
+/// defaults to 100% when the asset has no parameters and for native XLM.
+fn get_collateral_factor(env: &Env, user: &Address, asset: Option<&Address>) -> i128 {
+    if let Some(category) = crate::risk_management::get_active_emode_category(env, user, None) {
+        return category.collateral_factor;
+    }
+    if let Some(asset_addr) = asset {
+        let asset_params_key = DepositDataKey::AssetParams(asset_addr.clone());
+        if let Some(params) = env
+            .storage()
+            .persistent()
+            .get::<DepositDataKey, AssetParams>(&asset_params_key)
+        {
+            params.collateral_factor
+        } else {
+            10000 // Default 100% if not configured
+        }
+    } else {
+        10000 // Default 100% for native XLM
+    }
+}
+
+/// Minimum collateral ratio (basis points) for `user`, 150% if risk params are not
+/// initialized
+///
+/// In e-mode the category's collateral factor is the whole borrowing limit, so
+/// the ratio is 100%.
+fn get_min_collateral_ratio(env: &Env, user: &Address) -> i128 {
+    if crate::risk_management::get_active_emode_category(env, user, None).is_some() {
+        return 10000;
+    }
+    crate::risk_params::get_min_collateral_ratio(env).unwrap_or(15000)
+}
+
+/// Amount of `asset` the user can currently redeem
+///
+/// Amounts waiting in the withdrawal queue are excluded.
+fn get_withdrawable_balance(env: &Env, user: &Address, asset: &Option<Address>) -> i128 {
+    if crate::deposit::get_user_shares(env, user.clone(), asset.clone()) > 0 {
+        crate::deposit::get_user_deposit_balance(env, user, asset)
+    } else {
+        env.storage()
+            .persistent()
+            .get::<DepositDataKey, i128>(&DepositDataKey::CollateralBalance(user.clone()))
+            .unwrap_or(0)
+            .saturating_sub(crate::withdraw_queue::get_user_queued_total(env, user))
+            .max(0)
+    }
+}
+
+/// Check the global `pause_withdraw` switch
+///
+/// An emergency pause also stops regular withdrawals, leaving
+/// [`emergency_withdraw`] as the only exit.
+pub(crate) fn is_withdraw_paused(env: &Env) -> bool {
+    crate::risk_management::is_emergency_paused(env)
+        || env
+            .storage()
+            .persistent()
+            .get::<DepositDataKey, Map<Symbol, bool>>(&DepositDataKey::PauseSwitches)
+            .and_then(|pause_map| pause_map.get(Symbol::new(env, "pause_withdraw")))
+            .unwrap_or(false)
+}
+
+/// Check the asset-scoped `pause_withdraw` switch of `asset`
+pub(crate) fn is_asset_withdraw_paused(env: &Env, asset: &Option<Address>) -> bool {
+    crate::risk_management::is_asset_operation_paused(
+        env,
+        asset,
+        Symbol::new(env, "pause_withdraw"),
+    )
+}
+
+/// Cash the protocol holds in `asset`, including amounts owed to the withdrawal queue
+pub(crate) fn get_pool_liquidity(env: &Env, asset: &Option<Address>) -> i128 {
+    let totals = crate::deposit::get_asset_totals(env, asset);
+    let pool = crate::deposit::load_supply_pool(env, asset);
+    let queued = crate::withdraw_queue::get_asset_queued_total(env, asset);
+    totals
+        .total_supplied
+        .max(pool.total_underlying.saturating_add(queued))
+        .saturating_sub(totals.total_borrowed)
+        .saturating_add(totals.total_reserves)
+        .max(0)
+}
+
+/// Amount of `asset` the protocol can pay out right now
+///
+/// Computed as total supplied minus total borrowed plus reserves held, less
+/// whatever the withdrawal queue is already waiting for. Supplied is taken from
+/// the share pool when it holds more, so interest accrued to depositors counts
+/// toward liquidity. Never negative.
+pub fn get_available_liquidity(env: &Env, asset: &Option<Address>) -> i128 {
+    get_pool_liquidity(env, asset)
+        .saturating_sub(crate::withdraw_queue::get_asset_queued_total(env, asset))
+        .max(0)
+}
+
+/// Oracle price of `asset`
+///
+/// Native XLM is priced through its configured token address. Missing or stale
+/// prices fail with `PriceUnavailable`, which blocks the withdrawal.
+fn get_asset_price(env: &Env, asset: &Option<Address>) -> Result<i128, WithdrawError> {
+    let asset_addr = match asset {
+        Some(asset_addr) => asset_addr.clone(),
+        None => env
+            .storage()
+            .persistent()
+            .get::<DepositDataKey, Address>(&DepositDataKey::NativeAssetAddress)
+            .ok_or(WithdrawError::PriceUnavailable)?,
+    };
+    crate::oracle::get_price(env, &asset_addr).map_err(|_| WithdrawError::PriceUnavailable)
+}
+
+/// Oracle-priced (collateral value, debt value) of a cross-asset position
+///
+/// Collateral-enabled deposits are valued at `balance * price * collateral_factor`,
+/// with `withdrawal` taken out of its asset first. Total debt (principal plus
+/// accrued interest) is attributed to each borrowed asset pro rata to its principal.
+///
+/// Returns `None` when the collateral and debt are all in one asset, where
+/// prices cancel out and the unpriced ratio check is exact, and for debt that
+/// has no per-asset record.
+fn get_priced_position(
+    env: &Env,
+    user: &Address,
+    position: &Position,
+    withdrawal: Option<(&Option<Address>, i128)>,
+) -> Result<Option<(i128, i128)>, WithdrawError> {
+    let borrowed_assets = crate::borrow::get_user_borrowed_assets(env, user);
+    if borrowed_assets.is_empty() {
+        return Ok(None);
+    }
+
+    // Queued withdrawals keep counting as collateral until they are paid
+    let queued = crate::withdraw_queue::get_user_queued(env, user);
+    let mut deposit_assets = crate::deposit::get_user_assets(env, user);
+    for asset in queued.keys().iter() {
+        if !deposit_assets.contains(&asset) {
+            deposit_assets.push_back(asset);
+        }
+    }
+
+    let mut collateral: Vec<(Option<Address>, i128)> = Vec::new(env);
+    let mut involved = borrowed_assets.clone();
+    for asset in deposit_assets.iter() {
+        let balance = crate::deposit::get_user_deposit_balance(env, user, &asset)
+            .saturating_add(queued.get(asset.clone()).unwrap_or(0));
+        if balance > 0 && crate::deposit::is_collateral_enabled(env, user, &asset) {
+            if !involved.contains(&asset) {
+                involved.push_back(asset.clone());
+            }
+            collateral.push_back((asset, balance));
+        }
+    }
+    if involved.len() < 2 {
+        return Ok(None);
+    }
+
+    let mut collateral_value: i128 = 0;
+    for (asset, balance) in collateral.iter() {
+        let balance = match withdrawal {
+            Some((withdrawn_asset, amount)) if *withdrawn_asset == asset => {
+                balance.saturating_sub(amount).max(0)
+            }
+            _ => balance,
+        };
+        let value = balance
+            .checked_mul(get_asset_price(env, &asset)?)
+            .and_then(|v| v.checked_mul(get_collateral_factor(env, user, asset.as_ref())))
+            .map(|v| v / 10000)
+            .ok_or(WithdrawError::Overflow)?;
+        collateral_value = collateral_value
+            .checked_add(value)
+            .ok_or(WithdrawError::Overflow)?;
+    }
+
+    let total_debt = position
+        .debt
+        .checked_add(position.borrow_interest)
+        .ok_or(WithdrawError::Overflow)?;
+    let mut total_principal: i128 = 0;
+    for asset in borrowed_assets.iter() {
+        total_principal = total_principal
+            .checked_add(crate::borrow::get_user_debt_principal(env, user, &asset))
+            .ok_or(WithdrawError::Overflow)?;
+    }
+    if total_principal == 0 {
+        return Ok(None);
+    }
+
+    let mut debt_value: i128 = 0;
+    for asset in borrowed_assets.iter() {
+        let principal = crate::borrow::get_user_debt_principal(env, user, &asset);
+        let price = get_asset_price(env, &asset)?;
+        let value = total_debt
+            .checked_mul(principal)
+            .map(|v| v / total_principal)
+            .and_then(|debt| debt.checked_mul(price))
+            .ok_or(WithdrawError::Overflow)?;
+        debt_value = debt_value
+            .checked_add(value)
+            .ok_or(WithdrawError::Overflow)?;
+    }
+
+    Ok(Some((collateral_value, debt_value)))
+}
+
+/// Largest amount of `asset` the user can withdraw right now
+///
+/// The result is the smallest of:
+/// - the user's redeemable balance in the asset,
+/// - the amount that keeps the position at or above the minimum collateral ratio
+///   (unbounded when the user has no debt or the deposit is not used as collateral),
+/// - the asset's available liquidity (see [`get_available_liquidity`]).
+///
+/// Cross-asset positions are bounded at oracle prices; if a price is missing or
+/// stale the result is 0, matching the withdraw path.
+///
+/// Returns 0 for users without a position.
+pub fn get_max_withdraw(env: &Env, user: &Address, asset: &Option<Address>) -> i128 {
+    let position = match env
+        .storage()
+        .persistent()
+        .get::<DepositDataKey, Position>(&DepositDataKey::Position(user.clone()))
+    {
+        Some(position) => position,
+        None => return 0,
+    };
+
+    let mut max = get_withdrawable_balance(env, user, asset);
+
+    let total_debt = position.debt.saturating_add(position.borrow_interest);
+    if total_debt > 0 && crate::deposit::is_collateral_enabled(env, user, asset) {
+        match get_priced_position(env, user, &position, Some((asset, max))) {
+            Ok(Some((remaining_value, debt_value))) => {
+                // The asset's own value must cover whatever the rest of the
+                // collateral leaves short of the minimum ratio
+                let required_value = match get_min_collateral_ratio(env, user)
+                    .checked_mul(debt_value)
+                    .map(|v| (v + 9_999) / 10_000)
+                {
+                    Some(value) => value,
+                    None => return 0,
+                };
+                let shortfall = required_value.saturating_sub(remaining_value);
+                if shortfall > 0 {
+                    let unit_value = match get_asset_price(env, asset) {
+                        Ok(price) => {
+                            price.saturating_mul(get_collateral_factor(env, user, asset.as_ref()))
+                        }
+                        Err(_) => return 0,
+                    };
+                    if unit_value <= 0 {
+                        return 0;
+                    }
+                    let kept = match shortfall
+                        .checked_mul(10_000)
+                        .map(|v| (v + unit_value - 1) / unit_value)
+                    {
+                        Some(kept) => kept,
+                        None => return 0,
+                    };
+                    max = max.saturating_sub(kept);
+                }
+            }
+            Ok(None) => {
+                // Invert the ratio check: find the smallest collateral C' with
+                // floor(floor(C' * cf / 10000) * 10000 / debt) >= min_ratio
+                let collateral_factor = get_collateral_factor(env, user, asset.as_ref());
+                if collateral_factor <= 0 {
+                    return 0;
+                }
+                let min_value = match get_min_collateral_ratio(env, user)
+                    .checked_mul(total_debt)
+                    .map(|v| (v + 9_999) / 10_000)
+                {
+                    Some(value) => value,
+                    None => return 0,
+                };
+                let min_collateral = match min_value
+                    .checked_mul(10_000)
+                    .map(|v| (v + collateral_factor - 1) / collateral_factor)
+                {
+                    Some(collateral) => collateral,
+                    None => return 0,
+                };
+                let current_collateral = crate::deposit::get_effective_collateral(env, user);
+                max = max.min(current_collateral.saturating_sub(min_collateral));
+            }
+            Err(_) => return 0,
+        }
+    }
+
+    max.min(get_available_liquidity(env, asset)).max(0)
+}
+
+/// Withdraw the user's entire balance of an asset
+///
+/// The balance is read at execution time, so interest accrued since the user
+/// quoted it is included. The withdrawal is all-or-nothing: if health checks
+/// only allow part of the balance out, it fails instead of withdrawing less.
+///
+/// # Returns
+/// The net amount received after any withdrawal fee
+pub fn withdraw_all(
+    env: &Env,
+    user: Address,
+    asset: Option<Address>,
+) -> Result<i128, WithdrawError> {
+    let amount = get_withdrawable_balance(env, &user, &asset);
+    if amount <= 0 {
+        return Err(WithdrawError::InsufficientCollateral);
+    }
+    withdraw_collateral(env, user, asset, amount)
+}
+
+/// Redeem `shares` of a deposit, reverting if they are worth less than `min_amount_out`
+///
+/// The exchange rate can move between quoting and execution, so callers such
+/// as routers pass the smallest underlying amount they accept. The shares are
+/// then withdrawn like [`withdraw_collateral`].
+///
+/// # Returns
+/// The net amount received after any withdrawal fee
+///
+/// # Errors
+/// * `WithdrawError::InvalidAmount` - If `shares` is zero or negative
+/// * `WithdrawError::InsufficientCollateral` - If the user holds fewer than `shares`
+/// * `WithdrawError::SlippageExceeded` - If the shares redeem for less than `min_amount_out`
+/// * Any error [`withdraw_collateral`] can return
+pub fn withdraw_with_min(
+    env: &Env,
+    user: Address,
+    asset: Option<Address>,
+    shares: i128,
+    min_amount_out: i128,
+) -> Result<i128, WithdrawError> {
+    user.require_auth();
+
+    if shares <= 0 {
+        return Err(WithdrawError::InvalidAmount);
+    }
+    if crate::deposit::get_user_shares(env, user.clone(), asset.clone()) < shares {
+        return Err(WithdrawError::InsufficientCollateral);
+    }
+
+    let amount =
+        crate::deposit::preview_redeem(env, &asset, shares).ok_or(WithdrawError::Overflow)?;
+    if amount < min_amount_out {
+        return Err(WithdrawError::SlippageExceeded);
+    }
+    if amount <= 0 {
+        return Err(WithdrawError::InvalidAmount);
+    }
+
+    execute_withdrawal(env, &user, &asset, amount, &user, true).map(|result| result.amount)
+}
+
+/// Withdraw several assets in one all-or-nothing call
+///
+/// Each item gets the same validation as [`withdraw_collateral`] except the
+/// collateral ratio, which is checked once against the state after the whole
+/// batch, so splitting or reordering items cannot slip past the health check.
+/// Any failure reverts every item. Items may use [`WITHDRAW_ALL`].
+///
+/// # Returns
+/// The net amount received for each item, in order
+///
+/// # Errors
+/// * `WithdrawError::InvalidAmount` - If the batch is empty or an amount is not positive
+/// * Any error [`withdraw_collateral`] can return for an item or the final state
+pub fn withdraw_batch(
+    env: &Env,
+    user: Address,
+    requests: Vec<(Option<Address>, i128)>,
+) -> Result<Vec<i128>, WithdrawError> {
+    user.require_auth();
+
+    if requests.is_empty() {
+        return Err(WithdrawError::InvalidAmount);
+    }
+
+    let mut received = Vec::new(env);
+    let mut assets: Vec<Option<Address>> = Vec::new(env);
+    for (asset, amount) in requests.iter() {
+        received.push_back(execute_withdrawal(env, &user, &asset, amount, &user, false)?.amount);
+        if !assets.contains(&asset) {
+            assets.push_back(asset);
+        }
+    }
+
+    // A fully exited position has nothing left to check
+    if env
+        .storage()
+        .persistent()
+        .has(&DepositDataKey::Position(user.clone()))
+    {
+        for asset in assets.iter() {
+            validate_collateral_ratio_after_withdraw(env, &user, 0, asset.as_ref())?;
+        }
+    }
+
+    Ok(received)
+}
+
+/// Withdraw the user's entire balance of an asset during an emergency pause
+///
+/// Only users with no debt in any asset may use this path, so no health check
+/// or price is needed and the global pause is bypassed. Available
+/// liquidity and the withdrawal fee still apply, and an
+/// `EmergencyWithdrawalEvent` is emitted alongside the usual withdrawal event.
+/// When the protocol is not emergency paused this is a regular [`withdraw_all`].
+///
+/// # Returns
+/// The net amount received after any withdrawal fee
+///
+/// # Errors
+/// * `WithdrawError::HasOutstandingDebt` - If the user owes anything
+/// * `WithdrawError::AssetPaused` - If withdrawals are paused for this asset
+/// * `WithdrawError::InsufficientCollateral` - If the user has no balance in `asset`
+/// * `WithdrawError::InsufficientLiquidity` - If the pool cannot pay the balance
+pub fn emergency_withdraw(
+    env: &Env,
+    user: Address,
+    asset: Option<Address>,
+) -> Result<i128, WithdrawError> {
+    if !crate::risk_management::is_emergency_paused(env) {
+        return withdraw_all(env, user, asset);
+    }
+
+    user.require_auth();
+
+    // A frozen asset stays frozen even in an emergency
+    if is_asset_withdraw_paused(env, &asset) {
+        return Err(WithdrawError::AssetPaused);
+    }
+
+    let position = env
+        .storage()
+        .persistent()
+        .get::<DepositDataKey, Position>(&DepositDataKey::Position(user.clone()));
+    let has_debt = position.is_some_and(|p| p.debt > 0 || p.borrow_interest > 0)
+        || !crate::borrow::get_user_borrowed_assets(env, &user).is_empty();
+    if has_debt {
+        return Err(WithdrawError::HasOutstandingDebt);
+    }
+
+    let amount = get_withdrawable_balance(env, &user, &asset);
+    if amount <= 0 {
+        return Err(WithdrawError::InsufficientCollateral);
+    }
+    if amount > get_available_liquidity(env, &asset) {
+        return Err(WithdrawError::InsufficientLiquidity);
+    }
+
+    if crate::deposit::get_user_shares(env, user.clone(), asset.clone()) > 0 {
+        crate::deposit::redeem_shares(env, &user, &asset, amount).map_err(|e| match e {
+            crate::deposit::DepositError::InsufficientBalance => {
+                WithdrawError::InsufficientCollateral
+            }
+            _ => WithdrawError::Overflow,
+        })?;
+    }
+
+    let net_amount = complete_withdrawal(env, &user, &asset, amount, &user)?.amount;
+
+    emit_emergency_withdrawal(
+        env,
+        EmergencyWithdrawalEvent {
+            user,
+            asset,
+            amount,
+            fee: amount - net_amount,
+            timestamp: env.ledger().timestamp(),
+        },
+    );
+
+    Ok(net_amount)
+}
+
 /// Check if withdrawal would violate minimum collateral ratio
-fn validate_collateral_ratio_after_withdraw(
+///
+/// Only collateral-enabled deposits count; withdrawing a disabled deposit never
+/// changes the ratio.
+pub fn validate_collateral_ratio_after_withdraw(
     env: &Env,
     user: &Address,
     withdraw_amount: i128,
@@ -76,35 +758,39 @@ fn validate_collateral_ratio_after_withdraw(
         return Ok(());
     }
 
-    // Get current collateral balance
-    let collateral_key = DepositDataKey::CollateralBalance(user.clone());
-    let current_collateral = env
-        .storage()
-        .persistent()
-        .get::<DepositDataKey, i128>(&collateral_key)
-        .unwrap_or(0);
+    // Cross-asset positions are valued at oracle prices
+    let withdrawn_asset = asset.cloned();
+    let withdrawal = Some((&withdrawn_asset, withdraw_amount));
+    if let Some((collateral_value, debt_value)) =
+        get_priced_position(env, user, &position, withdrawal)?
+    {
+        if debt_value == 0 {
+            return Ok(());
+        }
+        let ratio = collateral_value
+            .checked_mul(10000)
+            .ok_or(WithdrawError::Overflow)?
+            / debt_value;
+        if ratio < get_min_collateral_ratio(env, user) {
+            return Err(WithdrawError::WouldBeUndercollateralized);
+        }
+        return Ok(());
+    }
 
-    // Calculate new collateral after withdrawal
-    let new_collateral = current_collateral
-        .checked_sub(withdraw_amount)
-        .ok_or(WithdrawError::InsufficientCollateral)?;
+    // Get collateral counted toward borrowing power
+    let current_collateral = crate::deposit::get_effective_collateral(env, user);
 
-    // Get asset parameters for collateral factor
-    // Default collateral factor if asset params not found
-    let collateral_factor = if let Some(asset_addr) = asset {
-        let asset_params_key = DepositDataKey::AssetParams(asset_addr.clone());
-        if let Some(params) = env
-            .storage()
-            .persistent()
-            .get::<DepositDataKey, AssetParams>(&asset_params_key)
-        {
-            params.collateral_factor
-        } else {
-            10000 // Default 100% if not configured
-        }
+    // Calculate new collateral after withdrawal. Redeemed interest can exceed the
+    // legacy aggregate, so floor at zero.
+    let counted_amount = if crate::deposit::is_collateral_enabled(env, user, &asset.cloned()) {
+        withdraw_amount
     } else {
-        10000 // Default 100% for native XLM
+        0
     };
+    let new_collateral = current_collateral.saturating_sub(counted_amount).max(0);
+
+    // Get asset parameters for collateral factor
+    let collateral_factor = get_collateral_factor(env, user, asset);
 
     // Calculate total debt (debt + accrued interest)
     let _total_debt = position
@@ -119,8 +805,7 @@ fn validate_collateral_ratio_after_withdraw(
         position.borrow_interest,
         collateral_factor,
     ) {
-        let min_ratio = crate::risk_params::get_min_collateral_ratio(env).unwrap_or(15000);
-        if new_ratio < min_ratio {
+        if new_ratio < get_min_collateral_ratio(env, user) {
             return Err(WithdrawError::InsufficientCollateralRatio);
         }
     } else {
@@ -143,17 +828,34 @@ fn validate_collateral_ratio_after_withdraw(
 /// * `env` - The Soroban environment
 /// * `user` - The address of the user withdrawing collateral
 /// * `asset` - The address of the asset contract to withdraw (None for native XLM)
-/// * `amount` - The amount to withdraw
+/// * `amount` - The amount to withdraw, or [`WITHDRAW_ALL`] for the entire
+///   current balance including accrued interest
 ///
 /// # Returns
-/// Returns the updated collateral balance for the user
+/// Returns the net amount received: `amount` minus the asset's withdrawal fee,
+/// which is credited to the protocol reserve. Returns 0 when the asset has a
+/// withdrawal cooldown and this call only registered the intent to withdraw.
+///
+/// # Cooldown
+/// For assets with `withdraw_cooldown_secs` set, the first call registers a
+/// [`WithdrawIntent`] for `amount`. Calling again once the cooldown has passed
+/// withdraws up to that amount and consumes the intent. An intent left unused
+/// past `withdraw_intent_window_secs` lapses and the next call registers a new one.
 ///
 /// # Errors
 /// * `WithdrawError::InvalidAmount` - If amount is zero or negative
 /// * `WithdrawError::InvalidAsset` - If asset address is invalid
 /// * `WithdrawError::InsufficientCollateral` - If user doesn't have enough collateral
 /// * `WithdrawError::WithdrawPaused` - If withdrawals are paused
+/// * `WithdrawError::AssetPaused` - If withdrawals are paused for this asset
 /// * `WithdrawError::InsufficientCollateralRatio` - If withdrawal would violate minimum ratio
+/// * `WithdrawError::WouldBeUndercollateralized` - If a cross-asset position would fall
+///   below the minimum ratio at oracle prices
+/// * `WithdrawError::PriceUnavailable` - If a cross-asset position with debt cannot be priced
+/// * `WithdrawError::InsufficientLiquidity` - If the asset's available liquidity is below
+///   `amount`
+/// * `WithdrawError::WithdrawalLocked` - If the withdrawal intent is still in its cooldown
+/// * `WithdrawError::IntentMismatch` - If `amount` exceeds the registered intent
 /// * `WithdrawError::Overflow` - If calculation overflow occurs
 ///
 /// # Security
@@ -171,37 +873,146 @@ pub fn withdraw_collateral(
     asset: Option<Address>,
     amount: i128,
 ) -> Result<i128, WithdrawError> {
+    withdraw_collateral_to(env, user.clone(), asset, amount, user)
+}
+
+/// Withdraw collateral from `user`'s position and send the tokens to `to`
+///
+/// Behaves exactly like [`withdraw_collateral`] except for the token recipient.
+/// `user` must authorize; `to` does not need to.
+pub fn withdraw_collateral_to(
+    env: &Env,
+    user: Address,
+    asset: Option<Address>,
+    amount: i128,
+    to: Address,
+) -> Result<i128, WithdrawError> {
+    user.require_auth();
+    execute_withdrawal(env, &user, &asset, amount, &to, true).map(|result| result.amount)
+}
+
+/// Withdraw collateral like [`withdraw_collateral`], also returning the receipt id
+///
+/// The receipt can be looked up with [`get_withdrawal_receipt`] while it is
+/// within the retained window. A call that only registers a cooldown intent
+/// returns a zero amount and receipt id 0.
+pub fn withdraw_with_receipt(
+    env: &Env,
+    user: Address,
+    asset: Option<Address>,
+    amount: i128,
+) -> Result<WithdrawalResult, WithdrawError> {
+    user.require_auth();
+    execute_withdrawal(env, &user, &asset, amount, &user, true)
+}
+
+/// Validate and execute one withdrawal for an already-authorized user
+///
+/// Health validation can be deferred with `check_health = false` when the
+/// caller checks the final state itself, as [`withdraw_batch`] does.
+pub(crate) fn execute_withdrawal(
+    env: &Env,
+    user: &Address,
+    asset: &Option<Address>,
+    amount: i128,
+    to: &Address,
+    check_health: bool,
+) -> Result<WithdrawalResult, WithdrawError> {
     // Validate amount
     if amount <= 0 {
         return Err(WithdrawError::InvalidAmount);
     }
 
-    // Check if withdrawals are paused
-    let pause_switches_key = DepositDataKey::PauseSwitches;
-    if let Some(pause_map) = env
-        .storage()
-        .persistent()
-        .get::<DepositDataKey, Map<Symbol, bool>>(&pause_switches_key)
-    {
-        if let Some(paused) = pause_map.get(Symbol::new(env, "pause_withdraw")) {
-            if paused {
-                return Err(WithdrawError::WithdrawPaused);
-            }
+    // Bring the asset's interest indexes up to date before its balances move
+    crate::interest_rate::accrue_interest(env, asset.clone())
+        .map_err(|_| WithdrawError::Overflow)?;
+
+    // Resolve the withdraw-all sentinel to the balance at execution time
+    let amount = if amount == WITHDRAW_ALL {
+        let balance = get_withdrawable_balance(env, user, asset);
+        if balance <= 0 {
+            return Err(WithdrawError::InsufficientCollateral);
         }
-    }
+        balance
+    } else {
+        amount
+    };
 
-    // Get current timestamp
-    let timestamp = env.ledger().timestamp();
+    // Check if withdrawals are paused
+    if is_withdraw_paused(env) {
+        return Err(WithdrawError::WithdrawPaused);
+    }
+    if is_asset_withdraw_paused(env, asset) {
+        return Err(WithdrawError::AssetPaused);
+    }
 
     // Validate asset if provided
-    if let Some(ref asset_addr) = asset {
+    if let Some(asset_addr) = asset {
         // Validate asset address - ensure it's not the contract itself
         if asset_addr == &env.current_contract_address() {
             return Err(WithdrawError::InvalidAsset);
         }
     }
 
-    // Get current collateral balance
+    // Check sufficient collateral. Share-backed deposits are valued at the pool's
+    // exchange rate so accrued supply interest can be withdrawn.
+    let has_shares = crate::deposit::get_user_shares(env, user.clone(), asset.clone()) > 0;
+    let available = get_withdrawable_balance(env, user, asset);
+    if available < amount {
+        return Err(WithdrawError::InsufficientCollateral);
+    }
+
+    // Fail cleanly when borrowers hold the cash; the shortfall is
+    // `amount - get_available_liquidity(asset)`
+    if amount > get_available_liquidity(env, asset) {
+        return Err(WithdrawError::InsufficientLiquidity);
+    }
+
+    // Validate collateral ratio after withdrawal
+    if check_health {
+        validate_collateral_ratio_after_withdraw(env, user, amount, asset.as_ref())?;
+    }
+
+    // Assets with a cooldown need a matured intent; without one this call
+    // registers it and nothing is withdrawn
+    if let Some(asset_addr) = asset {
+        let (cooldown, window) = get_withdraw_cooldown(env, asset_addr);
+        if cooldown > 0
+            && !consume_withdraw_intent(env, user, asset_addr, amount, cooldown, window)?
+        {
+            return Ok(WithdrawalResult {
+                amount: 0,
+                receipt_id: 0,
+            });
+        }
+    }
+
+    // Burn the shares backing the withdrawn amount
+    if has_shares {
+        crate::deposit::redeem_shares(env, user, asset, amount).map_err(|e| match e {
+            crate::deposit::DepositError::InsufficientBalance => {
+                WithdrawError::InsufficientCollateral
+            }
+            _ => WithdrawError::Overflow,
+        })?;
+    }
+
+    complete_withdrawal(env, user, asset, amount, to)
+}
+
+/// Debit `amount` from `user`'s position and pay it out to `to`
+///
+/// Shared by direct withdrawals and the withdrawal queue. Callers must already
+/// have validated the amount and burned any shares backing it. Charges the
+/// asset's withdrawal fee, records a receipt and returns the net amount paid.
+pub(crate) fn complete_withdrawal(
+    env: &Env,
+    user: &Address,
+    asset: &Option<Address>,
+    amount: i128,
+    to: &Address,
+) -> Result<WithdrawalResult, WithdrawError> {
+    let timestamp = env.ledger().timestamp();
     let collateral_key = DepositDataKey::CollateralBalance(user.clone());
     let current_collateral = env
         .storage()
@@ -209,18 +1020,8 @@ pub fn withdraw_collateral(
         .get::<DepositDataKey, i128>(&collateral_key)
         .unwrap_or(0);
 
-    // Check sufficient collateral
-    if current_collateral < amount {
-        return Err(WithdrawError::InsufficientCollateral);
-    }
-
-    // Validate collateral ratio after withdrawal
-    validate_collateral_ratio_after_withdraw(env, &user, amount, asset.as_ref())?;
-
     // Calculate new collateral balance
-    let new_collateral = current_collateral
-        .checked_sub(amount)
-        .ok_or(WithdrawError::Overflow)?;
+    let new_collateral = current_collateral.saturating_sub(amount).max(0);
 
     // Update storage
     env.storage()
@@ -244,16 +1045,42 @@ pub fn withdraw_collateral(
     // Update position
     position.collateral = new_collateral;
     position.last_accrual_time = timestamp;
-    env.storage().persistent().set(&position_key, &position);
+
+    // Drop the asset from the user's deposits once nothing redeemable is left
+    // and no debt is owed in it
+    if crate::borrow::get_user_debt_principal(env, user, asset) == 0 {
+        crate::deposit::clear_empty_deposit(env, user, asset);
+    }
+
+    // A fully exited position is removed so a later deposit starts from a fresh
+    // snapshot rather than stale accrual state, and so it stops costing rent
+    let fully_exited = new_collateral == 0
+        && position.debt == 0
+        && position.borrow_interest == 0
+        && crate::deposit::get_user_assets(env, user).is_empty()
+        && crate::withdraw_queue::get_user_queued_total(env, user) == 0;
+    if fully_exited {
+        env.storage().persistent().remove(&collateral_key);
+        env.storage().persistent().remove(&position_key);
+    } else {
+        env.storage().persistent().set(&position_key, &position);
+    }
+
+    // Withdrawal fee stays in the contract as protocol reserve
+    let fee = amount
+        .checked_mul(get_withdraw_fee_bps(env, asset))
+        .ok_or(WithdrawError::Overflow)?
+        / 10000;
+    let net_amount = amount - fee;
 
     // Handle asset transfer
-    if let Some(ref asset_addr) = asset {
-        // Transfer tokens from contract to user
+    if let Some(asset_addr) = asset {
+        // Transfer tokens from contract to the recipient
         let token_client = soroban_sdk::token::Client::new(env, asset_addr);
         token_client.transfer(
             &env.current_contract_address(), // from (this contract)
-            &user,                           // to (user)
-            &amount,
+            to,                              // to (recipient)
+            &net_amount,
         );
     } else {
         // Native XLM withdrawal - in Soroban, native assets are handled differently
@@ -262,15 +1089,21 @@ pub fn withdraw_collateral(
     }
 
     // Update user analytics
-    update_user_analytics_withdraw(env, &user, amount, timestamp)?;
+    update_user_analytics_withdraw(env, user, amount, timestamp)?;
 
     // Update protocol analytics
     update_protocol_analytics_withdraw(env, amount)?;
+    crate::deposit::update_asset_totals(env, asset, -amount, 0, 0)
+        .map_err(|_| WithdrawError::Overflow)?;
+    if fee > 0 {
+        crate::deposit::credit_protocol_reserve(env, user, asset, fee)
+            .map_err(|_| WithdrawError::Overflow)?;
+    }
 
     // Add to activity log
     add_activity_log(
         env,
-        &user,
+        user,
         Symbol::new(env, "withdraw"),
         amount,
         asset.clone(),
@@ -281,27 +1114,139 @@ pub fn withdraw_collateral(
         _ => WithdrawError::Overflow,
     })?;
 
+    let receipt_id = record_withdrawal_receipt(env, user, to, asset, amount, fee, timestamp);
+
     // Emit withdraw event
     emit_withdrawal(
         env,
         WithdrawalEvent {
             user: user.clone(),
+            to: to.clone(),
             asset: asset.clone(),
             amount,
+            fee,
+            receipt_id,
             timestamp,
         },
     );
 
     // Emit position updated event
-    emit_position_updated_event(env, &user, &position);
+    emit_position_updated_event(env, user, &position);
 
     // Emit analytics updated event
-    emit_analytics_updated_event(env, &user, "withdraw", amount, timestamp);
+    emit_analytics_updated_event(env, user, "withdraw", amount, timestamp);
 
     // Emit user activity tracked event
-    emit_user_activity_tracked_event(env, &user, Symbol::new(env, "withdraw"), amount, timestamp);
+    emit_user_activity_tracked_event(env, user, Symbol::new(env, "withdraw"), amount, timestamp);
+
+    // Per-user analytics of a fully exited position are not kept
+    if fully_exited {
+        env.storage()
+            .persistent()
+            .remove(&DepositDataKey::UserAnalytics(user.clone()));
+    }
+
+    // Keep the touched entries from being archived
+    crate::ttl::bump_user_entries(env, user);
+    crate::ttl::bump_protocol_entries(env, asset);
+
+    Ok(WithdrawalResult {
+        amount: net_amount,
+        receipt_id,
+    })
+}
+
+/// Store a receipt under the next id, evicting the oldest beyond the capacity
+fn record_withdrawal_receipt(
+    env: &Env,
+    user: &Address,
+    to: &Address,
+    asset: &Option<Address>,
+    amount: i128,
+    fee: i128,
+    timestamp: u64,
+) -> u64 {
+    let id = env
+        .storage()
+        .persistent()
+        .get::<DepositDataKey, u64>(&DepositDataKey::NextWithdrawalReceiptId)
+        .unwrap_or(1);
+    env.storage()
+        .persistent()
+        .set(&DepositDataKey::NextWithdrawalReceiptId, &(id + 1));
+
+    env.storage().persistent().set(
+        &DepositDataKey::WithdrawalReceipt(id),
+        &WithdrawalReceipt {
+            id,
+            user: user.clone(),
+            to: to.clone(),
+            asset: asset.clone(),
+            amount,
+            fee,
+            timestamp,
+        },
+    );
+
+    let capacity = u64::from(get_withdrawal_receipt_capacity(env));
+    if id > capacity {
+        env.storage()
+            .persistent()
+            .remove(&DepositDataKey::WithdrawalReceipt(id - capacity));
+    }
+    id
+}
+
+/// Get a withdrawal receipt by id
+///
+/// Only the most recent receipts, up to the configured capacity, are kept;
+/// older ids return `None`.
+pub fn get_withdrawal_receipt(env: &Env, id: u64) -> Option<WithdrawalReceipt> {
+    let next_id = env
+        .storage()
+        .persistent()
+        .get::<DepositDataKey, u64>(&DepositDataKey::NextWithdrawalReceiptId)
+        .unwrap_or(1);
+    let capacity = u64::from(get_withdrawal_receipt_capacity(env));
+    if id.saturating_add(capacity) < next_id {
+        return None;
+    }
+    env.storage()
+        .persistent()
+        .get::<DepositDataKey, WithdrawalReceipt>(&DepositDataKey::WithdrawalReceipt(id))
+}
+
+/// Number of withdrawal receipts kept in storage
+pub fn get_withdrawal_receipt_capacity(env: &Env) -> u32 {
+    env.storage()
+        .persistent()
+        .get::<DepositDataKey, u32>(&DepositDataKey::WithdrawalReceiptCapacity)
+        .unwrap_or(DEFAULT_WITHDRAWAL_RECEIPT_CAPACITY)
+}
+
+/// Set the number of withdrawal receipts kept in storage (admin only)
+///
+/// # Errors
+/// * `WithdrawError::Unauthorized` - If `caller` is not the admin
+/// * `WithdrawError::InvalidAmount` - If `capacity` is zero
+pub fn set_withdrawal_receipt_capacity(
+    env: &Env,
+    caller: Address,
+    capacity: u32,
+) -> Result<(), WithdrawError> {
+    let admin = crate::admin::get_admin(env).ok_or(WithdrawError::Unauthorized)?;
+    if caller != admin {
+        return Err(WithdrawError::Unauthorized);
+    }
+    caller.require_auth();
 
-    Ok(new_collateral)
+    if capacity == 0 {
+        return Err(WithdrawError::InvalidAmount);
+    }
+    env.storage()
+        .persistent()
+        .set(&DepositDataKey::WithdrawalReceiptCapacity, &capacity);
+    Ok(())
 }
 
 /// Update user analytics after withdrawal
@@ -360,7 +1305,7 @@ fn update_user_analytics_withdraw(
 }
 
 /// Update protocol analytics after withdrawal
-fn update_protocol_analytics_withdraw(env: &Env, amount: i128) -> Result<(), WithdrawError> {
+pub(crate) fn update_protocol_analytics_withdraw(env: &Env, amount: i128) -> Result<(), WithdrawError> {
     let analytics_key = DepositDataKey::ProtocolAnalytics;
     let mut analytics = env
         .storage()
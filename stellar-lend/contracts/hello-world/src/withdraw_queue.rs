@@ -0,0 +1,347 @@
+//! # Withdrawal Queue Module
+//!
+//! Lets users line up for liquidity instead of polling when borrowers hold most
+//! of a pool's cash.
+//!
+//! `request_withdrawal` burns the shares backing the requested amount, so the
+//! queued amount stops earning supply interest, and records a claim at the back
+//! of the asset's FIFO queue. Until it is paid the amount still counts as
+//! collateral in health checks.
+//!
+//! `process_withdrawal_queue` can be called by anyone. It pays requests from the
+//! front of the queue with whatever liquidity repayments have returned; the
+//! request at the front is partially filled when liquidity runs out. A request
+//! whose payout would leave its owner below the minimum collateral ratio is
+//! skipped and stays queued.
+//!
+//! ## Storage Keys
+//! - `DepositDataKey::WithdrawalRequest(id)` — a pending [`WithdrawalRequest`]
+//! - `DepositDataKey::WithdrawalQueue(asset)` — pending request ids, oldest first
+//! - `DepositDataKey::QueuedWithdrawals(user)` — per-asset amounts a user has queued
+//! - `DepositDataKey::QueuedWithdrawalTotal(asset)` — total queued in an asset
+//! - `DepositDataKey::NextWithdrawalRequestId` — id counter
+
+use soroban_sdk::{contracttype, Address, Env, Map, Vec};
+
+use crate::deposit::{DepositDataKey, DepositError};
+use crate::events::{
+    emit_withdrawal_cancelled, emit_withdrawal_queued, WithdrawalCancelledEvent,
+    WithdrawalQueuedEvent,
+};
+use crate::withdraw::WithdrawError;
+
+/// A queued withdrawal claim
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct WithdrawalRequest {
+    /// Sequence number, unique across all assets
+    pub id: u64,
+    /// Owner of the claim; payouts are sent here
+    pub user: Address,
+    /// Asset to withdraw (None for native XLM)
+    pub asset: Option<Address>,
+    /// Amount still to be paid
+    pub amount: i128,
+    /// Time the request was made
+    pub requested_at: u64,
+}
+
+/// Amounts `user` has queued, by asset
+pub fn get_user_queued(env: &Env, user: &Address) -> Map<Option<Address>, i128> {
+    env.storage()
+        .persistent()
+        .get::<DepositDataKey, Map<Option<Address>, i128>>(&DepositDataKey::QueuedWithdrawals(
+            user.clone(),
+        ))
+        .unwrap_or_else(|| Map::new(env))
+}
+
+/// Total amount `user` has queued across all assets
+pub fn get_user_queued_total(env: &Env, user: &Address) -> i128 {
+    get_user_queued(env, user)
+        .values()
+        .iter()
+        .fold(0i128, |total, amount| total.saturating_add(amount))
+}
+
+/// Total amount queued for withdrawal in `asset`
+pub fn get_asset_queued_total(env: &Env, asset: &Option<Address>) -> i128 {
+    env.storage()
+        .persistent()
+        .get::<DepositDataKey, i128>(&DepositDataKey::QueuedWithdrawalTotal(asset.clone()))
+        .unwrap_or(0)
+}
+
+/// Adjust the queued amounts of `user` and `asset` by `delta`
+fn update_queued(env: &Env, user: &Address, asset: &Option<Address>, delta: i128) {
+    let user_key = DepositDataKey::QueuedWithdrawals(user.clone());
+    let mut queued = get_user_queued(env, user);
+    let amount = queued
+        .get(asset.clone())
+        .unwrap_or(0)
+        .saturating_add(delta)
+        .max(0);
+    if amount > 0 {
+        queued.set(asset.clone(), amount);
+    } else {
+        queued.remove(asset.clone());
+    }
+    if queued.is_empty() {
+        env.storage().persistent().remove(&user_key);
+    } else {
+        env.storage().persistent().set(&user_key, &queued);
+    }
+
+    let total = get_asset_queued_total(env, asset)
+        .saturating_add(delta)
+        .max(0);
+    env.storage().persistent().set(
+        &DepositDataKey::QueuedWithdrawalTotal(asset.clone()),
+        &total,
+    );
+}
+
+fn get_queue(env: &Env, asset: &Option<Address>) -> Vec<u64> {
+    env.storage()
+        .persistent()
+        .get::<DepositDataKey, Vec<u64>>(&DepositDataKey::WithdrawalQueue(asset.clone()))
+        .unwrap_or_else(|| Vec::new(env))
+}
+
+fn save_queue(env: &Env, asset: &Option<Address>, queue: &Vec<u64>) {
+    let key = DepositDataKey::WithdrawalQueue(asset.clone());
+    if queue.is_empty() {
+        env.storage().persistent().remove(&key);
+    } else {
+        env.storage().persistent().set(&key, queue);
+    }
+}
+
+/// Queue a withdrawal to be paid once liquidity is available
+///
+/// The shares backing `amount` are burned immediately, so the queued amount no
+/// longer earns interest. The request must pass the same collateral checks as
+/// a direct withdrawal.
+///
+/// # Returns
+/// The request id
+///
+/// # Errors
+/// * `WithdrawError::InvalidAmount` - If amount is zero or negative
+/// * `WithdrawError::WithdrawPaused` - If withdrawals are paused
+/// * `WithdrawError::AssetPaused` - If withdrawals are paused for this asset
+/// * `WithdrawError::InsufficientCollateral` - If the user's deposit is below `amount`
+/// * `WithdrawError::InsufficientCollateralRatio` / `WouldBeUndercollateralized` -
+///   If paying the request would leave the position below the minimum ratio
+pub fn request_withdrawal(
+    env: &Env,
+    user: Address,
+    asset: Option<Address>,
+    amount: i128,
+) -> Result<u64, WithdrawError> {
+    user.require_auth();
+
+    if amount <= 0 {
+        return Err(WithdrawError::InvalidAmount);
+    }
+    if crate::withdraw::is_withdraw_paused(env) {
+        return Err(WithdrawError::WithdrawPaused);
+    }
+    if crate::withdraw::is_asset_withdraw_paused(env, &asset) {
+        return Err(WithdrawError::AssetPaused);
+    }
+    if crate::deposit::get_user_deposit_balance(env, &user, &asset) < amount {
+        return Err(WithdrawError::InsufficientCollateral);
+    }
+    crate::withdraw::validate_collateral_ratio_after_withdraw(env, &user, amount, asset.as_ref())?;
+
+    crate::deposit::redeem_shares(env, &user, &asset, amount).map_err(|e| match e {
+        DepositError::InsufficientBalance => WithdrawError::InsufficientCollateral,
+        _ => WithdrawError::Overflow,
+    })?;
+    update_queued(env, &user, &asset, amount);
+
+    let id = env
+        .storage()
+        .persistent()
+        .get::<DepositDataKey, u64>(&DepositDataKey::NextWithdrawalRequestId)
+        .unwrap_or(1);
+    env.storage()
+        .persistent()
+        .set(&DepositDataKey::NextWithdrawalRequestId, &(id + 1));
+
+    let timestamp = env.ledger().timestamp();
+    let request = WithdrawalRequest {
+        id,
+        user: user.clone(),
+        asset: asset.clone(),
+        amount,
+        requested_at: timestamp,
+    };
+    env.storage()
+        .persistent()
+        .set(&DepositDataKey::WithdrawalRequest(id), &request);
+
+    let mut queue = get_queue(env, &asset);
+    queue.push_back(id);
+    save_queue(env, &asset, &queue);
+
+    emit_withdrawal_queued(
+        env,
+        WithdrawalQueuedEvent {
+            request_id: id,
+            user,
+            asset,
+            amount,
+            timestamp,
+        },
+    );
+
+    Ok(id)
+}
+
+/// Pay queued withdrawals of `asset` in FIFO order. Callable by anyone.
+///
+/// Examines at most `max_items` requests. The first request that cannot be
+/// fully paid is partially filled and processing stops there. Requests whose
+/// payout would break their owner's collateral ratio are skipped.
+///
+/// # Returns
+/// The total amount paid out, before withdrawal fees
+pub fn process_withdrawal_queue(
+    env: &Env,
+    asset: Option<Address>,
+    max_items: u32,
+) -> Result<i128, WithdrawError> {
+    if crate::withdraw::is_withdraw_paused(env) {
+        return Err(WithdrawError::WithdrawPaused);
+    }
+    if crate::withdraw::is_asset_withdraw_paused(env, &asset) {
+        return Err(WithdrawError::AssetPaused);
+    }
+
+    let queue = get_queue(env, &asset);
+    let mut remaining_queue = Vec::new(env);
+    let mut liquidity = crate::withdraw::get_pool_liquidity(env, &asset);
+    let mut paid: i128 = 0;
+
+    for (index, id) in queue.iter().enumerate() {
+        if index as u32 >= max_items || liquidity <= 0 {
+            remaining_queue.push_back(id);
+            continue;
+        }
+        let key = DepositDataKey::WithdrawalRequest(id);
+        let mut request = match env
+            .storage()
+            .persistent()
+            .get::<DepositDataKey, WithdrawalRequest>(&key)
+        {
+            Some(request) => request,
+            None => continue,
+        };
+
+        let payout = request.amount.min(liquidity);
+        if crate::withdraw::validate_collateral_ratio_after_withdraw(
+            env,
+            &request.user,
+            payout,
+            asset.as_ref(),
+        )
+        .is_err()
+        {
+            remaining_queue.push_back(id);
+            continue;
+        }
+
+        update_queued(env, &request.user, &asset, -payout);
+        crate::withdraw::complete_withdrawal(env, &request.user, &asset, payout, &request.user)?;
+        liquidity -= payout;
+        paid += payout;
+
+        request.amount -= payout;
+        if request.amount > 0 {
+            env.storage().persistent().set(&key, &request);
+            remaining_queue.push_back(id);
+        } else {
+            env.storage().persistent().remove(&key);
+        }
+    }
+
+    save_queue(env, &asset, &remaining_queue);
+    Ok(paid)
+}
+
+/// Get up to `limit` pending requests for `asset`, oldest first
+pub fn get_withdrawal_queue(
+    env: &Env,
+    asset: &Option<Address>,
+    limit: u32,
+) -> Vec<WithdrawalRequest> {
+    let mut requests = Vec::new(env);
+    for id in get_queue(env, asset).iter() {
+        if requests.len() >= limit {
+            break;
+        }
+        if let Some(request) = env
+            .storage()
+            .persistent()
+            .get::<DepositDataKey, WithdrawalRequest>(&DepositDataKey::WithdrawalRequest(id))
+        {
+            requests.push_back(request);
+        }
+    }
+    requests
+}
+
+/// Cancel a pending request and return its unpaid amount to the user's deposit
+///
+/// The amount is re-deposited at the current exchange rate and resumes earning
+/// interest.
+///
+/// # Returns
+/// The amount returned to the deposit
+///
+/// # Errors
+/// * `WithdrawError::RequestNotFound` - If no pending request has this id
+/// * `WithdrawError::Unauthorized` - If `user` does not own the request
+pub fn cancel_withdrawal_request(
+    env: &Env,
+    user: Address,
+    request_id: u64,
+) -> Result<i128, WithdrawError> {
+    user.require_auth();
+
+    let key = DepositDataKey::WithdrawalRequest(request_id);
+    let request = env
+        .storage()
+        .persistent()
+        .get::<DepositDataKey, WithdrawalRequest>(&key)
+        .ok_or(WithdrawError::RequestNotFound)?;
+    if request.user != user {
+        return Err(WithdrawError::Unauthorized);
+    }
+
+    let mut queue = get_queue(env, &request.asset);
+    if let Some(index) = queue.first_index_of(request_id) {
+        queue.remove(index);
+    }
+    save_queue(env, &request.asset, &queue);
+    env.storage().persistent().remove(&key);
+
+    update_queued(env, &user, &request.asset, -request.amount);
+    crate::deposit::mint_shares(env, &user, &request.asset, request.amount)
+        .map_err(|_| WithdrawError::Overflow)?;
+
+    emit_withdrawal_cancelled(
+        env,
+        WithdrawalCancelledEvent {
+            request_id,
+            user,
+            asset: request.asset,
+            amount: request.amount,
+            timestamp: env.ledger().timestamp(),
+        },
+    );
+
+    Ok(request.amount)
+}
@@ -0,0 +1,214 @@
+//! # Scheduled Withdrawal Module
+//!
+//! Lets a depositor unwind a large position in equal tranches instead of all at
+//! once, so utilization and rates move gradually.
+//!
+//! `schedule_withdrawal` records a plan of `tranches` payouts, one becoming due
+//! every `interval_secs`. `execute_scheduled_withdrawal` can be called by anyone;
+//! it pays every tranche that has become due since the last execution, so
+//! skipped intervals catch up. Each execution is a regular withdrawal with the
+//! usual pause, liquidity and collateral checks, evaluated at execution time.
+//! The plan does not lock the funds: the owner can still withdraw directly, in
+//! which case later tranches fail until the balance is restored.
+//!
+//! ## Storage Keys
+//! - `DepositDataKey::WithdrawalSchedule(id)` — an active [`WithdrawalSchedule`]
+//! - `DepositDataKey::NextWithdrawalScheduleId` — id counter
+
+use soroban_sdk::{contracttype, Address, Env};
+
+use crate::deposit::DepositDataKey;
+use crate::events::{
+    emit_withdrawal_schedule_cancelled, emit_withdrawal_scheduled,
+    WithdrawalScheduleCancelledEvent, WithdrawalScheduledEvent,
+};
+use crate::withdraw::WithdrawError;
+
+/// A plan to withdraw `total_amount` in equal tranches
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct WithdrawalSchedule {
+    /// Sequence number, unique across all users
+    pub id: u64,
+    /// Owner of the plan; payouts are sent here
+    pub user: Address,
+    /// Asset to withdraw (None for native XLM)
+    pub asset: Option<Address>,
+    /// Amount withdrawn over the whole plan
+    pub total_amount: i128,
+    /// Number of tranches
+    pub tranches: u32,
+    /// Time between tranches
+    pub interval_secs: u64,
+    /// Time the plan was created; the first tranche is due one interval later
+    pub start_time: u64,
+    /// Tranches paid so far
+    pub tranches_paid: u32,
+    /// Amount paid so far, before withdrawal fees
+    pub amount_paid: i128,
+}
+
+/// Get an active withdrawal schedule by id
+pub fn get_withdrawal_schedule(env: &Env, schedule_id: u64) -> Option<WithdrawalSchedule> {
+    env.storage()
+        .persistent()
+        .get::<DepositDataKey, WithdrawalSchedule>(&DepositDataKey::WithdrawalSchedule(schedule_id))
+}
+
+/// Schedule `total_amount` of `asset` to be withdrawn in `tranches` equal parts
+///
+/// # Returns
+/// The schedule id
+///
+/// # Errors
+/// * `WithdrawError::InvalidAmount` - If the amount, tranche count or interval is zero
+/// * `WithdrawError::InsufficientCollateral` - If the user's deposit is below `total_amount`
+pub fn schedule_withdrawal(
+    env: &Env,
+    user: Address,
+    asset: Option<Address>,
+    total_amount: i128,
+    tranches: u32,
+    interval_secs: u64,
+) -> Result<u64, WithdrawError> {
+    user.require_auth();
+
+    if total_amount <= 0 || tranches == 0 || interval_secs == 0 {
+        return Err(WithdrawError::InvalidAmount);
+    }
+    if crate::deposit::get_user_deposit_balance(env, &user, &asset) < total_amount {
+        return Err(WithdrawError::InsufficientCollateral);
+    }
+
+    let id = env
+        .storage()
+        .persistent()
+        .get::<DepositDataKey, u64>(&DepositDataKey::NextWithdrawalScheduleId)
+        .unwrap_or(1);
+    env.storage()
+        .persistent()
+        .set(&DepositDataKey::NextWithdrawalScheduleId, &(id + 1));
+
+    let timestamp = env.ledger().timestamp();
+    let schedule = WithdrawalSchedule {
+        id,
+        user: user.clone(),
+        asset: asset.clone(),
+        total_amount,
+        tranches,
+        interval_secs,
+        start_time: timestamp,
+        tranches_paid: 0,
+        amount_paid: 0,
+    };
+    env.storage()
+        .persistent()
+        .set(&DepositDataKey::WithdrawalSchedule(id), &schedule);
+
+    emit_withdrawal_scheduled(
+        env,
+        WithdrawalScheduledEvent {
+            schedule_id: id,
+            user,
+            asset,
+            total_amount,
+            tranches,
+            interval_secs,
+            timestamp,
+        },
+    );
+
+    Ok(id)
+}
+
+/// Pay every tranche of a schedule that has become due. Callable by anyone.
+///
+/// The plan is removed once its last tranche is paid.
+///
+/// # Returns
+/// The net amount sent to the user
+///
+/// # Errors
+/// * `WithdrawError::RequestNotFound` - If `user` has no active schedule with this id
+/// * `WithdrawError::WithdrawalLocked` - If no tranche has become due since the last execution
+/// * Any error a regular withdrawal of the due amount can return
+pub fn execute_scheduled_withdrawal(
+    env: &Env,
+    user: Address,
+    schedule_id: u64,
+) -> Result<i128, WithdrawError> {
+    let mut schedule = get_withdrawal_schedule(env, schedule_id)
+        .filter(|schedule| schedule.user == user)
+        .ok_or(WithdrawError::RequestNotFound)?;
+
+    let elapsed =
+        env.ledger().timestamp().saturating_sub(schedule.start_time) / schedule.interval_secs;
+    let due = elapsed.min(u64::from(schedule.tranches)) as u32;
+    if due <= schedule.tranches_paid {
+        return Err(WithdrawError::WithdrawalLocked);
+    }
+
+    // Cumulative target rounds down, so the last tranche picks up the remainder
+    let target = schedule
+        .total_amount
+        .checked_mul(i128::from(due))
+        .ok_or(WithdrawError::Overflow)?
+        / i128::from(schedule.tranches);
+    let amount = target - schedule.amount_paid;
+
+    let result =
+        crate::withdraw::execute_withdrawal(env, &user, &schedule.asset, amount, &user, true)?;
+    if result.receipt_id == 0 {
+        // Only a cooldown intent was registered; the tranches stay due
+        return Ok(0);
+    }
+
+    schedule.tranches_paid = due;
+    schedule.amount_paid = target;
+    let key = DepositDataKey::WithdrawalSchedule(schedule_id);
+    if due == schedule.tranches {
+        env.storage().persistent().remove(&key);
+    } else {
+        env.storage().persistent().set(&key, &schedule);
+    }
+
+    Ok(result.amount)
+}
+
+/// Cancel a schedule; tranches not yet paid stay in the user's deposit
+///
+/// # Returns
+/// The scheduled amount that was not paid out
+///
+/// # Errors
+/// * `WithdrawError::RequestNotFound` - If no active schedule has this id
+/// * `WithdrawError::Unauthorized` - If `user` does not own the schedule
+pub fn cancel_scheduled_withdrawal(
+    env: &Env,
+    user: Address,
+    schedule_id: u64,
+) -> Result<i128, WithdrawError> {
+    user.require_auth();
+
+    let schedule =
+        get_withdrawal_schedule(env, schedule_id).ok_or(WithdrawError::RequestNotFound)?;
+    if schedule.user != user {
+        return Err(WithdrawError::Unauthorized);
+    }
+    env.storage()
+        .persistent()
+        .remove(&DepositDataKey::WithdrawalSchedule(schedule_id));
+
+    let remaining = schedule.total_amount - schedule.amount_paid;
+    emit_withdrawal_schedule_cancelled(
+        env,
+        WithdrawalScheduleCancelledEvent {
+            schedule_id,
+            user,
+            remaining,
+            timestamp: env.ledger().timestamp(),
+        },
+    );
+
+    Ok(remaining)
+}